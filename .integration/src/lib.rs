@@ -17,7 +17,7 @@
 #[cfg(test)]
 mod tests {
     use aleo_std::StorageMode;
-    use snarkos_node_cdn::sync_ledger_with_cdn;
+    use snarkos_node_cdn::{sync_ledger_with_cdn, CdnConfig};
     use snarkvm::prelude::{
         block::Block,
         store::helpers::memory::ConsensusMemory,
@@ -43,8 +43,9 @@ mod tests {
         // Perform the sync.
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
+            let config = CdnConfig::new(vec![TEST_BASE_URL.to_string()]);
             let completed_height =
-                sync_ledger_with_cdn(TEST_BASE_URL, ledger.clone(), Default::default()).await.unwrap();
+                sync_ledger_with_cdn(&config, ledger.clone(), Default::default(), Default::default()).await.unwrap();
             assert_eq!(completed_height, ledger.latest_height());
         });
     }
@@ -14,6 +14,9 @@
 
 #![forbid(unsafe_code)]
 
+mod roles;
+pub use roles::*;
+
 use snarkvm::{
     console::{network::prelude::*, types::Field},
     prelude::*,
@@ -0,0 +1,115 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Account;
+use snarkvm::prelude::Network;
+
+/// The accounts of a node, one for each of its roles.
+///
+/// Separating the roles ensures that a compromise of one key (e.g. the key behind the public-facing REST API)
+/// does not expose the others (e.g. the key that signs the blocks). By default, a single account fills every role.
+#[derive(Clone, Debug)]
+pub struct AccountRoles<N: Network> {
+    /// The account that signs the blocks, i.e. the batches and certificates of a validator.
+    signing: Account<N>,
+    /// The account that identifies the node on the peer-to-peer network.
+    p2p: Account<N>,
+    /// The account that signs the admin tokens of the REST server.
+    admin: Account<N>,
+}
+
+impl<N: Network> AccountRoles<N> {
+    /// Initializes the roles of a node, with the given account filling every role.
+    pub fn new(account: Account<N>) -> Self {
+        Self { signing: account.clone(), p2p: account.clone(), admin: account }
+    }
+
+    /// Sets the account that identifies the node on the peer-to-peer network.
+    pub fn with_p2p(mut self, account: Account<N>) -> Self {
+        self.p2p = account;
+        self
+    }
+
+    /// Sets the account that signs the admin tokens of the REST server.
+    pub fn with_admin(mut self, account: Account<N>) -> Self {
+        self.admin = account;
+        self
+    }
+
+    /// Returns the account that signs the blocks.
+    pub const fn signing(&self) -> &Account<N> {
+        &self.signing
+    }
+
+    /// Returns the account that identifies the node on the peer-to-peer network.
+    pub const fn p2p(&self) -> &Account<N> {
+        &self.p2p
+    }
+
+    /// Returns the account that signs the admin tokens of the REST server.
+    pub const fn admin(&self) -> &Account<N> {
+        &self.admin
+    }
+
+    /// Returns `true` if the peer-to-peer identity is separate from the signing account.
+    pub fn is_p2p_separated(&self) -> bool {
+        self.p2p.address() != self.signing.address()
+    }
+
+    /// Returns `true` if the admin account is separate from the other accounts.
+    pub fn is_admin_separated(&self) -> bool {
+        self.admin.address() != self.signing.address() && self.admin.address() != self.p2p.address()
+    }
+}
+
+impl<N: Network> From<Account<N>> for AccountRoles<N> {
+    /// Initializes the roles of a node, with the given account filling every role.
+    fn from(account: Account<N>) -> Self {
+        Self::new(account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_roles() {
+        let mut rng = TestRng::default();
+        let account = Account::<CurrentNetwork>::new(&mut rng).unwrap();
+
+        // By default, the account fills every role.
+        let roles = AccountRoles::new(account.clone());
+        assert_eq!(roles.p2p().address(), account.address());
+        assert_eq!(roles.admin().address(), account.address());
+        assert!(!roles.is_p2p_separated());
+        assert!(!roles.is_admin_separated());
+
+        // Separate the roles.
+        let (p2p, admin) =
+            (Account::<CurrentNetwork>::new(&mut rng).unwrap(), Account::<CurrentNetwork>::new(&mut rng).unwrap());
+        let roles = roles.with_p2p(p2p.clone()).with_admin(admin.clone());
+        assert_eq!(roles.signing().address(), account.address());
+        assert_eq!(roles.p2p().address(), p2p.address());
+        assert_eq!(roles.admin().address(), admin.address());
+        assert!(roles.is_p2p_separated());
+        assert!(roles.is_admin_separated());
+
+        // An admin account that reuses the peer-to-peer identity is not separated.
+        assert!(!AccountRoles::new(account).with_p2p(p2p.clone()).with_admin(p2p).is_admin_separated());
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::{Crawler, NodeInterface};
+use snarkvm::{
+    console::network::{Network, Testnet3},
+    ledger::block::Block,
+    prelude::FromBytes,
+};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+use tokio::runtime;
+
+/// Crawls the network to map its topology, without participating in consensus or sync.
+#[derive(Clone, Debug, Parser)]
+pub struct Crawl {
+    /// Specify the network ID of the network to crawl
+    #[clap(default_value = "3", long = "network")]
+    pub network: u16,
+    /// Specify the IP address and port for the crawler
+    #[clap(default_value = "0.0.0.0:4140", long = "node")]
+    pub node: SocketAddr,
+    /// Specify the IP address and port of the peer(s) to start crawling from (defaults to the bootstrap peers)
+    #[clap(default_value = "", long = "peers")]
+    pub peers: String,
+    /// Specify the path to the file where the network graph will be exported, as JSON
+    #[clap(default_value = "network.json", long = "output")]
+    pub output: PathBuf,
+    /// Specify the number of seconds to crawl for
+    #[clap(default_value = "600", long = "duration")]
+    pub duration: u64,
+    /// Specify the number of seconds in between exports of the network graph
+    #[clap(default_value = "30", long = "interval")]
+    pub interval: u64,
+    /// Specify the verbosity of the crawler [options: 0, 1, 2, 3, 4]
+    #[clap(default_value = "1", long = "verbosity")]
+    pub verbosity: u8,
+    /// Enables development mode, to crawl a local devnet
+    #[clap(long)]
+    pub dev: bool,
+}
+
+impl Crawl {
+    /// Crawls the network, and exports the network graph.
+    pub fn parse(self) -> Result<String> {
+        // Initialize the logger.
+        crate::helpers::initialize_logger(self.verbosity, true, std::env::temp_dir().join("snarkos-crawl.log"));
+        // Initialize the runtime.
+        let runtime = runtime::Builder::new_multi_thread().enable_all().build()?;
+        // Parse the network.
+        match self.network {
+            3 => runtime.block_on(self.crawl::<Testnet3>()),
+            _ => bail!("Unsupported network ID"),
+        }
+    }
+
+    /// Runs the crawler for the configured duration.
+    async fn crawl<N: Network>(&self) -> Result<String> {
+        // Parse the seed peers.
+        let seed_peers = self.parse_peers()?;
+        // Load the genesis block.
+        let genesis = Block::<N>::from_bytes_le(N::genesis_bytes())?;
+        // Initialize the crawler.
+        let crawler = Crawler::<N>::new(self.node, &seed_peers, genesis, self.dev).await?;
+
+        // Crawl the network, periodically exporting the network graph.
+        let interval = Duration::from_secs(self.interval.max(1));
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(self.duration);
+        while tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(interval.min(deadline - tokio::time::Instant::now())).await;
+            crawler.export(&self.output)?;
+            println!("Crawled {} of {} observed nodes", crawler.num_crawled(), crawler.num_observed());
+        }
+
+        // Export the final network graph.
+        crawler.export(&self.output)?;
+        let (num_crawled, num_observed) = (crawler.num_crawled(), crawler.num_observed());
+        // Shut down the crawler.
+        crawler.shut_down().await;
+
+        let path_string = format!("(in \"{}\")", self.output.display()).dimmed();
+        Ok(format!("✅ Crawled {num_crawled} of {num_observed} observed nodes {path_string}"))
+    }
+
+    /// Returns the seed peers, from the given configurations.
+    fn parse_peers(&self) -> Result<Vec<SocketAddr>> {
+        match self.peers.is_empty() {
+            true => Ok(vec![]),
+            false => self
+                .peers
+                .split(',')
+                .map(|ip| match ip.parse::<SocketAddr>() {
+                    Ok(ip) => Ok(ip),
+                    Err(e) => bail!("The IP supplied to --peers ('{ip}') is malformed: {e}"),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_peers() {
+        let crawl = Crawl::try_parse_from(["snarkos", "--peers", "1.2.3.4:5,6.7.8.9:10"].iter()).unwrap();
+        assert_eq!(crawl.parse_peers().unwrap(), vec![
+            SocketAddr::from(([1, 2, 3, 4], 5)),
+            SocketAddr::from(([6, 7, 8, 9], 10))
+        ]);
+
+        let crawl = Crawl::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(crawl.parse_peers().unwrap().is_empty());
+
+        let crawl = Crawl::try_parse_from(["snarkos", "--peers", "1.2.3.4"].iter()).unwrap();
+        assert!(crawl.parse_peers().is_err());
+    }
+}
@@ -258,9 +258,10 @@ impl Scan {
         // Scan the blocks via the CDN.
         rt.block_on(async move {
             let _ = snarkos_node_cdn::load_blocks(
-                &cdn,
+                &snarkos_node_cdn::CdnConfig::new(vec![cdn]),
                 cdn_request_start,
                 Some(cdn_request_end),
+                Default::default(),
                 _shutdown,
                 move |block| {
                     // Check if the block is within the requested range.
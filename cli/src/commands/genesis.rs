@@ -0,0 +1,279 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{
+        account::{Address, PrivateKey},
+        network::{Network, Testnet3},
+    },
+    ledger::{
+        block::Block,
+        committee::{Committee, MIN_VALIDATOR_STAKE},
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+    },
+    prelude::{FromBytes, ToBytes},
+    synthesizer::VM,
+};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use indexmap::IndexMap;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+type CurrentNetwork = Testnet3;
+
+/// Commands to build and verify a custom genesis block from a ceremony manifest.
+#[derive(Debug, Parser)]
+pub enum Genesis {
+    /// Builds the genesis block from a manifest, and writes its bytes to a file
+    Build {
+        /// Specify the path to the TOML manifest of the genesis block
+        #[clap(long)]
+        manifest: PathBuf,
+        /// Specify the path of the file to write the genesis block to
+        #[clap(default_value = "genesis.block", long)]
+        output: PathBuf,
+    },
+    /// Rebuilds the genesis block from a manifest, and checks it matches the given genesis block
+    Verify {
+        /// Specify the path to the TOML manifest of the genesis block
+        #[clap(long)]
+        manifest: PathBuf,
+        /// Specify the path to the genesis block to verify
+        #[clap(long)]
+        genesis: PathBuf,
+    },
+}
+
+impl Genesis {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Build { manifest, output } => {
+                // Build the genesis block.
+                let block = GenesisManifest::<CurrentNetwork>::load(&manifest)?.build()?;
+                // Write the genesis block to the file.
+                std::fs::write(&output, block.to_bytes_le()?)?;
+                Ok(format!("✅ Wrote the genesis block {} to {}", block.hash().to_string().bold(), output.display()))
+            }
+            Self::Verify { manifest, genesis } => {
+                // Load the given genesis block.
+                let bytes = std::fs::read(&genesis)?;
+                let candidate = Block::<CurrentNetwork>::from_bytes_le(&bytes)?;
+                // Rebuild the genesis block from the manifest.
+                let expected = GenesisManifest::<CurrentNetwork>::load(&manifest)?.build()?;
+                // Ensure the genesis blocks are byte-for-byte identical.
+                ensure!(
+                    bytes == expected.to_bytes_le()?,
+                    "The genesis block {} does not match the manifest, which yields the genesis block {}",
+                    candidate.hash(),
+                    expected.hash()
+                );
+                Ok(format!("✅ The genesis block {} matches the manifest", candidate.hash().to_string().bold()))
+            }
+        }
+    }
+}
+
+/// The manifest of a genesis ceremony, from which every participant deterministically builds the same genesis block.
+///
+/// ```toml
+/// network = 3
+/// seed = 1234567890
+/// genesis_private_key = "APrivateKey1..."
+///
+/// [[committee]]
+/// address = "aleo1..."
+/// stake = 10000000000000
+///
+/// [[accounts]]
+/// address = "aleo1..."
+/// balance = 1000000000
+/// ```
+#[derive(Clone, Deserialize)]
+#[serde(bound = "", deny_unknown_fields)]
+pub struct GenesisManifest<N: Network> {
+    /// The ID of the network.
+    pub network: u16,
+    /// The seed of the RNG used to build the genesis block.
+    pub seed: u64,
+    /// The private key that signs the genesis block, whose account must hold a public balance.
+    pub genesis_private_key: PrivateKey<N>,
+    /// The members of the genesis committee.
+    pub committee: Vec<GenesisMember<N>>,
+    /// The accounts that are allocated a public balance.
+    #[serde(default)]
+    pub accounts: Vec<GenesisAccount<N>>,
+}
+
+/// A member of the genesis committee.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(bound = "", deny_unknown_fields)]
+pub struct GenesisMember<N: Network> {
+    /// The address of the member.
+    pub address: Address<N>,
+    /// The stake of the member, in microcredits.
+    pub stake: u64,
+    /// Whether the member accepts delegators.
+    #[serde(default = "GenesisMember::<N>::default_is_open")]
+    pub is_open: bool,
+}
+
+impl<N: Network> GenesisMember<N> {
+    const fn default_is_open() -> bool {
+        true
+    }
+}
+
+/// An account that is allocated a public balance in the genesis block.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(bound = "", deny_unknown_fields)]
+pub struct GenesisAccount<N: Network> {
+    /// The address of the account.
+    pub address: Address<N>,
+    /// The public balance of the account, in microcredits.
+    pub balance: u64,
+}
+
+impl<N: Network> GenesisManifest<N> {
+    /// Loads the manifest from the given TOML file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let manifest = std::fs::read_to_string(path)?;
+        toml::from_str(&manifest).map_err(|e| anyhow!("Invalid genesis manifest {} - {e}", path.display()))
+    }
+
+    /// Returns the genesis committee.
+    pub fn committee(&self) -> Result<Committee<N>> {
+        let mut members = IndexMap::with_capacity(self.committee.len());
+        for member in &self.committee {
+            ensure!(member.stake >= MIN_VALIDATOR_STAKE, "The stake of committee member {} is too low", member.address);
+            if members.insert(member.address, (member.stake, member.is_open)).is_some() {
+                bail!("Committee member {} is listed more than once", member.address);
+            }
+        }
+        Committee::new(0u64, members)
+    }
+
+    /// Returns the public balances of the genesis accounts.
+    pub fn public_balances(&self) -> Result<IndexMap<Address<N>, u64>> {
+        let mut public_balances = IndexMap::with_capacity(self.accounts.len());
+        for account in &self.accounts {
+            if public_balances.insert(account.address, account.balance).is_some() {
+                bail!("Account {} is listed more than once", account.address);
+            }
+        }
+        Ok(public_balances)
+    }
+
+    /// Checks the manifest, and returns the genesis committee and public balances.
+    pub fn check(&self) -> Result<(Committee<N>, IndexMap<Address<N>, u64>)> {
+        ensure!(self.network == N::ID, "The manifest is for network {}, not network {}", self.network, N::ID);
+
+        let committee = self.committee()?;
+        let public_balances = self.public_balances()?;
+
+        // Ensure the account of the genesis private key holds a public balance.
+        let genesis_address = Address::try_from(&self.genesis_private_key)?;
+        ensure!(
+            public_balances.get(&genesis_address).is_some_and(|balance| *balance > 0),
+            "The account of the genesis private key ({genesis_address}) must hold a public balance"
+        );
+        // Ensure the committee stakes and public balances add up to the starting supply.
+        let total = public_balances
+            .values()
+            .try_fold(committee.total_stake(), |sum, balance| sum.checked_add(*balance))
+            .ok_or_else(|| anyhow!("Sum of committee stakes and public balances overflows"))?;
+        ensure!(
+            total == N::STARTING_SUPPLY,
+            "Sum of committee stakes and public balances ({total}) does not equal the starting supply ({})",
+            N::STARTING_SUPPLY
+        );
+        Ok((committee, public_balances))
+    }
+
+    /// Builds the genesis block. Given the same manifest, this yields the same genesis block on every machine.
+    pub fn build(&self) -> Result<Block<N>> {
+        let (committee, public_balances) = self.check()?;
+        // Initialize the RNG from the seed of the ceremony.
+        let mut rng = ChaChaRng::seed_from_u64(self.seed);
+        // Initialize a new VM.
+        let vm = VM::from(ConsensusStore::<N, ConsensusMemory<N>>::open(Some(0))?)?;
+        // Build the genesis block.
+        vm.genesis_quorum(&self.genesis_private_key, committee, public_balances, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::TestRng;
+
+    /// Returns a manifest of a committee of 4 members, where the first member holds the remaining supply.
+    fn sample_manifest(rng: &mut TestRng) -> String {
+        let private_keys = (0..4).map(|_| PrivateKey::<CurrentNetwork>::new(rng).unwrap()).collect::<Vec<_>>();
+        let addresses = private_keys.iter().map(|key| Address::try_from(key).unwrap()).collect::<Vec<_>>();
+        let balance = CurrentNetwork::STARTING_SUPPLY - 4 * MIN_VALIDATOR_STAKE;
+
+        let mut manifest = format!("network = 3\nseed = 1\ngenesis_private_key = \"{}\"\n", private_keys[0]);
+        for address in &addresses {
+            manifest += &format!("\n[[committee]]\naddress = \"{address}\"\nstake = {MIN_VALIDATOR_STAKE}\n");
+        }
+        manifest += &format!("\n[[accounts]]\naddress = \"{}\"\nbalance = {balance}\n", addresses[0]);
+        manifest
+    }
+
+    #[test]
+    fn test_genesis_manifest() {
+        let rng = &mut TestRng::default();
+
+        let manifest: GenesisManifest<CurrentNetwork> = toml::from_str(&sample_manifest(rng)).unwrap();
+        assert_eq!(manifest.committee.len(), 4);
+        assert!(manifest.committee.iter().all(|member| member.is_open));
+        let (committee, public_balances) = manifest.check().unwrap();
+        assert_eq!(committee.members().len(), 4);
+        assert_eq!(public_balances.len(), 1);
+    }
+
+    #[test]
+    fn test_genesis_manifest_rejects_invalid() {
+        let rng = &mut TestRng::default();
+        let manifest: GenesisManifest<CurrentNetwork> = toml::from_str(&sample_manifest(rng)).unwrap();
+
+        // Reject a manifest for another network.
+        let mut candidate = manifest.clone();
+        candidate.network = 1;
+        assert!(candidate.check().is_err());
+
+        // Reject a manifest that does not allocate the starting supply.
+        let mut candidate = manifest.clone();
+        candidate.accounts[0].balance -= 1;
+        assert!(candidate.check().is_err());
+
+        // Reject a manifest that lists an account twice.
+        let mut candidate = manifest.clone();
+        candidate.accounts.push(candidate.accounts[0].clone());
+        assert!(candidate.check().is_err());
+
+        // Reject a manifest whose genesis account holds no public balance.
+        let mut candidate = manifest.clone();
+        candidate.genesis_private_key = PrivateKey::new(rng).unwrap();
+        assert!(candidate.check().is_err());
+
+        // Reject a manifest with unknown fields.
+        assert!(toml::from_str::<GenesisManifest<CurrentNetwork>>(&(sample_manifest(rng) + "foo = 1\n")).is_err());
+    }
+}
@@ -18,12 +18,30 @@ pub use account::*;
 mod clean;
 pub use clean::*;
 
+mod crawl;
+pub use crawl::*;
+
 mod developer;
 pub use developer::*;
 
+mod genesis;
+pub use genesis::*;
+
+mod replay;
+pub use replay::*;
+
 mod start;
 pub use start::*;
 
+mod storage;
+pub use storage::*;
+
+mod top;
+pub use top::*;
+
+mod trace_view;
+pub use trace_view::*;
+
 mod update;
 pub use update::*;
 
@@ -55,10 +73,22 @@ pub enum Command {
     Account(Account),
     #[clap(name = "clean")]
     Clean(Clean),
+    #[clap(name = "crawl")]
+    Crawl(Crawl),
     #[clap(subcommand)]
     Developer(Developer),
+    #[clap(subcommand)]
+    Genesis(Genesis),
+    #[clap(name = "replay")]
+    Replay(Replay),
     #[clap(name = "start")]
     Start(Box<Start>),
+    #[clap(subcommand)]
+    Storage(Storage),
+    #[clap(name = "top")]
+    Top(Top),
+    #[clap(name = "trace-view")]
+    TraceView(TraceView),
     #[clap(name = "update")]
     Update(Update),
 }
@@ -69,8 +99,14 @@ impl Command {
         match self {
             Self::Account(command) => command.parse(),
             Self::Clean(command) => command.parse(),
+            Self::Crawl(command) => command.parse(),
             Self::Developer(command) => command.parse(),
+            Self::Genesis(command) => command.parse(),
+            Self::Replay(command) => command.parse(),
             Self::Start(command) => command.parse(),
+            Self::Storage(command) => command.parse(),
+            Self::Top(command) => command.parse(),
+            Self::TraceView(command) => command.parse(),
             Self::Update(command) => command.parse(),
         }
     }
@@ -0,0 +1,194 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::consensus::{ConsensusArchive, ConsensusOutput};
+use snarkvm::{
+    console::network::{Network, Testnet3},
+    ledger::{block::Block, store::helpers::rocksdb::ConsensusDB, Ledger},
+    prelude::FromBytes,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+/// Replays the blocks of a reference ledger into an empty ledger, and reports the first divergence.
+///
+/// Each block is checked against the consensus output archived for it, if there is one,
+/// and is then re-validated and re-finalized on top of the replayed state.
+#[derive(Debug, Parser)]
+pub struct Replay {
+    /// Specify the network of the ledgers.
+    #[clap(default_value = "3", long = "network")]
+    pub network: u16,
+    /// Specify the path to the reference ledger (the node must be stopped)
+    #[clap(long = "reference")]
+    pub reference: PathBuf,
+    /// Specify the path to the consensus archive [default: the archive in the reference ledger]
+    #[clap(long = "archive")]
+    pub archive: Option<PathBuf>,
+    /// Specify the path to an empty directory for the replayed ledger [default: a temporary directory]
+    #[clap(long = "ledger")]
+    pub ledger: Option<PathBuf>,
+    /// Specify the path to a file with the genesis block [default: the genesis block of the network]
+    #[clap(long = "genesis")]
+    pub genesis: Option<PathBuf>,
+    /// Specify the height to replay up to [default: the latest height of the reference ledger]
+    #[clap(long = "end-height")]
+    pub end_height: Option<u32>,
+}
+
+impl Replay {
+    /// Replays the reference ledger.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            3 => self.replay::<Testnet3>(),
+            _ => bail!("Unsupported network ID"),
+        }
+    }
+
+    /// Replays the reference ledger, returning the replay report.
+    fn replay<N: Network>(&self) -> Result<String> {
+        // Load the genesis block.
+        let genesis = match &self.genesis {
+            Some(path) => Block::<N>::read_le(&std::fs::read(path)?[..])?,
+            None => Block::<N>::from_bytes_le(N::genesis_bytes())?,
+        };
+
+        // Load the archived consensus outputs, indexed by block height.
+        let archive_path =
+            self.archive.clone().unwrap_or_else(|| self.reference.join(ConsensusArchive::<N>::FILE_NAME));
+        if !archive_path.exists() {
+            bail!("The consensus archive was not found at '{}'", archive_path.display())
+        }
+        let archive = ConsensusArchive::<N>::open_read_only(archive_path)?;
+        let mut outputs = BTreeMap::new();
+        for round in archive.rounds() {
+            if let Some(output) = archive.get(round)? {
+                outputs.insert(output.block_height, output);
+            }
+        }
+
+        // Open the reference ledger, and an empty ledger to replay the blocks into.
+        let reference_mode = StorageMode::Custom(self.reference.clone());
+        let reference = Ledger::<N, ConsensusDB<N>>::load(genesis.clone(), reference_mode)?;
+        let ledger_path = match &self.ledger {
+            Some(path) => path.clone(),
+            None => std::env::temp_dir().join(format!("snarkos-replay-{}", rand::random::<u64>())),
+        };
+        if ledger_path.read_dir().map_or(false, |mut entries| entries.next().is_some()) {
+            bail!("The replayed ledger directory '{}' is not empty", ledger_path.display())
+        }
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, StorageMode::Custom(ledger_path.clone()))?;
+        println!("🔁 Replaying into the ledger at '{}'\n", ledger_path.display());
+
+        // Ensure the ledgers start from the same genesis block.
+        if reference.get_hash(0)? != ledger.get_hash(0)? {
+            bail!("The reference ledger was not created from the given genesis block")
+        }
+
+        let end_height = self.end_height.unwrap_or(u32::MAX).min(reference.latest_height());
+        let rng = &mut rand::thread_rng();
+        for height in 1..=end_height {
+            let block = reference.get_block(height)?;
+            // Ensure the reference block is the block recorded at this height.
+            let recorded_hash = reference.get_hash(height)?;
+            if block.hash() != recorded_hash {
+                return Ok(Self::divergence(height, "the reference block does not match the recorded block hash"));
+            }
+            // Ensure the block matches the consensus output archived for it.
+            if let Some(output) = outputs.get(&height) {
+                if let Some(divergence) = Self::check_output(output, &block)? {
+                    return Ok(Self::divergence(height, &divergence));
+                }
+            }
+            // Ensure the block is valid on top of the replayed state.
+            if let Err(error) = ledger.check_next_block(&block, rng) {
+                return Ok(Self::divergence(height, &format!("the replayed ledger rejected the block - {error}")));
+            }
+            ledger.advance_to_next_block(&block)?;
+            // Ensure the replayed block is the recorded block.
+            if ledger.get_block(height)?.hash() != recorded_hash {
+                return Ok(Self::divergence(height, "the replayed block hash does not match the recorded block hash"));
+            }
+            if height % 1000 == 0 {
+                println!("Replayed {height} of {end_height} blocks");
+            }
+        }
+
+        let num_archived = outputs.range(1..=end_height).count();
+        Ok(format!("✅ Replayed {end_height} blocks ({num_archived} with archived outputs) without a divergence"))
+    }
+
+    /// Returns the divergence between the archived consensus output and the reference block, if there is one.
+    fn check_output<N: Network>(output: &ConsensusOutput<N>, block: &Block<N>) -> Result<Option<String>> {
+        // Ensure the block hashes match.
+        if output.block_hash != block.hash() {
+            return Ok(Some(format!(
+                "the archived block hash '{}' (round {}) does not match the reference block hash '{}'",
+                output.block_hash,
+                output.round,
+                block.hash()
+            )));
+        }
+        // Ensure the block includes the transactions in the order they were output by the BFT.
+        // Note: The output may contain transactions that were not included in the block (e.g. aborted transactions).
+        let positions = output.transaction_ids.iter().enumerate().map(|(i, id)| (*id, i)).collect::<HashMap<_, _>>();
+        let mut previous_position = None;
+        for transaction in block.transactions().iter() {
+            let transaction_id = transaction.to_unconfirmed_transaction_id()?;
+            let position = match positions.get(&transaction_id) {
+                Some(position) => *position,
+                None => return Ok(Some(format!("transaction '{transaction_id}' is not in the archived output"))),
+            };
+            if previous_position.map_or(false, |previous| position <= previous) {
+                return Ok(Some(format!("transaction '{transaction_id}' is out of order with the archived output")));
+            }
+            previous_position = Some(position);
+        }
+        Ok(None)
+    }
+
+    /// Returns the report for a divergence at the given height.
+    fn divergence(height: u32, reason: &str) -> String {
+        format!("❌ {} {reason}", format!("The ledgers diverge at block {height}:").bold())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    #[test]
+    fn clap_snarkos_replay() {
+        let arg_vec = vec!["snarkos", "replay", "--reference", "/tmp/reference", "--end-height", "100"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::Replay(replay) = cli.command {
+            assert_eq!(replay.network, 3);
+            assert_eq!(replay.reference, PathBuf::from("/tmp/reference"));
+            assert_eq!(replay.archive, None);
+            assert_eq!(replay.ledger, None);
+            assert_eq!(replay.end_height, Some(100));
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+}
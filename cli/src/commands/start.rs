@@ -12,19 +12,53 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkos_account::Account;
+use snarkos_account::{Account, AccountRoles};
 use snarkos_display::Display;
-use snarkos_node::{bft::MEMORY_POOL_PORT, router::messages::NodeType, Node};
+use snarkos_node::{
+    bft::helpers::{BatchTuningBounds, MaxTransactionSize, ProgramDenyList, ProtocolUpgrade, TransactionValidator},
+    cdn::{CdnConfig, DEFAULT_CDN_CONCURRENCY, DEFAULT_CDN_MAX_PENDING_BUNDLES},
+    router::{
+        messages::NodeType,
+        AuditLog,
+        BandwidthCaps,
+        FanoutPolicy,
+        Liveness,
+        MessageRecorder,
+        PeerGroupConfig,
+        DEFAULT_AUDIT_LOG_MAX_SIZE,
+        DEFAULT_MAXIMUM_CONNECTIONS_PER_IDENTITY,
+        DEFAULT_MESSAGE_TRACE_MAX_FILES,
+        DEFAULT_MESSAGE_TRACE_MAX_SIZE,
+    },
+    recover_latest_blocks,
+    rest::DebugBundleConfig,
+    AlertConfig,
+    DataLayout,
+    DevPortAllocation,
+    EphemeralFixtures,
+    IndexAuditConfig,
+    LockFile,
+    Node,
+    WebhookConfig,
+    DEFAULT_INDEX_AUDIT_INTERVAL_IN_SECS,
+    DEFAULT_INDEX_AUDIT_SAMPLE_SIZE,
+    DEFAULT_SAFE_MODE_DEPTH,
+};
 use snarkvm::{
     console::{
         account::{Address, PrivateKey},
         algorithms::Hash,
         network::{Network, Testnet3},
+        program::ProgramID,
     },
     ledger::{
         block::Block,
         committee::{Committee, MIN_VALIDATOR_STAKE},
-        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        store::{
+            helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
+            ConsensusStore,
+        },
+        Ledger,
     },
     prelude::{FromBytes, ToBits, ToBytes},
     synthesizer::VM,
@@ -32,13 +66,17 @@ use snarkvm::{
 };
 
 use aleo_std::StorageMode;
-use anyhow::{bail, ensure, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use clap::Parser;
 use colored::Colorize;
 use core::str::FromStr;
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
-use std::{net::SocketAddr, path::PathBuf};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use tokio::runtime::{self, Runtime};
 
 /// The recommended minimum number of 'open files' limit for a validator.
@@ -50,6 +88,8 @@ const RECOMMENDED_MIN_NOFILES_LIMIT: u64 = 2048;
 const DEVELOPMENT_MODE_RNG_SEED: u64 = 1234567890u64;
 /// The development mode number of genesis committee members.
 const DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS: u16 = 4;
+/// The ephemeral development network RNG seed, from which the missing fixture private keys are generated.
+const EPHEMERAL_FIXTURES_RNG_SEED: u64 = 987654321u64;
 
 /// Starts the snarkOS node.
 #[derive(Clone, Debug, Parser)]
@@ -74,6 +114,22 @@ pub struct Start {
     /// Specify the path to a file containing the account private key of the node
     #[clap(long = "private-key-file")]
     pub private_key_file: Option<PathBuf>,
+    /// Specify the private key of the peer-to-peer identity of a validator, separate from the validator account
+    #[clap(long = "p2p-private-key")]
+    pub p2p_private_key: Option<String>,
+    /// Specify the path to a file containing the private key of the peer-to-peer identity of a validator
+    #[clap(long = "p2p-private-key-file")]
+    pub p2p_private_key_file: Option<PathBuf>,
+    /// Specify the private key that signs the admin tokens of the REST server, separate from the node account
+    #[clap(long = "admin-private-key")]
+    pub admin_private_key: Option<String>,
+    /// Specify the path to a file containing the private key that signs the admin tokens of the REST server
+    #[clap(long = "admin-private-key-file")]
+    pub admin_private_key_file: Option<PathBuf>,
+    /// Specify the names of the operators of the REST server, as `NAME,NAME`, to issue each of them a separate
+    /// admin token, under which their actions are recorded in the action journal
+    #[clap(long = "rest-operators")]
+    pub rest_operators: Option<String>,
 
     /// Specify the IP address and port for the node server
     #[clap(default_value = "0.0.0.0:4133", long = "node")]
@@ -87,6 +143,85 @@ pub struct Start {
     /// Specify the IP address and port of the validator(s) to connect to
     #[clap(default_value = "", long = "validators")]
     pub validators: String,
+    /// Specify the path to a JSON file with named peer groups, and the policies they grant to their peers
+    #[clap(long = "peer-groups")]
+    pub peer_groups: Option<PathBuf>,
+    /// Specify the maximum number of connections a single node identity may hold; older duplicates are closed
+    #[clap(default_value_t = DEFAULT_MAXIMUM_CONNECTIONS_PER_IDENTITY, long = "max-connections-per-identity")]
+    pub max_connections_per_identity: usize,
+    /// Specify the idle time in seconds after which TCP keepalive probes are sent to a peer (0 to disable)
+    #[clap(default_value_t = Liveness::DEFAULT_KEEPALIVE_IN_SECS, long = "tcp-keepalive-secs")]
+    pub tcp_keepalive_secs: u64,
+    /// Specify the time in seconds after which a peer that has sent no messages is disconnected
+    #[clap(default_value_t = Liveness::DEFAULT_RADIO_SILENCE_IN_SECS, long = "radio-silence-secs")]
+    pub radio_silence_secs: u64,
+    /// Specify the time in seconds within which a peer must answer a ping, before the connection is reaped
+    #[clap(default_value_t = Liveness::DEFAULT_PONG_TIMEOUT_IN_SECS, long = "pong-timeout-secs")]
+    pub pong_timeout_secs: u64,
+    /// Specify the cap on the bandwidth of the messages sent to all clients together, in bytes per second
+    #[clap(long = "bandwidth-cap-clients")]
+    pub bandwidth_cap_clients: Option<u64>,
+    /// Specify the cap on the bandwidth of the messages sent to all validators together, in bytes per second
+    #[clap(long = "bandwidth-cap-validators")]
+    pub bandwidth_cap_validators: Option<u64>,
+    /// Specify the cap on the bandwidth of the messages sent to all provers together, in bytes per second
+    #[clap(long = "bandwidth-cap-provers")]
+    pub bandwidth_cap_provers: Option<u64>,
+    /// Specify the size in bytes from which a propagated payload is only sent to the validators and a sample of peers
+    #[clap(default_value_t = FanoutPolicy::DEFAULT_LARGE_PAYLOAD_SIZE, long = "fanout-payload-size")]
+    pub fanout_payload_size: usize,
+    /// If the flag is set, the node propagates every message to every peer, regardless of its size
+    #[clap(long = "full-propagation", conflicts_with = "fanout_payload_size")]
+    pub full_propagation: bool,
+    /// Specify the number of arriving transactions upon which the validator proposes a batch without delay
+    #[clap(long = "proposal-trigger-transactions")]
+    pub proposal_trigger_transactions: Option<usize>,
+    /// Specify the sum of priority fees (in microcredits) upon which the validator proposes a batch without delay
+    #[clap(long = "proposal-trigger-fees")]
+    pub proposal_trigger_fees: Option<u64>,
+    /// If the flag is set, the validator tunes the batch size and delay at runtime, based on the transaction
+    /// arrival rate and the ordering latency, rather than using the static values
+    #[clap(long = "batch-tuning")]
+    pub batch_tuning: bool,
+    /// Specify the bounds of the tuned batch size in transmissions, as `MIN,MAX`
+    #[clap(long = "batch-size-bounds", value_delimiter = ',', requires = "batch_tuning")]
+    pub batch_size_bounds: Option<Vec<usize>>,
+    /// Specify the bounds of the tuned batch delay in milliseconds, as `MIN,MAX`
+    #[clap(long = "batch-delay-bounds", value_delimiter = ',', requires = "batch_tuning")]
+    pub batch_delay_bounds: Option<Vec<u64>>,
+    /// If the flag is set, the validator widens the tolerance for future batch timestamps when its clock is behind
+    /// the network time, as estimated from the timestamps in the pings and pongs of the committee members
+    #[clap(long = "adjust-clock-skew")]
+    pub adjust_clock_skew: bool,
+    /// Specify the maximum number of transmissions the validator checks concurrently when validating a batch
+    #[clap(long = "validation-concurrency")]
+    pub validation_concurrency: Option<usize>,
+    /// Specify the height of the last beacon-produced block, after which the blocks are produced by the validators
+    #[clap(long = "bft-activation-height")]
+    pub bft_activation_height: Option<u32>,
+    /// Specify the minimum priority fee (in microcredits) of the transactions the validator batches
+    #[clap(long = "min-priority-fee")]
+    pub min_priority_fee: Option<u64>,
+    /// Specify the program IDs whose deployments and executions the validator does not batch, e.g. `foo.aleo,bar.aleo`
+    #[clap(default_value = "", long = "deny-programs")]
+    pub deny_programs: String,
+    /// Specify the maximum size (in bytes) of the transactions the validator batches
+    #[clap(long = "max-transaction-size")]
+    pub max_transaction_size: Option<usize>,
+    /// Specify the protocol version the validator signals its readiness for, which requires `--upgrade-height`
+    #[clap(long = "upgrade-version", requires = "upgrade_height")]
+    pub upgrade_version: Option<u32>,
+    /// Specify the block height at which the signaled protocol version activates, if a quorum of the committee
+    /// is ready for it
+    #[clap(long = "upgrade-height", requires = "upgrade_version")]
+    pub upgrade_height: Option<u32>,
+    /// If the flag is set, the validator only accepts the inbound connections of the committee members and of the
+    /// relays given in `--relays`, while it still dials out to any peer
+    #[clap(long)]
+    pub hidden: bool,
+    /// Specify the addresses of the relays whose inbound connections a hidden validator accepts, as `ADDRESS,ADDRESS`
+    #[clap(long = "relays", requires = "hidden")]
+    pub relays: Option<String>,
 
     /// Specify the IP address and port for the REST server
     #[clap(default_value = "0.0.0.0:3033", long = "rest")]
@@ -94,10 +229,50 @@ pub struct Start {
     /// Specify the requests per second (RPS) rate limit per IP for the REST server
     #[clap(default_value = "10", long = "rest-rps")]
     pub rest_rps: u32,
+    /// Specify the public URL of the REST server, which the validator signs and advertises to its peers
+    #[clap(long = "rest-public-url")]
+    pub rest_public_url: Option<String>,
     /// If the flag is set, the node will not initialize the REST server
     #[clap(long)]
     pub norest: bool,
 
+    /// If the flag is set, the client runs as a read-only replica, which serves the ledger over REST
+    /// without relaying or accepting new transactions and solutions
+    #[clap(long)]
+    pub readonly: bool,
+
+    /// If the flag is set, the client applies the blocks covered by a checkpoint certificate from its trusted peers
+    /// after verifying only their hashes and signatures, and fully verifies the blocks after the checkpoint
+    #[clap(long = "fast-sync")]
+    pub fast_sync: bool,
+
+    /// If the flag is set, the node periodically re-derives the secondary indexes of a sample of its blocks,
+    /// and reports the index entries that do not match the blocks
+    #[clap(long = "index-audit")]
+    pub index_audit: bool,
+    /// Specify the interval in seconds in between the rounds of the index audit
+    #[clap(default_value_t = DEFAULT_INDEX_AUDIT_INTERVAL_IN_SECS, long = "index-audit-interval-secs")]
+    pub index_audit_interval_secs: u64,
+    /// Specify the number of blocks that are sampled in each round of the index audit
+    #[clap(default_value_t = DEFAULT_INDEX_AUDIT_SAMPLE_SIZE, long = "index-audit-sample-size")]
+    pub index_audit_sample_size: usize,
+    /// If the flag is set, the index audit rewrites the inconsistent transition and program indexes from the blocks
+    #[clap(long = "index-audit-repair", requires = "index_audit")]
+    pub index_audit_repair: bool,
+
+    /// If the flag is set, the node relays transactions along a random path of peers before they are broadcast
+    /// (Dandelion), which obscures the IP address from which a transaction originated
+    #[clap(long)]
+    pub dandelion: bool,
+
+    /// Specify the number of latest blocks a validator verifies when it starts in safe mode after an unclean shutdown
+    #[clap(default_value_t = DEFAULT_SAFE_MODE_DEPTH, long = "safe-mode-depth")]
+    pub safe_mode_depth: u32,
+    /// If the flag is set, a validator in safe mode rejoins consensus once its ledger is verified,
+    /// instead of waiting for the operator to confirm
+    #[clap(long)]
+    pub safe_mode_resume: bool,
+
     /// If the flag is set, the node will not render the display
     #[clap(long)]
     pub nodisplay: bool,
@@ -110,20 +285,80 @@ pub struct Start {
     /// Enables the metrics exporter
     #[clap(default_value = "false", long = "metrics")]
     pub metrics: bool,
-
-    /// Enables the node to prefetch initial blocks from a CDN
+    /// Specify the comma-separated labels attached to every metric, e.g. `node=validator-0,region=eu-west`
+    #[clap(long = "metrics-labels", requires = "metrics")]
+    pub metrics_labels: Option<String>,
+    /// Specify the URL of a Prometheus push gateway, to push the metrics of a node that cannot be scraped,
+    /// e.g. `http://pushgateway:9091/metrics/job/snarkos/instance/validator-0`
+    #[clap(long = "metrics-push-gateway", requires = "metrics")]
+    pub metrics_push_gateway: Option<String>,
+    /// Specify the interval in seconds in between the pushes to the push gateway
+    #[clap(default_value_t = metrics::DEFAULT_PUSH_INTERVAL_IN_SECS, long = "metrics-push-interval")]
+    pub metrics_push_interval: u64,
+    /// Specify the path to a JSONL file, to which the node appends an audit log of its peer connections
+    #[clap(long = "audit-log")]
+    pub audit_log: Option<PathBuf>,
+    /// Specify the path to a JSONL file, to which the node appends a journal of the admin actions taken over REST
+    #[clap(long = "action-journal")]
+    pub action_journal: Option<PathBuf>,
+    /// Specify the path to a JSON file, to which the REST server persists its accounting of the validator rewards.
+    /// The rewards are only accounted for, and served over REST, if this is set
+    #[clap(long = "rewards-tracker")]
+    pub rewards_tracker: Option<PathBuf>,
+    /// Specify the path to a directory, to which the node records every message of its router in rotating files,
+    /// for debugging development networks (see `snarkos trace-view`)
+    #[clap(long = "record-messages")]
+    pub record_messages: Option<PathBuf>,
+    /// If the flag is set, the serialized messages are recorded, in addition to their metadata
+    #[clap(long, requires = "record_messages")]
+    pub record_payloads: bool,
+    /// Specify the path to a JSON file with the alerting rules of the node
+    #[clap(long = "alerts")]
+    pub alerts: Option<PathBuf>,
+    /// Specify the path to a JSON file with the webhooks, to which the node posts its block and incident events
+    #[clap(long = "webhooks")]
+    pub webhooks: Option<PathBuf>,
+    /// Specify the path to a JSON file with the faucet configuration, to start a faucet on a validator
+    #[cfg(feature = "faucet")]
+    #[clap(long = "faucet")]
+    pub faucet: Option<PathBuf>,
+
+    /// Enables the node to prefetch initial blocks from a CDN, or from multiple comma-separated CDNs
     #[clap(default_value = "https://s3.us-west-1.amazonaws.com/testnet3.blocks/phase3", long = "cdn")]
     pub cdn: String,
     /// If the flag is set, the node will not prefetch from a CDN
     #[clap(long)]
     pub nocdn: bool,
+    /// Specify the number of block bundles that are downloaded concurrently from the CDN
+    #[clap(default_value_t = DEFAULT_CDN_CONCURRENCY, long = "cdn-concurrency")]
+    pub cdn_concurrency: usize,
+    /// Specify the maximum number of downloaded block bundles that may wait to be added to the ledger
+    #[clap(default_value_t = DEFAULT_CDN_MAX_PENDING_BUNDLES, long = "cdn-max-pending-bundles")]
+    pub cdn_max_pending_bundles: usize,
 
     /// Enables development mode, specify a unique ID for this node
     #[clap(long)]
     pub dev: Option<u16>,
+    /// If development mode is enabled, specify the first port of the ranges from which the router, REST, and BFT
+    /// ports are allocated by development ID, to run several development networks side by side
+    #[clap(long = "port-base", requires = "dev")]
+    pub port_base: Option<u16>,
     /// If development mode is enabled, specify the number of genesis validators (default: 4)
     #[clap(long)]
     pub dev_num_validators: Option<u16>,
+    /// If development mode is enabled, discover the nodes on the local network over mDNS, instead of
+    /// trusting the nodes on the local ports of the other development IDs
+    #[clap(long)]
+    pub mdns: bool,
+    /// Runs an ephemeral development network in memory on random local ports, seeded with fixture accounts and programs
+    #[clap(long)]
+    pub dev_ephemeral: bool,
+    /// If the ephemeral development network is enabled, specify the path to a JSON file with its fixtures
+    #[clap(long)]
+    pub dev_fixtures: Option<PathBuf>,
+    /// Specify the path to a custom genesis block, e.g. one built with `snarkos genesis build`
+    #[clap(long = "genesis")]
+    pub genesis: Option<PathBuf>,
     /// Specify the path to a directory containing the ledger
     #[clap(long = "storage_path")]
     pub storage_path: Option<PathBuf>,
@@ -197,25 +432,193 @@ impl Start {
         }
     }
 
-    /// Returns the CDN to prefetch initial blocks from, from the given configurations.
-    fn parse_cdn(&self) -> Option<String> {
+    /// Returns the bounds of the batch tuning, using the default bounds where none are given.
+    fn parse_batch_tuning_bounds(&self) -> Result<BatchTuningBounds> {
+        let default = BatchTuningBounds::default();
+        let batch_size = match self.batch_size_bounds.as_deref() {
+            Some(&[min, max]) => (min, max),
+            Some(_) => bail!("The batch size bounds must be given as 'MIN,MAX'"),
+            None => (default.min_batch_size, default.max_batch_size),
+        };
+        let batch_delay = match self.batch_delay_bounds.as_deref() {
+            Some(&[min, max]) => (min, max),
+            Some(_) => bail!("The batch delay bounds must be given as 'MIN,MAX'"),
+            None => (default.min_batch_delay_ms, default.max_batch_delay_ms),
+        };
+        BatchTuningBounds::new(batch_size, batch_delay)
+    }
+
+    /// Returns the local transaction validation pipeline of a validator, from the given configurations.
+    fn parse_transaction_validator<N: Network>(&self) -> Result<Option<TransactionValidator<N>>> {
+        let mut transaction_validator = TransactionValidator::default();
+        if let Some(min_priority_fee) = self.min_priority_fee {
+            transaction_validator = transaction_validator.with_minimum_priority_fee(min_priority_fee);
+        }
+        if !self.deny_programs.is_empty() {
+            let program_ids = self
+                .deny_programs
+                .split(',')
+                .map(|program_id| {
+                    ProgramID::<N>::from_str(program_id.trim()).map_err(|e| {
+                        anyhow!("The program ID supplied to --deny-programs ('{program_id}') is malformed: {e}")
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            transaction_validator = transaction_validator.with_policy(ProgramDenyList::new(program_ids));
+        }
+        if let Some(max_transaction_size) = self.max_transaction_size {
+            transaction_validator = transaction_validator.with_policy(MaxTransactionSize::new(max_transaction_size));
+        }
+        match transaction_validator.is_basic() {
+            true => Ok(None),
+            false => Ok(Some(transaction_validator)),
+        }
+    }
+
+    /// Returns the addresses of the relays whose inbound connections a hidden validator accepts.
+    fn parse_relays<N: Network>(&self) -> Result<Vec<Address<N>>> {
+        let Some(relays) = &self.relays else {
+            return Ok(vec![]);
+        };
+        relays
+            .split(',')
+            .map(|relay| {
+                Address::<N>::from_str(relay.trim())
+                    .map_err(|e| anyhow!("The address supplied to --relays ('{relay}') is malformed: {e}"))
+            })
+            .collect()
+    }
+
+    /// Returns the names of the operators of the REST server, who are each issued a separate admin token.
+    fn parse_rest_operators(&self) -> Result<Vec<String>> {
+        let Some(operators) = &self.rest_operators else {
+            return Ok(vec![]);
+        };
+        ensure!(!self.norest, "The '--rest-operators' flag requires the REST server to be enabled");
+        let mut names = Vec::new();
+        for name in operators.split(',').map(str::trim) {
+            ensure!(
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+                "The operator name supplied to --rest-operators ('{name}') must be alphanumeric"
+            );
+            ensure!(!names.iter().any(|n| n == name), "The operator '{name}' is given more than once");
+            names.push(name.to_string());
+        }
+        Ok(names)
+    }
+
+    /// Acquires the lock file of a validator. If the previous run did not shut down cleanly, the validator
+    /// starts in safe mode: it verifies the latest blocks of the ledger, rebuilds their indexes, and waits for
+    /// the operator to confirm before it rejoins consensus, so that a corrupted ledger is not used to sign or gossip.
+    async fn parse_safe_mode<N: Network>(&self, genesis: &Block<N>, storage_mode: &StorageMode) -> Result<LockFile> {
+        use std::io::{BufRead, IsTerminal};
+
+        let (lock_file, is_unclean) = LockFile::acquire(LockFile::path::<N>(storage_mode))?;
+        if !is_unclean {
+            return Ok(lock_file);
+        }
+        println!("⚠️  The validator did not shut down cleanly, starting in safe mode.\n");
+
+        // Verify the latest blocks of the ledger, and rebuild their indexes.
+        let (genesis, mode, depth) = (genesis.clone(), storage_mode.clone(), self.safe_mode_depth);
+        let recovery = tokio::task::spawn_blocking(move || {
+            recover_latest_blocks(Ledger::<N, ConsensusDB<N>>::load(genesis, mode)?, depth)
+        });
+        match recovery.await? {
+            Ok(report) => println!(
+                "✅ Verified the latest {} blocks of the ledger, and rebuilt {} index entries.\n",
+                report.num_blocks, report.num_repaired
+            ),
+            Err(error) => {
+                snarkos_node::log_clean_error(storage_mode);
+                bail!("Safe mode failed to recover the ledger - {error}")
+            }
+        }
+
+        // Wait for the operator to confirm, before rejoining consensus.
+        if !self.safe_mode_resume {
+            ensure!(
+                std::io::stdin().is_terminal(),
+                "Safe mode requires the operator to confirm - restart with '--safe-mode-resume' to rejoin consensus"
+            );
+            let confirmation = tokio::task::spawn_blocking(|| {
+                println!("Type 'resume' to rejoin consensus:");
+                let mut lines = std::io::stdin().lock().lines();
+                loop {
+                    match lines.next().transpose()? {
+                        Some(line) if line.trim() == "resume" => return Ok(()),
+                        Some(_) => println!("Type 'resume' to rejoin consensus:"),
+                        None => bail!("Safe mode was not confirmed by the operator"),
+                    }
+                }
+            });
+            confirmation.await??;
+        }
+        Ok(lock_file)
+    }
+
+    /// Initializes the metrics with their labels and push gateway, if the metrics are enabled.
+    /// The metrics that cannot be pushed are spooled in the given directory, if any.
+    fn initialize_metrics(&self, spool_dir: Option<&Path>) -> Result<()> {
+        if !self.metrics {
+            return Ok(());
+        }
+        // Parse the comma-separated labels.
+        let labels = self
+            .metrics_labels
+            .iter()
+            .flat_map(|labels| labels.split(','))
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(|label| match label.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.trim().to_string())),
+                _ => bail!("Invalid metrics label '{label}' - expected 'key=value'"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Configure the push gateway.
+        let push_gateway = self.metrics_push_gateway.clone().map(|endpoint| {
+            let mut config = metrics::PushGatewayConfig::new(endpoint);
+            config.interval = Duration::from_secs(self.metrics_push_interval.max(1));
+            config.spool_path = spool_dir.map(|dir| dir.join(metrics::PushGatewayConfig::SPOOL_FILE_NAME));
+            config
+        });
+        metrics::initialize_metrics_with(&labels, push_gateway);
+        Ok(())
+    }
+
+    /// Returns the CDNs to prefetch initial blocks from, from the given configurations.
+    fn parse_cdn(&self) -> Option<Vec<String>> {
         // Determine if the node type is not declared.
         let is_no_node_type = !(self.validator || self.prover || self.client);
+        // Parse the comma-separated CDNs.
+        let cdns =
+            self.cdn.split(',').map(str::trim).filter(|url| !url.is_empty()).map(String::from).collect::<Vec<_>>();
 
         // Disable CDN if:
         //  1. The node is in development mode.
         //  2. The user has explicitly disabled CDN.
         //  3. The node is a prover (no need to sync).
         //  4. The node type is not declared (defaults to client) (no need to sync).
-        if self.dev.is_some() || self.cdn.is_empty() || self.nocdn || self.prover || is_no_node_type {
+        if self.dev.is_some() || cdns.is_empty() || self.nocdn || self.prover || is_no_node_type {
             None
         }
-        // Enable the CDN otherwise.
+        // Enable the CDNs otherwise.
         else {
-            Some(self.cdn.clone())
+            Some(cdns)
         }
     }
 
+    /// Returns the configuration of the sync from the CDNs, if the node prefetches initial blocks from them.
+    fn parse_cdn_config(&self) -> Result<Option<CdnConfig>> {
+        ensure!(self.cdn_concurrency > 0, "The '--cdn-concurrency' flag must be greater than 0");
+        ensure!(self.cdn_max_pending_bundles > 0, "The '--cdn-max-pending-bundles' flag must be greater than 0");
+        Ok(self.parse_cdn().map(|base_urls| CdnConfig {
+            base_urls,
+            concurrency: self.cdn_concurrency,
+            max_pending_bundles: self.cdn_max_pending_bundles,
+        }))
+    }
+
     /// Read the private key directly from an argument or from a filesystem location,
     /// returning the Aleo account.
     fn parse_private_key<N: Network>(&self) -> Result<Account<N>> {
@@ -255,50 +658,111 @@ impl Start {
         }
     }
 
+    /// Returns the accounts of the node for each of its roles, given the account of the node.
+    /// The peer-to-peer identity and the admin account default to the account of the node.
+    fn parse_account_roles<N: Network>(&self, account: Account<N>) -> Result<AccountRoles<N>> {
+        let mut accounts = AccountRoles::new(account);
+        // Parse the peer-to-peer identity, which only a validator can separate from its account.
+        if let Some(p2p) = parse_role_key("p2p-private-key", &self.p2p_private_key, &self.p2p_private_key_file)? {
+            ensure!(self.validator, "The '--p2p-private-key' flag is only supported for validators");
+            accounts = accounts.with_p2p(p2p);
+            ensure!(accounts.is_p2p_separated(), "The peer-to-peer identity must differ from the validator account");
+        }
+        // Parse the admin account.
+        let admin = parse_role_key("admin-private-key", &self.admin_private_key, &self.admin_private_key_file)?;
+        if let Some(admin) = admin {
+            ensure!(!self.norest, "The '--admin-private-key' flag requires the REST server to be enabled");
+            accounts = accounts.with_admin(admin);
+            ensure!(accounts.is_admin_separated(), "The admin account must differ from the other accounts of the node");
+        }
+        Ok(accounts)
+    }
+
+    /// Returns a copy of the configuration with its private keys redacted, for the debug bundles.
+    fn redacted(&self) -> Self {
+        const REDACTED: &str = "<redacted>";
+        let redact = |key: &Option<String>| key.as_ref().map(|_| REDACTED.to_string());
+        Self {
+            private_key: redact(&self.private_key),
+            p2p_private_key: redact(&self.p2p_private_key),
+            admin_private_key: redact(&self.admin_private_key),
+            ..self.clone()
+        }
+    }
+
     /// Updates the configurations if the node is in development mode.
+    /// Returns the allocation of the ports of the development network, if the node is in development mode.
     fn parse_development(
         &mut self,
         trusted_peers: &mut Vec<SocketAddr>,
         trusted_validators: &mut Vec<SocketAddr>,
-    ) -> Result<()> {
+    ) -> Result<Option<DevPortAllocation>> {
         // If `--dev` is set, assume the dev nodes are initialized from 0 to `dev`,
         // and add each of them to the trusted peers. In addition, set the node IP to `4130 + dev`,
-        // and the REST IP to `3030 + dev`.
-        if let Some(dev) = self.dev {
-            // Add the dev nodes to the trusted peers.
-            if trusted_peers.is_empty() {
-                for i in 0..dev {
-                    if i != dev {
-                        trusted_peers.push(SocketAddr::from_str(&format!("127.0.0.1:{}", 4130 + i))?);
-                    }
+        // and the REST IP to `3030 + dev` (or the ports of `dev` in the ranges from the `--port-base`).
+        ensure!(!self.mdns || self.dev.is_some(), "The '--mdns' flag is only supported in development mode");
+        let Some(dev) = self.dev else {
+            return Ok(None);
+        };
+        let allocation = DevPortAllocation::new(self.port_base)?;
+        // Add the dev nodes to the trusted peers, unless they are discovered over mDNS.
+        if trusted_peers.is_empty() && !self.mdns {
+            for i in 0..dev {
+                if i != dev {
+                    trusted_peers.push(SocketAddr::from(([127, 0, 0, 1], allocation.node_port(i)?)));
                 }
             }
-            // Add the dev nodes to the trusted validators.
-            if trusted_validators.is_empty() {
-                // To avoid ambiguity, we define the first few nodes to be the trusted validators to connect to.
-                for i in 0..2 {
-                    if i != dev {
-                        trusted_validators.push(SocketAddr::from_str(&format!("127.0.0.1:{}", MEMORY_POOL_PORT + i))?);
-                    }
+        }
+        // Add the dev nodes to the trusted validators, unless they are discovered over mDNS.
+        if trusted_validators.is_empty() && !self.mdns {
+            // To avoid ambiguity, we define the first few nodes to be the trusted validators to connect to.
+            for i in 0..2 {
+                if i != dev {
+                    trusted_validators.push(SocketAddr::from(([127, 0, 0, 1], allocation.bft_port(i)?)));
                 }
             }
-            // Set the node IP to `4130 + dev`.
-            self.node = SocketAddr::from_str(&format!("0.0.0.0:{}", 4130 + dev))?;
-            // If the `norest` flag is not set, and the `bft` flag was not overridden,
-            // then set the REST IP to `3030 + dev`.
-            //
-            // Note: the reason the `bft` flag is an option is to detect for remote devnet testing.
-            if !self.norest && self.bft.is_none() {
-                self.rest = SocketAddr::from_str(&format!("0.0.0.0:{}", 3030 + dev))?;
+        }
+        // Set the node IP to `4130 + dev`.
+        self.node = SocketAddr::from(([0, 0, 0, 0], allocation.node_port(dev)?));
+        // If the `norest` flag is not set, and the `bft` flag was not overridden,
+        // then set the REST IP to `3030 + dev`.
+        //
+        // Note: the reason the `bft` flag is an option is to detect for remote devnet testing.
+        if self.bft.is_none() {
+            if !self.norest {
+                self.rest = SocketAddr::from(([0, 0, 0, 0], allocation.rest_port(dev)?));
+            }
+            // Set the BFT IP from the port base, as the BFT otherwise defaults to the historical port.
+            if self.port_base.is_some() {
+                self.bft = Some(SocketAddr::from(([127, 0, 0, 1], allocation.bft_port(dev)?)));
             }
         }
-        Ok(())
+        Ok(Some(allocation))
+    }
+
+    /// Returns the fixtures of the ephemeral development network, from the given configurations.
+    fn parse_fixtures(&self) -> Result<EphemeralFixtures> {
+        match &self.dev_fixtures {
+            Some(path) => EphemeralFixtures::load(path),
+            None => Ok(EphemeralFixtures::default()),
+        }
+    }
+
+    /// Returns the private keys and balances of the fixture accounts of the ephemeral development network.
+    fn parse_fixture_accounts<N: Network>(&self, fixtures: &EphemeralFixtures) -> Result<Vec<(PrivateKey<N>, u64)>> {
+        fixtures.accounts(&mut ChaChaRng::seed_from_u64(EPHEMERAL_FIXTURES_RNG_SEED))
     }
 
     /// Returns an alternative genesis block if the node is in development mode.
     /// Otherwise, returns the actual genesis block.
     fn parse_genesis<N: Network>(&self) -> Result<Block<N>> {
-        if self.dev.is_some() {
+        if self.dev.is_some() || self.dev_ephemeral {
+            // Ensure a custom genesis block is not specified alongside development mode.
+            ensure!(
+                self.genesis.is_none(),
+                "The '--genesis' flag cannot be combined with '--dev' or '--dev-ephemeral'"
+            );
+
             // Determine the number of genesis committee members.
             let num_committee_members = match self.dev_num_validators {
                 Some(num_committee_members) => num_committee_members,
@@ -332,8 +796,20 @@ impl Start {
                 Committee::<N>::new(0u64, members)?
             };
 
+            // Retrieve the fixture accounts of the ephemeral development network, which are funded first.
+            let fixture_accounts = match self.dev_ephemeral {
+                true => self.parse_fixture_accounts::<N>(&self.parse_fixtures()?)?,
+                false => vec![],
+            };
+            let fixture_balances = fixture_accounts
+                .iter()
+                .try_fold(0u64, |sum, (_, balance)| sum.checked_add(*balance))
+                .ok_or_else(|| anyhow!("Sum of the fixture balances overflows"))?;
+
             // Calculate the public balance per validator.
             let remaining_balance = N::STARTING_SUPPLY.saturating_sub(committee.total_stake());
+            ensure!(fixture_balances <= remaining_balance, "Sum of the fixture balances exceeds the starting supply");
+            let remaining_balance = remaining_balance - fixture_balances;
             let public_balance_per_validator = remaining_balance.saturating_div(num_committee_members as u64);
 
             // Construct the public balances with fairly equal distribution.
@@ -349,6 +825,10 @@ impl Start {
                 let (_, balance) = public_balances.get_index_mut(0).unwrap();
                 *balance += leftover;
             }
+            // Add the balances of the fixture accounts.
+            for (private_key, balance) in fixture_accounts {
+                *public_balances.entry(Address::try_from(private_key)?).or_default() += balance;
+            }
 
             // Check if the sum of committee stakes and public balances equals the total starting supply.
             let public_balances_sum: u64 = public_balances.values().copied().sum();
@@ -363,8 +843,20 @@ impl Start {
             if self.dev_num_validators.is_some() {
                 eprintln!("The '--dev-num-validators' flag is ignored because '--dev' is not set");
             }
+            // If the `dev_fixtures` flag is set, inform the user that it is ignored.
+            if self.dev_fixtures.is_some() {
+                eprintln!("The '--dev-fixtures' flag is ignored because '--dev-ephemeral' is not set");
+            }
 
-            Block::from_bytes_le(N::genesis_bytes())
+            match &self.genesis {
+                // Load the custom genesis block.
+                Some(path) => {
+                    let block = Block::<N>::from_bytes_le(&std::fs::read(path)?)?;
+                    ensure!(block.height() == 0, "The block in {} is not a genesis block", path.display());
+                    Ok(block)
+                }
+                None => Block::from_bytes_le(N::genesis_bytes()),
+            }
         }
     }
 
@@ -385,20 +877,35 @@ impl Start {
         // Print the welcome.
         println!("{}", crate::helpers::welcome_message());
 
+        // If the ephemeral development network is enabled, run it instead of a single node.
+        if self.dev_ephemeral {
+            return self.parse_ephemeral::<N>().await;
+        }
+
         // Parse the trusted peers to connect to.
         let mut trusted_peers = self.parse_trusted_peers()?;
         // Parse the trusted validators to connect to.
         let mut trusted_validators = self.parse_trusted_validators()?;
-        // Parse the development configurations.
-        self.parse_development(&mut trusted_peers, &mut trusted_validators)?;
+        // Parse the development configurations, and record the port allocation of the development network.
+        if let Some(allocation) = self.parse_development(&mut trusted_peers, &mut trusted_validators)? {
+            allocation.record(&std::env::current_dir()?, N::ID)?;
+        }
 
         // Parse the CDN.
-        let cdn = self.parse_cdn();
+        let cdn = self.parse_cdn_config()?;
 
         // Parse the genesis block.
         let genesis = self.parse_genesis::<N>()?;
         // Parse the private key of the node.
         let account = self.parse_private_key::<N>()?;
+        // Parse the accounts of the node for each of its roles.
+        let accounts = self.parse_account_roles(account.clone())?;
+        // Parse the operators of the REST server.
+        let rest_operators = self.parse_rest_operators()?;
+        // Sign the admin tokens of the REST server with the admin account, if it is separate.
+        if accounts.is_admin_separated() {
+            snarkos_node_rest::set_jwt_signing_key(accounts.admin().private_key())?;
+        }
         // Parse the node type.
         let node_type = self.parse_node_type();
 
@@ -426,9 +933,15 @@ impl Start {
                 if let Some(rest_ip) = rest_ip {
                     println!("🌐 Starting the REST server at {}.\n", rest_ip.to_string().bold());
 
-                    if let Ok(jwt_token) = snarkos_node_rest::Claims::new(account.address()).to_jwt_string() {
+                    if let Ok(jwt_token) = snarkos_node_rest::Claims::new(accounts.admin().address()).to_jwt_string() {
                         println!("🔑 Your one-time JWT token is {}\n", jwt_token.dimmed());
                     }
+                    // Issue a separate token to each operator, so that their actions are journaled apart.
+                    for operator in &rest_operators {
+                        if let Ok(jwt_token) = snarkos_node_rest::Claims::for_operator(operator).to_jwt_string() {
+                            println!("🔑 The JWT token of '{}' is {}\n", operator.bold(), jwt_token.dimmed());
+                        }
+                    }
                 }
             }
         }
@@ -441,10 +954,29 @@ impl Start {
         // Check if the machine meets the minimum requirements for a validator.
         crate::helpers::check_validator_machine(node_type);
 
-        // Initialize the metrics.
-        if self.metrics {
-            metrics::initialize_metrics();
-        }
+        // Open the audit log of the peer connections.
+        let audit_log =
+            self.audit_log.as_deref().map(|path| AuditLog::open(path, DEFAULT_AUDIT_LOG_MAX_SIZE)).transpose()?;
+        // Open the message trace of the router.
+        let message_recorder = match &self.record_messages {
+            Some(directory) => {
+                println!("⚠️  Recording the messages of the router to '{}' (development only).\n", directory.display());
+                let (max_size, max_files) = (DEFAULT_MESSAGE_TRACE_MAX_SIZE, DEFAULT_MESSAGE_TRACE_MAX_FILES);
+                Some(MessageRecorder::open(directory, self.node, max_size, max_files, self.record_payloads)?)
+            }
+            None => None,
+        };
+        // Load the local transaction validation pipeline.
+        let transaction_validator = self.parse_transaction_validator::<N>()?;
+        // Load the peer group configuration.
+        let peer_group_config = self.peer_groups.as_deref().map(PeerGroupConfig::load).transpose()?;
+        // Load the alerting configuration.
+        let alert_config = self.alerts.as_deref().map(AlertConfig::load).transpose()?;
+        // Load the webhook configuration.
+        let webhook_config = self.webhooks.as_deref().map(WebhookConfig::load).transpose()?;
+        // Load the faucet configuration.
+        #[cfg(feature = "faucet")]
+        let faucet_config = self.faucet.as_deref().map(snarkos_node::rest::FaucetConfig::load).transpose()?;
 
         // Initialize the storage mode.
         let storage_mode = match &self.storage_path {
@@ -452,13 +984,202 @@ impl Start {
             None => StorageMode::from(self.dev),
         };
 
+        // Retrieve the ledger directory, for the alerts.
+        let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+        // Initialize the metrics, which are spooled in the ledger directory if they cannot be pushed.
+        self.initialize_metrics(Some(&ledger_dir))?;
+        // Ensure the ledger directory has the layout of this release, before the stores are opened.
+        if matches!(node_type, NodeType::Validator | NodeType::Client) {
+            DataLayout::new(ledger_dir.clone(), N::ID).check()?;
+        }
+
+        // Acquire the lock file of a validator, and start in safe mode if it did not shut down cleanly.
+        let lock_file = match node_type {
+            NodeType::Validator => Some(self.parse_safe_mode(&genesis, &storage_mode).await?),
+            NodeType::Prover | NodeType::Client => None,
+        };
+
         // Initialize the node.
         let bft_ip = if self.dev.is_some() { self.bft } else { None };
-        match node_type {
-            NodeType::Validator => Node::new_validator(self.node, bft_ip, rest_ip, self.rest_rps, account, &trusted_peers, &trusted_validators, genesis, cdn, storage_mode).await,
+        let node = match node_type {
+            NodeType::Validator => Node::new_validator(self.node, bft_ip, rest_ip, self.rest_rps, accounts, &trusted_peers, &trusted_validators, genesis, cdn, storage_mode, self.validation_concurrency, self.bft_activation_height).await,
             NodeType::Prover => Node::new_prover(self.node, account, &trusted_peers, genesis, storage_mode).await,
-            NodeType::Client => Node::new_client(self.node, rest_ip, self.rest_rps, account, &trusted_peers, genesis, cdn, storage_mode).await,
+            NodeType::Client => Node::new_client(self.node, rest_ip, self.rest_rps, account, &trusted_peers, genesis, cdn, storage_mode, self.bft_activation_height).await,
+        }?;
+        // Release the lock file when the validator shuts down cleanly.
+        if let Some(lock_file) = lock_file {
+            node.set_lock_file(lock_file)?;
+        }
+        // Record the peer connections in the audit log.
+        if let Some(audit_log) = audit_log {
+            node.set_audit_log(audit_log);
+        }
+        // Record the admin actions in the action journal.
+        if let Some(path) = &self.action_journal {
+            node.open_action_journal(path)?;
+        }
+        // Account for the validator rewards.
+        if let Some(path) = &self.rewards_tracker {
+            node.start_rewards_tracker(path)?;
+        }
+        // Include the logs, ledger directory, and redacted configuration in the debug bundles.
+        node.set_debug_bundle_config(DebugBundleConfig {
+            log_path: Some(self.logfile.clone()),
+            storage_path: Some(ledger_dir.clone()),
+            config: Some(format!("{:#?}", self.redacted())),
+        });
+        // Record the messages of the router.
+        if let Some(message_recorder) = message_recorder {
+            node.set_message_recorder(message_recorder);
         }
+        // Enforce the policies of the peer groups.
+        if let Some(peer_group_config) = peer_group_config {
+            node.add_peer_groups(peer_group_config)?;
+        }
+        // Limit the number of connections per node identity.
+        node.set_max_connections_per_identity(self.max_connections_per_identity)?;
+        // Set the thresholds with which unresponsive peer connections are reaped.
+        node.set_liveness(Liveness {
+            keepalive_in_secs: Some(self.tcp_keepalive_secs).filter(|secs| *secs > 0),
+            radio_silence_in_secs: self.radio_silence_secs,
+            pong_timeout_in_secs: self.pong_timeout_secs,
+        })?;
+        // Cap the bandwidth of the messages sent to each class of peers.
+        node.set_bandwidth_caps(BandwidthCaps {
+            client: self.bandwidth_cap_clients,
+            validator: self.bandwidth_cap_validators,
+            prover: self.bandwidth_cap_provers,
+        })?;
+        // Select the peers to which the large payloads are propagated.
+        node.set_fanout_policy(match self.full_propagation {
+            true => FanoutPolicy::broadcast(),
+            false => FanoutPolicy { large_payload_size: Some(self.fanout_payload_size), ..Default::default() },
+        })?;
+        // Advertise the public URL of the REST server to the peers.
+        if let Some(url) = &self.rest_public_url {
+            node.set_rest_endpoint(url.clone())?;
+        }
+        // Apply the local transaction validation pipeline.
+        if let Some(transaction_validator) = transaction_validator {
+            node.set_transaction_validator(transaction_validator)?;
+        }
+        // Trigger the batch proposals upon the arriving transactions.
+        if self.proposal_trigger_transactions.is_some() || self.proposal_trigger_fees.is_some() {
+            let (min_transactions, min_priority_fees) =
+                (self.proposal_trigger_transactions.unwrap_or(0), self.proposal_trigger_fees.unwrap_or(0));
+            node.set_proposal_trigger(min_transactions, min_priority_fees)?;
+        }
+        // Tune the batch size and delay at runtime.
+        if self.batch_tuning {
+            node.set_batch_tuning(self.parse_batch_tuning_bounds()?)?;
+        }
+        // Signal the readiness for the protocol upgrade.
+        if let (Some(version), Some(height)) = (self.upgrade_version, self.upgrade_height) {
+            node.signal_upgrade(ProtocolUpgrade::new(version, height))?;
+        }
+        // Only accept the inbound connections of the committee members and the relays.
+        if self.hidden {
+            node.enable_hidden_mode(self.parse_relays()?)?;
+        }
+        // Adjust the timestamp tolerance for the skew of the local clock.
+        if self.adjust_clock_skew {
+            node.enable_clock_adjustment()?;
+        }
+        // Discover the nodes on the local network.
+        if self.mdns {
+            node.enable_local_discovery()?;
+        }
+        // Audit the derived indexes of the ledger.
+        if self.index_audit {
+            node.start_index_audit(IndexAuditConfig {
+                interval_secs: self.index_audit_interval_secs,
+                sample_size: self.index_audit_sample_size,
+                repair: self.index_audit_repair,
+            })?;
+        }
+        // Start the alerts.
+        if let Some(alert_config) = alert_config {
+            node.start_alerts(alert_config, ledger_dir);
+        }
+        // Post the events of the node to the webhooks.
+        if let Some(webhook_config) = webhook_config {
+            node.start_webhooks(webhook_config)?;
+        }
+        // Start the faucet.
+        #[cfg(feature = "faucet")]
+        if let Some(faucet_config) = faucet_config {
+            node.start_faucet(faucet_config)?;
+        }
+        // Run the node as a read-only replica.
+        if self.readonly {
+            node.set_read_only()?;
+        }
+        // Enable fast sync.
+        if self.fast_sync {
+            node.enable_fast_sync()?;
+        }
+        // Enable the Dandelion relay.
+        if self.dandelion {
+            node.enable_dandelion();
+        }
+        Ok(node)
+    }
+
+    /// Returns an ephemeral development network, which runs the development committee in memory,
+    /// seeded with the fixture accounts and programs.
+    async fn parse_ephemeral<N: Network>(&self) -> Result<Node<N>> {
+        ensure!(self.dev.is_none(), "The '--dev-ephemeral' flag cannot be combined with '--dev'");
+
+        // Parse the fixtures.
+        let fixtures = self.parse_fixtures()?;
+        let fixture_accounts = self.parse_fixture_accounts::<N>(&fixtures)?;
+        let programs = fixtures.programs::<N>()?;
+        // Parse the genesis block, which funds the fixture accounts.
+        let genesis = self.parse_genesis::<N>()?;
+
+        // Sample the accounts of the development committee, in the same order as the genesis block.
+        let num_committee_members = self.dev_num_validators.unwrap_or(DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS);
+        let mut rng = ChaChaRng::seed_from_u64(DEVELOPMENT_MODE_RNG_SEED);
+        let committee = (0..num_committee_members)
+            .map(|_| Account::try_from(PrivateKey::<N>::new(&mut rng)?))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Initialize the metrics.
+        self.initialize_metrics(None)?;
+        // Load the local transaction validation pipeline.
+        let transaction_validator = self.parse_transaction_validator::<N>()?;
+
+        // Initialize the ephemeral development network.
+        println!("🧪 Starting an ephemeral development network of {num_committee_members} validators in memory.\n");
+        let node = Node::new_ephemeral(committee, genesis, self.rest_rps, programs).await?;
+        // Apply the local transaction validation pipeline.
+        if let Some(transaction_validator) = transaction_validator {
+            node.set_transaction_validator(transaction_validator)?;
+        }
+        // Trigger the batch proposals upon the arriving transactions.
+        if self.proposal_trigger_transactions.is_some() || self.proposal_trigger_fees.is_some() {
+            let (min_transactions, min_priority_fees) =
+                (self.proposal_trigger_transactions.unwrap_or(0), self.proposal_trigger_fees.unwrap_or(0));
+            node.set_proposal_trigger(min_transactions, min_priority_fees)?;
+        }
+        // Tune the batch size and delay at runtime.
+        if self.batch_tuning {
+            node.set_batch_tuning(self.parse_batch_tuning_bounds()?)?;
+        }
+
+        // Print the REST server and the fixture accounts.
+        if let Node::Ephemeral(devnet) = &node {
+            println!("🌐 Started the REST server at {}.\n", devnet.rest_ip().to_string().bold());
+        }
+        for (private_key, balance) in fixture_accounts {
+            println!(
+                "💰 Fixture account {} holds {balance} microcredits, with private key {}",
+                Address::try_from(private_key)?.to_string().bold(),
+                private_key.to_string().dimmed()
+            );
+        }
+        println!();
+        Ok(node)
     }
 
     /// Returns a runtime for the node.
@@ -490,6 +1211,23 @@ impl Start {
     }
 }
 
+/// Reads the private key of a node role directly from an argument or from a filesystem location, if one is given.
+fn parse_role_key<N: Network>(
+    flag: &str,
+    private_key: &Option<String>,
+    path: &Option<PathBuf>,
+) -> Result<Option<Account<N>>> {
+    match (private_key, path) {
+        (Some(private_key), None) => Ok(Some(Account::from_str(private_key.trim())?)),
+        (None, Some(path)) => {
+            check_permissions(path)?;
+            Ok(Some(Account::from_str(std::fs::read_to_string(path)?.trim())?))
+        }
+        (None, None) => Ok(None),
+        (Some(_), Some(_)) => bail!("Cannot use '--{flag}' and '--{flag}-file' simultaneously, please use only one"),
+    }
+}
+
 fn check_permissions(path: &PathBuf) -> Result<(), snarkvm::prelude::Error> {
     #[cfg(target_family = "unix")]
     {
@@ -578,7 +1316,7 @@ fn load_or_compute_genesis<N: Network>(
 mod tests {
     use super::*;
     use crate::commands::{Command, CLI};
-    use snarkvm::prelude::Testnet3;
+    use snarkvm::prelude::{TestRng, Testnet3};
 
     type CurrentNetwork = Testnet3;
 
@@ -618,6 +1356,85 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_transaction_validator() {
+        let config = Start::try_parse_from(["snarkos", "--validator"].iter()).unwrap();
+        assert!(config.parse_transaction_validator::<CurrentNetwork>().unwrap().is_none());
+
+        let config = Start::try_parse_from(
+            ["snarkos", "--validator", "--min-priority-fee", "5", "--deny-programs", "foo.aleo, bar.aleo"].iter(),
+        )
+        .unwrap();
+        let transaction_validator = config.parse_transaction_validator::<CurrentNetwork>().unwrap().unwrap();
+        assert_eq!(transaction_validator.minimum_priority_fee(), 5);
+        assert_eq!(transaction_validator.policy_names().collect::<Vec<_>>(), vec!["program_deny_list"]);
+
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--max-transaction-size", "1024"].iter()).unwrap();
+        let transaction_validator = config.parse_transaction_validator::<CurrentNetwork>().unwrap().unwrap();
+        assert_eq!(transaction_validator.minimum_priority_fee(), 0);
+        assert_eq!(transaction_validator.policy_names().collect::<Vec<_>>(), vec!["max_transaction_size"]);
+
+        let config = Start::try_parse_from(["snarkos", "--validator", "--deny-programs", "foo"].iter()).unwrap();
+        assert!(config.parse_transaction_validator::<CurrentNetwork>().is_err());
+    }
+
+    #[test]
+    fn test_parse_relays() {
+        let config = Start::try_parse_from(["snarkos", "--validator", "--hidden"].iter()).unwrap();
+        assert!(config.parse_relays::<CurrentNetwork>().unwrap().is_empty());
+
+        let rng = &mut TestRng::default();
+        let relay = Address::<CurrentNetwork>::try_from(PrivateKey::new(rng).unwrap()).unwrap();
+        let relays = format!("{relay}, {relay}");
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--hidden", "--relays", &relays].iter()).unwrap();
+        assert_eq!(config.parse_relays::<CurrentNetwork>().unwrap(), vec![relay, relay]);
+
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--hidden", "--relays", "aleo1xx"].iter()).unwrap();
+        assert!(config.parse_relays::<CurrentNetwork>().is_err());
+
+        // The relays require the hidden mode.
+        assert!(Start::try_parse_from(["snarkos", "--validator", "--relays", &relays].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_rest_operators() {
+        let config = Start::try_parse_from(["snarkos", "--validator"].iter()).unwrap();
+        assert!(config.parse_rest_operators().unwrap().is_empty());
+
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--rest-operators", "alice, bob-2"].iter()).unwrap();
+        assert_eq!(config.parse_rest_operators().unwrap(), vec!["alice".to_string(), "bob-2".to_string()]);
+
+        // The names must be distinct and non-empty, and the REST server must be enabled.
+        for operators in ["alice,alice", "alice,", "operator:alice"] {
+            let config =
+                Start::try_parse_from(["snarkos", "--validator", "--rest-operators", operators].iter()).unwrap();
+            assert!(config.parse_rest_operators().is_err());
+        }
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--norest", "--rest-operators", "alice"].iter()).unwrap();
+        assert!(config.parse_rest_operators().is_err());
+    }
+
+    #[test]
+    fn test_parse_fanout() {
+        let config = Start::try_parse_from(["snarkos", "--client"].iter()).unwrap();
+        assert_eq!(config.fanout_payload_size, FanoutPolicy::DEFAULT_LARGE_PAYLOAD_SIZE);
+        assert!(!config.full_propagation);
+
+        let config = Start::try_parse_from(["snarkos", "--client", "--fanout-payload-size", "4096"].iter()).unwrap();
+        assert_eq!(config.fanout_payload_size, 4096);
+        let config = Start::try_parse_from(["snarkos", "--client", "--full-propagation"].iter()).unwrap();
+        assert!(config.full_propagation);
+
+        // The full propagation ignores the size of the payloads.
+        let args = ["snarkos", "--client", "--full-propagation", "--fanout-payload-size", "4096"];
+        assert!(Start::try_parse_from(args.iter()).is_err());
+    }
+
     #[test]
     fn test_parse_cdn() {
         // Validator (Prod)
@@ -630,6 +1447,10 @@ mod tests {
         let config =
             Start::try_parse_from(["snarkos", "--validator", "--private-key", "aleo1xx", "--cdn", ""].iter()).unwrap();
         assert!(config.parse_cdn().is_none());
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--private-key", "aleo1xx", "--cdn", "url1, url2"].iter())
+                .unwrap();
+        assert_eq!(config.parse_cdn(), Some(vec!["url1".to_string(), "url2".to_string()]));
 
         // Validator (Dev)
         let config =
@@ -713,6 +1534,95 @@ mod tests {
         assert!(config.parse_cdn().is_none());
     }
 
+    #[test]
+    fn test_parse_cdn_config() {
+        let config = Start::try_parse_from(["snarkos", "--validator", "--cdn", "url"].iter()).unwrap();
+        assert_eq!(config.parse_cdn_config().unwrap(), Some(CdnConfig::new(vec!["url".to_string()])));
+
+        let config = Start::try_parse_from(
+            ["snarkos", "--client", "--cdn", "url", "--cdn-concurrency", "2", "--cdn-max-pending-bundles", "4"].iter(),
+        )
+        .unwrap();
+        let cdn = config.parse_cdn_config().unwrap().unwrap();
+        assert_eq!((cdn.concurrency, cdn.max_pending_bundles), (2, 4));
+
+        // The pipeline must allow at least one bundle.
+        for flag in ["--cdn-concurrency", "--cdn-max-pending-bundles"] {
+            let config = Start::try_parse_from(["snarkos", "--validator", flag, "0"].iter()).unwrap();
+            assert!(config.parse_cdn_config().is_err());
+        }
+        // The bounds are irrelevant when the CDN is disabled.
+        let config = Start::try_parse_from(["snarkos", "--validator", "--nocdn"].iter()).unwrap();
+        assert_eq!(config.parse_cdn_config().unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_custom_genesis() {
+        let prod_genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let path = std::env::temp_dir().join(format!("snarkos-test-genesis-{}.block", std::process::id()));
+        std::fs::write(&path, CurrentNetwork::genesis_bytes()).unwrap();
+        let path_string = path.to_str().unwrap();
+
+        let config = Start::try_parse_from(["snarkos", "--genesis", path_string].iter()).unwrap();
+        assert_eq!(config.parse_genesis::<CurrentNetwork>().unwrap(), prod_genesis);
+
+        let config = Start::try_parse_from(["snarkos", "--dev", "0", "--genesis", path_string].iter()).unwrap();
+        assert!(config.parse_genesis::<CurrentNetwork>().is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_development_with_mdns() {
+        // The local nodes are discovered over mDNS, rather than trusted by their development IDs.
+        let (mut trusted_peers, mut trusted_validators) = (vec![], vec![]);
+        let mut config = Start::try_parse_from(["snarkos", "--dev", "2", "--validator", "--mdns"].iter()).unwrap();
+        config.parse_development(&mut trusted_peers, &mut trusted_validators).unwrap();
+        assert!(trusted_peers.is_empty());
+        assert!(trusted_validators.is_empty());
+        assert_eq!(config.node, SocketAddr::from_str("0.0.0.0:4132").unwrap());
+
+        // The discovery is only supported in development mode.
+        let mut config = Start::try_parse_from(["snarkos", "--validator", "--mdns"].iter()).unwrap();
+        assert!(config.parse_development(&mut trusted_peers, &mut trusted_validators).is_err());
+    }
+
+    #[test]
+    fn test_parse_development_with_port_base() {
+        // The ports of the development network are allocated from the port base.
+        let (mut trusted_peers, mut trusted_validators) = (vec![], vec![]);
+        let args = ["snarkos", "--dev", "2", "--validator", "--port-base", "10000"];
+        let mut config = Start::try_parse_from(args.iter()).unwrap();
+        let allocation = config.parse_development(&mut trusted_peers, &mut trusted_validators).unwrap().unwrap();
+        assert_eq!(allocation, DevPortAllocation::new(Some(10000)).unwrap());
+        assert_eq!(config.node, SocketAddr::from_str("0.0.0.0:10002").unwrap());
+        assert_eq!(config.rest, SocketAddr::from_str("0.0.0.0:11002").unwrap());
+        assert_eq!(config.bft, Some(SocketAddr::from_str("127.0.0.1:12002").unwrap()));
+        assert_eq!(trusted_peers, vec![
+            SocketAddr::from_str("127.0.0.1:10000").unwrap(),
+            SocketAddr::from_str("127.0.0.1:10001").unwrap()
+        ]);
+        assert_eq!(trusted_validators, vec![
+            SocketAddr::from_str("127.0.0.1:12000").unwrap(),
+            SocketAddr::from_str("127.0.0.1:12001").unwrap()
+        ]);
+
+        // Without a port base, the BFT falls back to its historical port.
+        let (mut trusted_peers, mut trusted_validators) = (vec![], vec![]);
+        let mut config = Start::try_parse_from(["snarkos", "--dev", "2", "--validator"].iter()).unwrap();
+        let allocation = config.parse_development(&mut trusted_peers, &mut trusted_validators).unwrap().unwrap();
+        assert_eq!(allocation.port_base, None);
+        assert_eq!(config.bft, None);
+
+        // The development IDs must fit in the ranges of the port base.
+        let args = ["snarkos", "--dev", "1000", "--validator", "--port-base", "10000"];
+        let mut config = Start::try_parse_from(args.iter()).unwrap();
+        assert!(config.parse_development(&mut trusted_peers, &mut trusted_validators).is_err());
+
+        // The port base is only supported in development mode.
+        assert!(Start::try_parse_from(["snarkos", "--validator", "--port-base", "10000"].iter()).is_err());
+    }
+
     #[test]
     fn test_parse_development_and_genesis() {
         let prod_genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
@@ -786,6 +1696,59 @@ mod tests {
         assert!(!config.prover);
         assert!(config.client);
         assert_eq!(genesis, expected_genesis);
+
+        // The ephemeral development network funds the fixture accounts in the genesis block.
+        let config = Start::try_parse_from(["snarkos", "--dev-ephemeral"].iter()).unwrap();
+        let genesis = config.parse_genesis::<CurrentNetwork>().unwrap();
+        assert_ne!(genesis, expected_genesis);
+        let fixture_accounts = config.parse_fixture_accounts::<CurrentNetwork>(&EphemeralFixtures::default()).unwrap();
+        assert!(!fixture_accounts.is_empty());
+        let config = Start::try_parse_from(["snarkos", "--dev-ephemeral"].iter()).unwrap();
+        assert_eq!(config.parse_genesis::<CurrentNetwork>().unwrap(), genesis);
+    }
+
+    #[test]
+    fn test_parse_account_roles() {
+        let mut rng = rand::thread_rng();
+        let account = Account::<CurrentNetwork>::new(&mut rng).unwrap();
+        let p2p = PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap().to_string();
+        let admin = account.private_key().to_string();
+
+        // By default, the account of the node fills every role.
+        let config = Start::try_parse_from(["snarkos", "--validator"].iter()).unwrap();
+        let accounts = config.parse_account_roles(account.clone()).unwrap();
+        assert!(!accounts.is_p2p_separated());
+        assert!(!accounts.is_admin_separated());
+
+        // A validator may separate its peer-to-peer identity.
+        let config = Start::try_parse_from(["snarkos", "--validator", "--p2p-private-key", &p2p].iter()).unwrap();
+        let accounts = config.parse_account_roles(account.clone()).unwrap();
+        assert!(accounts.is_p2p_separated());
+        assert_eq!(accounts.signing().address(), account.address());
+
+        // A client has no peer-to-peer identity to separate.
+        let config = Start::try_parse_from(["snarkos", "--client", "--p2p-private-key", &p2p].iter()).unwrap();
+        assert!(config.parse_account_roles(account.clone()).is_err());
+
+        // The admin account must differ from the other accounts.
+        let config = Start::try_parse_from(["snarkos", "--validator", "--admin-private-key", &admin].iter()).unwrap();
+        assert!(config.parse_account_roles(account.clone()).is_err());
+        let config = Start::try_parse_from(["snarkos", "--validator", "--admin-private-key", &p2p].iter()).unwrap();
+        assert!(config.parse_account_roles(account).unwrap().is_admin_separated());
+    }
+
+    #[test]
+    fn test_redacted() {
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut rand::thread_rng()).unwrap().to_string();
+        let args = ["snarkos", "--validator", "--private-key", &private_key, "--admin-private-key", &private_key];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+
+        // The private keys are redacted, and the rest of the configuration is kept.
+        let redacted = format!("{:#?}", config.redacted());
+        assert!(!redacted.contains(&private_key));
+        assert!(redacted.contains("<redacted>"));
+        assert!(config.redacted().validator);
+        assert!(config.redacted().p2p_private_key.is_none());
     }
 
     #[test]
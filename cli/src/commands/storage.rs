@@ -0,0 +1,122 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::{DataLayout, CURRENT_LAYOUT_VERSION};
+
+use aleo_std::StorageMode;
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Commands to inspect and upgrade the layout of the snarkOS node storage.
+#[derive(Debug, Parser)]
+pub enum Storage {
+    /// Prints the layout version of the node storage
+    Status {
+        /// Specify the network of the node storage
+        #[clap(default_value = "3", long = "network")]
+        network: u16,
+        /// Enables development mode, specify the unique ID of the local node
+        #[clap(long)]
+        dev: Option<u16>,
+        /// Specify the path to a directory containing the ledger
+        #[clap(long = "path")]
+        path: Option<PathBuf>,
+    },
+    /// Migrates the node storage to the layout of this release; the node must be stopped
+    Migrate {
+        /// Specify the network of the node storage
+        #[clap(default_value = "3", long = "network")]
+        network: u16,
+        /// Enables development mode, specify the unique ID of the local node
+        #[clap(long)]
+        dev: Option<u16>,
+        /// Specify the path to a directory containing the ledger
+        #[clap(long = "path")]
+        path: Option<PathBuf>,
+    },
+}
+
+impl Storage {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Status { network, dev, path } => {
+                let layout = Self::layout(network, dev, path);
+                match layout.version()? {
+                    None => Ok(format!("No snarkOS node storage was found {}", Self::path_string(&layout))),
+                    Some(version) => Ok(format!(
+                        "The snarkOS node storage has layout version {version} (this release uses version \
+                         {CURRENT_LAYOUT_VERSION}) {}",
+                        Self::path_string(&layout)
+                    )),
+                }
+            }
+            Self::Migrate { network, dev, path } => {
+                let layout = Self::layout(network, dev, path);
+                let migrations = layout.migrate()?;
+                match migrations.is_empty() {
+                    true => Ok(format!("✅ The snarkOS node storage is up to date {}", Self::path_string(&layout))),
+                    false => {
+                        let steps = migrations.iter().map(|step| format!("  - {step}")).collect::<Vec<_>>().join("\n");
+                        let path_string = Self::path_string(&layout);
+                        Ok(format!(
+                            "✅ Migrated the snarkOS node storage to layout version {CURRENT_LAYOUT_VERSION} \
+                             {path_string}\n{steps}"
+                        ))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the layout of the node storage with the given options.
+    fn layout(network: u16, dev: Option<u16>, path: Option<PathBuf>) -> DataLayout {
+        let mode = match path {
+            Some(path) => StorageMode::Custom(path),
+            None => StorageMode::from(dev),
+        };
+        DataLayout::new(aleo_std::aleo_ledger_dir(network, mode), network)
+    }
+
+    /// Returns the formatted path of the node storage.
+    fn path_string(layout: &DataLayout) -> String {
+        format!("(in \"{}\")", layout.dir().display()).dimmed().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_migrate() {
+        let path = std::env::temp_dir().join(format!("snarkos-storage-{}", rand::random::<u64>()));
+        let options = || (3, None, Some(path.clone()));
+
+        // There is nothing to migrate in an empty directory.
+        let (network, dev, path_) = options();
+        assert!(Storage::Migrate { network, dev, path: path_ }.parse().is_err());
+
+        // Migrate the unversioned storage.
+        let dir = Storage::layout(3, None, Some(path.clone())).dir().to_path_buf();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("CURRENT"), "MANIFEST-000001").unwrap();
+        let (network, dev, path_) = options();
+        assert!(Storage::Migrate { network, dev, path: path_ }.parse().is_ok());
+        assert_eq!(Storage::layout(3, None, Some(path.clone())).version().unwrap(), Some(CURRENT_LAYOUT_VERSION));
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_rest::{MemoryPoolStatus, NodeStatus, PRIORITY_FEE_BUCKETS};
+use snarkvm::{
+    console::network::{Network, Testnet3},
+    ledger::block::Block,
+};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{BarChart, Block as BlockTui, Borders, Paragraph, Row, Sparkline, Table},
+    Frame,
+    Terminal,
+};
+use std::{
+    collections::VecDeque,
+    io,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The number of samples of the BFT round and the memory pool size that are kept for the sparklines.
+const MAX_SAMPLES: usize = 120;
+/// The number of seconds after which a request to the node times out.
+const REQUEST_TIMEOUT_IN_SECS: u64 = 5;
+
+/// Renders a live dashboard of a node in the terminal, from its REST API.
+#[derive(Clone, Debug, Parser)]
+pub struct Top {
+    /// Specify the network ID of the node
+    #[clap(default_value = "3", long = "network")]
+    pub network: u16,
+    /// Specify the REST endpoint of the node
+    #[clap(default_value = "http://127.0.0.1:3033", long = "endpoint")]
+    pub endpoint: String,
+    /// Specify the number of milliseconds in between refreshes
+    #[clap(default_value = "1000", long = "refresh")]
+    pub refresh: u64,
+    /// Specify the number of recent blocks to show
+    #[clap(default_value = "10", long = "blocks")]
+    pub blocks: u32,
+}
+
+impl Top {
+    /// Renders the dashboard, until the user quits.
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            3 => self.run::<Testnet3>()?,
+            _ => bail!("Unsupported network ID"),
+        }
+        Ok(String::new())
+    }
+
+    /// Initializes the terminal, renders the dashboard, and restores the terminal.
+    fn run<N: Network>(&self) -> Result<()> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+        let mut dashboard = Dashboard::<N>::new(self.endpoint.trim_end_matches('/'), self.blocks);
+        let result = dashboard.render(&mut terminal, Duration::from_millis(self.refresh.max(100)));
+
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+        result
+    }
+}
+
+/// A summary of a recent block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BlockSummary {
+    height: u32,
+    round: u64,
+    num_transactions: usize,
+    timestamp: i64,
+}
+
+impl<N: Network> From<&Block<N>> for BlockSummary {
+    fn from(block: &Block<N>) -> Self {
+        Self {
+            height: block.height(),
+            round: block.round(),
+            num_transactions: block.transactions().len(),
+            timestamp: block.timestamp(),
+        }
+    }
+}
+
+/// The state of the dashboard.
+struct Dashboard<N: Network> {
+    /// The HTTP agent.
+    agent: ureq::Agent,
+    /// The REST endpoint of the node.
+    endpoint: String,
+    /// The number of recent blocks to show.
+    num_blocks: u32,
+    /// The latest status of the node.
+    status: Option<NodeStatus>,
+    /// The error of the latest refresh, if it failed.
+    error: Option<String>,
+    /// The recent blocks, from the newest to the oldest.
+    blocks: VecDeque<BlockSummary>,
+    /// The recent samples of the BFT round.
+    rounds: VecDeque<u64>,
+    /// The recent samples of the number of unconfirmed transmissions.
+    transmissions: VecDeque<u64>,
+    _phantom: std::marker::PhantomData<N>,
+}
+
+impl<N: Network> Dashboard<N> {
+    /// Initializes a new dashboard for the given REST endpoint.
+    fn new(endpoint: &str, num_blocks: u32) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(REQUEST_TIMEOUT_IN_SECS)).build(),
+            endpoint: endpoint.to_string(),
+            num_blocks: num_blocks.max(1),
+            status: None,
+            error: None,
+            blocks: VecDeque::new(),
+            rounds: VecDeque::new(),
+            transmissions: VecDeque::new(),
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Refreshes and draws the dashboard at the given interval, until the user presses 'q' or ESC.
+    fn render<B: Backend>(&mut self, terminal: &mut Terminal<B>, interval: Duration) -> Result<()> {
+        loop {
+            let start = Instant::now();
+            match self.refresh() {
+                Ok(()) => self.error = None,
+                Err(error) => self.error = Some(error.to_string()),
+            }
+            terminal.draw(|f| self.draw(f))?;
+
+            // Wait for the next refresh, while handling the key presses.
+            while let Some(timeout) = interval.checked_sub(start.elapsed()) {
+                if event::poll(timeout)? {
+                    if let Event::Key(key) = event::read()? {
+                        if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fetches the status of the node and the blocks produced since the last refresh.
+    fn refresh(&mut self) -> Result<()> {
+        let url = format!("{}/testnet3/node/status", self.endpoint);
+        let status: NodeStatus = self.agent.get(&url).call()?.into_json()?;
+
+        // Fetch the new blocks, up to the number of blocks to show.
+        let end = status.height + 1;
+        let start = match self.blocks.front() {
+            Some(latest) => latest.height + 1,
+            None => 0,
+        }
+        .max(end.saturating_sub(self.num_blocks));
+        if start < end {
+            let url = format!("{}/testnet3/blocks?start={start}&end={end}", self.endpoint);
+            let blocks: Vec<Block<N>> = self.agent.get(&url).call()?.into_json()?;
+            for block in &blocks {
+                self.blocks.push_front(BlockSummary::from(block));
+            }
+            self.blocks.truncate(self.num_blocks as usize);
+        }
+
+        // Record the samples for the sparklines.
+        if let Some(bft) = &status.bft {
+            push_sample(&mut self.rounds, bft.round);
+        }
+        if let Some(memory_pool) = &status.memory_pool {
+            push_sample(&mut self.transmissions, memory_pool.transmissions as u64);
+        }
+        self.status = Some(status);
+        Ok(())
+    }
+
+    /// Draws the dashboard.
+    fn draw(&self, f: &mut Frame) {
+        let rows = Layout::default()
+            .margin(1)
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Length(12), Constraint::Min(0)].as_ref())
+            .split(f.size());
+        let panels = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(25), Constraint::Percentage(35), Constraint::Percentage(40)].as_ref())
+            .split(rows[1]);
+
+        self.draw_header(f, rows[0]);
+        self.draw_peers(f, panels[0]);
+        self.draw_bft(f, panels[1]);
+        self.draw_memory_pool(f, panels[2]);
+        self.draw_blocks(f, rows[2]);
+    }
+
+    /// Draws the node type, the sync status, and the error of the latest refresh.
+    fn draw_header(&self, f: &mut Frame, area: Rect) {
+        let mut spans = vec![Span::styled(&self.endpoint, Style::default().add_modifier(Modifier::BOLD))];
+        if let Some(status) = &self.status {
+            spans.push(Span::raw(format!("  {}  height {}  ", status.node_type, status.height)));
+            match status.is_synced {
+                true => spans.push(Span::styled("synced", Style::default().fg(Color::Green))),
+                false => spans.push(Span::styled(sync_label(status), Style::default().fg(Color::Yellow))),
+            }
+        }
+        if let Some(error) = &self.error {
+            spans.push(Span::styled(format!("  {error}"), Style::default().fg(Color::Red)));
+        }
+        let block = BlockTui::default().borders(Borders::ALL).title("snarkos top (press 'q' to quit)");
+        f.render_widget(Paragraph::new(Line::from(spans)).block(block), area);
+    }
+
+    /// Draws the number of connected peers by type.
+    fn draw_peers(&self, f: &mut Frame, area: Rect) {
+        let lines = match &self.status {
+            Some(status) => vec![
+                Line::from(format!("Validators  {}", status.peers.validators)),
+                Line::from(format!("Provers     {}", status.peers.provers)),
+                Line::from(format!("Clients     {}", status.peers.clients)),
+                Line::from(""),
+                Line::from(format!("Highest     {}", status.highest_peer_height.map_or("-".into(), |h| h.to_string()))),
+            ],
+            None => vec![],
+        };
+        f.render_widget(Paragraph::new(lines).block(BlockTui::default().borders(Borders::ALL).title("Peers")), area);
+    }
+
+    /// Draws the progress of the BFT rounds.
+    fn draw_bft(&self, f: &mut Frame, area: Rect) {
+        let block = BlockTui::default().borders(Borders::ALL).title("BFT");
+        let Some(bft) = self.status.as_ref().and_then(|status| status.bft.as_ref()) else {
+            f.render_widget(Paragraph::new("The node does not run the BFT").block(block), area);
+            return;
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(block.inner(area));
+        f.render_widget(block, area);
+
+        let state = if bft.paused { Span::styled("paused", Style::default().fg(Color::Yellow)) } else { Span::raw("") };
+        let lines = vec![
+            Line::from(vec![Span::raw(format!("Round {}  ", bft.round)), state]),
+            Line::from(format!("{} rounds in the last {} samples", round_progress(&self.rounds), self.rounds.len())),
+        ];
+        f.render_widget(Paragraph::new(lines), chunks[0]);
+        // Plot the number of rounds advanced in between samples.
+        let deltas = self.rounds.iter().zip(self.rounds.iter().skip(1)).map(|(a, b)| b.saturating_sub(*a));
+        let deltas = deltas.collect::<Vec<_>>();
+        f.render_widget(Sparkline::default().data(&deltas).style(Style::default().fg(Color::Cyan)), chunks[1]);
+    }
+
+    /// Draws the size of the memory pool and the histogram of the priority fees.
+    fn draw_memory_pool(&self, f: &mut Frame, area: Rect) {
+        let block = BlockTui::default().borders(Borders::ALL).title("Memory pool");
+        let Some(memory_pool) = self.status.as_ref().and_then(|status| status.memory_pool.as_ref()) else {
+            f.render_widget(Paragraph::new("The node does not run a memory pool").block(block), area);
+            return;
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(block.inner(area));
+        f.render_widget(block, area);
+
+        let transmissions = self.transmissions.iter().copied().collect::<Vec<_>>();
+        let sparkline = Sparkline::default()
+            .block(BlockTui::default().title(format!("{} transmissions", memory_pool.transmissions)))
+            .data(&transmissions)
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(sparkline, chunks[0]);
+
+        // Plot the histogram of the priority fees, in microcredits.
+        let labels = (0..PRIORITY_FEE_BUCKETS.len()).map(MemoryPoolStatus::bucket_label).collect::<Vec<_>>();
+        let data = labels
+            .iter()
+            .zip(&memory_pool.priority_fees)
+            .map(|(label, count)| (label.as_str(), *count as u64))
+            .collect::<Vec<_>>();
+        let chart = BarChart::default()
+            .block(BlockTui::default().title("Priority fees (microcredits)"))
+            .bar_width(9)
+            .bar_gap(1)
+            .data(data.as_slice())
+            .bar_style(Style::default().fg(Color::Green));
+        f.render_widget(chart, chunks[1]);
+    }
+
+    /// Draws the recent blocks.
+    fn draw_blocks(&self, f: &mut Frame, area: Rect) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs() as i64);
+        let header = Row::new(vec!["Height", "Round", "Transactions", "Age"]).style(Style::default().fg(Color::Yellow));
+        let rows = self.blocks.iter().map(|block| {
+            Row::new(vec![
+                block.height.to_string(),
+                block.round.to_string(),
+                block.num_transactions.to_string(),
+                format!("{}s", now.saturating_sub(block.timestamp).max(0)),
+            ])
+        });
+        let widths = [Constraint::Length(12), Constraint::Length(12), Constraint::Length(14), Constraint::Min(8)];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(BlockTui::default().borders(Borders::ALL).title("Recent blocks"));
+        f.render_widget(table, area);
+    }
+}
+
+/// Appends the given sample, dropping the oldest sample if there are too many.
+fn push_sample(samples: &mut VecDeque<u64>, sample: u64) {
+    if samples.len() == MAX_SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+/// Returns the number of rounds the BFT advanced over the given samples.
+fn round_progress(rounds: &VecDeque<u64>) -> u64 {
+    match (rounds.front(), rounds.back()) {
+        (Some(first), Some(last)) => last.saturating_sub(*first),
+        _ => 0,
+    }
+}
+
+/// Returns the label of a node that is not synced.
+fn sync_label(status: &NodeStatus) -> String {
+    match status.highest_peer_height {
+        Some(highest) => format!("syncing ({} blocks behind)", highest.saturating_sub(status.height)),
+        None => "syncing".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_rest::PeerCounts;
+
+    #[test]
+    fn test_samples() {
+        let mut samples = VecDeque::new();
+        assert_eq!(round_progress(&samples), 0);
+        for round in 0..(MAX_SAMPLES as u64 + 10) {
+            push_sample(&mut samples, round * 2);
+        }
+        assert_eq!(samples.len(), MAX_SAMPLES);
+        assert_eq!(samples.front(), Some(&20));
+        assert_eq!(round_progress(&samples), 2 * (MAX_SAMPLES as u64 - 1));
+    }
+
+    #[test]
+    fn test_sync_label() {
+        let mut status = NodeStatus {
+            node_type: "Client".to_string(),
+            height: 10,
+            highest_peer_height: Some(250),
+            is_synced: false,
+            peers: PeerCounts::default(),
+            memory_pool: None,
+            bft: None,
+            cdn_sync: None,
+        };
+        assert_eq!(sync_label(&status), "syncing (240 blocks behind)");
+        status.highest_peer_height = None;
+        assert_eq!(sync_label(&status), "syncing");
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::router::{MessageDirection, MessageTraceEntry};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use colored::Colorize;
+use indexmap::IndexMap;
+use std::{
+    fmt::Write,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+/// Filters and summarizes the message traces recorded with `snarkos start --record-messages`.
+#[derive(Clone, Debug, Parser)]
+pub struct TraceView {
+    /// Specify the directory of the message traces
+    pub directory: PathBuf,
+    /// Show only the messages recorded by the node listening on the given port
+    #[clap(long = "node")]
+    pub node: Option<u16>,
+    /// Show only the messages exchanged with the given peer
+    #[clap(long = "peer")]
+    pub peer: Option<SocketAddr>,
+    /// Show only the messages of the given type, e.g. 'BlockRequest'
+    #[clap(long = "message")]
+    pub message: Option<String>,
+    /// Show only the messages in the given direction [options: inbound, outbound]
+    #[clap(long = "direction")]
+    pub direction: Option<String>,
+    /// Show only the messages recorded at or after the given UNIX timestamp, in milliseconds
+    #[clap(long = "since")]
+    pub since: Option<i128>,
+    /// Show only the messages recorded before the given UNIX timestamp, in milliseconds
+    #[clap(long = "until")]
+    pub until: Option<i128>,
+    /// Specify the maximum number of messages to list, starting from the most recent
+    #[clap(long = "limit")]
+    pub limit: Option<usize>,
+    /// If the flag is set, summarizes the messages by type and by peer, instead of listing them
+    #[clap(long)]
+    pub summary: bool,
+}
+
+impl TraceView {
+    /// Reads the message traces, and lists or summarizes the messages that match the filters.
+    pub fn parse(self) -> Result<String> {
+        let direction = match self.direction.as_deref() {
+            None => None,
+            Some("inbound") => Some(MessageDirection::Inbound),
+            Some("outbound") => Some(MessageDirection::Outbound),
+            Some(direction) => bail!("Invalid direction '{direction}' (expected 'inbound' or 'outbound')"),
+        };
+
+        // Read the matching messages from every trace file, in chronological order.
+        let mut entries = Vec::new();
+        for path in Self::trace_files(&self.directory)? {
+            let contents =
+                fs::read_to_string(&path).with_context(|| format!("Failed to read '{}'", path.display()))?;
+            for (index, line) in contents.lines().enumerate() {
+                let entry = serde_json::from_str::<MessageTraceEntry>(line)
+                    .with_context(|| format!("Malformed entry on line {} of '{}'", index + 1, path.display()))?;
+                if self.matches(&entry, direction) {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries.sort_by_key(|entry| entry.timestamp);
+
+        match self.summary {
+            true => Ok(Self::summarize(&entries)),
+            false => {
+                let skip = self.limit.map_or(0, |limit| entries.len().saturating_sub(limit));
+                Ok(Self::list(&entries[skip..]))
+            }
+        }
+    }
+
+    /// Returns the paths of the trace files in the given directory, including the rotated ones.
+    fn trace_files(directory: &Path) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(directory).with_context(|| format!("Failed to read '{}'", directory.display()))? {
+            let path = entry?.path();
+            let is_trace = path.file_name().and_then(|name| name.to_str()).map_or(false, |name| {
+                name.starts_with("messages-") && name.contains(".jsonl")
+            });
+            if is_trace && path.is_file() {
+                paths.push(path);
+            }
+        }
+        if paths.is_empty() {
+            bail!("There are no message traces in '{}'", directory.display())
+        }
+        Ok(paths)
+    }
+
+    /// Returns `true` if the given entry matches the filters.
+    fn matches(&self, entry: &MessageTraceEntry, direction: Option<MessageDirection>) -> bool {
+        self.node.map_or(true, |port| entry.node.port() == port)
+            && self.peer.map_or(true, |peer| entry.peer == peer)
+            && self.message.as_ref().map_or(true, |message| entry.message.eq_ignore_ascii_case(message))
+            && direction.map_or(true, |direction| entry.direction == direction)
+            && self.since.map_or(true, |since| entry.timestamp >= since)
+            && self.until.map_or(true, |until| entry.timestamp < until)
+    }
+
+    /// Returns a line for each of the given entries.
+    fn list(entries: &[MessageTraceEntry]) -> String {
+        let mut output = String::new();
+        for entry in entries {
+            let arrow = match entry.direction {
+                MessageDirection::Inbound => "<-".green(),
+                MessageDirection::Outbound => "->".cyan(),
+            };
+            let _ = writeln!(
+                output,
+                "{} {} {arrow} {} {} ({} bytes)",
+                entry.timestamp,
+                entry.node,
+                entry.peer,
+                entry.name.bold(),
+                entry.size
+            );
+        }
+        let _ = write!(output, "{} messages", entries.len());
+        output
+    }
+
+    /// Returns the number of messages and bytes in each direction, by message type and by peer.
+    fn summarize(entries: &[MessageTraceEntry]) -> String {
+        let mut by_message = IndexMap::<&str, Totals>::new();
+        let mut by_peer = IndexMap::<SocketAddr, Totals>::new();
+        for entry in entries {
+            by_message.entry(entry.message.as_str()).or_default().add(entry);
+            by_peer.entry(entry.peer).or_default().add(entry);
+        }
+        by_message.sort_by(|_, a, _, b| b.bytes().cmp(&a.bytes()));
+        by_peer.sort_by(|_, a, _, b| b.bytes().cmp(&a.bytes()));
+
+        let mut output = String::new();
+        let _ = writeln!(output, "{}", format!("{} messages, by type", entries.len()).bold());
+        for (message, totals) in &by_message {
+            let _ = writeln!(output, "  {message:<32} {totals}");
+        }
+        let _ = writeln!(output, "\n{}", format!("{} peers", by_peer.len()).bold());
+        for (peer, totals) in &by_peer {
+            let _ = writeln!(output, "  {:<32} {totals}", peer.to_string());
+        }
+        output.trim_end().to_string()
+    }
+}
+
+/// The number of messages and bytes in each direction.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Totals {
+    inbound: (usize, usize),
+    outbound: (usize, usize),
+}
+
+impl Totals {
+    /// Adds the given entry to the totals.
+    fn add(&mut self, entry: &MessageTraceEntry) {
+        let totals = match entry.direction {
+            MessageDirection::Inbound => &mut self.inbound,
+            MessageDirection::Outbound => &mut self.outbound,
+        };
+        totals.0 += 1;
+        totals.1 += entry.size;
+    }
+
+    /// Returns the number of bytes in both directions.
+    fn bytes(&self) -> usize {
+        self.inbound.1 + self.outbound.1
+    }
+}
+
+impl std::fmt::Display for Totals {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "in: {:>8} messages {:>12} bytes | out: {:>8} messages {:>12} bytes",
+            self.inbound.0, self.inbound.1, self.outbound.0, self.outbound.1
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    /// Returns a trace entry with the given fields.
+    fn sample_entry(timestamp: i128, peer: &str, direction: MessageDirection, message: &str) -> MessageTraceEntry {
+        MessageTraceEntry {
+            timestamp,
+            node: "127.0.0.1:4130".parse().unwrap(),
+            peer: peer.parse().unwrap(),
+            direction,
+            message: message.to_string(),
+            name: message.to_string(),
+            size: 10,
+            payload: None,
+        }
+    }
+
+    #[test]
+    fn clap_snarkos_trace_view() {
+        let arg_vec = vec!["snarkos", "trace-view", "/tmp/traces", "--message", "Ping", "--direction", "inbound"];
+        let cli = CLI::parse_from(arg_vec);
+
+        if let Command::TraceView(trace_view) = cli.command {
+            assert_eq!(trace_view.directory, PathBuf::from("/tmp/traces"));
+            assert_eq!(trace_view.message, Some("Ping".to_string()));
+            assert_eq!(trace_view.direction, Some("inbound".to_string()));
+            assert!(!trace_view.summary);
+        } else {
+            panic!("Unexpected result of clap parsing!");
+        }
+    }
+
+    #[test]
+    fn test_trace_view() {
+        let directory = std::env::temp_dir().join(format!("snarkos-trace-view-{}", rand::random::<u64>()));
+        fs::create_dir_all(&directory).unwrap();
+        // Write a current and a rotated trace file, out of order.
+        let lines = |entries: &[MessageTraceEntry]| {
+            entries.iter().map(|entry| serde_json::to_string(entry).unwrap() + "\n").collect::<String>()
+        };
+        let current = [
+            sample_entry(3, "127.0.0.1:4131", MessageDirection::Inbound, "Ping"),
+            sample_entry(4, "127.0.0.1:4132", MessageDirection::Outbound, "Pong"),
+        ];
+        let rotated = [sample_entry(1, "127.0.0.1:4131", MessageDirection::Outbound, "Ping")];
+        fs::write(directory.join("messages-4130.jsonl"), lines(&current)).unwrap();
+        fs::write(directory.join("messages-4130.jsonl.1"), lines(&rotated)).unwrap();
+
+        let trace_view = TraceView::parse_from(["trace-view", directory.to_str().unwrap()]);
+        let direction = Some(MessageDirection::Inbound);
+        assert!(trace_view.matches(&current[0], direction));
+        assert!(!trace_view.matches(&current[1], direction));
+
+        // Ensure the messages are filtered, and listed in chronological order.
+        let output = TraceView { message: Some("ping".to_string()), ..trace_view.clone() }.parse().unwrap();
+        assert!(output.ends_with("2 messages"));
+        let output = TraceView { limit: Some(1), ..trace_view.clone() }.parse().unwrap();
+        assert!(output.contains("127.0.0.1:4132") && output.ends_with("1 messages"));
+
+        // Ensure the messages are summarized by type and by peer.
+        let output = TraceView { summary: true, ..trace_view }.parse().unwrap();
+        assert!(output.contains("3 messages, by type") && output.contains("2 peers"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
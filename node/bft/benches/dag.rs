@@ -0,0 +1,70 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::{helpers::DAG, MAX_GC_ROUNDS};
+use snarkvm::{
+    ledger::narwhal::{batch_certificate::test_helpers::sample_batch_certificate_for_round, BatchCertificate},
+    prelude::Testnet3,
+    utilities::TestRng,
+};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+type CurrentNetwork = Testnet3;
+
+/// The number of rounds of sample certificates.
+const NUM_ROUNDS: u64 = 2 * MAX_GC_ROUNDS;
+/// The number of sample certificates in each round.
+const CERTIFICATES_PER_ROUND: usize = 4;
+
+/// Returns the sample certificates, in ascending round order.
+fn sample_certificates() -> Vec<BatchCertificate<CurrentNetwork>> {
+    let rng = &mut TestRng::fixed(1);
+    (1..=NUM_ROUNDS)
+        .flat_map(|round| (0..CERTIFICATES_PER_ROUND).map(move |_| round))
+        .map(|round| sample_batch_certificate_for_round(round, rng))
+        .collect()
+}
+
+/// Returns a DAG holding the given certificates.
+fn sample_dag(certificates: &[BatchCertificate<CurrentNetwork>]) -> DAG<CurrentNetwork> {
+    let mut dag = DAG::new();
+    certificates.iter().cloned().for_each(|certificate| dag.insert(certificate));
+    dag
+}
+
+fn dag(c: &mut Criterion) {
+    let certificates = sample_certificates();
+
+    let mut group = c.benchmark_group("dag");
+    group.throughput(Throughput::Elements(certificates.len() as u64));
+    group.bench_function("insert", |b| {
+        b.iter_batched(|| certificates.clone(), |certificates| sample_dag(&certificates), BatchSize::SmallInput)
+    });
+    // Commit every certificate in ascending round order, which garbage collects the rounds left behind.
+    group.bench_function("commit", |b| {
+        b.iter_batched(
+            || sample_dag(&certificates),
+            |mut dag| {
+                certificates.iter().for_each(|certificate| dag.commit(certificate, MAX_GC_ROUNDS));
+                dag
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dag);
+criterion_main!(benches);
@@ -0,0 +1,94 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::{
+    helpers::{MaxTransactionSize, Ready, TransactionValidator},
+    ledger_service::{LedgerService, MockLedgerService},
+};
+use snarkvm::{
+    ledger::{committee::test_helpers::sample_committee, ledger_test_helpers::sample_fee_public_transaction},
+    prelude::{
+        block::Transaction,
+        narwhal::{Data, Transmission},
+        Network,
+        Testnet3,
+        ToBytes,
+    },
+    utilities::TestRng,
+};
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+type CurrentNetwork = Testnet3;
+type SampleTransaction = (<CurrentNetwork as Network>::TransactionID, Data<Transaction<CurrentNetwork>>);
+
+/// The number of sample transactions admitted in each iteration.
+const NUM_TRANSACTIONS: usize = 8;
+
+/// Returns the sample transactions, serialized as they arrive from the network.
+fn sample_transactions() -> Vec<SampleTransaction> {
+    let rng = &mut TestRng::fixed(1);
+    (0..NUM_TRANSACTIONS)
+        .map(|_| {
+            let transaction = sample_fee_public_transaction(rng);
+            (transaction.id(), Data::Buffer(Bytes::from(transaction.to_bytes_le().unwrap())))
+        })
+        .collect()
+}
+
+/// Admits the given transactions into the ready queue, as a worker does with the unconfirmed transactions.
+async fn admit(
+    validator: &TransactionValidator<CurrentNetwork>,
+    ledger: &Arc<dyn LedgerService<CurrentNetwork>>,
+    ready: Ready<CurrentNetwork>,
+    transactions: Vec<SampleTransaction>,
+) -> Ready<CurrentNetwork> {
+    for (transaction_id, transaction) in transactions {
+        validator.validate(ledger, transaction_id, transaction.clone()).await.unwrap();
+        ready.insert(&transaction_id, Transmission::Transaction(transaction));
+    }
+    ready
+}
+
+fn mempool(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let committee = sample_committee(&mut TestRng::fixed(1));
+    let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(MockLedgerService::new(committee));
+    let transactions = sample_transactions();
+
+    // The basic pipeline leaves the transaction serialized, while a policy hook requires it to be deserialized.
+    let validators = [
+        ("admit_basic", TransactionValidator::default()),
+        ("admit_with_policy", TransactionValidator::default().with_policy(MaxTransactionSize::new(128 * 1024))),
+    ];
+
+    let mut group = c.benchmark_group("mempool");
+    group.throughput(Throughput::Elements(NUM_TRANSACTIONS as u64));
+    for (name, validator) in validators {
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter_batched(
+                || (Ready::new(), transactions.clone()),
+                |(ready, transactions)| admit(&validator, &ledger, ready, transactions),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, mempool);
+criterion_main!(benches);
@@ -20,6 +20,16 @@ pub struct ChallengeResponse<N: Network> {
     pub nonce: u64,
 }
 
+impl<N: Network> ChallengeResponse<N> {
+    /// The domain of the signed nonces, which keeps them distinct from the other messages signed by the validator.
+    const DOMAIN: &'static [u8] = b"snarkos.gateway_handshake";
+
+    /// Returns the signed message for the given challenge nonce and response nonce, which is bound to the network.
+    pub fn message(challenge_nonce: u64, response_nonce: u64) -> Vec<u8> {
+        [&N::ID.to_le_bytes()[..], Self::DOMAIN, &challenge_nonce.to_le_bytes(), &response_nonce.to_le_bytes()].concat()
+    }
+}
+
 impl<N: Network> EventTrait for ChallengeResponse<N> {
     /// Returns the event name.
     #[inline]
@@ -0,0 +1,90 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointSignature<N: Network> {
+    pub checkpoint: CommitteeCheckpoint<N>,
+    pub signature: Signature<N>,
+}
+
+impl<N: Network> CheckpointSignature<N> {
+    /// Initializes a new checkpoint signature event.
+    pub fn new(checkpoint: CommitteeCheckpoint<N>, signature: Signature<N>) -> Self {
+        Self { checkpoint, signature }
+    }
+}
+
+impl<N: Network> EventTrait for CheckpointSignature<N> {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "CheckpointSignature".into()
+    }
+}
+
+impl<N: Network> ToBytes for CheckpointSignature<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.checkpoint.write_le(&mut writer)?;
+        self.signature.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for CheckpointSignature<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let checkpoint = CommitteeCheckpoint::read_le(&mut reader)?;
+        let signature = Signature::read_le(&mut reader)?;
+
+        Ok(Self { checkpoint, signature })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{
+        certificate_request::prop_tests::any_field,
+        challenge_response::prop_tests::any_signature,
+        CheckpointSignature,
+    };
+    use snarkos_node_sync_locators::CommitteeCheckpoint;
+    use snarkvm::console::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{any, BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_checkpoint_signature() -> BoxedStrategy<CheckpointSignature<CurrentNetwork>> {
+        (any::<u32>(), any_field(), any_field(), any_signature())
+            .prop_map(|(height, block_hash, previous_state_root, signature)| {
+                let previous_state_root = previous_state_root.into();
+                let checkpoint = CommitteeCheckpoint { height, block_hash: block_hash.into(), previous_state_root };
+                CheckpointSignature::new(checkpoint, signature)
+            })
+            .boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(#[strategy(any_checkpoint_signature())] original: CheckpointSignature<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        CheckpointSignature::write_le(&original, &mut buf).unwrap();
+
+        let deserialized: CheckpointSignature<CurrentNetwork> =
+            CheckpointSignature::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
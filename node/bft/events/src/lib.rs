@@ -41,6 +41,9 @@ pub use challenge_request::ChallengeRequest;
 mod challenge_response;
 pub use challenge_response::ChallengeResponse;
 
+mod checkpoint_signature;
+pub use checkpoint_signature::CheckpointSignature;
+
 mod disconnect;
 pub use disconnect::{Disconnect, DisconnectReason};
 
@@ -56,6 +59,12 @@ pub use transmission_request::TransmissionRequest;
 mod transmission_response;
 pub use transmission_response::TransmissionResponse;
 
+mod transmission_sketch;
+pub use transmission_sketch::{SketchCell, TransmissionSketch};
+
+mod upgrade_signal;
+pub use upgrade_signal::UpgradeSignal;
+
 mod validators_request;
 pub use validators_request::ValidatorsRequest;
 
@@ -65,7 +74,7 @@ pub use validators_response::ValidatorsResponse;
 mod worker_ping;
 pub use worker_ping::WorkerPing;
 
-use snarkos_node_sync_locators::BlockLocators;
+use snarkos_node_sync_locators::{BlockLocators, CommitteeCheckpoint};
 use snarkvm::{
     console::prelude::{error, FromBytes, Network, Read, ToBytes, Write},
     ledger::{
@@ -108,6 +117,9 @@ pub enum Event<N: Network> {
     ValidatorsRequest(ValidatorsRequest),
     ValidatorsResponse(ValidatorsResponse<N>),
     WorkerPing(WorkerPing<N>),
+    TransmissionSketch(TransmissionSketch),
+    CheckpointSignature(CheckpointSignature<N>),
+    UpgradeSignal(UpgradeSignal<N>),
 }
 
 impl<N: Network> From<DisconnectReason> for Event<N> {
@@ -118,7 +130,7 @@ impl<N: Network> From<DisconnectReason> for Event<N> {
 
 impl<N: Network> Event<N> {
     /// The version of the event protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 7;
+    pub const VERSION: u32 = 11;
 
     /// Returns the event name.
     #[inline]
@@ -140,6 +152,9 @@ impl<N: Network> Event<N> {
             Self::ValidatorsRequest(event) => event.name(),
             Self::ValidatorsResponse(event) => event.name(),
             Self::WorkerPing(event) => event.name(),
+            Self::TransmissionSketch(event) => event.name(),
+            Self::CheckpointSignature(event) => event.name(),
+            Self::UpgradeSignal(event) => event.name(),
         }
     }
 
@@ -163,6 +178,9 @@ impl<N: Network> Event<N> {
             Self::ValidatorsRequest(..) => 13,
             Self::ValidatorsResponse(..) => 14,
             Self::WorkerPing(..) => 15,
+            Self::TransmissionSketch(..) => 16,
+            Self::CheckpointSignature(..) => 17,
+            Self::UpgradeSignal(..) => 18,
         }
     }
 }
@@ -188,6 +206,9 @@ impl<N: Network> ToBytes for Event<N> {
             Self::ValidatorsRequest(event) => event.write_le(writer),
             Self::ValidatorsResponse(event) => event.write_le(writer),
             Self::WorkerPing(event) => event.write_le(writer),
+            Self::TransmissionSketch(event) => event.write_le(writer),
+            Self::CheckpointSignature(event) => event.write_le(writer),
+            Self::UpgradeSignal(event) => event.write_le(writer),
         }
     }
 }
@@ -215,7 +236,10 @@ impl<N: Network> FromBytes for Event<N> {
             13 => Self::ValidatorsRequest(ValidatorsRequest::read_le(&mut reader)?),
             14 => Self::ValidatorsResponse(ValidatorsResponse::read_le(&mut reader)?),
             15 => Self::WorkerPing(WorkerPing::read_le(&mut reader)?),
-            16.. => return Err(error("Unknown event ID {id}")),
+            16 => Self::TransmissionSketch(TransmissionSketch::read_le(&mut reader)?),
+            17 => Self::CheckpointSignature(CheckpointSignature::read_le(&mut reader)?),
+            18 => Self::UpgradeSignal(UpgradeSignal::read_le(&mut reader)?),
+            19.. => return Err(error("Unknown event ID {id}")),
         };
 
         // Ensure that there are no "dangling" bytes.
@@ -256,8 +280,11 @@ pub mod prop_tests {
         certificate_response::prop_tests::any_certificate_response,
         challenge_request::prop_tests::any_challenge_request,
         challenge_response::prop_tests::any_challenge_response,
+        checkpoint_signature::prop_tests::any_checkpoint_signature,
         transmission_request::prop_tests::any_transmission_request,
         transmission_response::prop_tests::any_transmission_response,
+        transmission_sketch::prop_tests::any_transmission_sketch,
+        upgrade_signal::prop_tests::any_upgrade_signal,
         worker_ping::prop_tests::any_worker_ping,
         Disconnect,
         DisconnectReason,
@@ -322,7 +349,10 @@ pub mod prop_tests {
                 .prop_map(|(reasons, selector)| Event::Disconnect(Disconnect::from(selector.select(reasons)))),
             any_transmission_request().prop_map(Event::TransmissionRequest),
             any_transmission_response().prop_map(Event::TransmissionResponse),
-            any_worker_ping().prop_map(Event::WorkerPing)
+            any_worker_ping().prop_map(Event::WorkerPing),
+            any_transmission_sketch().prop_map(Event::TransmissionSketch),
+            any_checkpoint_signature().prop_map(Event::CheckpointSignature),
+            any_upgrade_signal().prop_map(Event::UpgradeSignal)
         ]
         .boxed()
     }
@@ -0,0 +1,125 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+/// A cell of an invertible Bloom lookup table.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SketchCell {
+    /// The number of keys inserted into the cell, minus the number of keys removed.
+    pub count: i32,
+    /// The XOR of the keys in the cell.
+    pub key_sum: u64,
+    /// The XOR of the checksums of the keys in the cell.
+    pub hash_sum: u64,
+}
+
+impl ToBytes for SketchCell {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.count.write_le(&mut writer)?;
+        self.key_sum.write_le(&mut writer)?;
+        self.hash_sum.write_le(&mut writer)
+    }
+}
+
+impl FromBytes for SketchCell {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let count = i32::read_le(&mut reader)?;
+        let key_sum = u64::read_le(&mut reader)?;
+        let hash_sum = u64::read_le(&mut reader)?;
+        Ok(Self { count, key_sum, hash_sum })
+    }
+}
+
+/// A compact sketch of the transmission IDs in the ready queue of a worker,
+/// used to reconcile the ready queues of two validators without exchanging them in full.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransmissionSketch {
+    /// The ID of the worker that produced the sketch.
+    pub worker_id: u8,
+    /// The cells of the sketch.
+    pub cells: Vec<SketchCell>,
+}
+
+impl TransmissionSketch {
+    /// Initializes a new transmission sketch event.
+    pub fn new(worker_id: u8, cells: Vec<SketchCell>) -> Self {
+        Self { worker_id, cells }
+    }
+}
+
+impl EventTrait for TransmissionSketch {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "TransmissionSketch".into()
+    }
+}
+
+impl ToBytes for TransmissionSketch {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.worker_id.write_le(&mut writer)?;
+        u16::try_from(self.cells.len()).map_err(error)?.write_le(&mut writer)?;
+        for cell in &self.cells {
+            cell.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromBytes for TransmissionSketch {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let worker_id = u8::read_le(&mut reader)?;
+        let num_cells = u16::read_le(&mut reader)?;
+        let mut cells = Vec::with_capacity(num_cells as usize);
+        for _ in 0..num_cells {
+            cells.push(SketchCell::read_le(&mut reader)?);
+        }
+        Ok(Self { worker_id, cells })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{SketchCell, TransmissionSketch};
+    use snarkvm::console::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::{
+        collection::vec,
+        prelude::{any, BoxedStrategy, Strategy},
+    };
+    use test_strategy::proptest;
+
+    pub fn any_sketch_cell() -> BoxedStrategy<SketchCell> {
+        (any::<i32>(), any::<u64>(), any::<u64>())
+            .prop_map(|(count, key_sum, hash_sum)| SketchCell { count, key_sum, hash_sum })
+            .boxed()
+    }
+
+    pub fn any_transmission_sketch() -> BoxedStrategy<TransmissionSketch> {
+        (any::<u8>(), vec(any_sketch_cell(), 0..64))
+            .prop_map(|(worker_id, cells)| TransmissionSketch::new(worker_id, cells))
+            .boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(#[strategy(any_transmission_sketch())] original: TransmissionSketch) {
+        let mut buf = BytesMut::default().writer();
+        TransmissionSketch::write_le(&original, &mut buf).unwrap();
+
+        let deserialized = TransmissionSketch::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
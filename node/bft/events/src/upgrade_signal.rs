@@ -0,0 +1,85 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UpgradeSignal<N: Network> {
+    pub version: u32,
+    pub activation_height: u32,
+    pub signature: Signature<N>,
+}
+
+impl<N: Network> UpgradeSignal<N> {
+    /// Initializes a new upgrade signal event.
+    pub fn new(version: u32, activation_height: u32, signature: Signature<N>) -> Self {
+        Self { version, activation_height, signature }
+    }
+}
+
+impl<N: Network> EventTrait for UpgradeSignal<N> {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "UpgradeSignal".into()
+    }
+}
+
+impl<N: Network> ToBytes for UpgradeSignal<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.version.write_le(&mut writer)?;
+        self.activation_height.write_le(&mut writer)?;
+        self.signature.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for UpgradeSignal<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let version = u32::read_le(&mut reader)?;
+        let activation_height = u32::read_le(&mut reader)?;
+        let signature = Signature::read_le(&mut reader)?;
+
+        Ok(Self { version, activation_height, signature })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{challenge_response::prop_tests::any_signature, UpgradeSignal};
+    use snarkvm::console::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{any, BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_upgrade_signal() -> BoxedStrategy<UpgradeSignal<CurrentNetwork>> {
+        (any::<u32>(), any::<u32>(), any_signature())
+            .prop_map(|(version, activation_height, signature)| {
+                UpgradeSignal::new(version, activation_height, signature)
+            })
+            .boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(#[strategy(any_upgrade_signal())] original: UpgradeSignal<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        UpgradeSignal::write_le(&original, &mut buf).unwrap();
+
+        let deserialized: UpgradeSignal<CurrentNetwork> = UpgradeSignal::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
@@ -52,7 +52,15 @@ use axum_extra::response::ErasedJson;
 use clap::{Parser, ValueEnum};
 use indexmap::IndexMap;
 use rand::{Rng, SeedableRng};
-use std::{collections::HashMap, net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    ops::Range,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{net::TcpListener, sync::oneshot};
 use tracing_subscriber::{
     layer::{Layer, SubscriberExt},
@@ -257,6 +265,9 @@ fn handle_signals(primary: &Primary<CurrentNetwork>) {
 
 /**************************************************************************************************/
 
+/// The maximum number of recently fired transaction IDs, which the conflicting transactions reuse.
+const MAX_RECENT_TRANSACTION_IDS: usize = 64;
+
 /// Fires *fake* unconfirmed solutions at the node.
 fn fire_unconfirmed_solutions(sender: &PrimarySender<CurrentNetwork>, node_id: u16, interval_ms: u64) {
     let tx_unconfirmed_solution = sender.tx_unconfirmed_solution.clone();
@@ -299,23 +310,145 @@ fn fire_unconfirmed_solutions(sender: &PrimarySender<CurrentNetwork>, node_id: u
     });
 }
 
-/// Fires *fake* unconfirmed transactions at the node.
-fn fire_unconfirmed_transactions(sender: &PrimarySender<CurrentNetwork>, node_id: u16, interval_ms: u64) {
+/// The kind of a *fake* transaction, which determines its size.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum TransactionKind {
+    /// A transaction of 1 KiB.
+    Uniform,
+    /// A transfer, of a few KiB.
+    Transfer,
+    /// A deployment, of tens of KiB.
+    Deploy,
+    /// An execution with many transitions, of tens of KiB.
+    LargeExecution,
+}
+
+impl TransactionKind {
+    /// Returns the range of the sizes of the transaction, in bytes.
+    fn size_range(&self) -> Range<usize> {
+        match self {
+            Self::Uniform => 1024..1025,
+            Self::Transfer => 2_048..4_096,
+            Self::Deploy => 32_768..131_072,
+            Self::LargeExecution => 16_384..65_536,
+        }
+    }
+}
+
+/// The workload profile of the transaction cannons, which determines the mix of the fired transactions.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum Workload {
+    /// Fires transactions of a uniform size.
+    #[default]
+    Uniform,
+    /// Fires mostly transfers, with a few deployments and large executions.
+    TransferHeavy,
+    /// Fires mostly deployments, along with transfers.
+    DeployHeavy,
+    /// Fires mostly large executions, along with transfers.
+    LargeExecution,
+    /// Fires transfers, half of which conflict with (i.e. reuse the ID of) a recently fired transaction.
+    Conflicting,
+}
+
+impl Workload {
+    /// Samples the kind of the next transaction.
+    fn sample_kind(&self, rng: &mut impl Rng) -> TransactionKind {
+        use TransactionKind::*;
+
+        let roll = rng.gen_range(0..100);
+        match self {
+            Self::Uniform => Uniform,
+            Self::TransferHeavy => match roll {
+                0..=89 => Transfer,
+                90..=94 => Deploy,
+                _ => LargeExecution,
+            },
+            Self::DeployHeavy => match roll {
+                0..=59 => Deploy,
+                60..=89 => Transfer,
+                _ => LargeExecution,
+            },
+            Self::LargeExecution => match roll {
+                0..=69 => LargeExecution,
+                _ => Transfer,
+            },
+            Self::Conflicting => Transfer,
+        }
+    }
+
+    /// Returns the percentage of the transactions that conflict with a recently fired transaction.
+    const fn conflict_percentage(&self) -> u32 {
+        match self {
+            Self::Conflicting => 50,
+            Self::Uniform | Self::TransferHeavy | Self::DeployHeavy | Self::LargeExecution => 0,
+        }
+    }
+}
+
+/// The number of transactions that are fired back-to-back in a burst.
+const BURST_SIZE: u64 = 20;
+
+/// The arrival process of the transaction cannons.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum Arrival {
+    /// Fires a transaction at every interval.
+    #[default]
+    Steady,
+    /// Fires the transactions with exponentially-distributed gaps (i.e. a Poisson process), averaging the interval.
+    Poisson,
+    /// Fires bursts of transactions back-to-back, each followed by an idle period, averaging the interval.
+    Bursty,
+}
+
+impl Arrival {
+    /// Samples the delay before the next transaction, given the number of transactions fired so far.
+    fn sample_delay(&self, rng: &mut impl Rng, counter: u64, interval_ms: u64) -> Duration {
+        match self {
+            Self::Steady => Duration::from_millis(interval_ms),
+            Self::Poisson => {
+                // Sample an exponentially-distributed gap, with inverse transform sampling.
+                let uniform = rng.gen_range(f64::EPSILON..1.0);
+                Duration::from_secs_f64(-uniform.ln() * interval_ms as f64 / 1000.0)
+            }
+            Self::Bursty => match (counter + 1) % BURST_SIZE {
+                0 => Duration::from_millis(interval_ms * BURST_SIZE),
+                _ => Duration::ZERO,
+            },
+        }
+    }
+}
+
+/// Fires *fake* unconfirmed transactions at the node, with the given workload profile and arrival process.
+fn fire_unconfirmed_transactions(
+    sender: &PrimarySender<CurrentNetwork>,
+    node_id: u16,
+    interval_ms: u64,
+    workload: Workload,
+    arrival: Arrival,
+) {
     let tx_unconfirmed_transaction = sender.tx_unconfirmed_transaction.clone();
     tokio::task::spawn(async move {
         // This RNG samples the *same* fake transactions for all nodes.
         let mut shared_rng = rand_chacha::ChaChaRng::seed_from_u64(123456789);
         // This RNG samples *different* fake transactions for each node.
         let mut unique_rng = rand_chacha::ChaChaRng::seed_from_u64(node_id as u64);
+        // This RNG samples the arrivals and the conflicts for each node.
+        let mut arrival_rng = rand_chacha::ChaChaRng::seed_from_u64(u64::MAX - node_id as u64);
+        // The IDs of the recently fired transactions, which the conflicting transactions reuse.
+        let mut recent_ids = VecDeque::with_capacity(MAX_RECENT_TRANSACTION_IDS);
 
         // A closure to generate an ID and transaction.
         fn sample(
             mut rng: impl Rng,
+            workload: Workload,
         ) -> (<CurrentNetwork as Network>::TransactionID, Data<Transaction<CurrentNetwork>>) {
             // Sample a random fake transaction ID.
             let id = Field::<CurrentNetwork>::rand(&mut rng).into();
+            // Sample the size of the transaction, from the workload profile.
+            let size = rng.gen_range(workload.sample_kind(&mut rng).size_range());
             // Sample random fake transaction bytes.
-            let transaction = Data::Buffer(Bytes::from((0..1024).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+            let transaction = Data::Buffer(Bytes::from((0..size).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
             // Return the ID and transaction.
             (id, transaction)
         }
@@ -325,7 +458,17 @@ fn fire_unconfirmed_transactions(sender: &PrimarySender<CurrentNetwork>, node_id
 
         loop {
             // Sample a random fake transaction ID and transaction.
-            let (id, transaction) = if counter % 2 == 0 { sample(&mut shared_rng) } else { sample(&mut unique_rng) };
+            let (mut id, transaction) =
+                if counter % 2 == 0 { sample(&mut shared_rng, workload) } else { sample(&mut unique_rng, workload) };
+            // Reuse the ID of a recently fired transaction, if the transaction conflicts.
+            if !recent_ids.is_empty() && arrival_rng.gen_range(0..100) < workload.conflict_percentage() {
+                id = recent_ids[arrival_rng.gen_range(0..recent_ids.len())];
+            } else {
+                if recent_ids.len() == MAX_RECENT_TRANSACTION_IDS {
+                    recent_ids.pop_front();
+                }
+                recent_ids.push_back(id);
+            }
             // Initialize a callback sender and receiver.
             let (callback, callback_receiver) = oneshot::channel();
             // Send the fake transaction.
@@ -333,10 +476,10 @@ fn fire_unconfirmed_transactions(sender: &PrimarySender<CurrentNetwork>, node_id
                 error!("Failed to send unconfirmed transaction: {e}");
             }
             let _ = callback_receiver.await;
+            // Sleep until the next arrival.
+            tokio::time::sleep(arrival.sample_delay(&mut arrival_rng, counter, interval_ms)).await;
             // Increment the counter.
             counter += 1;
-            // Sleep briefly.
-            tokio::time::sleep(std::time::Duration::from_millis(interval_ms)).await;
         }
     });
 }
@@ -441,6 +584,12 @@ struct Args {
     /// Enables the solution and transaction cannons, and optionally the interval in ms to run them on.
     #[arg(long, value_name = "INTERVAL_MS")]
     fire_transmissions: Option<Option<u64>>,
+    /// The workload profile of the transaction cannons.
+    #[arg(long, value_enum, default_value_t)]
+    workload: Workload,
+    /// The arrival process of the transaction cannons.
+    #[arg(long, value_enum, default_value_t)]
+    arrival: Arrival,
     /// Enables the metrics exporter.
     #[clap(long, default_value = "false")]
     metrics: bool,
@@ -505,7 +654,8 @@ async fn main() -> Result<()> {
     match (args.fire_transmissions, args.fire_transactions) {
         // Note: We allow the user to overload the transactions rate, even when the 'fire-transmissions' flag is enabled.
         (Some(rate), _) | (_, Some(rate)) => {
-            fire_unconfirmed_transactions(&sender, args.id, rate.unwrap_or(DEFAULT_INTERVAL_MS));
+            let interval_ms = rate.unwrap_or(DEFAULT_INTERVAL_MS);
+            fire_unconfirmed_transactions(&sender, args.id, interval_ms, args.workload, args.arrival);
         }
         _ => (),
     };
@@ -528,6 +678,36 @@ async fn main() -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn workload_mix() {
+        let rng = &mut rand_chacha::ChaChaRng::seed_from_u64(0);
+        // Ensure the workload profiles produce their dominant kind of transaction.
+        let count = |workload: Workload, kind: TransactionKind, rng: &mut rand_chacha::ChaChaRng| {
+            (0..1000).filter(|_| workload.sample_kind(rng) == kind).count()
+        };
+        assert_eq!(count(Workload::Uniform, TransactionKind::Uniform, rng), 1000);
+        assert!(count(Workload::TransferHeavy, TransactionKind::Transfer, rng) > 800);
+        assert!(count(Workload::DeployHeavy, TransactionKind::Deploy, rng) > 500);
+        assert!(count(Workload::LargeExecution, TransactionKind::LargeExecution, rng) > 600);
+        assert_eq!(count(Workload::Conflicting, TransactionKind::Transfer, rng), 1000);
+    }
+
+    #[test]
+    fn arrival_delays() {
+        let rng = &mut rand_chacha::ChaChaRng::seed_from_u64(0);
+        // Ensure the arrival processes average the interval.
+        let total = |arrival: Arrival, rng: &mut rand_chacha::ChaChaRng| {
+            (0..2000).map(|counter| arrival.sample_delay(rng, counter, 100)).sum::<Duration>()
+        };
+        assert_eq!(total(Arrival::Steady, rng), Duration::from_secs(200));
+        assert_eq!(total(Arrival::Bursty, rng), Duration::from_secs(200));
+        let poisson = total(Arrival::Poisson, rng).as_secs_f64();
+        assert!((180.0..220.0).contains(&poisson), "{poisson}");
+        // Ensure the bursts are fired back-to-back.
+        assert_eq!(Arrival::Bursty.sample_delay(rng, 0, 100), Duration::ZERO);
+        assert_eq!(Arrival::Bursty.sample_delay(rng, BURST_SIZE - 1, 100), Duration::from_millis(100 * BURST_SIZE));
+    }
+
     #[test]
     fn parse_peers_empty() -> Result<(), Error> {
         let peers = parse_peers("".to_owned())?;
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{fmt_id, spawn_blocking, LedgerService};
+use crate::{fmt_id, spawn_blocking, CommitteeSource, LedgerService};
+use snarkos_node_bus::{EventBus, NodeEvent};
 use snarkvm::{
     ledger::{
+        authority::Authority,
         block::{Block, Transaction},
         coinbase::{CoinbaseVerifyingKey, ProverSolution, PuzzleCommitment},
         committee::Committee,
@@ -27,6 +29,7 @@ use snarkvm::{
 
 use indexmap::IndexMap;
 use lru::LruCache;
+use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use std::{
     fmt,
@@ -45,6 +48,9 @@ pub struct CoreLedgerService<N: Network, C: ConsensusStorage<N>> {
     ledger: Ledger<N, C>,
     coinbase_verifying_key: Arc<CoinbaseVerifyingKey<N>>,
     committee_cache: Arc<Mutex<LruCache<u64, Committee<N>>>>,
+    advance_lock: Arc<Mutex<()>>,
+    event_bus: OnceCell<EventBus<N>>,
+    bft_activation_height: OnceCell<u32>,
     shutdown: Arc<AtomicBool>,
 }
 
@@ -53,7 +59,59 @@ impl<N: Network, C: ConsensusStorage<N>> CoreLedgerService<N, C> {
     pub fn new(ledger: Ledger<N, C>, shutdown: Arc<AtomicBool>) -> Self {
         let coinbase_verifying_key = Arc::new(ledger.coinbase_puzzle().coinbase_verifying_key().clone());
         let committee_cache = Arc::new(Mutex::new(LruCache::new(COMMITTEE_CACHE_SIZE.try_into().unwrap())));
-        Self { ledger, coinbase_verifying_key, committee_cache, shutdown }
+        Self {
+            ledger,
+            coinbase_verifying_key,
+            committee_cache,
+            advance_lock: Default::default(),
+            event_bus: Default::default(),
+            bft_activation_height: Default::default(),
+            shutdown,
+        }
+    }
+
+    /// Sets the event bus, to which the ledger publishes the blocks it advances to.
+    pub fn set_event_bus(&self, event_bus: EventBus<N>) {
+        self.event_bus.set(event_bus).expect("Event bus already set");
+    }
+
+    /// Sets the height of the last beacon-produced block, after which the blocks must be produced by the BFT.
+    pub fn set_bft_activation_height(&self, height: u32) {
+        self.bft_activation_height.set(height).expect("BFT activation height already set");
+    }
+
+    /// Returns the height of the last beacon-produced block, if a transition to the BFT is configured.
+    pub fn bft_activation_height(&self) -> Option<u32> {
+        self.bft_activation_height.get().copied()
+    }
+
+    /// Ensures the authority of the given block follows the transition from the beacon to the BFT, if configured.
+    fn check_authority_transition(&self, block: &Block<N>) -> Result<()> {
+        match self.bft_activation_height() {
+            Some(activation_height) => {
+                let is_beacon = matches!(block.authority(), Authority::Beacon(_));
+                check_authority_transition(activation_height, block.height(), is_beacon)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the ledger.
+    pub const fn ledger(&self) -> &Ledger<N, C> {
+        &self.ledger
+    }
+
+    /// Runs the given closure while holding the advance lock, so that its writes to the ledger storage
+    /// are never interleaved with an advance to the next block.
+    pub fn with_advance_lock<T>(&self, f: impl FnOnce(&Ledger<N, C>) -> T) -> T {
+        let _lock = self.advance_lock.lock();
+        f(&self.ledger)
+    }
+
+    /// Waits for an in-progress advance to the next block to be written to the ledger.
+    /// Note: Once the shutdown flag is set, no further blocks are advanced to.
+    pub fn wait_for_pending_writes(&self) {
+        let _lock = self.advance_lock.lock();
     }
 }
 
@@ -64,6 +122,50 @@ impl<N: Network, C: ConsensusStorage<N>> fmt::Debug for CoreLedgerService<N, C>
     }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> CommitteeSource<N> for CoreLedgerService<N, C> {
+    /// Returns the genesis committee.
+    fn genesis_committee(&self) -> Result<Committee<N>> {
+        match self.ledger.get_committee_for_round(0)? {
+            Some(committee) => Ok(committee),
+            None => bail!("No genesis committee found in the ledger"),
+        }
+    }
+
+    /// Returns the current committee.
+    fn current_committee(&self) -> Result<Committee<N>> {
+        self.ledger.latest_committee()
+    }
+
+    /// Returns the committee for the given round.
+    /// If the given round is in the future, then the current committee is returned.
+    fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>> {
+        // Check if the committee is already in the cache.
+        if let Some(committee) = self.committee_cache.lock().get(&round) {
+            return Ok(committee.clone());
+        }
+
+        match self.ledger.get_committee_for_round(round)? {
+            // Return the committee if it exists.
+            Some(committee) => {
+                // Insert the committee into the cache.
+                self.committee_cache.lock().push(round, committee.clone());
+                // Return the committee.
+                Ok(committee)
+            }
+            // Return the current committee if the round is in the future.
+            None => {
+                // Retrieve the current committee.
+                let current_committee = self.current_committee()?;
+                // Return the current committee if the round is in the future.
+                match current_committee.starting_round() <= round {
+                    true => Ok(current_committee),
+                    false => bail!("No committee found for round {round} in the ledger"),
+                }
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<N, C> {
     /// Returns the latest round in the ledger.
@@ -126,57 +228,6 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
         }
     }
 
-    /// Returns the current committee.
-    fn current_committee(&self) -> Result<Committee<N>> {
-        self.ledger.latest_committee()
-    }
-
-    /// Returns the committee for the given round.
-    /// If the given round is in the future, then the current committee is returned.
-    fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>> {
-        // Check if the committee is already in the cache.
-        if let Some(committee) = self.committee_cache.lock().get(&round) {
-            return Ok(committee.clone());
-        }
-
-        match self.ledger.get_committee_for_round(round)? {
-            // Return the committee if it exists.
-            Some(committee) => {
-                // Insert the committee into the cache.
-                self.committee_cache.lock().push(round, committee.clone());
-                // Return the committee.
-                Ok(committee)
-            }
-            // Return the current committee if the round is in the future.
-            None => {
-                // Retrieve the current committee.
-                let current_committee = self.current_committee()?;
-                // Return the current committee if the round is in the future.
-                match current_committee.starting_round() <= round {
-                    true => Ok(current_committee),
-                    false => bail!("No committee found for round {round} in the ledger"),
-                }
-            }
-        }
-    }
-
-    /// Returns the committee lookback for the given round.
-    /// If the committee lookback round is in the future, then the current committee is returned.
-    fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>> {
-        // Get the round number for the previous committee. Note, we subtract 2 from odd rounds,
-        // because committees are updated in even rounds.
-        let previous_round = match round % 2 == 0 {
-            true => round.saturating_sub(1),
-            false => round.saturating_sub(2),
-        };
-
-        // Get the committee lookback round.
-        let committee_lookback_round = previous_round.saturating_sub(Committee::<N>::COMMITTEE_LOOKBACK_RANGE);
-
-        // Retrieve the committee for the committee lookback round.
-        self.get_committee_for_round(committee_lookback_round)
-    }
-
     /// Returns `true` if the ledger contains the given certificate ID in block history.
     fn contains_certificate(&self, certificate_id: &Field<N>) -> Result<bool> {
         self.ledger.contains_certificate(certificate_id)
@@ -301,6 +352,7 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
 
     /// Checks the given block is valid next block.
     fn check_next_block(&self, block: &Block<N>) -> Result<()> {
+        self.check_authority_transition(block)?;
         self.ledger.check_next_block(block, &mut rand::thread_rng())
     }
 
@@ -315,15 +367,78 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
     }
 
     /// Adds the given block as the next block in the ledger.
+    ///
+    /// Note: The block is written to the ledger storage directly. The next block is checked against the finalize
+    /// state and the committees in the stores of the ledger, so an in-memory overlay in front of the storage
+    /// could not serve these checks without first writing its blocks out, which is the latency it would save.
     #[cfg(feature = "ledger-write")]
     fn advance_to_next_block(&self, block: &Block<N>) -> Result<()> {
+        // Acquire the advance lock, so that shutdown can wait for the block to be written.
+        let _lock = self.advance_lock.lock();
         // If the Ctrl-C handler registered the signal, then skip advancing to the next block.
         if self.shutdown.load(Ordering::Relaxed) {
             bail!("Skipping advancing to block {} - The node is shutting down", block.height());
         }
+        // Ensure the block follows the transition from the beacon to the BFT.
+        self.check_authority_transition(block)?;
+        // Retrieve the starting round of the current committee, to detect a change of committee.
+        let event_bus = self.event_bus.get();
+        let previous_starting_round =
+            event_bus.and_then(|_| self.ledger.latest_committee().ok()).map(|committee| committee.starting_round());
         // Advance to the next block.
         self.ledger.advance_to_next_block(block)?;
         tracing::info!("\n\nAdvanced to block {} at round {} - {}\n", block.height(), block.round(), block.hash());
+        // Publish the block, and the new committee if it changed.
+        if let Some(event_bus) = event_bus {
+            event_bus.publish(NodeEvent::BlockAdvanced { height: block.height(), hash: block.hash() });
+            if let Ok(committee) = self.ledger.latest_committee() {
+                if previous_starting_round != Some(committee.starting_round()) {
+                    event_bus.publish(NodeEvent::EpochChanged { starting_round: committee.starting_round() });
+                }
+            }
+        }
         Ok(())
     }
 }
+
+/// Ensures the block at the given height, produced by the beacon if `is_beacon` is set or by the BFT otherwise,
+/// follows the transition from the beacon to the BFT after the block at the given activation height.
+/// Note: The genesis block is always produced by the beacon.
+fn check_authority_transition(activation_height: u32, height: u32, is_beacon: bool) -> Result<()> {
+    match (is_beacon, height) {
+        (_, 0) => Ok(()),
+        (true, height) if height > activation_height => {
+            bail!("Block {height} must be produced by the BFT, which activated after block {activation_height}")
+        }
+        (false, height) if height <= activation_height => {
+            bail!("Block {height} must be produced by the beacon, as the BFT activates after {activation_height}")
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_authority_transition() {
+        let activation_height = 10;
+        // Ensure the genesis block is accepted from either authority.
+        assert!(check_authority_transition(activation_height, 0, true).is_ok());
+        assert!(check_authority_transition(activation_height, 0, false).is_ok());
+        // Ensure the blocks up to the activation height must be produced by the beacon.
+        for height in [1, activation_height - 1, activation_height] {
+            assert!(check_authority_transition(activation_height, height, true).is_ok());
+            assert!(check_authority_transition(activation_height, height, false).is_err());
+        }
+        // Ensure the blocks after the activation height must be produced by the BFT.
+        for height in [activation_height + 1, activation_height + 100] {
+            assert!(check_authority_transition(activation_height, height, false).is_ok());
+            assert!(check_authority_transition(activation_height, height, true).is_err());
+        }
+        // Ensure the BFT may produce every block after the genesis block, if it is active from the start.
+        assert!(check_authority_transition(0, 1, false).is_ok());
+        assert!(check_authority_transition(0, 1, true).is_err());
+    }
+}
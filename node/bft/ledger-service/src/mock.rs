@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{fmt_id, LedgerService};
+use crate::{fmt_id, CommitteeSource, LedgerService};
 use snarkvm::{
     ledger::{
         block::{Block, Transaction},
@@ -51,6 +51,24 @@ impl<N: Network> MockLedgerService<N> {
     }
 }
 
+impl<N: Network> CommitteeSource<N> for MockLedgerService<N> {
+    /// Returns the genesis committee.
+    fn genesis_committee(&self) -> Result<Committee<N>> {
+        Ok(self.committee.clone())
+    }
+
+    /// Returns the current committee.
+    fn current_committee(&self) -> Result<Committee<N>> {
+        Ok(self.committee.clone())
+    }
+
+    /// Returns the committee for the given round.
+    /// If the given round is in the future, then the current committee is returned.
+    fn get_committee_for_round(&self, _round: u64) -> Result<Committee<N>> {
+        Ok(self.committee.clone())
+    }
+}
+
 #[async_trait]
 impl<N: Network> LedgerService<N> for MockLedgerService<N> {
     /// Returns the latest round in the ledger.
@@ -115,22 +133,6 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
         unreachable!("MockLedgerService does not support get_batch_certificate")
     }
 
-    /// Returns the current committee.
-    fn current_committee(&self) -> Result<Committee<N>> {
-        Ok(self.committee.clone())
-    }
-
-    /// Returns the committee for the given round.
-    /// If the given round is in the future, then the current committee is returned.
-    fn get_committee_for_round(&self, _round: u64) -> Result<Committee<N>> {
-        Ok(self.committee.clone())
-    }
-
-    /// Returns the committee lookback for the given round.
-    fn get_committee_lookback_for_round(&self, _round: u64) -> Result<Committee<N>> {
-        Ok(self.committee.clone())
-    }
-
     /// Returns `false` for all queries.
     fn contains_certificate(&self, certificate_id: &Field<N>) -> Result<bool> {
         trace!("[MockLedgerService] Contains certificate ID {} - false", fmt_id(certificate_id));
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::LedgerService;
+use crate::{CommitteeSource, LedgerService};
 use snarkvm::{
     ledger::{
         block::{Block, Transaction},
@@ -39,6 +39,24 @@ impl<N: Network> ProverLedgerService<N> {
     }
 }
 
+impl<N: Network> CommitteeSource<N> for ProverLedgerService<N> {
+    /// Returns the genesis committee.
+    fn genesis_committee(&self) -> Result<Committee<N>> {
+        bail!("Genesis committee does not exist in prover")
+    }
+
+    /// Returns the current committee.
+    fn current_committee(&self) -> Result<Committee<N>> {
+        bail!("Committee does not exist in prover")
+    }
+
+    /// Returns the committee for the given round.
+    /// If the given round is in the future, then the current committee is returned.
+    fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>> {
+        bail!("Committee for round {round} does not exist in prover")
+    }
+}
+
 #[async_trait]
 impl<N: Network> LedgerService<N> for ProverLedgerService<N> {
     /// Returns the latest round in the ledger.
@@ -97,23 +115,6 @@ impl<N: Network> LedgerService<N> for ProverLedgerService<N> {
         bail!("Batch certificate '{certificate_id}' does not exist in prover")
     }
 
-    /// Returns the current committee.
-    fn current_committee(&self) -> Result<Committee<N>> {
-        bail!("Committee does not exist in prover")
-    }
-
-    /// Returns the committee for the given round.
-    /// If the given round is in the future, then the current committee is returned.
-    fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>> {
-        bail!("Committee for round {round} does not exist in prover")
-    }
-
-    /// Returns the committee lookback for the given round.
-    /// If the committee lookback round is in the future, then the current committee is returned.
-    fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>> {
-        bail!("Previous committee for round {round} does not exist in prover")
-    }
-
     /// Returns `true` if the ledger contains the given certificate ID in block history.
     fn contains_certificate(&self, certificate_id: &Field<N>) -> Result<bool> {
         bail!("Certificate '{certificate_id}' does not exist in prover")
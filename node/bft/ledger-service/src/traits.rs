@@ -25,8 +25,45 @@ use snarkvm::{
 use indexmap::IndexMap;
 use std::{fmt::Debug, ops::Range};
 
+/// A source of the committees of the BFT.
+///
+/// The committees are derived from the ledger state, starting with the committee in the genesis block,
+/// so that all validators agree on the committee by consensus.
+pub trait CommitteeSource<N: Network>: Send + Sync {
+    /// Returns the genesis committee.
+    fn genesis_committee(&self) -> Result<Committee<N>>;
+
+    /// Returns the current committee.
+    fn current_committee(&self) -> Result<Committee<N>>;
+
+    /// Returns the committee for the given round.
+    /// If the given round is in the future, then the current committee is returned.
+    fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>>;
+
+    /// Returns the committee lookback for the given round.
+    /// If the committee lookback round is in the future, then the current committee is returned.
+    fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>> {
+        // Retrieve the committee for the committee lookback round.
+        self.get_committee_for_round(committee_lookback_round::<N>(round))
+    }
+}
+
+/// Returns the round of the committee lookback for the given round, i.e. the round of the committee
+/// that elects the leader of the given round, and certifies its batches.
+pub fn committee_lookback_round<N: Network>(round: u64) -> u64 {
+    // Get the round number for the previous committee. Note, we subtract 2 from odd rounds,
+    // because committees are updated in even rounds.
+    let previous_round = match round % 2 == 0 {
+        true => round.saturating_sub(1),
+        false => round.saturating_sub(2),
+    };
+
+    // Get the committee lookback round.
+    previous_round.saturating_sub(Committee::<N>::COMMITTEE_LOOKBACK_RANGE)
+}
+
 #[async_trait]
-pub trait LedgerService<N: Network>: Debug + Send + Sync {
+pub trait LedgerService<N: Network>: CommitteeSource<N> + Debug + Send + Sync {
     /// Returns the latest round in the ledger.
     fn latest_round(&self) -> u64;
 
@@ -61,17 +98,6 @@ pub trait LedgerService<N: Network>: Debug + Send + Sync {
     /// Returns the batch certificate for the given batch certificate ID.
     fn get_batch_certificate(&self, certificate_id: &Field<N>) -> Result<BatchCertificate<N>>;
 
-    /// Returns the current committee.
-    fn current_committee(&self) -> Result<Committee<N>>;
-
-    /// Returns the committee for the given round.
-    /// If the given round is in the future, then the current committee is returned.
-    fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>>;
-
-    /// Returns the committee lookback for the given round.
-    /// If the committee lookback round is in the future, then the current committee is returned.
-    fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>>;
-
     /// Returns `true` if the ledger contains the given certificate ID.
     fn contains_certificate(&self, certificate_id: &Field<N>) -> Result<bool>;
 
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{CoreLedgerService, LedgerService};
+use crate::{CommitteeSource, CoreLedgerService, LedgerService};
 use async_trait::async_trait;
 use indexmap::IndexMap;
 use snarkvm::{
@@ -50,6 +50,24 @@ impl<N: Network, C: ConsensusStorage<N>> TranslucentLedgerService<N, C> {
     }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> CommitteeSource<N> for TranslucentLedgerService<N, C> {
+    /// Returns the genesis committee.
+    fn genesis_committee(&self) -> Result<Committee<N>> {
+        self.inner.genesis_committee()
+    }
+
+    /// Returns the current committee.
+    fn current_committee(&self) -> Result<Committee<N>> {
+        self.inner.current_committee()
+    }
+
+    /// Returns the committee for the given round.
+    /// If the given round is in the future, then the current committee is returned.
+    fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>> {
+        self.inner.get_committee_for_round(round)
+    }
+}
+
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for TranslucentLedgerService<N, C> {
     /// Returns the latest round in the ledger.
@@ -108,22 +126,6 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for TranslucentLedgerS
         self.inner.get_batch_certificate(certificate_id)
     }
 
-    /// Returns the current committee.
-    fn current_committee(&self) -> Result<Committee<N>> {
-        self.inner.current_committee()
-    }
-
-    /// Returns the committee for the given round.
-    /// If the given round is in the future, then the current committee is returned.
-    fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>> {
-        self.inner.get_committee_for_round(round)
-    }
-
-    /// Returns the committee lookback for the given round.
-    fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>> {
-        self.inner.get_committee_lookback_for_round(round)
-    }
-
     /// Returns `true` if the ledger contains the given certificate ID in block history.
     fn contains_certificate(&self, certificate_id: &Field<N>) -> Result<bool> {
         self.inner.contains_certificate(certificate_id)
@@ -19,16 +19,22 @@ use crate::{
         now,
         BFTReceiver,
         ConsensusSender,
+        DagSnapshot,
         PrimaryReceiver,
         PrimarySender,
         Storage,
         DAG,
     },
     Primary,
+    DAG_SNAPSHOT_IN_SECS,
+    MAX_BATCH_DELAY_IN_MS,
+    MAX_COMMIT_LAG_IN_ROUNDS,
     MAX_LEADER_CERTIFICATE_DELAY_IN_SECS,
+    MAX_ROUND_STALL_IN_SECS,
 };
 use snarkos_account::Account;
-use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_bft_ledger_service::{CommitteeSource, LedgerService};
+use snarkos_node_clock as clock;
 use snarkvm::{
     console::account::Address,
     ledger::{
@@ -47,10 +53,12 @@ use std::{
     collections::{BTreeMap, HashSet},
     future::Future,
     net::SocketAddr,
+    path::PathBuf,
     sync::{
         atomic::{AtomicI64, Ordering},
         Arc,
     },
+    time::Duration,
 };
 use tokio::{
     sync::{oneshot, Mutex as TMutex, OnceCell},
@@ -71,6 +79,8 @@ pub struct BFT<N: Network> {
     last_election_certificate_ids: Arc<RwLock<IndexSet<Field<N>>>>,
     /// The consensus sender.
     consensus_sender: Arc<OnceCell<ConsensusSender<N>>>,
+    /// The path at which the snapshots of the DAG are stored, if snapshots are enabled.
+    snapshot_path: Arc<OnceCell<PathBuf>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The BFT lock.
@@ -94,6 +104,7 @@ impl<N: Network> BFT<N> {
             leader_certificate_timer: Default::default(),
             last_election_certificate_ids: Default::default(),
             consensus_sender: Default::default(),
+            snapshot_path: Default::default(),
             handles: Default::default(),
             lock: Default::default(),
         })
@@ -121,6 +132,11 @@ impl<N: Network> BFT<N> {
         Ok(())
     }
 
+    /// Sets the path at which the snapshots of the DAG are stored, and from which the DAG is restored at bootup.
+    pub fn set_snapshot_path(&self, path: PathBuf) {
+        self.snapshot_path.set(path).expect("Snapshot path already set");
+    }
+
     /// Returns the primary.
     pub const fn primary(&self) -> &Primary<N> {
         &self.primary
@@ -243,7 +259,19 @@ impl<N: Network> BFT<N> {
                 }
             } else {
                 match is_ready {
-                    true => info!("\n\nRound {current_round} reached quorum without a leader\n"),
+                    true => {
+                        info!("\n\nRound {current_round} reached quorum without a leader\n");
+                        // Log the leader whose certificate is missing.
+                        let leader = self
+                            .ledger()
+                            .get_committee_lookback_for_round(current_round)
+                            .and_then(|committee| committee.get_leader(current_round));
+                        if let Ok(leader) = leader {
+                            warn!("BFT advanced past round {current_round} without the certificate of leader {leader}");
+                        }
+                        #[cfg(feature = "metrics")]
+                        metrics::increment_counter(metrics::bft::LEADERS_MISSING);
+                    }
                     false => info!("{}", format!("\n\nRound {current_round} did not elect a leader\n").dimmed()),
                 }
             }
@@ -348,6 +376,52 @@ impl<N: Network> BFT<N> {
         self.leader_certificate_timer.load(Ordering::SeqCst) + MAX_LEADER_CERTIFICATE_DELAY_IN_SECS <= now()
     }
 
+    /// Returns `true` if the current round has not advanced for longer than `MAX_ROUND_STALL_IN_SECS`.
+    /// Note: The timer for the leader certificate is reset whenever the BFT advances to the next round.
+    fn is_round_stalled(&self) -> bool {
+        let round_start = self.leader_certificate_timer.load(Ordering::SeqCst);
+        // Only report a stall once the timer was set, otherwise the time is measured since the EPOCH.
+        round_start > 0 && round_start + MAX_ROUND_STALL_IN_SECS <= now()
+    }
+
+    /// Logs the stalled round, along with the committee members whose certificates are missing in the round.
+    fn report_stalled_round(&self, current_round: u64) {
+        let elapsed = now().saturating_sub(self.leader_certificate_timer.load(Ordering::SeqCst));
+        // Retrieve the committee lookback for the current round.
+        let committee_lookback = match self.ledger().get_committee_lookback_for_round(current_round) {
+            Ok(committee) => committee,
+            Err(e) => {
+                warn!("BFT round {current_round} has stalled for {elapsed}s (failed to retrieve the committee - {e})");
+                return;
+            }
+        };
+        // Determine the committee members whose certificates are missing in the current round.
+        let authors: HashSet<_> =
+            self.storage().get_certificates_for_round(current_round).iter().map(|c| c.author()).collect();
+        let stake = authors.iter().map(|author| committee_lookback.get_stake(*author)).sum::<u64>();
+        let missing = committee_lookback
+            .members()
+            .keys()
+            .filter(|member| !authors.contains(member))
+            .map(|member| member.to_string())
+            .collect::<Vec<_>>();
+        warn!(
+            "BFT round {current_round} stalled for {elapsed}s, with {stake}/{} stake of certificates - missing [{}]",
+            committee_lookback.quorum_threshold(),
+            missing.join(", ")
+        );
+        // In an even round, log the leader if their certificate is missing.
+        if current_round % 2 == 0 {
+            if let Ok(leader) = committee_lookback.get_leader(current_round) {
+                if !authors.contains(&leader) {
+                    warn!("BFT round {current_round} is missing the certificate of its leader {leader}");
+                }
+            }
+        }
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::bft::ROUNDS_STALLED);
+    }
+
     /// Returns 'true' if any of the following conditions hold:
     ///  - The leader certificate is 'None'.
     ///  - The leader certificate reached quorum threshold `(2f + 1)` (in the previous certificates in the current round).
@@ -776,6 +850,44 @@ impl<N: Network> BFT<N> {
             }
         });
 
+        // Periodically store a snapshot of the DAG, if new rounds were committed since the last snapshot.
+        let self_ = self.clone();
+        self.spawn(async move {
+            let mut last_snapshot_round = 0;
+            loop {
+                clock::sleep(Duration::from_secs(DAG_SNAPSHOT_IN_SECS)).await;
+                let last_committed_round = self_.dag.read().last_committed_round();
+                if last_committed_round > last_snapshot_round {
+                    self_.store_snapshot().await;
+                    last_snapshot_round = last_committed_round;
+                }
+            }
+        });
+
+        // Periodically report the rounds that stall, and the commits that lag behind the current round.
+        let self_ = self.clone();
+        self.spawn(async move {
+            let (mut last_stalled_round, mut last_lagging_round) = (0, 0);
+            loop {
+                clock::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                let current_round = self_.storage().current_round();
+                // Report the current round once, if it has stalled.
+                if current_round != last_stalled_round && self_.is_round_stalled() {
+                    self_.report_stalled_round(current_round);
+                    last_stalled_round = current_round;
+                }
+                // Report the number of rounds since the last commit.
+                let last_committed_round = self_.dag.read().last_committed_round();
+                let commit_lag = current_round.saturating_sub(last_committed_round);
+                #[cfg(feature = "metrics")]
+                metrics::gauge(metrics::bft::COMMIT_LAG, commit_lag as f64);
+                if commit_lag > MAX_COMMIT_LAG_IN_ROUNDS && current_round != last_lagging_round {
+                    warn!("BFT has not committed since round {last_committed_round} ({commit_lag} rounds behind)");
+                    last_lagging_round = current_round;
+                }
+            }
+        });
+
         // Process the request to sync the BFT.
         let self_ = self.clone();
         self.spawn(async move {
@@ -796,6 +908,9 @@ impl<N: Network> BFT<N> {
     /// Note that there is no need to insert the certificates into the DAG, because these certificates
     /// already exist in the ledger and therefore do not need to be re-ordered into future committed subdags.
     async fn sync_bft_dag_at_bootup(&self, certificates: Vec<BatchCertificate<N>>) {
+        // Restore the DAG from the latest snapshot, if there is one.
+        self.restore_snapshot();
+
         // Acquire the BFT write lock.
         let mut dag = self.dag.write();
 
@@ -805,6 +920,66 @@ impl<N: Network> BFT<N> {
         }
     }
 
+    /// Stores a snapshot of the DAG and the last election certificate IDs, if snapshots are enabled.
+    async fn store_snapshot(&self) {
+        let Some(path) = self.snapshot_path.get().cloned() else {
+            return;
+        };
+        let snapshot = DagSnapshot::new(
+            self.ledger().latest_block_height(),
+            self.dag.read().clone(),
+            self.last_election_certificate_ids(),
+        );
+        match tokio::task::spawn_blocking(move || snapshot.save(&path)).await {
+            Ok(Ok(())) => trace!("Stored a snapshot of the DAG"),
+            Ok(Err(error)) => warn!("Failed to store a snapshot of the DAG - {error}"),
+            Err(error) => warn!("Failed to store a snapshot of the DAG - {error}"),
+        }
+    }
+
+    /// Restores the DAG and the last election certificate IDs from the stored snapshot, if snapshots are enabled.
+    ///
+    /// A snapshot that is ahead of the ledger is discarded. The uncommitted certificates in the snapshot
+    /// are only restored if they are (or can be re-inserted) in storage, where they are checked as usual.
+    fn restore_snapshot(&self) {
+        let Some(path) = self.snapshot_path.get() else {
+            return;
+        };
+        let snapshot = match DagSnapshot::<N>::load(path) {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return,
+            Err(error) => {
+                warn!("Failed to load the snapshot of the DAG - {error}");
+                return;
+            }
+        };
+        // Ensure the snapshot is not ahead of the ledger.
+        let latest_height = self.ledger().latest_block_height();
+        if snapshot.height() > latest_height {
+            warn!(
+                "Discarding the snapshot of the DAG at block {}, as the ledger is at block {latest_height}",
+                snapshot.height()
+            );
+            return;
+        }
+        // Restore the uncommitted certificates that are valid in storage, in ascending round order.
+        let mut dag = snapshot.dag().clone();
+        let storage = self.storage();
+        dag.retain(|certificate| {
+            storage.contains_certificate(certificate.id())
+                || storage.insert_certificate(certificate.clone(), Default::default()).is_ok()
+        });
+        let num_certificates = dag.graph().values().map(|certificates| certificates.len()).sum::<usize>();
+        let last_committed_round = dag.last_committed_round();
+        // Restore the DAG and the last election certificate IDs.
+        *self.dag.write() = dag;
+        *self.last_election_certificate_ids.write() = snapshot.last_election_certificate_ids().clone();
+        info!(
+            "Restored the DAG from the snapshot at block {} (last committed round {last_committed_round}, {num_certificates} uncommitted certificates)",
+            snapshot.height()
+        );
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -817,6 +992,8 @@ impl<N: Network> BFT<N> {
         let _lock = self.lock.lock().await;
         // Shut down the primary.
         self.primary.shut_down().await;
+        // Store a final snapshot of the DAG.
+        self.store_snapshot().await;
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
     }
@@ -827,6 +1004,7 @@ mod tests {
     use crate::{
         helpers::{now, Storage},
         BFT,
+        MAX_ROUND_STALL_IN_SECS,
     };
     use snarkos_account::Account;
     use snarkos_node_bft_ledger_service::MockLedgerService;
@@ -975,6 +1153,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_is_round_stalled() -> Result<()> {
+        let rng = &mut TestRng::default();
+
+        // Sample the test instance.
+        let (committee, account, ledger, storage) = sample_test_instance(Some(2), 10, rng);
+        assert_eq!(storage.current_round(), 2);
+
+        // Initialize the BFT.
+        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        // Ensure the round is not stalled before the timer is set.
+        assert!(!bft.is_round_stalled());
+
+        // Set the timer to now().
+        bft.leader_certificate_timer.store(now(), Ordering::SeqCst);
+        assert!(!bft.is_round_stalled());
+
+        // Set the timer to the stall threshold.
+        bft.leader_certificate_timer.store(now() - MAX_ROUND_STALL_IN_SECS, Ordering::SeqCst);
+        assert!(bft.is_round_stalled());
+
+        // Ensure the report names every committee member, as none of their certificates are in storage.
+        bft.report_stalled_round(2);
+        for member in committee.members().keys() {
+            assert!(logs_contain(&member.to_string()));
+        }
+        assert!(logs_contain("is missing the certificate of its leader"));
+        Ok(())
+    }
+
     #[test]
     #[tracing_test::traced_test]
     fn test_update_leader_certificate_odd() -> Result<()> {
@@ -22,6 +22,7 @@ use crate::{
     MAX_TRANSMISSIONS_PER_BATCH,
     MAX_TRANSMISSIONS_PER_WORKER_PING,
     MEMORY_POOL_PORT,
+    TRANSMISSION_SKETCH_CELLS,
 };
 use snarkos_account::Account;
 use snarkos_node_bft_events::{
@@ -40,7 +41,8 @@ use snarkos_node_bft_events::{
     ValidatorsRequest,
     ValidatorsResponse,
 };
-use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_bft_ledger_service::{CommitteeSource, LedgerService};
+use snarkos_node_clock as clock;
 use snarkos_node_sync::communication_service::CommunicationService;
 use snarkos_node_tcp::{
     is_bogon_ip,
@@ -626,6 +628,19 @@ impl<N: Network> Gateway<N> {
                 }
                 Ok(())
             }
+            Event::UpgradeSignal(upgrade_signal) => {
+                // Send the upgrade signal to the primary.
+                let _ = self.primary_sender().tx_upgrade_signal.send((peer_ip, upgrade_signal)).await;
+                Ok(())
+            }
+            Event::CheckpointSignature(checkpoint_signature) => {
+                // If a sync sender was provided, send the checkpoint signature to the sync module.
+                if let Some(sync_sender) = self.sync_sender.get() {
+                    // Send the checkpoint signature to the sync module.
+                    let _ = sync_sender.tx_checkpoint_signature.send((peer_ip, checkpoint_signature)).await;
+                }
+                Ok(())
+            }
             Event::ChallengeRequest(..) | Event::ChallengeResponse(..) => {
                 // Disconnect as the peer is not following the protocol.
                 bail!("{CONTEXT} Peer '{peer_ip}' is not following the protocol")
@@ -790,6 +805,16 @@ impl<N: Network> Gateway<N> {
                 }
                 Ok(())
             }
+            Event::TransmissionSketch(sketch) => {
+                // Ensure the sketch has the expected number of cells.
+                ensure!(sketch.cells.len() == TRANSMISSION_SKETCH_CELLS, "{CONTEXT} Received a malformed sketch");
+                // Send the transmission sketch to the worker.
+                if let Some(sender) = self.get_worker_sender(sketch.worker_id) {
+                    // Send the transmission sketch to the worker.
+                    let _ = sender.tx_transmission_sketch.send((peer_ip, sketch)).await;
+                }
+                Ok(())
+            }
         }
     }
 
@@ -810,13 +835,13 @@ impl<N: Network> Gateway<N> {
         let self_clone = self.clone();
         self.spawn(async move {
             // Sleep briefly to ensure the other nodes are ready to connect.
-            tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+            clock::sleep(std::time::Duration::from_millis(1000)).await;
             info!("Starting the heartbeat of the gateway...");
             loop {
                 // Process a heartbeat in the router.
                 self_clone.heartbeat();
                 // Sleep for the heartbeat interval.
-                tokio::time::sleep(Duration::from_secs(15)).await;
+                clock::sleep(Duration::from_secs(15)).await;
             }
         });
     }
@@ -944,7 +969,7 @@ impl<N: Network> Transport<N> for Gateway<N> {
                 // Rate limit the number of certificate requests sent to the peer.
                 while $self.cache.$cache_map(peer_ip, $interval) > $self.$freq() {
                     // Sleep for a short period of time to allow the cache to clear.
-                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    clock::sleep(Duration::from_millis(10)).await;
                 }
                 // Send the event to the peer.
                 $self.send_inner(peer_ip, event)
@@ -1059,8 +1084,15 @@ impl<N: Network> Disconnect for Gateway<N> {
 
 #[async_trait]
 impl<N: Network> OnConnect for Gateway<N> {
-    async fn on_connect(&self, _peer_addr: SocketAddr) {
-        return;
+    /// Notifies the workers of the newly-connected peer, so that they send it the IDs of their pending transmissions.
+    async fn on_connect(&self, peer_addr: SocketAddr) {
+        // Resolve the peer address to the listener address.
+        let Some(peer_ip) = self.resolver.get_listener(peer_addr) else { return };
+        if let Some(worker_senders) = self.worker_senders.get() {
+            for sender in worker_senders.values() {
+                let _ = sender.tx_connected_peer.send(peer_ip).await;
+            }
+        }
     }
 }
 
@@ -1194,7 +1226,7 @@ impl<N: Network> Gateway<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = ChallengeResponse::<N>::message(peer_request.nonce, response_nonce);
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
@@ -1249,7 +1281,7 @@ impl<N: Network> Gateway<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = ChallengeResponse::<N>::message(peer_request.nonce, response_nonce);
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
@@ -1318,7 +1350,7 @@ impl<N: Network> Gateway<N> {
             return Some(DisconnectReason::InvalidChallengeResponse);
         };
         // Verify the signature.
-        if !signature.verify_bytes(&peer_address, &[expected_nonce.to_le_bytes(), nonce.to_le_bytes()].concat()) {
+        if !signature.verify_bytes(&peer_address, &ChallengeResponse::<N>::message(expected_nonce, nonce)) {
             warn!("{CONTEXT} Gateway handshake with '{peer_addr}' failed (invalid signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         }
@@ -1502,9 +1534,16 @@ mod prop_tests {
                 let (tx_worker, rx_worker) = init_worker_channels();
                 // Construct the worker instance.
                 let ledger = Arc::new(MockLedgerService::new(committee.clone()));
-                let worker =
-                    Worker::new(id, Arc::new(gateway.clone()), worker_storage.clone(), ledger, Default::default())
-                        .unwrap();
+                let worker = Worker::new(
+                    id,
+                    Arc::new(gateway.clone()),
+                    worker_storage.clone(),
+                    ledger,
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                )
+                .unwrap();
                 // Run the worker instance.
                 worker.run(rx_worker);
 
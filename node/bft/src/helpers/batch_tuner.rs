@@ -0,0 +1,287 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{MAX_BATCH_DELAY_IN_MS, MAX_TRANSMISSIONS_PER_BATCH};
+
+use anyhow::{ensure, Result};
+use parking_lot::Mutex;
+use std::time::Duration;
+
+/// The minimum batch delay (in milliseconds) that the batch tuner may be bounded by.
+pub const MIN_TUNED_BATCH_DELAY_IN_MS: u64 = 100; // ms
+
+/// The weight of the latest observation in the moving averages of the arrival rate and the ordering latency.
+const SMOOTHING: f64 = 0.5;
+/// The multiple of the expected arrivals per batch delay that the batch size leaves room for.
+const SIZE_HEADROOM: f64 = 1.5;
+/// The multiple of the batch delay above which the ordering latency indicates the network is congested.
+const CONGESTION_FACTOR: f64 = 4.0;
+/// The factor by which the batch delay grows while the network is congested.
+const DELAY_BACKOFF: f64 = 1.25;
+/// The factor by which the batch delay shrinks while the network keeps up.
+const DELAY_DECAY: f64 = 0.9;
+
+/// The operator-set bounds within which the batch tuner adjusts the batch size and delay.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BatchTuningBounds {
+    /// The minimum number of transmissions in a batch.
+    pub min_batch_size: usize,
+    /// The maximum number of transmissions in a batch.
+    pub max_batch_size: usize,
+    /// The minimum delay (in milliseconds) before proposing a batch.
+    pub min_batch_delay_ms: u64,
+    /// The maximum delay (in milliseconds) before proposing a batch.
+    pub max_batch_delay_ms: u64,
+}
+
+impl Default for BatchTuningBounds {
+    /// Returns the bounds from a tenth of the static values, up to the static values.
+    fn default() -> Self {
+        Self {
+            min_batch_size: MAX_TRANSMISSIONS_PER_BATCH / 10,
+            max_batch_size: MAX_TRANSMISSIONS_PER_BATCH,
+            min_batch_delay_ms: MAX_BATCH_DELAY_IN_MS / 10,
+            max_batch_delay_ms: MAX_BATCH_DELAY_IN_MS,
+        }
+    }
+}
+
+impl BatchTuningBounds {
+    /// Initializes the bounds, ensuring they are ordered and within the limits that the other validators accept.
+    pub fn new(
+        (min_batch_size, max_batch_size): (usize, usize),
+        (min_batch_delay_ms, max_batch_delay_ms): (u64, u64),
+    ) -> Result<Self> {
+        ensure!(min_batch_size > 0, "The minimum batch size must be positive");
+        ensure!(min_batch_size <= max_batch_size, "The minimum batch size exceeds the maximum batch size");
+        ensure!(
+            max_batch_size <= MAX_TRANSMISSIONS_PER_BATCH,
+            "The maximum batch size must be at most {MAX_TRANSMISSIONS_PER_BATCH} transmissions"
+        );
+        ensure!(
+            min_batch_delay_ms >= MIN_TUNED_BATCH_DELAY_IN_MS,
+            "The minimum batch delay must be at least {MIN_TUNED_BATCH_DELAY_IN_MS} ms"
+        );
+        ensure!(min_batch_delay_ms <= max_batch_delay_ms, "The minimum batch delay exceeds the maximum batch delay");
+        ensure!(
+            max_batch_delay_ms <= MAX_BATCH_DELAY_IN_MS,
+            "The maximum batch delay must be at most {MAX_BATCH_DELAY_IN_MS} ms"
+        );
+        Ok(Self { min_batch_size, max_batch_size, min_batch_delay_ms, max_batch_delay_ms })
+    }
+}
+
+/// The live batch size and delay, and the observations they are derived from.
+#[derive(Copy, Clone, Debug)]
+struct TunerState {
+    /// The bounds of the tuning, or `None` if the static values are used.
+    bounds: Option<BatchTuningBounds>,
+    /// The number of transmissions in a batch.
+    batch_size: usize,
+    /// The delay (in milliseconds) before proposing a batch.
+    batch_delay_ms: u64,
+    /// The moving average of the transaction arrival rate, in transactions per second.
+    arrival_rate: Option<f64>,
+    /// The moving average of the ordering latency, in seconds.
+    latency: Option<f64>,
+    /// The time elapsed since the latest committed leader certificate, as accumulated by the tuning rounds.
+    since_commit: Duration,
+    /// Whether a leader certificate was committed since the previous tuning round.
+    has_committed: bool,
+}
+
+/// The feedback controller of the batch size and delay.
+///
+/// While the ordering latency keeps up with the batch delay, the delay shrinks towards its minimum, so that
+/// the transactions are ordered sooner. Once the latency exceeds a multiple of the delay, the network is
+/// considered congested, and the delay backs off towards its maximum. While nothing commits, the latency estimate
+/// is moved towards the time since the latest commit, so that it does not go stale. The batch size follows the
+/// arrival rate, leaving room for the transactions that are expected to arrive within a batch delay, and grows to
+/// drain the backlog of unconfirmed transmissions.
+///
+/// Until bounds are set, the tuner returns the static values, `MAX_TRANSMISSIONS_PER_BATCH` and
+/// `MAX_BATCH_DELAY_IN_MS`.
+#[derive(Debug)]
+pub struct BatchTuner {
+    state: Mutex<TunerState>,
+}
+
+impl Default for BatchTuner {
+    /// Initializes a new tuner, which returns the static values.
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(TunerState {
+                bounds: None,
+                batch_size: MAX_TRANSMISSIONS_PER_BATCH,
+                batch_delay_ms: MAX_BATCH_DELAY_IN_MS,
+                arrival_rate: None,
+                latency: None,
+                since_commit: Duration::ZERO,
+                has_committed: false,
+            }),
+        }
+    }
+}
+
+impl BatchTuner {
+    /// Returns the bounds of the tuning, or `None` if the static values are used.
+    pub fn bounds(&self) -> Option<BatchTuningBounds> {
+        self.state.lock().bounds
+    }
+
+    /// Returns `true` if the batch size and delay are tuned at runtime.
+    pub fn is_enabled(&self) -> bool {
+        self.bounds().is_some()
+    }
+
+    /// Returns the number of transmissions in a batch.
+    pub fn batch_size(&self) -> usize {
+        self.state.lock().batch_size
+    }
+
+    /// Returns the delay (in milliseconds) before proposing a batch.
+    pub fn batch_delay_ms(&self) -> u64 {
+        self.state.lock().batch_delay_ms
+    }
+
+    /// Returns the delay before proposing a batch.
+    pub fn batch_delay(&self) -> Duration {
+        Duration::from_millis(self.batch_delay_ms())
+    }
+
+    /// Tunes the batch size and delay within the given bounds, starting from the maximum values.
+    pub fn enable(&self, bounds: BatchTuningBounds) {
+        let mut state = self.state.lock();
+        state.bounds = Some(bounds);
+        state.batch_size = bounds.max_batch_size;
+        state.batch_delay_ms = bounds.max_batch_delay_ms;
+    }
+
+    /// Records the ordering latency of a committed leader certificate.
+    pub fn record_latency(&self, latency: Duration) {
+        let mut state = self.state.lock();
+        state.latency = Some(smooth(state.latency, latency.as_secs_f64()));
+        state.since_commit = Duration::ZERO;
+        state.has_committed = true;
+    }
+
+    /// Adjusts the batch size and delay, given the number of transactions that arrived within the elapsed time,
+    /// and the number of unconfirmed transmissions that are waiting to be proposed.
+    pub fn tune(&self, num_arrivals: usize, num_unconfirmed: usize, elapsed: Duration) {
+        let mut state = self.state.lock();
+        let Some(bounds) = state.bounds else {
+            return;
+        };
+        if elapsed.is_zero() {
+            return;
+        }
+        let arrival_rate = smooth(state.arrival_rate, num_arrivals as f64 / elapsed.as_secs_f64());
+        state.arrival_rate = Some(arrival_rate);
+
+        // If nothing was committed since the previous round, the ordering latency is at least the time since
+        // the latest commit, so move the estimate towards it.
+        state.since_commit = state.since_commit.saturating_add(elapsed);
+        if !std::mem::take(&mut state.has_committed) && state.latency.is_some() {
+            state.latency = Some(smooth(state.latency, state.since_commit.as_secs_f64()));
+        }
+
+        // Back off while the ordering latency lags behind the batch delay, and shrink the delay otherwise.
+        let delay_secs = state.batch_delay_ms as f64 / 1000.0;
+        let is_congested = state.latency.map_or(false, |latency| latency > CONGESTION_FACTOR * delay_secs);
+        let factor = if is_congested { DELAY_BACKOFF } else { DELAY_DECAY };
+        let batch_delay_ms = (state.batch_delay_ms as f64 * factor).round() as u64;
+        state.batch_delay_ms = batch_delay_ms.clamp(bounds.min_batch_delay_ms, bounds.max_batch_delay_ms);
+
+        // Leave room for the transactions that are expected to arrive within a batch delay,
+        // and for the backlog of unconfirmed transmissions, so that it is drained after a burst.
+        let expected_arrivals = arrival_rate * state.batch_delay_ms as f64 / 1000.0;
+        let batch_size = ((expected_arrivals * SIZE_HEADROOM).ceil() as usize).max(num_unconfirmed);
+        state.batch_size = batch_size.clamp(bounds.min_batch_size, bounds.max_batch_size);
+    }
+}
+
+/// Returns the moving average, given the previous average (if any) and the latest observation.
+fn smooth(average: Option<f64>, observation: f64) -> f64 {
+    match average {
+        Some(average) => SMOOTHING * observation + (1.0 - SMOOTHING) * average,
+        None => observation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_tuning_bounds() {
+        assert!(BatchTuningBounds::new((25, 250), (250, 2500)).is_ok());
+        assert!(BatchTuningBounds::new((0, 250), (250, 2500)).is_err());
+        assert!(BatchTuningBounds::new((250, 25), (250, 2500)).is_err());
+        assert!(BatchTuningBounds::new((25, MAX_TRANSMISSIONS_PER_BATCH + 1), (250, 2500)).is_err());
+        assert!(BatchTuningBounds::new((25, 250), (MIN_TUNED_BATCH_DELAY_IN_MS - 1, 2500)).is_err());
+        assert!(BatchTuningBounds::new((25, 250), (250, MAX_BATCH_DELAY_IN_MS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_batch_tuner() {
+        let tuner = BatchTuner::default();
+        // While disabled, the static values are used.
+        tuner.tune(1_000, 0, Duration::from_secs(1));
+        assert_eq!(tuner.batch_size(), MAX_TRANSMISSIONS_PER_BATCH);
+        assert_eq!(tuner.batch_delay_ms(), MAX_BATCH_DELAY_IN_MS);
+
+        let bounds = BatchTuningBounds::new((10, 200), (500, 2000)).unwrap();
+        tuner.enable(bounds);
+
+        // While the network keeps up, the delay shrinks to its minimum, and the size follows the arrivals.
+        for _ in 0..50 {
+            tuner.record_latency(Duration::from_millis(100));
+            tuner.tune(100, 0, Duration::from_secs(1));
+        }
+        assert_eq!(tuner.batch_delay_ms(), 500);
+        assert_eq!(tuner.batch_size(), 75);
+
+        // Once the network is congested, the delay backs off to its maximum, and the size to its bounds.
+        for _ in 0..50 {
+            tuner.record_latency(Duration::from_secs(20));
+            tuner.tune(1_000, 0, Duration::from_secs(1));
+        }
+        assert_eq!(tuner.batch_delay_ms(), 2000);
+        assert_eq!(tuner.batch_size(), 200);
+
+        // When idle, the size drops to its minimum.
+        for _ in 0..50 {
+            tuner.record_latency(Duration::from_millis(100));
+            tuner.tune(0, 0, Duration::from_secs(1));
+        }
+        assert_eq!(tuner.batch_delay_ms(), 500);
+        assert_eq!(tuner.batch_size(), 10);
+
+        // After a burst, the size grows to drain the backlog, even while few transactions arrive.
+        tuner.record_latency(Duration::from_millis(100));
+        tuner.tune(0, 150, Duration::from_secs(1));
+        assert_eq!(tuner.batch_size(), 150);
+
+        // While nothing commits, the latency estimate catches up with the time since the latest commit,
+        // so the delay backs off.
+        for _ in 0..2 {
+            tuner.tune(0, 0, Duration::from_secs(1));
+        }
+        assert_eq!(tuner.batch_delay_ms(), 625);
+        for _ in 0..8 {
+            tuner.tune(0, 0, Duration::from_secs(1));
+        }
+        assert_eq!(tuner.batch_delay_ms(), 2000);
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use snarkos_node_clock as clock;
 use snarkvm::{console::types::Field, ledger::narwhal::TransmissionID, prelude::Network};
 
 use core::hash::Hash;
@@ -20,7 +21,6 @@ use std::{
     collections::{BTreeMap, HashMap},
     net::{IpAddr, SocketAddr},
 };
-use time::OffsetDateTime;
 
 #[derive(Debug)]
 pub struct Cache<N: Network> {
@@ -129,7 +129,7 @@ impl<N: Network> Cache<N> {
         interval_in_secs: i64,
     ) -> usize {
         // Fetch the current timestamp.
-        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let now = clock::unix_timestamp();
 
         // Get the write lock.
         let mut map_write = map.write();
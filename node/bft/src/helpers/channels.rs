@@ -17,8 +17,11 @@ use crate::events::{
     BatchSignature,
     CertificateRequest,
     CertificateResponse,
+    CheckpointSignature,
     TransmissionRequest,
     TransmissionResponse,
+    TransmissionSketch,
+    UpgradeSignal,
 };
 use snarkos_node_sync::locators::BlockLocators;
 use snarkvm::{
@@ -155,6 +158,7 @@ pub struct PrimarySender<N: Network> {
     pub tx_unconfirmed_solution:
         mpsc::Sender<(PuzzleCommitment<N>, Data<ProverSolution<N>>, oneshot::Sender<Result<()>>)>,
     pub tx_unconfirmed_transaction: mpsc::Sender<(N::TransactionID, Data<Transaction<N>>, oneshot::Sender<Result<()>>)>,
+    pub tx_upgrade_signal: mpsc::Sender<(SocketAddr, UpgradeSignal<N>)>,
 }
 
 impl<N: Network> PrimarySender<N> {
@@ -198,6 +202,7 @@ pub struct PrimaryReceiver<N: Network> {
         mpsc::Receiver<(PuzzleCommitment<N>, Data<ProverSolution<N>>, oneshot::Sender<Result<()>>)>,
     pub rx_unconfirmed_transaction:
         mpsc::Receiver<(N::TransactionID, Data<Transaction<N>>, oneshot::Sender<Result<()>>)>,
+    pub rx_upgrade_signal: mpsc::Receiver<(SocketAddr, UpgradeSignal<N>)>,
 }
 
 /// Initializes the primary channels.
@@ -208,6 +213,7 @@ pub fn init_primary_channels<N: Network>() -> (PrimarySender<N>, PrimaryReceiver
     let (tx_primary_ping, rx_primary_ping) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_unconfirmed_solution, rx_unconfirmed_solution) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_unconfirmed_transaction, rx_unconfirmed_transaction) = mpsc::channel(MAX_CHANNEL_SIZE);
+    let (tx_upgrade_signal, rx_upgrade_signal) = mpsc::channel(MAX_CHANNEL_SIZE);
 
     let sender = PrimarySender {
         tx_batch_propose,
@@ -216,6 +222,7 @@ pub fn init_primary_channels<N: Network>() -> (PrimarySender<N>, PrimaryReceiver
         tx_primary_ping,
         tx_unconfirmed_solution,
         tx_unconfirmed_transaction,
+        tx_upgrade_signal,
     };
     let receiver = PrimaryReceiver {
         rx_batch_propose,
@@ -224,6 +231,7 @@ pub fn init_primary_channels<N: Network>() -> (PrimarySender<N>, PrimaryReceiver
         rx_primary_ping,
         rx_unconfirmed_solution,
         rx_unconfirmed_transaction,
+        rx_upgrade_signal,
     };
 
     (sender, receiver)
@@ -234,6 +242,8 @@ pub struct WorkerSender<N: Network> {
     pub tx_worker_ping: mpsc::Sender<(SocketAddr, TransmissionID<N>)>,
     pub tx_transmission_request: mpsc::Sender<(SocketAddr, TransmissionRequest<N>)>,
     pub tx_transmission_response: mpsc::Sender<(SocketAddr, TransmissionResponse<N>)>,
+    pub tx_transmission_sketch: mpsc::Sender<(SocketAddr, TransmissionSketch)>,
+    pub tx_connected_peer: mpsc::Sender<SocketAddr>,
 }
 
 #[derive(Debug)]
@@ -241,6 +251,8 @@ pub struct WorkerReceiver<N: Network> {
     pub rx_worker_ping: mpsc::Receiver<(SocketAddr, TransmissionID<N>)>,
     pub rx_transmission_request: mpsc::Receiver<(SocketAddr, TransmissionRequest<N>)>,
     pub rx_transmission_response: mpsc::Receiver<(SocketAddr, TransmissionResponse<N>)>,
+    pub rx_transmission_sketch: mpsc::Receiver<(SocketAddr, TransmissionSketch)>,
+    pub rx_connected_peer: mpsc::Receiver<SocketAddr>,
 }
 
 /// Initializes the worker channels.
@@ -248,9 +260,23 @@ pub fn init_worker_channels<N: Network>() -> (WorkerSender<N>, WorkerReceiver<N>
     let (tx_worker_ping, rx_worker_ping) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_transmission_request, rx_transmission_request) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_transmission_response, rx_transmission_response) = mpsc::channel(MAX_CHANNEL_SIZE);
-
-    let sender = WorkerSender { tx_worker_ping, tx_transmission_request, tx_transmission_response };
-    let receiver = WorkerReceiver { rx_worker_ping, rx_transmission_request, rx_transmission_response };
+    let (tx_transmission_sketch, rx_transmission_sketch) = mpsc::channel(MAX_CHANNEL_SIZE);
+    let (tx_connected_peer, rx_connected_peer) = mpsc::channel(MAX_CHANNEL_SIZE);
+
+    let sender = WorkerSender {
+        tx_worker_ping,
+        tx_transmission_request,
+        tx_transmission_response,
+        tx_transmission_sketch,
+        tx_connected_peer,
+    };
+    let receiver = WorkerReceiver {
+        rx_worker_ping,
+        rx_transmission_request,
+        rx_transmission_response,
+        rx_transmission_sketch,
+        rx_connected_peer,
+    };
 
     (sender, receiver)
 }
@@ -262,6 +288,7 @@ pub struct SyncSender<N: Network> {
     pub tx_block_sync_update_peer_locators: mpsc::Sender<(SocketAddr, BlockLocators<N>, oneshot::Sender<Result<()>>)>,
     pub tx_certificate_request: mpsc::Sender<(SocketAddr, CertificateRequest<N>)>,
     pub tx_certificate_response: mpsc::Sender<(SocketAddr, CertificateResponse<N>)>,
+    pub tx_checkpoint_signature: mpsc::Sender<(SocketAddr, CheckpointSignature<N>)>,
 }
 
 impl<N: Network> SyncSender<N> {
@@ -294,6 +321,7 @@ pub struct SyncReceiver<N: Network> {
     pub rx_block_sync_update_peer_locators: mpsc::Receiver<(SocketAddr, BlockLocators<N>, oneshot::Sender<Result<()>>)>,
     pub rx_certificate_request: mpsc::Receiver<(SocketAddr, CertificateRequest<N>)>,
     pub rx_certificate_response: mpsc::Receiver<(SocketAddr, CertificateResponse<N>)>,
+    pub rx_checkpoint_signature: mpsc::Receiver<(SocketAddr, CheckpointSignature<N>)>,
 }
 
 /// Initializes the sync channels.
@@ -304,6 +332,7 @@ pub fn init_sync_channels<N: Network>() -> (SyncSender<N>, SyncReceiver<N>) {
     let (tx_block_sync_update_peer_locators, rx_block_sync_update_peer_locators) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_certificate_request, rx_certificate_request) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_certificate_response, rx_certificate_response) = mpsc::channel(MAX_CHANNEL_SIZE);
+    let (tx_checkpoint_signature, rx_checkpoint_signature) = mpsc::channel(MAX_CHANNEL_SIZE);
 
     let sender = SyncSender {
         tx_block_sync_advance_with_sync_blocks,
@@ -311,6 +340,7 @@ pub fn init_sync_channels<N: Network>() -> (SyncSender<N>, SyncReceiver<N>) {
         tx_block_sync_update_peer_locators,
         tx_certificate_request,
         tx_certificate_response,
+        tx_checkpoint_signature,
     };
     let receiver = SyncReceiver {
         rx_block_sync_advance_with_sync_blocks,
@@ -318,6 +348,7 @@ pub fn init_sync_channels<N: Network>() -> (SyncSender<N>, SyncReceiver<N>) {
         rx_block_sync_update_peer_locators,
         rx_certificate_request,
         rx_certificate_response,
+        rx_checkpoint_signature,
     };
 
     (sender, receiver)
@@ -15,13 +15,16 @@
 use snarkvm::{
     console::types::{Address, Field},
     ledger::narwhal::BatchCertificate,
-    prelude::Network,
+    prelude::{FromBytes, Network, ToBytes},
 };
 
 use indexmap::IndexSet;
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct DAG<N: Network> {
     /// The in-memory collection of certificates that comprise the DAG.
     graph: BTreeMap<u64, HashMap<Address<N>, BatchCertificate<N>>>,
@@ -103,6 +106,14 @@ impl<N: Network> DAG<N> {
         }
     }
 
+    /// Retains only the certificates in the DAG that satisfy the given predicate, in ascending round order.
+    pub fn retain(&mut self, mut f: impl FnMut(&BatchCertificate<N>) -> bool) {
+        self.graph.retain(|_, certificates| {
+            certificates.retain(|_, certificate| f(certificate));
+            !certificates.is_empty()
+        });
+    }
+
     /// Commits a certificate, removing all certificates for this author at or before this round from the DAG.
     pub fn commit(&mut self, certificate: &BatchCertificate<N>, max_gc_rounds: u64) {
         let certificate_id = certificate.id();
@@ -135,6 +146,51 @@ impl<N: Network> DAG<N> {
     }
 }
 
+impl<N: Network> ToBytes for DAG<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Write the last committed round.
+        self.last_committed_round.write_le(&mut writer)?;
+        // Write the recently committed certificate IDs.
+        u32::try_from(self.recent_committed_ids.len()).map_err(io::Error::other)?.write_le(&mut writer)?;
+        for (round, certificate_ids) in &self.recent_committed_ids {
+            round.write_le(&mut writer)?;
+            u32::try_from(certificate_ids.len()).map_err(io::Error::other)?.write_le(&mut writer)?;
+            for certificate_id in certificate_ids {
+                certificate_id.write_le(&mut writer)?;
+            }
+        }
+        // Write the certificates in the graph.
+        let certificates = self.graph.values().flat_map(|certificates| certificates.values()).collect::<Vec<_>>();
+        u32::try_from(certificates.len()).map_err(io::Error::other)?.write_le(&mut writer)?;
+        for certificate in certificates {
+            certificate.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for DAG<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut dag = Self::new();
+        // Read the last committed round.
+        dag.last_committed_round = u64::read_le(&mut reader)?;
+        // Read the recently committed certificate IDs.
+        for _ in 0..u32::read_le(&mut reader)? {
+            let round = u64::read_le(&mut reader)?;
+            let num_certificate_ids = u32::read_le(&mut reader)?;
+            let certificate_ids =
+                (0..num_certificate_ids).map(|_| Field::read_le(&mut reader)).collect::<io::Result<IndexSet<_>>>()?;
+            dag.recent_committed_ids.insert(round, certificate_ids);
+        }
+        // Read the certificates in the graph.
+        for _ in 0..u32::read_le(&mut reader)? {
+            let certificate = BatchCertificate::read_le(&mut reader)?;
+            dag.graph.entry(certificate.round()).or_default().insert(certificate.author(), certificate);
+        }
+        Ok(dag)
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod test_helpers {
     use super::*;
@@ -184,6 +240,25 @@ mod tests {
         assert_eq!(dag.last_committed_round(), 0);
     }
 
+    #[test]
+    fn test_dag_bytes() {
+        let rng = &mut TestRng::default();
+        let mut dag = DAG::<Testnet3>::new();
+
+        // Sample a committed certificate for round 2, and an uncommitted certificate for round 3.
+        let certificate_2 = sample_batch_certificate_for_round(2, rng);
+        let certificate_3 = sample_batch_certificate_for_round(3, rng);
+        dag.commit(&certificate_2, 10);
+        dag.insert(certificate_3.clone());
+
+        // Ensure the DAG roundtrips.
+        let candidate = DAG::<Testnet3>::read_le(&dag.to_bytes_le().unwrap()[..]).unwrap();
+        assert_eq!(candidate.last_committed_round(), 2);
+        assert!(candidate.is_recently_committed(2, certificate_2.id()));
+        assert!(candidate.contains_certificate_in_round(3, certificate_3.id()));
+        assert_eq!(candidate.graph(), dag.graph());
+    }
+
     #[test]
     fn test_dag_commit() {
         let rng = &mut TestRng::default();
@@ -0,0 +1,152 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_sync::locators::BlockLocators;
+use snarkvm::prelude::{Address, Network};
+
+use parking_lot::RwLock;
+use std::{collections::VecDeque, fmt, net::SocketAddr};
+
+/// A block hash reported by a committee member, which conflicts with the block hash in the ledger at the same height.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockHashConflict<N: Network> {
+    /// The IP of the committee member.
+    pub peer_ip: SocketAddr,
+    /// The address of the committee member.
+    pub address: Address<N>,
+    /// The lowest height at which the block hashes conflict.
+    pub height: u32,
+    /// The block hash in the ledger.
+    pub expected_hash: N::BlockHash,
+    /// The block hash reported by the committee member.
+    pub reported_hash: N::BlockHash,
+}
+
+impl<N: Network> fmt::Display for BlockHashConflict<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Validator '{}' ('{}') reports block hash '{}' at height {}, but the ledger has '{}'",
+            self.address, self.peer_ip, self.reported_hash, self.height, self.expected_hash
+        )
+    }
+}
+
+/// The log of the most recent block hash conflicts, each with the UTC epoch timestamp (in seconds) at which
+/// the committee member first reported it, so that the recent forks remain visible after they are resolved.
+#[derive(Debug)]
+pub struct ForkLog<N: Network> {
+    /// The most recent block hash conflicts, from the oldest to the newest.
+    events: RwLock<VecDeque<(i64, BlockHashConflict<N>)>>,
+}
+
+impl<N: Network> Default for ForkLog<N> {
+    fn default() -> Self {
+        Self { events: Default::default() }
+    }
+}
+
+impl<N: Network> ForkLog<N> {
+    /// The maximum number of block hash conflicts retained in the log.
+    pub const CAPACITY: usize = 64;
+
+    /// Records the given block hash conflict, first reported at the given timestamp.
+    pub fn record(&self, timestamp: i64, conflict: BlockHashConflict<N>) {
+        let mut events = self.events.write();
+        if events.len() == Self::CAPACITY {
+            events.pop_front();
+        }
+        events.push_back((timestamp, conflict));
+    }
+
+    /// Returns the block hash conflicts first reported at or after the given timestamp, from the oldest to the newest.
+    pub fn since(&self, timestamp: i64) -> Vec<(i64, BlockHashConflict<N>)> {
+        self.events.read().iter().filter(|(reported_at, _)| *reported_at >= timestamp).cloned().collect()
+    }
+}
+
+/// Returns the lowest height at which the given block locators conflict with the ledger,
+/// along with the block hash in the ledger and the block hash in the locators.
+/// Note: Only the heights that exist in the ledger are cross-checked.
+pub fn find_block_hash_conflict<N: Network>(
+    ledger: &dyn LedgerService<N>,
+    locators: &BlockLocators<N>,
+) -> Option<(u32, N::BlockHash, N::BlockHash)> {
+    let latest_height = ledger.latest_block_height();
+    locators.clone().into_iter().take_while(|(height, _)| *height <= latest_height).find_map(|(height, hash)| {
+        match ledger.get_block_hash(height) {
+            Ok(expected_hash) if expected_hash != hash => Some((height, expected_hash, hash)),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::MockLedgerService;
+    use snarkos_node_sync::locators::test_helpers::{sample_block_locators, sample_block_locators_with_fork};
+    use snarkvm::{ledger::committee::test_helpers::sample_committee, utilities::TestRng};
+
+    use rand::Rng;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_find_block_hash_conflict() {
+        let rng = &mut TestRng::default();
+        let ledger = MockLedgerService::new_at_height(sample_committee(rng), 50);
+
+        // Ensure matching locators do not conflict, whether the peer is behind, level, or ahead.
+        for height in [10, 50, 80] {
+            assert!(find_block_hash_conflict(&ledger, &sample_block_locators(height)).is_none());
+        }
+        // Ensure a fork above the latest height of the ledger does not conflict.
+        assert!(find_block_hash_conflict(&ledger, &sample_block_locators_with_fork(80, 60)).is_none());
+        // Ensure a fork at or below the latest height of the ledger conflicts, at the fork height.
+        let (height, expected_hash, reported_hash) =
+            find_block_hash_conflict(&ledger, &sample_block_locators_with_fork(80, 40)).unwrap();
+        assert_eq!(height, 40);
+        assert_eq!(expected_hash, ledger.get_block_hash(40).unwrap());
+        assert_ne!(expected_hash, reported_hash);
+    }
+    #[test]
+    fn test_fork_log() {
+        let rng = &mut TestRng::default();
+        let ledger = MockLedgerService::new_at_height(sample_committee(rng), 50);
+        let mut conflict = |port: u16, height: u32| BlockHashConflict {
+            peer_ip: SocketAddr::from(([127, 0, 0, 1], port)),
+            address: Address::new(rng.gen()),
+            height,
+            expected_hash: ledger.get_block_hash(height).unwrap(),
+            reported_hash: ledger.get_block_hash(height + 1).unwrap(),
+        };
+
+        let log = ForkLog::default();
+        log.record(100, conflict(1, 10));
+        log.record(110, conflict(2, 20));
+        assert_eq!(log.since(0).len(), 2);
+        assert_eq!(log.since(105).iter().map(|(_, conflict)| conflict.height).collect::<Vec<_>>(), vec![20]);
+        assert!(log.since(111).is_empty());
+
+        // Only the most recent conflicts are retained.
+        for _ in 0..ForkLog::<CurrentNetwork>::CAPACITY {
+            log.record(200, conflict(3, 30));
+        }
+        let events = log.since(0);
+        assert_eq!(events.len(), ForkLog::<CurrentNetwork>::CAPACITY);
+        assert!(events.iter().all(|(timestamp, _)| *timestamp == 200));
+    }
+}
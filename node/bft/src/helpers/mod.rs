@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod batch_tuner;
+pub use batch_tuner::*;
+
 pub mod cache;
 pub use cache::*;
 
@@ -21,6 +24,9 @@ pub use channels::*;
 pub mod dag;
 pub use dag::*;
 
+pub mod divergence;
+pub use divergence::*;
+
 pub mod partition;
 pub use partition::*;
 
@@ -30,18 +36,36 @@ pub use pending::*;
 pub mod proposal;
 pub use proposal::*;
 
+pub mod proposal_gate;
+pub use proposal_gate::*;
+
+pub mod proposal_trigger;
+pub use proposal_trigger::*;
+
 pub mod ready;
 pub use ready::*;
 
 pub mod resolver;
 pub use resolver::*;
 
+pub mod sketch;
+pub use sketch::*;
+
+pub mod snapshot;
+pub use snapshot::*;
+
 pub mod storage;
 pub use storage::*;
 
 pub mod timestamp;
 pub use timestamp::*;
 
+pub mod transaction_validator;
+pub use transaction_validator::*;
+
+pub mod upgrade;
+pub use upgrade::*;
+
 /// Formats an ID into a truncated identifier (for logging purposes).
 pub fn fmt_id(id: impl ToString) -> String {
     let id = id.to_string();
@@ -0,0 +1,80 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// A reason for which the primary holds off proposing batches.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ProposalHold {
+    /// The operator paused proposing batches, e.g. for a maintenance window.
+    Paused,
+    /// The ledger is catching up with the network, so a proposal would be at a stale height.
+    CatchingUp,
+    /// The connected stake stayed below the quorum threshold, so no batch can be certified.
+    QuorumLost,
+    /// The ledger has not reached the last beacon-produced block, so the BFT is not active yet.
+    AwaitingActivation,
+}
+
+impl ProposalHold {
+    /// The holds, in the order in which they are reported.
+    pub const ALL: [Self; 4] = [Self::Paused, Self::CatchingUp, Self::QuorumLost, Self::AwaitingActivation];
+
+    /// Returns the bit of the hold in the gate.
+    const fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Returns the reason for skipping a batch proposal, for logging purposes.
+    pub const fn reason(self) -> &'static str {
+        match self {
+            Self::Paused => "proposing is paused",
+            Self::CatchingUp => "catching up with the network",
+            Self::QuorumLost => "the connected stake is below quorum",
+            Self::AwaitingActivation => "the BFT is not active yet",
+        }
+    }
+}
+
+/// The gate of the batch proposals, which is open while no hold is placed on it.
+///
+/// The holds are independent of each other, so that lifting one (e.g. as the connected stake regains quorum)
+/// never lifts another (e.g. a pause by the operator).
+#[derive(Debug, Default)]
+pub struct ProposalGate {
+    /// The bits of the holds that are placed on the gate.
+    holds: AtomicU8,
+}
+
+impl ProposalGate {
+    /// Returns `true` if the given hold is placed on the gate.
+    pub fn is_held(&self, hold: ProposalHold) -> bool {
+        self.holds.load(Ordering::Relaxed) & hold.mask() != 0
+    }
+
+    /// Returns the first hold that is placed on the gate, or `None` if the gate is open.
+    pub fn blocking_hold(&self) -> Option<ProposalHold> {
+        ProposalHold::ALL.into_iter().find(|hold| self.is_held(*hold))
+    }
+
+    /// Places the given hold on the gate, and returns `true` if it was not placed already.
+    pub fn hold(&self, hold: ProposalHold) -> bool {
+        self.holds.fetch_or(hold.mask(), Ordering::Relaxed) & hold.mask() == 0
+    }
+
+    /// Lifts the given hold from the gate, and returns `true` if it was placed.
+    pub fn lift(&self, hold: ProposalHold) -> bool {
+        self.holds.fetch_and(!hold.mask(), Ordering::Relaxed) & hold.mask() != 0
+    }
+}
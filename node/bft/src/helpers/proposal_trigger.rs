@@ -0,0 +1,168 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+/// The trigger with which the workers wake up the batch proposer, as the unconfirmed transactions arrive.
+///
+/// The batch proposer proposes when the transactions that arrived since the last proposal reach either
+/// threshold, or when the maximum batch delay elapses, whichever is first. A threshold of `0` is disabled,
+/// in which case the proposer falls back to waiting out the maximum batch delay.
+#[derive(Debug, Default)]
+pub struct ProposalTrigger {
+    /// The number of transactions that triggers a proposal.
+    min_transactions: AtomicUsize,
+    /// The sum of the priority fees (in microcredits) that triggers a proposal.
+    min_priority_fees: AtomicU64,
+    /// The number of transactions that arrived since the last proposal.
+    num_transactions: AtomicUsize,
+    /// The sum of the priority fees (in microcredits) that arrived since the last proposal.
+    priority_fees: AtomicU64,
+    /// The number of transactions that arrived since the node started.
+    total_transactions: AtomicUsize,
+    /// The notifier for the batch proposer.
+    notify: Notify,
+}
+
+impl ProposalTrigger {
+    /// Returns the number of transactions that triggers a proposal, where `0` is disabled.
+    pub fn min_transactions(&self) -> usize {
+        self.min_transactions.load(Ordering::Relaxed)
+    }
+
+    /// Returns the sum of the priority fees (in microcredits) that triggers a proposal, where `0` is disabled.
+    pub fn min_priority_fees(&self) -> u64 {
+        self.min_priority_fees.load(Ordering::Relaxed)
+    }
+
+    /// Sets the thresholds that trigger a proposal, where `0` disables a threshold.
+    pub fn set_thresholds(&self, min_transactions: usize, min_priority_fees: u64) {
+        self.min_transactions.store(min_transactions, Ordering::Relaxed);
+        self.min_priority_fees.store(min_priority_fees, Ordering::Relaxed);
+    }
+
+    /// Returns the number of transactions that arrived since the node started.
+    pub fn total_transactions(&self) -> usize {
+        self.total_transactions.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if either threshold is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.min_transactions() > 0 || self.min_priority_fees() > 0
+    }
+
+    /// Returns `true` if the transactions that arrived since the last proposal reach either threshold.
+    pub fn is_triggered(&self) -> bool {
+        let (min_transactions, min_priority_fees) = (self.min_transactions(), self.min_priority_fees());
+        (min_transactions > 0 && self.num_transactions.load(Ordering::Relaxed) >= min_transactions)
+            || (min_priority_fees > 0 && self.priority_fees.load(Ordering::Relaxed) >= min_priority_fees)
+    }
+
+    /// Records a transaction that was added to the ready queue, and wakes up the batch proposer.
+    pub fn record_transaction(&self, priority_fee: u64) {
+        self.num_transactions.fetch_add(1, Ordering::Relaxed);
+        self.total_transactions.fetch_add(1, Ordering::Relaxed);
+        self.priority_fees.fetch_add(priority_fee, Ordering::Relaxed);
+        self.notify.notify_one();
+    }
+
+    /// Resets the transactions that arrived, once a batch has been proposed.
+    pub fn reset(&self) {
+        self.num_transactions.store(0, Ordering::Relaxed);
+        self.priority_fees.store(0, Ordering::Relaxed);
+    }
+
+    /// Waits until an arriving transaction triggers a proposal while `can_propose` holds,
+    /// or until the given maximum delay elapses, whichever is first.
+    ///
+    /// Note: The trigger only fires upon an arrival, so that a proposal that is not ready yet
+    /// is retried on the next arrival or after the maximum delay, rather than in a busy loop.
+    pub async fn wait(&self, max_delay: Duration, can_propose: impl Fn() -> bool) {
+        let _ = tokio::time::timeout(max_delay, async {
+            loop {
+                self.notify.notified().await;
+                if self.is_triggered() && can_propose() {
+                    return;
+                }
+            }
+        })
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+    use tokio::time::Instant;
+
+    const MAX_DELAY: Duration = Duration::from_secs(5);
+
+    #[tokio::test]
+    async fn test_proposal_trigger_transactions() {
+        let trigger = Arc::new(ProposalTrigger::default());
+        trigger.set_thresholds(2, 0);
+
+        // A single transaction does not reach the threshold.
+        trigger.record_transaction(0);
+        assert!(!trigger.is_triggered());
+
+        // The second arrival wakes up the proposer well before the maximum delay.
+        let trigger_ = trigger.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            trigger_.record_transaction(0);
+        });
+        let start = Instant::now();
+        trigger.wait(MAX_DELAY, || true).await;
+        assert!(trigger.is_triggered());
+        assert!(start.elapsed() < MAX_DELAY);
+
+        // Resetting clears the arrivals.
+        trigger.reset();
+        assert!(!trigger.is_triggered());
+    }
+
+    #[tokio::test]
+    async fn test_proposal_trigger_priority_fees() {
+        let trigger = ProposalTrigger::default();
+        trigger.set_thresholds(0, 1_000);
+        assert!(trigger.is_enabled());
+
+        trigger.record_transaction(400);
+        assert!(!trigger.is_triggered());
+        trigger.record_transaction(600);
+        assert!(trigger.is_triggered());
+    }
+
+    #[tokio::test]
+    async fn test_proposal_trigger_max_delay() {
+        let trigger = ProposalTrigger::default();
+        // While disabled, arrivals never trigger a proposal.
+        assert!(!trigger.is_enabled());
+        trigger.record_transaction(1_000);
+        assert!(!trigger.is_triggered());
+
+        // The proposer waits out the maximum delay.
+        let max_delay = Duration::from_millis(100);
+        let start = Instant::now();
+        trigger.wait(max_delay, || true).await;
+        assert!(start.elapsed() >= max_delay);
+    }
+}
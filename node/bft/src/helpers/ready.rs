@@ -117,6 +117,11 @@ impl<N: Network> Ready<N> {
         is_new
     }
 
+    /// Removes the specified transmission from the ready queue, and returns it, if it exists.
+    pub fn remove(&self, transmission_id: impl Into<TransmissionID<N>>) -> Option<Transmission<N>> {
+        self.transmissions.write().shift_remove(&transmission_id.into())
+    }
+
     /// Removes up to the specified number of transmissions and returns them.
     pub fn drain(&self, num_transmissions: usize) -> IndexMap<TransmissionID<N>, Transmission<N>> {
         // Acquire the write lock.
@@ -197,6 +202,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ready_remove() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+
+        // Initialize the ready queue.
+        let ready = Ready::<CurrentNetwork>::new();
+
+        // Insert the commitments.
+        let commitment_1 = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        let commitment_2 = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        let solution_1 = Transmission::Solution(data(rng));
+        let solution_2 = Transmission::Solution(data(rng));
+        assert!(ready.insert(commitment_1, solution_1.clone()));
+        assert!(ready.insert(commitment_2, solution_2.clone()));
+
+        // Remove the first commitment.
+        assert_eq!(ready.remove(commitment_1), Some(solution_1));
+        assert_eq!(ready.remove(commitment_1), None);
+        assert!(!ready.contains(commitment_1));
+
+        // Check that the order of the remaining transmissions is preserved.
+        assert_eq!(ready.drain(2), vec![(commitment_2, solution_2)].into_iter().collect::<IndexMap<_, _>>());
+        assert!(ready.is_empty());
+    }
+
     #[test]
     fn test_ready_duplicate() {
         use rand::RngCore;
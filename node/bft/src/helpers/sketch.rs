@@ -0,0 +1,190 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{events::SketchCell, helpers::sha256d_to_u128};
+use snarkvm::{
+    ledger::narwhal::TransmissionID,
+    prelude::{Network, ToBytes},
+};
+
+use anyhow::{ensure, Result};
+
+/// An invertible Bloom lookup table over the short IDs of transmission IDs.
+///
+/// Two validators can reconcile their ready queues by exchanging sketches: subtracting the
+/// sketch of a peer from the local sketch, and decoding the result, yields the short IDs that
+/// only one side has, as long as the number of differences is small relative to the number of cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sketch {
+    /// The cells of the sketch.
+    cells: Vec<SketchCell>,
+}
+
+impl Sketch {
+    /// The number of cells each key is inserted into.
+    const NUM_HASHES: usize = 3;
+
+    /// Initializes a new, empty sketch with the given number of cells.
+    pub fn new(num_cells: usize) -> Result<Self> {
+        Self::from_cells(vec![SketchCell::default(); num_cells])
+    }
+
+    /// Initializes a sketch from the given cells.
+    pub fn from_cells(cells: Vec<SketchCell>) -> Result<Self> {
+        // Ensure the cells can be split into one sub-table per hash function.
+        ensure!(!cells.is_empty(), "A sketch must contain at least one cell");
+        ensure!(cells.len() % Self::NUM_HASHES == 0, "The number of sketch cells must be a multiple of 3");
+        Ok(Self { cells })
+    }
+
+    /// Returns the short ID of the given transmission ID.
+    pub fn short_id<N: Network>(transmission_id: TransmissionID<N>) -> Result<u64> {
+        Ok(sha256d_to_u128(&transmission_id.to_bytes_le()?) as u64)
+    }
+
+    /// Returns the cells of the sketch.
+    pub fn cells(&self) -> &[SketchCell] {
+        &self.cells
+    }
+
+    /// Returns the cells of the sketch, consuming the sketch.
+    pub fn into_cells(self) -> Vec<SketchCell> {
+        self.cells
+    }
+
+    /// Inserts the given short ID into the sketch.
+    pub fn insert(&mut self, key: u64) {
+        self.update(key, 1);
+    }
+
+    /// Subtracts the given sketch from this sketch.
+    pub fn subtract(&mut self, other: &Self) -> Result<()> {
+        ensure!(self.cells.len() == other.cells.len(), "Cannot subtract sketches of different sizes");
+        for (cell, other) in self.cells.iter_mut().zip(&other.cells) {
+            cell.count = cell.count.wrapping_sub(other.count);
+            cell.key_sum ^= other.key_sum;
+            cell.hash_sum ^= other.hash_sum;
+        }
+        Ok(())
+    }
+
+    /// Decodes the difference stored in this sketch, returning the short IDs that were
+    /// only in the local sketch, and the short IDs that were only in the subtracted sketch.
+    /// Returns `None` if the sketch holds too many differences to be decoded.
+    pub fn decode(mut self) -> Option<(Vec<u64>, Vec<u64>)> {
+        let (mut local, mut remote) = (Vec::new(), Vec::new());
+        // Peel the pure cells, until none are left.
+        // Note: The number of decoded keys is bounded, to guard against malformed sketches.
+        'peel: while local.len() + remote.len() <= self.cells.len() {
+            for index in 0..self.cells.len() {
+                let cell = self.cells[index];
+                if (cell.count == 1 || cell.count == -1) && cell.hash_sum == Self::checksum(cell.key_sum) {
+                    match cell.count {
+                        1 => local.push(cell.key_sum),
+                        _ => remote.push(cell.key_sum),
+                    }
+                    self.update(cell.key_sum, -cell.count);
+                    continue 'peel;
+                }
+            }
+            break;
+        }
+        // The sketch is fully decoded if every cell is empty.
+        match self.cells.iter().all(|cell| *cell == SketchCell::default()) {
+            true => Some((local, remote)),
+            false => None,
+        }
+    }
+
+    /// Adds the given delta to the cells of the given short ID.
+    fn update(&mut self, key: u64, delta: i32) {
+        let checksum = Self::checksum(key);
+        let size = self.cells.len() / Self::NUM_HASHES;
+        // Insert the key into one cell of each sub-table, so its cells are always distinct.
+        for i in 0..Self::NUM_HASHES {
+            let index = i * size + (Self::mix(key, i as u64) % size as u64) as usize;
+            let cell = &mut self.cells[index];
+            cell.count = cell.count.wrapping_add(delta);
+            cell.key_sum ^= key;
+            cell.hash_sum ^= checksum;
+        }
+    }
+
+    /// Returns the checksum of the given short ID.
+    fn checksum(key: u64) -> u64 {
+        Self::mix(key, Self::NUM_HASHES as u64)
+    }
+
+    /// Returns the SplitMix64 hash of the given short ID, using the given seed.
+    fn mix(key: u64, seed: u64) -> u64 {
+        let mut z = key.wrapping_add(seed.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Uniform};
+
+    #[test]
+    fn test_sketch_reconciliation() {
+        let rng = &mut TestRng::default();
+
+        // Sample the shared keys, and the keys that only one side has.
+        let shared = (0..500).map(|_| u64::rand(rng)).collect::<Vec<_>>();
+        let local_only = (0..20).map(|_| u64::rand(rng)).collect::<Vec<_>>();
+        let remote_only = (0..15).map(|_| u64::rand(rng)).collect::<Vec<_>>();
+
+        // Construct the sketches.
+        let mut local = Sketch::new(150).unwrap();
+        let mut remote = Sketch::new(150).unwrap();
+        for key in shared.iter().chain(&local_only) {
+            local.insert(*key);
+        }
+        for key in shared.iter().chain(&remote_only) {
+            remote.insert(*key);
+        }
+
+        // Ensure the differences are recovered.
+        local.subtract(&remote).unwrap();
+        let (mut decoded_local, mut decoded_remote) = local.decode().unwrap();
+        let (mut expected_local, mut expected_remote) = (local_only, remote_only);
+        decoded_local.sort_unstable();
+        decoded_remote.sort_unstable();
+        expected_local.sort_unstable();
+        expected_remote.sort_unstable();
+        assert_eq!(decoded_local, expected_local);
+        assert_eq!(decoded_remote, expected_remote);
+    }
+
+    #[test]
+    fn test_sketch_decode_failure() {
+        let rng = &mut TestRng::default();
+
+        // Insert more keys than the sketch can decode.
+        let mut sketch = Sketch::new(30).unwrap();
+        for _ in 0..100 {
+            sketch.insert(u64::rand(rng));
+        }
+        assert!(sketch.decode().is_none());
+
+        // Ensure malformed sketches are rejected.
+        assert!(Sketch::new(0).is_err());
+        assert!(Sketch::new(10).is_err());
+        assert!(Sketch::new(30).unwrap().subtract(&Sketch::new(60).unwrap()).is_err());
+    }
+}
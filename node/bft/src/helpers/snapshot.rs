@@ -0,0 +1,147 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::DAG;
+use snarkvm::{
+    console::types::Field,
+    prelude::{FromBytes, Network, ToBytes},
+};
+
+use anyhow::{Context, Result};
+use indexmap::IndexSet;
+use std::{io, path::Path};
+
+/// A snapshot of the DAG and the BFT bookkeeping, from which a restarting validator resumes ordering.
+#[derive(Clone, Debug)]
+pub struct DagSnapshot<N: Network> {
+    /// The latest block height in the ledger when the snapshot was taken.
+    height: u32,
+    /// The DAG.
+    dag: DAG<N>,
+    /// The last election certificate IDs.
+    last_election_certificate_ids: IndexSet<Field<N>>,
+}
+
+impl<N: Network> DagSnapshot<N> {
+    /// The name of the snapshot file, which is stored inside the ledger directory.
+    pub const FILE_NAME: &'static str = "bft-dag-snapshot";
+    /// The version of the snapshot format.
+    const VERSION: u8 = 1;
+
+    /// Initializes a new snapshot.
+    pub fn new(height: u32, dag: DAG<N>, last_election_certificate_ids: IndexSet<Field<N>>) -> Self {
+        Self { height, dag, last_election_certificate_ids }
+    }
+
+    /// Returns the latest block height in the ledger when the snapshot was taken.
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the DAG.
+    pub const fn dag(&self) -> &DAG<N> {
+        &self.dag
+    }
+
+    /// Returns the last election certificate IDs.
+    pub const fn last_election_certificate_ids(&self) -> &IndexSet<Field<N>> {
+        &self.last_election_certificate_ids
+    }
+
+    /// Loads the snapshot from the given path, returning `None` if it does not exist.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read the snapshot at '{}'", path.display()))?;
+        let snapshot = Self::read_le(&bytes[..])
+            .with_context(|| format!("Failed to deserialize the snapshot at '{}'", path.display()))?;
+        Ok(Some(snapshot))
+    }
+
+    /// Stores the snapshot at the given path.
+    /// Note: The snapshot is written to a temporary file first, so that a crash never leaves a partial snapshot.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, self.to_bytes_le()?)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> ToBytes for DagSnapshot<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        Self::VERSION.write_le(&mut writer)?;
+        self.height.write_le(&mut writer)?;
+        self.dag.write_le(&mut writer)?;
+        u32::try_from(self.last_election_certificate_ids.len()).map_err(io::Error::other)?.write_le(&mut writer)?;
+        for certificate_id in &self.last_election_certificate_ids {
+            certificate_id.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for DagSnapshot<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let version = u8::read_le(&mut reader)?;
+        if version != Self::VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid snapshot version {version}")));
+        }
+        let height = u32::read_le(&mut reader)?;
+        let dag = DAG::read_le(&mut reader)?;
+        let num_certificate_ids = u32::read_le(&mut reader)?;
+        let last_election_certificate_ids =
+            (0..num_certificate_ids).map(|_| Field::read_le(&mut reader)).collect::<io::Result<IndexSet<_>>>()?;
+        Ok(Self { height, dag, last_election_certificate_ids })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        prelude::{narwhal::batch_certificate::test_helpers::sample_batch_certificate_for_round, Testnet3},
+        utilities::{TestRng, Uniform},
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_snapshot_save_and_load() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-dag-snapshot-{}", u64::rand(rng)));
+
+        // Ensure a missing snapshot is not an error.
+        assert!(DagSnapshot::<CurrentNetwork>::load(&path).unwrap().is_none());
+
+        // Store a snapshot.
+        let mut dag = DAG::new();
+        dag.insert(sample_batch_certificate_for_round(3, rng));
+        let election_certificate_ids = [Field::rand(rng)].into_iter().collect::<IndexSet<_>>();
+        DagSnapshot::<CurrentNetwork>::new(5, dag.clone(), election_certificate_ids.clone()).save(&path).unwrap();
+
+        // Ensure the snapshot is loaded.
+        let snapshot = DagSnapshot::<CurrentNetwork>::load(&path).unwrap().unwrap();
+        assert_eq!(snapshot.height(), 5);
+        assert_eq!(snapshot.dag().graph(), dag.graph());
+        assert_eq!(snapshot.last_election_certificate_ids(), &election_certificate_ids);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
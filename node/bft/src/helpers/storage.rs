@@ -13,11 +13,12 @@
 // limitations under the License.
 
 use crate::helpers::{check_timestamp_for_liveness, fmt_id};
-use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_bft_ledger_service::{CommitteeSource, LedgerService};
 use snarkos_node_bft_storage_service::StorageService;
 use snarkvm::{
     ledger::{
-        block::Block,
+        authority::Authority,
+        block::{Block, ConfirmedTransaction},
         narwhal::{BatchCertificate, BatchHeader, Transmission, TransmissionID},
     },
     prelude::{anyhow, bail, cfg_iter, ensure, Address, Field, Network, Result},
@@ -427,6 +428,11 @@ impl<N: Network> Storage<N> {
     /// - The previous certificates reached the quorum threshold (2f+1).
     /// - The timestamps from the signers are all within the allowed time range.
     /// - The signers have reached the quorum threshold (2f+1).
+    ///
+    /// Note: The signatures are verified one at a time. An Aleo signature carries its challenge rather than
+    /// its commitment, and the challenge is checked against a hash of the recomputed commitment, so the
+    /// signatures cannot be folded into a single multi-scalar multiplication; nor are they BLS signatures,
+    /// which could be aggregated.
     pub fn check_certificate(
         &self,
         certificate: &BatchCertificate<N>,
@@ -609,8 +615,30 @@ impl<N: Network> Storage<N> {
         }
     }
 
+    /// Syncs the batch certificates in the block with storage.
+    pub(crate) fn sync_certificates_with_block(&self, block: &Block<N>) {
+        // If the block authority is a beacon, there are no certificates to sync.
+        let Authority::Quorum(subdag) = block.authority() else {
+            return;
+        };
+        // Index the transactions in the block by their unconfirmed transaction ID, once for all certificates.
+        // Note: The unconfirmed transactions are only reconstructed for the transactions missing from storage.
+        let mut block_transactions = cfg_iter!(block.transactions())
+            .filter_map(|tx| tx.to_unconfirmed_transaction_id().map(|transaction_id| (transaction_id, tx)).ok())
+            .collect::<HashMap<_, _>>();
+        // Iterate over the certificates.
+        for certificate in subdag.values().flatten() {
+            self.sync_certificate_with_block(block, certificate, &mut block_transactions);
+        }
+    }
+
     /// Syncs the batch certificate with the block.
-    pub(crate) fn sync_certificate_with_block(&self, block: &Block<N>, certificate: &BatchCertificate<N>) {
+    fn sync_certificate_with_block(
+        &self,
+        block: &Block<N>,
+        certificate: &BatchCertificate<N>,
+        block_transactions: &mut HashMap<N::TransactionID, &ConfirmedTransaction<N>>,
+    ) {
         // Skip if the certificate round is below the GC round.
         if certificate.round() <= self.gc_round() {
             return;
@@ -622,18 +650,13 @@ impl<N: Network> Storage<N> {
         // Retrieve the transmissions for the certificate.
         let mut missing_transmissions = HashMap::new();
 
-        // Reconstruct the unconfirmed transactions.
-        let mut unconfirmed_transactions = cfg_iter!(block.transactions())
-            .filter_map(|tx| tx.to_unconfirmed_transaction().map(|unconfirmed| (unconfirmed.id(), unconfirmed)).ok())
-            .collect::<HashMap<_, _>>();
-
         // Iterate over the transmission IDs.
         for transmission_id in certificate.transmission_ids() {
             // If the transmission ID already exists in the map, skip it.
             if missing_transmissions.contains_key(transmission_id) {
                 continue;
             }
-            // If the transmission ID exists in storage, skip it.
+            // If the transmission ID exists in storage, skip it, as the stored transmission is reused.
             if self.contains_transmission(*transmission_id) {
                 continue;
             }
@@ -657,12 +680,12 @@ impl<N: Network> Storage<N> {
                     };
                 }
                 TransmissionID::Transaction(transaction_id) => {
-                    // Retrieve the transaction.
-                    match unconfirmed_transactions.remove(transaction_id) {
+                    // Reconstruct the unconfirmed transaction from the block.
+                    match block_transactions.remove(transaction_id).map(|tx| tx.to_unconfirmed_transaction()) {
                         // Insert the transaction.
-                        Some(transaction) => missing_transmissions.insert(*transmission_id, transaction.into()),
+                        Some(Ok(transaction)) => missing_transmissions.insert(*transmission_id, transaction.into()),
                         // Otherwise, try to load the unconfirmed transaction from the ledger.
-                        None => match self.ledger.get_unconfirmed_transaction(*transaction_id) {
+                        _ => match self.ledger.get_unconfirmed_transaction(*transaction_id) {
                             // Insert the transaction.
                             Ok(transaction) => missing_transmissions.insert(*transmission_id, transaction.into()),
                             Err(_) => {
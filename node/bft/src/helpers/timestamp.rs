@@ -12,20 +12,38 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::MAX_TIMESTAMP_DELTA_IN_SECS;
+use crate::{MAX_CLOCK_ADJUSTMENT_IN_SECS, MAX_TIMESTAMP_DELTA_IN_SECS};
+use snarkos_node_clock as clock;
 use snarkvm::prelude::{bail, Result};
 
-use time::OffsetDateTime;
+use std::sync::atomic::{AtomicI64, Ordering};
 
-/// Returns the current UTC epoch timestamp.
+/// The estimated offset of the network time from the local clock, in seconds, if the node adjusts for clock skew.
+static CLOCK_OFFSET_IN_SECS: AtomicI64 = AtomicI64::new(0);
+
+/// Returns the current UTC epoch timestamp, as given by the clock of the node.
 pub fn now() -> i64 {
-    OffsetDateTime::now_utc().unix_timestamp()
+    clock::unix_timestamp()
+}
+
+/// Sets the estimated offset of the network time from the local clock, in seconds.
+///
+/// A local clock that is behind the network would reject the timestamps of honest validators as too far in the
+/// future, so a positive offset widens the tolerance of the liveness check, up to `MAX_CLOCK_ADJUSTMENT_IN_SECS`.
+/// The tolerance is never narrowed, so an inaccurate estimate cannot cause honest timestamps to be rejected.
+pub fn set_clock_offset(offset_in_secs: i64) {
+    CLOCK_OFFSET_IN_SECS.store(offset_in_secs.clamp(0, MAX_CLOCK_ADJUSTMENT_IN_SECS), Ordering::Relaxed);
+}
+
+/// Returns the adjustment to the tolerance of the liveness check, in seconds.
+pub fn clock_offset() -> i64 {
+    CLOCK_OFFSET_IN_SECS.load(Ordering::Relaxed)
 }
 
 /// Sanity checks the timestamp for liveness.
 pub fn check_timestamp_for_liveness(timestamp: i64) -> Result<()> {
     // Ensure the timestamp is within range.
-    if timestamp > (now() + MAX_TIMESTAMP_DELTA_IN_SECS) {
+    if timestamp > (now() + MAX_TIMESTAMP_DELTA_IN_SECS + clock_offset()) {
         bail!("Timestamp {timestamp} is too far in the future")
     }
     // TODO (howardwu): Ensure the timestamp is after the previous timestamp. (Needs Bullshark committee)
@@ -36,6 +54,22 @@ pub fn check_timestamp_for_liveness(timestamp: i64) -> Result<()> {
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_check_timestamp_for_liveness_follows_the_clock() {
+        let timestamp = now() + MAX_TIMESTAMP_DELTA_IN_SECS + MAX_CLOCK_ADJUSTMENT_IN_SECS + 60;
+        assert!(check_timestamp_for_liveness(timestamp).is_err());
+        // Once the clock catches up, the timestamp is no longer too far in the future.
+        clock::advance(Duration::from_secs((MAX_CLOCK_ADJUSTMENT_IN_SECS + 60) as u64)).await;
+        check_timestamp_for_liveness(timestamp).unwrap();
+    }
+}
+
 #[cfg(test)]
 mod prop_tests {
     use super::*;
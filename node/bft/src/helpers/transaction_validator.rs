@@ -0,0 +1,245 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft_ledger_service::LedgerService;
+use snarkvm::{
+    console::program::ProgramID,
+    ledger::narwhal::Data,
+    prelude::{block::Transaction, Network, ToBytes},
+};
+
+use anyhow::{anyhow, bail, Result};
+use std::{collections::HashSet, fmt, sync::Arc};
+
+#[cfg(feature = "metrics")]
+const STAGE_LABEL: &str = "stage";
+
+/// A local policy hook, which a validator applies to the unconfirmed transactions before its workers batch them.
+pub trait TransactionPolicy<N: Network>: Send + Sync {
+    /// Returns the name of the policy, which labels its rejects in the metrics.
+    fn name(&self) -> &'static str;
+
+    /// Returns an error if the given transaction violates the policy.
+    fn check(&self, transaction: &Transaction<N>) -> Result<()>;
+}
+
+/// A policy that rejects the deployments of, and the executions that call into, the given programs.
+#[derive(Clone, Debug)]
+pub struct ProgramDenyList<N: Network> {
+    /// The denied program IDs.
+    program_ids: HashSet<ProgramID<N>>,
+}
+
+impl<N: Network> ProgramDenyList<N> {
+    /// Initializes a new deny-list from the given program IDs.
+    pub fn new(program_ids: impl IntoIterator<Item = ProgramID<N>>) -> Self {
+        Self { program_ids: program_ids.into_iter().collect() }
+    }
+}
+
+impl<N: Network> TransactionPolicy<N> for ProgramDenyList<N> {
+    fn name(&self) -> &'static str {
+        "program_deny_list"
+    }
+
+    fn check(&self, transaction: &Transaction<N>) -> Result<()> {
+        // Check the deployed program.
+        if let Some(deployment) = transaction.deployment() {
+            if self.program_ids.contains(deployment.program_id()) {
+                bail!("Program '{}' is denied", deployment.program_id());
+            }
+        }
+        // Check the programs called by the transitions.
+        if let Some(transition) = transaction.transitions().find(|t| self.program_ids.contains(t.program_id())) {
+            bail!("Program '{}' is denied", transition.program_id());
+        }
+        Ok(())
+    }
+}
+
+/// A policy that rejects the transactions whose serialized size exceeds the given maximum.
+#[derive(Copy, Clone, Debug)]
+pub struct MaxTransactionSize {
+    /// The maximum size of a transaction, in bytes.
+    max_size_in_bytes: usize,
+}
+
+impl MaxTransactionSize {
+    /// Initializes a new policy with the given maximum size, in bytes.
+    pub const fn new(max_size_in_bytes: usize) -> Self {
+        Self { max_size_in_bytes }
+    }
+}
+
+impl<N: Network> TransactionPolicy<N> for MaxTransactionSize {
+    fn name(&self) -> &'static str {
+        "max_transaction_size"
+    }
+
+    fn check(&self, transaction: &Transaction<N>) -> Result<()> {
+        let size = transaction.to_bytes_le()?.len();
+        if size > self.max_size_in_bytes {
+            bail!("Transaction is {size} bytes, exceeding the maximum of {} bytes", self.max_size_in_bytes);
+        }
+        Ok(())
+    }
+}
+
+/// The pipeline that a worker runs on each unconfirmed transaction before adding it to its ready queue.
+///
+/// The stages run in order, and the first failing stage rejects the transaction:
+///  1. `basic` - the ledger checks that the transaction is well-formed, unique, and correctly funded.
+///  2. `fee` - the priority fee must meet the local minimum, if one is set.
+///  3. the policy hooks, in the order they were added, each labeled by its own name.
+///
+/// Note: The pipeline only applies to the transactions this validator batches itself;
+/// the batches proposed by other validators are validated by the ledger alone.
+#[derive(Clone)]
+pub struct TransactionValidator<N: Network> {
+    /// The minimum priority fee, in microcredits.
+    minimum_priority_fee: u64,
+    /// The policy hooks.
+    policies: Vec<Arc<dyn TransactionPolicy<N>>>,
+}
+
+impl<N: Network> Default for TransactionValidator<N> {
+    /// Initializes a pipeline with the basic stage only.
+    fn default() -> Self {
+        Self { minimum_priority_fee: 0, policies: Vec::new() }
+    }
+}
+
+impl<N: Network> fmt::Debug for TransactionValidator<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionValidator")
+            .field("minimum_priority_fee", &self.minimum_priority_fee)
+            .field("policies", &self.policies.iter().map(|policy| policy.name()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<N: Network> TransactionValidator<N> {
+    /// Sets the minimum priority fee, in microcredits.
+    pub fn with_minimum_priority_fee(mut self, minimum_priority_fee: u64) -> Self {
+        self.minimum_priority_fee = minimum_priority_fee;
+        self
+    }
+
+    /// Appends the given policy hook to the pipeline.
+    pub fn with_policy(mut self, policy: impl TransactionPolicy<N> + 'static) -> Self {
+        self.policies.push(Arc::new(policy));
+        self
+    }
+
+    /// Returns the minimum priority fee, in microcredits.
+    pub const fn minimum_priority_fee(&self) -> u64 {
+        self.minimum_priority_fee
+    }
+
+    /// Returns the names of the policy hooks, in order.
+    pub fn policy_names(&self) -> impl '_ + Iterator<Item = &'static str> {
+        self.policies.iter().map(|policy| policy.name())
+    }
+
+    /// Returns `true` if the pipeline only consists of the basic stage.
+    pub fn is_basic(&self) -> bool {
+        self.minimum_priority_fee == 0 && self.policies.is_empty()
+    }
+
+    /// Runs the pipeline on the given transaction, returning an error if any stage rejects it.
+    pub async fn validate(
+        &self,
+        ledger: &Arc<dyn LedgerService<N>>,
+        transaction_id: N::TransactionID,
+        transaction: Data<Transaction<N>>,
+    ) -> Result<()> {
+        // If there are no local stages, skip deserializing the transaction, and let the ledger check it as is.
+        if self.is_basic() {
+            return ledger.check_transaction_basic(transaction_id, transaction).await.map_err(|e| reject("basic", e));
+        }
+        // Deserialize the transaction once, for all of the stages.
+        let transaction = transaction.deserialize().await.map_err(|e| reject("basic", e))?;
+        // Check that the transaction is well-formed, unique, and correctly funded.
+        ledger
+            .check_transaction_basic(transaction_id, Data::Object(transaction.clone()))
+            .await
+            .map_err(|e| reject("basic", e))?;
+        // Check the priority fee.
+        let priority_fee =
+            transaction.fee_transition().and_then(|fee| fee.priority_amount().ok()).map_or(0, |amount| *amount);
+        if priority_fee < self.minimum_priority_fee {
+            let error = anyhow!("Priority fee of {priority_fee} is below the minimum of {}", self.minimum_priority_fee);
+            return Err(reject("fee", error));
+        }
+        // Run the policy hooks.
+        for policy in &self.policies {
+            policy.check(&transaction).map_err(|e| reject(policy.name(), e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Records a reject at the given stage, and returns the error to surface.
+fn reject(stage: &'static str, error: anyhow::Error) -> anyhow::Error {
+    #[cfg(feature = "metrics")]
+    metrics::increment_labeled_counter(metrics::bft::TRANSACTIONS_REJECTED, STAGE_LABEL, stage);
+    error.context(format!("Rejected at the '{stage}' stage"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::Testnet3;
+
+    use std::str::FromStr;
+
+    type CurrentNetwork = Testnet3;
+
+    /// A policy that rejects every transaction.
+    struct DenyAll;
+
+    impl<N: Network> TransactionPolicy<N> for DenyAll {
+        fn name(&self) -> &'static str {
+            "deny_all"
+        }
+
+        fn check(&self, _transaction: &Transaction<N>) -> Result<()> {
+            bail!("Denied")
+        }
+    }
+
+    #[test]
+    fn test_pipeline_configuration() {
+        // The default pipeline only consists of the basic stage.
+        let validator = TransactionValidator::<CurrentNetwork>::default();
+        assert!(validator.is_basic());
+        assert_eq!(validator.minimum_priority_fee(), 0);
+        assert_eq!(validator.policy_names().count(), 0);
+
+        // The policy hooks run in the order they were added.
+        let program_id = ProgramID::<CurrentNetwork>::from_str("foo.aleo").unwrap();
+        let validator = TransactionValidator::<CurrentNetwork>::default()
+            .with_minimum_priority_fee(10)
+            .with_policy(ProgramDenyList::new([program_id]))
+            .with_policy(MaxTransactionSize::new(1024))
+            .with_policy(DenyAll);
+        assert!(!validator.is_basic());
+        assert_eq!(validator.minimum_priority_fee(), 10);
+        assert_eq!(validator.policy_names().collect::<Vec<_>>(), vec![
+            "program_deny_list",
+            "max_transaction_size",
+            "deny_all"
+        ]);
+    }
+}
@@ -0,0 +1,432 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{committee::Committee, Address, FromBytes, Network, PrivateKey, Signature, ToBytes};
+
+use anyhow::{bail, ensure, Context, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use rand::{CryptoRng, Rng};
+use std::{fmt, io, path::PathBuf};
+
+/// A breaking upgrade of the protocol, to the given protocol version from the given block height.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ProtocolUpgrade {
+    /// The protocol version.
+    pub version: u32,
+    /// The block height from which the protocol version is active.
+    pub activation_height: u32,
+}
+
+impl ProtocolUpgrade {
+    /// The domain of the signed messages, which keeps them distinct from the other messages signed by validators.
+    const DOMAIN: &'static [u8] = b"snarkos.upgrade_signal";
+
+    /// Initializes a new protocol upgrade.
+    pub const fn new(version: u32, activation_height: u32) -> Self {
+        Self { version, activation_height }
+    }
+
+    /// Signs the readiness for the upgrade with the given private key.
+    pub fn sign<N: Network, R: Rng + CryptoRng>(
+        &self,
+        private_key: &PrivateKey<N>,
+        rng: &mut R,
+    ) -> Result<Signature<N>> {
+        Signature::sign_bytes(private_key, &self.message::<N>()?, rng)
+    }
+
+    /// Returns `true` if the given signature of the readiness for the upgrade is from the given address.
+    pub fn verify<N: Network>(&self, address: &Address<N>, signature: &Signature<N>) -> bool {
+        self.message::<N>().map_or(false, |message| signature.verify_bytes(address, &message))
+    }
+
+    /// Returns the signed message, which is bound to the network.
+    fn message<N: Network>(&self) -> Result<Vec<u8>> {
+        Ok([N::ID.to_bytes_le()?, Self::DOMAIN.to_vec(), self.to_bytes_le()?].concat())
+    }
+}
+
+impl ToBytes for ProtocolUpgrade {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.version.write_le(&mut writer)?;
+        self.activation_height.write_le(&mut writer)
+    }
+}
+
+impl FromBytes for ProtocolUpgrade {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        Ok(Self::new(u32::read_le(&mut reader)?, u32::read_le(&mut reader)?))
+    }
+}
+
+/// The final decision on a protocol upgrade, taken once the ledger reaches its activation height.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UpgradeDecision {
+    /// A quorum of the committee was ready for the upgrade, which is active from its activation height.
+    Activated,
+    /// A quorum of the committee was not ready for the upgrade by its activation height, which never activates.
+    Refused,
+}
+
+impl fmt::Display for UpgradeDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Activated => write!(f, "activated"),
+            Self::Refused => write!(f, "refused"),
+        }
+    }
+}
+
+impl fmt::Display for ProtocolUpgrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "protocol version {} at block {}", self.version, self.activation_height)
+    }
+}
+
+/// The readiness of a committee for a protocol upgrade.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpgradeReadiness<N: Network> {
+    /// The protocol upgrade.
+    pub upgrade: ProtocolUpgrade,
+    /// The committee members that signaled their readiness for the upgrade.
+    pub signers: Vec<Address<N>>,
+    /// The stake of the committee members that signaled their readiness.
+    pub signaled_stake: u64,
+    /// The total stake of the committee.
+    pub total_stake: u64,
+    /// The stake required for the upgrade to activate.
+    pub quorum_threshold: u64,
+    /// The decision on the upgrade, if the ledger reached its activation height.
+    pub decision: Option<UpgradeDecision>,
+}
+
+impl<N: Network> UpgradeReadiness<N> {
+    /// Returns `true` if a quorum of the committee signaled its readiness for the upgrade.
+    pub const fn is_ready(&self) -> bool {
+        self.signaled_stake >= self.quorum_threshold
+    }
+
+    /// Returns the block height from which the upgrade is active, if it activated.
+    pub fn activated_at(&self) -> Option<u32> {
+        match self.decision {
+            Some(UpgradeDecision::Activated) => Some(self.upgrade.activation_height),
+            _ => None,
+        }
+    }
+}
+
+/// The tracker of the readiness of the committee for the protocol upgrades.
+///
+/// Each validator signals the single upgrade it is ready for, and signs its signal, so that the readiness of the
+/// committee does not rest on out-of-band coordination. The decision on an upgrade is taken once, when the ledger
+/// first reaches its activation height: it activates if a quorum of the committee signaled its readiness by then,
+/// and is refused for good otherwise, so that it never activates at a later, arbitrary height. The signals and the
+/// decisions are persisted, so that a restarting validator neither forgets them nor decides again.
+#[derive(Debug)]
+pub struct UpgradeTracker<N: Network> {
+    /// The upgrade this validator is ready for, if any.
+    local: RwLock<Option<ProtocolUpgrade>>,
+    /// The latest signal of each validator, i.e. the upgrade it is ready for, and its signature.
+    signals: RwLock<IndexMap<Address<N>, (ProtocolUpgrade, Signature<N>)>>,
+    /// The decision on each upgrade whose activation height was reached.
+    decisions: RwLock<IndexMap<ProtocolUpgrade, UpgradeDecision>>,
+    /// The path at which the signals and the decisions are persisted, if any.
+    path: RwLock<Option<PathBuf>>,
+}
+
+impl<N: Network> Default for UpgradeTracker<N> {
+    fn default() -> Self {
+        Self {
+            local: Default::default(),
+            signals: Default::default(),
+            decisions: Default::default(),
+            path: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> UpgradeTracker<N> {
+    /// The name of the file of the signals and the decisions, which is stored inside the ledger directory.
+    pub const FILE_NAME: &'static str = "upgrade-signals";
+    /// The version of the file format.
+    const VERSION: u8 = 1;
+
+    /// Persists the signals and the decisions at the given path, and loads the ones stored at it.
+    pub fn open(&self, path: PathBuf) -> Result<()> {
+        if path.exists() {
+            let bytes =
+                std::fs::read(&path).with_context(|| format!("Failed to read the upgrades at '{}'", path.display()))?;
+            let (signals, decisions) = Self::read_state(&bytes[..])
+                .with_context(|| format!("Failed to deserialize the upgrades at '{}'", path.display()))?;
+            self.signals.write().extend(signals);
+            self.decisions.write().extend(decisions);
+        }
+        let mut current = self.path.write();
+        if current.is_some() {
+            bail!("The upgrade path is already set");
+        }
+        *current = Some(path);
+        Ok(())
+    }
+
+    /// Returns the upgrade this validator is ready for, if any.
+    pub fn local(&self) -> Option<ProtocolUpgrade> {
+        *self.local.read()
+    }
+
+    /// Sets the upgrade this validator is ready for.
+    pub fn set_local(&self, upgrade: ProtocolUpgrade) {
+        *self.local.write() = Some(upgrade);
+    }
+
+    /// Returns the signal of the given validator, if it signaled its readiness for an upgrade.
+    pub fn get_signal(&self, address: &Address<N>) -> Option<(ProtocolUpgrade, Signature<N>)> {
+        self.signals.read().get(address).copied()
+    }
+
+    /// Records the signal of the given validator, after checking its signature.
+    /// Returns `true` if the validator signaled a different upgrade than before.
+    pub fn insert_signal(
+        &self,
+        address: Address<N>,
+        upgrade: ProtocolUpgrade,
+        signature: Signature<N>,
+    ) -> Result<bool> {
+        // Skip the signal if it was already recorded.
+        let previous = self.get_signal(&address);
+        if previous == Some((upgrade, signature)) {
+            return Ok(false);
+        }
+        ensure!(upgrade.verify(&address, &signature), "Invalid signature for the upgrade to {upgrade}");
+        self.signals.write().insert(address, (upgrade, signature));
+        self.persist()?;
+        Ok(previous.map(|(upgrade, _)| upgrade) != Some(upgrade))
+    }
+
+    /// Returns the decision on the given upgrade, if the ledger reached its activation height.
+    pub fn decision(&self, upgrade: &ProtocolUpgrade) -> Option<UpgradeDecision> {
+        self.decisions.read().get(upgrade).copied()
+    }
+
+    /// Returns the upgrades that this validator or any other validator signaled, by activation height.
+    pub fn upgrades(&self) -> Vec<ProtocolUpgrade> {
+        let mut upgrades = self.local().into_iter().collect::<Vec<_>>();
+        for (upgrade, _) in self.signals.read().values() {
+            if !upgrades.contains(upgrade) {
+                upgrades.push(*upgrade);
+            }
+        }
+        upgrades.sort_unstable_by_key(|upgrade| (upgrade.activation_height, upgrade.version));
+        upgrades
+    }
+
+    /// Returns the readiness of the given committee for the given upgrade.
+    pub fn readiness(&self, upgrade: ProtocolUpgrade, committee: &Committee<N>) -> UpgradeReadiness<N> {
+        let signers = self
+            .signals
+            .read()
+            .iter()
+            .filter(|(address, (signaled, _))| *signaled == upgrade && committee.is_committee_member(**address))
+            .map(|(address, _)| *address)
+            .collect::<Vec<_>>();
+        UpgradeReadiness {
+            upgrade,
+            signaled_stake: signers.iter().map(|address| committee.get_stake(*address)).sum(),
+            signers,
+            total_stake: committee.total_stake(),
+            quorum_threshold: committee.quorum_threshold(),
+            decision: self.decision(&upgrade),
+        }
+    }
+
+    /// Decides on the given upgrade, if the given block height reached its activation height, and it is undecided.
+    /// Returns the decision if it was taken now, or `None` if the upgrade is pending or was decided before.
+    pub fn decide(
+        &self,
+        upgrade: ProtocolUpgrade,
+        height: u32,
+        committee: &Committee<N>,
+    ) -> Result<Option<UpgradeDecision>> {
+        if height < upgrade.activation_height || self.decision(&upgrade).is_some() {
+            return Ok(None);
+        }
+        let decision = match self.readiness(upgrade, committee).is_ready() {
+            true => UpgradeDecision::Activated,
+            false => UpgradeDecision::Refused,
+        };
+        self.decisions.write().insert(upgrade, decision);
+        self.persist()?;
+        Ok(Some(decision))
+    }
+
+    /// Stores the signals and the decisions, if a path is set.
+    /// Note: The file is written to a temporary file first, so that a crash never leaves a partial file.
+    fn persist(&self) -> Result<()> {
+        let Some(path) = self.path.read().clone() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, self.write_state()?)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// Serializes the signals and the decisions.
+    fn write_state(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        Self::VERSION.write_le(&mut bytes)?;
+        let signals = self.signals.read();
+        u32::try_from(signals.len()).map_err(io::Error::other)?.write_le(&mut bytes)?;
+        for (address, (upgrade, signature)) in signals.iter() {
+            address.write_le(&mut bytes)?;
+            upgrade.write_le(&mut bytes)?;
+            signature.write_le(&mut bytes)?;
+        }
+        let decisions = self.decisions.read();
+        u32::try_from(decisions.len()).map_err(io::Error::other)?.write_le(&mut bytes)?;
+        for (upgrade, decision) in decisions.iter() {
+            upgrade.write_le(&mut bytes)?;
+            (*decision == UpgradeDecision::Activated).write_le(&mut bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Deserializes the signals and the decisions.
+    #[allow(clippy::type_complexity)]
+    fn read_state<R: io::Read>(
+        mut reader: R,
+    ) -> io::Result<(
+        IndexMap<Address<N>, (ProtocolUpgrade, Signature<N>)>,
+        IndexMap<ProtocolUpgrade, UpgradeDecision>,
+    )> {
+        let version = u8::read_le(&mut reader)?;
+        if version != Self::VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid upgrades version {version}")));
+        }
+        let num_signals = u32::read_le(&mut reader)?;
+        let signals = (0..num_signals)
+            .map(|_| {
+                let address = Address::read_le(&mut reader)?;
+                let upgrade = ProtocolUpgrade::read_le(&mut reader)?;
+                Ok((address, (upgrade, Signature::read_le(&mut reader)?)))
+            })
+            .collect::<io::Result<IndexMap<_, _>>>()?;
+        let num_decisions = u32::read_le(&mut reader)?;
+        let decisions = (0..num_decisions)
+            .map(|_| {
+                let upgrade = ProtocolUpgrade::read_le(&mut reader)?;
+                let decision = match bool::read_le(&mut reader)? {
+                    true => UpgradeDecision::Activated,
+                    false => UpgradeDecision::Refused,
+                };
+                Ok((upgrade, decision))
+            })
+            .collect::<io::Result<IndexMap<_, _>>>()?;
+        Ok((signals, decisions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        prelude::Testnet3,
+        utilities::{TestRng, Uniform},
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    /// Samples a committee of four members with equal stake, and their private keys.
+    #[allow(clippy::type_complexity)]
+    fn sample_committee(
+        rng: &mut TestRng,
+    ) -> (Vec<PrivateKey<CurrentNetwork>>, Vec<Address<CurrentNetwork>>, Committee<CurrentNetwork>) {
+        let private_keys = (0..4).map(|_| PrivateKey::<CurrentNetwork>::new(rng).unwrap()).collect::<Vec<_>>();
+        let addresses = private_keys.iter().map(|key| Address::try_from(key).unwrap()).collect::<Vec<_>>();
+        let members = addresses.iter().map(|address| (*address, (1_000_000_000_000, true))).collect::<IndexMap<_, _>>();
+        (private_keys, addresses, Committee::new(0, members).unwrap())
+    }
+
+    #[test]
+    fn test_upgrade_activation() {
+        let rng = &mut TestRng::default();
+        let (private_keys, addresses, committee) = sample_committee(rng);
+
+        let tracker = UpgradeTracker::<CurrentNetwork>::default();
+        let upgrade = ProtocolUpgrade::new(2, 100);
+        tracker.set_local(upgrade);
+        assert_eq!(tracker.upgrades(), vec![upgrade]);
+
+        // Ensure a signal with a signature from another validator is rejected.
+        let signature = upgrade.sign(&private_keys[0], rng).unwrap();
+        assert!(tracker.insert_signal(addresses[1], upgrade, signature).is_err());
+        assert!(!upgrade.verify(&addresses[0], &ProtocolUpgrade::new(3, 100).sign(&private_keys[0], rng).unwrap()));
+
+        // Ensure a validator that signals another upgrade does not count towards the quorum.
+        let other = ProtocolUpgrade::new(2, 200);
+        assert!(tracker.insert_signal(addresses[3], other, other.sign(&private_keys[3], rng).unwrap()).unwrap());
+        assert_eq!(tracker.upgrades(), vec![upgrade, other]);
+
+        // Ensure the upgrade activates at its activation height once three of four members signaled.
+        for (private_key, address) in private_keys.iter().zip(&addresses).take(3) {
+            assert!(tracker.insert_signal(*address, upgrade, upgrade.sign(private_key, rng).unwrap()).unwrap());
+        }
+        assert_eq!(tracker.decide(upgrade, 99, &committee).unwrap(), None);
+        assert_eq!(tracker.decide(upgrade, 102, &committee).unwrap(), Some(UpgradeDecision::Activated));
+        let readiness = tracker.readiness(upgrade, &committee);
+        assert!(readiness.is_ready());
+        assert_eq!(readiness.signers.len(), 3);
+        // Ensure the upgrade is active from its activation height, regardless of the height it was decided at.
+        assert_eq!(readiness.activated_at(), Some(100));
+        // Ensure the decision is taken only once.
+        assert_eq!(tracker.decide(upgrade, 103, &committee).unwrap(), None);
+        assert_eq!(tracker.decision(&upgrade), Some(UpgradeDecision::Activated));
+    }
+
+    #[test]
+    fn test_upgrade_refusal_is_final() {
+        let rng = &mut TestRng::default();
+        let (private_keys, addresses, committee) = sample_committee(rng);
+        let path = std::env::temp_dir().join(format!("snarkos-upgrade-signals-{}", u64::rand(rng)));
+
+        let tracker = UpgradeTracker::<CurrentNetwork>::default();
+        tracker.open(path.clone()).unwrap();
+        let upgrade = ProtocolUpgrade::new(2, 100);
+
+        // Ensure two of four members are not a quorum, so the upgrade is refused at its activation height.
+        for (private_key, address) in private_keys.iter().zip(&addresses).take(2) {
+            assert!(tracker.insert_signal(*address, upgrade, upgrade.sign(private_key, rng).unwrap()).unwrap());
+        }
+        assert_eq!(tracker.decide(upgrade, 100, &committee).unwrap(), Some(UpgradeDecision::Refused));
+        assert_eq!(tracker.readiness(upgrade, &committee).activated_at(), None);
+
+        // Ensure a late quorum does not activate the refused upgrade.
+        assert!(tracker.insert_signal(addresses[2], upgrade, upgrade.sign(&private_keys[2], rng).unwrap()).unwrap());
+        assert!(tracker.readiness(upgrade, &committee).is_ready());
+        assert_eq!(tracker.decide(upgrade, 101, &committee).unwrap(), None);
+        assert_eq!(tracker.decision(&upgrade), Some(UpgradeDecision::Refused));
+
+        // Ensure the signals and the decision survive a restart.
+        let restarted = UpgradeTracker::<CurrentNetwork>::default();
+        restarted.open(path.clone()).unwrap();
+        assert_eq!(restarted.readiness(upgrade, &committee).signers.len(), 3);
+        assert_eq!(restarted.decision(&upgrade), Some(UpgradeDecision::Refused));
+        assert_eq!(restarted.decide(upgrade, 102, &committee).unwrap(), None);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -55,12 +55,26 @@ pub const MAX_GC_ROUNDS: u64 = 50; // rounds
 pub const MAX_BATCH_DELAY_IN_MS: u64 = 2500; // ms
 /// The maximum number of seconds allowed for the leader to send their certificate.
 pub const MAX_LEADER_CERTIFICATE_DELAY_IN_SECS: i64 = 2 * MAX_BATCH_DELAY_IN_MS as i64 / 1000; // seconds
+/// The number of seconds a round may go without advancing, before it is reported as stalled.
+pub const MAX_ROUND_STALL_IN_SECS: i64 = 2 * MAX_LEADER_CERTIFICATE_DELAY_IN_SECS; // seconds
+/// The number of rounds the last committed round may lag behind the current round, before it is reported.
+pub const MAX_COMMIT_LAG_IN_ROUNDS: u64 = 10; // rounds
+/// The number of seconds the connected stake may stay below the quorum threshold, before the primary halts.
+pub const MAX_QUORUM_LOSS_IN_SECS: u64 = 10; // seconds
 /// The maximum number of seconds before the timestamp is considered expired.
 pub const MAX_TIMESTAMP_DELTA_IN_SECS: i64 = 10; // seconds
+/// The maximum adjustment to the timestamp tolerance, for a local clock that is behind the network time.
+pub const MAX_CLOCK_ADJUSTMENT_IN_SECS: i64 = 60; // seconds
 /// The maximum number of transmissions allowed in a batch.
 pub const MAX_TRANSMISSIONS_PER_BATCH: usize = 250; // transmissions
 /// The maximum number of transmissions allowed in a worker ping.
 pub const MAX_TRANSMISSIONS_PER_WORKER_PING: usize = MAX_TRANSMISSIONS_PER_BATCH / 10; // transmissions
+/// The maximum number of transmission IDs that a worker sends to a newly-connected validator.
+pub const MAX_TRANSMISSIONS_PER_INVENTORY: usize = MAX_TRANSMISSIONS_PER_BATCH; // transmissions
+/// The number of cells in a transmission sketch, which bounds the number of differences it can reconcile.
+pub const TRANSMISSION_SKETCH_CELLS: usize = 6 * MAX_TRANSMISSIONS_PER_WORKER_PING; // cells
+/// The default maximum number of transmissions that are checked concurrently when validating a batch.
+pub const MAX_CONCURRENT_TRANSMISSION_CHECKS: usize = 8; // transmissions
 /// The maximum number of workers that can be spawned.
 pub const MAX_WORKERS: u8 = 1; // workers
 
@@ -68,6 +82,16 @@ pub const MAX_WORKERS: u8 = 1; // workers
 pub const PRIMARY_PING_IN_MS: u64 = 4 * MAX_BATCH_DELAY_IN_MS; // ms
 /// The frequency at which each worker broadcasts a ping to every other node.
 pub const WORKER_PING_IN_MS: u64 = 4 * MAX_BATCH_DELAY_IN_MS; // ms
+/// The frequency at which each worker broadcasts a transmission sketch to every other node.
+pub const TRANSMISSION_SKETCH_IN_MS: u64 = 2 * WORKER_PING_IN_MS; // ms
+/// The frequency at which each primary broadcasts its signature over the latest checkpoint, until it is certified.
+pub const CHECKPOINT_SIGNATURE_IN_MS: u64 = 2 * PRIMARY_PING_IN_MS; // ms
+/// The frequency at which each primary broadcasts its readiness for a protocol upgrade, and checks its activation.
+pub const UPGRADE_SIGNAL_IN_MS: u64 = 2 * PRIMARY_PING_IN_MS; // ms
+/// The frequency at which the primary tunes the batch size and delay, if batch tuning is enabled.
+pub const BATCH_TUNING_IN_MS: u64 = 4 * MAX_BATCH_DELAY_IN_MS; // ms
+/// The frequency at which the BFT stores a snapshot of the DAG, if new rounds were committed.
+pub const DAG_SNAPSHOT_IN_SECS: u64 = 30; // seconds
 
 /// A helper macro to spawn a blocking task.
 #[macro_export]
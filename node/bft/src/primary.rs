@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::{
-    events::{BatchPropose, BatchSignature, Event},
+    events::{BatchPropose, BatchSignature, Event, UpgradeSignal},
     helpers::{
         assign_to_worker,
         assign_to_workers,
@@ -22,25 +22,38 @@ use crate::{
         init_worker_channels,
         now,
         BFTSender,
+        BatchTuner,
+        BatchTuningBounds,
         PrimaryReceiver,
         PrimarySender,
         Proposal,
+        ProposalGate,
+        ProposalHold,
+        ProposalTrigger,
+        ProtocolUpgrade,
         Storage,
+        TransactionValidator,
+        UpgradeDecision,
+        UpgradeTracker,
     },
     spawn_blocking,
     Gateway,
     Sync,
     Transport,
     Worker,
-    MAX_BATCH_DELAY_IN_MS,
-    MAX_TRANSMISSIONS_PER_BATCH,
+    BATCH_TUNING_IN_MS,
+    MAX_CONCURRENT_TRANSMISSION_CHECKS,
+    MAX_QUORUM_LOSS_IN_SECS,
     MAX_WORKERS,
     PRIMARY_PING_IN_MS,
+    TRANSMISSION_SKETCH_IN_MS,
+    UPGRADE_SIGNAL_IN_MS,
     WORKER_PING_IN_MS,
 };
 use snarkos_account::Account;
 use snarkos_node_bft_events::PrimaryPing;
-use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_bft_ledger_service::{CommitteeSource, LedgerService};
+use snarkos_node_clock as clock;
 use snarkvm::{
     console::{
         account::Signature,
@@ -63,8 +76,11 @@ use std::{
     collections::{HashMap, HashSet},
     future::Future,
     net::SocketAddr,
-    sync::Arc,
-    time::Duration,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 use tokio::{
     sync::{Mutex as TMutex, OnceCell},
@@ -96,6 +112,18 @@ pub struct Primary<N: Network> {
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The lock for propose_batch.
     propose_lock: Arc<TMutex<u64>>,
+    /// The maximum number of transmissions that are checked concurrently when validating a batch.
+    max_concurrent_checks: Arc<AtomicUsize>,
+    /// The gate of the batch proposals, on which the reasons to hold off proposing batches are placed.
+    proposal_gate: Arc<ProposalGate>,
+    /// The pipeline that the workers apply to the unconfirmed transactions before batching them.
+    transaction_validator: Arc<RwLock<TransactionValidator<N>>>,
+    /// The trigger with which the workers wake up the batch proposer, as the unconfirmed transactions arrive.
+    proposal_trigger: Arc<ProposalTrigger>,
+    /// The controller of the batch size and delay.
+    batch_tuner: Arc<BatchTuner>,
+    /// The tracker of the readiness of the committee for the protocol upgrades.
+    upgrades: Arc<UpgradeTracker<N>>,
 }
 
 impl<N: Network> Primary<N> {
@@ -124,6 +152,12 @@ impl<N: Network> Primary<N> {
             signed_proposals: Default::default(),
             handles: Default::default(),
             propose_lock: Default::default(),
+            max_concurrent_checks: Arc::new(AtomicUsize::new(MAX_CONCURRENT_TRANSMISSION_CHECKS)),
+            proposal_gate: Default::default(),
+            transaction_validator: Default::default(),
+            proposal_trigger: Default::default(),
+            batch_tuner: Default::default(),
+            upgrades: Default::default(),
         })
     }
 
@@ -136,6 +170,10 @@ impl<N: Network> Primary<N> {
     ) -> Result<()> {
         info!("Starting the primary instance of the memory pool...");
 
+        // Retrieve the genesis committee, from which the committees of the BFT are bootstrapped.
+        let genesis_committee = self.ledger.genesis_committee()?;
+        debug!("Bootstrapping the committee from the genesis block ({} members)", genesis_committee.num_members());
+
         // Set the BFT sender.
         if let Some(bft_sender) = &bft_sender {
             // Set the BFT sender in the primary.
@@ -157,6 +195,8 @@ impl<N: Network> Primary<N> {
                 self.storage.clone(),
                 self.ledger.clone(),
                 self.proposed_batch.clone(),
+                self.transaction_validator.clone(),
+                self.proposal_trigger.clone(),
             )?;
             // Run the worker instance.
             worker.run(rx_worker);
@@ -191,6 +231,11 @@ impl<N: Network> Primary<N> {
         &self.gateway
     }
 
+    /// Returns the sync module.
+    pub const fn sync(&self) -> &Sync<N> {
+        &self.sync
+    }
+
     /// Returns the storage.
     pub const fn storage(&self) -> &Storage<N> {
         &self.storage
@@ -215,6 +260,135 @@ impl<N: Network> Primary<N> {
     pub fn proposed_batch(&self) -> &Arc<ProposedBatch<N>> {
         &self.proposed_batch
     }
+
+    /// Returns the maximum number of transmissions that are checked concurrently when validating a batch.
+    pub fn max_concurrent_checks(&self) -> usize {
+        self.max_concurrent_checks.load(Ordering::Relaxed)
+    }
+
+    /// Sets the maximum number of transmissions that are checked concurrently when validating a batch.
+    pub fn set_max_concurrent_checks(&self, max_concurrent_checks: usize) -> Result<()> {
+        ensure!(max_concurrent_checks > 0, "The maximum number of concurrent transmission checks must be nonzero");
+        self.max_concurrent_checks.store(max_concurrent_checks, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns the pipeline that the workers apply to the unconfirmed transactions before batching them.
+    pub fn transaction_validator(&self) -> TransactionValidator<N> {
+        self.transaction_validator.read().clone()
+    }
+
+    /// Sets the pipeline that the workers apply to the unconfirmed transactions before batching them.
+    /// Note: The pipeline does not apply to the batches proposed by other validators.
+    pub fn set_transaction_validator(&self, transaction_validator: TransactionValidator<N>) {
+        info!("Validating the unconfirmed transactions with {transaction_validator:?}");
+        *self.transaction_validator.write() = transaction_validator;
+    }
+
+    /// Returns the trigger with which the workers wake up the batch proposer, as the unconfirmed transactions arrive.
+    pub fn proposal_trigger(&self) -> &ProposalTrigger {
+        &self.proposal_trigger
+    }
+
+    /// Sets the thresholds of the unconfirmed transactions that arrived since the last proposal, upon which a batch
+    /// is proposed without waiting out the maximum batch delay. A threshold of `0` is disabled.
+    pub fn set_proposal_trigger(&self, min_transactions: usize, min_priority_fees: u64) {
+        info!(
+            "Triggering batch proposals at {min_transactions} transactions, or {min_priority_fees} microcredits \
+             in priority fees (0 is disabled)"
+        );
+        self.proposal_trigger.set_thresholds(min_transactions, min_priority_fees);
+    }
+
+    /// Returns the tracker of the readiness of the committee for the protocol upgrades.
+    pub fn upgrades(&self) -> &Arc<UpgradeTracker<N>> {
+        &self.upgrades
+    }
+
+    /// Signals to the committee that this validator is ready for the given protocol upgrade.
+    /// The upgrade activates at its activation height if a quorum of the committee is ready by then, and never
+    /// otherwise.
+    pub fn signal_upgrade(&self, upgrade: ProtocolUpgrade) {
+        info!("Signaling the readiness for the {upgrade} to the committee");
+        self.upgrades.set_local(upgrade);
+    }
+
+    /// Returns the controller of the batch size and delay.
+    pub fn batch_tuner(&self) -> &BatchTuner {
+        &self.batch_tuner
+    }
+
+    /// Tunes the batch size and delay at runtime within the given bounds, based on the transaction arrival rate
+    /// and the ordering latency, rather than using the static values.
+    pub fn set_batch_tuning(&self, bounds: BatchTuningBounds) {
+        info!(
+            "Tuning the batch size within {}..={} transmissions, and the batch delay within {}..={} ms",
+            bounds.min_batch_size, bounds.max_batch_size, bounds.min_batch_delay_ms, bounds.max_batch_delay_ms
+        );
+        self.batch_tuner.enable(bounds);
+    }
+
+    /// Returns `true` if the primary holds off proposing batches for the given reason.
+    pub fn is_holding_proposals(&self, hold: ProposalHold) -> bool {
+        self.proposal_gate.is_held(hold)
+    }
+
+    /// Holds off proposing batches for the given reason, until `release_proposals` is called for it.
+    /// The reasons are independent, e.g. regaining quorum never resumes a batch proposal paused by the operator.
+    /// Note: While held, the primary continues to sign the batches of other validators and to commit the rounds.
+    pub fn hold_proposals(&self, hold: ProposalHold) {
+        if self.proposal_gate.hold(hold) {
+            match hold {
+                ProposalHold::Paused => info!("Paused proposing batches"),
+                ProposalHold::CatchingUp => info!("Holding off proposing batches until the ledger catches up"),
+                ProposalHold::QuorumLost => {
+                    warn!("The connected stake is below the quorum threshold, halting batch proposals")
+                }
+                ProposalHold::AwaitingActivation => info!("Holding off proposing batches until the BFT activates"),
+            }
+        }
+        #[cfg(feature = "metrics")]
+        metrics::gauge(Self::proposal_hold_gauge(hold), 1.0);
+    }
+
+    /// Releases the hold on proposing batches for the given reason.
+    pub fn release_proposals(&self, hold: ProposalHold) {
+        if self.proposal_gate.lift(hold) {
+            match hold {
+                ProposalHold::Paused => info!("Resumed proposing batches"),
+                ProposalHold::CatchingUp => info!("Caught up with the network, starting to propose batches"),
+                ProposalHold::QuorumLost => {
+                    info!("The connected stake regained the quorum threshold, resuming batch proposals")
+                }
+                ProposalHold::AwaitingActivation => info!("The BFT is active, starting to propose batches"),
+            }
+        }
+        #[cfg(feature = "metrics")]
+        metrics::gauge(Self::proposal_hold_gauge(hold), 0.0);
+    }
+
+    /// Returns the name of the gauge that reports whether the given hold is placed.
+    #[cfg(feature = "metrics")]
+    const fn proposal_hold_gauge(hold: ProposalHold) -> &'static str {
+        match hold {
+            ProposalHold::Paused => metrics::bft::PAUSED,
+            ProposalHold::CatchingUp => metrics::bft::CATCHING_UP,
+            ProposalHold::QuorumLost => metrics::bft::QUORUM_LOST,
+            ProposalHold::AwaitingActivation => metrics::bft::AWAITING_ACTIVATION,
+        }
+    }
+
+    /// Returns `true` if the connected committee members, including this node, hold the quorum threshold of stake
+    /// in the committee lookback of the given round.
+    fn is_connected_quorum(&self, round: u64) -> Result<bool> {
+        // Retrieve the committee to check against.
+        let committee_lookback = self.ledger.get_committee_lookback_for_round(round)?;
+        // Retrieve the connected validator addresses.
+        let mut connected_validators = self.gateway.connected_addresses();
+        // Append the primary to the set.
+        connected_validators.insert(self.gateway.account().address());
+        Ok(committee_lookback.is_quorum_threshold_reached(&connected_validators))
+    }
 }
 
 impl<N: Network> Primary<N> {
@@ -273,6 +447,17 @@ impl<N: Network> Primary<N> {
         // This function isn't re-entrant.
         let mut lock_guard = self.propose_lock.lock().await;
 
+        // If the primary holds off proposing batches, return early.
+        if let Some(hold) = self.proposal_gate.blocking_hold() {
+            debug!("Skipping batch proposal {}", format!("({})", hold.reason()).dimmed());
+            return Ok(());
+        }
+        // If the ledger diverged from the committee members, refrain from proposing batches on top of it.
+        if self.sync.is_diverged() {
+            warn!("Skipping batch proposal {}", "(the ledger conflicts with the committee)".dimmed());
+            return Ok(());
+        }
+
         // Check if the proposed batch has expired, and clear it if it has expired.
         if let Err(e) = self.check_proposed_batch_for_expiration().await {
             warn!("Failed to check the proposed batch for expiration - {e}");
@@ -333,22 +518,10 @@ impl<N: Network> Primary<N> {
         }
 
         // Check if the primary is connected to enough validators to reach quorum threshold.
-        {
-            // Retrieve the committee to check against.
-            let committee_lookback = self.ledger.get_committee_lookback_for_round(round)?;
-            // Retrieve the connected validator addresses.
-            let mut connected_validators = self.gateway.connected_addresses();
-            // Append the primary to the set.
-            connected_validators.insert(self.gateway.account().address());
-            // If quorum threshold is not reached, return early.
-            if !committee_lookback.is_quorum_threshold_reached(&connected_validators) {
-                debug!(
-                    "Primary is safely skipping a batch proposal {}",
-                    "(please connect to more validators)".dimmed()
-                );
-                trace!("Primary is connected to {} validators", connected_validators.len() - 1);
-                return Ok(());
-            }
+        if !self.is_connected_quorum(round)? {
+            debug!("Primary is safely skipping a batch proposal {}", "(please connect to more validators)".dimmed());
+            trace!("Primary is connected to {} validators", self.gateway.connected_addresses().len());
+            return Ok(());
         }
 
         // Compute the previous round.
@@ -382,42 +555,29 @@ impl<N: Network> Primary<N> {
         }
 
         // Determined the required number of transmissions per worker.
-        let num_transmissions_per_worker = MAX_TRANSMISSIONS_PER_BATCH / self.num_workers() as usize;
+        let num_transmissions_per_worker = (self.batch_tuner.batch_size() / self.num_workers() as usize).max(1);
         // Initialize the map of transmissions.
         let mut transmissions: IndexMap<_, _> = Default::default();
         // Initialize a tracker for the number of transactions.
         let mut num_transactions = 0;
         // Take the transmissions from the workers.
         for worker in self.workers.iter() {
-            for (id, transmission) in worker.drain(num_transmissions_per_worker) {
-                // Check if the ledger already contains the transmission.
-                if self.ledger.contains_transmission(&id).unwrap_or(true) {
-                    trace!("Proposing - Skipping transmission '{}' - Already in ledger", fmt_id(id));
-                    continue;
-                }
-                // Check the transmission is still valid.
-                match (id, transmission.clone()) {
-                    (TransmissionID::Solution(solution_id), Transmission::Solution(solution)) => {
-                        // Check if the solution is still valid.
-                        if let Err(e) = self.ledger.check_solution_basic(solution_id, solution).await {
-                            trace!("Proposing - Skipping solution '{}' - {e}", fmt_id(solution_id));
-                            continue;
-                        }
+            // Drain the transmissions that do not already exist in the ledger.
+            let candidates = worker
+                .drain(num_transmissions_per_worker)
+                .filter(|(id, _)| match self.ledger.contains_transmission(id).unwrap_or(true) {
+                    true => {
+                        trace!("Proposing - Skipping transmission '{}' - Already in ledger", fmt_id(id));
+                        false
                     }
-                    (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction)) => {
-                        // Check if the transaction is still valid.
-                        if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
-                            trace!("Proposing - Skipping transaction '{}' - {e}", fmt_id(transaction_id));
-                            continue;
-                        }
-                        // Increment the number of transactions.
-                        num_transactions += 1;
-                    }
-                    // Note: We explicitly forbid including ratifications,
-                    // as the protocol currently does not support ratifications.
-                    (TransmissionID::Ratification, Transmission::Ratification) => continue,
-                    // All other combinations are clearly invalid.
-                    _ => continue,
+                    false => true,
+                })
+                .collect::<Vec<_>>();
+            // Check the transmissions are still valid.
+            for (id, transmission) in worker.check_transmissions(candidates, self.max_concurrent_checks()).await {
+                // Increment the number of transactions.
+                if let TransmissionID::Transaction(..) = id {
+                    num_transactions += 1;
                 }
                 // Insert the transmission into the map.
                 transmissions.insert(id, transmission);
@@ -441,6 +601,8 @@ impl<N: Network> Primary<N> {
         }
 
         *lock_guard = round;
+        // Reset the transactions that arrived, as they are drained into this batch.
+        self.proposal_trigger.reset();
 
         /* Proceeding to sign & propose the batch. */
         info!("Proposing a batch with {} transmissions for round {round}...", transmissions.len());
@@ -569,6 +731,20 @@ impl<N: Network> Primary<N> {
             }
         }
 
+        // Check that the transmissions fetched from the peer are valid.
+        // Note: Transmissions held by a worker or in storage were checked when they were first received.
+        if let Some(worker) = self.workers.first() {
+            let candidates = transmissions
+                .iter()
+                .filter(|(id, _)| !self.workers.iter().any(|worker| worker.contains_transmission(**id)))
+                .map(|(id, transmission)| (*id, transmission.clone()))
+                .collect::<Vec<_>>();
+            if let Err(err) = worker.ensure_transmissions_are_valid(candidates, self.max_concurrent_checks()).await {
+                debug!("Batch propose from '{peer_ip}' contains an invalid transmission - {err}");
+                return Ok(());
+            }
+        }
+
         // Ensure the batch is for the current round.
         // This method must be called after fetching previous certificates (above),
         // and prior to checking the batch header (below).
@@ -827,6 +1003,7 @@ impl<N: Network> Primary<N> {
             mut rx_primary_ping,
             mut rx_unconfirmed_solution,
             mut rx_unconfirmed_transaction,
+            mut rx_upgrade_signal,
         } = primary_receiver;
 
         // Start the primary ping.
@@ -835,7 +1012,7 @@ impl<N: Network> Primary<N> {
             self.spawn(async move {
                 loop {
                     // Sleep briefly.
-                    tokio::time::sleep(Duration::from_millis(PRIMARY_PING_IN_MS)).await;
+                    clock::sleep(Duration::from_millis(PRIMARY_PING_IN_MS)).await;
 
                     // Retrieve the block locators.
                     let block_locators = match self_.sync.get_block_locators() {
@@ -970,7 +1147,7 @@ impl<N: Network> Primary<N> {
             let self_ = self.clone();
             self.spawn(async move {
                 loop {
-                    tokio::time::sleep(Duration::from_millis(WORKER_PING_IN_MS)).await;
+                    clock::sleep(Duration::from_millis(WORKER_PING_IN_MS)).await;
                     // If the primary is not synced, then do not broadcast the worker ping(s).
                     if !self_.sync.is_synced() {
                         trace!("Skipping worker ping(s) {}", "(node is syncing)".dimmed());
@@ -982,14 +1159,87 @@ impl<N: Network> Primary<N> {
                     }
                 }
             });
+
+            // Start the transmission sketch(es), to reconcile the ready queues with the other validators.
+            let self_ = self.clone();
+            self.spawn(async move {
+                loop {
+                    clock::sleep(Duration::from_millis(TRANSMISSION_SKETCH_IN_MS)).await;
+                    // If the primary is not synced, then do not broadcast the transmission sketch(es).
+                    if !self_.sync.is_synced() {
+                        trace!("Skipping transmission sketch(es) {}", "(node is syncing)".dimmed());
+                        continue;
+                    }
+                    // Broadcast the transmission sketch(es).
+                    for worker in self_.workers.iter() {
+                        worker.broadcast_sketch();
+                    }
+                }
+            });
         }
 
+        // Start the batch tuner, which adjusts the batch size and delay to the arrivals and the ordering latency.
+        let self_ = self.clone();
+        self.spawn(async move {
+            let mut last_total = self_.proposal_trigger.total_transactions();
+            loop {
+                clock::sleep(Duration::from_millis(BATCH_TUNING_IN_MS)).await;
+                let total = self_.proposal_trigger.total_transactions();
+                let num_arrivals = total.saturating_sub(last_total);
+                last_total = total;
+                if !self_.batch_tuner.is_enabled() {
+                    continue;
+                }
+                let num_unconfirmed = self_.num_unconfirmed_transmissions();
+                self_.batch_tuner.tune(num_arrivals, num_unconfirmed, Duration::from_millis(BATCH_TUNING_IN_MS));
+                debug!(
+                    "Tuned the batch size to {} transmissions, and the batch delay to {} ms",
+                    self_.batch_tuner.batch_size(),
+                    self_.batch_tuner.batch_delay_ms()
+                );
+                #[cfg(feature = "metrics")]
+                {
+                    metrics::gauge(metrics::bft::BATCH_SIZE, self_.batch_tuner.batch_size() as f64);
+                    metrics::gauge(metrics::bft::BATCH_DELAY, self_.batch_tuner.batch_delay_ms() as f64);
+                }
+            }
+        });
+
+        // Start the quorum monitor, which halts the batch proposals while the connected stake is below quorum.
+        let self_ = self.clone();
+        self.spawn(async move {
+            let mut below_quorum_since = None;
+            loop {
+                clock::sleep(Duration::from_secs(1)).await;
+                // If the primary is catching up, then it already waits for the connected stake to reach quorum.
+                if self_.is_holding_proposals(ProposalHold::CatchingUp) {
+                    continue;
+                }
+                match self_.is_connected_quorum(self_.current_round()) {
+                    Ok(true) => {
+                        below_quorum_since = None;
+                        self_.release_proposals(ProposalHold::QuorumLost);
+                    }
+                    // Halt once the connected stake stays below quorum, so that brief disconnections are tolerated.
+                    Ok(false) => {
+                        let since = *below_quorum_since.get_or_insert_with(Instant::now);
+                        if since.elapsed().as_secs() >= MAX_QUORUM_LOSS_IN_SECS {
+                            self_.hold_proposals(ProposalHold::QuorumLost);
+                        }
+                    }
+                    Err(e) => warn!("Failed to check the connected stake - {e}"),
+                }
+            }
+        });
+
         // Start the batch proposer.
         let self_ = self.clone();
         self.spawn(async move {
             loop {
-                // Sleep briefly, but longer than if there were no batch.
-                tokio::time::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                // Wait until the arriving transactions trigger a proposal, or until the maximum batch delay elapses.
+                // Note: While there is a proposed batch, the arrivals do not trigger, as the batch is only resent.
+                let max_batch_delay = self_.batch_tuner.batch_delay();
+                self_.proposal_trigger.wait(max_batch_delay, || self_.proposed_batch.read().is_none()).await;
                 // If the primary is not synced, then do not propose a batch.
                 if !self_.sync.is_synced() {
                     debug!("Skipping batch proposal {}", "(node is syncing)".dimmed());
@@ -1117,6 +1367,79 @@ impl<N: Network> Primary<N> {
                 });
             }
         });
+
+        // Process the upgrade signals.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some((peer_ip, upgrade_signal)) = rx_upgrade_signal.recv().await {
+                if let Err(error) = self_.process_upgrade_signal(peer_ip, upgrade_signal) {
+                    warn!("Failed to process an upgrade signal from '{peer_ip}' - {error}");
+                }
+            }
+        });
+
+        // Periodically signal the readiness for the protocol upgrade, and check whether it activates.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                clock::sleep(Duration::from_millis(UPGRADE_SIGNAL_IN_MS)).await;
+                if let Err(error) = self_.signal_and_activate_upgrade() {
+                    warn!("{error}");
+                }
+            }
+        });
+    }
+
+    /// Broadcasts the readiness of this validator for its protocol upgrade, if it has one, and decides on the
+    /// upgrade once the ledger reaches its activation height.
+    fn signal_and_activate_upgrade(&self) -> Result<()> {
+        let Some(upgrade) = self.upgrades.local() else {
+            return Ok(());
+        };
+        let committee = self.ledger.current_committee()?;
+        // Sign the readiness for the upgrade, if this node is a member of the committee.
+        let address = self.gateway.account().address();
+        if committee.is_committee_member(address) {
+            let signature = match self.upgrades.get_signal(&address) {
+                Some((signaled, signature)) if signaled == upgrade => signature,
+                _ => {
+                    let signature = upgrade.sign(self.gateway.account().private_key(), &mut rand::thread_rng())?;
+                    self.upgrades.insert_signal(address, upgrade, signature)?;
+                    signature
+                }
+            };
+            // Broadcast the signal to the other committee members.
+            let event = UpgradeSignal::new(upgrade.version, upgrade.activation_height, signature);
+            self.gateway.broadcast(Event::UpgradeSignal(event));
+        }
+        // Decide on the upgrade, if the ledger reached its activation height.
+        let height = self.ledger.latest_block_height();
+        match self.upgrades.decide(upgrade, height, &committee)? {
+            Some(UpgradeDecision::Activated) => info!("Activated the {upgrade}, as a quorum of the committee is ready"),
+            Some(UpgradeDecision::Refused) => {
+                warn!("Refused the {upgrade}, as a quorum of the committee was not ready by its activation height")
+            }
+            None => (),
+        }
+        Ok(())
+    }
+
+    /// Records the readiness of the given validator for a protocol upgrade.
+    fn process_upgrade_signal(&self, peer_ip: SocketAddr, event: UpgradeSignal<N>) -> Result<()> {
+        let UpgradeSignal { version, activation_height, signature } = event;
+        // Retrieve the address of the validator.
+        let Some(address) = self.gateway.resolver().get_address(peer_ip) else {
+            return Ok(());
+        };
+        // Ignore the signals of the peers that are not validators.
+        if !self.gateway.is_authorized_validator_address(address) {
+            return Ok(());
+        }
+        let upgrade = ProtocolUpgrade::new(version, activation_height);
+        if self.upgrades.insert_signal(address, upgrade, signature)? {
+            info!("Validator '{address}' is ready for the {upgrade}");
+        }
+        Ok(())
     }
 
     /// Checks if the proposed batch is expired, and clears the proposed batch if it has expired.
@@ -1606,6 +1929,8 @@ mod tests {
             primary.storage.clone(),
             primary.ledger.clone(),
             primary.proposed_batch.clone(),
+            primary.transaction_validator.clone(),
+            primary.proposal_trigger.clone(),
         )
         .unwrap()]);
         for a in accounts.iter() {
@@ -1800,6 +2125,38 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_propose_batch_while_held() {
+        for hold in ProposalHold::ALL {
+            let mut rng = TestRng::default();
+            let (primary, _) = primary_without_handlers(&mut rng).await;
+
+            // Store a transaction on one of the workers.
+            let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
+            primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+
+            // Ensure a primary that holds off proposing does not propose a batch.
+            primary.hold_proposals(hold);
+            assert!(primary.is_holding_proposals(hold));
+            assert!(primary.propose_batch().await.is_ok());
+            assert!(primary.proposed_batch.read().is_none());
+
+            // Ensure releasing the other holds does not release this one.
+            for other in ProposalHold::ALL.into_iter().filter(|other| *other != hold) {
+                primary.release_proposals(other);
+            }
+            assert!(primary.is_holding_proposals(hold));
+            assert!(primary.propose_batch().await.is_ok());
+            assert!(primary.proposed_batch.read().is_none());
+
+            // Ensure the primary proposes a batch once released.
+            primary.release_proposals(hold);
+            assert!(!primary.is_holding_proposals(hold));
+            assert!(primary.propose_batch().await.is_ok());
+            assert!(primary.proposed_batch.read().is_some());
+        }
+    }
+
     #[tokio::test]
     async fn test_propose_batch() {
         let mut rng = TestRng::default();
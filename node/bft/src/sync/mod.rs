@@ -13,23 +13,32 @@
 // limitations under the License.
 
 use crate::{
-    helpers::{fmt_id, BFTSender, Pending, Storage, SyncReceiver},
+    helpers::{find_block_hash_conflict, fmt_id, BFTSender, BlockHashConflict, ForkLog, Pending, Storage, SyncReceiver},
     spawn_blocking,
     Gateway,
     Transport,
+    CHECKPOINT_SIGNATURE_IN_MS,
     MAX_BATCH_DELAY_IN_MS,
     PRIMARY_PING_IN_MS,
 };
-use snarkos_node_bft_events::{CertificateRequest, CertificateResponse, Event};
+use snarkos_node_bft_events::{CertificateRequest, CertificateResponse, CheckpointSignature, Event};
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_sync::{locators::BlockLocators, BlockSync, BlockSyncMode};
+use snarkos_node_clock as clock;
+use snarkos_node_sync::{
+    locators::{BlockLocators, CommitteeCheckpoint},
+    BlockSync,
+    BlockSyncMode,
+    ChainPolicy,
+    CheckpointStore,
+};
 use snarkvm::{
-    console::{network::Network, types::Field},
-    ledger::{authority::Authority, block::Block, narwhal::BatchCertificate},
+    console::{account::Address, network::Network, types::Field},
+    ledger::{authority::Authority, block::Block, committee::Committee, narwhal::BatchCertificate},
 };
 
-use anyhow::{bail, Result};
-use parking_lot::Mutex;
+use anyhow::{bail, ensure, Result};
+use indexmap::IndexMap;
+use parking_lot::{Mutex, RwLock};
 use std::{future::Future, net::SocketAddr, sync::Arc};
 use tokio::{
     sync::{oneshot, Mutex as TMutex, OnceCell},
@@ -50,6 +59,10 @@ pub struct Sync<N: Network> {
     pending: Arc<Pending<Field<N>, BatchCertificate<N>>>,
     /// The BFT sender.
     bft_sender: Arc<OnceCell<BFTSender<N>>>,
+    /// The committee members whose block locators conflict with the ledger.
+    block_hash_conflicts: Arc<RwLock<IndexMap<Address<N>, BlockHashConflict<N>>>>,
+    /// The log of the recent block hash conflicts, including the resolved ones.
+    forks: Arc<ForkLog<N>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The sync lock.
@@ -69,6 +82,8 @@ impl<N: Network> Sync<N> {
             block_sync,
             pending: Default::default(),
             bft_sender: Default::default(),
+            block_hash_conflicts: Default::default(),
+            forks: Default::default(),
             handles: Default::default(),
             lock: Default::default(),
         }
@@ -93,7 +108,7 @@ impl<N: Network> Sync<N> {
         self.handles.lock().push(tokio::spawn(async move {
             loop {
                 // Sleep briefly to avoid triggering spam detection.
-                tokio::time::sleep(std::time::Duration::from_millis(PRIMARY_PING_IN_MS)).await;
+                clock::sleep(std::time::Duration::from_millis(PRIMARY_PING_IN_MS)).await;
                 // Perform the sync routine.
                 let communication = &self_.gateway;
                 // let communication = &node.router;
@@ -108,6 +123,7 @@ impl<N: Network> Sync<N> {
             mut rx_block_sync_update_peer_locators,
             mut rx_certificate_request,
             mut rx_certificate_response,
+            mut rx_checkpoint_signature,
         } = sync_receiver;
 
         // Process the block sync request to advance with sync blocks.
@@ -138,6 +154,7 @@ impl<N: Network> Sync<N> {
         self.spawn(async move {
             while let Some(peer_ip) = rx_block_sync_remove_peer.recv().await {
                 self_.block_sync.remove_peer(&peer_ip);
+                self_.block_hash_conflicts.write().retain(|_, conflict| conflict.peer_ip != peer_ip);
             }
         });
 
@@ -147,6 +164,8 @@ impl<N: Network> Sync<N> {
             while let Some((peer_ip, locators, callback)) = rx_block_sync_update_peer_locators.recv().await {
                 let self_clone = self_.clone();
                 tokio::spawn(async move {
+                    // Cross-check the block hashes of the peer against the ledger.
+                    self_clone.check_block_hashes(peer_ip, &locators);
                     // Update the peer locators.
                     let result = self_clone.block_sync.update_peer_locators(peer_ip, locators);
                     // Send the result to the callback.
@@ -171,6 +190,27 @@ impl<N: Network> Sync<N> {
             }
         });
 
+        // Process the checkpoint signatures.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some((peer_ip, checkpoint_signature)) = rx_checkpoint_signature.recv().await {
+                if let Err(error) = self_.process_checkpoint_signature(peer_ip, checkpoint_signature) {
+                    warn!("Failed to process a checkpoint signature from '{peer_ip}' - {error}");
+                }
+            }
+        });
+
+        // Periodically sign the latest checkpoint, until it is certified.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                clock::sleep(std::time::Duration::from_millis(CHECKPOINT_SIGNATURE_IN_MS)).await;
+                if let Err(error) = self_.sign_latest_checkpoint() {
+                    warn!("Failed to sign the latest checkpoint - {error}");
+                }
+            }
+        });
+
         Ok(())
     }
 }
@@ -204,16 +244,10 @@ impl<N: Network> Sync<N> {
         self.storage.sync_round_with_block(latest_block.round());
         // Iterate over the blocks.
         for block in &blocks {
-            // If the block authority is a subdag, then sync the batch certificates with the block.
-            if let Authority::Quorum(subdag) = block.authority() {
-                // Iterate over the certificates.
-                for certificate in subdag.values().flatten().cloned() {
-                    // Sync the batch certificate with the block.
-                    let storage = self.storage.clone();
-                    let block = block.clone();
-                    let _ = spawn_blocking!(Ok(storage.sync_certificate_with_block(&block, &certificate)));
-                }
-            }
+            // Sync the batch certificates with the block.
+            let storage = self.storage.clone();
+            let block = block.clone();
+            let _ = spawn_blocking!(Ok(storage.sync_certificates_with_block(&block)));
         }
 
         /* Sync the BFT DAG */
@@ -265,13 +299,12 @@ impl<N: Network> Sync<N> {
 
         // If the block authority is a subdag, then sync the batch certificates with the block.
         if let Authority::Quorum(subdag) = block.authority() {
+            // Sync the batch certificates with the block.
+            let storage = self.storage.clone();
+            let block_clone = block.clone();
+            let _ = spawn_blocking!(Ok(storage.sync_certificates_with_block(&block_clone)));
             // Iterate over the certificates.
             for certificate in subdag.values().flatten() {
-                // Sync the batch certificate with the block.
-                let storage = self.storage.clone();
-                let block_clone = block.clone();
-                let certificate_clone = certificate.clone();
-                let _ = spawn_blocking!(Ok(storage.sync_certificate_with_block(&block_clone, &certificate_clone)));
                 // If a BFT sender was provided, send the certificate to the BFT.
                 if let Some(bft_sender) = self.bft_sender.get() {
                     // Await the callback to continue.
@@ -301,6 +334,32 @@ impl<N: Network> Sync<N> {
         self.block_sync.is_block_synced()
     }
 
+    /// Returns the greatest block height that the committee members holding the quorum threshold of stake reached,
+    /// as reported by the block locators of the connected validators, and including this node.
+    /// Returns `None` if the connected committee members do not hold the quorum threshold of stake.
+    pub fn quorum_peer_height(&self, committee: &Committee<N>) -> Option<u32> {
+        // Retrieve the latest height of each connected committee member, including this node.
+        let resolver = self.gateway.resolver();
+        let heights = self
+            .block_sync
+            .peer_heights()
+            .into_iter()
+            .filter_map(|(peer_ip, height)| Some((resolver.get_address(peer_ip)?, height)))
+            .chain([(self.gateway.account().address(), self.ledger.latest_block_height())])
+            .filter(|(address, _)| committee.is_committee_member(*address))
+            .collect::<IndexMap<_, _>>();
+        // Sort the heights in descending order.
+        let mut heights =
+            heights.into_iter().map(|(address, height)| (height, committee.get_stake(address))).collect::<Vec<_>>();
+        heights.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+        // Find the greatest height, for which the committee members at or above it hold the quorum threshold of stake.
+        let mut stake = 0u64;
+        heights.into_iter().find_map(|(height, member_stake)| {
+            stake = stake.saturating_add(member_stake);
+            (stake >= committee.quorum_threshold()).then_some(height)
+        })
+    }
+
     /// Returns `true` if the node is in gateway mode.
     pub const fn is_gateway_mode(&self) -> bool {
         self.block_sync.mode().is_gateway()
@@ -310,6 +369,74 @@ impl<N: Network> Sync<N> {
     pub fn get_block_locators(&self) -> Result<BlockLocators<N>> {
         self.block_sync.get_block_locators()
     }
+
+    /// Returns the checkpoint certificates.
+    pub fn checkpoints(&self) -> &Arc<CheckpointStore<N>> {
+        self.block_sync.checkpoints()
+    }
+
+    /// Returns the chain policy.
+    pub fn chain_policy(&self) -> Arc<dyn ChainPolicy<N>> {
+        self.block_sync.chain_policy()
+    }
+
+    /// Sets the chain policy, with which the next block is accepted, and the chains of the peers are compared.
+    pub fn set_chain_policy(&self, policy: Arc<dyn ChainPolicy<N>>) {
+        self.block_sync.set_chain_policy(policy)
+    }
+
+    /// Returns the block hash conflicts reported by the connected committee members.
+    pub fn block_hash_conflicts(&self) -> Vec<BlockHashConflict<N>> {
+        self.block_hash_conflicts.read().values().cloned().collect()
+    }
+
+    /// Returns the log of the recent block hash conflicts, including the resolved ones.
+    pub fn forks(&self) -> &Arc<ForkLog<N>> {
+        &self.forks
+    }
+
+    /// Returns `true` if the committee members that report a conflicting block hash hold at least
+    /// the availability threshold of stake, in which case at least one honest validator disagrees with the ledger.
+    pub fn is_diverged(&self) -> bool {
+        let conflicts = self.block_hash_conflicts.read();
+        if conflicts.is_empty() {
+            return false;
+        }
+        let Ok(committee) = self.ledger.current_committee() else {
+            return false;
+        };
+        let conflicting_stake = conflicts.keys().map(|address| committee.get_stake(*address)).sum::<u64>();
+        conflicting_stake >= committee.availability_threshold()
+    }
+
+    /// Cross-checks the block hashes in the given block locators of a committee member against the ledger,
+    /// and records the conflict (or its resolution) for the committee member.
+    fn check_block_hashes(&self, peer_ip: SocketAddr, locators: &BlockLocators<N>) {
+        // Ensure the peer is a committee member.
+        let Some(address) = self.gateway.resolver().get_address(peer_ip) else {
+            return;
+        };
+        if !self.gateway.is_authorized_validator_address(address) {
+            return;
+        }
+        match find_block_hash_conflict(self.ledger.as_ref(), locators) {
+            Some((height, expected_hash, reported_hash)) => {
+                let conflict = BlockHashConflict { peer_ip, address, height, expected_hash, reported_hash };
+                // Alert once per conflicting committee member.
+                if self.block_hash_conflicts.write().insert(address, conflict.clone()).is_none() {
+                    error!("Block hash conflict - {conflict}");
+                    self.forks.record(clock::unix_timestamp(), conflict);
+                    #[cfg(feature = "metrics")]
+                    metrics::increment_counter(metrics::bft::BLOCK_HASH_CONFLICTS);
+                }
+            }
+            None => {
+                if self.block_hash_conflicts.write().shift_remove(&address).is_some() {
+                    info!("Validator '{address}' ('{peer_ip}') no longer reports a conflicting block hash");
+                }
+            }
+        }
+    }
 }
 
 // Methods to assist with fetching batch certificates from peers.
@@ -365,6 +492,77 @@ impl<N: Network> Sync<N> {
     }
 }
 
+// Methods to certify the checkpoints with the committee.
+impl<N: Network> Sync<N> {
+    /// Returns the checkpoint of the block at the given height in the ledger,
+    /// and the committee of the block round, which certifies the checkpoint.
+    fn checkpoint_from_ledger(&self, height: u32) -> Result<(CommitteeCheckpoint<N>, Committee<N>)> {
+        let block = self.ledger.get_block(height)?;
+        let previous_state_root = block.previous_state_root();
+        let checkpoint = CommitteeCheckpoint { height, block_hash: block.hash(), previous_state_root };
+        let committee = self.ledger.get_committee_for_round(block.round())?;
+        Ok((checkpoint, committee))
+    }
+
+    /// Signs the latest checkpoint in the ledger, if it is not certified yet, and broadcasts the signature.
+    fn sign_latest_checkpoint(&self) -> Result<()> {
+        // Retrieve the height of the latest checkpoint.
+        let Some(height) = CommitteeCheckpoint::<N>::latest_checkpoint_height(self.ledger.latest_block_height()) else {
+            return Ok(());
+        };
+        // Skip the checkpoint if it is already certified.
+        if self.checkpoints().contains(height) {
+            return Ok(());
+        }
+        let (checkpoint, committee) = self.checkpoint_from_ledger(height)?;
+        // Ensure this node is a member of the committee that certifies the checkpoint.
+        let account = self.gateway.account();
+        if !committee.is_committee_member(account.address()) {
+            return Ok(());
+        }
+        // Sign the checkpoint, and add the signature.
+        let signature = checkpoint.sign(account.private_key(), &mut rand::thread_rng())?;
+        if let Some(certificate) =
+            self.checkpoints().insert_signature(checkpoint, account.address(), signature, &committee)?
+        {
+            info!("Certified the checkpoint at block {height} ({} signatures)", certificate.signatures.len());
+            return Ok(());
+        }
+        // Broadcast the signature to the other committee members.
+        self.gateway.broadcast(Event::CheckpointSignature(CheckpointSignature::new(checkpoint, signature)));
+        Ok(())
+    }
+
+    /// Adds the signature of the given validator over a checkpoint, if the checkpoint matches the ledger.
+    fn process_checkpoint_signature(&self, peer_ip: SocketAddr, event: CheckpointSignature<N>) -> Result<()> {
+        let CheckpointSignature { checkpoint, signature } = event;
+        let height = checkpoint.height;
+        // Retrieve the address of the validator.
+        let Some(signer) = self.gateway.resolver().get_address(peer_ip) else {
+            return Ok(());
+        };
+        // Ensure the committee signs a checkpoint at this height.
+        ensure!(CommitteeCheckpoint::<N>::is_checkpoint_height(height), "Block {height} is not a checkpoint");
+        // Skip the signature if the checkpoint is already certified.
+        if self.checkpoints().contains(height) {
+            return Ok(());
+        }
+        // Skip the signature if the ledger has not reached the checkpoint yet, as the validator signs it again later.
+        if !self.ledger.contains_block_height(height) {
+            return Ok(());
+        }
+        // Ensure the checkpoint matches the ledger.
+        let (expected_checkpoint, committee) = self.checkpoint_from_ledger(height)?;
+        if checkpoint != expected_checkpoint {
+            bail!("Validator '{signer}' signed a checkpoint that conflicts with block {height} in the ledger");
+        }
+        if let Some(certificate) = self.checkpoints().insert_signature(checkpoint, signer, signature, &committee)? {
+            info!("Certified the checkpoint at block {height} ({} signatures)", certificate.signatures.len());
+        }
+        Ok(())
+    }
+}
+
 impl<N: Network> Sync<N> {
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
@@ -13,16 +13,18 @@
 // limitations under the License.
 
 use crate::{
-    events::{Event, TransmissionRequest, TransmissionResponse},
-    helpers::{fmt_id, Pending, Ready, Storage, WorkerReceiver},
+    events::{Event, TransmissionRequest, TransmissionResponse, TransmissionSketch},
+    helpers::{fmt_id, Pending, ProposalTrigger, Ready, Sketch, Storage, TransactionValidator, WorkerReceiver},
     ProposedBatch,
     Transport,
     MAX_BATCH_DELAY_IN_MS,
     MAX_TRANSMISSIONS_PER_BATCH,
+    MAX_TRANSMISSIONS_PER_INVENTORY,
     MAX_TRANSMISSIONS_PER_WORKER_PING,
     MAX_WORKERS,
+    TRANSMISSION_SKETCH_CELLS,
 };
-use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_bft_ledger_service::{CommitteeSource, LedgerService};
 use snarkvm::{
     console::prelude::*,
     ledger::narwhal::{Data, Transmission, TransmissionID},
@@ -32,13 +34,20 @@ use snarkvm::{
     },
 };
 
+use futures::stream::{self, StreamExt, TryStreamExt};
 use indexmap::{IndexMap, IndexSet};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use rayon::prelude::*;
 use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
 use tokio::{sync::oneshot, task::JoinHandle, time::timeout};
 
 const MAX_TRANSMISSIONS_PER_WORKER: usize = MAX_TRANSMISSIONS_PER_BATCH / MAX_WORKERS as usize;
 
+/// A worker collects the unconfirmed transmissions, which the primary drains into its batch proposals.
+///
+/// Note: Unlike the workers of Narwhal, a worker has no network identity of its own. It sends and receives its
+/// events through the gateway of the primary, which authenticates with the account of the validator. As such,
+/// there is no worker keypair or port that could be rotated; the gateway is the only network endpoint of the BFT.
 #[derive(Clone)]
 pub struct Worker<N: Network> {
     /// The worker ID.
@@ -51,6 +60,10 @@ pub struct Worker<N: Network> {
     ledger: Arc<dyn LedgerService<N>>,
     /// The proposed batch.
     proposed_batch: Arc<ProposedBatch<N>>,
+    /// The pipeline that validates the unconfirmed transactions before they are added to the ready queue.
+    transaction_validator: Arc<RwLock<TransactionValidator<N>>>,
+    /// The trigger with which the batch proposer is woken up, as the unconfirmed transactions arrive.
+    proposal_trigger: Arc<ProposalTrigger>,
     /// The ready queue.
     ready: Ready<N>,
     /// The pending transmissions queue.
@@ -67,6 +80,8 @@ impl<N: Network> Worker<N> {
         storage: Storage<N>,
         ledger: Arc<dyn LedgerService<N>>,
         proposed_batch: Arc<ProposedBatch<N>>,
+        transaction_validator: Arc<RwLock<TransactionValidator<N>>>,
+        proposal_trigger: Arc<ProposalTrigger>,
     ) -> Result<Self> {
         // Ensure the worker ID is valid.
         ensure!(id < MAX_WORKERS, "Invalid worker ID '{id}'");
@@ -77,6 +92,8 @@ impl<N: Network> Worker<N> {
             storage,
             ledger,
             proposed_batch,
+            transaction_validator,
+            proposal_trigger,
             ready: Default::default(),
             pending: Default::default(),
             handles: Default::default(),
@@ -196,8 +213,15 @@ impl<N: Network> Worker<N> {
         self.ready.drain(num_transmissions).into_iter()
     }
 
+    /// Removes the specified transmission from the ready queue, and returns it, if it exists.
+    ///
+    /// Note: Transmissions that are already in the proposed batch or in storage are not removed.
+    pub fn remove_transmission(&self, transmission_id: impl Into<TransmissionID<N>>) -> Option<Transmission<N>> {
+        self.ready.remove(transmission_id)
+    }
+
     /// Reinserts the specified transmission into the ready queue.
-    pub(crate) fn reinsert(&self, transmission_id: TransmissionID<N>, transmission: Transmission<N>) -> bool {
+    pub fn reinsert(&self, transmission_id: TransmissionID<N>, transmission: Transmission<N>) -> bool {
         // Check if the transmission ID exists.
         if !self.contains_transmission(transmission_id) {
             // Insert the transmission into the ready queue.
@@ -217,6 +241,31 @@ impl<N: Network> Worker<N> {
             self.gateway.broadcast(Event::WorkerPing(transmission_ids.into()));
         }
     }
+
+    /// Broadcasts a sketch of the transmission IDs in the ready queue.
+    pub(crate) fn broadcast_sketch(&self) {
+        match self.sketch() {
+            Ok((sketch, _)) => {
+                let event = TransmissionSketch::new(self.id, sketch.into_cells());
+                self.gateway.broadcast(Event::TransmissionSketch(event));
+            }
+            Err(e) => warn!("Worker {} - Failed to construct a transmission sketch - {e}", self.id),
+        }
+    }
+
+    /// Returns a sketch of the transmission IDs in the ready queue, and the map of short IDs to transmission IDs.
+    fn sketch(&self) -> Result<(Sketch, IndexMap<u64, TransmissionID<N>>)> {
+        let mut sketch = Sketch::new(TRANSMISSION_SKETCH_CELLS)?;
+        let mut short_ids = IndexMap::new();
+        for transmission_id in self.ready.transmission_ids() {
+            let short_id = Sketch::short_id(transmission_id)?;
+            // Note: In the unlikely event of a collision, only the first transmission ID is sketched.
+            if short_ids.insert(short_id, transmission_id).is_none() {
+                sketch.insert(short_id);
+            }
+        }
+        Ok((sketch, short_ids))
+    }
 }
 
 impl<N: Network> Worker<N> {
@@ -258,6 +307,67 @@ impl<N: Network> Worker<N> {
         });
     }
 
+    /// Handles the incoming transmission sketch from a peer, by sending a worker ping
+    /// with the transmission IDs in the ready queue that the peer does not have.
+    ///
+    /// The transmissions that only the peer has are not requested here, as the peer
+    /// sends them in response to the sketch broadcast by this worker.
+    fn process_transmission_sketch(&self, peer_ip: SocketAddr, sketch: TransmissionSketch) -> Result<()> {
+        // Compute the difference between the ready queue and the sketch from the peer.
+        let (mut difference, short_ids) = self.sketch()?;
+        difference.subtract(&Sketch::from_cells(sketch.cells)?)?;
+        // Decode the difference.
+        let Some((missing_at_peer, missing_locally)) = difference.decode() else {
+            // If the difference is too large, then rely on the worker pings instead.
+            debug!("Worker {} - Unable to reconcile with '{peer_ip}' (too many differences)", self.id);
+            return Ok(());
+        };
+        trace!(
+            "Worker {} - Reconciled with '{peer_ip}' ({} missing at peer, {} missing locally)",
+            self.id,
+            missing_at_peer.len(),
+            missing_locally.len()
+        );
+        // Resolve the transmission IDs that the peer is missing.
+        let transmission_ids =
+            missing_at_peer.iter().filter_map(|short_id| short_ids.get(short_id).copied()).collect::<Vec<_>>();
+        // Send the transmission IDs to the peer, so that it can fetch the transmissions.
+        self.send_pings(peer_ip, &transmission_ids);
+        Ok(())
+    }
+
+    /// Sends the transmission IDs in the ready queue to a newly-connected peer, so that it can fetch
+    /// the transmissions it is missing, and include them in its next batch.
+    ///
+    /// The unconfirmed transactions are sent first, and at most `MAX_TRANSMISSIONS_PER_INVENTORY` IDs are sent.
+    fn send_inventory(&self, peer_ip: SocketAddr) {
+        // Retrieve the transmission IDs, with the unconfirmed transactions first.
+        let (transactions, others): (Vec<_>, Vec<_>) = self
+            .ready
+            .transmission_ids()
+            .into_iter()
+            .partition(|transmission_id| matches!(transmission_id, TransmissionID::Transaction(_)));
+        let transmission_ids =
+            transactions.into_iter().chain(others).take(MAX_TRANSMISSIONS_PER_INVENTORY).collect::<Vec<_>>();
+        if transmission_ids.is_empty() {
+            return;
+        }
+        debug!("Worker {} - Sending {} transmission IDs to '{peer_ip}'", self.id, transmission_ids.len());
+        // Send the transmission IDs to the peer, so that it can fetch the transmissions.
+        self.send_pings(peer_ip, &transmission_ids);
+    }
+
+    /// Sends the given transmission IDs to the peer, in worker pings.
+    fn send_pings(&self, peer_ip: SocketAddr, transmission_ids: &[TransmissionID<N>]) {
+        for chunk in transmission_ids.chunks(MAX_TRANSMISSIONS_PER_WORKER_PING) {
+            let ping = Event::WorkerPing(chunk.iter().copied().collect::<IndexSet<_>>().into());
+            let gateway = self.gateway.clone();
+            tokio::spawn(async move {
+                gateway.send(peer_ip, ping).await;
+            });
+        }
+    }
+
     /// Handles the incoming transmission from a peer.
     pub(crate) fn process_transmission_from_peer(
         &self,
@@ -325,22 +435,159 @@ impl<N: Network> Worker<N> {
         if self.contains_transmission(&transaction_id) {
             bail!("Transaction '{}' already exists.", fmt_id(transaction_id));
         }
-        // Check that the transaction is well-formed and unique.
-        if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
+        // Check that the transaction passes the validation pipeline.
+        let transaction_validator = self.transaction_validator.read().clone();
+        if let Err(e) = transaction_validator.validate(&self.ledger, transaction_id, transaction.clone()).await {
             bail!("Invalid unconfirmed transaction '{}': {e}", fmt_id(transaction_id));
         }
-        // Adds the transaction to the ready queue.
+        // Retrieve the priority fee, only if the batch proposer is triggered by the priority fees.
+        let priority_fee = match self.proposal_trigger.min_priority_fees() > 0 {
+            true => transaction
+                .deserialize()
+                .await
+                .ok()
+                .and_then(|transaction| transaction.fee_transition())
+                .and_then(|fee| fee.priority_amount().ok())
+                .map_or(0, |amount| *amount),
+            false => 0,
+        };
+        // Adds the transaction to the ready queue, and wakes up the batch proposer.
         if self.ready.insert(&transaction_id, transmission) {
             trace!("Worker {} - Added unconfirmed transaction '{}'", self.id, fmt_id(transaction_id));
+            self.proposal_trigger.record_transaction(priority_fee);
         }
         Ok(())
     }
 }
 
+impl<N: Network> Worker<N> {
+    /// Checks the given transmissions, with up to `max_concurrent_checks` checks in flight at once.
+    /// Returns the valid transmissions, in their original order.
+    pub(crate) async fn check_transmissions(
+        &self,
+        transmissions: Vec<(TransmissionID<N>, Transmission<N>)>,
+        max_concurrent_checks: usize,
+    ) -> Vec<(TransmissionID<N>, Transmission<N>)> {
+        // Deserialize the transmissions as a single batch.
+        let transmissions = Self::deserialize_transmissions(transmissions).await;
+        // Check the transmissions concurrently, preserving their order.
+        stream::iter(transmissions)
+            .map(|(transmission_id, transmission)| {
+                let ledger = self.ledger.clone();
+                async move {
+                    let result = Self::check_transmission(&ledger, transmission_id, transmission.clone()).await;
+                    (transmission_id, transmission, result)
+                }
+            })
+            .buffered(max_concurrent_checks.max(1))
+            .filter_map(|(transmission_id, transmission, result)| async move {
+                match result {
+                    Ok(()) => Some((transmission_id, transmission)),
+                    Err(e) => {
+                        trace!("Worker - Skipping transmission '{}' - {e}", fmt_id(transmission_id));
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Ensures the given transmissions are valid, with up to `max_concurrent_checks` checks in flight at once.
+    /// This method returns on the first invalid transmission, without waiting for the remaining checks.
+    pub(crate) async fn ensure_transmissions_are_valid(
+        &self,
+        transmissions: Vec<(TransmissionID<N>, Transmission<N>)>,
+        max_concurrent_checks: usize,
+    ) -> Result<()> {
+        // Deserialize the transmissions as a single batch.
+        let transmissions = Self::deserialize_transmissions(transmissions).await;
+        // Check the transmissions concurrently, aborting on the first failure.
+        stream::iter(transmissions)
+            .map(|(transmission_id, transmission)| {
+                let ledger = self.ledger.clone();
+                async move {
+                    Self::check_transmission(&ledger, transmission_id, transmission)
+                        .await
+                        .map_err(|e| anyhow!("Invalid transmission '{}' - {e}", fmt_id(transmission_id)))
+                }
+            })
+            .buffer_unordered(max_concurrent_checks.max(1))
+            .try_collect::<Vec<()>>()
+            .await?;
+        Ok(())
+    }
+
+    /// Checks the given transmission is well-formed and unique.
+    async fn check_transmission(
+        ledger: &Arc<dyn LedgerService<N>>,
+        transmission_id: TransmissionID<N>,
+        transmission: Transmission<N>,
+    ) -> Result<()> {
+        match (transmission_id, transmission) {
+            (TransmissionID::Solution(solution_id), Transmission::Solution(solution)) => {
+                ledger.check_solution_basic(solution_id, solution).await
+            }
+            (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction)) => {
+                ledger.check_transaction_basic(transaction_id, transaction).await
+            }
+            // Note: We explicitly forbid including ratifications,
+            // as the protocol currently does not support ratifications.
+            (TransmissionID::Ratification, Transmission::Ratification) => bail!("Ratifications are not supported"),
+            // All other combinations are clearly invalid.
+            _ => bail!("Mismatching transmission ID and transmission type"),
+        }
+    }
+
+    /// Deserializes the given transmissions in parallel, on a single blocking task.
+    /// Transmissions that fail to deserialize are returned as-is, and are rejected by the subsequent checks.
+    async fn deserialize_transmissions(
+        transmissions: Vec<(TransmissionID<N>, Transmission<N>)>,
+    ) -> Vec<(TransmissionID<N>, Transmission<N>)> {
+        // If there is at most one transmission, there is nothing to batch.
+        if transmissions.len() <= 1 {
+            return transmissions;
+        }
+        let transmissions_ = transmissions.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            transmissions_
+                .into_par_iter()
+                .map(|(transmission_id, transmission)| {
+                    let transmission = match transmission {
+                        Transmission::Solution(Data::Buffer(bytes)) => {
+                            match Data::<ProverSolution<N>>::Buffer(bytes.clone()).deserialize_blocking() {
+                                Ok(solution) => Transmission::Solution(Data::Object(solution)),
+                                Err(_) => Transmission::Solution(Data::Buffer(bytes)),
+                            }
+                        }
+                        Transmission::Transaction(Data::Buffer(bytes)) => {
+                            match Data::<Transaction<N>>::Buffer(bytes.clone()).deserialize_blocking() {
+                                Ok(transaction) => Transmission::Transaction(Data::Object(transaction)),
+                                Err(_) => Transmission::Transaction(Data::Buffer(bytes)),
+                            }
+                        }
+                        transmission => transmission,
+                    };
+                    (transmission_id, transmission)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await;
+        // If the blocking task failed, fall back to the original transmissions.
+        result.unwrap_or(transmissions)
+    }
+}
+
 impl<N: Network> Worker<N> {
     /// Starts the worker handlers.
     fn start_handlers(&self, receiver: WorkerReceiver<N>) {
-        let WorkerReceiver { mut rx_worker_ping, mut rx_transmission_request, mut rx_transmission_response } = receiver;
+        let WorkerReceiver {
+            mut rx_worker_ping,
+            mut rx_transmission_request,
+            mut rx_transmission_response,
+            mut rx_transmission_sketch,
+            mut rx_connected_peer,
+        } = receiver;
 
         // Process the ping events.
         let self_ = self.clone();
@@ -366,6 +613,26 @@ impl<N: Network> Worker<N> {
                 self_.finish_transmission_request(peer_ip, transmission_response);
             }
         });
+
+        // Process the transmission sketches.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some((peer_ip, transmission_sketch)) = rx_transmission_sketch.recv().await {
+                // Process the transmission sketch.
+                if let Err(e) = self_.process_transmission_sketch(peer_ip, transmission_sketch) {
+                    warn!("Worker {} - Failed to process a transmission sketch from '{peer_ip}' - {e}", self_.id);
+                }
+            }
+        });
+
+        // Process the connected peers.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some(peer_ip) = rx_connected_peer.recv().await {
+                // Send the transmission IDs in the ready queue to the peer.
+                self_.send_inventory(peer_ip);
+            }
+        });
     }
 
     /// Sends a transmission request to the specified peer.
@@ -439,7 +706,7 @@ impl<N: Network> Worker<N> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use snarkos_node_bft_ledger_service::LedgerService;
+    use snarkos_node_bft_ledger_service::{CommitteeSource, LedgerService};
     use snarkos_node_bft_storage_service::BFTMemoryService;
     use snarkvm::{
         console::{network::Network, types::Field},
@@ -469,6 +736,12 @@ mod tests {
     mock! {
         #[derive(Debug)]
         Ledger<N: Network> {}
+        impl<N: Network> CommitteeSource<N> for Ledger<N> {
+            fn genesis_committee(&self) -> Result<Committee<N>>;
+            fn current_committee(&self) -> Result<Committee<N>>;
+            fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>>;
+            fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>>;
+        }
         #[async_trait]
         impl<N: Network> LedgerService<N> for Ledger<N> {
             fn latest_round(&self) -> u64;
@@ -482,9 +755,6 @@ mod tests {
             fn get_solution(&self, solution_id: &PuzzleCommitment<N>) -> Result<ProverSolution<N>>;
             fn get_unconfirmed_transaction(&self, transaction_id: N::TransactionID) -> Result<Transaction<N>>;
             fn get_batch_certificate(&self, certificate_id: &Field<N>) -> Result<BatchCertificate<N>>;
-            fn current_committee(&self) -> Result<Committee<N>>;
-            fn get_committee_for_round(&self, round: u64) -> Result<Committee<N>>;
-            fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>>;
             fn contains_certificate(&self, certificate_id: &Field<N>) -> Result<bool>;
             fn contains_transmission(&self, transmission_id: &TransmissionID<N>) -> Result<bool>;
             fn ensure_transmission_is_well_formed(
@@ -528,7 +798,16 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
         let transmission_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
         let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
@@ -566,7 +845,16 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         let transmission_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
         let worker_ = worker.clone();
         let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
@@ -603,7 +891,16 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         let puzzle = PuzzleCommitment::from_g1_affine(rng.gen());
         let transmission_id = TransmissionID::Solution(puzzle);
         let worker_ = worker.clone();
@@ -642,7 +939,16 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         let puzzle = PuzzleCommitment::from_g1_affine(rng.gen());
         let transmission_id = TransmissionID::Solution(puzzle);
         let worker_ = worker.clone();
@@ -680,7 +986,16 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         let transaction_id: <CurrentNetwork as Network>::TransactionID = Field::<CurrentNetwork>::rand(&mut rng).into();
         let transmission_id = TransmissionID::Transaction(transaction_id);
         let worker_ = worker.clone();
@@ -718,7 +1033,16 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         let transaction_id: <CurrentNetwork as Network>::TransactionID = Field::<CurrentNetwork>::rand(&mut rng).into();
         let transmission_id = TransmissionID::Transaction(transaction_id);
         let worker_ = worker.clone();
@@ -735,6 +1059,160 @@ mod tests {
         assert!(!worker.pending.contains(transmission_id));
         assert!(!worker.ready.contains(transmission_id));
     }
+
+    #[tokio::test]
+    async fn test_check_transmissions() {
+        let rng = &mut TestRng::default();
+        // Sample the transactions, and select one to be invalid.
+        let transaction_ids: Vec<<CurrentNetwork as Network>::TransactionID> =
+            (0..10).map(|_| Field::<CurrentNetwork>::rand(rng).into()).collect();
+        let invalid_id = transaction_ids[3];
+        // Setup the mock gateway and ledger.
+        let gateway = MockGateway::default();
+        let mut mock_ledger = MockLedger::default();
+        mock_ledger.expect_check_transaction_basic().returning(move |transaction_id, _| match transaction_id {
+            id if id == invalid_id => Err(anyhow!("Invalid transaction")),
+            _ => Ok(()),
+        });
+        let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+
+        // Create the Worker.
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        let transmissions = transaction_ids
+            .iter()
+            .map(|id| {
+                let bytes = Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>());
+                (TransmissionID::Transaction(*id), Transmission::Transaction(Data::Buffer(bytes)))
+            })
+            .collect::<Vec<_>>();
+
+        // Ensure the invalid transaction is filtered out, and the order is preserved.
+        let valid = worker.check_transmissions(transmissions.clone(), 4).await;
+        let expected = transaction_ids.iter().filter(|id| **id != invalid_id).map(|id| TransmissionID::Transaction(*id));
+        assert!(valid.iter().map(|(id, _)| *id).eq(expected));
+        // Ensure the batch is rejected.
+        assert!(worker.ensure_transmissions_are_valid(transmissions.clone(), 4).await.is_err());
+        // Ensure the batch is accepted without the invalid transaction.
+        assert!(worker.ensure_transmissions_are_valid(valid, 4).await.is_ok());
+        // Ensure ratifications are rejected.
+        let ratification = vec![(TransmissionID::Ratification, Transmission::Ratification)];
+        assert!(worker.check_transmissions(ratification.clone(), 4).await.is_empty());
+        assert!(worker.ensure_transmissions_are_valid(ratification, 4).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_process_transmission_sketch() {
+        let rng = &mut TestRng::default();
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Sample the transmission IDs.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+        let shared_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        let missing_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        // Setup the mock gateway and ledger.
+        let mut gateway = MockGateway::default();
+        gateway
+            .expect_send()
+            .times(1)
+            .withf(move |_, event| match event {
+                Event::WorkerPing(ping) => ping.transmission_ids.iter().eq([missing_id].iter()),
+                _ => false,
+            })
+            .returning(|_, _| {
+                let (_tx, rx) = oneshot::channel();
+                Some(rx)
+            });
+        let mut mock_ledger = MockLedger::default();
+        mock_ledger.expect_current_committee().returning(move || Ok(committee.clone()));
+        mock_ledger.expect_contains_transmission().returning(|_| Ok(false));
+        let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+
+        // Create the Worker, with both transmissions in the ready queue.
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
+        worker.process_transmission_from_peer(peer_ip, shared_id, Transmission::Solution(data(rng)));
+        worker.process_transmission_from_peer(peer_ip, missing_id, Transmission::Solution(data(rng)));
+
+        // Construct the sketch of a peer that only has the shared transmission.
+        let mut sketch = Sketch::new(TRANSMISSION_SKETCH_CELLS).unwrap();
+        sketch.insert(Sketch::short_id(shared_id).unwrap());
+
+        // Process the sketch, and ensure the missing transmission ID is sent to the peer.
+        worker.process_transmission_sketch(peer_ip, TransmissionSketch::new(0, sketch.into_cells())).unwrap();
+        snarkos_node_clock::sleep(Duration::from_millis(100)).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_send_inventory() {
+        let rng = &mut TestRng::default();
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Sample the transmission IDs.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+        let solution_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        let transaction_id: <CurrentNetwork as Network>::TransactionID = Field::<CurrentNetwork>::rand(rng).into();
+        let transaction_id = TransmissionID::Transaction(transaction_id);
+        // Setup the mock gateway and ledger.
+        let mut gateway = MockGateway::default();
+        gateway
+            .expect_send()
+            .times(1)
+            .withf(move |_, event| match event {
+                Event::WorkerPing(ping) => ping.transmission_ids.iter().eq([transaction_id, solution_id].iter()),
+                _ => false,
+            })
+            .returning(|_, _| {
+                let (_tx, rx) = oneshot::channel();
+                Some(rx)
+            });
+        let mut mock_ledger = MockLedger::default();
+        mock_ledger.expect_current_committee().returning(move || Ok(committee.clone()));
+        mock_ledger.expect_contains_transmission().returning(|_| Ok(false));
+        let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+
+        // Create the Worker, with a solution and a transaction in the ready queue.
+        let worker = Worker::new(
+            0,
+            Arc::new(gateway),
+            storage,
+            ledger,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
+        worker.process_transmission_from_peer(peer_ip, solution_id, Transmission::Solution(data(rng)));
+        worker.process_transmission_from_peer(peer_ip, transaction_id, Transmission::Transaction(data(rng)));
+
+        // Send the inventory, and ensure the transaction ID is sent before the solution ID.
+        worker.send_inventory(peer_ip);
+        snarkos_node_clock::sleep(Duration::from_millis(100)).await;
+    }
 }
 
 #[cfg(test)]
@@ -773,7 +1251,8 @@ mod prop_tests {
     ) {
         let committee = new_test_committee(4);
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(MockLedgerService::new(committee));
-        let worker = Worker::new(id, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker =
+            Worker::new(id, Arc::new(gateway), storage, ledger, Default::default(), Default::default()).unwrap();
         assert_eq!(worker.id(), id);
     }
 
@@ -785,7 +1264,7 @@ mod prop_tests {
     ) {
         let committee = new_test_committee(4);
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(MockLedgerService::new(committee));
-        let worker = Worker::new(id, Arc::new(gateway), storage, ledger, Default::default());
+        let worker = Worker::new(id, Arc::new(gateway), storage, ledger, Default::default(), Default::default());
         // TODO once Worker implements Debug, simplify this with `unwrap_err`
         if let Err(error) = worker {
             assert_eq!(error.to_string(), format!("Invalid worker ID '{}'", id));
@@ -19,8 +19,8 @@ use crate::common::primary::{TestNetwork, TestNetworkConfig};
 use deadline::deadline;
 use itertools::Itertools;
 use snarkos_node_bft::MAX_BATCH_DELAY_IN_MS;
+use snarkos_node_clock::sleep;
 use std::time::Duration;
-use tokio::time::sleep;
 
 #[tokio::test(flavor = "multi_thread")]
 #[ignore = "long-running e2e test"]
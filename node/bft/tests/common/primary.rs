@@ -25,7 +25,9 @@ use snarkos_node_bft::{
     MAX_BATCH_DELAY_IN_MS,
     MAX_GC_ROUNDS,
 };
+use snarkos_node_bft_ledger_service::CommitteeSource;
 use snarkos_node_bft_storage_service::BFTMemoryService;
+use snarkos_node_clock::sleep;
 use snarkvm::{
     console::algorithms::BHP256,
     ledger::{
@@ -59,7 +61,7 @@ use std::{
     sync::{Arc, OnceLock},
     time::Duration,
 };
-use tokio::{task::JoinHandle, time::sleep};
+use tokio::task::JoinHandle;
 use tracing::*;
 
 /// The configuration for the test network.
@@ -232,7 +234,7 @@ impl TestNetwork {
             let ip = other_validator.primary.gateway().local_ip();
             validator.primary.gateway().connect(ip);
             // Give the connection time to be established.
-            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            sleep(Duration::from_millis(10)).await;
         }
     }
 
@@ -245,7 +247,7 @@ impl TestNetwork {
                 // Connect to the node.
                 validator.primary.gateway().connect(target_ip);
                 // Give the connection time to be established.
-                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                sleep(Duration::from_millis(10)).await;
             }
         }
     }
@@ -14,6 +14,7 @@
 
 use crate::common::CurrentNetwork;
 use snarkos_node_bft::helpers::PrimarySender;
+use snarkos_node_clock::sleep;
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{
@@ -30,7 +31,7 @@ use std::time::Duration;
 
 use ::bytes::Bytes;
 use rand::Rng;
-use tokio::{sync::oneshot, task, task::JoinHandle, time::sleep};
+use tokio::{sync::oneshot, task, task::JoinHandle};
 use tracing::*;
 use tracing_subscriber::{
     layer::{Layer, SubscriberExt},
@@ -17,11 +17,11 @@ mod common;
 
 use crate::common::primary::{TestNetwork, TestNetworkConfig};
 use snarkos_node_bft::MAX_BATCH_DELAY_IN_MS;
+use snarkos_node_clock::sleep;
 
 use std::time::Duration;
 
 use deadline::deadline;
-use tokio::time::sleep;
 
 #[tokio::test(flavor = "multi_thread")]
 #[ignore = "long-running e2e test"]
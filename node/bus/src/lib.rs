@@ -0,0 +1,104 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+use snarkvm::prelude::Network;
+
+use serde::Serialize;
+use std::net::SocketAddr;
+use tokio::sync::broadcast;
+
+/// The maximum number of events buffered for each subscriber.
+/// Note: A subscriber that falls further behind misses the oldest events.
+pub const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// An event published by a subsystem of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case", bound = "")]
+pub enum NodeEvent<N: Network> {
+    /// The ledger advanced to the given block.
+    BlockAdvanced { height: u32, hash: N::BlockHash },
+    /// The number of unconfirmed transmissions in the memory pool changed.
+    MempoolChanged { num_unconfirmed_transmissions: usize },
+    /// The router connected to the given peer.
+    PeerConnected { peer_ip: SocketAddr },
+    /// The router disconnected from the given peer.
+    PeerDisconnected { peer_ip: SocketAddr },
+    /// The BFT started.
+    BftStarted,
+    /// The BFT stopped.
+    BftStopped,
+    /// A new committee took effect, starting at the given round.
+    EpochChanged { starting_round: u64 },
+}
+
+/// A broadcast channel for the events between the subsystems of the node.
+///
+/// Publishing never blocks, and events published while there are no subscribers are dropped.
+#[derive(Clone, Debug)]
+pub struct EventBus<N: Network> {
+    /// The sender of the broadcast channel.
+    sender: broadcast::Sender<NodeEvent<N>>,
+}
+
+impl<N: Network> Default for EventBus<N> {
+    /// Initializes a new event bus.
+    fn default() -> Self {
+        Self { sender: broadcast::channel(EVENT_BUS_CAPACITY).0 }
+    }
+}
+
+impl<N: Network> EventBus<N> {
+    /// Publishes the given event to all subscribers.
+    pub fn publish(&self, event: NodeEvent<N>) {
+        // Note: This only fails if there are no subscribers, in which case the event is dropped.
+        let _ = self.sender.send(event);
+    }
+
+    /// Returns a new subscription to the events published after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent<N>> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the number of subscribers.
+    pub fn num_subscribers(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::Testnet3;
+
+    type CurrentNetwork = Testnet3;
+
+    #[tokio::test]
+    async fn test_event_bus() {
+        let bus = EventBus::<CurrentNetwork>::default();
+        // Ensure publishing without subscribers does not fail.
+        bus.publish(NodeEvent::BftStarted);
+
+        let mut subscriber = bus.subscribe();
+        assert_eq!(bus.num_subscribers(), 1);
+
+        // Ensure the subscriber receives the events in order.
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+        bus.publish(NodeEvent::PeerConnected { peer_ip });
+        bus.publish(NodeEvent::MempoolChanged { num_unconfirmed_transmissions: 1 });
+        assert_eq!(subscriber.recv().await.unwrap(), NodeEvent::PeerConnected { peer_ip });
+        assert_eq!(subscriber.recv().await.unwrap(), NodeEvent::MempoolChanged { num_unconfirmed_transmissions: 1 });
+    }
+}
@@ -26,44 +26,76 @@ use snarkvm::prelude::{
     Serialize,
 };
 
+use crate::CdnSyncProgress;
+
 use anyhow::{anyhow, bail, Result};
 use colored::Colorize;
-use parking_lot::Mutex;
+use futures::StreamExt;
 use reqwest::Client;
 use std::{
     cmp,
     sync::{
-        atomic::{AtomicBool, AtomicU32, Ordering},
+        atomic::{AtomicBool, Ordering},
         Arc,
     },
     time::{Duration, Instant},
 };
+use tokio::sync::mpsc;
 
 /// The number of blocks per file.
 const BLOCKS_PER_FILE: u32 = 50;
-/// The desired number of concurrent requests to the CDN.
-const CONCURRENT_REQUESTS: u32 = 16;
-/// Maximum number of pending sync blocks.
-const MAXIMUM_PENDING_BLOCKS: u32 = BLOCKS_PER_FILE * CONCURRENT_REQUESTS * 2;
+/// The default number of concurrent requests to the CDN.
+pub const DEFAULT_CDN_CONCURRENCY: usize = 16;
+/// The default maximum number of verified bundles of blocks that are pending insertion.
+pub const DEFAULT_CDN_MAX_PENDING_BUNDLES: usize = DEFAULT_CDN_CONCURRENCY * 2;
 /// Maximum number of attempts for a request to the CDN.
 const MAXIMUM_REQUEST_ATTEMPTS: u8 = 10;
 /// The supported network.
 const NETWORK_ID: u16 = 3;
 
-/// Loads blocks from a CDN into the ledger.
+/// The CDNs to load blocks from, and the bounds of the sync pipeline.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CdnConfig {
+    /// The base URLs of the CDNs.
+    pub base_urls: Vec<String>,
+    /// The number of bundles of blocks that are downloaded concurrently.
+    pub concurrency: usize,
+    /// The maximum number of verified bundles of blocks that are pending insertion.
+    /// Once it is reached, the downloads pause until the ledger catches up.
+    pub max_pending_bundles: usize,
+}
+
+impl CdnConfig {
+    /// Initializes a new CDN configuration with the given base URLs, and the default bounds.
+    pub fn new(base_urls: Vec<String>) -> Self {
+        Self { base_urls, concurrency: DEFAULT_CDN_CONCURRENCY, max_pending_bundles: DEFAULT_CDN_MAX_PENDING_BUNDLES }
+    }
+}
+
+/// A CDN to load blocks from.
+#[derive(Clone, Debug)]
+struct CdnSource {
+    /// The base URL of the CDN.
+    base_url: String,
+    /// The height of the CDN.
+    height: u32,
+}
+
+/// Loads blocks from the given CDNs into the ledger.
 ///
 /// On success, this function returns the completed block height.
 /// On failure, this function returns the last successful block height (if any), along with the error.
 pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
-    base_url: &str,
+    config: &CdnConfig,
     ledger: Ledger<N, C>,
+    progress: CdnSyncProgress,
     shutdown: Arc<AtomicBool>,
 ) -> Result<u32, (u32, anyhow::Error)> {
     // Fetch the node height.
     let start_height = ledger.latest_height() + 1;
     // Load the blocks from the CDN into the ledger.
     let ledger_clone = ledger.clone();
-    let result = load_blocks(base_url, start_height, None, shutdown, move |block: Block<N>| {
+    let result = load_blocks(config, start_height, None, progress, shutdown, move |block: Block<N>| {
         ledger_clone.advance_to_next_block(&block)
     })
     .await;
@@ -96,14 +128,21 @@ pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
     }
 }
 
-/// Loads blocks from a CDN and process them with the given function.
+/// Loads blocks from the given CDNs and process them with the given function.
+///
+/// The blocks flow through a bounded pipeline: the block bundles are downloaded from the CDNs concurrently,
+/// cross-verified across them, checked to link to one another in order, and then processed in order.
+/// A CDN that is unreachable, lags behind, or serves conflicting blocks is failed over to the other CDNs.
+/// At most `concurrency` bundles are downloading and `max_pending_bundles` bundles are pending at a time,
+/// so that the memory use does not grow with the size of the range.
 ///
 /// On success, this function returns the completed block height.
 /// On failure, this function returns the last successful block height (if any), along with the error.
 pub async fn load_blocks<N: Network>(
-    base_url: &str,
+    config: &CdnConfig,
     start_height: u32,
     end_height: Option<u32>,
+    progress: CdnSyncProgress,
     shutdown: Arc<AtomicBool>,
     process: impl FnMut(Block<N>) -> Result<()> + Clone + Send + Sync + 'static,
 ) -> Result<u32, (u32, anyhow::Error)> {
@@ -120,10 +159,21 @@ pub async fn load_blocks<N: Network>(
         }
     };
 
-    // Fetch the CDN height.
-    let cdn_height = match cdn_height::<BLOCKS_PER_FILE>(&client, base_url).await {
-        Ok(cdn_height) => cdn_height,
-        Err(error) => return Err((start_height, error)),
+    // Fetch the height of each CDN, skipping the CDNs that are unreachable.
+    let mut sources = Vec::with_capacity(config.base_urls.len());
+    let mut last_error = None;
+    for base_url in &config.base_urls {
+        match cdn_height::<BLOCKS_PER_FILE>(&client, base_url).await {
+            Ok(height) => sources.push(CdnSource { base_url: base_url.clone(), height }),
+            Err(error) => {
+                warn!("Skipping the CDN at '{base_url}' - {error}");
+                last_error = Some(error);
+            }
+        }
+    }
+    // Set the CDN height to the highest height among the CDNs.
+    let Some(cdn_height) = sources.iter().map(|source| source.height).max() else {
+        return Err((start_height, last_error.unwrap_or_else(|| anyhow!("No CDN was given to load blocks from"))));
     };
     // If the CDN height is less than the start height, return.
     if cdn_height < start_height {
@@ -153,18 +203,19 @@ pub async fn load_blocks<N: Network>(
         return Ok(cdn_end);
     }
 
-    // A collection of downloaded blocks pending insertion into the ledger.
-    let pending_blocks: Arc<Mutex<Vec<Block<N>>>> = Default::default();
+    // The verified bundles of blocks pending insertion, in the order of their heights.
+    // The channel is bounded, so that the downloads pause while the blocks are being processed.
+    let (sender, mut receiver) = mpsc::channel(config.max_pending_bundles.max(1));
 
-    // Start a timer.
-    let timer = Instant::now();
+    // Start tracking the progress.
+    progress.start(start_height, end_height - 1);
 
     // Spawn a background task responsible for concurrent downloads.
-    let pending_blocks_clone = pending_blocks.clone();
-    let base_url = base_url.to_owned();
+    let sources = Arc::from(sources);
+    let concurrency = config.concurrency.max(1);
     let shutdown_clone = shutdown.clone();
     tokio::spawn(async move {
-        download_block_bundles(client, base_url, cdn_start, cdn_end, pending_blocks_clone, shutdown_clone).await;
+        download_block_bundles(client, sources, cdn_start, cdn_end, concurrency, sender, shutdown_clone).await;
     });
 
     // A loop for inserting the pending blocks into the ledger.
@@ -177,32 +228,16 @@ pub async fn load_blocks<N: Network>(
             std::process::exit(0);
         }
 
-        let mut candidate_blocks = pending_blocks.lock();
-
-        // Obtain the height of the nearest pending block.
-        let Some(next_height) = candidate_blocks.first().map(|b| b.height()) else {
-            debug!("No pending blocks yet");
-            drop(candidate_blocks);
-            tokio::time::sleep(Duration::from_secs(3)).await;
-            continue;
+        // Wait for the next bundle of blocks.
+        let next_blocks = match receiver.recv().await {
+            Some(Ok(blocks)) => blocks,
+            Some(Err(error)) => return Err((current_height, error)),
+            None => return Err((current_height, anyhow!("The CDN downloads stopped before block {end_height}"))),
         };
 
-        // Wait if the nearest pending block is not the next one that can be inserted.
-        if next_height > current_height + 1 {
-            // There is a gap in pending blocks, we need to wait.
-            debug!("Waiting for the first relevant blocks ({} pending)", candidate_blocks.len());
-            drop(candidate_blocks);
-            tokio::time::sleep(Duration::from_secs(1)).await;
-            continue;
-        }
-
-        // Obtain the first BLOCKS_PER_FILE applicable blocks.
-        let retained_blocks = candidate_blocks.split_off(BLOCKS_PER_FILE as usize);
-        let next_blocks = std::mem::replace(&mut *candidate_blocks, retained_blocks);
-        drop(candidate_blocks);
-
         // Attempt to advance the ledger using the CDN block bundle.
         let mut process_clone = process.clone();
+        let progress_clone = progress.clone();
         let shutdown_clone = shutdown.clone();
         current_height = tokio::task::spawn_blocking(move || {
             for block in next_blocks.into_iter().filter(|b| (start_height..end_height).contains(&b.height())) {
@@ -223,7 +258,8 @@ pub async fn load_blocks<N: Network>(
                 current_height = block_height;
 
                 // Log the progress.
-                log_progress::<BLOCKS_PER_FILE>(timer, current_height, cdn_start, cdn_end, "block");
+                progress_clone.update(current_height);
+                progress_clone.log();
             }
 
             Ok(current_height)
@@ -236,112 +272,164 @@ pub async fn load_blocks<N: Network>(
     Ok(current_height)
 }
 
+/// Downloads the bundles of blocks in the given range, and sends them in order to the given channel.
+///
+/// Up to `concurrency` bundles are downloaded at a time. Each bundle is verified to link to the previous one
+/// before it is sent. As the channel is bounded, the downloads pause while it is full.
+/// A bundle that fails to download or verify is sent as an error, which ends the downloads.
 async fn download_block_bundles<N: Network>(
     client: Client,
-    base_url: String,
+    sources: Arc<[CdnSource]>,
     cdn_start: u32,
     cdn_end: u32,
-    pending_blocks: Arc<Mutex<Vec<Block<N>>>>,
+    concurrency: usize,
+    sender: mpsc::Sender<Result<Vec<Block<N>>>>,
     shutdown: Arc<AtomicBool>,
 ) {
-    // Keep track of the number of concurrent requests.
-    let active_requests: Arc<AtomicU32> = Default::default();
-
-    let mut start = cdn_start;
-    while start < cdn_end - 1 {
+    // Download the bundles concurrently, and yield them in the order of their heights.
+    let mut bundles = futures::stream::iter((cdn_start..cdn_end).step_by(BLOCKS_PER_FILE as usize))
+        .map(|start| download_block_bundle_with_retries(&client, &sources, start, cdn_end, &shutdown))
+        .buffered(concurrency);
+
+    // The hash of the last block of the previous bundle.
+    let mut previous_hash = None;
+    while let Some(result) = bundles.next().await {
         // If we are instructed to shut down, stop downloading.
         if shutdown.load(Ordering::Relaxed) {
             break;
         }
-
-        // Avoid collecting too many blocks in order to restrict memory use.
-        let num_pending_blocks = pending_blocks.lock().len();
-        if num_pending_blocks >= MAXIMUM_PENDING_BLOCKS as usize {
-            debug!("Maximum number of pending blocks reached ({num_pending_blocks}), waiting...");
-            tokio::time::sleep(Duration::from_secs(5)).await;
-            continue;
+        // Ensure the bundle follows the previous one.
+        let result = result.and_then(|(start, blocks)| {
+            verify_block_bundle(&blocks, start, &mut previous_hash)?;
+            Ok(blocks)
+        });
+        let is_error = result.is_err();
+        // Wait for room in the channel, and stop downloading if the blocks are no longer processed.
+        if sender.send(result).await.is_err() || is_error {
+            break;
         }
+    }
 
-        // The number of concurrent requests is maintained at CONCURRENT_REQUESTS, unless the maximum
-        // number of pending blocks may be breached.
-        let active_request_count = active_requests.load(Ordering::Relaxed);
-        let num_requests =
-            cmp::min(CONCURRENT_REQUESTS, (MAXIMUM_PENDING_BLOCKS - num_pending_blocks as u32) / BLOCKS_PER_FILE)
-                .saturating_sub(active_request_count);
-
-        // Spawn concurrent requests for bundles of blocks.
-        for i in 0..num_requests {
-            let start = start + i * BLOCKS_PER_FILE;
-            let end = start + BLOCKS_PER_FILE;
-
-            // If this request would breach the upper limit, stop downloading.
-            if end > cdn_end + BLOCKS_PER_FILE {
-                debug!("Finishing network requests to the CDN...");
-                break;
-            }
+    debug!("Finished network requests to the CDN");
+}
 
-            let client_clone = client.clone();
-            let base_url_clone = base_url.clone();
-            let pending_blocks_clone = pending_blocks.clone();
-            let active_requests_clone = active_requests.clone();
-            let shutdown_clone = shutdown.clone();
-            tokio::spawn(async move {
-                // Increment the number of active requests.
-                active_requests_clone.fetch_add(1, Ordering::Relaxed);
-
-                let ctx = format!("blocks {start} to {end}");
-                debug!("Requesting {ctx} (of {cdn_end})");
-
-                // Prepare the URL.
-                let blocks_url = format!("{base_url_clone}/{start}.{end}.blocks");
-                let ctx = format!("blocks {start} to {end}");
-                // Download blocks, retrying on failure.
-                let mut attempts = 0;
-                let request_time = Instant::now();
-
-                loop {
-                    // Fetch the blocks.
-                    match cdn_get(client_clone.clone(), &blocks_url, &ctx).await {
-                        Ok::<Vec<Block<N>>, _>(blocks) => {
-                            // Keep the collection of pending blocks sorted by the height.
-                            let mut pending_blocks = pending_blocks_clone.lock();
-                            for block in blocks {
-                                match pending_blocks.binary_search_by_key(&block.height(), |b| b.height()) {
-                                    Ok(_idx) => warn!("Found a duplicate pending block at height {}", block.height()),
-                                    Err(idx) => pending_blocks.insert(idx, block),
-                                }
-                            }
-                            debug!("Received {ctx} {}", format!("(in {:.2?})", request_time.elapsed()).dimmed());
-                            break;
-                        }
-                        Err(error) => {
-                            // Increment the attempt counter, and wait with a linear backoff, or abort in
-                            // case the maximum number of attempts has been breached.
-                            attempts += 1;
-                            if attempts > MAXIMUM_REQUEST_ATTEMPTS {
-                                warn!("Maximum number of requests to {blocks_url} reached - shutting down...");
-                                shutdown_clone.store(true, Ordering::Relaxed);
-                                break;
-                            }
-                            tokio::time::sleep(Duration::from_secs(attempts as u64 * 10)).await;
-                            warn!("{error} - retrying ({attempts} attempt(s) so far)");
-                        }
-                    }
+/// Downloads the bundle of blocks starting at the given height, retrying on failure with a linear backoff.
+/// On success, this function returns the start height of the bundle, along with its blocks.
+async fn download_block_bundle_with_retries<N: Network>(
+    client: &Client,
+    sources: &[CdnSource],
+    start: u32,
+    cdn_end: u32,
+    shutdown: &AtomicBool,
+) -> Result<(u32, Vec<Block<N>>)> {
+    let end = start + BLOCKS_PER_FILE;
+    let ctx = format!("blocks {start} to {end}");
+    debug!("Requesting {ctx} (of {cdn_end})");
+
+    // Download blocks, retrying on failure.
+    let mut attempts = 0;
+    let request_time = Instant::now();
+    loop {
+        // Fetch the blocks.
+        match download_block_bundle(client, sources, start, end, &ctx).await {
+            Ok(blocks) => {
+                debug!("Received {ctx} {}", format!("(in {:.2?})", request_time.elapsed()).dimmed());
+                return Ok((start, blocks));
+            }
+            Err(error) => {
+                // Increment the attempt counter, and wait with a linear backoff, or abort in
+                // case the maximum number of attempts has been breached.
+                attempts += 1;
+                if attempts > MAXIMUM_REQUEST_ATTEMPTS || shutdown.load(Ordering::Relaxed) {
+                    bail!("Maximum number of requests for {ctx} reached - {error}");
                 }
-
-                // Decrement the number of active requests.
-                active_requests_clone.fetch_sub(1, Ordering::Relaxed);
-            });
+                tokio::time::sleep(Duration::from_secs(attempts as u64 * 10)).await;
+                warn!("{error} - retrying ({attempts} attempt(s) so far)");
+            }
         }
+    }
+}
 
-        // Increase the starting block height for the subsequent requests.
-        start += BLOCKS_PER_FILE * num_requests;
+/// Ensures the given bundle holds the consecutive blocks from the given height onward,
+/// and that each block links to the one before it, including the last block of the previous bundle.
+fn verify_block_bundle<N: Network>(
+    blocks: &[Block<N>],
+    start: u32,
+    previous_hash: &mut Option<N::BlockHash>,
+) -> Result<()> {
+    if blocks.len() != BLOCKS_PER_FILE as usize {
+        bail!("Expected {BLOCKS_PER_FILE} blocks from block {start}, found {}", blocks.len());
+    }
+    for (height, block) in (start..).zip(blocks) {
+        if block.height() != height {
+            bail!("Expected block {height} from the CDN, found block {}", block.height());
+        }
+        if previous_hash.as_ref().is_some_and(|hash| *hash != block.previous_hash()) {
+            bail!("Block {height} from the CDN does not link to the previous block");
+        }
+        *previous_hash = Some(block.hash());
+    }
+    Ok(())
+}
 
-        // A short sleep in order to allow some block processing to happen in the meantime.
-        tokio::time::sleep(Duration::from_secs(1)).await;
+/// Downloads the given bundle of blocks from the CDNs that have it, and cross-verifies the bundle across them.
+///
+/// The block hashes serve as the checksum of a bundle. A bundle is accepted once two CDNs agree on it,
+/// or if only one CDN was able to provide it. The CDNs are tried in turn, starting from an offset
+/// based on the bundle, so that concurrent downloads are spread across the CDNs.
+async fn download_block_bundle<N: Network>(
+    client: &Client,
+    sources: &[CdnSource],
+    start: u32,
+    end: u32,
+    ctx: &str,
+) -> Result<Vec<Block<N>>> {
+    // Select the CDNs that have synced past the bundle.
+    let sources = sources.iter().filter(|source| source.height >= end).collect::<Vec<_>>();
+    if sources.is_empty() {
+        bail!("No CDN has synced up to {ctx}");
+    }
+    // Determine the CDN to start from.
+    let offset = (start / BLOCKS_PER_FILE) as usize % sources.len();
+
+    // The bundles received so far, along with their block hashes.
+    let mut bundles: Vec<(Vec<N::BlockHash>, Vec<Block<N>>)> = Vec::new();
+    let mut last_error = None;
+    for source in sources.iter().cycle().skip(offset).take(sources.len()) {
+        // Fetch the blocks, failing over to the next CDN on error.
+        let url = format!("{}/{start}.{end}.blocks", source.base_url);
+        let blocks: Vec<Block<N>> = match cdn_get(client.clone(), &url, ctx).await {
+            Ok(blocks) => blocks,
+            Err(error) => {
+                warn!("{error} from '{}' - trying the next CDN", source.base_url);
+                last_error = Some(error);
+                continue;
+            }
+        };
+        // Accept the bundle once two CDNs agree on it.
+        let hashes = blocks.iter().map(|block| block.hash()).collect::<Vec<_>>();
+        if bundles.iter().any(|(other_hashes, _)| *other_hashes == hashes) {
+            return Ok(blocks);
+        }
+        if !bundles.is_empty() {
+            warn!("The CDN at '{}' disagrees with the other CDNs on {ctx}", source.base_url);
+        }
+        bundles.push((hashes, blocks));
     }
 
-    debug!("Finished network requests to the CDN");
+    match bundles.len() {
+        // If no CDN provided the bundle, return the last error.
+        0 => Err(last_error.unwrap_or_else(|| anyhow!("Failed to fetch {ctx}"))),
+        // If only one CDN provided the bundle, it cannot be cross-verified.
+        1 => {
+            if sources.len() > 1 {
+                warn!("Unable to cross-verify {ctx}, as only one CDN provided it");
+            }
+            Ok(bundles.remove(0).1)
+        }
+        // If the CDNs disagree on the bundle, it cannot be trusted.
+        _ => bail!("The CDNs provided conflicting versions of {ctx}"),
+    }
 }
 
 /// Retrieves the CDN height with the given base URL.
@@ -404,50 +492,32 @@ async fn cdn_get<T: 'static + DeserializeOwned + Send>(client: Client, url: &str
     }
 }
 
-/// Logs the progress of the sync.
-fn log_progress<const OBJECTS_PER_FILE: u32>(
-    timer: Instant,
-    current_index: u32,
-    cdn_start: u32,
-    mut cdn_end: u32,
-    object_name: &str,
-) {
-    // Subtract 1, as the end of the range is exclusive.
-    cdn_end -= 1;
-    // Compute the percentage completed.
-    let percentage = current_index * 100 / cdn_end;
-    // Compute the number of files processed so far.
-    let num_files_done = 1 + (current_index - cdn_start) / OBJECTS_PER_FILE;
-    // Compute the number of files remaining.
-    let num_files_remaining = 1 + (cdn_end.saturating_sub(current_index)) / OBJECTS_PER_FILE;
-    // Compute the milliseconds per file.
-    let millis_per_file = timer.elapsed().as_millis() / num_files_done as u128;
-    // Compute the heuristic slowdown factor (in millis).
-    let slowdown = 100 * num_files_remaining as u128;
-    // Compute the time remaining (in millis).
-    let time_remaining = num_files_remaining as u128 * millis_per_file + slowdown;
-    // Prepare the estimate message (in secs).
-    let estimate = format!("(est. {} minutes remaining)", time_remaining / (60 * 1000));
-    // Log the progress.
-    info!("Synced up to {object_name} {current_index} of {cdn_end} - {percentage}% complete {}", estimate.dimmed());
-}
-
 #[cfg(test)]
 mod tests {
     use crate::{
-        blocks::{cdn_get, cdn_height, log_progress, BLOCKS_PER_FILE},
+        blocks::{cdn_get, cdn_height, BLOCKS_PER_FILE},
         load_blocks,
+        CdnConfig,
+        CdnSyncProgress,
     };
     use snarkvm::prelude::{block::Block, Testnet3};
 
     use parking_lot::RwLock;
-    use std::{sync::Arc, time::Instant};
+    use std::sync::Arc;
 
     type CurrentNetwork = Testnet3;
 
     const TEST_BASE_URL: &str = "https://s3.us-west-1.amazonaws.com/testnet3.blocks/phase3";
 
     fn check_load_blocks(start: u32, end: Option<u32>, expected: usize) {
+        check_load_blocks_from(&[TEST_BASE_URL.to_string()], start, end, expected)
+    }
+
+    fn check_load_blocks_from(base_urls: &[String], start: u32, end: Option<u32>, expected: usize) {
+        check_load_blocks_with(&CdnConfig::new(base_urls.to_vec()), start, end, expected)
+    }
+
+    fn check_load_blocks_with(config: &CdnConfig, start: u32, end: Option<u32>, expected: usize) {
         let blocks = Arc::new(RwLock::new(Vec::new()));
         let blocks_clone = blocks.clone();
         let process = move |block: Block<CurrentNetwork>| {
@@ -457,10 +527,13 @@ mod tests {
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            let completed_height = load_blocks(TEST_BASE_URL, start, end, Default::default(), process).await.unwrap();
+            let progress = CdnSyncProgress::default();
+            let completed_height =
+                load_blocks(config, start, end, progress.clone(), Default::default(), process).await.unwrap();
             assert_eq!(blocks.read().len(), expected);
             if expected > 0 {
                 assert_eq!(blocks.read().last().unwrap().height(), completed_height);
+                assert!(progress.status().unwrap().is_complete);
             }
             // Check they are sequential.
             for (i, block) in blocks.read().iter().enumerate() {
@@ -497,6 +570,23 @@ mod tests {
         check_load_blocks(start_height, end_height, 188);
     }
 
+    #[test]
+    fn test_load_blocks_with_multiple_cdns() {
+        // Ensure the blocks are cross-verified across the CDNs.
+        let base_urls = [TEST_BASE_URL.to_string(), TEST_BASE_URL.to_string()];
+        check_load_blocks_from(&base_urls, 0, Some(123), 123);
+        // Ensure an unreachable CDN is failed over.
+        let base_urls = ["http://127.0.0.1:1".to_string(), TEST_BASE_URL.to_string()];
+        check_load_blocks_from(&base_urls, 46, Some(234), 188);
+    }
+
+    #[test]
+    fn test_load_blocks_with_bounded_pipeline() {
+        // Ensure the blocks are loaded in order with a single download and a single pending bundle at a time.
+        let config = CdnConfig { concurrency: 1, max_pending_bundles: 1, ..CdnConfig::new(vec![TEST_BASE_URL.into()]) };
+        check_load_blocks_with(&config, 46, Some(234), 188);
+    }
+
     #[test]
     fn test_cdn_height() {
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -517,24 +607,4 @@ mod tests {
             assert!(height > 0);
         });
     }
-
-    #[test]
-    fn test_log_progress() {
-        // This test sanity checks that basic arithmetic is correct (i.e. no divide by zero, etc.).
-        let timer = Instant::now();
-        let cdn_start = 0;
-        let cdn_end = 100;
-        let object_name = "blocks";
-        log_progress::<10>(timer, 0, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 10, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 20, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 30, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 40, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 50, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 60, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 70, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 80, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 90, cdn_start, cdn_end, object_name);
-        log_progress::<10>(timer, 100, cdn_start, cdn_end, object_name);
-    }
 }
@@ -18,4 +18,13 @@
 extern crate tracing;
 
 mod blocks;
-pub use blocks::{load_blocks, sync_ledger_with_cdn};
+pub use blocks::{
+    load_blocks,
+    sync_ledger_with_cdn,
+    CdnConfig,
+    DEFAULT_CDN_CONCURRENCY,
+    DEFAULT_CDN_MAX_PENDING_BUNDLES,
+};
+
+mod progress;
+pub use progress::*;
@@ -0,0 +1,154 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Deserialize, Serialize};
+
+use colored::Colorize;
+use parking_lot::Mutex;
+use std::{sync::Arc, time::Instant};
+
+/// A summary of the progress of a sync from the CDN.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CdnSyncStatus {
+    /// The height of the first block to sync.
+    pub start_height: u32,
+    /// The height of the last synced block.
+    pub current_height: u32,
+    /// The height of the last block to sync.
+    pub end_height: u32,
+    /// The average number of blocks synced per second.
+    pub blocks_per_sec: f64,
+    /// The estimated number of seconds until the sync completes, once the rate is known.
+    pub eta_in_secs: Option<u64>,
+    /// Whether the sync reached the last block.
+    pub is_complete: bool,
+}
+
+/// The state of a sync from the CDN.
+struct SyncState {
+    /// The height of the first block to sync.
+    start_height: u32,
+    /// The height of the last synced block.
+    current_height: u32,
+    /// The height of the last block to sync.
+    end_height: u32,
+    /// The time at which the sync started.
+    started_at: Instant,
+    /// The time at which the last block was synced.
+    updated_at: Instant,
+}
+
+/// The progress of a sync from the CDN, which is shared with the observers of the sync.
+#[derive(Clone, Default)]
+pub struct CdnSyncProgress {
+    /// The state of the sync, once it started.
+    state: Arc<Mutex<Option<SyncState>>>,
+}
+
+impl CdnSyncProgress {
+    /// Starts tracking a sync of the blocks from `start_height` to `end_height` (inclusive).
+    pub(crate) fn start(&self, start_height: u32, end_height: u32) {
+        let now = Instant::now();
+        *self.state.lock() = Some(SyncState {
+            start_height,
+            current_height: start_height.saturating_sub(1),
+            end_height,
+            started_at: now,
+            updated_at: now,
+        });
+    }
+
+    /// Records that the block at the given height was synced.
+    pub(crate) fn update(&self, height: u32) {
+        if let Some(state) = &mut *self.state.lock() {
+            state.current_height = height;
+            state.updated_at = Instant::now();
+        }
+    }
+
+    /// Returns a summary of the progress of the sync, if it started.
+    pub fn status(&self) -> Option<CdnSyncStatus> {
+        let state = self.state.lock();
+        let state = state.as_ref()?;
+        // Measure the rate up to the last synced block, so that it does not decay once the sync is complete.
+        let num_synced = (state.current_height + 1).saturating_sub(state.start_height);
+        let elapsed = state.updated_at.duration_since(state.started_at).as_secs_f64();
+        let blocks_per_sec = if elapsed > 0.0 { num_synced as f64 / elapsed } else { 0.0 };
+        let num_remaining = state.end_height.saturating_sub(state.current_height);
+        let eta_in_secs = (blocks_per_sec > 0.0).then(|| (num_remaining as f64 / blocks_per_sec).ceil() as u64);
+        Some(CdnSyncStatus {
+            start_height: state.start_height,
+            current_height: state.current_height,
+            end_height: state.end_height,
+            blocks_per_sec,
+            eta_in_secs,
+            is_complete: state.current_height >= state.end_height,
+        })
+    }
+
+    /// Logs the progress of the sync.
+    pub(crate) fn log(&self) {
+        let Some(status) = self.status() else {
+            return;
+        };
+        // Compute the percentage completed.
+        let percentage = status.current_height as u64 * 100 / status.end_height.max(1) as u64;
+        // Prepare the rate and estimate message.
+        let estimate = match status.eta_in_secs {
+            Some(secs) => format!("({:.1} blocks/s, est. {} minutes remaining)", status.blocks_per_sec, secs / 60),
+            None => "(estimating the time remaining)".to_string(),
+        };
+        info!(
+            "Synced up to block {} of {} - {percentage}% complete {}",
+            status.current_height,
+            status.end_height,
+            estimate.dimmed()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_sync_progress() {
+        let progress = CdnSyncProgress::default();
+        assert!(progress.status().is_none());
+        // Logging before the sync starts is a no-op.
+        progress.log();
+
+        progress.start(100, 299);
+        let status = progress.status().unwrap();
+        assert_eq!(status.current_height, 99);
+        assert_eq!(status.eta_in_secs, None);
+        assert!(!status.is_complete);
+        progress.log();
+
+        // Sync 100 blocks, and check the rate and the estimate.
+        std::thread::sleep(Duration::from_millis(100));
+        progress.update(199);
+        let status = progress.status().unwrap();
+        assert!(status.blocks_per_sec > 0.0);
+        assert!(status.eta_in_secs.is_some());
+        progress.log();
+
+        // Once the last block is synced, the sync is complete.
+        progress.update(299);
+        let status = progress.status().unwrap();
+        assert!(status.is_complete);
+        assert_eq!(status.eta_in_secs, Some(0));
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+//! The clock from which the loops of the node take their time, e.g. the router heartbeat, the pings of the primary
+//! and its workers, and the sleeps of the sync modules.
+//!
+//! The clock is the clock of the Tokio runtime, so the sleeps, the instants, and the UTC timestamps of a node all
+//! advance together. With the `test` feature, a test may pause the clock of its runtime, upon which the clock becomes
+//! a deterministic mock: it only advances when the test calls [`advance`], or when the runtime is otherwise idle.
+//! The UTC timestamps are derived from the clock of the runtime in that case, so that they advance with it.
+
+use std::time::Duration;
+use time::OffsetDateTime;
+
+pub use tokio::time::Instant;
+#[cfg(feature = "test")]
+pub use tokio::time::{advance, pause, resume};
+
+/// Returns the current instant of the clock.
+pub fn now() -> Instant {
+    Instant::now()
+}
+
+/// Returns the current UTC epoch timestamp in seconds.
+#[cfg(not(feature = "test"))]
+pub fn unix_timestamp() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+/// Returns the current UTC epoch timestamp in seconds, which advances with the (possibly paused) clock.
+#[cfg(feature = "test")]
+pub fn unix_timestamp() -> i64 {
+    use once_cell::sync::Lazy;
+
+    /// The UTC time and the instant of the clock, when the clock was first read.
+    static ORIGIN: Lazy<(OffsetDateTime, std::time::Instant)> =
+        Lazy::new(|| (OffsetDateTime::now_utc(), std::time::Instant::now()));
+
+    let (origin_time, origin_instant) = *ORIGIN;
+    let now = Instant::now().into_std();
+    match now.checked_duration_since(origin_instant) {
+        Some(elapsed) => (origin_time + elapsed).unix_timestamp(),
+        None => (origin_time - origin_instant.duration_since(now)).unix_timestamp(),
+    }
+}
+
+/// Waits until the given duration has elapsed on the clock.
+pub async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_paused_clock() {
+        let start = now();
+        let real_start = std::time::Instant::now();
+
+        // A paused clock does not wait for the wall clock, as it advances while the runtime is idle.
+        sleep(Duration::from_secs(3600)).await;
+        assert_eq!(now() - start, Duration::from_secs(3600));
+        assert!(real_start.elapsed() < Duration::from_secs(60));
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test(start_paused = true)]
+    async fn test_paused_unix_timestamp() {
+        let start = unix_timestamp();
+
+        // The UTC timestamps advance with the clock.
+        sleep(Duration::from_secs(3600)).await;
+        assert!((3600..=3601).contains(&(unix_timestamp() - start)));
+        advance(Duration::from_secs(60)).await;
+        assert!((3660..=3661).contains(&(unix_timestamp() - start)));
+    }
+}
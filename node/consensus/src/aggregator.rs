@@ -0,0 +1,254 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::coinbase::{ProverSolution, PuzzleCommitment},
+    prelude::Network,
+};
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// The default number of seconds for which the solutions of an epoch are buffered, while they do not meet
+/// the coinbase target, before they are released for inclusion regardless.
+pub const DEFAULT_SOLUTION_AGGREGATION_DEADLINE_IN_SECS: u64 = 30;
+/// The default maximum number of solutions in the aggregation buffer.
+pub const DEFAULT_SOLUTION_AGGREGATION_CAPACITY: usize = 1 << 10;
+
+/// The state of the aggregation buffer, which is guarded by a single lock.
+struct AggregatorState<N: Network> {
+    /// The epoch number of the buffered solutions.
+    epoch_number: u32,
+    /// The buffered solutions and their proof targets, in arrival order.
+    solutions: IndexMap<PuzzleCommitment<N>, (ProverSolution<N>, u64)>,
+    /// The time at which the oldest buffered solution arrived.
+    oldest_arrival: Option<Instant>,
+}
+
+/// A buffer that aggregates the prover solutions of the current epoch before they are included in a block.
+///
+/// Instead of including the solutions as they arrive, the buffer defers them until their combined proof target
+/// meets the remaining coinbase target, and then releases the fewest solutions that meet it, taking the highest
+/// proof targets first, and the earliest arrivals among equal targets. If the target is not met before the
+/// deadline, every buffered solution is released, so that no prover is left out of the reward. The solutions
+/// that are not released stay buffered towards the next coinbase, until the epoch ends.
+///
+/// The buffer holds up to a maximum number of solutions. Once it is full, a new solution only enters it by
+/// evicting the buffered solution with the lowest proof target, if its own proof target is higher.
+pub struct SolutionAggregator<N: Network> {
+    /// The state of the buffer.
+    state: Mutex<AggregatorState<N>>,
+    /// The duration for which the solutions are buffered, while they do not meet the coinbase target.
+    deadline: Duration,
+    /// The maximum number of buffered solutions.
+    capacity: usize,
+}
+
+impl<N: Network> SolutionAggregator<N> {
+    /// Initializes a new aggregation buffer, with the given deadline and maximum number of solutions.
+    pub fn new(deadline: Duration, capacity: usize) -> Self {
+        let state = AggregatorState { epoch_number: 0, solutions: Default::default(), oldest_arrival: None };
+        Self { state: Mutex::new(state), deadline, capacity: capacity.max(1) }
+    }
+
+    /// Returns the number of buffered solutions.
+    pub fn len(&self) -> usize {
+        self.state.lock().solutions.len()
+    }
+
+    /// Returns `true` if there are no buffered solutions.
+    pub fn is_empty(&self) -> bool {
+        self.state.lock().solutions.is_empty()
+    }
+
+    /// Buffers the given solution of the given epoch, with its proof target.
+    /// Returns `false` if the solution is already buffered, belongs to a past epoch, or the buffer is full
+    /// and the proof target of the solution does not exceed the lowest buffered proof target.
+    ///
+    /// A solution of a new epoch discards the solutions of the previous one, as they can no longer be included.
+    pub fn insert(&self, epoch_number: u32, solution: ProverSolution<N>, proof_target: u64) -> bool {
+        let mut state = self.state.lock();
+        if epoch_number < state.epoch_number {
+            return false;
+        }
+        if epoch_number > state.epoch_number {
+            state.epoch_number = epoch_number;
+            state.solutions.clear();
+            state.oldest_arrival = None;
+        }
+        let commitment = solution.commitment();
+        if state.solutions.contains_key(&commitment) {
+            return false;
+        }
+        if state.solutions.len() >= self.capacity {
+            // Evict the earliest of the solutions with the lowest proof target, if it is lower than the new one.
+            let lowest = state
+                .solutions
+                .values()
+                .enumerate()
+                .min_by_key(|(_, (_, target))| *target)
+                .map(|(index, (_, target))| (index, *target));
+            match lowest {
+                Some((index, lowest_target)) if lowest_target < proof_target => {
+                    state.solutions.shift_remove_index(index);
+                }
+                _ => return false,
+            }
+        }
+        state.solutions.insert(commitment, (solution, proof_target));
+        state.oldest_arrival.get_or_insert_with(Instant::now);
+        true
+    }
+
+    /// Removes and returns the solutions to include, given the remaining coinbase target and the maximum number
+    /// of solutions in a block, or `None` if their inclusion is deferred.
+    pub fn select(&self, remaining_target: u64, max_solutions: usize) -> Option<Vec<ProverSolution<N>>> {
+        let mut state = self.state.lock();
+        let is_expired = state.oldest_arrival.map_or(false, |arrival| arrival.elapsed() >= self.deadline);
+        let targets = state.solutions.values().map(|(_, proof_target)| *proof_target).collect::<Vec<_>>();
+        let indices = select_by_target(&targets, remaining_target, max_solutions, is_expired)?;
+
+        // Remove the selected solutions, keeping the remaining ones in arrival order.
+        let mut selected = Vec::with_capacity(indices.len());
+        for index in indices.iter().rev() {
+            if let Some((_, (solution, _))) = state.solutions.shift_remove_index(*index) {
+                selected.push(solution);
+            }
+        }
+        selected.reverse();
+        // Restart the deadline for the remaining solutions.
+        state.oldest_arrival = (!state.solutions.is_empty()).then(Instant::now);
+        Some(selected)
+    }
+}
+
+/// Returns the indices (in ascending order) of the fewest proof targets whose sum meets the remaining target,
+/// taking the highest targets first, and the earliest among equal targets. Once the deadline has passed, the
+/// indices of the highest targets are returned, up to the maximum, even if they do not meet the remaining target.
+/// Returns `None` if the selection is deferred, i.e. the targets do not meet the remaining target yet.
+fn select_by_target(
+    targets: &[u64],
+    remaining_target: u64,
+    max_solutions: usize,
+    is_expired: bool,
+) -> Option<Vec<usize>> {
+    if targets.is_empty() || max_solutions == 0 {
+        return None;
+    }
+    // Order the targets from highest to lowest, keeping the arrival order among equal targets.
+    let mut order = (0..targets.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| targets[*b].cmp(&targets[*a]));
+
+    let mut combined_target = 0u128;
+    let mut selected = Vec::new();
+    for index in order.into_iter().take(max_solutions) {
+        combined_target += targets[index] as u128;
+        selected.push(index);
+        if combined_target >= remaining_target as u128 {
+            selected.sort_unstable();
+            return Some(selected);
+        }
+    }
+    // The targets do not meet the remaining target, so they are released only once the deadline has passed.
+    match is_expired {
+        true => {
+            selected.sort_unstable();
+            Some(selected)
+        }
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        algorithms::polycommit::kzg10::{KZGCommitment, KZGProof},
+        ledger::coinbase::PartialSolution,
+        prelude::{Address, PrivateKey, Rng, TestRng, Testnet3},
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    /// Samples a prover solution, whose proof is not valid.
+    fn sample_solution(rng: &mut TestRng) -> ProverSolution<CurrentNetwork> {
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let partial_solution = PartialSolution::new(address, rng.gen(), KZGCommitment(rng.gen()));
+        ProverSolution::new(partial_solution, KZGProof { w: rng.gen(), random_v: None })
+    }
+
+    #[test]
+    fn test_insert() {
+        let rng = &mut TestRng::default();
+        let aggregator = SolutionAggregator::<CurrentNetwork>::new(Duration::from_secs(30), 10);
+
+        // Ensure a solution is buffered once.
+        let solution = sample_solution(rng);
+        assert!(aggregator.insert(1, solution, 10));
+        assert!(!aggregator.insert(1, solution, 10));
+        assert_eq!(aggregator.len(), 1);
+
+        // Ensure a solution of a past epoch is refused.
+        assert!(!aggregator.insert(0, sample_solution(rng), 10));
+        // Ensure a solution of a new epoch discards the solutions of the previous one.
+        assert!(aggregator.insert(2, sample_solution(rng), 10));
+        assert_eq!(aggregator.len(), 1);
+    }
+
+    #[test]
+    fn test_insert_is_capped() {
+        let rng = &mut TestRng::default();
+        let aggregator = SolutionAggregator::<CurrentNetwork>::new(Duration::from_secs(30), 3);
+
+        let solutions = (0..3).map(|_| sample_solution(rng)).collect::<Vec<_>>();
+        for (solution, target) in solutions.iter().zip([20, 10, 10]) {
+            assert!(aggregator.insert(1, *solution, target));
+        }
+        // Ensure a full buffer refuses a solution that does not exceed the lowest proof target.
+        assert!(!aggregator.insert(1, sample_solution(rng), 10));
+        assert_eq!(aggregator.len(), 3);
+
+        // Ensure a full buffer evicts the earliest of the lowest proof targets for a higher one.
+        let solution = sample_solution(rng);
+        assert!(aggregator.insert(1, solution, 15));
+        assert_eq!(aggregator.len(), 3);
+        let selected = aggregator.select(u64::MAX, 10);
+        assert!(selected.is_none());
+        let state = aggregator.state.lock();
+        let buffered = state.solutions.keys().copied().collect::<Vec<_>>();
+        assert_eq!(buffered, vec![solutions[0].commitment(), solutions[2].commitment(), solution.commitment()]);
+    }
+
+    #[test]
+    fn test_select_by_target() {
+        let targets = [10, 40, 25, 40, 5];
+
+        // Ensure the fewest solutions that meet the target are selected, with the earliest among equal targets.
+        assert_eq!(select_by_target(&targets, 40, 10, false), Some(vec![1]));
+        assert_eq!(select_by_target(&targets, 65, 10, false), Some(vec![1, 3]));
+        assert_eq!(select_by_target(&targets, 100, 10, false), Some(vec![1, 2, 3]));
+        assert_eq!(select_by_target(&targets, 0, 10, false), Some(vec![1]));
+
+        // Ensure the selection is deferred while the target is not met, and released once the deadline passes.
+        assert_eq!(select_by_target(&targets, 121, 10, false), None);
+        assert_eq!(select_by_target(&targets, 121, 10, true), Some(vec![0, 1, 2, 3, 4]));
+
+        // Ensure the maximum number of solutions is respected.
+        assert_eq!(select_by_target(&targets, 100, 2, false), None);
+        assert_eq!(select_by_target(&targets, 100, 2, true), Some(vec![1, 3]));
+        assert_eq!(select_by_target(&targets, 10, 0, true), None);
+        assert_eq!(select_by_target(&[], 10, 10, true), None);
+    }
+}
@@ -0,0 +1,418 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{
+        block::Block,
+        narwhal::{Subdag, Transmission, TransmissionID},
+    },
+    prelude::{bail, error, Address, Field, FromBytes, Network, ToBytes},
+};
+
+use aleo_std::StorageMode;
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+/// A summary of a committed subdag, and the block it resulted in.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct ConsensusOutput<N: Network> {
+    /// The anchor round of the committed subdag.
+    pub round: u64,
+    /// The address of the leader for the anchor round.
+    pub leader: Address<N>,
+    /// The batch certificate IDs of the committed subdag, in commit order.
+    pub certificate_ids: Vec<Field<N>>,
+    /// The transaction IDs, in the order they were output by the BFT.
+    pub transaction_ids: Vec<N::TransactionID>,
+    /// The height of the resulting block.
+    pub block_height: u32,
+    /// The hash of the resulting block.
+    pub block_hash: N::BlockHash,
+}
+
+impl<N: Network> ConsensusOutput<N> {
+    /// Initializes a new consensus output from the committed subdag, its ordered transaction IDs, and the resulting block.
+    pub fn new(subdag: &Subdag<N>, transaction_ids: Vec<N::TransactionID>, block: &Block<N>) -> Self {
+        Self {
+            round: subdag.anchor_round(),
+            leader: subdag.leader_certificate().author(),
+            certificate_ids: subdag.values().flatten().map(|certificate| certificate.id()).collect(),
+            transaction_ids,
+            block_height: block.height(),
+            block_hash: block.hash(),
+        }
+    }
+
+    /// Returns the transaction IDs in the given transmissions, in order.
+    pub fn transaction_ids(transmissions: &IndexMap<TransmissionID<N>, Transmission<N>>) -> Vec<N::TransactionID> {
+        transmissions
+            .keys()
+            .filter_map(|transmission_id| match transmission_id {
+                TransmissionID::Transaction(transaction_id) => Some(*transaction_id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl<N: Network> ToBytes for ConsensusOutput<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        self.round.write_le(&mut writer)?;
+        self.leader.write_le(&mut writer)?;
+        u32::try_from(self.certificate_ids.len()).map_err(error)?.write_le(&mut writer)?;
+        for certificate_id in &self.certificate_ids {
+            certificate_id.write_le(&mut writer)?;
+        }
+        u32::try_from(self.transaction_ids.len()).map_err(error)?.write_le(&mut writer)?;
+        for transaction_id in &self.transaction_ids {
+            transaction_id.write_le(&mut writer)?;
+        }
+        self.block_height.write_le(&mut writer)?;
+        self.block_hash.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for ConsensusOutput<N> {
+    fn read_le<R: Read>(mut reader: R) -> io::Result<Self> {
+        let round = u64::read_le(&mut reader)?;
+        let leader = Address::read_le(&mut reader)?;
+        let num_certificates = u32::read_le(&mut reader)?;
+        let certificate_ids =
+            (0..num_certificates).map(|_| Field::read_le(&mut reader)).collect::<io::Result<Vec<_>>>()?;
+        let num_transactions = u32::read_le(&mut reader)?;
+        let transaction_ids =
+            (0..num_transactions).map(|_| N::TransactionID::read_le(&mut reader)).collect::<io::Result<Vec<_>>>()?;
+        let block_height = u32::read_le(&mut reader)?;
+        let block_hash = N::BlockHash::read_le(&mut reader)?;
+
+        Ok(Self { round, leader, certificate_ids, transaction_ids, block_height, block_hash })
+    }
+}
+
+/// The marker at the start of each archive entry, from which a scan resumes after a corrupt entry.
+const ENTRY_MARKER: [u8; 4] = *b"SCAE";
+/// The size of the header of each archive entry: the marker, the length of the output, and its CRC-32 checksum.
+const ENTRY_HEADER_SIZE: usize = 12;
+
+/// An append-only archive of consensus outputs, indexed by anchor round.
+///
+/// Each entry is stored as a header, with a marker, the little-endian `u32` length of the serialized output,
+/// and its CRC-32 checksum, followed by the serialized output. Only the byte offsets of the entries are kept in
+/// memory, so the archive can grow with the ledger. A corrupt entry is skipped when the archive is opened,
+/// and the entries after it are kept.
+pub struct ConsensusArchive<N: Network> {
+    /// The archive file.
+    file: Mutex<File>,
+    /// The map of anchor rounds to the byte offset of their entry in the archive file.
+    offsets: RwLock<BTreeMap<u64, u64>>,
+    /// The path to the archive file.
+    path: PathBuf,
+    /// PhantomData.
+    _phantom: std::marker::PhantomData<N>,
+}
+
+impl<N: Network> ConsensusArchive<N> {
+    /// The name of the archive file, which is stored inside the ledger directory.
+    pub const FILE_NAME: &'static str = "consensus-archive";
+
+    /// Opens the consensus archive for the ledger in the given storage mode.
+    pub fn open(storage_mode: StorageMode) -> Result<Self> {
+        Self::open_at(aleo_std::aleo_ledger_dir(N::ID, storage_mode).join(Self::FILE_NAME))
+    }
+
+    /// Opens the consensus archive at the given path, creating it if it does not exist.
+    pub fn open_at(path: PathBuf) -> Result<Self> {
+        // Ensure the parent directory exists.
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Open the archive file.
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        // Load the offsets of the existing entries.
+        let offsets = Self::load_offsets(&mut file, &path, true)?;
+
+        Ok(Self { file: Mutex::new(file), offsets: RwLock::new(offsets), path, _phantom: Default::default() })
+    }
+
+    /// Opens the existing consensus archive at the given path for reading only.
+    /// The archive file is left untouched, i.e. a partially-written trailing entry is not truncated,
+    /// and any attempt to insert into the archive fails.
+    pub fn open_read_only(path: PathBuf) -> Result<Self> {
+        // Open the archive file.
+        let mut file = OpenOptions::new().read(true).open(&path)?;
+        // Load the offsets of the existing entries.
+        let offsets = Self::load_offsets(&mut file, &path, false)?;
+
+        Ok(Self { file: Mutex::new(file), offsets: RwLock::new(offsets), path, _phantom: Default::default() })
+    }
+
+    /// Returns the number of archived consensus outputs.
+    pub fn len(&self) -> usize {
+        self.offsets.read().len()
+    }
+
+    /// Returns `true` if the archive is empty.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.read().is_empty()
+    }
+
+    /// Returns the latest archived anchor round, if one exists.
+    pub fn latest_round(&self) -> Option<u64> {
+        self.offsets.read().keys().next_back().copied()
+    }
+
+    /// Returns the archived anchor rounds, in ascending order.
+    pub fn rounds(&self) -> Vec<u64> {
+        self.offsets.read().keys().copied().collect()
+    }
+
+    /// Returns `true` if the archive contains an entry for the given anchor round.
+    pub fn contains_round(&self, round: u64) -> bool {
+        self.offsets.read().contains_key(&round)
+    }
+
+    /// Returns the consensus output for the given anchor round, if it exists.
+    pub fn get(&self, round: u64) -> Result<Option<ConsensusOutput<N>>> {
+        // Retrieve the offset of the entry.
+        let Some(offset) = self.offsets.read().get(&round).copied() else {
+            return Ok(None);
+        };
+        // Read the entry from a separate handle, so that appends are not blocked.
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; ENTRY_HEADER_SIZE];
+        file.read_exact(&mut header)?;
+        let (length, checksum) = Self::parse_header(&header).ok_or_else(|| error("Invalid entry header"))?;
+        let mut buffer = vec![0u8; length as usize];
+        file.read_exact(&mut buffer)?;
+        // Ensure the entry was not corrupted on disk.
+        if crc32fast::hash(&buffer) != checksum {
+            bail!("The archived consensus output for round {round} is corrupt")
+        }
+        Ok(Some(ConsensusOutput::read_le(&buffer[..])?))
+    }
+
+    /// Flushes the archive file to disk.
+    pub fn sync(&self) -> Result<()> {
+        Ok(self.file.lock().sync_all()?)
+    }
+
+    /// Appends the given consensus output to the archive.
+    pub fn insert(&self, output: &ConsensusOutput<N>) -> Result<()> {
+        // Serialize the output.
+        let bytes = output.to_bytes_le()?;
+        let length = u32::try_from(bytes.len())?;
+
+        // Acquire the file lock for the duration of the append.
+        let mut file = self.file.lock();
+        // Determine the offset of the new entry.
+        let offset = file.seek(SeekFrom::End(0))?;
+        // Write the entry as a single buffer.
+        let mut entry = Vec::with_capacity(ENTRY_HEADER_SIZE + bytes.len());
+        entry.extend_from_slice(&ENTRY_MARKER);
+        entry.extend_from_slice(&length.to_le_bytes());
+        entry.extend_from_slice(&crc32fast::hash(&bytes).to_le_bytes());
+        entry.extend_from_slice(&bytes);
+        file.write_all(&entry)?;
+        file.flush()?;
+        drop(file);
+
+        // Index the entry.
+        self.offsets.write().insert(output.round, offset);
+        Ok(())
+    }
+
+    /// Returns the length and the checksum of an entry from its header, if the header starts with the marker.
+    fn parse_header(header: &[u8; ENTRY_HEADER_SIZE]) -> Option<(u32, u32)> {
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let checksum = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        (header[..4] == ENTRY_MARKER).then_some((length, checksum))
+    }
+
+    /// Scans the archive file, one entry at a time, and returns the offsets of its entries.
+    ///
+    /// A corrupt entry is skipped, and the scan resumes at the next entry marker. If `truncate` is set,
+    /// the trailing bytes after the last valid entry (e.g. a partially-written entry from a crash) are truncated.
+    fn load_offsets(file: &mut File, path: &Path, truncate: bool) -> Result<BTreeMap<u64, u64>> {
+        let file_size = file.metadata()?.len();
+        file.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new(&*file);
+
+        let mut offsets = BTreeMap::new();
+        // The offset of the entry being read, and the end of the last valid entry.
+        let (mut cursor, mut valid_end) = (0u64, 0u64);
+        let mut buffer = Vec::new();
+        while cursor + ENTRY_HEADER_SIZE as u64 <= file_size {
+            // Read the header of the entry.
+            let mut header = [0u8; ENTRY_HEADER_SIZE];
+            reader.read_exact(&mut header)?;
+            if let Some((length, checksum)) = Self::parse_header(&header) {
+                let end = cursor + ENTRY_HEADER_SIZE as u64 + length as u64;
+                if end <= file_size {
+                    buffer.resize(length as usize, 0);
+                    reader.read_exact(&mut buffer)?;
+                    // If the checksum matches, the entry is framed correctly, so the scan continues after it.
+                    if crc32fast::hash(&buffer) == checksum {
+                        match ConsensusOutput::<N>::read_le(&buffer[..]) {
+                            Ok(output) => {
+                                offsets.insert(output.round, cursor);
+                            }
+                            Err(e) => {
+                                warn!("Skipping an invalid entry in the consensus archive at byte {cursor} - {e}")
+                            }
+                        }
+                        (cursor, valid_end) = (end, end);
+                        continue;
+                    }
+                }
+            }
+            // Otherwise, resume the scan at the next entry marker.
+            reader.seek(SeekFrom::Start(cursor + 1))?;
+            match Self::find_next_marker(&mut reader, cursor + 1)? {
+                Some(offset) => {
+                    warn!("Skipping {} corrupt bytes in the consensus archive at byte {cursor}", offset - cursor);
+                    reader.seek(SeekFrom::Start(offset))?;
+                    cursor = offset;
+                }
+                None => break,
+            }
+        }
+
+        // Truncate any trailing bytes that do not form a complete entry.
+        if truncate && valid_end < file_size {
+            warn!("Truncating {} trailing bytes from the consensus archive", file_size - valid_end);
+            OpenOptions::new().write(true).open(path)?.set_len(valid_end)?;
+        }
+        Ok(offsets)
+    }
+
+    /// Reads until the next entry marker, and returns its offset, given the offset of the reader.
+    fn find_next_marker(reader: &mut impl Read, mut offset: u64) -> io::Result<Option<u64>> {
+        let mut window = [0u8; 4];
+        let mut byte = [0u8; 1];
+        let mut num_read = 0;
+        while reader.read(&mut byte)? == 1 {
+            window.rotate_left(1);
+            window[3] = byte[0];
+            num_read += 1;
+            offset += 1;
+            if num_read >= window.len() && window == ENTRY_MARKER {
+                return Ok(Some(offset - window.len() as u64));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    fn sample_output(round: u64, rng: &mut TestRng) -> ConsensusOutput<CurrentNetwork> {
+        ConsensusOutput {
+            round,
+            leader: Address::rand(rng),
+            certificate_ids: (0..3).map(|_| Field::rand(rng)).collect(),
+            transaction_ids: (0..5).map(|_| Field::rand(rng).into()).collect(),
+            block_height: round as u32 / 2,
+            block_hash: Field::rand(rng).into(),
+        }
+    }
+
+    #[test]
+    fn test_archive_roundtrip_and_reopen() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-consensus-archive-{}", u64::rand(rng)));
+
+        let outputs = (1..=5).map(|i| sample_output(i * 2, rng)).collect::<Vec<_>>();
+        {
+            let archive = ConsensusArchive::<CurrentNetwork>::open_at(path.clone()).unwrap();
+            assert!(archive.is_empty());
+            for output in &outputs {
+                archive.insert(output).unwrap();
+            }
+            assert_eq!(archive.len(), outputs.len());
+            assert_eq!(archive.latest_round(), Some(10));
+            assert_eq!(archive.rounds(), vec![2, 4, 6, 8, 10]);
+            assert_eq!(archive.get(4).unwrap().as_ref(), Some(&outputs[1]));
+            assert_eq!(archive.get(5).unwrap(), None);
+        }
+
+        // Simulate a crash in the middle of an append.
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(&[42u8, 0, 0, 0, 1, 2]).unwrap();
+
+        // Reopen the archive, and ensure the entries are intact.
+        let archive = ConsensusArchive::<CurrentNetwork>::open_at(path.clone()).unwrap();
+        assert_eq!(archive.len(), outputs.len());
+        for output in &outputs {
+            assert_eq!(archive.get(output.round).unwrap().as_ref(), Some(output));
+        }
+        // Ensure a read-only archive leaves a partially-written entry in place.
+        let length = std::fs::metadata(&path).unwrap().len();
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(&[42u8, 0, 0, 0, 1, 2]).unwrap();
+        let read_only = ConsensusArchive::<CurrentNetwork>::open_read_only(path.clone()).unwrap();
+        assert_eq!(read_only.len(), outputs.len());
+        assert!(read_only.insert(&sample_output(12, rng)).is_err());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), length + 6);
+        drop(read_only);
+
+        // Reopen the archive, and ensure new entries are appended after the truncated bytes.
+        let archive = ConsensusArchive::<CurrentNetwork>::open_at(path.clone()).unwrap();
+        let output = sample_output(12, rng);
+        archive.insert(&output).unwrap();
+        assert_eq!(archive.get(12).unwrap(), Some(output));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_archive_skips_corrupt_entries() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-consensus-archive-{}", u64::rand(rng)));
+
+        let outputs = (1..=3).map(|i| sample_output(i * 2, rng)).collect::<Vec<_>>();
+        let archive = ConsensusArchive::<CurrentNetwork>::open_at(path.clone()).unwrap();
+        for output in &outputs {
+            archive.insert(output).unwrap();
+        }
+        let offset = archive.offsets.read()[&4];
+        drop(archive);
+
+        // Corrupt the output of the entry in the middle.
+        let mut bytes = std::fs::read(&path).unwrap();
+        let length = bytes.len();
+        bytes[offset as usize + ENTRY_HEADER_SIZE + 1] ^= 0xff;
+        std::fs::write(&path, &bytes).unwrap();
+
+        // Ensure the corrupt entry is skipped, and the entries after it are kept.
+        let archive = ConsensusArchive::<CurrentNetwork>::open_at(path.clone()).unwrap();
+        assert_eq!(archive.rounds(), vec![2, 6]);
+        assert_eq!(archive.get(6).unwrap().as_ref(), Some(&outputs[2]));
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), length as u64);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
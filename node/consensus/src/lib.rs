@@ -17,23 +17,38 @@
 #[macro_use]
 extern crate tracing;
 
+mod aggregator;
+pub use aggregator::*;
+
+mod archive;
+pub use archive::*;
+
+mod overflow;
+pub use overflow::*;
+
 use snarkos_account::Account;
 use snarkos_node_bft::{
     helpers::{
         fmt_id,
         init_consensus_channels,
         ConsensusReceiver,
+        DagSnapshot,
         PrimaryReceiver,
         PrimarySender,
         Storage as NarwhalStorage,
+        UpgradeTracker,
     },
     spawn_blocking,
     BFT,
+    MAX_BATCH_DELAY_IN_MS,
     MAX_GC_ROUNDS,
     MAX_TRANSMISSIONS_PER_BATCH,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_bft_storage_service::BFTPersistentStorage;
+use snarkos_node_bft_storage_service::{BFTMemoryService, BFTPersistentStorage, StorageService};
+use snarkos_node_bus::{EventBus, NodeEvent};
+use snarkos_node_clock as clock;
+use snarkos_node_sync::CheckpointStore;
 use snarkvm::{
     ledger::{
         block::Transaction,
@@ -46,14 +61,56 @@ use snarkvm::{
 use aleo_std::StorageMode;
 use anyhow::Result;
 use colored::Colorize;
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use lru::LruCache;
 use parking_lot::Mutex;
-use std::{future::Future, net::SocketAddr, num::NonZeroUsize, sync::Arc};
+use std::{
+    collections::HashSet,
+    future::Future,
+    net::SocketAddr,
+    num::NonZeroUsize,
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{
     sync::{oneshot, OnceCell},
     task::JoinHandle,
 };
+use tracing::{Instrument, Span};
+
+/// The minimum fee increase, in percent of the fees of the replaced transactions, for an unconfirmed transaction
+/// to replace the pending transactions that spend the same serial numbers.
+pub const MIN_REPLACEMENT_FEE_BUMP_PERCENT: u64 = 10;
+
+/// An unconfirmed transaction that was taken for a replacement, along with where it was waiting,
+/// so that it can be restored if the replacement is refused.
+enum Replaceable<N: Network> {
+    /// The transaction was waiting in the transactions queue.
+    Queued(Transaction<N>),
+    /// The transaction was waiting in the overflow queue.
+    Overflowed(Transaction<N>),
+    /// The transaction was waiting in the ready queue of the worker with the given ID.
+    Ready(u8, N::TransactionID, Data<Transaction<N>>),
+}
+
+impl<N: Network> Replaceable<N> {
+    /// Returns the ID of the transaction.
+    fn id(&self) -> N::TransactionID {
+        match self {
+            Self::Queued(transaction) | Self::Overflowed(transaction) => transaction.id(),
+            Self::Ready(_, transaction_id, _) => *transaction_id,
+        }
+    }
+
+    /// Returns the transaction, deserializing it if needed.
+    async fn transaction(&self) -> Result<Transaction<N>> {
+        match self {
+            Self::Queued(transaction) | Self::Overflowed(transaction) => Ok(transaction.clone()),
+            Self::Ready(_, _, transaction) => transaction.clone().deserialize().await,
+        }
+    }
+}
 
 #[allow(dead_code)]
 #[derive(Clone)]
@@ -64,14 +121,28 @@ pub struct Consensus<N: Network> {
     bft: BFT<N>,
     /// The primary sender.
     primary_sender: Arc<OnceCell<PrimarySender<N>>>,
-    /// The unconfirmed solutions queue.
-    solutions_queue: Arc<Mutex<LruCache<PuzzleCommitment<N>, ProverSolution<N>>>>,
+    /// The aggregation buffer of the unconfirmed solutions.
+    solution_aggregator: Arc<SolutionAggregator<N>>,
     /// The unconfirmed transactions queue.
     transactions_queue: Arc<Mutex<LruCache<N::TransactionID, Transaction<N>>>>,
+    /// The overflow queue for the unconfirmed transactions that do not fit in the transactions queue.
+    transactions_overflow: Arc<TransactionOverflow<N>>,
     /// The recently-seen unconfirmed solutions.
     seen_solutions: Arc<Mutex<LruCache<PuzzleCommitment<N>, ()>>>,
     /// The recently-seen unconfirmed transactions.
     seen_transactions: Arc<Mutex<LruCache<N::TransactionID, ()>>>,
+    /// The serial numbers spent by the validated unconfirmed transactions, mapped to the spending transaction ID.
+    pending_serial_numbers: Arc<Mutex<LruCache<Field<N>, N::TransactionID>>>,
+    /// The tags of the records spent by the validated unconfirmed transactions, mapped to the spending transaction ID.
+    pending_tags: Arc<Mutex<LruCache<Field<N>, N::TransactionID>>>,
+    /// The unconfirmed transactions that were replaced by a higher-fee transaction, mapped to the replacement.
+    superseded_transactions: Arc<Mutex<LruCache<N::TransactionID, N::TransactionID>>>,
+    /// The spans of the requests that submitted the unconfirmed transactions, to correlate their log lines.
+    transaction_spans: Arc<Mutex<LruCache<N::TransactionID, Span>>>,
+    /// The archive of consensus outputs.
+    archive: Arc<ConsensusArchive<N>>,
+    /// The event bus, to which the state of the BFT and the memory pool is published.
+    event_bus: Arc<OnceCell<EventBus<N>>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
@@ -90,25 +161,109 @@ impl<N: Network> Consensus<N> {
             StorageMode::Development(id) => Some(id),
             StorageMode::Production | StorageMode::Custom(..) => None,
         };
+        // Retrieve the ledger directory.
+        let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+        // Initialize the consensus archive.
+        let archive = ConsensusArchive::open(storage_mode.clone())?;
         // Initialize the Narwhal transmissions.
         let transmissions = Arc::new(BFTPersistentStorage::open(storage_mode)?);
+        // Initialize the overflow queue for the unconfirmed transactions.
+        let transactions_overflow = TransactionOverflow::open_at(
+            ledger_dir.join(TransactionOverflow::<N>::FILE_NAME),
+            DEFAULT_TRANSACTION_OVERFLOW_MAX_SIZE,
+        )?;
+        // Initialize the consensus.
+        let consensus = Self::new_with(
+            account,
+            ledger,
+            ip,
+            trusted_validators,
+            dev,
+            transmissions,
+            archive,
+            transactions_overflow,
+        )?;
+        // Store the snapshots of the DAG in the ledger directory.
+        consensus.bft.set_snapshot_path(ledger_dir.join(DagSnapshot::<N>::FILE_NAME));
+        // Store the checkpoint certificates in the ledger directory.
+        consensus.bft.primary().sync().checkpoints().open(ledger_dir.join(CheckpointStore::<N>::DIRECTORY_NAME))?;
+        // Store the signals and the decisions on the protocol upgrades in the ledger directory.
+        consensus.bft.primary().upgrades().open(ledger_dir.join(UpgradeTracker::<N>::FILE_NAME))?;
+        Ok(consensus)
+    }
+
+    /// Initializes a new instance of consensus for an ephemeral development node.
+    ///
+    /// The Narwhal transmissions are kept in memory and the DAG is never snapshotted. Only the consensus archive
+    /// and the transaction overflow queue are written, inside the given (temporary) directory.
+    pub fn new_ephemeral(
+        account: Account<N>,
+        ledger: Arc<dyn LedgerService<N>>,
+        ip: Option<SocketAddr>,
+        trusted_validators: &[SocketAddr],
+        dev: u16,
+        dir: &Path,
+    ) -> Result<Self> {
+        // Initialize the consensus archive.
+        let archive = ConsensusArchive::open_at(dir.join(ConsensusArchive::<N>::FILE_NAME))?;
+        // Initialize the Narwhal transmissions.
+        let transmissions = Arc::new(BFTMemoryService::new());
+        // Initialize the overflow queue for the unconfirmed transactions.
+        let transactions_overflow = TransactionOverflow::open_at(
+            dir.join(TransactionOverflow::<N>::FILE_NAME),
+            DEFAULT_TRANSACTION_OVERFLOW_MAX_SIZE,
+        )?;
+        // Initialize the consensus.
+        Self::new_with(
+            account,
+            ledger,
+            ip,
+            trusted_validators,
+            Some(dev),
+            transmissions,
+            archive,
+            transactions_overflow,
+        )
+    }
+
+    /// Initializes a new instance of consensus from the given storage.
+    #[allow(clippy::too_many_arguments)]
+    fn new_with(
+        account: Account<N>,
+        ledger: Arc<dyn LedgerService<N>>,
+        ip: Option<SocketAddr>,
+        trusted_validators: &[SocketAddr],
+        dev: Option<u16>,
+        transmissions: Arc<dyn StorageService<N>>,
+        archive: ConsensusArchive<N>,
+        transactions_overflow: TransactionOverflow<N>,
+    ) -> Result<Self> {
         // Initialize the Narwhal storage.
         let storage = NarwhalStorage::new(ledger.clone(), transmissions, MAX_GC_ROUNDS);
         // Initialize the BFT.
         let bft = BFT::new(account, storage, ledger.clone(), ip, trusted_validators, dev)?;
+        let (archive, transactions_overflow) = (Arc::new(archive), Arc::new(transactions_overflow));
         // Return the consensus.
         Ok(Self {
             ledger,
             bft,
             primary_sender: Default::default(),
-            solutions_queue: Arc::new(Mutex::new(LruCache::new(
-                NonZeroUsize::new(MAX_TRANSMISSIONS_PER_BATCH).unwrap(),
-            ))),
+            solution_aggregator: Arc::new(SolutionAggregator::new(
+                Duration::from_secs(DEFAULT_SOLUTION_AGGREGATION_DEADLINE_IN_SECS),
+                DEFAULT_SOLUTION_AGGREGATION_CAPACITY,
+            )),
             transactions_queue: Arc::new(Mutex::new(LruCache::new(
                 NonZeroUsize::new(MAX_TRANSMISSIONS_PER_BATCH).unwrap(),
             ))),
+            transactions_overflow,
             seen_solutions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
             seen_transactions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            pending_serial_numbers: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            pending_tags: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            superseded_transactions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            transaction_spans: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            archive,
+            event_bus: Default::default(),
             handles: Default::default(),
         })
     }
@@ -125,9 +280,16 @@ impl<N: Network> Consensus<N> {
         self.start_handlers(consensus_receiver);
         // Lastly, the consensus.
         self.bft.run(Some(consensus_sender), primary_sender, primary_receiver).await?;
+        // Publish that the BFT started.
+        self.publish(NodeEvent::BftStarted);
         Ok(())
     }
 
+    /// Sets the event bus, to which the state of the BFT and the memory pool is published.
+    pub fn set_event_bus(&self, event_bus: EventBus<N>) {
+        self.event_bus.set(event_bus).expect("Event bus already set");
+    }
+
     /// Returns the ledger.
     pub const fn ledger(&self) -> &Arc<dyn LedgerService<N>> {
         &self.ledger
@@ -138,10 +300,20 @@ impl<N: Network> Consensus<N> {
         &self.bft
     }
 
+    /// Returns the archive of consensus outputs.
+    pub fn archive(&self) -> &Arc<ConsensusArchive<N>> {
+        &self.archive
+    }
+
     /// Returns the primary sender.
     pub fn primary_sender(&self) -> &PrimarySender<N> {
         self.primary_sender.get().expect("Primary sender not set")
     }
+
+    /// Returns the span of the request that submitted the given unconfirmed transaction, or a disabled span.
+    fn transaction_span(&self, transaction_id: &N::TransactionID) -> Span {
+        self.transaction_spans.lock().get(transaction_id).cloned().unwrap_or_else(Span::none)
+    }
 }
 
 impl<N: Network> Consensus<N> {
@@ -189,56 +361,74 @@ impl<N: Network> Consensus<N> {
 }
 
 impl<N: Network> Consensus<N> {
-    /// Adds the given unconfirmed solution to the memory pool.
-    pub async fn add_unconfirmed_solution(&self, _solution: ProverSolution<N>) -> Result<()> {
-        // // Process the unconfirmed solution.
-        // {
-        //     let solution_id = solution.commitment();
-        //
-        //     // Check if the transaction was recently seen.
-        //     if self.seen_solutions.lock().put(solution_id, ()).is_some() {
-        //         // If the transaction was recently seen, return early.
-        //         return Ok(());
-        //     }
-        //     // Check if the solution already exists in the ledger.
-        //     if self.ledger.contains_transmission(&TransmissionID::from(solution_id))? {
-        //         bail!("Solution '{}' exists in the ledger {}", fmt_id(solution_id), "(skipping)".dimmed());
-        //     }
-        //     // Add the solution to the memory pool.
-        //     trace!("Received unconfirmed solution '{}' in the queue", fmt_id(solution_id));
-        //     if self.solutions_queue.lock().put(solution_id, solution).is_some() {
-        //         bail!("Solution '{}' exists in the memory pool", fmt_id(solution_id));
-        //     }
-        // }
-        //
-        // // If the memory pool of this node is full, return early.
-        // let num_unconfirmed = self.num_unconfirmed_transmissions();
-        // if num_unconfirmed > N::MAX_SOLUTIONS || num_unconfirmed > MAX_TRANSMISSIONS_PER_BATCH {
-        //     return Ok(());
-        // }
-        // // Retrieve the solutions.
-        // let solutions = {
-        //     // Determine the available capacity.
-        //     let capacity = N::MAX_SOLUTIONS.saturating_sub(num_unconfirmed);
-        //     // Acquire the lock on the queue.
-        //     let mut queue = self.solutions_queue.lock();
-        //     // Determine the number of solutions to send.
-        //     let num_solutions = queue.len().min(capacity);
-        //     // Drain the solutions from the queue.
-        //     (0..num_solutions).filter_map(|_| queue.pop_lru().map(|(_, solution)| solution)).collect::<Vec<_>>()
-        // };
-        // // Iterate over the solutions.
-        // for solution in solutions.into_iter() {
-        //     let solution_id = solution.commitment();
-        //     trace!("Adding unconfirmed solution '{}' to the memory pool...", fmt_id(solution_id));
-        //     // Send the unconfirmed solution to the primary.
-        //     if let Err(e) = self.primary_sender().send_unconfirmed_solution(solution_id, Data::Object(solution)).await {
-        //         warn!("Failed to add unconfirmed solution '{}' to the memory pool - {e}", fmt_id(solution_id));
-        //     }
-        // }
+    /// Adds the given unconfirmed solution to the aggregation buffer, from which it is sent to the memory pool
+    /// once the buffered solutions meet the coinbase target, or their deadline passes.
+    pub async fn add_unconfirmed_solution(&self, solution: ProverSolution<N>) -> Result<()> {
+        // Process the unconfirmed solution.
+        {
+            let solution_id = solution.commitment();
+
+            // Check if the solution was recently seen.
+            if self.seen_solutions.lock().put(solution_id, ()).is_some() {
+                // If the solution was recently seen, return early.
+                return Ok(());
+            }
+            // Check if the solution already exists in the ledger.
+            if self.ledger.contains_transmission(&TransmissionID::from(solution_id))? {
+                bail!("Solution '{}' exists in the ledger {}", fmt_id(solution_id), "(skipping)".dimmed());
+            }
+            // Check that the solution is valid for the current epoch, before it is buffered.
+            self.ledger.check_solution_basic(solution_id, Data::Object(solution)).await?;
+            // Compute the proof target of the solution.
+            let proof_target = spawn_blocking!(solution.to_target())?;
+            // Add the solution to the aggregation buffer of the current epoch.
+            let epoch_number = self.ledger.latest_block_height() / N::NUM_BLOCKS_PER_EPOCH;
+            trace!("Received unconfirmed solution '{}' in the aggregation buffer", fmt_id(solution_id));
+            if !self.solution_aggregator.insert(epoch_number, solution, proof_target) {
+                bail!("Solution '{}' exists in the memory pool, or is stale", fmt_id(solution_id));
+            }
+        }
+
+        // Send the aggregated solutions to the memory pool, if they are ready.
+        self.process_solution_aggregator().await;
         Ok(())
     }
 
+    /// Sends the aggregated unconfirmed solutions to the memory pool, once they meet the remaining coinbase target
+    /// of the latest block, or their aggregation deadline passes.
+    async fn process_solution_aggregator(&self) {
+        // If there are no aggregated solutions, return early.
+        if self.solution_aggregator.is_empty() {
+            return;
+        }
+        // If the memory pool of this node is full, return early.
+        let num_unconfirmed = self.num_unconfirmed_transmissions();
+        if num_unconfirmed > MAX_TRANSMISSIONS_PER_BATCH {
+            return;
+        }
+        // Determine the available capacity.
+        let capacity = N::MAX_SOLUTIONS.saturating_sub(self.num_unconfirmed_solutions());
+        // Determine the remaining coinbase target.
+        let latest_block = self.ledger.latest_block();
+        let header = latest_block.header();
+        let remaining_target = u128::from(header.coinbase_target()).saturating_sub(header.cumulative_proof_target());
+        let remaining_target = u64::try_from(remaining_target).unwrap_or(u64::MAX);
+        // Retrieve the solutions, if they are ready.
+        let Some(solutions) = self.solution_aggregator.select(remaining_target, capacity) else {
+            return;
+        };
+        debug!("Releasing {} aggregated solutions (remaining coinbase target {remaining_target})", solutions.len());
+        // Iterate over the solutions.
+        for solution in solutions.into_iter() {
+            let solution_id = solution.commitment();
+            trace!("Adding unconfirmed solution '{}' to the memory pool...", fmt_id(solution_id));
+            // Send the unconfirmed solution to the primary.
+            if let Err(e) = self.primary_sender().send_unconfirmed_solution(solution_id, Data::Object(solution)).await {
+                warn!("Failed to add unconfirmed solution '{}' to the memory pool - {e}", fmt_id(solution_id));
+            }
+        }
+    }
+
     /// Adds the given unconfirmed transaction to the memory pool.
     pub async fn add_unconfirmed_transaction(&self, transaction: Transaction<N>) -> Result<()> {
         // Process the unconfirmed transaction.
@@ -258,17 +448,57 @@ impl<N: Network> Consensus<N> {
             if self.ledger.contains_transmission(&TransmissionID::from(&transaction_id))? {
                 bail!("Transaction '{}' exists in the ledger {}", fmt_id(transaction_id), "(skipping)".dimmed());
             }
+            // If the transaction spends a serial number that is already spent in the memory pool,
+            // replace the conflicting transactions, if the transaction pays a sufficiently higher fee.
+            // Note: The serial numbers are only reserved once the transaction is validated by a worker.
+            let conflicting_ids = self.conflicting_transactions(&transaction);
+            if !conflicting_ids.is_empty() {
+                if let Err(e) = self.replace_unconfirmed_transactions(&transaction, conflicting_ids).await {
+                    #[cfg(feature = "metrics")]
+                    metrics::counter(metrics::consensus::MEMPOOL_CONFLICTS, 1);
+                    return Err(e);
+                }
+            }
             // Add the transaction to the memory pool.
             trace!("Received unconfirmed transaction '{}' in the queue", fmt_id(transaction_id));
-            if self.transactions_queue.lock().put(transaction_id, transaction).is_some() {
-                bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
+            let spilled = {
+                let mut queue = self.transactions_queue.lock();
+                if queue.contains(&transaction_id) || self.transactions_overflow.contains(&transaction_id) {
+                    bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
+                }
+                if queue.len() < queue.cap().get() {
+                    queue.put(transaction_id, transaction);
+                    None
+                } else {
+                    Some(transaction)
+                }
+            };
+            // If the queue is full, spill the transaction to the overflow queue, without holding the lock on the queue.
+            if let Some(transaction) = spilled {
+                if let Err(e) = self.transactions_overflow.push(&transaction) {
+                    bail!("Transaction '{}' was refused - {e}", fmt_id(transaction_id));
+                }
+            }
+            // Retain the span of the request that submitted the transaction, if any, to correlate its later log lines.
+            let span = Span::current();
+            if !span.is_none() {
+                self.transaction_spans.lock().put(transaction_id, span);
             }
+            debug!("Queued unconfirmed transaction '{}'", fmt_id(transaction_id));
         }
 
+        // Send the queued transactions to the memory pool.
+        self.process_transactions_queue().await;
+        Ok(())
+    }
+
+    /// Sends the queued unconfirmed transactions to the memory pool, up to its available capacity,
+    /// and refills the transactions queue from the overflow queue.
+    async fn process_transactions_queue(&self) {
         // If the memory pool of this node is full, return early.
         let num_unconfirmed = self.num_unconfirmed_transmissions();
         if num_unconfirmed > MAX_TRANSMISSIONS_PER_BATCH {
-            return Ok(());
+            return;
         }
         // Retrieve the transactions.
         let transactions = {
@@ -283,19 +513,359 @@ impl<N: Network> Consensus<N> {
                 .filter_map(|_| queue.pop_lru().map(|(_, transaction)| transaction))
                 .collect::<Vec<_>>()
         };
+        // Refill the transactions queue from the overflow queue.
+        self.refill_transactions_queue();
+        if transactions.is_empty() {
+            return;
+        }
         // Iterate over the transactions.
         for transaction in transactions.into_iter() {
             let transaction_id = transaction.id();
-            trace!("Adding unconfirmed transaction '{}' to the memory pool...", fmt_id(transaction_id));
-            // Send the unconfirmed transaction to the primary.
-            if let Err(e) =
-                self.primary_sender().send_unconfirmed_transaction(transaction_id, Data::Object(transaction)).await
-            {
-                warn!("Failed to add unconfirmed transaction '{}' to the memory pool - {e}", fmt_id(transaction_id));
+            let transaction_serial_numbers = transaction.serial_numbers().copied().collect::<Vec<_>>();
+            let transaction_tags = transaction.tags().copied().collect::<Vec<_>>();
+            // Log in the span of the request that submitted the transaction, if any.
+            let span = self.transaction_span(&transaction_id);
+            async {
+                trace!("Adding unconfirmed transaction '{}' to the memory pool...", fmt_id(transaction_id));
+                // Send the unconfirmed transaction to the primary.
+                let result =
+                    self.primary_sender().send_unconfirmed_transaction(transaction_id, Data::Object(transaction)).await;
+                // Reserve the serial numbers of the validated transaction.
+                let result = result.and_then(|()| {
+                    let (serial_numbers, tags) = (&transaction_serial_numbers, &transaction_tags);
+                    self.reserve_serial_numbers(transaction_id, serial_numbers, tags).map_err(|e| {
+                        // Remove the conflicting transaction from the memory pool.
+                        self.bft.primary().workers().iter().for_each(|worker| {
+                            worker.remove_transmission(transaction_id);
+                        });
+                        e
+                    })
+                });
+                match result {
+                    Ok(()) => {
+                        debug!("Added unconfirmed transaction '{}' to the memory pool", fmt_id(transaction_id))
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to add unconfirmed transaction '{}' to the memory pool - {e}",
+                            fmt_id(transaction_id)
+                        );
+                        self.transaction_spans.lock().pop(&transaction_id);
+                    }
+                }
+            }
+            .instrument(span)
+            .await;
+        }
+        // Publish the change to the memory pool.
+        self.publish(NodeEvent::MempoolChanged { num_unconfirmed_transmissions: self.num_unconfirmed_transmissions() });
+    }
+
+    /// Moves the spilled transactions from the overflow queue into the transactions queue, while it has room.
+    fn refill_transactions_queue(&self) {
+        // Determine the room in the transactions queue.
+        let room = {
+            let queue = self.transactions_queue.lock();
+            queue.cap().get().saturating_sub(queue.len())
+        };
+        // Read the spilled transactions, without holding the lock on the queue.
+        let mut transactions = Vec::with_capacity(room);
+        while transactions.len() < room {
+            match self.transactions_overflow.pop() {
+                Ok(Some(transaction)) => transactions.push(transaction),
+                Ok(None) => break,
+                Err(e) => warn!("Failed to read an unconfirmed transaction from the overflow queue - {e}"),
+            }
+        }
+        // Insert the transactions into the queue, and spill back any that no longer fit.
+        let mut evicted = Vec::new();
+        {
+            let mut queue = self.transactions_queue.lock();
+            for transaction in transactions {
+                let transaction_id = transaction.id();
+                if let Some((evicted_id, evicted_transaction)) = queue.push(transaction_id, transaction) {
+                    if evicted_id != transaction_id {
+                        evicted.push(evicted_transaction);
+                    }
+                }
+            }
+        }
+        for transaction in evicted {
+            if let Err(e) = self.transactions_overflow.push(&transaction) {
+                warn!("Failed to spill an unconfirmed transaction to the overflow queue - {e}");
+            }
+        }
+    }
+
+    /// Returns the number of unconfirmed transactions in the overflow queue.
+    pub fn num_overflow_transactions(&self) -> usize {
+        self.transactions_overflow.len()
+    }
+
+    /// Returns every unconfirmed transaction held by this node: those in the memory pool, then those in
+    /// the transactions queue (in arrival order), and then those in the overflow queue.
+    ///
+    /// This method deserializes the transactions and reads the overflow files, so it should be called
+    /// from a blocking thread.
+    pub fn mempool_transactions(&self) -> Result<Vec<Transaction<N>>> {
+        let mut transactions = self
+            .unconfirmed_transactions()
+            .map(|(_, transaction)| transaction.deserialize_blocking())
+            .collect::<Result<Vec<_>>>()?;
+        transactions.extend(self.transactions_queue.lock().iter().rev().map(|(_, transaction)| transaction.clone()));
+        transactions.extend(self.transactions_overflow.transactions()?);
+        Ok(transactions)
+    }
+
+    /// Returns `true` if the given transaction is held by this node, and is not yet in the ledger.
+    pub fn contains_unconfirmed_transaction(&self, transaction_id: &N::TransactionID) -> bool {
+        self.transactions_queue.lock().contains(transaction_id)
+            || self.transactions_overflow.contains(transaction_id)
+            || self.bft.primary().workers().iter().any(|worker| worker.contains_transmission(transaction_id))
+    }
+
+    /// Returns the ID of the transaction that replaced the given unconfirmed transaction, if it was superseded.
+    pub fn superseded_by(&self, transaction_id: &N::TransactionID) -> Option<N::TransactionID> {
+        self.superseded_transactions.lock().peek(transaction_id).copied()
+    }
+
+    /// Returns the IDs of the unconfirmed transactions that spend any serial number of the given transaction.
+    fn conflicting_transactions(&self, transaction: &Transaction<N>) -> IndexSet<N::TransactionID> {
+        let transaction_id = transaction.id();
+        let pending = self.pending_serial_numbers.lock();
+        transaction
+            .serial_numbers()
+            .filter_map(|serial_number| pending.peek(serial_number).copied())
+            .filter(|id| *id != transaction_id)
+            .collect()
+    }
+
+    /// Returns the fee of the given transaction, in microcredits.
+    fn fee_amount(transaction: &Transaction<N>) -> u64 {
+        transaction.fee_transition().and_then(|fee| fee.amount().ok()).map_or(0, |amount| *amount)
+    }
+
+    /// Ensures the given fee is sufficient to replace transactions that pay the given fee in total.
+    fn check_replacement_fee(fee: u64, replaced_fee: u64) -> Result<()> {
+        let bump = replaced_fee.saturating_mul(MIN_REPLACEMENT_FEE_BUMP_PERCENT) / 100;
+        let min_fee = replaced_fee.saturating_add(bump.max(1));
+        ensure!(fee >= min_fee, "its fee ({fee}) is below {min_fee}");
+        Ok(())
+    }
+
+    /// Replaces the given conflicting unconfirmed transactions with the given transaction.
+    ///
+    /// The transaction must be valid, and pay at least `MIN_REPLACEMENT_FEE_BUMP_PERCENT` percent more than the
+    /// combined fees of the conflicting transactions. The conflicting transactions must still be waiting in the
+    /// transactions queue, the overflow queue, or a ready queue of the workers. Transactions that were already
+    /// proposed in a batch can no longer be replaced.
+    async fn replace_unconfirmed_transactions(
+        &self,
+        transaction: &Transaction<N>,
+        conflicting_ids: IndexSet<N::TransactionID>,
+    ) -> Result<()> {
+        let transaction_id = transaction.id();
+        // Ensure the transaction is valid, including its proof and fee, before it evicts any transaction.
+        let transaction_ = Data::Object(transaction.clone());
+        if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction_).await {
+            bail!("Transaction '{}' is an invalid replacement - {e}", fmt_id(transaction_id));
+        }
+        // Take the conflicting transactions, so that none of them is proposed while the fees are compared.
+        let mut replaced = Vec::with_capacity(conflicting_ids.len());
+        for conflicting_id in &conflicting_ids {
+            let Some(conflicting) = self.take_replaceable_transaction(conflicting_id) else {
+                self.restore_replaceable_transactions(replaced);
+                bail!(
+                    "Transaction '{}' conflicts with unconfirmed transaction '{}', which is already proposed",
+                    fmt_id(transaction_id),
+                    fmt_id(conflicting_id)
+                );
+            };
+            replaced.push(conflicting);
+        }
+        // Retrieve the conflicting transactions.
+        let mut conflicting_transactions = Vec::with_capacity(replaced.len());
+        for conflicting in &replaced {
+            conflicting_transactions.push(conflicting.transaction().await);
+        }
+        let conflicting_transactions = match conflicting_transactions.into_iter().collect::<Result<Vec<_>>>() {
+            Ok(conflicting_transactions) => conflicting_transactions,
+            Err(e) => {
+                self.restore_replaceable_transactions(replaced);
+                bail!("Failed to read the transactions that conflict with '{}' - {e}", fmt_id(transaction_id));
+            }
+        };
+        // Ensure the transaction pays a sufficiently higher fee than the conflicting transactions.
+        let replaced_fee = conflicting_transactions.iter().map(Self::fee_amount).fold(0u64, u64::saturating_add);
+        if let Err(e) = Self::check_replacement_fee(Self::fee_amount(transaction), replaced_fee) {
+            self.restore_replaceable_transactions(replaced);
+            bail!("Transaction '{}' double-spends unconfirmed transactions, and {e}", fmt_id(transaction_id));
+        }
+        // Release the conflicting transactions.
+        for conflicting in conflicting_transactions {
+            let conflicting_id = conflicting.id();
+            // Release the serial numbers of the replaced transaction.
+            self.release_serial_numbers(conflicting_id, conflicting.serial_numbers(), conflicting.tags());
+            self.transaction_spans.lock().pop(&conflicting_id);
+            self.superseded_transactions.lock().put(conflicting_id, transaction_id);
+            info!("Transaction '{}' was superseded by '{}'", fmt_id(conflicting_id), fmt_id(transaction_id));
+        }
+        Ok(())
+    }
+
+    /// Removes the given unconfirmed transaction from the transactions queue, the overflow queue,
+    /// or the ready queues of the workers, and returns it, if it is still waiting in one of them.
+    fn take_replaceable_transaction(&self, transaction_id: &N::TransactionID) -> Option<Replaceable<N>> {
+        // Remove the transaction from the transactions queue.
+        if let Some(transaction) = self.transactions_queue.lock().pop(transaction_id) {
+            return Some(Replaceable::Queued(transaction));
+        }
+        // Remove the transaction from the overflow queue.
+        match self.transactions_overflow.remove(transaction_id) {
+            Ok(Some(transaction)) => return Some(Replaceable::Overflowed(transaction)),
+            Ok(None) => (),
+            Err(e) => warn!("Failed to read the overflow queue for transaction '{}' - {e}", fmt_id(transaction_id)),
+        }
+        // Remove the transaction from the ready queues of the workers.
+        for worker in self.bft.primary().workers().iter() {
+            if let Some(Transmission::Transaction(transaction)) = worker.remove_transmission(transaction_id) {
+                return Some(Replaceable::Ready(worker.id(), *transaction_id, transaction));
+            }
+        }
+        None
+    }
+
+    /// Restores the given unconfirmed transactions, which were taken for a replacement that did not happen.
+    fn restore_replaceable_transactions(&self, transactions: Vec<Replaceable<N>>) {
+        for replaceable in transactions {
+            let transaction_id = replaceable.id();
+            let is_restored = match replaceable {
+                Replaceable::Queued(transaction) => {
+                    let spilled = {
+                        let mut queue = self.transactions_queue.lock();
+                        if queue.len() < queue.cap().get() {
+                            queue.put(transaction_id, transaction);
+                            None
+                        } else {
+                            Some(transaction)
+                        }
+                    };
+                    // Spill the transaction, without holding the lock on the queue.
+                    spilled.map_or(true, |transaction| self.transactions_overflow.push(&transaction).is_ok())
+                }
+                Replaceable::Overflowed(transaction) => self.transactions_overflow.push(&transaction).is_ok(),
+                Replaceable::Ready(worker_id, transaction_id, transaction) => {
+                    let worker = self.bft.primary().workers().iter().find(|worker| worker.id() == worker_id);
+                    let (transmission_id, transmission) =
+                        (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction));
+                    worker.map_or(false, |worker| worker.reinsert(transmission_id, transmission))
+                }
+            };
+            if !is_restored {
+                warn!("Failed to restore transaction '{}' after a refused replacement", fmt_id(transaction_id));
             }
         }
+    }
+
+    /// Returns the number of serial numbers spent by the unconfirmed transactions.
+    pub fn num_pending_serial_numbers(&self) -> usize {
+        self.pending_serial_numbers.lock().len()
+    }
+
+    /// Returns the ID of the unconfirmed transaction that spends the given serial number, if any.
+    pub fn pending_spender(&self, serial_number: &Field<N>) -> Option<N::TransactionID> {
+        self.pending_serial_numbers.lock().peek(serial_number).copied()
+    }
+
+    /// Returns the ID of the unconfirmed transaction that spends the record with the given tag, if any.
+    pub fn pending_tag_spender(&self, tag: &Field<N>) -> Option<N::TransactionID> {
+        self.pending_tags.lock().peek(tag).copied()
+    }
+
+    /// Reserves the serial numbers and tags spent by the given validated transaction.
+    /// Returns an error if any serial number is already spent by another unconfirmed transaction.
+    fn reserve_serial_numbers(
+        &self,
+        transaction_id: N::TransactionID,
+        serial_numbers: &[Field<N>],
+        tags: &[Field<N>],
+    ) -> Result<()> {
+        // Acquire the locks on the serial numbers and tags.
+        let mut pending = self.pending_serial_numbers.lock();
+        let mut pending_tags = self.pending_tags.lock();
+        // Check if any serial number is already spent by another unconfirmed transaction.
+        for serial_number in serial_numbers {
+            if let Some(conflicting_id) = pending.peek(serial_number).filter(|id| **id != transaction_id) {
+                #[cfg(feature = "metrics")]
+                metrics::counter(metrics::consensus::MEMPOOL_CONFLICTS, 1);
+                bail!(
+                    "Transaction '{}' double-spends serial number '{}' of unconfirmed transaction '{}'",
+                    fmt_id(transaction_id),
+                    fmt_id(serial_number),
+                    fmt_id(conflicting_id)
+                );
+            }
+        }
+        // Reserve the serial numbers and tags.
+        for serial_number in serial_numbers {
+            pending.put(*serial_number, transaction_id);
+        }
+        for tag in tags {
+            pending_tags.put(*tag, transaction_id);
+        }
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::consensus::MEMPOOL_SERIAL_NUMBERS, pending.len() as f64);
         Ok(())
     }
+
+    /// Releases the given spent serial numbers and tags, and those reserved by the given committed transactions.
+    fn release_committed_serial_numbers<'a>(
+        &self,
+        transaction_ids: &[N::TransactionID],
+        serial_numbers: impl IntoIterator<Item = &'a Field<N>>,
+        tags: impl IntoIterator<Item = &'a Field<N>>,
+    ) {
+        let transaction_ids = transaction_ids.iter().collect::<HashSet<_>>();
+        // Acquire the locks on the serial numbers and tags.
+        let mut pending = self.pending_serial_numbers.lock();
+        let mut pending_tags = self.pending_tags.lock();
+        let mut released = serial_numbers.into_iter().copied().collect::<Vec<_>>();
+        released.extend(pending.iter().filter(|(_, id)| transaction_ids.contains(id)).map(|(sn, _)| *sn));
+        for serial_number in released {
+            pending.pop(&serial_number);
+        }
+        let mut released = tags.into_iter().copied().collect::<Vec<_>>();
+        released.extend(pending_tags.iter().filter(|(_, id)| transaction_ids.contains(id)).map(|(tag, _)| *tag));
+        for tag in released {
+            pending_tags.pop(&tag);
+        }
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::consensus::MEMPOOL_SERIAL_NUMBERS, pending.len() as f64);
+    }
+
+    /// Releases the given serial numbers and tags, if they are reserved by the given transaction.
+    fn release_serial_numbers<'a>(
+        &self,
+        transaction_id: N::TransactionID,
+        serial_numbers: impl IntoIterator<Item = &'a Field<N>>,
+        tags: impl IntoIterator<Item = &'a Field<N>>,
+    ) {
+        // Acquire the locks on the serial numbers and tags.
+        let mut pending = self.pending_serial_numbers.lock();
+        let mut pending_tags = self.pending_tags.lock();
+        for serial_number in serial_numbers {
+            if pending.peek(serial_number) == Some(&transaction_id) {
+                pending.pop(serial_number);
+            }
+        }
+        for tag in tags {
+            if pending_tags.peek(tag) == Some(&transaction_id) {
+                pending_tags.pop(tag);
+            }
+        }
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::consensus::MEMPOOL_SERIAL_NUMBERS, pending.len() as f64);
+    }
 }
 
 impl<N: Network> Consensus<N> {
@@ -310,6 +880,24 @@ impl<N: Network> Consensus<N> {
                 self_.process_bft_subdag(committed_subdag, transmissions, callback).await;
             }
         });
+
+        // Send the queued unconfirmed transactions to the memory pool, as it frees up.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                clock::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                self_.process_transactions_queue().await;
+            }
+        });
+
+        // Send the aggregated unconfirmed solutions to the memory pool, once they are ready.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                clock::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                self_.process_solution_aggregator().await;
+            }
+        });
     }
 
     /// Processes the committed subdag and transmissions from the BFT.
@@ -348,12 +936,48 @@ impl<N: Network> Consensus<N> {
         #[cfg(feature = "metrics")]
         let current_block_timestamp = self.ledger.latest_block().header().metadata().timestamp();
 
+        // Retain the committed subdag and the ordered transaction IDs, for the consensus archive.
+        let subdag_ = subdag.clone();
+        let transaction_ids = ConsensusOutput::<N>::transaction_ids(&transmissions);
+
+        // Retrieve the chain policy, with which the next block is accepted.
+        let policy = self.bft.primary().sync().chain_policy();
         // Create the candidate next block.
-        let next_block = self.ledger.prepare_advance_to_next_quorum_block(subdag, transmissions)?;
+        let next_block = policy.prepare_next_block(self.ledger.as_ref(), subdag, transmissions)?;
         // Check that the block is well-formed.
-        self.ledger.check_next_block(&next_block)?;
+        policy.check_next_block(self.ledger.as_ref(), &next_block)?;
         // Advance to the next block.
-        self.ledger.advance_to_next_block(&next_block)?;
+        policy.advance_to_next_block(self.ledger.as_ref(), &next_block)?;
+
+        // Feed the ordering latency of the leader certificate to the batch tuner.
+        let latency = snarkos_node_bft::helpers::now() - subdag_.leader_certificate().batch_header().timestamp();
+        self.bft.primary().batch_tuner().record_latency(Duration::from_secs(latency.max(0) as u64));
+
+        // Release the serial numbers spent in the block, and those reserved by the transactions of the subdag,
+        // which the block either included, or aborted.
+        let (serial_numbers, tags) = (next_block.transactions().serial_numbers(), next_block.transactions().tags());
+        self.release_committed_serial_numbers(&transaction_ids, serial_numbers, tags);
+
+        // Log the inclusion of the transactions in the spans of the requests that submitted them.
+        {
+            let mut spans = self.transaction_spans.lock();
+            for transaction_id in next_block.transaction_ids() {
+                if let Some(span) = spans.pop(transaction_id) {
+                    span.in_scope(|| {
+                        info!("Transaction '{}' was included in block {}", fmt_id(transaction_id), next_block.height())
+                    });
+                }
+            }
+        }
+
+        // Archive the consensus output.
+        // Note: The block has already been added to the ledger, so a failure here must not fail the block.
+        let output = ConsensusOutput::new(&subdag_, transaction_ids, &next_block);
+        if let Err(e) = self.archive.insert(&output) {
+            warn!("Unable to archive the consensus output for round {} - {e}", output.round);
+        }
+        // Publish the change to the memory pool.
+        self.publish(NodeEvent::MempoolChanged { num_unconfirmed_transmissions: self.num_unconfirmed_transmissions() });
 
         #[cfg(feature = "metrics")]
         {
@@ -407,6 +1031,13 @@ impl<N: Network> Consensus<N> {
         callback_receiver.await?
     }
 
+    /// Publishes the given event, if the event bus is set.
+    fn publish(&self, event: NodeEvent<N>) {
+        if let Some(event_bus) = self.event_bus.get() {
+            event_bus.publish(event);
+        }
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -417,7 +1048,172 @@ impl<N: Network> Consensus<N> {
         info!("Shutting down consensus...");
         // Shut down the BFT.
         self.bft.shut_down().await;
+        // Publish that the BFT stopped.
+        self.publish(NodeEvent::BftStopped);
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft::helpers::init_primary_channels;
+    use snarkos_node_bft_ledger_service::MockLedgerService;
+    use snarkvm::{
+        ledger::{
+            committee::test_helpers::sample_committee,
+            ledger_test_helpers::{sample_execution_transaction_with_fee, sample_fee_public_transaction},
+        },
+        prelude::{TestRng, Testnet3},
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    /// Samples an ephemeral consensus instance over a mock ledger.
+    fn sample_consensus(rng: &mut TestRng) -> Consensus<CurrentNetwork> {
+        let dir = std::env::temp_dir().join(format!("snarkos-consensus-{}", u64::rand(rng)));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ledger = Arc::new(MockLedgerService::new(sample_committee(rng)));
+        let account = Account::new(rng).unwrap();
+        Consensus::new_ephemeral(account, ledger, None, &[], 0, &dir).unwrap()
+    }
+
+    /// Sets the primary sender of the given consensus, and spawns a primary that answers every unconfirmed
+    /// transaction with the given validation outcome.
+    fn spawn_primary(consensus: &Consensus<CurrentNetwork>, is_valid: bool) {
+        let (primary_sender, mut primary_receiver) = init_primary_channels();
+        consensus.primary_sender.set(primary_sender).unwrap();
+        tokio::spawn(async move {
+            while let Some((_, _, callback)) = primary_receiver.rx_unconfirmed_transaction.recv().await {
+                let result = match is_valid {
+                    true => Ok(()),
+                    false => Err(anyhow!("Invalid transaction")),
+                };
+                callback.send(result).ok();
+            }
+        });
+    }
+
+    #[test]
+    fn test_reserve_and_release_serial_numbers() {
+        let rng = &mut TestRng::default();
+        let consensus = sample_consensus(rng);
+
+        let (first, second) = (sample_fee_public_transaction(rng).id(), sample_fee_public_transaction(rng).id());
+        let serial_numbers = (0..3).map(|_| Field::<CurrentNetwork>::rand(rng)).collect::<Vec<_>>();
+        let tags = (0..3).map(|_| Field::<CurrentNetwork>::rand(rng)).collect::<Vec<_>>();
+
+        // Ensure a serial number can only be reserved by one transaction.
+        consensus.reserve_serial_numbers(first, &serial_numbers[..2], &tags[..2]).unwrap();
+        assert!(consensus.reserve_serial_numbers(second, &serial_numbers[1..], &tags[1..]).is_err());
+        assert_eq!(consensus.num_pending_serial_numbers(), 2);
+        assert_eq!(consensus.pending_spender(&serial_numbers[1]), Some(first));
+        assert_eq!(consensus.pending_spender(&serial_numbers[2]), None);
+        assert_eq!(consensus.pending_tag_spender(&tags[1]), Some(first));
+        assert_eq!(consensus.pending_tag_spender(&tags[2]), None);
+
+        // Ensure a transaction only releases its own serial numbers and tags.
+        consensus.release_serial_numbers(second, &serial_numbers, &tags);
+        assert_eq!(consensus.num_pending_serial_numbers(), 2);
+        assert_eq!(consensus.pending_tag_spender(&tags[0]), Some(first));
+
+        // Ensure the serial numbers of a committed transaction are released, even if the block did not spend them.
+        consensus.release_committed_serial_numbers(&[first], &[], &[]);
+        assert_eq!(consensus.num_pending_serial_numbers(), 0);
+        assert_eq!(consensus.pending_tag_spender(&tags[0]), None);
+        consensus.reserve_serial_numbers(second, &serial_numbers[1..], &tags[1..]).unwrap();
+
+        // Ensure the serial numbers spent in a block are released, regardless of the reserving transaction.
+        consensus.release_committed_serial_numbers(&[], &serial_numbers[2..], &tags[2..]);
+        assert_eq!(consensus.pending_spender(&serial_numbers[1]), Some(second));
+        assert_eq!(consensus.pending_spender(&serial_numbers[2]), None);
+        assert_eq!(consensus.pending_tag_spender(&tags[1]), Some(second));
+        assert_eq!(consensus.pending_tag_spender(&tags[2]), None);
+    }
+
+    #[tokio::test]
+    async fn test_serial_numbers_are_reserved_after_validation() {
+        let rng = &mut TestRng::default();
+
+        // Ensure a transaction that fails validation reserves no serial number.
+        let consensus = sample_consensus(rng);
+        spawn_primary(&consensus, false);
+        let transaction = sample_execution_transaction_with_fee(true, rng);
+        assert!(transaction.serial_numbers().next().is_some());
+        consensus.add_unconfirmed_transaction(transaction.clone()).await.unwrap();
+        assert_eq!(consensus.num_pending_serial_numbers(), 0);
+
+        // Ensure a transaction that passes validation reserves its serial numbers.
+        let consensus = sample_consensus(rng);
+        spawn_primary(&consensus, true);
+        consensus.add_unconfirmed_transaction(transaction.clone()).await.unwrap();
+        assert_eq!(consensus.num_pending_serial_numbers(), transaction.serial_numbers().count());
+        for serial_number in transaction.serial_numbers() {
+            assert_eq!(consensus.pending_spender(serial_number), Some(transaction.id()));
+        }
+        for tag in transaction.tags() {
+            assert_eq!(consensus.pending_tag_spender(tag), Some(transaction.id()));
+        }
+    }
+
+    #[test]
+    fn test_check_replacement_fee() {
+        type Consensus = super::Consensus<CurrentNetwork>;
+
+        // Ensure the replacement must pay at least 10% more than the replaced transactions.
+        assert!(Consensus::check_replacement_fee(110, 100).is_ok());
+        assert!(Consensus::check_replacement_fee(109, 100).is_err());
+        assert!(Consensus::check_replacement_fee(100, 100).is_err());
+        // Ensure the replacement must pay strictly more, even for small fees.
+        assert!(Consensus::check_replacement_fee(1, 0).is_ok());
+        assert!(Consensus::check_replacement_fee(0, 0).is_err());
+        assert!(Consensus::check_replacement_fee(u64::MAX, u64::MAX).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replacement_of_proposed_transaction_is_refused() {
+        let rng = &mut TestRng::default();
+        let consensus = sample_consensus(rng);
+
+        // Sample a conflicting transaction that is no longer waiting in the memory pool.
+        let conflicting = sample_fee_public_transaction(rng);
+        let transaction = sample_fee_public_transaction(rng);
+        let conflicting_ids = IndexSet::from([conflicting.id()]);
+
+        // Ensure the replacement is refused, and nothing is superseded.
+        assert!(consensus.replace_unconfirmed_transactions(&transaction, conflicting_ids).await.is_err());
+        assert_eq!(consensus.superseded_by(&conflicting.id()), None);
+    }
+
+    #[tokio::test]
+    async fn test_replacement_is_atomic() {
+        let rng = &mut TestRng::default();
+        let consensus = sample_consensus(rng);
+
+        // Queue one conflicting transaction, and spill another to the overflow queue.
+        let queued = sample_fee_public_transaction(rng);
+        let overflowed = sample_fee_public_transaction(rng);
+        let missing = sample_fee_public_transaction(rng);
+        let transaction = sample_fee_public_transaction(rng);
+        consensus.transactions_queue.lock().put(queued.id(), queued.clone());
+        consensus.transactions_overflow.push(&overflowed).unwrap();
+
+        // Ensure a replacement that conflicts with a transaction that is already proposed evicts nothing.
+        let conflicting_ids = IndexSet::from([queued.id(), overflowed.id(), missing.id()]);
+        assert!(consensus.replace_unconfirmed_transactions(&transaction, conflicting_ids).await.is_err());
+        assert!(consensus.transactions_queue.lock().contains(&queued.id()));
+        assert!(consensus.transactions_overflow.contains(&overflowed.id()));
+
+        // Ensure the conflicting transactions are evicted if, and only if, the replacement pays a sufficient fee.
+        let replaced_fee = Consensus::fee_amount(&queued).saturating_add(Consensus::fee_amount(&overflowed));
+        let is_replaced = Consensus::check_replacement_fee(Consensus::fee_amount(&transaction), replaced_fee).is_ok();
+        let conflicting_ids = IndexSet::from([queued.id(), overflowed.id()]);
+        let result = consensus.replace_unconfirmed_transactions(&transaction, conflicting_ids).await;
+        assert_eq!(result.is_ok(), is_replaced);
+        assert_eq!(consensus.transactions_queue.lock().contains(&queued.id()), !is_replaced);
+        assert_eq!(consensus.transactions_overflow.contains(&overflowed.id()), !is_replaced);
+        assert_eq!(consensus.superseded_by(&queued.id()), is_replaced.then(|| transaction.id()));
+        assert_eq!(consensus.superseded_by(&overflowed.id()), is_replaced.then(|| transaction.id()));
+    }
+}
@@ -0,0 +1,524 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::block::Transaction,
+    prelude::{bail, FromBytes, Network, Result, ToBytes},
+};
+
+use parking_lot::Mutex;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+/// The default maximum size of the transaction overflow files, in bytes.
+pub const DEFAULT_TRANSACTION_OVERFLOW_MAX_SIZE: u64 = 256 * 1024 * 1024; // 256 MiB
+
+/// The number of segment files that the maximum size of the overflow queue is divided into.
+const NUM_SEGMENTS: u64 = 16;
+/// The maximum number of spilled transactions that are relocated in one compaction step.
+const MAX_RELOCATIONS_PER_COMPACTION: usize = 8;
+
+/// The position of a spilled transaction in the overflow files.
+#[derive(Clone, Copy)]
+struct OverflowEntry<N: Network> {
+    /// The transaction ID.
+    transaction_id: N::TransactionID,
+    /// The segment file that holds the transaction.
+    segment: u64,
+    /// The byte offset of the transaction in the segment file.
+    offset: u64,
+    /// The length of the serialized transaction, in bytes.
+    length: u32,
+}
+
+impl<N: Network> OverflowEntry<N> {
+    /// Returns the size of the entry in the segment file, in bytes.
+    fn size(&self) -> u64 {
+        4 + self.length as u64
+    }
+}
+
+/// A segment file of the overflow queue.
+#[derive(Default)]
+struct OverflowSegment {
+    /// The size of the segment file, in bytes.
+    size: u64,
+    /// The number of spilled transactions, and of pending reads and writes, that refer to the segment file.
+    references: usize,
+}
+
+/// The state of the transaction overflow queue, which is guarded by a single lock.
+/// The lock is never held while reading or writing the overflow files.
+struct OverflowInner<N: Network> {
+    /// The spilled transactions, ordered by their priority fee (highest first), and then by arrival.
+    entries: BTreeMap<(Reverse<u64>, u64), OverflowEntry<N>>,
+    /// The IDs of the spilled transactions, including those that are still being written.
+    transaction_ids: HashSet<N::TransactionID>,
+    /// The sequence number of the next spilled transaction.
+    sequence: u64,
+    /// The segment files, by their sequence number. The last one is the one that is appended to.
+    segments: BTreeMap<u64, OverflowSegment>,
+    /// The sequence number of the next segment file.
+    next_segment: u64,
+    /// The total size of the segment files, in bytes.
+    size: u64,
+    /// The total size of the spilled transactions in the segment files, in bytes.
+    live_size: u64,
+}
+
+/// A bounded, disk-backed queue for the unconfirmed transactions that do not fit in the memory pool.
+///
+/// Under burst load, the transactions beyond the in-memory queue are appended to the overflow files,
+/// and drained back into the memory pool as capacity frees up, with the highest priority fee first,
+/// and in arrival order otherwise. Only the byte offsets are kept in memory. The queue is split into
+/// segment files, and each segment file is deleted as soon as its last transaction is drained. Once most
+/// of the space is held by drained transactions, every drain relocates a few transactions out of the oldest
+/// segment file, so the space is reclaimed incrementally. The queue refuses new transactions once the
+/// files reach their maximum size. The queue is not persisted across restarts.
+pub struct TransactionOverflow<N: Network> {
+    /// The state of the queue.
+    inner: Mutex<OverflowInner<N>>,
+    /// The path to the directory of the overflow files.
+    path: PathBuf,
+    /// The maximum size of the overflow files, in bytes.
+    max_size: u64,
+}
+
+impl<N: Network> TransactionOverflow<N> {
+    /// The name of the overflow directory, which is stored inside the ledger directory.
+    pub const FILE_NAME: &'static str = "transaction-overflow";
+
+    /// Opens the overflow queue in the given directory, discarding any transactions from a previous run.
+    pub fn open_at(path: PathBuf, max_size: u64) -> Result<Self> {
+        // Discard the overflow files from a previous run.
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        // Create the overflow directory.
+        std::fs::create_dir_all(&path)?;
+
+        let inner = OverflowInner {
+            entries: Default::default(),
+            transaction_ids: Default::default(),
+            sequence: 0,
+            segments: Default::default(),
+            next_segment: 0,
+            size: 0,
+            live_size: 0,
+        };
+        Ok(Self { inner: Mutex::new(inner), path, max_size })
+    }
+
+    /// Returns the number of spilled transactions.
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    /// Returns `true` if there are no spilled transactions.
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().entries.is_empty()
+    }
+
+    /// Returns the size of the overflow files, in bytes.
+    pub fn size_in_bytes(&self) -> u64 {
+        self.inner.lock().size
+    }
+
+    /// Returns `true` if the given transaction is spilled.
+    pub fn contains(&self, transaction_id: &N::TransactionID) -> bool {
+        self.inner.lock().transaction_ids.contains(transaction_id)
+    }
+
+    /// Appends the given transaction to the overflow files.
+    /// Returns an error if the transaction is already spilled, or if the overflow files are full.
+    pub fn push(&self, transaction: &Transaction<N>) -> Result<()> {
+        let transaction_id = transaction.id();
+        // Serialize the transaction.
+        let bytes = transaction.to_bytes_le()?;
+        let length = u32::try_from(bytes.len())?;
+        // Determine the priority fee of the transaction.
+        let priority_fee =
+            transaction.fee_transition().and_then(|fee| fee.priority_amount().ok()).map_or(0, |amount| *amount);
+
+        // Reserve the space for the transaction.
+        let (segment, offset) = {
+            let mut inner = self.inner.lock();
+            // Ensure the transaction is not already spilled.
+            if inner.transaction_ids.contains(&transaction_id) {
+                bail!("Transaction '{transaction_id}' is already in the overflow queue")
+            }
+            // Ensure the overflow files have room for the transaction.
+            if inner.size + 4 + bytes.len() as u64 > self.max_size {
+                bail!("The transaction overflow queue is full ({} transactions)", inner.entries.len())
+            }
+            inner.transaction_ids.insert(transaction_id);
+            self.reserve(&mut inner, 4 + bytes.len() as u64)
+        };
+
+        // Write the transaction, and release the reservation if the write fails.
+        if let Err(error) = self.write_entry(segment, offset, &bytes) {
+            self.inner.lock().transaction_ids.remove(&transaction_id);
+            self.release(segment);
+            return Err(error);
+        }
+
+        // Index the entry.
+        let entry = OverflowEntry { transaction_id, segment, offset, length };
+        let mut inner = self.inner.lock();
+        let sequence = inner.sequence;
+        inner.entries.insert((Reverse(priority_fee), sequence), entry);
+        inner.sequence += 1;
+        inner.live_size += entry.size();
+        Ok(())
+    }
+
+    /// Removes and returns the spilled transaction with the highest priority fee, if one exists.
+    pub fn pop(&self) -> Result<Option<Transaction<N>>> {
+        // Retrieve the next entry.
+        let entry = {
+            let mut inner = self.inner.lock();
+            let Some((_, entry)) = inner.entries.pop_first() else {
+                return Ok(None);
+            };
+            inner.transaction_ids.remove(&entry.transaction_id);
+            inner.live_size -= entry.size();
+            entry
+        };
+        self.take(entry).map(Some)
+    }
+
+    /// Returns the given spilled transaction, without removing it, if it exists.
+    pub fn get(&self, transaction_id: &N::TransactionID) -> Result<Option<Transaction<N>>> {
+        // Retrieve the entry of the transaction, and hold on to its segment file while it is read.
+        let entry = {
+            let mut inner = self.inner.lock();
+            let Some(entry) = inner.entries.values().find(|entry| entry.transaction_id == *transaction_id).copied()
+            else {
+                return Ok(None);
+            };
+            inner.segments.entry(entry.segment).or_default().references += 1;
+            entry
+        };
+        // Read the transaction from the overflow files.
+        let result = self.read_entry(&entry);
+        self.release(entry.segment);
+        result.map(Some)
+    }
+
+    /// Removes and returns the given spilled transaction, if it exists.
+    pub fn remove(&self, transaction_id: &N::TransactionID) -> Result<Option<Transaction<N>>> {
+        // Retrieve the entry of the transaction.
+        let entry = {
+            let mut inner = self.inner.lock();
+            let key =
+                inner.entries.iter().find(|(_, entry)| entry.transaction_id == *transaction_id).map(|(key, _)| *key);
+            let Some(entry) = key.and_then(|key| inner.entries.remove(&key)) else {
+                return Ok(None);
+            };
+            inner.transaction_ids.remove(transaction_id);
+            inner.live_size -= entry.size();
+            entry
+        };
+        self.take(entry).map(Some)
+    }
+
+    /// Returns the spilled transactions, with the highest priority fee first, without removing them.
+    pub fn transactions(&self) -> Result<Vec<Transaction<N>>> {
+        // Retrieve the entries, and hold on to their segment files while they are read.
+        let entries = {
+            let mut inner = self.inner.lock();
+            let entries = inner.entries.values().copied().collect::<Vec<_>>();
+            for entry in &entries {
+                inner.segments.entry(entry.segment).or_default().references += 1;
+            }
+            entries
+        };
+        // Read the transactions from the overflow files.
+        let transactions = entries.iter().map(|entry| self.read_entry(entry)).collect::<Result<Vec<_>>>();
+        for entry in &entries {
+            self.release(entry.segment);
+        }
+        transactions
+    }
+
+    /// Reads the transaction of the given entry, which was removed from the queue, and releases its space.
+    fn take(&self, entry: OverflowEntry<N>) -> Result<Transaction<N>> {
+        // Read the transaction from the overflow files.
+        let result = self.read_entry(&entry);
+        // Release the space of the transaction, even if it could not be read.
+        self.release(entry.segment);
+        // Reclaim the space of the drained transactions.
+        if let Err(e) = self.compact() {
+            warn!("Failed to compact the transaction overflow queue - {e}");
+        }
+        result
+    }
+
+    /// Relocates a few of the spilled transactions out of the oldest segment file, into the newest one,
+    /// if the drained transactions hold most of the space in the overflow files.
+    fn compact(&self) -> Result<()> {
+        // Select the entries to relocate, and hold on to their segment file while they are relocated.
+        let (segment, entries) = {
+            let mut inner = self.inner.lock();
+            if inner.segments.len() < 2 || inner.size <= 2 * inner.live_size {
+                return Ok(());
+            }
+            let Some(segment) = inner.segments.keys().next().copied() else {
+                return Ok(());
+            };
+            let entries = inner
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.segment == segment)
+                .take(MAX_RELOCATIONS_PER_COMPACTION)
+                .map(|(key, entry)| (*key, *entry))
+                .collect::<Vec<_>>();
+            inner.segments.entry(segment).or_default().references += 1;
+            (segment, entries)
+        };
+
+        let result = entries.into_iter().try_for_each(|(key, entry)| {
+            // Copy the transaction into the newest segment file.
+            let bytes = self.read_entry_bytes(&entry)?;
+            let (new_segment, new_offset) = self.reserve(&mut self.inner.lock(), entry.size());
+            if let Err(error) = self.write_entry(new_segment, new_offset, &bytes) {
+                self.release(new_segment);
+                return Err(error);
+            }
+            // Point the entry to the copy, unless the transaction was removed in the meantime.
+            let is_relocated = {
+                let mut inner = self.inner.lock();
+                match inner.entries.get_mut(&key) {
+                    Some(current) if current.segment == segment && current.offset == entry.offset => {
+                        current.segment = new_segment;
+                        current.offset = new_offset;
+                        true
+                    }
+                    _ => false,
+                }
+            };
+            // Release the space of the original, or of the unused copy.
+            self.release(if is_relocated { segment } else { new_segment });
+            Ok(())
+        });
+        self.release(segment);
+        result
+    }
+
+    /// Reserves the given number of bytes at the end of the newest segment file, or of a new segment file,
+    /// and returns the segment file and the offset. The reservation must be released with `release`.
+    fn reserve(&self, inner: &mut OverflowInner<N>, size: u64) -> (u64, u64) {
+        let segment_size = (self.max_size / NUM_SEGMENTS).max(1);
+        // Append to the newest segment file, if it has room.
+        let id = match inner.segments.last_key_value() {
+            Some((id, segment)) if segment.size + size <= segment_size => *id,
+            _ => {
+                let id = inner.next_segment;
+                inner.next_segment += 1;
+                id
+            }
+        };
+        let segment = inner.segments.entry(id).or_default();
+        let offset = segment.size;
+        segment.size += size;
+        segment.references += 1;
+        inner.size += size;
+        (id, offset)
+    }
+
+    /// Releases a reference to the given segment file, and deletes it once nothing refers to it.
+    fn release(&self, id: u64) {
+        {
+            let mut inner = self.inner.lock();
+            let Some(segment) = inner.segments.get_mut(&id) else {
+                return;
+            };
+            segment.references = segment.references.saturating_sub(1);
+            if segment.references > 0 {
+                return;
+            }
+            let size = segment.size;
+            inner.segments.remove(&id);
+            inner.size -= size;
+        }
+        // Delete the segment file, as its sequence number is never reused.
+        if let Err(e) = std::fs::remove_file(self.segment_path(id)) {
+            warn!("Failed to delete the transaction overflow file {id} - {e}");
+        }
+    }
+
+    /// Returns the path to the given segment file.
+    fn segment_path(&self, id: u64) -> PathBuf {
+        self.path.join(id.to_string())
+    }
+
+    /// Writes the given serialized transaction at the given offset of the given segment file.
+    fn write_entry(&self, segment: u64, offset: u64, bytes: &[u8]) -> Result<()> {
+        let length = u32::try_from(bytes.len())?;
+        // Write the entry as a single buffer.
+        let mut entry = Vec::with_capacity(4 + bytes.len());
+        entry.extend_from_slice(&length.to_le_bytes());
+        entry.extend_from_slice(bytes);
+        let mut file = OpenOptions::new().write(true).create(true).truncate(false).open(self.segment_path(segment))?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(&entry)?;
+        Ok(())
+    }
+
+    /// Reads the serialized transaction of the given entry.
+    fn read_entry_bytes(&self, entry: &OverflowEntry<N>) -> Result<Vec<u8>> {
+        let mut file = File::open(self.segment_path(entry.segment))?;
+        file.seek(SeekFrom::Start(entry.offset + 4))?;
+        let mut buffer = vec![0u8; entry.length as usize];
+        file.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Reads the transaction of the given entry.
+    fn read_entry(&self, entry: &OverflowEntry<N>) -> Result<Transaction<N>> {
+        let transaction = Transaction::read_le(&self.read_entry_bytes(entry)?[..])?;
+        // Ensure the transaction was not corrupted on disk.
+        if transaction.id() != entry.transaction_id {
+            bail!("Transaction '{}' is corrupted in the overflow files '{}'", entry.transaction_id, self.path.display())
+        }
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::ledger_test_helpers::sample_fee_public_transaction,
+        prelude::{TestRng, Testnet3, Uniform},
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_transaction_overflow() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-transaction-overflow-{}", u64::rand(rng)));
+
+        let transactions = (0..3).map(|_| sample_fee_public_transaction(rng)).collect::<Vec<_>>();
+        let entry_size =
+            |transaction: &Transaction<CurrentNetwork>| 4 + transaction.to_bytes_le().unwrap().len() as u64;
+        let max_size = entry_size(&transactions[0]) + entry_size(&transactions[1]);
+
+        // Open a queue with room for two transactions.
+        let overflow = TransactionOverflow::<CurrentNetwork>::open_at(path.clone(), max_size).unwrap();
+        assert!(overflow.is_empty());
+        overflow.push(&transactions[0]).unwrap();
+        overflow.push(&transactions[1]).unwrap();
+        assert_eq!(overflow.len(), 2);
+        assert!(overflow.contains(&transactions[1].id()));
+        // Ensure duplicates and transactions beyond the maximum size are refused.
+        assert!(overflow.push(&transactions[0]).is_err());
+        assert!(overflow.push(&transactions[2]).is_err());
+        assert_eq!(overflow.size_in_bytes(), max_size);
+        // Ensure the transactions can be read without draining them.
+        assert_eq!(overflow.transactions().unwrap(), transactions[..2].to_vec());
+        assert_eq!(overflow.len(), 2);
+
+        // Ensure the transactions with the same priority fee are drained in arrival order.
+        assert_eq!(overflow.pop().unwrap(), Some(transactions[0].clone()));
+        assert_eq!(overflow.pop().unwrap(), Some(transactions[1].clone()));
+        assert_eq!(overflow.pop().unwrap(), None);
+        // Ensure the space is reclaimed once the queue is empty.
+        assert_eq!(overflow.size_in_bytes(), 0);
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 0);
+        overflow.push(&transactions[2]).unwrap();
+        assert_eq!(overflow.pop().unwrap(), Some(transactions[2].clone()));
+
+        // Ensure a transaction can be read and removed out of order.
+        overflow.push(&transactions[0]).unwrap();
+        overflow.push(&transactions[1]).unwrap();
+        assert_eq!(overflow.get(&transactions[1].id()).unwrap(), Some(transactions[1].clone()));
+        assert_eq!(overflow.remove(&transactions[1].id()).unwrap(), Some(transactions[1].clone()));
+        assert_eq!(overflow.remove(&transactions[1].id()).unwrap(), None);
+        assert!(!overflow.contains(&transactions[1].id()));
+        assert_eq!(overflow.pop().unwrap(), Some(transactions[0].clone()));
+        assert_eq!(overflow.size_in_bytes(), 0);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_transaction_overflow_compaction() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-transaction-overflow-{}", u64::rand(rng)));
+
+        let transactions = (0..9).map(|_| sample_fee_public_transaction(rng)).collect::<Vec<_>>();
+        let entry_sizes = transactions.iter().map(|transaction| 4 + transaction.to_bytes_le().unwrap().len() as u64);
+        let total_size = entry_sizes.clone().sum::<u64>();
+        let segment_size = 3 * entry_sizes.max().unwrap();
+
+        // Open a queue whose segment files hold three transactions each.
+        let max_size = segment_size * NUM_SEGMENTS;
+        let overflow = TransactionOverflow::<CurrentNetwork>::open_at(path.clone(), max_size).unwrap();
+        for transaction in &transactions {
+            overflow.push(transaction).unwrap();
+        }
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 3);
+
+        // Remove two transactions from each segment file.
+        for (i, transaction) in transactions.iter().enumerate().filter(|(i, _)| i % 3 != 2) {
+            assert_eq!(overflow.remove(&transaction.id()).unwrap(), Some(transaction.clone()), "transaction {i}");
+        }
+        // Ensure the remaining transactions were relocated out of the oldest segment files.
+        assert!(overflow.size_in_bytes() < total_size);
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 2);
+        let remaining = transactions.iter().skip(2).step_by(3).cloned().collect::<Vec<_>>();
+        assert_eq!(overflow.transactions().unwrap(), remaining);
+        for transaction in &remaining {
+            assert_eq!(overflow.pop().unwrap(), Some(transaction.clone()));
+        }
+        assert_eq!(overflow.size_in_bytes(), 0);
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_transaction_overflow_releases_unreadable_transactions() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-transaction-overflow-{}", u64::rand(rng)));
+
+        let transaction = sample_fee_public_transaction(rng);
+        let overflow = TransactionOverflow::<CurrentNetwork>::open_at(path.clone(), 1 << 20).unwrap();
+        overflow.push(&transaction).unwrap();
+
+        // Corrupt the transaction on disk.
+        let segment = std::fs::read_dir(&path).unwrap().next().unwrap().unwrap().path();
+        let length = std::fs::metadata(&segment).unwrap().len();
+        std::fs::write(&segment, vec![0u8; length as usize]).unwrap();
+
+        // Ensure the transaction is refused, and its space is released.
+        assert!(overflow.pop().is_err());
+        assert!(overflow.is_empty());
+        assert!(!overflow.contains(&transaction.id()));
+        assert_eq!(overflow.size_in_bytes(), 0);
+        overflow.push(&transaction).unwrap();
+        assert_eq!(overflow.pop().unwrap(), Some(transaction));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
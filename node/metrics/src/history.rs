@@ -0,0 +1,151 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The interval in seconds in between the samples of the metrics history.
+pub const HISTORY_SAMPLE_INTERVAL_IN_SECS: u64 = 60; // 1 minute
+/// The number of samples retained in the metrics history, which covers the last 24 hours.
+pub const HISTORY_CAPACITY: usize = (24 * 60 * 60 / HISTORY_SAMPLE_INTERVAL_IN_SECS) as usize;
+
+/// A sample of the peer connectivity of the node.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PeerSample {
+    /// The UNIX timestamp of the sample, in seconds.
+    pub timestamp: i64,
+    /// The number of connected clients.
+    pub clients: usize,
+    /// The number of connected provers.
+    pub provers: usize,
+    /// The number of connected validators.
+    pub validators: usize,
+    /// The stake of the committee members connected to the node (including itself), if the node is a validator.
+    pub connected_stake: Option<u64>,
+    /// The total stake of the committee, if the node is a validator.
+    pub total_stake: Option<u64>,
+    /// The number of peers that connected since the previous sample.
+    pub connects: usize,
+    /// The number of peers that disconnected since the previous sample.
+    pub disconnects: usize,
+}
+
+/// The retained time series of the peer connectivity, held in an in-process ring buffer.
+/// This offers basic historical visibility to operators that do not run a Prometheus stack.
+#[derive(Debug)]
+pub struct MetricsHistory {
+    /// The samples, from oldest to newest.
+    samples: RwLock<VecDeque<PeerSample>>,
+    /// The maximum number of samples retained.
+    capacity: usize,
+    /// The number of peers that connected since the last sample.
+    connects: AtomicUsize,
+    /// The number of peers that disconnected since the last sample.
+    disconnects: AtomicUsize,
+}
+
+impl Default for MetricsHistory {
+    /// Initializes a new metrics history, retaining the samples of the last 24 hours.
+    fn default() -> Self {
+        Self::new(HISTORY_CAPACITY)
+    }
+}
+
+impl MetricsHistory {
+    /// Initializes a new metrics history, retaining up to the given number of samples.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: RwLock::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            connects: Default::default(),
+            disconnects: Default::default(),
+        }
+    }
+
+    /// Records that a peer connected.
+    pub fn record_connect(&self) {
+        self.connects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records that a peer disconnected.
+    pub fn record_disconnect(&self) {
+        self.disconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the given sample, along with the churn since the previous sample.
+    /// If the history is full, the oldest sample is evicted.
+    pub fn record(&self, mut sample: PeerSample) {
+        sample.connects = self.connects.swap(0, Ordering::Relaxed);
+        sample.disconnects = self.disconnects.swap(0, Ordering::Relaxed);
+
+        let mut samples = self.samples.write();
+        while samples.len() >= self.capacity {
+            samples.pop_front();
+        }
+        if self.capacity > 0 {
+            samples.push_back(sample);
+        }
+    }
+
+    /// Returns the samples taken at or after the given UNIX timestamp, from oldest to newest.
+    pub fn samples_since(&self, timestamp: i64) -> Vec<PeerSample> {
+        self.samples.read().iter().filter(|sample| sample.timestamp >= timestamp).copied().collect()
+    }
+
+    /// Returns the number of retained samples.
+    pub fn len(&self) -> usize {
+        self.samples.read().len()
+    }
+
+    /// Returns `true` if there are no retained samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.read().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_history() {
+        let history = MetricsHistory::new(3);
+        assert!(history.is_empty());
+
+        // Record the churn in between the first two samples.
+        history.record(PeerSample { timestamp: 1, ..Default::default() });
+        history.record_connect();
+        history.record_connect();
+        history.record_disconnect();
+        history.record(PeerSample { timestamp: 2, clients: 2, ..Default::default() });
+
+        let samples = history.samples_since(0);
+        assert_eq!(samples.len(), 2);
+        assert_eq!((samples[0].connects, samples[0].disconnects), (0, 0));
+        assert_eq!((samples[1].connects, samples[1].disconnects), (2, 1));
+        assert_eq!(samples[1].clients, 2);
+
+        // Ensure the oldest samples are evicted once the history is full.
+        history.record(PeerSample { timestamp: 3, ..Default::default() });
+        history.record(PeerSample { timestamp: 4, ..Default::default() });
+        assert_eq!(history.len(), 3);
+        let timestamps: Vec<_> = history.samples_since(0).iter().map(|sample| sample.timestamp).collect();
+        assert_eq!(timestamps, vec![2, 3, 4]);
+        assert_eq!(history.samples_since(4).len(), 1);
+    }
+}
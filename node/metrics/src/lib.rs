@@ -12,8 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod history;
+pub use history::*;
+
 mod names;
 
+mod push;
+pub use push::*;
+
+mod resources;
+pub use resources::*;
+
 // Expose the names at the crate level for easy access.
 pub use names::*;
 // Re-export the snarkVM metrics.
@@ -21,8 +30,26 @@ pub use snarkvm::metrics::*;
 
 /// Initializes the metrics and returns a handle to the task running the metrics exporter.
 pub fn initialize_metrics() {
+    initialize_metrics_with(&[], None)
+}
+
+/// Initializes the metrics with the given labels, which are attached to every metric (e.g. the node name,
+/// region, or committee membership), and pushes them to the given push gateway, if any.
+pub fn initialize_metrics_with(labels: &[(String, String)], push_gateway: Option<PushGatewayConfig>) {
     // Build the Prometheus exporter.
-    metrics_exporter_prometheus::PrometheusBuilder::new().install().expect("can't build the prometheus exporter");
+    let mut builder = metrics_exporter_prometheus::PrometheusBuilder::new();
+    for (key, value) in labels {
+        builder = builder.add_global_label(key, value);
+    }
+    let (recorder, exporter) = builder.build().expect("can't build the prometheus exporter");
+    let handle = recorder.handle();
+    ::metrics::set_global_recorder(recorder).expect("can't install the prometheus recorder");
+    tokio::spawn(exporter);
+
+    // Push the metrics to the push gateway, for the nodes that cannot be scraped.
+    if let Some(config) = push_gateway {
+        start_push_gateway(config, handle);
+    }
 
     // Register the snarkVM metrics.
     snarkvm::metrics::register_metrics();
@@ -37,4 +64,12 @@ pub fn initialize_metrics() {
     for name in crate::names::HISTOGRAM_NAMES {
         register_histogram(name);
     }
+
+    // Start sampling the resource usage of the node.
+    start_resource_metrics();
+}
+
+/// Increments the counter with the given name and label value by one.
+pub fn increment_labeled_counter(name: &'static str, label: &'static str, value: &'static str) {
+    ::metrics::counter!(name, label => value).increment(1);
 }
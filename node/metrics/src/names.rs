@@ -12,20 +12,49 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub(super) const COUNTER_NAMES: [&str; 1] = [bft::LEADERS_ELECTED];
+pub(super) const COUNTER_NAMES: [&str; 12] = [
+    bft::BLOCK_HASH_CONFLICTS,
+    bft::LEADERS_ELECTED,
+    bft::LEADERS_MISSING,
+    bft::ROUNDS_STALLED,
+    bft::TRANSACTIONS_REJECTED,
+    consensus::MEMPOOL_CONFLICTS,
+    router::BLOCK_CACHE_HITS,
+    router::BLOCK_CACHE_MISSES,
+    router::DISCONNECTS_RECEIVED,
+    router::DISCONNECTS_SENT,
+    router::MALFORMED_FRAMES,
+    router::OVERSIZED_MESSAGES,
+];
 
-pub(super) const GAUGE_NAMES: [&str; 12] = [
+pub(super) const GAUGE_NAMES: [&str; 28] = [
+    bft::AWAITING_ACTIVATION,
+    bft::BATCH_DELAY,
+    bft::BATCH_SIZE,
+    bft::CATCHING_UP,
+    bft::COMMIT_LAG,
     bft::CONNECTED,
     bft::CONNECTING,
     bft::LAST_STORED_ROUND,
+    bft::PAUSED,
     bft::PROPOSAL_ROUND,
+    bft::QUORUM_LOST,
     blocks::HEIGHT,
     blocks::TRANSACTIONS,
     consensus::COMMITTED_CERTIFICATES,
     consensus::LAST_COMMITTED_ROUND,
+    consensus::MEMPOOL_SERIAL_NUMBERS,
+    process::CPU_UTILIZATION,
+    process::OPEN_FDS,
+    process::RESIDENT_MEMORY,
     router::CONNECTED,
     router::CANDIDATE,
+    router::CLOCK_OFFSET,
     router::RESTRICTED,
+    runtime::ACTIVE_TASKS,
+    runtime::QUEUE_DEPTH,
+    runtime::WORKER_UTILIZATION,
+    runtime::WORKERS,
     tcp::TCP_TASKS,
 ];
 
@@ -40,12 +69,23 @@ pub(super) const HISTOGRAM_NAMES: [&str; 7] = [
 ];
 
 pub mod bft {
+    pub const AWAITING_ACTIVATION: &str = "snarkos_bft_awaiting_activation";
+    pub const BATCH_DELAY: &str = "snarkos_bft_batch_delay_ms";
+    pub const BATCH_SIZE: &str = "snarkos_bft_batch_size_transmissions";
+    pub const BLOCK_HASH_CONFLICTS: &str = "snarkos_bft_block_hash_conflicts_total";
+    pub const CATCHING_UP: &str = "snarkos_bft_catching_up";
+    pub const COMMIT_LAG: &str = "snarkos_bft_commit_lag_rounds";
     pub const COMMIT_ROUNDS_LATENCY: &str = "snarkos_bft_commit_rounds_latency_secs"; // <-- This one doesn't even make sense.
     pub const CONNECTED: &str = "snarkos_bft_connected_total";
     pub const CONNECTING: &str = "snarkos_bft_connecting_total";
     pub const LAST_STORED_ROUND: &str = "snarkos_bft_last_stored_round";
     pub const LEADERS_ELECTED: &str = "snarkos_bft_leaders_elected_total";
+    pub const LEADERS_MISSING: &str = "snarkos_bft_leaders_missing_total";
+    pub const PAUSED: &str = "snarkos_bft_paused";
     pub const PROPOSAL_ROUND: &str = "snarkos_bft_primary_proposal_round";
+    pub const QUORUM_LOST: &str = "snarkos_bft_quorum_lost";
+    pub const ROUNDS_STALLED: &str = "snarkos_bft_rounds_stalled_total";
+    pub const TRANSACTIONS_REJECTED: &str = "snarkos_bft_transactions_rejected_total";
 }
 
 pub mod blocks {
@@ -58,12 +98,38 @@ pub mod consensus {
     pub const COMMITTED_CERTIFICATES: &str = "snarkos_consensus_committed_certificates_total";
     pub const LAST_COMMITTED_ROUND: &str = "snarkos_consensus_last_committed_round";
     pub const BLOCK_LATENCY: &str = "snarkos_consensus_block_latency_secs";
+    pub const MEMPOOL_CONFLICTS: &str = "snarkos_consensus_mempool_conflicts_total";
+    pub const MEMPOOL_SERIAL_NUMBERS: &str = "snarkos_consensus_mempool_serial_numbers";
+}
+
+pub mod process {
+    pub const CPU_UTILIZATION: &str = "snarkos_process_cpu_utilization_percent";
+    pub const OPEN_FDS: &str = "snarkos_process_open_fds_total";
+    pub const RESIDENT_MEMORY: &str = "snarkos_process_resident_memory_bytes";
 }
 
 pub mod router {
     pub const CONNECTED: &str = "snarkos_router_connected_total";
     pub const CANDIDATE: &str = "snarkos_router_candidate_total";
     pub const RESTRICTED: &str = "snarkos_router_restricted_total";
+    pub const OVERSIZED_MESSAGES: &str = "snarkos_router_oversized_messages_total";
+    pub const MALFORMED_FRAMES: &str = "snarkos_router_malformed_frames_total";
+    pub const DISCONNECTS_RECEIVED: &str = "snarkos_router_disconnects_received_total";
+    pub const DISCONNECTS_SENT: &str = "snarkos_router_disconnects_sent_total";
+    pub const BLOCK_CACHE_HITS: &str = "snarkos_router_block_cache_hits_total";
+    pub const BLOCK_CACHE_MISSES: &str = "snarkos_router_block_cache_misses_total";
+    pub const CLOCK_OFFSET: &str = "snarkos_router_clock_offset_ms";
+    /// The label holding the disconnect reason, for the disconnect counters.
+    pub const DISCONNECT_REASON_LABEL: &str = "reason";
+    /// The label holding the reason a frame is malformed, for the malformed frames counter.
+    pub const MALFORMED_FRAME_REASON_LABEL: &str = "reason";
+}
+
+pub mod runtime {
+    pub const ACTIVE_TASKS: &str = "snarkos_runtime_active_tasks_total";
+    pub const QUEUE_DEPTH: &str = "snarkos_runtime_queue_depth_total";
+    pub const WORKER_UTILIZATION: &str = "snarkos_runtime_worker_utilization_percent";
+    pub const WORKERS: &str = "snarkos_runtime_workers_total";
 }
 
 pub mod tcp {
@@ -0,0 +1,196 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use metrics_exporter_prometheus::PrometheusHandle;
+use std::{path::PathBuf, sync::OnceLock, time::Duration};
+use tracing::{debug, warn};
+
+/// The default interval in seconds in between the pushes to the push gateway.
+pub const DEFAULT_PUSH_INTERVAL_IN_SECS: u64 = 15;
+/// The timeout in seconds of a single push to the push gateway.
+const PUSH_TIMEOUT_IN_SECS: u64 = 10;
+/// The number of times a failed push is retried, with an exponential backoff, before it is spooled.
+const MAX_PUSH_RETRIES: u32 = 3;
+/// The timeout in seconds of the final push on shutdown, which is attempted once, so that it never delays shutdown.
+const FLUSH_TIMEOUT_IN_SECS: u64 = 2;
+
+/// The push gateway of the node, once it is started.
+static PUSH_GATEWAY: OnceLock<PushGateway> = OnceLock::new();
+
+/// The configuration of the push gateway, for the nodes that cannot be scraped by Prometheus (e.g. behind a NAT).
+#[derive(Clone, Debug)]
+pub struct PushGatewayConfig {
+    /// The URL of the push gateway, including the grouping key of the node,
+    /// e.g. `http://pushgateway:9091/metrics/job/snarkos/instance/validator-0`.
+    pub endpoint: String,
+    /// The interval in between the pushes.
+    pub interval: Duration,
+    /// The file in which the metrics that could not be pushed are spooled, so that they survive a crash of the node.
+    pub spool_path: Option<PathBuf>,
+}
+
+impl PushGatewayConfig {
+    /// The name of the spool file, which is stored inside the ledger directory.
+    pub const SPOOL_FILE_NAME: &'static str = "metrics-spool.prom";
+
+    /// Initializes a new push gateway configuration for the given endpoint, with the default interval.
+    pub fn new(endpoint: String) -> Self {
+        Self { endpoint, interval: Duration::from_secs(DEFAULT_PUSH_INTERVAL_IN_SECS), spool_path: None }
+    }
+}
+
+/// Periodically pushes the rendered metrics of the node to a Prometheus push gateway.
+///
+/// A failed push is retried with an exponential backoff, and the metrics are then spooled to disk (atomically),
+/// until a later push succeeds. The spooled metrics of a previous run are pushed first on startup, so the last
+/// state of a node that crashed while the push gateway was unreachable is not lost.
+struct PushGateway {
+    /// The configuration of the push gateway.
+    config: PushGatewayConfig,
+    /// The handle to render the metrics.
+    handle: PrometheusHandle,
+    /// The HTTP client.
+    client: reqwest::Client,
+}
+
+impl PushGateway {
+    /// Pushes the given rendered metrics to the push gateway, with the given timeout per attempt,
+    /// retrying up to the given number of times with an exponential backoff on failure.
+    async fn push_body(&self, body: &str, max_retries: u32, timeout: Duration) -> Result<(), String> {
+        let mut backoff = Duration::from_secs(1);
+        let mut attempt = 0;
+        loop {
+            let request = self.client.put(&self.config.endpoint).timeout(timeout).body(body.to_string());
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => format!("the push gateway responded with '{}'", response.status()),
+                Err(error) => error.to_string(),
+            };
+            attempt += 1;
+            if attempt > max_retries {
+                return Err(error);
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    /// Pushes the current metrics, with the given number of retries and timeout, and spools them if the push fails.
+    async fn push(&self, max_retries: u32, timeout: Duration) {
+        let body = self.handle.render();
+        match self.push_body(&body, max_retries, timeout).await {
+            Ok(()) => {
+                // Discard the spooled metrics, as they are superseded.
+                if let Some(path) = &self.config.spool_path {
+                    if path.exists() {
+                        let _ = std::fs::remove_file(path);
+                    }
+                }
+            }
+            Err(error) => {
+                warn!("Failed to push the metrics to '{}' - {error}", self.config.endpoint);
+                if let Err(error) = self.spool(&body) {
+                    warn!("Failed to spool the metrics - {error}");
+                }
+            }
+        }
+    }
+
+    /// Writes the given rendered metrics to the spool file, atomically.
+    fn spool(&self, body: &str) -> std::io::Result<()> {
+        let Some(path) = &self.config.spool_path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, body)?;
+        std::fs::rename(temp_path, path)
+    }
+
+    /// Pushes the metrics spooled by a previous run, if any.
+    async fn push_spooled(&self) {
+        let Some(path) = &self.config.spool_path else {
+            return;
+        };
+        let Ok(body) = std::fs::read_to_string(path) else {
+            return;
+        };
+        match self.push_body(&body, MAX_PUSH_RETRIES, Duration::from_secs(PUSH_TIMEOUT_IN_SECS)).await {
+            Ok(()) => {
+                debug!("Pushed the spooled metrics of the previous run to '{}'", self.config.endpoint);
+                let _ = std::fs::remove_file(path);
+            }
+            Err(error) => warn!("Failed to push the spooled metrics to '{}' - {error}", self.config.endpoint),
+        }
+    }
+}
+
+/// Starts a task that periodically pushes the metrics rendered by the given handle to the push gateway.
+pub(crate) fn start_push_gateway(config: PushGatewayConfig, handle: PrometheusHandle) {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(PUSH_TIMEOUT_IN_SECS))
+        .build()
+        .expect("can't build the push gateway client");
+    if PUSH_GATEWAY.set(PushGateway { config, handle, client }).is_err() {
+        warn!("The metrics push gateway is already started");
+        return;
+    }
+    tokio::spawn(async move {
+        let Some(gateway) = PUSH_GATEWAY.get() else {
+            return;
+        };
+        // Push the metrics that were spooled by a previous run.
+        gateway.push_spooled().await;
+        let mut interval = tokio::time::interval(gateway.config.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            gateway.push(MAX_PUSH_RETRIES, Duration::from_secs(PUSH_TIMEOUT_IN_SECS)).await;
+        }
+    });
+}
+
+/// Pushes the final metrics of the node to the push gateway, if it is started.
+/// This is called on shutdown, so the last state of the node is not lost in between two pushes.
+/// The push is attempted once with a short timeout, and the metrics are spooled if it fails.
+pub async fn flush_metrics() {
+    if let Some(gateway) = PUSH_GATEWAY.get() {
+        gateway.push(0, Duration::from_secs(FLUSH_TIMEOUT_IN_SECS)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spool() {
+        let dir = std::env::temp_dir().join(format!("snarkos-metrics-spool-{}", std::process::id()));
+        let path = dir.join("metrics.prom");
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        let mut config = PushGatewayConfig::new("http://127.0.0.1:9091/metrics/job/snarkos".to_string());
+        config.spool_path = Some(path.clone());
+        let gateway = PushGateway { config, handle: recorder.handle(), client: reqwest::Client::new() };
+
+        // Ensure the metrics are spooled, and replaced by the newer metrics.
+        gateway.spool("first 1\n").unwrap();
+        gateway.spool("second 2\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second 2\n");
+        assert!(!path.with_extension("tmp").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
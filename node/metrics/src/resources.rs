@@ -0,0 +1,174 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+/// The interval in seconds in between the samples of the resource usage of the node.
+pub const RESOURCE_SAMPLE_INTERVAL_IN_SECS: u64 = 5;
+
+/// A sample of the resource usage of the node process.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProcessSample {
+    /// The resident set size of the process, in bytes.
+    pub resident_memory: Option<u64>,
+    /// The CPU time (user and system) consumed by the process so far.
+    pub cpu_time: Option<Duration>,
+    /// The number of file descriptors open by the process.
+    pub open_fds: Option<u64>,
+}
+
+impl ProcessSample {
+    /// Samples the resource usage of the current process.
+    /// The fields that cannot be determined on the current platform are left as `None`.
+    pub fn read() -> Self {
+        Self { resident_memory: resident_memory(), cpu_time: cpu_time(), open_fds: open_fds() }
+    }
+}
+
+/// Returns the CPU utilization in percent (of a single core) in between the two given CPU times.
+fn cpu_utilization(previous: Duration, current: Duration, elapsed: Duration) -> f64 {
+    match elapsed.is_zero() {
+        true => 0.0,
+        false => current.saturating_sub(previous).as_secs_f64() / elapsed.as_secs_f64() * 100.0,
+    }
+}
+
+/// Returns the resident set size of the current process, in bytes.
+fn resident_memory() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        // The line is formatted as `VmRSS:     1234 kB`.
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+        let kilobytes = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+        Some(kilobytes * 1024)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Returns the CPU time (user and system) consumed by the current process.
+fn cpu_time() -> Option<Duration> {
+    #[cfg(target_family = "unix")]
+    {
+        use nix::sys::{
+            resource::{getrusage, UsageWho},
+            time::TimeValLike,
+        };
+
+        let usage = getrusage(UsageWho::RUSAGE_SELF).ok()?;
+        let micros = usage.user_time().num_microseconds() + usage.system_time().num_microseconds();
+        Some(Duration::from_micros(u64::try_from(micros).ok()?))
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        None
+    }
+}
+
+/// Returns the number of file descriptors open by the current process.
+fn open_fds() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        Some(std::fs::read_dir("/proc/self/fd").ok()?.count() as u64)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Updates the gauges of the current tokio runtime, and returns the number of its workers
+/// along with their total busy duration.
+///
+/// Note: The runtime metrics are only available if the node is built with `RUSTFLAGS="--cfg tokio_unstable"`.
+fn sample_runtime() -> Option<(usize, Duration)> {
+    #[cfg(tokio_unstable)]
+    {
+        let runtime = tokio::runtime::Handle::current().metrics();
+        let num_workers = runtime.num_workers();
+        let local_queue_depth = (0..num_workers).map(|i| runtime.worker_local_queue_depth(i)).sum::<usize>();
+
+        crate::gauge(crate::runtime::WORKERS, num_workers as f64);
+        crate::gauge(crate::runtime::ACTIVE_TASKS, runtime.active_tasks_count() as f64);
+        crate::gauge(crate::runtime::QUEUE_DEPTH, (runtime.injection_queue_depth() + local_queue_depth) as f64);
+        Some((num_workers, (0..num_workers).map(|i| runtime.worker_total_busy_duration(i)).sum()))
+    }
+    #[cfg(not(tokio_unstable))]
+    {
+        None
+    }
+}
+
+/// Starts a task that periodically samples the resource usage of the node process and its tokio runtime.
+pub fn start_resource_metrics() -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(RESOURCE_SAMPLE_INTERVAL_IN_SECS));
+        let mut previous: Option<(Instant, ProcessSample, Option<(usize, Duration)>)> = None;
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+
+            // Sample the process.
+            let sample = ProcessSample::read();
+            if let Some(resident_memory) = sample.resident_memory {
+                crate::gauge(crate::process::RESIDENT_MEMORY, resident_memory as f64);
+            }
+            if let Some(open_fds) = sample.open_fds {
+                crate::gauge(crate::process::OPEN_FDS, open_fds as f64);
+            }
+            // Sample the runtime.
+            let runtime = sample_runtime();
+
+            // Derive the utilization since the previous sample.
+            if let Some((timestamp, previous_sample, previous_runtime)) = previous {
+                let elapsed = now.saturating_duration_since(timestamp);
+                if let (Some(previous), Some(current)) = (previous_sample.cpu_time, sample.cpu_time) {
+                    crate::gauge(crate::process::CPU_UTILIZATION, cpu_utilization(previous, current, elapsed));
+                }
+                if let (Some((_, previous)), Some((num_workers, current))) = (previous_runtime, runtime) {
+                    let utilization = cpu_utilization(previous, current, elapsed) / num_workers.max(1) as f64;
+                    crate::gauge(crate::runtime::WORKER_UTILIZATION, utilization);
+                }
+            }
+            previous = Some((now, sample, runtime));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_utilization() {
+        let elapsed = Duration::from_secs(2);
+        assert_eq!(cpu_utilization(Duration::from_secs(1), Duration::from_secs(2), elapsed), 50.0);
+        assert_eq!(cpu_utilization(Duration::from_secs(1), Duration::from_secs(5), elapsed), 200.0);
+        // A clock that did not advance yields no utilization.
+        assert_eq!(cpu_utilization(Duration::from_secs(1), Duration::from_secs(2), Duration::ZERO), 0.0);
+        assert_eq!(cpu_utilization(Duration::from_secs(2), Duration::from_secs(1), elapsed), 0.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_sample() {
+        let sample = ProcessSample::read();
+        assert!(sample.resident_memory.unwrap() > 0);
+        assert!(sample.cpu_time.is_some());
+        assert!(sample.open_fds.unwrap() > 0);
+    }
+}
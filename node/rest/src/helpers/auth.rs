@@ -30,19 +30,35 @@ use axum_extra::{
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// The time a jwt token is valid for.
 pub const EXPIRATION: i64 = 10 * 365 * 24 * 60 * 60; // 10 years.
 
+/// The JWT secret for the node instance.
+static SECRET: OnceCell<Vec<u8>> = OnceCell::new();
+
 /// Returns the JWT secret for the node instance.
+/// Unless an admin key is set, the secret is sampled at random, and the tokens are only valid until a restart.
 fn jwt_secret() -> &'static Vec<u8> {
-    static SECRET: OnceCell<Vec<u8>> = OnceCell::new();
     SECRET.get_or_init(|| {
         let seed: [u8; 16] = ::rand::thread_rng().gen();
         seed.to_vec()
     })
 }
 
+/// The domain separator of the JWT secret, which is derived from the private key of the admin account.
+const JWT_SECRET_DOMAIN: &[u8] = b"snarkos-rest-jwt-secret-v1";
+
+/// Derives the JWT secret from the private key of the admin account of the node, as the SHA-256 hash of a domain
+/// separator and the private key. The tokens are then signed with a key that is separate from the keys of the node,
+/// and remain valid across restarts.
+/// Note: This must be called before any token is issued or checked.
+pub fn set_jwt_signing_key<N: Network>(private_key: &PrivateKey<N>) -> Result<()> {
+    let secret = Sha256::new().chain_update(JWT_SECRET_DOMAIN).chain_update(private_key.to_bytes_le()?).finalize();
+    SECRET.set(secret.to_vec()).map_err(|_| anyhow!("The JWT secret is already set"))
+}
+
 /// The Json web token claims.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Claims {
@@ -62,6 +78,20 @@ impl Claims {
         Self { sub: address.to_string(), iat: issued_at, exp: expiration }
     }
 
+    /// Initializes the claims of a token for the given operator of the REST server.
+    /// The subject is `operator:{name}`, so that the actions of each operator are journaled apart.
+    pub fn for_operator(name: &str) -> Self {
+        let issued_at = OffsetDateTime::now_utc().unix_timestamp();
+        let expiration = issued_at.saturating_add(EXPIRATION);
+
+        Self { sub: format!("operator:{name}"), iat: issued_at, exp: expiration }
+    }
+
+    /// Returns the subject of the token, i.e. the principal that it authenticates.
+    pub fn subject(&self) -> &str {
+        &self.sub
+    }
+
     /// Returns true if the token is expired.
     pub fn is_expired(&self) -> bool {
         OffsetDateTime::now_utc().unix_timestamp() >= self.exp
@@ -85,6 +115,8 @@ pub async fn auth_middleware(request: Request<Body>, next: Next) -> Result<Respo
             if claims.is_expired() {
                 return Err((StatusCode::UNAUTHORIZED, "Expired JSON Web Token".to_owned()).into_response());
             }
+            // Pass the claims to the handler, to identify the principal of the request.
+            parts.extensions.insert(claims);
         }
 
         Err(_) => {
@@ -0,0 +1,84 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{bind_listener, serve_listener, NodeStatus, PeerCounts, RestListener};
+use snarkos_node_cdn::CdnSyncProgress;
+use snarkos_node_router::messages::NodeType;
+
+use anyhow::{anyhow, Result};
+use axum::routing::get;
+use axum_extra::response::ErasedJson;
+use std::net::SocketAddr;
+
+/// A provisional server that reports the progress of the sync from the CDN in the node status, while the node
+/// syncs its ledger from the CDN, before the REST server can start.
+///
+/// The REST server takes over the socket of this server when it starts, so that the address is never unbound.
+pub struct CdnSyncServer {
+    /// The listener that the server serves on.
+    pub(crate) listener: RestListener,
+    /// The progress of the sync from the CDN.
+    pub(crate) progress: CdnSyncProgress,
+}
+
+impl CdnSyncServer {
+    /// Starts serving the node status on the given address, with the given progress of the sync from the CDN.
+    /// The reported height is the given height of the ledger, until the sync starts.
+    pub fn start(
+        rest_ip: SocketAddr,
+        rest_rps: u32,
+        node_type: NodeType,
+        height: u32,
+        progress: CdnSyncProgress,
+    ) -> Result<Self> {
+        let socket =
+            bind_listener(rest_ip).map_err(|error| anyhow!("Failed to bind the REST server to '{rest_ip}' - {error}"))?;
+
+        // Serve the node status only, as the node is not initialized yet.
+        let status = progress.clone();
+        let router = axum::Router::new().route(
+            "/testnet3/node/status",
+            get(move || {
+                let cdn_sync = status.status();
+                async move {
+                    ErasedJson::pretty(NodeStatus {
+                        node_type: node_type.to_string(),
+                        height: cdn_sync.as_ref().map_or(height, |cdn_sync| cdn_sync.current_height),
+                        highest_peer_height: None,
+                        is_synced: false,
+                        peers: PeerCounts::default(),
+                        memory_pool: None,
+                        bft: None,
+                        cdn_sync,
+                    })
+                }
+            }),
+        );
+
+        // Serve the listener, until the REST server takes it over.
+        let listener = serve_listener(&socket)?;
+        let (shutdown, signal) = tokio::sync::oneshot::channel();
+        let handle = tokio::spawn(async move {
+            let signal = async {
+                let _ = signal.await;
+            };
+            if let Err(error) = axum::serve(listener, router).with_graceful_shutdown(signal).await {
+                error!("The CDN sync status server on '{rest_ip}' failed - {error}");
+            }
+        });
+        info!("Serving the progress of the CDN sync on '{rest_ip}'");
+        let listener = RestListener::new(rest_ip, rest_rps, socket, shutdown, handle.abort_handle());
+        Ok(Self { listener, progress })
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::http::HeaderMap;
+
+/// The header that carries the correlation ID of a request.
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+/// The maximum length of a correlation ID that is supplied by the client.
+const MAX_CORRELATION_ID_LENGTH: usize = 64;
+
+/// Returns the correlation ID of a request, which is taken from its header if it is well-formed,
+/// or generated otherwise.
+pub(crate) fn correlation_id(headers: &HeaderMap) -> String {
+    headers
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid_correlation_id(id))
+        .map(String::from)
+        .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>()))
+}
+
+/// Returns `true` if the given correlation ID is short and only contains alphanumerics, dashes, and underscores,
+/// so that it can be safely written to the logs.
+fn is_valid_correlation_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_CORRELATION_ID_LENGTH
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_correlation_id() {
+        // A well-formed correlation ID is taken from the header.
+        let mut headers = HeaderMap::new();
+        headers.insert(CORRELATION_ID_HEADER, HeaderValue::from_static("deploy-42_a"));
+        assert_eq!(correlation_id(&headers), "deploy-42_a");
+
+        // A malformed correlation ID is replaced with a generated one.
+        headers.insert(CORRELATION_ID_HEADER, HeaderValue::from_static("a b"));
+        let generated = correlation_id(&headers);
+        assert_eq!(generated.len(), 16);
+        assert!(is_valid_correlation_id(&generated));
+        let too_long = "a".repeat(MAX_CORRELATION_ID_LENGTH + 1);
+        headers.insert(CORRELATION_ID_HEADER, HeaderValue::from_str(&too_long).unwrap());
+        assert_ne!(correlation_id(&headers), too_long);
+
+        // A missing correlation ID is generated.
+        assert!(is_valid_correlation_id(&correlation_id(&HeaderMap::new())));
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+use time::OffsetDateTime;
+
+/// The maximum number of bytes of the log file that are included in a debug bundle.
+pub const MAXIMUM_LOG_TAIL_IN_BYTES: u64 = 4 * 1024 * 1024;
+/// The number of the most recent consensus rounds that are summarized in a debug bundle.
+pub const NUM_DEBUG_BUNDLE_ROUNDS: usize = 32;
+
+/// The local context of the node that is included in its debug bundles, as set by the operator.
+#[derive(Clone, Debug, Default)]
+pub struct DebugBundleConfig {
+    /// The path of the log file of the node.
+    pub log_path: Option<PathBuf>,
+    /// The path of the ledger directory of the node.
+    pub storage_path: Option<PathBuf>,
+    /// The configuration of the node, with its secrets redacted.
+    pub config: Option<String>,
+}
+
+/// The disk usage of the ledger directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct StorageStats {
+    /// The path of the ledger directory.
+    pub path: PathBuf,
+    /// The total size of the ledger directory, in bytes.
+    pub total_bytes: u64,
+    /// The size of each entry in the ledger directory, in bytes.
+    pub entries: BTreeMap<String, u64>,
+}
+
+impl StorageStats {
+    /// Measures the disk usage of the given ledger directory.
+    pub fn measure(path: &Path) -> Result<Self> {
+        let mut stats = Self { path: path.to_path_buf(), ..Default::default() };
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let size = Self::size_of(&entry.path())?;
+            stats.total_bytes += size;
+            stats.entries.insert(entry.file_name().to_string_lossy().into_owned(), size);
+        }
+        Ok(stats)
+    }
+
+    /// Returns the size of the given file, or the total size of the given directory, in bytes.
+    fn size_of(path: &Path) -> Result<u64> {
+        let metadata = std::fs::symlink_metadata(path)?;
+        if !metadata.is_dir() {
+            return Ok(metadata.len());
+        }
+        let mut size = 0;
+        for entry in std::fs::read_dir(path)? {
+            size += Self::size_of(&entry?.path())?;
+        }
+        Ok(size)
+    }
+}
+
+/// Returns at most the last `maximum` bytes of the given log file, starting at a line boundary.
+pub fn read_log_tail(path: &Path, maximum: u64) -> Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let length = file.metadata()?.len();
+    let start = length.saturating_sub(maximum);
+    file.seek(SeekFrom::Start(start))?;
+    let mut tail = Vec::with_capacity((length - start) as usize);
+    file.take(maximum).read_to_end(&mut tail)?;
+    // Drop the partial line at the start of the tail.
+    if start > 0 {
+        let first_line = tail.iter().position(|byte| *byte == b'\n').map_or(tail.len(), |index| index + 1);
+        tail.drain(..first_line);
+    }
+    Ok(tail)
+}
+
+/// An entry of the central directory of a zip archive.
+struct ZipEntry {
+    /// The name of the file.
+    name: String,
+    /// The CRC-32 of the contents of the file.
+    crc: u32,
+    /// The size of the file, in bytes.
+    size: u32,
+    /// The offset of the local header of the file in the archive.
+    offset: u32,
+}
+
+/// A writer of zip archives, whose files are stored without compression.
+pub struct ZipWriter {
+    /// The bytes of the archive so far.
+    buffer: Vec<u8>,
+    /// The entries of the files in the archive.
+    entries: Vec<ZipEntry>,
+    /// The modification time and date of the files, in the MS-DOS format.
+    modified: (u16, u16),
+}
+
+impl Default for ZipWriter {
+    /// Initializes a new, empty zip archive, whose files are dated now.
+    fn default() -> Self {
+        Self { buffer: Vec::new(), entries: Vec::new(), modified: Self::dos_date_time(OffsetDateTime::now_utc()) }
+    }
+}
+
+impl ZipWriter {
+    /// The signature of a local file header.
+    const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+    /// The signature of a central directory header.
+    const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+    /// The signature of the end of central directory record.
+    const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+    /// The version of the zip format needed to extract the files, i.e. 2.0.
+    const VERSION: u16 = 20;
+    /// The general purpose flag for UTF-8 file names.
+    const UTF8_FLAG: u16 = 1 << 11;
+
+    /// Adds a file with the given name and contents to the archive.
+    pub fn add(&mut self, name: &str, contents: &[u8]) -> Result<()> {
+        let (Ok(size), Ok(offset), Ok(_)) =
+            (u32::try_from(contents.len()), u32::try_from(self.buffer.len()), u16::try_from(name.len()))
+        else {
+            bail!("The file '{name}' does not fit in the zip archive")
+        };
+        let crc = crc32fast::hash(contents);
+        self.write_header(Self::LOCAL_HEADER_SIGNATURE, None, name, crc, size);
+        self.buffer.extend_from_slice(name.as_bytes());
+        self.buffer.extend_from_slice(contents);
+        self.entries.push(ZipEntry { name: name.to_string(), crc, size, offset });
+        Ok(())
+    }
+
+    /// Adds a file with the given name and the pretty-printed JSON of the given value to the archive.
+    pub fn add_json<T: Serialize>(&mut self, name: &str, value: &T) -> Result<()> {
+        self.add(name, &serde_json::to_vec_pretty(value)?)
+    }
+
+    /// Writes the central directory, and returns the bytes of the archive.
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        let (Ok(start), Ok(num_entries)) = (u32::try_from(self.buffer.len()), u16::try_from(self.entries.len()))
+        else {
+            bail!("The zip archive is too large")
+        };
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            self.write_header(Self::CENTRAL_HEADER_SIGNATURE, Some(entry.offset), &entry.name, entry.crc, entry.size);
+            self.buffer.extend_from_slice(entry.name.as_bytes());
+        }
+        let Ok(end) = u32::try_from(self.buffer.len()) else { bail!("The zip archive is too large") };
+
+        // Write the end of central directory record.
+        self.write_u32(Self::END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        self.write_u16(0); // The number of this disk.
+        self.write_u16(0); // The disk of the central directory.
+        self.write_u16(num_entries);
+        self.write_u16(num_entries);
+        self.write_u32(end - start);
+        self.write_u32(start);
+        self.write_u16(0); // The length of the comment.
+        Ok(self.buffer)
+    }
+
+    /// Writes a local file header, or a central directory header with the given local header offset.
+    /// The file name is written by the caller, right after the header.
+    fn write_header(&mut self, signature: u32, offset: Option<u32>, name: &str, crc: u32, size: u32) {
+        self.write_u32(signature);
+        if offset.is_some() {
+            self.write_u16(Self::VERSION); // The version made by.
+        }
+        self.write_u16(Self::VERSION);
+        self.write_u16(Self::UTF8_FLAG);
+        self.write_u16(0); // The compression method, i.e. stored.
+        self.write_u16(self.modified.0);
+        self.write_u16(self.modified.1);
+        self.write_u32(crc);
+        self.write_u32(size); // The compressed size.
+        self.write_u32(size); // The uncompressed size.
+        self.write_u16(name.len() as u16);
+        self.write_u16(0); // The length of the extra field.
+        if let Some(offset) = offset {
+            self.write_u16(0); // The length of the comment.
+            self.write_u16(0); // The disk of the file.
+            self.write_u16(0); // The internal attributes.
+            self.write_u32(0); // The external attributes.
+            self.write_u32(offset);
+        }
+    }
+
+    /// Writes the given `u16` in little-endian.
+    fn write_u16(&mut self, value: u16) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes the given `u32` in little-endian.
+    fn write_u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Returns the given timestamp as an MS-DOS time and date, which start in 1980.
+    fn dos_date_time(timestamp: OffsetDateTime) -> (u16, u16) {
+        let year = timestamp.year().clamp(1980, 2107) as u16 - 1980;
+        let time = (timestamp.hour() as u16) << 11 | (timestamp.minute() as u16) << 5 | timestamp.second() as u16 / 2;
+        let date = year << 9 | (timestamp.month() as u16) << 5 | timestamp.day() as u16;
+        (time, date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zip_writer() {
+        let mut zip = ZipWriter::default();
+        zip.add("status.json", b"{}").unwrap();
+        zip.add("snarkos.log", b"line 1\nline 2\n").unwrap();
+        let bytes = zip.finish().unwrap();
+
+        // The first local header is at the start, followed by its name and contents.
+        assert_eq!(&bytes[..4], &ZipWriter::LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        assert_eq!(&bytes[14..18], &crc32fast::hash(b"{}").to_le_bytes());
+        assert_eq!(&bytes[30..43], b"status.json{}");
+
+        // The end of central directory record counts both files, and points to the central directory.
+        let end = &bytes[bytes.len() - 22..];
+        assert_eq!(&end[..4], &ZipWriter::END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+        let start = u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize;
+        assert_eq!(&bytes[start..start + 4], &ZipWriter::CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+        assert_eq!(&bytes[start + 46..start + 57], b"status.json");
+    }
+
+    #[test]
+    fn test_read_log_tail() {
+        let path = std::env::temp_dir().join(format!("snarkos-log-tail-{}", rand::random::<u64>()));
+        std::fs::write(&path, b"first line\nsecond line\nthird line\n").unwrap();
+
+        // The whole file is returned when it fits.
+        assert_eq!(read_log_tail(&path, 1024).unwrap(), b"first line\nsecond line\nthird line\n");
+        // Otherwise, the partial line at the start of the tail is dropped.
+        assert_eq!(read_log_tail(&path, 16).unwrap(), b"third line\n");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
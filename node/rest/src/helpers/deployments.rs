@@ -0,0 +1,81 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{store::ConsensusStorage, Address, Ledger, Network, ProgramID, ToBytes};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// The metadata of a program deployment, as looked up in the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(bound = "")]
+pub struct DeploymentMetadata<N: Network> {
+    /// The ID of the program.
+    pub program_id: ProgramID<N>,
+    /// The ID of the transaction that deployed the program.
+    pub transaction_id: N::TransactionID,
+    /// The address of the deployer, i.e. the owner of the program.
+    pub deployer: Address<N>,
+    /// The height of the block in which the program was deployed.
+    pub height: u32,
+    /// The size of the deployment, in bytes.
+    pub size_in_bytes: usize,
+    /// The edition of the program.
+    pub edition: u16,
+}
+
+impl<N: Network> DeploymentMetadata<N> {
+    /// The number of programs returned per page.
+    pub const PAGE_SIZE: usize = 50;
+
+    /// Returns the metadata of the deployment of the given program,
+    /// or `None` if the program was not deployed by a transaction.
+    pub fn get<C: ConsensusStorage<N>>(ledger: &Ledger<N, C>, program_id: &ProgramID<N>) -> Result<Option<Self>> {
+        let store = ledger.vm().transaction_store().deployment_store();
+        let Some(transaction_id) = store.find_transaction_id_from_program_id(program_id)? else {
+            return Ok(None);
+        };
+        let Some(deployment) = store.get_deployment(&transaction_id)? else {
+            bail!("Missing the deployment of '{program_id}' in transaction '{transaction_id}'")
+        };
+        let Some(owner) = store.get_owner(program_id)? else {
+            bail!("Missing the owner of '{program_id}' in transaction '{transaction_id}'")
+        };
+        let Some(block_hash) = ledger.find_block_hash(&transaction_id)? else {
+            bail!("Missing the block of the deployment of '{program_id}' in transaction '{transaction_id}'")
+        };
+        Ok(Some(Self {
+            program_id: *program_id,
+            transaction_id,
+            deployer: owner.address(),
+            height: ledger.get_height(&block_hash)?,
+            size_in_bytes: deployment.to_bytes_le()?.len(),
+            edition: deployment.edition(),
+        }))
+    }
+
+    /// Returns the number of deployed programs, and the metadata of the programs on the given page,
+    /// starting from page 0. The programs are listed in the order of the transaction store.
+    pub fn page<C: ConsensusStorage<N>>(ledger: &Ledger<N, C>, page: usize) -> Result<(usize, Vec<Self>)> {
+        let start = page.saturating_mul(Self::PAGE_SIZE);
+        let (mut total, mut programs) = (0, Vec::new());
+        for program_id in ledger.vm().transaction_store().program_ids() {
+            if total >= start && programs.len() < Self::PAGE_SIZE {
+                programs.extend(Self::get(ledger, &program_id)?);
+            }
+            total += 1;
+        }
+        Ok((total, programs))
+    }
+}
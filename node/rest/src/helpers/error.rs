@@ -26,6 +26,15 @@ impl IntoResponse for RestError {
     }
 }
 
+/// A response for a request that is refused while the node is busy, which the client may retry later.
+pub struct RestBusy(pub String);
+
+impl IntoResponse for RestBusy {
+    fn into_response(self) -> Response {
+        (StatusCode::TOO_MANY_REQUESTS, self.0).into_response()
+    }
+}
+
 impl From<anyhow::Error> for RestError {
     fn from(err: anyhow::Error) -> Self {
         Self(err.to_string())
@@ -0,0 +1,230 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    block::{Execution, Transaction},
+    execution_cost,
+    store::ConsensusStorage,
+    Authorization,
+    Ciphertext,
+    CryptoRng,
+    Group,
+    Ledger,
+    Literal,
+    Network,
+    Plaintext,
+    PrivateKey,
+    Rng,
+    Value,
+    ViewKey,
+};
+
+use anyhow::{bail, ensure, Result};
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of executions that are proven concurrently.
+pub const MAX_CONCURRENT_EXECUTIONS: usize = 2;
+/// The maximum number of executions that are estimated concurrently.
+pub const MAX_CONCURRENT_ESTIMATES: usize = 4;
+/// The maximum number of seconds an execution may take, including its proof.
+pub const EXECUTION_TIMEOUT_IN_SECS: u64 = 120; // 2 minutes
+/// The number of bytes that is allowed for the proof of each transition of an estimated execution.
+/// As the estimate does not prove the execution, this allowance stands in for the size of its proof.
+pub const PROOF_SIZE_ALLOWANCE_IN_BYTES: u64 = 1024;
+
+/// A private key, encrypted to the address of the node.
+///
+/// The seed of the private key is encrypted as a field plaintext, in the same way as a record:
+/// the client samples a randomizer `r`, encrypts the plaintext to the address of the node with `r`,
+/// and sends the nonce `G^r`, from which the node recovers the shared secret with its view key.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+pub struct EncryptedPrivateKey<N: Network> {
+    /// The ciphertext of the private key seed.
+    pub ciphertext: Ciphertext<N>,
+    /// The nonce of the encryption.
+    pub nonce: Group<N>,
+}
+
+impl<N: Network> EncryptedPrivateKey<N> {
+    /// Decrypts the private key with the view key of the node.
+    pub fn decrypt(&self, view_key: &ViewKey<N>) -> Result<PrivateKey<N>> {
+        match self.ciphertext.decrypt(*view_key, self.nonce)? {
+            Plaintext::Literal(Literal::Field(seed), _) => PrivateKey::try_from(seed),
+            _ => bail!("The encrypted private key is not a field element"),
+        }
+    }
+}
+
+/// The `execute` request object.
+///
+/// The execution is either authorized by the client, in which case the node only generates the proofs,
+/// or signed by the node with a private key that the client encrypted to it.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+pub struct ExecuteRequest<N: Network> {
+    /// The authorization of the execution, signed by the client.
+    #[serde(default)]
+    pub authorization: Option<Authorization<N>>,
+    /// The authorization of the fee, signed by the client.
+    #[serde(default)]
+    pub fee_authorization: Option<Authorization<N>>,
+    /// The private key of the caller, encrypted to the node.
+    #[serde(default)]
+    pub private_key: Option<EncryptedPrivateKey<N>>,
+    /// The program ID, when the node signs the execution (e.g. `credits.aleo`).
+    #[serde(default)]
+    pub program_id: Option<String>,
+    /// The function name, when the node signs the execution (e.g. `transfer_public`).
+    #[serde(default)]
+    pub function: Option<String>,
+    /// The inputs, when the node signs the execution.
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// The priority fee in microcredits, when the node signs the execution.
+    #[serde(default)]
+    pub priority_fee: u64,
+    /// Whether to broadcast the transaction, instead of returning it.
+    #[serde(default)]
+    pub broadcast: bool,
+}
+
+/// The estimated cost of an execution, as returned by `POST /testnet3/execute/estimate`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ExecutionEstimate {
+    /// The number of transitions of the execution.
+    pub transitions: usize,
+    /// The size of the execution in bytes, including the allowance for its proof.
+    pub execution_size_in_bytes: u64,
+    /// The storage cost of the execution in microcredits.
+    pub storage_cost: u64,
+    /// The cost of the finalize logic of the execution in microcredits.
+    pub finalize_cost: u64,
+    /// The minimum base fee of the execution in microcredits, i.e. its storage and finalize costs.
+    pub minimum_fee: u64,
+    /// The priority fee of the request in microcredits, which is paid on top of the base fee.
+    pub priority_fee: u64,
+}
+
+impl<N: Network> ExecuteRequest<N> {
+    /// Executes the request, and returns the proven transaction.
+    /// This method is blocking, as it generates the proofs of the execution and the fee.
+    pub fn execute<C: ConsensusStorage<N>, R: Rng + CryptoRng>(
+        mut self,
+        ledger: &Ledger<N, C>,
+        view_key: &ViewKey<N>,
+        rng: &mut R,
+    ) -> Result<Transaction<N>> {
+        match (self.authorization.take(), self.private_key.take()) {
+            // Prove the execution that the client authorized.
+            (Some(authorization), None) => {
+                ledger.vm().execute_authorization(authorization, self.fee_authorization, None, rng)
+            }
+            // Sign and prove the execution with the private key of the client.
+            (None, Some(private_key)) => {
+                let (program_id, function, inputs) = self.call()?;
+                let private_key = private_key.decrypt(view_key)?;
+                let locator = (program_id, function);
+                ledger.vm().execute(&private_key, locator, inputs.iter(), None, self.priority_fee, None, rng)
+            }
+            _ => bail!("The execution requires either an authorization or an encrypted private key"),
+        }
+    }
+
+    /// Estimates the cost of the execution of the request, against the current state of the ledger.
+    ///
+    /// The execution is authorized, which evaluates its functions, but it is neither proven nor broadcast.
+    /// Its size is estimated from its transitions, with `PROOF_SIZE_ALLOWANCE_IN_BYTES` per transition
+    /// in place of its proof. The finalize logic is costed, but not run, so a finalize that would fail
+    /// against the current state is not detected.
+    pub fn estimate<C: ConsensusStorage<N>, R: Rng + CryptoRng>(
+        self,
+        ledger: &Ledger<N, C>,
+        view_key: &ViewKey<N>,
+        rng: &mut R,
+    ) -> Result<ExecutionEstimate> {
+        let authorization = match (&self.authorization, &self.private_key) {
+            // Estimate the execution that the client authorized.
+            (Some(authorization), None) => authorization.clone(),
+            // Authorize the execution with the private key of the client.
+            (None, Some(private_key)) => {
+                let (program_id, function, inputs) = self.call()?;
+                let private_key = private_key.decrypt(view_key)?;
+                ledger.vm().authorize(&private_key, program_id, function, inputs.iter(), rng)?
+            }
+            _ => bail!("The estimate requires either an authorization or an encrypted private key"),
+        };
+
+        // Assemble the execution from the transitions of the authorization, without a proof.
+        let transitions = authorization.transitions();
+        let num_transitions = transitions.len();
+        let execution = Execution::from(transitions.into_values(), ledger.latest_state_root(), None)?;
+        let (_, (storage_cost, finalize_cost)) = execution_cost(ledger.vm(), &execution)?;
+
+        // Account for the proof, whose bytes are charged like the other bytes of the execution.
+        let proof_allowance = PROOF_SIZE_ALLOWANCE_IN_BYTES * num_transitions as u64;
+        let storage_cost = storage_cost + proof_allowance;
+        Ok(ExecutionEstimate {
+            transitions: num_transitions,
+            execution_size_in_bytes: execution.size_in_bytes()? + proof_allowance,
+            storage_cost,
+            finalize_cost,
+            minimum_fee: storage_cost + finalize_cost,
+            priority_fee: self.priority_fee,
+        })
+    }
+
+    /// Returns the program ID, the function name, and the inputs of a call that the node signs.
+    fn call(&self) -> Result<(&str, &str, Vec<Value<N>>)> {
+        let (Some(program_id), Some(function)) = (&self.program_id, &self.function) else {
+            bail!("The program ID and function are required with a private key")
+        };
+        ensure!(self.inputs.len() <= N::MAX_INPUTS, "The execution has too many inputs");
+        let inputs = self.inputs.iter().map(|input| Value::from_str(input)).collect::<Result<Vec<_>>>()?;
+        Ok((program_id, function, inputs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Address, Scalar, TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_encrypted_private_key() {
+        let rng = &mut TestRng::default();
+        // Sample the account of the node, and the private key of the client.
+        let node_private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let node_address = Address::try_from(&node_private_key).unwrap();
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+
+        // Encrypt the private key seed to the node.
+        let randomizer = Scalar::rand(rng);
+        let plaintext = Plaintext::from(Literal::Field(private_key.seed()));
+        let encrypted = EncryptedPrivateKey {
+            ciphertext: plaintext.encrypt(&node_address, randomizer).unwrap(),
+            nonce: CurrentNetwork::g_scalar_multiply(&randomizer),
+        };
+
+        // Ensure the node recovers the private key.
+        let view_key = ViewKey::try_from(&node_private_key).unwrap();
+        assert_eq!(encrypted.decrypt(&view_key).unwrap(), private_key);
+        // Ensure another node does not.
+        let other_view_key = ViewKey::try_from(&PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        assert_ne!(encrypted.decrypt(&other_view_key).ok(), Some(private_key));
+    }
+}
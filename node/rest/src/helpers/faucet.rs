@@ -0,0 +1,278 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, Network, PrivateKey};
+
+use anyhow::{bail, Context, Result};
+use indexmap::IndexSet;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The default number of seconds an address must wait in between faucet requests.
+const DEFAULT_FAUCET_COOLDOWN_IN_SECS: u64 = 86_400; // 24 hours
+/// The default maximum number of faucet requests funded per block.
+const DEFAULT_FAUCET_BATCH_SIZE: usize = 32;
+/// The default maximum number of faucet requests waiting to be funded.
+const DEFAULT_FAUCET_MAX_PENDING: usize = 1024;
+/// The interval in between prunings of the requests that are past their cooldown.
+pub const FAUCET_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The faucet configuration of a node, loaded from a JSON file.
+///
+/// For example:
+/// ```json
+/// {
+///   "amount": 10000000,
+///   "tokens": ["a-secret-token"],
+///   "cooldown_secs": 86400,
+///   "batch_size": 32,
+///   "max_pending": 1024
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct FaucetConfig {
+    /// The number of microcredits sent per request.
+    pub amount: u64,
+    /// The tokens accepted by the faucet; at least one token is required.
+    pub tokens: Vec<String>,
+    /// The number of seconds an address, or an IP, must wait in between requests.
+    #[serde(default = "FaucetConfig::default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// The maximum number of requests funded per block.
+    #[serde(default = "FaucetConfig::default_batch_size")]
+    pub batch_size: usize,
+    /// The maximum number of requests waiting to be funded, beyond which requests are rejected.
+    #[serde(default = "FaucetConfig::default_max_pending")]
+    pub max_pending: usize,
+}
+
+impl FaucetConfig {
+    /// Loads the faucet configuration from the given path, and ensures it is valid.
+    pub fn load(path: &Path) -> Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the faucet configuration at '{}'", path.display()))?;
+        let config: Self = serde_json::from_str(&config)
+            .with_context(|| format!("Failed to parse the faucet configuration at '{}'", path.display()))?;
+        config.check()?;
+        Ok(config)
+    }
+
+    /// Ensures the faucet configuration is valid.
+    pub fn check(&self) -> Result<()> {
+        if self.tokens.iter().all(|token| token.is_empty()) {
+            bail!("The faucet requires at least one token")
+        }
+        if self.batch_size == 0 || self.max_pending == 0 {
+            bail!("The faucet batch size and maximum pending requests must be nonzero")
+        }
+        Ok(())
+    }
+
+    /// Returns the default number of seconds an address must wait in between requests.
+    const fn default_cooldown_secs() -> u64 {
+        DEFAULT_FAUCET_COOLDOWN_IN_SECS
+    }
+
+    /// Returns the default maximum number of requests funded per block.
+    const fn default_batch_size() -> usize {
+        DEFAULT_FAUCET_BATCH_SIZE
+    }
+
+    /// Returns the default maximum number of requests waiting to be funded.
+    const fn default_max_pending() -> usize {
+        DEFAULT_FAUCET_MAX_PENDING
+    }
+}
+
+/// The `faucet_request` request object.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+pub struct FaucetRequest<N: Network> {
+    /// The address to fund.
+    pub address: Address<N>,
+    /// The faucet token.
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// A faucet, which queues the requested addresses to be funded from the account of the node.
+#[derive(Clone)]
+pub struct Faucet<N: Network> {
+    /// The faucet configuration.
+    config: Arc<FaucetConfig>,
+    /// The private key of the account that funds the requests.
+    private_key: PrivateKey<N>,
+    /// The addresses waiting to be funded, in the order they were requested.
+    pending: Arc<Mutex<IndexSet<Address<N>>>>,
+    /// The time of the last accepted request of each address.
+    last_requests: Arc<Mutex<HashMap<Address<N>, Instant>>>,
+    /// The time of the last accepted request of each IP.
+    last_requests_by_ip: Arc<Mutex<HashMap<IpAddr, Instant>>>,
+}
+
+impl<N: Network> Faucet<N> {
+    /// Initializes a new faucet, which funds the requests from the given account.
+    pub fn new(private_key: PrivateKey<N>, config: FaucetConfig) -> Result<Self> {
+        config.check()?;
+        Ok(Self {
+            config: Arc::new(config),
+            private_key,
+            pending: Default::default(),
+            last_requests: Default::default(),
+            last_requests_by_ip: Default::default(),
+        })
+    }
+
+    /// Returns the number of microcredits sent per request.
+    pub fn amount(&self) -> u64 {
+        self.config.amount
+    }
+
+    /// Returns the private key of the account that funds the requests.
+    pub const fn private_key(&self) -> &PrivateKey<N> {
+        &self.private_key
+    }
+
+    /// Returns the number of addresses waiting to be funded.
+    pub fn num_pending(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    /// Queues the given address to be funded, if the token is valid, neither the address nor the IP
+    /// is rate limited, and the queue is not full.
+    pub fn request(&self, address: Address<N>, ip: IpAddr, token: Option<&str>) -> Result<()> {
+        // Ensure the token is valid.
+        if !token.map_or(false, |token| !token.is_empty() && self.config.tokens.iter().any(|t| t == token)) {
+            bail!("Invalid faucet token")
+        }
+        // Lock the queue first, so that concurrent requests observe the same state.
+        let mut pending = self.pending.lock();
+        let mut last_requests = self.last_requests.lock();
+        let mut last_requests_by_ip = self.last_requests_by_ip.lock();
+        // Ensure the address and the IP are not rate limited.
+        let cooldown = self.cooldown();
+        if let Some(elapsed) = last_requests.get(&address).map(|time| time.elapsed()).filter(|e| *e < cooldown) {
+            bail!("Address '{address}' was funded recently, try again in {}s", (cooldown - elapsed).as_secs())
+        }
+        if let Some(elapsed) = last_requests_by_ip.get(&ip).map(|time| time.elapsed()).filter(|e| *e < cooldown) {
+            bail!("IP '{ip}' requested funds recently, try again in {}s", (cooldown - elapsed).as_secs())
+        }
+        // Ensure the queue is not full.
+        if pending.len() >= self.config.max_pending {
+            bail!("The faucet has too many pending requests, try again later")
+        }
+        // Queue the address.
+        let now = Instant::now();
+        last_requests.insert(address, now);
+        last_requests_by_ip.insert(ip, now);
+        pending.insert(address);
+        Ok(())
+    }
+
+    /// Forgets the requests that are past their cooldown.
+    pub fn prune(&self) {
+        let cooldown = self.cooldown();
+        self.last_requests.lock().retain(|_, time| time.elapsed() < cooldown);
+        self.last_requests_by_ip.lock().retain(|_, time| time.elapsed() < cooldown);
+    }
+
+    /// Returns the time an address, or an IP, must wait in between requests.
+    fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.config.cooldown_secs)
+    }
+
+    /// Removes and returns the next batch of addresses to be funded.
+    pub fn next_batch(&self) -> Vec<Address<N>> {
+        let mut pending = self.pending.lock();
+        let batch_size = self.config.batch_size.min(pending.len());
+        pending.drain(..batch_size).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_faucet_config() {
+        let config: FaucetConfig = serde_json::from_str(r#"{ "amount": 100, "tokens": ["token"] }"#).unwrap();
+        assert_eq!(config.amount, 100);
+        assert_eq!(config.tokens, vec!["token".to_string()]);
+        assert_eq!(config.cooldown_secs, DEFAULT_FAUCET_COOLDOWN_IN_SECS);
+        assert_eq!(config.batch_size, DEFAULT_FAUCET_BATCH_SIZE);
+        assert_eq!(config.max_pending, DEFAULT_FAUCET_MAX_PENDING);
+        assert!(config.check().is_ok());
+
+        // Ensure a faucet without a token is refused.
+        assert!(serde_json::from_str::<FaucetConfig>(r#"{ "amount": 100 }"#).is_err());
+        let config: FaucetConfig = serde_json::from_str(r#"{ "amount": 100, "tokens": [] }"#).unwrap();
+        assert!(config.check().is_err());
+        let config: FaucetConfig = serde_json::from_str(r#"{ "amount": 100, "tokens": [""] }"#).unwrap();
+        assert!(config.check().is_err());
+    }
+
+    #[test]
+    fn test_faucet_requests() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let config = FaucetConfig {
+            amount: 100,
+            tokens: vec!["token".to_string()],
+            cooldown_secs: 60,
+            batch_size: 2,
+            max_pending: 3,
+        };
+        let faucet = Faucet::new(private_key, config).unwrap();
+
+        let addresses = (0..4)
+            .map(|_| Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        let ips = (0..5u8).map(|i| IpAddr::from([10, 0, 0, i])).collect::<Vec<_>>();
+
+        // Ensure the token is required.
+        assert!(faucet.request(addresses[0], ips[0], None).is_err());
+        assert!(faucet.request(addresses[0], ips[0], Some("invalid")).is_err());
+        // Ensure the requests are queued.
+        for (address, ip) in addresses[..3].iter().zip(&ips) {
+            faucet.request(*address, *ip, Some("token")).unwrap();
+        }
+        assert_eq!(faucet.num_pending(), 3);
+        // Ensure an address is rate limited.
+        assert!(faucet.request(addresses[0], ips[4], Some("token")).is_err());
+        // Ensure an IP is rate limited.
+        assert!(faucet.request(addresses[3], ips[0], Some("token")).is_err());
+        // Ensure the queue is capped.
+        assert!(faucet.request(addresses[3], ips[3], Some("token")).is_err());
+        assert_eq!(faucet.num_pending(), 3);
+
+        // Ensure the requests are batched in order.
+        assert_eq!(faucet.next_batch(), addresses[..2].to_vec());
+        assert_eq!(faucet.next_batch(), addresses[2..3].to_vec());
+        assert!(faucet.next_batch().is_empty());
+
+        // Ensure the requests within their cooldown are not pruned.
+        faucet.prune();
+        assert!(faucet.request(addresses[0], ips[4], Some("token")).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, net::SocketAddr};
+
+/// The judgment of the condition of the network.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthCondition {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// The condition of the network, as observed by this node from the gossip and the committee.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct NetworkHealth {
+    /// The judgment of the condition of the network.
+    pub condition: HealthCondition,
+    /// The reasons the network is not healthy, if any.
+    pub reasons: Vec<String>,
+    /// The latest block height of the node.
+    pub height: u32,
+    /// The median block height reported by the connected peers, excluding the provers.
+    pub median_peer_height: Option<u32>,
+    /// The connectivity of the committee, if the latest committee is known.
+    pub committee: Option<CommitteeHealth>,
+    /// The block hash conflicts reported by the committee members within the fork window, if the node runs the BFT.
+    pub recent_forks: Option<Vec<ForkSummary>>,
+    /// The average interval (in seconds) between the recent blocks.
+    pub average_block_interval_secs: Option<f64>,
+    /// The number of seconds since the timestamp of the latest block.
+    pub seconds_since_latest_block: i64,
+    /// The estimate of the propagation delay of the recent blocks.
+    pub propagation: PropagationHealth,
+}
+
+/// The connectivity of the committee, including this node if it is a member.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CommitteeHealth {
+    /// The number of committee members.
+    pub members: usize,
+    /// The number of connected committee members.
+    pub connected: usize,
+    /// The fraction of the committee members that are connected.
+    pub connected_fraction: f64,
+    /// The fraction of the stake of the committee that is connected.
+    pub connected_stake_fraction: f64,
+    /// Whether the connected stake reaches the quorum threshold.
+    pub has_quorum: bool,
+}
+
+/// A block hash conflict reported by a committee member.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ForkSummary {
+    /// The UTC epoch timestamp (in seconds) at which the committee member first reported the conflict.
+    pub timestamp: i64,
+    /// The IP of the committee member.
+    pub peer_ip: SocketAddr,
+    /// The address of the committee member.
+    pub address: String,
+    /// The lowest height at which the block hashes conflict.
+    pub height: u32,
+    /// The block hash in the ledger.
+    pub expected_hash: String,
+    /// The block hash reported by the committee member.
+    pub reported_hash: String,
+}
+
+/// The estimate of the propagation delay of the recent blocks, i.e. the time from the timestamp of a block
+/// until it is added to the ledger of this node. The timestamps of the blocks are in seconds, and so are the delays.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PropagationHealth {
+    /// The number of recent blocks that were sampled.
+    pub samples: usize,
+    /// The median propagation delay (in seconds) of the sampled blocks.
+    pub median_block_delay_secs: Option<i64>,
+    /// The maximum propagation delay (in seconds) of the sampled blocks.
+    pub max_block_delay_secs: Option<i64>,
+}
+
+impl NetworkHealth {
+    /// The window (in seconds) over which the block hash conflicts are reported as recent forks.
+    pub const FORK_WINDOW_IN_SECS: i64 = 3_600;
+    /// The number of recent blocks over which the average block interval is computed.
+    pub const BLOCK_INTERVAL_WINDOW: u32 = 20;
+    /// The fraction of the committee members below which the network is degraded.
+    pub const MINIMUM_COMMITTEE_FRACTION: f64 = 0.9;
+    /// The multiple of the block time beyond which the block interval or the propagation delay is degraded.
+    pub const DEGRADED_BLOCK_TIME_MULTIPLE: i64 = 3;
+    /// The multiple of the block time without a new block, beyond which the network is considered stalled.
+    pub const STALLED_BLOCK_TIME_MULTIPLE: i64 = 10;
+
+    /// Judges the condition of the network from the observations, given the target block time (in seconds),
+    /// and the number of blocks the node may lag behind its peers. Each observation that is out of bounds
+    /// is recorded as a reason, and the condition is the most severe over the reasons.
+    pub fn assess(&mut self, block_time: i64, maximum_height_lag: u32) {
+        let mut unhealthy = Vec::new();
+        let mut degraded = Vec::new();
+
+        if let Some(committee) = &self.committee {
+            if !committee.has_quorum {
+                unhealthy.push("The connected stake of the committee is below the quorum threshold".to_string());
+            } else if committee.connected_fraction < Self::MINIMUM_COMMITTEE_FRACTION {
+                let (connected, members) = (committee.connected, committee.members);
+                degraded.push(format!("Only {connected} of {members} committee members are connected"));
+            }
+        }
+        // A node that is behind its peers cannot judge whether the network is stalled.
+        let behind = self.median_peer_height.filter(|median| self.height.saturating_add(maximum_height_lag) < *median);
+        if let Some(median) = behind {
+            degraded.push(format!("The node is behind the median peer height of {median}"));
+        } else if self.seconds_since_latest_block > Self::STALLED_BLOCK_TIME_MULTIPLE * block_time {
+            unhealthy.push(format!("No block was produced in the last {} seconds", self.seconds_since_latest_block));
+        }
+        if let Some(interval) = self.average_block_interval_secs {
+            if interval > (Self::DEGRADED_BLOCK_TIME_MULTIPLE * block_time) as f64 {
+                degraded.push(format!("The average block interval is {interval:.1} seconds"));
+            }
+        }
+        if let Some(forks) = self.recent_forks.as_ref().filter(|forks| !forks.is_empty()) {
+            degraded.push(format!("{} block hash conflicts were reported recently", forks.len()));
+        }
+        if let Some(delay) = self.propagation.median_block_delay_secs {
+            if delay > Self::DEGRADED_BLOCK_TIME_MULTIPLE * block_time {
+                degraded.push(format!("The median propagation delay of the blocks is {delay} seconds"));
+            }
+        }
+
+        self.condition = match (unhealthy.is_empty(), degraded.is_empty()) {
+            (false, _) => HealthCondition::Unhealthy,
+            (true, false) => HealthCondition::Degraded,
+            (true, true) => HealthCondition::Healthy,
+        };
+        self.reasons = unhealthy.into_iter().chain(degraded).collect();
+    }
+}
+
+/// The tracker of the propagation delay of the most recent blocks.
+#[derive(Debug, Default)]
+pub struct PropagationTracker {
+    /// The propagation delays (in seconds) of the most recent blocks, from the oldest to the newest.
+    delays: Mutex<VecDeque<i64>>,
+}
+
+impl PropagationTracker {
+    /// The maximum number of recent blocks that are sampled.
+    pub const CAPACITY: usize = 20;
+    /// The number of blocks the node may lag behind its peers, beyond which the blocks it adds are not sampled,
+    /// as they are caught up with rather than propagated.
+    pub const MAXIMUM_BLOCKS_BEHIND: u32 = 2;
+
+    /// Records the propagation delay (in seconds) of a block.
+    pub fn record(&self, delay_secs: i64) {
+        let mut delays = self.delays.lock();
+        if delays.len() == Self::CAPACITY {
+            delays.pop_front();
+        }
+        delays.push_back(delay_secs.max(0));
+    }
+
+    /// Returns the estimate of the propagation delay of the sampled blocks.
+    pub fn estimate(&self) -> PropagationHealth {
+        let delays = self.delays.lock().iter().copied().collect::<Vec<_>>();
+        PropagationHealth {
+            samples: delays.len(),
+            max_block_delay_secs: delays.iter().max().copied(),
+            median_block_delay_secs: median(delays),
+        }
+    }
+}
+
+/// Returns the median of the given values, taking the lower of the two middle values if their number is even.
+pub(crate) fn median<T: Copy + Ord>(mut values: Vec<T>) -> Option<T> {
+    values.sort_unstable();
+    match values.len() {
+        0 => None,
+        len => Some(values[(len - 1) / 2]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_health() -> NetworkHealth {
+        NetworkHealth {
+            condition: HealthCondition::Healthy,
+            reasons: vec![],
+            height: 1_000,
+            median_peer_height: Some(1_001),
+            committee: Some(CommitteeHealth {
+                members: 10,
+                connected: 10,
+                connected_fraction: 1.0,
+                connected_stake_fraction: 1.0,
+                has_quorum: true,
+            }),
+            recent_forks: Some(vec![]),
+            average_block_interval_secs: Some(10.0),
+            seconds_since_latest_block: 5,
+            propagation: PropagationHealth {
+                samples: 3,
+                median_block_delay_secs: Some(2),
+                max_block_delay_secs: Some(4),
+            },
+        }
+    }
+
+    #[test]
+    fn test_assess_network_health() {
+        let mut health = sample_health();
+        health.assess(10, 100);
+        assert_eq!(health.condition, HealthCondition::Healthy);
+        assert!(health.reasons.is_empty());
+
+        // A partially connected committee, or a slow block interval, degrades the network.
+        let mut health = sample_health();
+        health.committee.as_mut().unwrap().connected_fraction = 0.8;
+        health.average_block_interval_secs = Some(45.0);
+        health.assess(10, 100);
+        assert_eq!(health.condition, HealthCondition::Degraded);
+        assert_eq!(health.reasons.len(), 2);
+
+        // A lost quorum outweighs the degraded observations.
+        let mut health = sample_health();
+        health.committee.as_mut().unwrap().has_quorum = false;
+        health.propagation.median_block_delay_secs = Some(40);
+        health.assess(10, 100);
+        assert_eq!(health.condition, HealthCondition::Unhealthy);
+        assert_eq!(health.reasons.len(), 2);
+
+        // A stalled chain is unhealthy, unless the node is behind its peers.
+        let mut health = sample_health();
+        health.seconds_since_latest_block = 500;
+        health.assess(10, 100);
+        assert_eq!(health.condition, HealthCondition::Unhealthy);
+        health.median_peer_height = Some(2_000);
+        health.assess(10, 100);
+        assert_eq!(health.condition, HealthCondition::Degraded);
+    }
+
+    #[test]
+    fn test_propagation_tracker() {
+        let tracker = PropagationTracker::default();
+        assert_eq!(tracker.estimate(), PropagationHealth::default());
+
+        for delay in [3, 1, -2, 8] {
+            tracker.record(delay);
+        }
+        assert_eq!(tracker.estimate(), PropagationHealth {
+            samples: 4,
+            median_block_delay_secs: Some(1),
+            max_block_delay_secs: Some(8)
+        });
+
+        // Only the most recent blocks are sampled.
+        for _ in 0..PropagationTracker::CAPACITY {
+            tracker.record(5);
+        }
+        assert_eq!(tracker.estimate().samples, PropagationTracker::CAPACITY);
+        assert_eq!(tracker.estimate().max_block_delay_secs, Some(5));
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median::<u32>(vec![]), None);
+        assert_eq!(median(vec![7]), Some(7));
+        assert_eq!(median(vec![9, 1, 5]), Some(5));
+        assert_eq!(median(vec![9, 1, 5, 3]), Some(3));
+    }
+}
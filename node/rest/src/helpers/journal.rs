@@ -0,0 +1,311 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread::JoinHandle,
+};
+use time::OffsetDateTime;
+
+/// The maximum number of recent entries that are kept in memory.
+const MAXIMUM_IN_MEMORY_ENTRIES: usize = 1024;
+/// The number of bytes that are read at once, when the journal file is read from its end.
+const READ_CHUNK_SIZE: u64 = 64 * 1024;
+/// The default number of entries returned by a query.
+pub const DEFAULT_JOURNAL_QUERY_LIMIT: usize = 100;
+
+/// An admin action taken against the node, as recorded in the action journal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionEntry {
+    /// The sequence number of the entry in the journal.
+    pub sequence: u64,
+    /// The UNIX timestamp of the action.
+    pub timestamp: i64,
+    /// The authenticated principal that took the action, i.e. the subject of its JSON Web Token.
+    pub principal: String,
+    /// The name of the action, e.g. `bft/pause`.
+    pub action: String,
+    /// The parameters of the action.
+    pub parameters: serde_json::Value,
+    /// The error of the action, if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The filters of a query to the action journal.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct JournalQuery {
+    /// Only returns the entries of the given action.
+    pub action: Option<String>,
+    /// Only returns the entries of the given principal.
+    pub principal: Option<String>,
+    /// Only returns the entries at or after the given UNIX timestamp.
+    pub since: Option<i64>,
+    /// The maximum number of entries to return.
+    pub limit: Option<usize>,
+}
+
+impl JournalQuery {
+    /// Returns `true` if the given entry matches the query.
+    fn matches(&self, entry: &ActionEntry) -> bool {
+        self.action.as_ref().map_or(true, |action| *action == entry.action)
+            && self.principal.as_ref().map_or(true, |principal| *principal == entry.principal)
+            && self.since.map_or(true, |since| entry.timestamp >= since)
+    }
+}
+
+/// The writer of the journal file, which appends the entries on its own thread.
+struct JournalWriter {
+    /// The path to the journal file.
+    path: PathBuf,
+    /// The sender of the serialized entries to the writer thread.
+    sender: mpsc::Sender<Vec<u8>>,
+    /// The handle of the writer thread.
+    handle: JoinHandle<()>,
+}
+
+/// The state of the action journal.
+#[derive(Default)]
+struct JournalInner {
+    /// The most recent entries, from oldest to newest.
+    recent: VecDeque<ActionEntry>,
+    /// The sequence number of the next entry.
+    sequence: u64,
+    /// The writer of the journal file, once the journal is opened at a path.
+    writer: Option<JournalWriter>,
+}
+
+/// An append-only journal of the admin actions taken against the node, for operator accountability.
+///
+/// Every action is recorded with its authenticated principal, timestamp, parameters, and outcome. Once the journal
+/// is opened at a path, the entries are appended to it as JSON lines, which are never rotated nor rewritten.
+/// The file is written on a dedicated thread, so recording an action never blocks on IO, and the most recent
+/// entries are kept in memory, so a query only reads the file, from its end, for older entries.
+#[derive(Default)]
+pub struct ActionJournal {
+    /// The state of the journal.
+    inner: Mutex<JournalInner>,
+}
+
+impl ActionJournal {
+    /// Opens the journal at the given path, appending to it if it exists.
+    /// The entries that were recorded in memory so far are appended to the file.
+    pub fn open(&self, path: &Path) -> Result<()> {
+        // Resume the sequence numbers of the existing journal.
+        let mut sequence = 0;
+        if path.exists() {
+            Self::read_entries_rev(path, u64::MAX, |entry| {
+                sequence = entry.sequence + 1;
+                false
+            })?;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open the action journal at '{}'", path.display()))?;
+
+        let mut inner = self.inner.lock();
+        // Append the entries recorded in memory so far.
+        for entry in inner.recent.iter_mut() {
+            entry.sequence = sequence;
+            file.write_all(&Self::to_line(entry)?)?;
+            sequence += 1;
+        }
+        inner.sequence = sequence;
+
+        // Spawn the writer thread, which stops once the journal is dropped.
+        let (sender, receiver) = mpsc::channel::<Vec<u8>>();
+        let writer_path = path.to_path_buf();
+        let handle = std::thread::Builder::new().name("action-journal".to_string()).spawn(move || {
+            for line in receiver {
+                if let Err(error) = file.write_all(&line) {
+                    error!("Failed to write to the action journal at '{}' - {error}", writer_path.display());
+                }
+            }
+        })?;
+        inner.writer = Some(JournalWriter { path: path.to_path_buf(), sender, handle });
+        Ok(())
+    }
+
+    /// Records the given action of the given principal, along with its parameters and outcome.
+    pub fn record(&self, principal: &str, action: &str, parameters: serde_json::Value, result: Result<(), String>) {
+        let mut inner = self.inner.lock();
+        let entry = ActionEntry {
+            sequence: inner.sequence,
+            timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+            principal: principal.to_string(),
+            action: action.to_string(),
+            parameters,
+            error: result.err(),
+        };
+        info!("Admin action '{action}' was taken by '{principal}'");
+        // Send the entry to the writer thread.
+        if let Some(writer) = &inner.writer {
+            match Self::to_line(&entry) {
+                Ok(line) => {
+                    if writer.sender.send(line).is_err() {
+                        error!("The writer of the action journal at '{}' has stopped", writer.path.display());
+                    }
+                }
+                Err(error) => error!("Failed to serialize an entry of the action journal - {error}"),
+            }
+        }
+        if inner.recent.len() == MAXIMUM_IN_MEMORY_ENTRIES {
+            inner.recent.pop_front();
+        }
+        inner.recent.push_back(entry);
+        inner.sequence += 1;
+    }
+
+    /// Returns the entries that match the given query, from newest to oldest.
+    ///
+    /// The recent entries are served from memory, and the journal file is only read, from its end,
+    /// if they do not satisfy the query. This method may read the file, so it should be called from
+    /// a blocking thread.
+    pub fn query(&self, query: &JournalQuery) -> Result<Vec<ActionEntry>> {
+        let limit = query.limit.unwrap_or(DEFAULT_JOURNAL_QUERY_LIMIT);
+        let (mut entries, oldest, path) = {
+            let inner = self.inner.lock();
+            let entries =
+                inner.recent.iter().rev().filter(|entry| query.matches(entry)).take(limit).cloned().collect::<Vec<_>>();
+            let oldest = inner.recent.front().map_or(inner.sequence, |entry| entry.sequence);
+            (entries, oldest, inner.writer.as_ref().map(|writer| writer.path.clone()))
+        };
+        // Read the entries that precede the recent ones from the journal file, if they are needed.
+        if let Some(path) = path.filter(|_| entries.len() < limit) {
+            Self::read_entries_rev(&path, oldest, |entry| {
+                // The entries are recorded in chronological order, so the older entries do not match either.
+                if query.since.map_or(false, |since| entry.timestamp < since) {
+                    return false;
+                }
+                if query.matches(&entry) {
+                    entries.push(entry);
+                }
+                entries.len() < limit
+            })?;
+        }
+        Ok(entries)
+    }
+
+    /// Serializes the given entry as a JSON line.
+    fn to_line(entry: &ActionEntry) -> Result<Vec<u8>> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        Ok(line)
+    }
+
+    /// Reads the entries of the journal at the given path that precede the given sequence number,
+    /// from newest to oldest, and passes them to the given closure, until it returns `false`.
+    fn read_entries_rev(path: &Path, before: u64, mut f: impl FnMut(ActionEntry) -> bool) -> Result<()> {
+        let mut file = File::open(path)?;
+        let mut position = file.metadata()?.len();
+        // The start of the earliest line read so far, which continues in the preceding chunk.
+        let mut partial = Vec::new();
+        while position > 0 {
+            // Read the preceding chunk.
+            let size = READ_CHUNK_SIZE.min(position);
+            position -= size;
+            file.seek(SeekFrom::Start(position))?;
+            let mut chunk = vec![0u8; size as usize];
+            file.read_exact(&mut chunk)?;
+            chunk.extend_from_slice(&partial);
+            // Determine the start of the first complete line in the chunk.
+            let start = match position {
+                0 => 0,
+                _ => match chunk.iter().position(|byte| *byte == b'\n') {
+                    Some(index) => index + 1,
+                    None => {
+                        partial = chunk;
+                        continue;
+                    }
+                },
+            };
+            for line in chunk[start..].split(|byte| *byte == b'\n').rev() {
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+                let entry: ActionEntry = serde_json::from_slice(line).with_context(|| {
+                    format!("Malformed entry in the action journal at '{}'", path.display())
+                })?;
+                if entry.sequence >= before {
+                    continue;
+                }
+                if !f(entry) {
+                    return Ok(());
+                }
+            }
+            partial = chunk[..start].to_vec();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ActionJournal {
+    /// Waits for the writer thread to append the pending entries to the journal file.
+    fn drop(&mut self) {
+        if let Some(JournalWriter { sender, handle, .. }) = self.inner.get_mut().writer.take() {
+            drop(sender);
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_action_journal() {
+        let path = std::env::temp_dir().join(format!("snarkos-action-journal-{}", rand::random::<u64>()));
+        let journal = ActionJournal::default();
+
+        // Record an action in memory, and then open the journal.
+        journal.record("alice", "bft/pause", json!({}), Ok(()));
+        journal.open(&path).unwrap();
+        journal.record("bob", "bft/resume", json!({}), Err("not paused".to_string()));
+        journal.record("alice", "rest/rebind", json!({ "ip": "0.0.0.0:3030" }), Ok(()));
+
+        // Ensure the entries are returned from newest to oldest, and filtered.
+        let entries = journal.query(&JournalQuery::default()).unwrap();
+        assert_eq!(entries.iter().map(|entry| entry.sequence).collect::<Vec<_>>(), vec![2, 1, 0]);
+        assert_eq!(entries[1].error.as_deref(), Some("not paused"));
+        let query = JournalQuery { principal: Some("alice".to_string()), limit: Some(1), ..Default::default() };
+        let entries = journal.query(&query).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "rest/rebind");
+
+        // Ensure a reopened journal resumes the sequence numbers, and reads the older entries from the file.
+        drop(journal);
+        let reopened = ActionJournal::default();
+        reopened.open(&path).unwrap();
+        reopened.record("carol", "mempool/import", json!({ "transactions": 3 }), Ok(()));
+        let entries = reopened.query(&JournalQuery::default()).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].sequence, 3);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{io, net::SocketAddr, time::Duration};
+use tokio::{
+    net::{TcpListener, TcpSocket},
+    sync::oneshot,
+    task::AbortHandle,
+};
+
+/// The duration for which the requests in flight on a replaced listener are drained, before they are aborted.
+pub const LISTENER_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+/// The maximum number of pending connections on a listener.
+const LISTENER_BACKLOG: u32 = 1024;
+
+/// A listener that the REST server serves on, which can be replaced at runtime.
+pub(crate) struct RestListener {
+    /// The address of the listener.
+    pub(crate) address: SocketAddr,
+    /// The rate limit per IP, in requests per second.
+    pub(crate) rps: u32,
+    /// The bound socket, which is owned by the listener, so that a server that replaces this one
+    /// on the same address can serve on the same socket, rather than binding the address again.
+    socket: std::net::TcpListener,
+    /// The sender that stops the listener from accepting connections.
+    shutdown: oneshot::Sender<()>,
+    /// The handle of the task serving the listener.
+    abort: AbortHandle,
+}
+
+impl RestListener {
+    /// Initializes a new listener record, for the task with the given abort handle.
+    pub(crate) fn new(
+        address: SocketAddr,
+        rps: u32,
+        socket: std::net::TcpListener,
+        shutdown: oneshot::Sender<()>,
+        abort: AbortHandle,
+    ) -> Self {
+        Self { address, rps, socket, shutdown, abort }
+    }
+
+    /// Returns a handle to the bound socket of the listener, to serve on it from another server.
+    pub(crate) fn socket(&self) -> io::Result<std::net::TcpListener> {
+        self.socket.try_clone()
+    }
+
+    /// Stops accepting connections, and aborts the requests in flight that are not done after the drain timeout.
+    /// Note: The socket stays open while a server that replaced this one serves on it.
+    pub(crate) fn drain(self) {
+        let _ = self.shutdown.send(());
+        let abort = self.abort;
+        tokio::spawn(async move {
+            tokio::time::sleep(LISTENER_DRAIN_TIMEOUT).await;
+            abort.abort();
+        });
+    }
+}
+
+/// Binds a TCP socket to the given address, failing if the address is in use, including by another process.
+pub(crate) fn bind_listener(address: SocketAddr) -> io::Result<std::net::TcpListener> {
+    let socket = match address {
+        SocketAddr::V4(_) => TcpSocket::new_v4()?,
+        SocketAddr::V6(_) => TcpSocket::new_v6()?,
+    };
+    // Allow the address to be bound again while its previous connections linger, as the standard library does.
+    #[cfg(unix)]
+    socket.set_reuseaddr(true)?;
+    socket.bind(address)?;
+    socket.listen(LISTENER_BACKLOG)?.into_std()
+}
+
+/// Returns an asynchronous listener on the given bound socket.
+pub(crate) fn serve_listener(socket: &std::net::TcpListener) -> io::Result<TcpListener> {
+    TcpListener::from_std(socket.try_clone()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bind_listener() {
+        // Bind a socket to a free port.
+        let socket = bind_listener("127.0.0.1:0".parse().unwrap()).unwrap();
+        let address = socket.local_addr().unwrap();
+
+        // Ensure the address may not be bound again, as the socket is shared rather than the address.
+        assert!(bind_listener(address).is_err());
+
+        // Ensure a listener on a handle to the socket accepts connections, after the original listener is dropped.
+        let listener = serve_listener(&socket).unwrap();
+        drop(socket);
+        let (_stream, accepted) = tokio::join!(tokio::net::TcpStream::connect(address), listener.accept());
+        assert!(accepted.is_ok());
+    }
+}
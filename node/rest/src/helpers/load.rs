@@ -0,0 +1,206 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header::RETRY_AFTER, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// The interval in between samples of the load of the node.
+pub const LOAD_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+/// The event loop lag above which the node is overloaded.
+const MAXIMUM_EVENT_LOOP_LAG: Duration = Duration::from_millis(250);
+/// The number of in-flight requests above which the node is overloaded.
+const MAXIMUM_REQUESTS_IN_FLIGHT: usize = 512;
+/// The percentage of available memory below which the node is overloaded.
+const MINIMUM_AVAILABLE_MEMORY_PERCENT: f64 = 5.0;
+/// The number of consecutive healthy samples after which the node stops shedding load.
+const HEALTHY_SAMPLES_TO_RECOVER: u32 = 20; // 10 seconds
+/// The number of seconds after which a client should retry a rejected request.
+const RETRY_AFTER_IN_SECS: u64 = 5;
+
+/// The routes that are cheap to serve, and keep being served while the node is shedding load.
+const CHEAP_ROUTES: [&str; 10] = [
+    "block/height/latest",
+    "block/hash/latest",
+    "block/latest",
+    "stateRoot/latest",
+    "committee/latest",
+    "latest/height",
+    "latest/hash",
+    "latest/block",
+    "latest/stateRoot",
+    "latest/committee",
+];
+
+/// A sample of the load of the node.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadSample {
+    /// The delay of a timer on the event loop, beyond its deadline.
+    pub event_loop_lag: Duration,
+    /// The number of REST requests being served.
+    pub requests_in_flight: usize,
+    /// The percentage of available system memory, if it is known.
+    pub memory_available_percent: Option<f64>,
+}
+
+impl LoadSample {
+    /// Returns `true` if the sample exceeds any of the load thresholds.
+    pub fn is_overloaded(&self) -> bool {
+        self.event_loop_lag > MAXIMUM_EVENT_LOOP_LAG
+            || self.requests_in_flight > MAXIMUM_REQUESTS_IN_FLIGHT
+            || self.memory_available_percent.map_or(false, |percent| percent < MINIMUM_AVAILABLE_MEMORY_PERCENT)
+    }
+}
+
+/// Tracks the load of the node, and decides when the REST server sheds load.
+#[derive(Debug, Default)]
+pub struct LoadMonitor {
+    /// The number of REST requests being served.
+    requests_in_flight: AtomicUsize,
+    /// The boolean flag for the load-shedding mode.
+    is_shedding: AtomicBool,
+    /// The number of consecutive healthy samples while shedding load.
+    healthy_samples: AtomicU32,
+}
+
+impl LoadMonitor {
+    /// Returns `true` if the node is shedding load.
+    pub fn is_shedding(&self) -> bool {
+        self.is_shedding.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of REST requests being served.
+    pub fn requests_in_flight(&self) -> usize {
+        self.requests_in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Returns a sample of the current load, given the measured event loop lag.
+    pub fn sample(&self, event_loop_lag: Duration) -> LoadSample {
+        LoadSample {
+            event_loop_lag,
+            requests_in_flight: self.requests_in_flight(),
+            memory_available_percent: memory_available_percent(),
+        }
+    }
+
+    /// Updates the load-shedding mode with the given sample, and returns the new mode if it changed.
+    ///
+    /// The node starts shedding load on the first overloaded sample, and stops only after
+    /// `HEALTHY_SAMPLES_TO_RECOVER` consecutive healthy samples, to avoid flapping in between modes.
+    pub fn update(&self, sample: &LoadSample) -> Option<bool> {
+        if sample.is_overloaded() {
+            self.healthy_samples.store(0, Ordering::Relaxed);
+            return (!self.is_shedding.swap(true, Ordering::Relaxed)).then_some(true);
+        }
+        if !self.is_shedding() {
+            return None;
+        }
+        if self.healthy_samples.fetch_add(1, Ordering::Relaxed) + 1 >= HEALTHY_SAMPLES_TO_RECOVER {
+            self.healthy_samples.store(0, Ordering::Relaxed);
+            self.is_shedding.store(false, Ordering::Relaxed);
+            return Some(false);
+        }
+        None
+    }
+}
+
+/// Decrements the number of in-flight requests when a request completes or is dropped.
+struct InFlightGuard<'a>(&'a AtomicUsize);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns `true` if the given path is a route that keeps being served while the node is shedding load.
+pub fn is_cheap_route(path: &str) -> bool {
+    path.split_once("/testnet3/").map_or(false, |(_, route)| CHEAP_ROUTES.contains(&route))
+}
+
+/// Rejects the expensive requests with `503 Service Unavailable` while the node is shedding load,
+/// and counts the requests that are being served.
+pub async fn load_shedding_middleware(
+    State(monitor): State<Arc<LoadMonitor>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if monitor.is_shedding() && !is_cheap_route(request.uri().path()) {
+        let message = "The node is overloaded, please retry later";
+        let mut response = (StatusCode::SERVICE_UNAVAILABLE, message).into_response();
+        response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(RETRY_AFTER_IN_SECS));
+        return response;
+    }
+    monitor.requests_in_flight.fetch_add(1, Ordering::Relaxed);
+    let _guard = InFlightGuard(&monitor.requests_in_flight);
+    next.run(request).await
+}
+
+/// Returns the percentage of available system memory, if it can be determined.
+fn memory_available_percent() -> Option<f64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| {
+        meminfo.lines().find_map(|line| line.strip_prefix(name)?.trim().strip_suffix("kB")?.trim().parse::<f64>().ok())
+    };
+    let (total, available) = (field("MemTotal:")?, field("MemAvailable:")?);
+    (total > 0.0).then(|| available / total * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cheap_routes() {
+        assert!(is_cheap_route("/testnet3/block/height/latest"));
+        assert!(is_cheap_route("/v1/testnet3/stateRoot/latest"));
+        assert!(!is_cheap_route("/testnet3/blocks"));
+        assert!(!is_cheap_route("/v1/testnet3/transaction/broadcast"));
+    }
+
+    #[test]
+    fn test_load_monitor() {
+        let monitor = LoadMonitor::default();
+        let healthy = LoadSample::default();
+        let overloaded = LoadSample { event_loop_lag: Duration::from_secs(1), ..Default::default() };
+
+        // Ensure the node starts shedding load on the first overloaded sample.
+        assert_eq!(monitor.update(&healthy), None);
+        assert_eq!(monitor.update(&overloaded), Some(true));
+        assert_eq!(monitor.update(&overloaded), None);
+        assert!(monitor.is_shedding());
+
+        // Ensure the node only stops shedding load after enough consecutive healthy samples.
+        for _ in 0..HEALTHY_SAMPLES_TO_RECOVER - 1 {
+            assert_eq!(monitor.update(&healthy), None);
+        }
+        assert_eq!(monitor.update(&overloaded), None);
+        for _ in 0..HEALTHY_SAMPLES_TO_RECOVER - 1 {
+            assert_eq!(monitor.update(&healthy), None);
+        }
+        assert_eq!(monitor.update(&healthy), Some(false));
+        assert!(!monitor.is_shedding());
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::block::Transaction,
+    prelude::{bail, error, FromBytes, Network, ToBytes},
+};
+
+use std::io::{self, Read, Write};
+
+/// The maximum size of a mempool snapshot that can be imported, in bytes.
+pub const MAX_MEMPOOL_SNAPSHOT_SIZE: usize = 512 * 1024 * 1024; // 512 MiB
+
+/// A snapshot of the unconfirmed transactions of a node, which is exported before planned maintenance,
+/// and imported after a restart, or on another node, so that the transactions are not silently dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MempoolSnapshot<N: Network> {
+    /// The unconfirmed transactions.
+    transactions: Vec<Transaction<N>>,
+}
+
+impl<N: Network> MempoolSnapshot<N> {
+    /// The version of the snapshot format.
+    pub const VERSION: u8 = 1;
+
+    /// Initializes a new snapshot of the given unconfirmed transactions.
+    pub fn new(transactions: Vec<Transaction<N>>) -> Self {
+        Self { transactions }
+    }
+
+    /// Returns the unconfirmed transactions.
+    pub fn transactions(&self) -> &[Transaction<N>] {
+        &self.transactions
+    }
+
+    /// Returns the unconfirmed transactions, consuming the snapshot.
+    pub fn into_transactions(self) -> Vec<Transaction<N>> {
+        self.transactions
+    }
+}
+
+impl<N: Network> ToBytes for MempoolSnapshot<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        Self::VERSION.write_le(&mut writer)?;
+        N::ID.write_le(&mut writer)?;
+        u32::try_from(self.transactions.len()).map_err(error)?.write_le(&mut writer)?;
+        for transaction in &self.transactions {
+            transaction.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for MempoolSnapshot<N> {
+    fn read_le<R: Read>(mut reader: R) -> io::Result<Self> {
+        let version = u8::read_le(&mut reader)?;
+        if version != Self::VERSION {
+            return Err(error(format!("Unsupported mempool snapshot version {version}")));
+        }
+        let network = u16::read_le(&mut reader)?;
+        if network != N::ID {
+            return Err(error(format!("The mempool snapshot is for network {network}, not network {}", N::ID)));
+        }
+        let num_transactions = u32::read_le(&mut reader)?;
+        let transactions =
+            (0..num_transactions).map(|_| Transaction::read_le(&mut reader)).collect::<io::Result<Vec<_>>>()?;
+        Ok(Self { transactions })
+    }
+}
+
+/// Parses a snapshot from the given bytes, ensuring that no bytes remain.
+pub fn parse_mempool_snapshot<N: Network>(bytes: &[u8]) -> anyhow::Result<MempoolSnapshot<N>> {
+    let mut reader = bytes;
+    let snapshot = MempoolSnapshot::read_le(&mut reader)?;
+    if !reader.is_empty() {
+        bail!("The mempool snapshot has {} trailing bytes", reader.len());
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::ledger_test_helpers::sample_fee_public_transaction,
+        prelude::{TestRng, Testnet3},
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_mempool_snapshot() {
+        let rng = &mut TestRng::default();
+
+        let transactions = (0..3).map(|_| sample_fee_public_transaction(rng)).collect::<Vec<_>>();
+        let snapshot = MempoolSnapshot::<CurrentNetwork>::new(transactions.clone());
+        let bytes = snapshot.to_bytes_le().unwrap();
+        assert_eq!(parse_mempool_snapshot::<CurrentNetwork>(&bytes).unwrap().into_transactions(), transactions);
+
+        // Ensure truncated snapshots, trailing bytes, and unknown versions are refused.
+        assert!(parse_mempool_snapshot::<CurrentNetwork>(&bytes[..bytes.len() - 1]).is_err());
+        assert!(parse_mempool_snapshot::<CurrentNetwork>(&[&bytes[..], &[0u8]].concat()).is_err());
+        let mut unknown = bytes.clone();
+        unknown[0] = MempoolSnapshot::<CurrentNetwork>::VERSION + 1;
+        assert!(parse_mempool_snapshot::<CurrentNetwork>(&unknown).is_err());
+
+        // Ensure an empty snapshot round-trips.
+        let empty = MempoolSnapshot::<CurrentNetwork>::new(vec![]).to_bytes_le().unwrap();
+        assert!(parse_mempool_snapshot::<CurrentNetwork>(&empty).unwrap().transactions().is_empty());
+    }
+}
@@ -15,5 +15,60 @@
 mod auth;
 pub use auth::*;
 
+mod cdn_sync;
+pub use cdn_sync::*;
+
+mod correlation;
+pub use correlation::*;
+
+mod debug_bundle;
+pub use debug_bundle::*;
+
+mod deployments;
+pub use deployments::*;
+
 mod error;
 pub use error::*;
+
+#[cfg(feature = "execute")]
+mod execute;
+#[cfg(feature = "execute")]
+pub use execute::*;
+
+#[cfg(feature = "faucet")]
+mod faucet;
+#[cfg(feature = "faucet")]
+pub use faucet::*;
+
+mod health;
+pub use health::*;
+
+mod journal;
+pub use journal::*;
+
+mod listener;
+pub use listener::*;
+
+mod load;
+pub use load::*;
+
+mod mempool;
+pub use mempool::*;
+
+mod rejections;
+pub use rejections::*;
+
+mod rewards;
+pub use rewards::*;
+
+mod status;
+pub use status::*;
+
+mod stream;
+pub(crate) use stream::*;
+
+mod subscription;
+pub use subscription::*;
+
+mod version;
+pub use version::*;
@@ -0,0 +1,164 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{ledger::block::Block, prelude::Network};
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// The reason a transaction that was ordered into a block was not accepted by the ledger.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionReason {
+    /// The ledger aborted the transaction while producing the block (e.g. it conflicted with an earlier
+    /// transaction in the block, or was no longer valid), so it was dropped from the block.
+    Aborted,
+    /// The transaction failed to finalize, so it was included in the block as rejected, and only its fee was consumed.
+    FailedToFinalize,
+}
+
+/// The transactions that were ordered into a block, but not accepted by the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct BlockRejections<N: Network> {
+    /// The height of the block.
+    pub block_height: u32,
+    /// The hash of the block.
+    pub block_hash: N::BlockHash,
+    /// The (unconfirmed) IDs of the rejected transactions, and their reasons, in the order they appear in the block.
+    pub transactions: IndexMap<N::TransactionID, RejectionReason>,
+}
+
+impl<N: Network> BlockRejections<N> {
+    /// Initializes the rejections of the given block, from the transactions it includes as rejected,
+    /// and the IDs of the transactions it aborted.
+    pub fn new(block: &Block<N>) -> Self {
+        let mut transactions = IndexMap::new();
+        for transaction in block.transactions().iter().filter(|transaction| transaction.is_rejected()) {
+            match transaction.to_unconfirmed_transaction_id() {
+                Ok(transaction_id) => transactions.insert(transaction_id, RejectionReason::FailedToFinalize),
+                Err(e) => {
+                    warn!("Failed to retrieve the unconfirmed ID of a transaction in block {} - {e}", block.height());
+                    continue;
+                }
+            };
+        }
+        for transaction_id in block.aborted_transaction_ids() {
+            transactions.insert(*transaction_id, RejectionReason::Aborted);
+        }
+        Self { block_height: block.height(), block_hash: block.hash(), transactions }
+    }
+}
+
+/// The tracker of the transactions that the most recent blocks rejected.
+///
+/// Only the blocks with rejections are tracked, up to the given capacity, so that the rejected transactions
+/// can be looked up by their ID. The rejections of older blocks are derived from the blocks in the ledger.
+pub struct RejectionTracker<N: Network> {
+    /// The map of block heights to their rejections, for the most recent blocks with rejections.
+    blocks: RwLock<BTreeMap<u32, BlockRejections<N>>>,
+    /// The map of rejected transaction IDs to the height of the latest tracked block that rejected them.
+    transactions: RwLock<HashMap<N::TransactionID, u32>>,
+}
+
+impl<N: Network> Default for RejectionTracker<N> {
+    fn default() -> Self {
+        Self { blocks: Default::default(), transactions: Default::default() }
+    }
+}
+
+impl<N: Network> RejectionTracker<N> {
+    /// The maximum number of blocks with rejections that are tracked.
+    pub const CAPACITY: usize = 1024;
+
+    /// Returns the height of the latest tracked block that rejected the given transaction, and the reason,
+    /// if there is one.
+    pub fn find(&self, transaction_id: &N::TransactionID) -> Option<(u32, RejectionReason)> {
+        let block_height = *self.transactions.read().get(transaction_id)?;
+        let reason = *self.blocks.read().get(&block_height)?.transactions.get(transaction_id)?;
+        Some((block_height, reason))
+    }
+
+    /// Tracks the given rejections, if there are any, evicting the oldest block if the tracker is full.
+    pub fn insert(&self, rejections: BlockRejections<N>) {
+        if rejections.transactions.is_empty() {
+            return;
+        }
+        let mut blocks = self.blocks.write();
+        let mut transactions = self.transactions.write();
+        for transaction_id in rejections.transactions.keys() {
+            transactions.insert(*transaction_id, rejections.block_height);
+        }
+        blocks.insert(rejections.block_height, rejections);
+        // Evict the oldest blocks, along with the transactions that they were the latest to reject.
+        while blocks.len() > Self::CAPACITY {
+            let Some((block_height, evicted)) = blocks.pop_first() else {
+                break;
+            };
+            for transaction_id in evicted.transactions.keys() {
+                if transactions.get(transaction_id) == Some(&block_height) {
+                    transactions.remove(transaction_id);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of tracked blocks.
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.read().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    fn sample_rejections(
+        rng: &mut TestRng,
+        block_height: u32,
+        transactions: &[(<CurrentNetwork as Network>::TransactionID, RejectionReason)],
+    ) -> BlockRejections<CurrentNetwork> {
+        let transactions = transactions.iter().copied().collect();
+        BlockRejections { block_height, block_hash: Field::rand(rng).into(), transactions }
+    }
+
+    #[test]
+    fn test_rejection_tracker() {
+        let rng = &mut TestRng::default();
+        let tracker = RejectionTracker::<CurrentNetwork>::default();
+
+        let (aborted, failed) = (Field::rand(rng).into(), Field::rand(rng).into());
+        // Ensure blocks without rejections are not tracked.
+        tracker.insert(sample_rejections(rng, 6, &[]));
+        assert_eq!(tracker.num_blocks(), 0);
+        tracker.insert(sample_rejections(rng, 7, &[(aborted, RejectionReason::Aborted)]));
+        tracker.insert(sample_rejections(rng, 8, &[(failed, RejectionReason::FailedToFinalize)]));
+        assert_eq!(tracker.find(&aborted), Some((7, RejectionReason::Aborted)));
+        assert_eq!(tracker.find(&failed), Some((8, RejectionReason::FailedToFinalize)));
+        assert_eq!(tracker.find(&Field::rand(rng).into()), None);
+
+        // Fill the tracker, and ensure the oldest blocks are evicted.
+        for height in 9..9 + RejectionTracker::<CurrentNetwork>::CAPACITY as u32 - 1 {
+            tracker.insert(sample_rejections(rng, height, &[(Field::rand(rng).into(), RejectionReason::Aborted)]));
+        }
+        assert_eq!(tracker.num_blocks(), RejectionTracker::<CurrentNetwork>::CAPACITY);
+        assert_eq!(tracker.find(&aborted), None);
+        assert_eq!(tracker.find(&failed), Some((8, RejectionReason::FailedToFinalize)));
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{committee::Committee, Address, Network};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The block production and rewards of a validator, accumulated over the blocks produced by the BFT.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ValidatorRewards {
+    /// The number of blocks whose leader certificate was authored by the validator.
+    pub blocks_led: u64,
+    /// The height of the last block led by the validator.
+    pub last_led_height: Option<u32>,
+    /// The number of blocks produced while the validator was a member of the committee.
+    pub blocks_in_committee: u64,
+    /// The block rewards attributed to the stake of the validator (including its delegators), in microcredits.
+    /// Each block reward is attributed to the committee members in proportion to their stake.
+    pub microcredits: u64,
+}
+
+/// The accounting of the validators, as persisted by the rewards tracker.
+#[derive(Deserialize, Serialize)]
+#[serde(bound = "")]
+struct RewardsState<N: Network> {
+    /// The accounting of each validator.
+    validators: IndexMap<Address<N>, ValidatorRewards>,
+    /// The height of the next block to account for.
+    next_height: u32,
+}
+
+impl<N: Network> Default for RewardsState<N> {
+    fn default() -> Self {
+        Self { validators: Default::default(), next_height: 0 }
+    }
+}
+
+/// The per-validator accounting of the block production and rewards, derived from the blocks in the ledger.
+pub struct RewardsTracker<N: Network> {
+    /// The accounting of the validators.
+    state: RwLock<RewardsState<N>>,
+}
+
+impl<N: Network> Default for RewardsTracker<N> {
+    /// Initializes a new instance of the rewards tracker.
+    fn default() -> Self {
+        Self { state: Default::default() }
+    }
+}
+
+impl<N: Network> RewardsTracker<N> {
+    /// Loads the rewards tracker from the given file, or initializes a new one if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let state = serde_json::from_slice(&std::fs::read(path)?)?;
+        Ok(Self { state: RwLock::new(state) })
+    }
+
+    /// Persists the rewards tracker to the given file.
+    /// The file is replaced atomically, so that a crash never leaves a partially written file behind.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = serde_json::to_vec(&*self.state.read())?;
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, bytes)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Discards the accounting, so that the blocks are accounted for again from genesis.
+    pub fn reset(&self) {
+        *self.state.write() = Default::default();
+    }
+
+    /// Returns the last accounted height, and the accounting of the given validator up to that height.
+    pub fn get(&self, address: &Address<N>) -> (Option<u32>, ValidatorRewards) {
+        let state = self.state.read();
+        (state.next_height.checked_sub(1), state.validators.get(address).cloned().unwrap_or_default())
+    }
+
+    /// Returns the height of the next block to account for.
+    pub fn next_height(&self) -> u32 {
+        self.state.read().next_height
+    }
+
+    /// Accounts for the block at the given height, which was not produced by the BFT.
+    pub fn record_unattributed(&self, height: u32) {
+        let mut state = self.state.write();
+        if state.next_height == height {
+            state.next_height += 1;
+        }
+    }
+
+    /// Accounts for the block at the given height, which was led by the given validator, and
+    /// whose block reward is attributed to the members of the given committee.
+    pub fn record(&self, height: u32, leader: Address<N>, committee: &Committee<N>, block_reward: u64) {
+        let mut state = self.state.write();
+        let RewardsState { validators, next_height } = &mut *state;
+        // Ensure the blocks are accounted for in order, and only once.
+        if *next_height != height {
+            return;
+        }
+        *next_height += 1;
+
+        // Attribute the block to its leader.
+        let rewards = validators.entry(leader).or_default();
+        rewards.blocks_led += 1;
+        rewards.last_led_height = Some(height);
+
+        // Attribute the block reward to the committee members, in proportion to their stake.
+        let total_stake = committee.total_stake().max(1) as u128;
+        for (address, (stake, _)) in committee.members() {
+            let reward = (block_reward as u128 * *stake as u128 / total_stake) as u64;
+            let rewards = validators.entry(*address).or_default();
+            rewards.blocks_in_committee += 1;
+            rewards.microcredits = rewards.microcredits.saturating_add(reward);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{committee::test_helpers::sample_committee, TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_rewards_tracker() {
+        let rng = &mut TestRng::default();
+        let committee = sample_committee(rng);
+        let members = committee.members().keys().copied().collect::<Vec<_>>();
+        let tracker = RewardsTracker::<CurrentNetwork>::default();
+
+        // Account for a block that was not produced by the BFT.
+        tracker.record_unattributed(0);
+        assert_eq!(tracker.next_height(), 1);
+
+        // Account for two blocks led by the first member.
+        let block_reward = 1_000_000;
+        tracker.record(1, members[0], &committee, block_reward);
+        tracker.record(2, members[0], &committee, block_reward);
+        // Ensure a block that was already accounted for is ignored.
+        tracker.record(2, members[1], &committee, block_reward);
+        assert_eq!(tracker.next_height(), 3);
+
+        let (accounted_height, leader) = tracker.get(&members[0]);
+        assert_eq!(accounted_height, Some(2));
+        assert_eq!(leader.blocks_led, 2);
+        assert_eq!(leader.last_led_height, Some(2));
+        assert_eq!(leader.blocks_in_committee, 2);
+        let (_, follower) = tracker.get(&members[1]);
+        assert_eq!(follower.blocks_led, 0);
+        assert_eq!(follower.last_led_height, None);
+        assert_eq!(follower.blocks_in_committee, 2);
+
+        // Ensure the block rewards are attributed in proportion to the stake.
+        let expected = |address: &Address<CurrentNetwork>| {
+            2 * (block_reward as u128 * committee.get_stake(*address) as u128 / committee.total_stake() as u128) as u64
+        };
+        assert_eq!(leader.microcredits, expected(&members[0]));
+        assert_eq!(follower.microcredits, expected(&members[1]));
+        let total = members.iter().map(|address| tracker.get(address).1.microcredits).sum::<u64>();
+        assert!(total <= 2 * block_reward);
+
+        // Ensure the accounting survives a restart.
+        let path = std::env::temp_dir().join(format!("snarkos-rewards-{}.json", rand::random::<u64>()));
+        assert_eq!(RewardsTracker::<CurrentNetwork>::load(&path).unwrap().next_height(), 0);
+        tracker.save(&path).unwrap();
+        let restored = RewardsTracker::<CurrentNetwork>::load(&path).unwrap();
+        assert_eq!(restored.next_height(), 3);
+        assert_eq!(restored.get(&members[0]), tracker.get(&members[0]));
+        assert_eq!(restored.get(&members[1]), tracker.get(&members[1]));
+        std::fs::remove_file(&path).unwrap();
+
+        // Ensure the accounting starts over once it is reset.
+        restored.reset();
+        assert_eq!(restored.get(&members[0]), (None, ValidatorRewards::default()));
+    }
+}
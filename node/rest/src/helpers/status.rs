@@ -0,0 +1,113 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_cdn::CdnSyncStatus;
+
+use serde::{Deserialize, Serialize};
+
+/// The lower bounds (in microcredits) of the buckets of the priority fee histogram.
+pub const PRIORITY_FEE_BUCKETS: [u64; 5] = [0, 1_000, 10_000, 100_000, 1_000_000];
+
+/// A summary of the state of the node, for dashboards.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct NodeStatus {
+    /// The type of the node.
+    pub node_type: String,
+    /// The latest block height of the node.
+    pub height: u32,
+    /// The highest block height reported by the connected peers, excluding the provers.
+    pub highest_peer_height: Option<u32>,
+    /// Whether the node is within the maximum height lag of its connected peers.
+    pub is_synced: bool,
+    /// The number of connected peers of each type.
+    pub peers: PeerCounts,
+    /// The state of the memory pool, if the node runs the BFT.
+    pub memory_pool: Option<MemoryPoolStatus>,
+    /// The state of the BFT, if the node runs it.
+    pub bft: Option<BftStatus>,
+    /// The progress of the sync from the CDN, if the node synced from a CDN.
+    #[serde(default)]
+    pub cdn_sync: Option<CdnSyncStatus>,
+}
+
+/// The number of connected peers of each type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PeerCounts {
+    pub validators: usize,
+    pub provers: usize,
+    pub clients: usize,
+}
+
+/// The state of the memory pool.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MemoryPoolStatus {
+    /// The number of unconfirmed transmissions.
+    pub transmissions: usize,
+    /// The number of unconfirmed transactions in each bucket of `PRIORITY_FEE_BUCKETS`.
+    pub priority_fees: Vec<usize>,
+}
+
+/// The state of the BFT.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BftStatus {
+    /// The current round.
+    pub round: u64,
+    /// Whether the node refrains from proposing batches.
+    pub paused: bool,
+    /// Whether the node halted proposing batches, as the connected stake is below the quorum threshold.
+    pub quorum_lost: bool,
+    /// Whether the batch size and delay are tuned at runtime, rather than static.
+    pub batch_tuning: bool,
+    /// The number of transmissions in a batch.
+    pub batch_size: usize,
+    /// The delay (in milliseconds) before proposing a batch.
+    pub batch_delay_ms: u64,
+}
+
+impl MemoryPoolStatus {
+    /// Returns the number of the given priority fees in each bucket of `PRIORITY_FEE_BUCKETS`.
+    pub fn histogram(priority_fees: impl IntoIterator<Item = u64>) -> Vec<usize> {
+        let mut counts = vec![0; PRIORITY_FEE_BUCKETS.len()];
+        for priority_fee in priority_fees {
+            let bucket = PRIORITY_FEE_BUCKETS.iter().rposition(|bound| priority_fee >= *bound).unwrap_or(0);
+            counts[bucket] += 1;
+        }
+        counts
+    }
+
+    /// Returns the label of the bucket at the given index of `PRIORITY_FEE_BUCKETS`.
+    pub fn bucket_label(index: usize) -> String {
+        match (PRIORITY_FEE_BUCKETS.get(index), PRIORITY_FEE_BUCKETS.get(index + 1)) {
+            (Some(lower), Some(upper)) => format!("{lower}-{}", upper - 1),
+            (Some(lower), None) => format!("{lower}+"),
+            (None, _) => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_fee_histogram() {
+        let counts = MemoryPoolStatus::histogram([0, 999, 1_000, 50_000, 99_999, 5_000_000]);
+        assert_eq!(counts, vec![2, 1, 1, 1, 1]);
+        assert!(MemoryPoolStatus::histogram([]).iter().all(|count| *count == 0));
+
+        assert_eq!(MemoryPoolStatus::bucket_label(0), "0-999");
+        assert_eq!(MemoryPoolStatus::bucket_label(4), "1000000+");
+        assert_eq!(MemoryPoolStatus::bucket_label(5), "");
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::header::CONTENT_TYPE,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::{mpsc, OwnedSemaphorePermit};
+use tokio_stream::wrappers::ReceiverStream;
+
+/// The maximum number of streamed responses that are served concurrently.
+pub(crate) const MAX_CONCURRENT_STREAMS: usize = 4;
+/// The number of lines that are buffered ahead of the reader of a streamed response.
+const STREAM_BUFFER_SIZE: usize = 256;
+
+/// Returns a chunked response of newline-delimited JSON, whose items are read in batches by the given function.
+///
+/// The function is called on a blocking thread with the cursor of a batch, and returns the items of the batch,
+/// along with the cursor of the next batch, or `None` once the stream is complete. The next batch is only read
+/// once the reader caught up, so that neither side holds the whole result in memory, and no thread is held
+/// while the reader is behind. If the function fails, the response is aborted, so that the reader cannot
+/// mistake it for a complete one. The given permit is held until the stream ends.
+pub(crate) fn stream_ndjson<Cursor, T, F>(permit: OwnedSemaphorePermit, cursor: Cursor, read_batch: F) -> Response
+where
+    Cursor: Send + 'static,
+    T: Serialize,
+    F: Fn(Cursor) -> Result<(Vec<T>, Option<Cursor>)> + Send + Sync + 'static,
+{
+    let (sender, receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+
+    let read_batch = Arc::new(read_batch);
+    tokio::spawn(async move {
+        let _permit = permit;
+        let mut cursor = Some(cursor);
+        while let Some(current) = cursor.take() {
+            // Read the next batch, and encode its items as lines.
+            let read_batch = read_batch.clone();
+            let batch = tokio::task::spawn_blocking(move || -> Result<(Vec<String>, Option<Cursor>)> {
+                let (items, next) = read_batch(current)?;
+                Ok((items.iter().map(to_line).collect::<Result<_>>()?, next))
+            });
+            match batch.await.map_err(anyhow::Error::from).and_then(|batch| batch) {
+                Ok((lines, next)) => {
+                    for line in lines {
+                        // Stop if the reader has gone away.
+                        if sender.send(Ok(line)).await.is_err() {
+                            return;
+                        }
+                    }
+                    cursor = next;
+                }
+                Err(error) => {
+                    warn!("Aborted a streamed response - {error}");
+                    let _ = sender.send(Err(error)).await;
+                    return;
+                }
+            }
+        }
+    });
+
+    ([(CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(ReceiverStream::new(receiver))).into_response()
+}
+
+/// Returns the given item as a line of JSON.
+fn to_line<T: Serialize>(item: &T) -> Result<String> {
+    let mut line = serde_json::to_string(item)?;
+    line.push('\n');
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_line() {
+        assert_eq!(to_line(&42u32).unwrap(), "42\n");
+        assert_eq!(to_line(&"aleo").unwrap(), "\"aleo\"\n");
+        // Multi-line values are still encoded on a single line.
+        let object = json!({ "height": 1, "transactions": ["a", "b"] });
+        assert_eq!(to_line(&object).unwrap(), "{\"height\":1,\"transactions\":[\"a\",\"b\"]}\n");
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Transaction, Identifier, Network, ProgramID};
+
+use serde::Deserialize;
+
+/// The filter of a transaction subscription, which is matched against the transitions of every confirmed transaction.
+///
+/// For example, `?program=credits.aleo&function=transfer_public` only matches `credits.aleo/transfer_public` calls.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(bound = "")]
+pub struct TransactionFilter<N: Network> {
+    /// The program ID that a transaction must touch, if any.
+    pub program: Option<ProgramID<N>>,
+    /// The function name that a transaction must call, if any.
+    pub function: Option<Identifier<N>>,
+}
+
+impl<N: Network> TransactionFilter<N> {
+    /// Returns `true` if the given transaction matches the filter.
+    ///
+    /// A transaction matches if any of its transitions (including the fee transition) matches the filter,
+    /// or if it deploys the given program and no function name is given.
+    pub fn matches(&self, transaction: &Transaction<N>) -> bool {
+        // Check the deployed program.
+        if let (Some(deployment), None) = (transaction.deployment(), &self.function) {
+            if self.program.as_ref().map_or(true, |program| deployment.program_id() == program) {
+                return true;
+            }
+        }
+        // Check the transitions.
+        transaction.transitions().any(|transition| {
+            self.program.as_ref().map_or(true, |program| transition.program_id() == program)
+                && self.function.as_ref().map_or(true, |function| transition.function_name() == function)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::ledger_test_helpers::sample_fee_public_transaction,
+        prelude::{TestRng, Testnet3},
+    };
+
+    use core::str::FromStr;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_transaction_filter() {
+        let rng = &mut TestRng::default();
+        // Sample a transaction with a `credits.aleo/fee_public` transition.
+        let transaction = sample_fee_public_transaction(rng);
+
+        let filter = |program: Option<&str>, function: Option<&str>| TransactionFilter::<CurrentNetwork> {
+            program: program.map(|program| ProgramID::from_str(program).unwrap()),
+            function: function.map(|function| Identifier::from_str(function).unwrap()),
+        };
+
+        // Ensure the matching filters match.
+        assert!(filter(None, None).matches(&transaction));
+        assert!(filter(Some("credits.aleo"), None).matches(&transaction));
+        assert!(filter(None, Some("fee_public")).matches(&transaction));
+        assert!(filter(Some("credits.aleo"), Some("fee_public")).matches(&transaction));
+        // Ensure the other filters do not match.
+        assert!(!filter(Some("token.aleo"), None).matches(&transaction));
+        assert!(!filter(None, Some("transfer_public")).matches(&transaction));
+        assert!(!filter(Some("token.aleo"), Some("fee_public")).matches(&transaction));
+    }
+
+    #[test]
+    fn test_transaction_filter_query() {
+        let filter: TransactionFilter<CurrentNetwork> =
+            serde_json::from_str(r#"{ "program": "credits.aleo", "function": "transfer_public" }"#).unwrap();
+        assert_eq!(filter.program, Some(ProgramID::from_str("credits.aleo").unwrap()));
+        assert_eq!(filter.function, Some(Identifier::from_str("transfer_public").unwrap()));
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::{
+    body::Body,
+    http::{
+        header::{HeaderName, HeaderValue, LINK},
+        Request,
+    },
+    middleware::Next,
+    response::Response,
+};
+
+/// A version of the REST API, which is served under its own path prefix.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ApiVersion {
+    /// The unversioned routes (e.g. `/testnet3/block/latest`), which are deprecated in favor of the latest version.
+    Unversioned,
+    /// The first version of the API (e.g. `/v1/testnet3/block/latest`).
+    V1,
+}
+
+/// A feature of the REST API that can be toggled per version.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ApiFeature {
+    /// The `GET ../latest/..` routes, which are superseded by the `../latest` suffixed routes.
+    LatestRoutes,
+    /// The `POST ../program/deploy` route.
+    ProgramDeploy,
+    /// The `GET ../consensus/round/{round}` route.
+    ConsensusOutput,
+}
+
+impl ApiVersion {
+    /// The versions of the API served by the node.
+    pub const ALL: [Self; 2] = [Self::Unversioned, Self::V1];
+    /// The latest version of the API.
+    pub const LATEST: Self = Self::V1;
+
+    /// Returns the path prefix of the version.
+    pub const fn prefix(&self) -> &'static str {
+        match self {
+            Self::Unversioned => "",
+            Self::V1 => "/v1",
+        }
+    }
+
+    /// Returns `true` if the version is deprecated.
+    pub const fn is_deprecated(&self) -> bool {
+        matches!(self, Self::Unversioned)
+    }
+
+    /// Returns `true` if the given feature is enabled in the version.
+    pub const fn supports(&self, feature: ApiFeature) -> bool {
+        match (self, feature) {
+            // The `../latest/..` routes are not carried over into the versioned API.
+            (Self::Unversioned, ApiFeature::LatestRoutes) => true,
+            (Self::V1, ApiFeature::LatestRoutes) => false,
+            // All other features are enabled in every version.
+            (_, ApiFeature::ProgramDeploy | ApiFeature::ConsensusOutput) => true,
+        }
+    }
+}
+
+/// Adds the deprecation headers to the responses of a deprecated version of the API.
+///
+/// The `Link` header points to the same route in the latest version of the API, if it exists there.
+pub async fn deprecation_middleware(request: Request<Body>, next: Next) -> Response {
+    // Determine the route in the latest version of the API.
+    let path = request.uri().path();
+    let is_removed = path.starts_with("/testnet3/latest/") && !ApiVersion::LATEST.supports(ApiFeature::LatestRoutes);
+    let successor =
+        (!is_removed).then(|| format!("<{}{path}>; rel=\"successor-version\"", ApiVersion::LATEST.prefix()));
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(HeaderName::from_static("deprecation"), HeaderValue::from_static("true"));
+    if let Some(link) = successor.and_then(|successor| HeaderValue::from_str(&successor).ok()) {
+        headers.insert(LINK, link);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_versions() {
+        // Ensure the latest version is not deprecated.
+        assert!(!ApiVersion::LATEST.is_deprecated());
+        // Ensure every version has a distinct prefix.
+        for (i, a) in ApiVersion::ALL.iter().enumerate() {
+            for b in &ApiVersion::ALL[i + 1..] {
+                assert_ne!(a.prefix(), b.prefix());
+            }
+        }
+        // Ensure the deprecated routes are not carried over.
+        assert!(ApiVersion::Unversioned.supports(ApiFeature::LatestRoutes));
+        assert!(!ApiVersion::V1.supports(ApiFeature::LatestRoutes));
+    }
+}
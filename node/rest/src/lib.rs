@@ -22,6 +22,8 @@ pub use helpers::*;
 
 mod routes;
 
+use snarkos_node_bus::EventBus;
+use snarkos_node_cdn::CdnSyncProgress;
 use snarkos_node_consensus::Consensus;
 use snarkos_node_router::{
     messages::{Message, UnconfirmedTransaction},
@@ -32,22 +34,37 @@ use snarkvm::{
     ledger::narwhal::Data,
     prelude::{cfg_into_iter, store::ConsensusStorage, Ledger, Network},
 };
+#[cfg(feature = "faucet")]
+use snarkvm::prelude::{PrivateKey, Value};
+#[cfg(feature = "metrics")]
+use metrics::{MetricsHistory, PeerSample};
 
 use anyhow::Result;
 use axum::{
-    body::Body,
+    body::{Body, Bytes},
     extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State},
-    http::{header::CONTENT_TYPE, Method, Request, StatusCode},
+    http::{header::{CONTENT_DISPOSITION, CONTENT_TYPE}, HeaderName, HeaderValue, Method, Request, StatusCode},
     middleware,
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
     routing::{get, post},
+    Extension,
     Json,
 };
 use axum_extra::response::ErasedJson;
 use parking_lot::Mutex;
-use std::{net::SocketAddr, sync::Arc};
-use tokio::{net::TcpListener, task::JoinHandle};
+#[cfg(feature = "faucet")]
+use core::str::FromStr;
+use std::{
+    net::{SocketAddr, TcpListener},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
 use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
 use tower_http::{
     cors::{Any, CorsLayer},
@@ -60,29 +77,254 @@ pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
     /// The consensus module.
     consensus: Option<Consensus<N>>,
     /// The ledger.
+    ///
+    /// Note: The REST server reads from the same ledger as consensus. The ledger opens its storage as the only
+    /// RocksDB instance, and exposes neither a read-only secondary instance nor a snapshot that could be refreshed,
+    /// so there is no separate replica that the reads could be served from.
     ledger: Ledger<N, C>,
     /// The node (routing).
     routing: Arc<R>,
+    /// The event bus, whose events are streamed to websocket subscribers.
+    event_bus: EventBus<N>,
+    /// The faucet, if it is started.
+    #[cfg(feature = "faucet")]
+    faucet: Arc<once_cell::sync::OnceCell<Faucet<N>>>,
+    /// The semaphore that limits the number of concurrent executions.
+    #[cfg(feature = "execute")]
+    executions: Arc<tokio::sync::Semaphore>,
+    /// The semaphore that limits the number of concurrent estimates of executions.
+    #[cfg(feature = "execute")]
+    estimates: Arc<tokio::sync::Semaphore>,
+    /// The semaphore that limits the number of concurrent scans for the records of a view key.
+    record_scans: Arc<tokio::sync::Semaphore>,
+    /// The semaphore that limits the number of broadcast batches that are checked concurrently.
+    batch_checks: Arc<tokio::sync::Semaphore>,
+    /// The semaphore that limits the number of concurrent streamed responses.
+    streams: Arc<tokio::sync::Semaphore>,
+    /// The retained history of the peer connectivity.
+    #[cfg(feature = "metrics")]
+    metrics_history: Arc<MetricsHistory>,
+    /// The load monitor, which decides when the server sheds load.
+    load: Arc<LoadMonitor>,
+    /// The per-validator accounting of the block production and rewards, if the rewards tracker is started.
+    rewards: Arc<once_cell::sync::OnceCell<Arc<RewardsTracker<N>>>>,
+    /// The tracker of the propagation delay of the recent blocks.
+    propagation: Arc<PropagationTracker>,
+    /// The tracker of the transactions that the recent blocks rejected.
+    rejections: Arc<RejectionTracker<N>>,
+    /// The boolean flag for the read-only mode, in which the routes that write to the network are refused.
+    is_read_only: Arc<AtomicBool>,
+    /// The journal of the admin actions taken against the node.
+    journal: Arc<ActionJournal>,
+    /// The local context of the node that is included in its debug bundles.
+    debug_bundle: Arc<Mutex<DebugBundleConfig>>,
+    /// The progress of the sync from the CDN, if the node synced from a CDN.
+    cdn_sync: Arc<once_cell::sync::OnceCell<CdnSyncProgress>>,
+    /// The listener that the server currently serves on.
+    listener: Arc<Mutex<Option<RestListener>>>,
     /// The server handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
 
 impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     /// Initializes a new instance of the server.
+    ///
+    /// If the node synced from a CDN, the server takes over the given CDN sync server, and keeps reporting
+    /// the progress of the sync in the node status.
     pub async fn start(
         rest_ip: SocketAddr,
         rest_rps: u32,
         consensus: Option<Consensus<N>>,
         ledger: Ledger<N, C>,
         routing: Arc<R>,
+        event_bus: EventBus<N>,
+        cdn_sync: Option<CdnSyncServer>,
     ) -> Result<Self> {
         // Initialize the server.
-        let mut server = Self { consensus, ledger, routing, handles: Default::default() };
+        let server = Self {
+            consensus,
+            ledger,
+            routing,
+            event_bus,
+            #[cfg(feature = "faucet")]
+            faucet: Default::default(),
+            #[cfg(feature = "execute")]
+            executions: Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_EXECUTIONS)),
+            #[cfg(feature = "execute")]
+            estimates: Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_ESTIMATES)),
+            record_scans: Arc::new(tokio::sync::Semaphore::new(routes::MAX_CONCURRENT_RECORD_SCANS)),
+            batch_checks: Arc::new(tokio::sync::Semaphore::new(routes::MAX_CONCURRENT_BATCH_CHECKS)),
+            streams: Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_STREAMS)),
+            #[cfg(feature = "metrics")]
+            metrics_history: Default::default(),
+            load: Default::default(),
+            rewards: Default::default(),
+            propagation: Default::default(),
+            rejections: Default::default(),
+            is_read_only: Default::default(),
+            journal: Default::default(),
+            debug_bundle: Default::default(),
+            cdn_sync: Default::default(),
+            listener: Default::default(),
+            handles: Default::default(),
+        };
+        // Take over the listener of the CDN sync server, so that the server is spawned on its socket.
+        if let Some(cdn_sync) = cdn_sync {
+            let _ = server.cdn_sync.set(cdn_sync.progress);
+            *server.listener.lock() = Some(cdn_sync.listener);
+        }
         // Spawn the server.
-        server.spawn_server(rest_ip, rest_rps).await;
+        server.rebind(rest_ip, rest_rps)?;
+        // Spawn the load monitor.
+        server.spawn_load_monitor();
+        // Spawn the sampling of the propagation delay of the blocks.
+        server.spawn_propagation_tracker();
+        // Spawn the tracking of the transactions that the blocks reject.
+        server.spawn_rejection_tracker();
+        // Spawn the sampling of the metrics history.
+        #[cfg(feature = "metrics")]
+        server.spawn_metrics_history();
         // Return the server.
         Ok(server)
     }
+
+    /// Opens the journal of the admin actions at the given path, to which the actions are appended from then on.
+    pub fn open_action_journal(&self, path: &std::path::Path) -> Result<()> {
+        self.journal.open(path)
+    }
+
+    /// Starts the rewards tracker, which accounts for the block production and rewards of the validators.
+    /// The accounting is persisted to the given file whenever it catches up with the ledger, so that only
+    /// the blocks that were added since the last run are accounted for on restart.
+    pub fn start_rewards_tracker(&self, path: &std::path::Path) -> Result<()> {
+        use snarkos_node_bus::NodeEvent;
+        use tokio::sync::broadcast::error::RecvError;
+
+        let tracker = Arc::new(RewardsTracker::load(path)?);
+        // Start over if the ledger is behind the accounting, e.g. if the ledger was removed since the last run.
+        if tracker.next_height() > self.ledger.latest_height().saturating_add(1) {
+            warn!("The rewards tracker is ahead of the ledger, accounting for the blocks from genesis again");
+            tracker.reset();
+        }
+        if self.rewards.set(tracker.clone()).is_err() {
+            anyhow::bail!("The rewards tracker is already started")
+        }
+        info!("Started the rewards tracker, from block {}", tracker.next_height());
+
+        let (rest, path) = (self.clone(), path.to_path_buf());
+        let mut events = self.event_bus.subscribe();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                // Account for the blocks that were added since the last update, and persist the accounting.
+                let (ledger, tracker, path) = (rest.ledger.clone(), tracker.clone(), path.clone());
+                let update = tokio::task::spawn_blocking(move || {
+                    let next_height = tracker.next_height();
+                    let result = Self::update_rewards(&ledger, &tracker);
+                    // Persist the blocks that were accounted for, even if a later block failed.
+                    if tracker.next_height() != next_height {
+                        tracker.save(&path)?;
+                    }
+                    result
+                });
+                match update.await {
+                    Ok(Ok(())) => (),
+                    Ok(Err(error)) => warn!("Failed to account for the validator rewards - {error}"),
+                    Err(error) => error!("Failed to account for the validator rewards - {error}"),
+                }
+                // Wait for the next block.
+                loop {
+                    match events.recv().await {
+                        Ok(NodeEvent::BlockAdvanced { .. }) | Err(RecvError::Lagged(_)) => break,
+                        Ok(_) => continue,
+                        Err(RecvError::Closed) => return,
+                    }
+                }
+            }
+        }));
+        Ok(())
+    }
+
+    /// Sets the log file, ledger directory, and redacted configuration that are included in the debug bundles.
+    pub fn set_debug_bundle_config(&self, config: DebugBundleConfig) {
+        *self.debug_bundle.lock() = config;
+    }
+
+    /// Starts the faucet, which funds the requested addresses from the given account.
+    /// The pending requests are funded whenever a block is added, so that they are included in the next block.
+    #[cfg(feature = "faucet")]
+    pub fn start_faucet(&self, private_key: PrivateKey<N>, config: FaucetConfig) -> Result<()> {
+        use snarkos_node_bus::NodeEvent;
+        use tokio::sync::broadcast::error::RecvError;
+
+        // Ensure the node can add the faucet transactions to the memory pool.
+        if self.consensus.is_none() {
+            anyhow::bail!("The faucet is only available on validators")
+        }
+        let faucet = Faucet::new(private_key, config)?;
+        if self.faucet.set(faucet.clone()).is_err() {
+            anyhow::bail!("The faucet is already started")
+        }
+        info!("Started the faucet, sending {} microcredits per request", faucet.amount());
+
+        let (rest, pruned_faucet) = (self.clone(), faucet.clone());
+        let mut events = self.event_bus.subscribe();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(NodeEvent::BlockAdvanced { .. }) => rest.fund_faucet_requests(&faucet).await,
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }));
+        // Periodically forget the requests that are past their cooldown.
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FAUCET_PRUNE_INTERVAL);
+            loop {
+                interval.tick().await;
+                pruned_faucet.prune();
+            }
+        }));
+        Ok(())
+    }
+
+    /// Funds the next batch of faucet requests, by broadcasting a `credits.aleo/transfer_public` for each address.
+    #[cfg(feature = "faucet")]
+    async fn fund_faucet_requests(&self, faucet: &Faucet<N>) {
+        let Some(consensus) = &self.consensus else {
+            return;
+        };
+        for address in faucet.next_batch() {
+            // Create the transfer, paying the fee from the public balance of the faucet account.
+            let (ledger, private_key, amount) = (self.ledger.clone(), *faucet.private_key(), faucet.amount());
+            let transaction = tokio::task::spawn_blocking(move || {
+                let inputs = [Value::from_str(&address.to_string())?, Value::from_str(&format!("{amount}u64"))?];
+                let rng = &mut rand::thread_rng();
+                let locator = ("credits.aleo", "transfer_public");
+                ledger.vm().execute(&private_key, locator, inputs.iter(), None, 0, None, rng)
+            })
+            .await;
+            let transaction = match transaction.map_err(anyhow::Error::from).and_then(|result| result) {
+                Ok(transaction) => transaction,
+                Err(error) => {
+                    warn!("Faucet failed to create a transfer to '{address}' - {error}");
+                    continue;
+                }
+            };
+            // Add the transfer to the memory pool, and broadcast it.
+            let transaction_id = transaction.id();
+            if let Err(error) = consensus.add_unconfirmed_transaction(transaction.clone()).await {
+                warn!("Faucet failed to add the transfer to '{address}' to the memory pool - {error}");
+                continue;
+            }
+            let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
+                transaction_id,
+                transaction: Data::Object(transaction),
+            });
+            self.routing.propagate(message, &[]);
+            debug!("Faucet sent {amount} microcredits to '{address}' in '{transaction_id}'");
+        }
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
@@ -95,51 +337,364 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     pub const fn handles(&self) -> &Arc<Mutex<Vec<JoinHandle<()>>>> {
         &self.handles
     }
+
+    /// Returns `true` if the server refuses the routes that write to the network.
+    pub fn is_read_only(&self) -> bool {
+        self.is_read_only.load(Ordering::Relaxed)
+    }
+
+    /// Sets the read-only mode, in which the routes that write to the network are refused.
+    pub fn set_read_only(&self, is_read_only: bool) {
+        self.is_read_only.store(is_read_only, Ordering::Relaxed)
+    }
+
+    /// Returns the address and the rate limit per IP of the listener that the server currently serves on.
+    pub fn listener(&self) -> Option<(SocketAddr, u32)> {
+        self.listener.lock().as_ref().map(|listener| (listener.address, listener.rps))
+    }
+
+    /// Moves the server to a new listener, with the given address and rate limit per IP.
+    ///
+    /// The new listener is bound before the current one stops accepting connections, so that no connection
+    /// is refused in the meantime, and the requests in flight on the current listener are drained.
+    /// If the new listener cannot be bound, the server keeps serving on the current listener.
+    pub fn rebind(&self, rest_ip: SocketAddr, rest_rps: u32) -> Result<SocketAddr> {
+        // Ensure the rate limit allows requests.
+        if rest_rps == 0 {
+            anyhow::bail!("The REST rate limit must be at least 1 request per second")
+        }
+        let mut current = self.listener.lock();
+        // Bind the new listener, or serve on the socket of the current listener, if the address is unchanged.
+        let socket = match current.as_ref().filter(|listener| listener.address == rest_ip) {
+            Some(listener) => listener.socket()?,
+            None => bind_listener(rest_ip)
+                .map_err(|error| anyhow::anyhow!("Failed to bind the REST server to '{rest_ip}' - {error}"))?,
+        };
+        let address = socket.local_addr()?;
+        let listener = self.spawn_server(socket, address, rest_rps)?;
+        info!("The REST server is listening on '{address}'");
+        // Drain the current listener.
+        if let Some(previous) = current.replace(listener) {
+            info!("Draining the REST server on '{}'", previous.address);
+            previous.drain();
+        }
+        Ok(address)
+    }
+
+    /// Shuts down the REST server.
+    pub fn shut_down(&self) {
+        info!("Shutting down the REST server...");
+        // Abort the server tasks.
+        self.handles.lock().iter().for_each(|handle| handle.abort());
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
-    async fn spawn_server(&mut self, rest_ip: SocketAddr, rest_rps: u32) {
+    /// Spawns the server on the given bound socket, with the given rate limit per IP.
+    fn spawn_server(&self, socket: TcpListener, rest_ip: SocketAddr, rest_rps: u32) -> Result<RestListener> {
+        use axum::ServiceExt;
+        use tower::Layer;
+
         let cors = CorsLayer::new()
             .allow_origin(Any)
             .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-            .allow_headers([CONTENT_TYPE]);
+            .allow_headers([CONTENT_TYPE, HeaderName::from_static(CORRELATION_ID_HEADER)])
+            .expose_headers([HeaderName::from_static(CORRELATION_ID_HEADER)]);
 
         // Log the REST rate limit per IP.
         debug!("REST rate limit per IP - {rest_rps} RPS");
 
         // Prepare the rate limiting setup.
-        let governor_config = Box::new(
-            GovernorConfigBuilder::default()
-                .per_second(1)
-                .burst_size(rest_rps)
-                .error_handler(|error| Response::new(error.to_string().into()))
-                .finish()
-                .expect("Couldn't set up rate limiting for the REST server!"),
-        );
+        let governor_config = GovernorConfigBuilder::default()
+            .per_second(1)
+            .burst_size(rest_rps)
+            .error_handler(|error| Response::new(error.to_string().into()))
+            .finish()
+            .ok_or_else(|| anyhow::anyhow!("Failed to set up the rate limiting of the REST server"))?;
 
         let router = {
-            axum::Router::new()
+            let mut router = axum::Router::new();
+            // Serve every version of the API under its own prefix.
+            for version in ApiVersion::ALL {
+                let routes = match version.is_deprecated() {
+                    true => Self::routes(version).layer(middleware::from_fn(deprecation_middleware)),
+                    false => Self::routes(version),
+                };
+                router = match version.prefix() {
+                    "" => router.merge(routes),
+                    prefix => router.nest(prefix, routes),
+                };
+            }
+
+            router
+            // Pass in `Rest` to make things convenient.
+            .with_state(self.clone())
+            // Refuse the routes that write to the network when the node is read-only.
+            .layer(middleware::from_fn_with_state(self.is_read_only.clone(), read_only_middleware))
+            // Shed the expensive requests when the node is overloaded.
+            .layer(middleware::from_fn_with_state(self.load.clone(), load_shedding_middleware))
+            // Enable tower-http tracing.
+            .layer(TraceLayer::new_for_http())
+            // Custom logging.
+            .layer(middleware::from_fn(log_middleware))
+            // Run each request in a span with its correlation ID.
+            .layer(middleware::from_fn(correlation_middleware))
+            // Enable CORS.
+            .layer(cors)
+            // Cap body size at 10MB.
+            .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
+        };
+        // Apply the rate limiting outside of the router, as the layer borrows its configuration, which would
+        // otherwise have to be leaked on every rebind. The limiter itself is shared by the layered service.
+        let service = GovernorLayer { config: &governor_config }.layer(router);
+
+        // Serve the listener, until it is drained.
+        let rest_listener = serve_listener(&socket)?;
+        let (shutdown, signal) = tokio::sync::oneshot::channel();
+        let handle = tokio::spawn(async move {
+            let service = service.into_make_service_with_connect_info::<SocketAddr>();
+            let signal = async {
+                let _ = signal.await;
+            };
+            if let Err(error) = axum::serve(rest_listener, service).with_graceful_shutdown(signal).await {
+                error!("The REST server on '{rest_ip}' failed - {error}");
+            }
+        });
+        let listener = RestListener::new(rest_ip, rest_rps, socket, shutdown, handle.abort_handle());
+        self.handles.lock().push(handle);
+        Ok(listener)
+    }
+
+    /// Spawns a task that periodically samples the load of the node, and toggles the load-shedding mode,
+    /// in which the expensive routes are rejected and serving blocks to peers is throttled.
+    fn spawn_load_monitor(&self) {
+        let rest = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                let start = Instant::now();
+                tokio::time::sleep(LOAD_SAMPLE_INTERVAL).await;
+                // The event loop lag is the time the timer fired beyond its deadline.
+                let sample = rest.load.sample(start.elapsed().saturating_sub(LOAD_SAMPLE_INTERVAL));
+                if let Some(is_shedding) = rest.load.update(&sample) {
+                    match is_shedding {
+                        true => warn!(
+                            "Shedding load (event loop lag {}ms, {} requests in flight)",
+                            sample.event_loop_lag.as_millis(),
+                            sample.requests_in_flight
+                        ),
+                        false => info!("Stopped shedding load"),
+                    }
+                    rest.routing.router().set_load_shedding(is_shedding);
+                }
+            }
+        }));
+    }
+
+    /// Spawns a task that samples the propagation delay of each block added to the ledger, i.e. the time from
+    /// the timestamp of the block until it is added, skipping the blocks added while catching up with the peers.
+    fn spawn_propagation_tracker(&self) {
+        use snarkos_node_bus::NodeEvent;
+        use tokio::sync::broadcast::error::RecvError;
+
+        let rest = self.clone();
+        let mut events = self.event_bus.subscribe();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                let height = match events.recv().await {
+                    Ok(NodeEvent::BlockAdvanced { height, .. }) => height,
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                let now = ::time::OffsetDateTime::now_utc().unix_timestamp();
+                // Skip the block if the node is catching up with its peers.
+                let highest_peer_height = rest.routing.router().highest_peer_height().unwrap_or(height);
+                if height.saturating_add(PropagationTracker::MAXIMUM_BLOCKS_BEHIND) < highest_peer_height {
+                    continue;
+                }
+                match rest.ledger.get_header(height) {
+                    Ok(header) => rest.propagation.record(now.saturating_sub(header.timestamp())),
+                    Err(error) => warn!("Failed to sample the propagation delay of block {height} - {error}"),
+                }
+            }
+        }));
+    }
+
+    /// Spawns a task that tracks the transactions that each block added to the ledger rejected.
+    /// The blocks are read from the ledger, so that any blocks that were skipped by a lagging receiver are tracked.
+    fn spawn_rejection_tracker(&self) {
+        use snarkos_node_bus::NodeEvent;
+        use tokio::sync::broadcast::error::RecvError;
+
+        let rest = self.clone();
+        let mut events = self.event_bus.subscribe();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut next_height = rest.ledger.latest_height().saturating_add(1);
+            loop {
+                let height = match events.recv().await {
+                    Ok(NodeEvent::BlockAdvanced { height, .. }) => height,
+                    Err(RecvError::Lagged(_)) => rest.ledger.latest_height(),
+                    Ok(_) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                // Track the blocks that were added since the last event, or the new block after a rollback.
+                let start = next_height.min(height);
+                let (ledger, rejections) = (rest.ledger.clone(), rest.rejections.clone());
+                let result = tokio::task::spawn_blocking(move || {
+                    for height in start..=height {
+                        rejections.insert(BlockRejections::new(&ledger.get_block(height)?));
+                    }
+                    Ok(())
+                })
+                .await
+                .unwrap_or_else(|error| Err(anyhow::Error::from(error)));
+                match result {
+                    Ok(()) => next_height = height.saturating_add(1),
+                    Err(error) => warn!("Failed to track the rejected transactions of block {height} - {error}"),
+                }
+            }
+        }));
+    }
+
+    /// Accounts for the blocks in the ledger that the given rewards tracker has not accounted for yet.
+    fn update_rewards(ledger: &Ledger<N, C>, rewards: &RewardsTracker<N>) -> Result<()> {
+        use snarkvm::{ledger::authority::Authority, prelude::block::Ratify};
+
+        for height in rewards.next_height()..=ledger.latest_height() {
+            let block = ledger.get_block(height)?;
+            // Retrieve the leader of the block, if the block was produced by the BFT.
+            let leader = match block.authority() {
+                Authority::Beacon(_) => {
+                    rewards.record_unattributed(height);
+                    continue;
+                }
+                Authority::Quorum(subdag) => subdag.leader_certificate().author(),
+            };
+            // Retrieve the committee that the block reward is attributed to.
+            let Some(committee) = ledger.get_committee_for_round(block.round())? else {
+                anyhow::bail!("No committee found for round {} in the ledger", block.round())
+            };
+            // Retrieve the block reward.
+            let block_reward = block
+                .ratifications()
+                .iter()
+                .find_map(|ratify| match ratify {
+                    Ratify::BlockReward(block_reward) => Some(*block_reward),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            rewards.record(height, leader, &committee, block_reward);
+        }
+        Ok(())
+    }
+
+    /// Spawns the tasks that sample the peer connectivity into the metrics history.
+    #[cfg(feature = "metrics")]
+    fn spawn_metrics_history(&self) {
+        use snarkos_node_bus::NodeEvent;
+        use tokio::sync::broadcast::error::RecvError;
+
+        // Count the connections and disconnections in between the samples.
+        let history = self.metrics_history.clone();
+        let mut events = self.event_bus.subscribe();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(NodeEvent::PeerConnected { .. }) => history.record_connect(),
+                    Ok(NodeEvent::PeerDisconnected { .. }) => history.record_disconnect(),
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }));
+
+        // Periodically sample the peer connectivity.
+        let rest = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(std::time::Duration::from_secs(metrics::HISTORY_SAMPLE_INTERVAL_IN_SECS)).await;
+                rest.metrics_history.record(rest.sample_peers());
+            }
+        }));
+    }
+
+    /// Returns a sample of the current peer connectivity.
+    #[cfg(feature = "metrics")]
+    fn sample_peers(&self) -> PeerSample {
+        use snarkos_node_router::messages::NodeType;
+
+        // Count the connected peers by node type.
+        let (mut clients, mut provers, mut validators) = (0, 0, 0);
+        for peer in self.routing.router().get_connected_peers() {
+            match peer.node_type() {
+                NodeType::Client => clients += 1,
+                NodeType::Prover => provers += 1,
+                NodeType::Validator => validators += 1,
+            }
+        }
+        // If the node is a validator, compute the stake of the connected committee members, including itself.
+        let stake = self.consensus.as_ref().and_then(|consensus| {
+            let committee = self.ledger.latest_committee().ok()?;
+            let gateway = consensus.bft().primary().gateway();
+            let mut connected_validators = gateway.connected_addresses();
+            connected_validators.insert(gateway.account().address());
+            let connected_stake: u64 = connected_validators.iter().map(|address| committee.get_stake(*address)).sum();
+            Some((connected_stake, committee.total_stake()))
+        });
+
+        PeerSample {
+            timestamp: ::time::OffsetDateTime::now_utc().unix_timestamp(),
+            clients,
+            provers,
+            validators,
+            connected_stake: stake.map(|(connected_stake, _)| connected_stake),
+            total_stake: stake.map(|(_, total_stake)| total_stake),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the routes of the given version of the API.
+    fn routes(version: ApiVersion) -> axum::Router<Self> {
+        let mut router = axum::Router::new()
 
             // All the endpoints before the call to `route_layer` are protected with JWT auth.
             .route("/testnet3/node/address", get(Self::get_node_address))
-            .route_layer(middleware::from_fn(auth_middleware))
-
-            // ----------------- DEPRECATED ROUTES -----------------
-            // The following `GET ../latest/..` routes will be removed before mainnet.
-            // Please refer to the recommended routes for each endpoint:
-
-            // Deprecated: use `/testnet3/block/height/latest` instead.
-            .route("/testnet3/latest/height", get(Self::latest_height))
-            // Deprecated: use `/testnet3/block/hash/latest` instead.
-            .route("/testnet3/latest/hash", get(Self::latest_hash))
-            // Deprecated: use `/testnet3/latest/block/height` instead.
-            .route("/testnet3/latest/block", get(Self::latest_block))
-            // Deprecated: use `/testnet3/stateRoot/latest` instead.
-            .route("/testnet3/latest/stateRoot", get(Self::latest_state_root))
-            // Deprecated: use `/testnet3/committee/latest` instead.
-            .route("/testnet3/latest/committee", get(Self::latest_committee))
-            // ------------------------------------------------------
+            .route("/testnet3/peers/audit", get(Self::get_peers_audit))
+            .route("/testnet3/admin/bft/status", get(Self::get_bft_status))
+            .route("/testnet3/admin/bft/pause", post(Self::bft_pause))
+            .route("/testnet3/admin/bft/resume", post(Self::bft_resume))
+            .route("/testnet3/admin/mempool/export", get(Self::mempool_export))
+            .route("/testnet3/admin/rest/rebind", post(Self::rest_rebind))
+            .route("/testnet3/admin/journal", get(Self::get_action_journal))
+            .route("/testnet3/admin/debug-bundle", get(Self::debug_bundle))
+            .route("/testnet3/stream/transactions", get(Self::stream_confirmed_transactions))
+            .route("/testnet3/stream/programs", get(Self::stream_program_ids))
+            .route("/testnet3/stream/serialNumbers", get(Self::stream_serial_numbers))
+            // Allow a mempool snapshot to exceed the default body limit.
+            .route(
+                "/testnet3/admin/mempool/import",
+                post(Self::mempool_import).layer(DefaultBodyLimit::max(MAX_MEMPOOL_SNAPSHOT_SIZE)),
+            )
+            .route_layer(middleware::from_fn(auth_middleware));
+
+        // ----------------- DEPRECATED ROUTES -----------------
+        // The following `GET ../latest/..` routes will be removed before mainnet.
+        // Please refer to the recommended routes for each endpoint:
+        if version.supports(ApiFeature::LatestRoutes) {
+            router = router
+                // Deprecated: use `/testnet3/block/height/latest` instead.
+                .route("/testnet3/latest/height", get(Self::latest_height))
+                // Deprecated: use `/testnet3/block/hash/latest` instead.
+                .route("/testnet3/latest/hash", get(Self::latest_hash))
+                // Deprecated: use `/testnet3/latest/block/height` instead.
+                .route("/testnet3/latest/block", get(Self::latest_block))
+                // Deprecated: use `/testnet3/stateRoot/latest` instead.
+                .route("/testnet3/latest/stateRoot", get(Self::latest_state_root))
+                // Deprecated: use `/testnet3/committee/latest` instead.
+                .route("/testnet3/latest/committee", get(Self::latest_committee));
+        }
+        // ------------------------------------------------------
 
+        router = router
             // GET ../block/..
             .route("/testnet3/block/height/latest", get(Self::get_block_height_latest))
             .route("/testnet3/block/hash/latest", get(Self::get_block_hash_latest))
@@ -148,11 +703,14 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             // The path param here is actually only the height, but the name must match the route
             // above, otherwise there'll be a conflict at runtime.
             .route("/testnet3/block/:height_or_hash/transactions", get(Self::get_block_transactions))
+            .route("/testnet3/block/:height_or_hash/rejected", get(Self::get_block_rejected))
 
             // GET and POST ../transaction/..
             .route("/testnet3/transaction/:id", get(Self::get_transaction))
             .route("/testnet3/transaction/confirmed/:id", get(Self::get_confirmed_transaction))
+            .route("/testnet3/transaction/status/:id", get(Self::get_transaction_status))
             .route("/testnet3/transaction/broadcast", post(Self::transaction_broadcast))
+            .route("/testnet3/transaction/broadcast/batch", post(Self::transaction_broadcast_batch))
 
             // POST ../solution/broadcast
             // .route("/testnet3/solution/broadcast", post(Self::solution_broadcast))
@@ -168,13 +726,24 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .route("/testnet3/peers/all", get(Self::get_peers_all))
             .route("/testnet3/peers/all/metrics", get(Self::get_peers_all_metrics))
 
+            // GET ../network/apis
+            .route("/testnet3/network/apis", get(Self::get_network_apis))
+            .route("/testnet3/network/health", get(Self::get_network_health))
+
+            // GET ../node/attest?nonce={nonce}
+            .route("/testnet3/node/attest", get(Self::get_node_attestation))
+            // GET ../node/status
+            .route("/testnet3/node/status", get(Self::get_node_status))
+
             // GET ../program/..
             .route("/testnet3/program/:id", get(Self::get_program))
+            .route("/testnet3/program/:id/meta", get(Self::get_program_metadata))
             .route("/testnet3/program/:id/mappings", get(Self::get_mapping_names))
             .route("/testnet3/program/:id/mapping/:name/:key", get(Self::get_mapping_value))
 
             // GET misc endpoints.
             .route("/testnet3/blocks", get(Self::get_blocks))
+            .route("/testnet3/programs", get(Self::get_programs))
             .route("/testnet3/height/:hash", get(Self::get_height))
             .route("/testnet3/memoryPool/transmissions", get(Self::get_memory_pool_transmissions))
             // .route("/testnet3/memoryPool/solutions", get(Self::get_memory_pool_solutions))
@@ -182,30 +751,97 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .route("/testnet3/statePath/:commitment", get(Self::get_state_path_for_commitment))
             .route("/testnet3/stateRoot/latest", get(Self::get_state_root_latest))
             .route("/testnet3/committee/latest", get(Self::get_committee_latest))
+            .route("/testnet3/committee/proof/:height", get(Self::get_committee_proof))
+            .route("/testnet3/checkpoint/latest", get(Self::get_checkpoint_latest))
+            .route("/testnet3/checkpoint/:height", get(Self::get_checkpoint))
+            .route("/testnet3/upgrades/readiness", get(Self::get_upgrade_readiness))
+            .route("/testnet3/validators/:address/rewards", get(Self::get_validator_rewards))
+            .route("/testnet3/validators/:address/endpoint", get(Self::get_validator_endpoint))
+            .route("/testnet3/validators/endpoints", get(Self::get_validator_endpoints))
+            .route("/testnet3/fee/suggestion", get(Self::get_fee_suggestion))
+            .route("/testnet3/wallet/context", post(Self::get_wallet_context))
+            .route("/testnet3/inputs/status", post(Self::get_input_status))
+            .route(
+                "/testnet3/address/:address/balance",
+                get(Self::get_address_balance).post(Self::get_address_balance_private),
+            )
 
-            // Pass in `Rest` to make things convenient.
-            .with_state(self.clone())
-            // Enable tower-http tracing.
-            .layer(TraceLayer::new_for_http())
-            // Custom logging.
-            .layer(middleware::from_fn(log_middleware))
-            // Enable CORS.
-            .layer(cors)
-            // Cap body size at 10MB.
-            .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
-            .layer(GovernorLayer {
-                // We can leak this because it is created only once and it persists.
-                config: Box::leak(governor_config),
-            })
-        };
+            // GET ../events (websocket)
+            .route("/testnet3/events", get(Self::get_events))
+            // GET ../events/transactions?program={programID}&function={functionName} (websocket)
+            .route("/testnet3/events/transactions", get(Self::get_transaction_events));
 
-        let rest_listener = TcpListener::bind(rest_ip).await.unwrap();
-        self.handles.lock().push(tokio::spawn(async move {
-            axum::serve(rest_listener, router.into_make_service_with_connect_info::<SocketAddr>())
-                .await
-                .expect("couldn't start rest server");
-        }))
+        // POST ../faucet
+        #[cfg(feature = "faucet")]
+        {
+            router = router.route("/testnet3/faucet", post(Self::faucet_request));
+        }
+        // POST ../execute
+        // POST ../execute/estimate
+        #[cfg(feature = "execute")]
+        {
+            router = router.route("/testnet3/execute", post(Self::execute));
+            router = router.route("/testnet3/execute/estimate", post(Self::execute_estimate));
+        }
+        // GET ../node/metrics/history?since={timestamp}
+        #[cfg(feature = "metrics")]
+        {
+            router = router.route("/testnet3/node/metrics/history", get(Self::get_metrics_history));
+        }
+        // POST ../program/deploy
+        if version.supports(ApiFeature::ProgramDeploy) {
+            router = router.route("/testnet3/program/deploy", post(Self::program_deploy));
+        }
+        // GET ../consensus/round/{round}
+        if version.supports(ApiFeature::ConsensusOutput) {
+            router = router.route("/testnet3/consensus/round/:round", get(Self::get_consensus_output));
+        }
+        router
+    }
+}
+
+/// The routes that write to the network, which are refused by read-only nodes, along with their sub-routes.
+const WRITE_ROUTES: [&str; 5] =
+    ["transaction/broadcast", "program/deploy", "faucet", "execute", "admin/mempool/import"];
+
+/// The sub-routes of the write routes that only read, which remain available on read-only nodes.
+const READ_ONLY_SUB_ROUTES: [&str; 1] = ["execute/estimate"];
+
+/// Returns `true` if the given request path is a write route, or one of its sub-routes.
+fn is_write_route(path: &str) -> bool {
+    let Some((_, route)) = path.split_once("/testnet3/") else {
+        return false;
+    };
+    let route = route.trim_end_matches('/');
+    let is_sub_route = |write_route: &&str| {
+        route.strip_prefix(*write_route).map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+    };
+    WRITE_ROUTES.iter().any(is_sub_route) && !READ_ONLY_SUB_ROUTES.contains(&route)
+}
+
+/// Refuses the routes that write to the network, if the node is read-only.
+async fn read_only_middleware(
+    State(is_read_only): State<Arc<AtomicBool>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let is_write_route = is_write_route(request.uri().path());
+    if is_write_route && is_read_only.load(Ordering::Relaxed) {
+        return (StatusCode::FORBIDDEN, "This node is read-only").into_response();
     }
+    next.run(request).await
+}
+
+/// Runs the request in a span with its correlation ID, so that an operator can follow a request, and the transaction
+/// it submits, through the log lines of the node. The correlation ID is returned in the response header.
+async fn correlation_middleware(request: Request<Body>, next: Next) -> Response {
+    let correlation_id = correlation_id(request.headers());
+    let span = info_span!("request", correlation_id = %correlation_id);
+    let mut response = next.run(request).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+        response.headers_mut().insert(CORRELATION_ID_HEADER, value);
+    }
+    response
 }
 
 async fn log_middleware(
@@ -217,3 +853,28 @@ async fn log_middleware(
 
     Ok(next.run(request).await)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_write_route() {
+        // Ensure the write routes, and their sub-routes, are refused by read-only nodes.
+        assert!(is_write_route("/testnet3/transaction/broadcast"));
+        assert!(is_write_route("/testnet3/transaction/broadcast/"));
+        assert!(is_write_route("/testnet3/transaction/broadcast/batch"));
+        assert!(is_write_route("/testnet3/program/deploy"));
+        assert!(is_write_route("/testnet3/faucet"));
+        assert!(is_write_route("/testnet3/execute"));
+        assert!(is_write_route("/testnet3/admin/mempool/import"));
+
+        // Ensure the other routes remain available.
+        assert!(!is_write_route("/testnet3/execute/estimate"));
+        assert!(!is_write_route("/testnet3/executed"));
+        assert!(!is_write_route("/testnet3/transaction/at1abc"));
+        assert!(!is_write_route("/testnet3/admin/mempool/export"));
+        assert!(!is_write_route("/testnet3/block/height/latest"));
+        assert!(!is_write_route("/transaction/broadcast"));
+    }
+}
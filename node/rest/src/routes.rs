@@ -13,16 +13,49 @@
 // limitations under the License.
 
 use super::*;
-use snarkos_node_router::messages::UnconfirmedSolution;
+use snarkos_node_bft::helpers::ProposalHold;
+use snarkos_node_bft_ledger_service::committee_lookback_round;
+use snarkos_node_bus::NodeEvent;
+use snarkos_node_router::{
+    messages::{CommitteeProof, DataTransactions, NodeType, UnconfirmedSolution},
+    Router,
+};
 use snarkvm::{
     ledger::coinbase::ProverSolution,
-    prelude::{block::Transaction, Identifier, Plaintext},
+    prelude::{
+        block::Transaction,
+        Address,
+        Identifier,
+        Literal,
+        Plaintext,
+        Record,
+        Signature,
+        ToBytes,
+        Value,
+        ViewKey,
+    },
 };
 
+use axum::extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade};
+use core::str::FromStr;
 use indexmap::IndexMap;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use tokio::sync::{broadcast, OwnedSemaphorePermit};
+
+/// The maximum number of scans for the records of a view key that run concurrently.
+pub(crate) const MAX_CONCURRENT_RECORD_SCANS: usize = 2;
+/// The maximum number of seconds for which a request awaits the scan for the records of a view key.
+const RECORD_SCAN_TIMEOUT_IN_SECS: u64 = 30;
+/// The number of blocks that are read in each batch of a streamed response.
+const STREAM_BATCH_BLOCKS: u32 = 16;
+/// The maximum number of broadcast batches whose transactions are checked concurrently, on nodes without consensus.
+pub(crate) const MAX_CONCURRENT_BATCH_CHECKS: usize = 2;
+/// The maximum number of seconds for which a request awaits the checks of a broadcast batch.
+const BATCH_CHECK_TIMEOUT_IN_SECS: u64 = 30;
 
 /// The `get_blocks` query object.
 #[derive(Deserialize, Serialize)]
@@ -39,6 +72,119 @@ pub(crate) struct Metadata {
     metadata: bool,
 }
 
+/// The `get_programs` query object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct ProgramsQuery {
+    /// The page of programs to return, starting from 0.
+    #[serde(default)]
+    page: usize,
+}
+
+/// The `get_peers_audit` query object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct AuditQuery {
+    /// The maximum number of entries to return.
+    #[serde(default = "AuditQuery::default_limit")]
+    limit: usize,
+}
+
+impl AuditQuery {
+    /// The maximum number of entries that can be returned per call.
+    const MAXIMUM_LIMIT: usize = 1024;
+
+    /// Returns the default maximum number of entries to return.
+    const fn default_limit() -> usize {
+        100
+    }
+}
+
+/// The `get_metrics_history` query object.
+#[cfg(feature = "metrics")]
+#[derive(Deserialize, Serialize)]
+pub(crate) struct HistoryQuery {
+    /// The UNIX timestamp, in seconds, of the oldest sample to return.
+    #[serde(default)]
+    since: i64,
+}
+
+/// The `get_node_attestation` query object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct AttestationQuery {
+    /// The caller-supplied nonce, which is signed by the node account.
+    nonce: String,
+}
+
+impl AttestationQuery {
+    /// The maximum length of the nonce, in bytes.
+    const MAXIMUM_NONCE_LENGTH: usize = 256;
+    /// The domain of the signed attestations, which keeps them distinct from the other messages signed by the node.
+    const DOMAIN: &'static [u8] = b"snarkos-attestation";
+
+    /// Returns the signed message, which is the domain, the network ID, and the SHA-256 hash of the nonce.
+    fn message<N: Network>(&self) -> Vec<u8> {
+        let nonce_hash = Sha256::digest(self.nonce.as_bytes());
+        [Self::DOMAIN, &N::ID.to_le_bytes()[..], &nonce_hash[..]].concat()
+    }
+}
+
+/// The `rest_rebind` request object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct RebindRequest {
+    /// The address to listen on.
+    ip: SocketAddr,
+    /// The rate limit per IP, in requests per second, or the current rate limit if it is not given.
+    rps: Option<u32>,
+}
+
+/// The `get_address_balance_private` request object.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+pub(crate) struct BalanceRequest<N: Network> {
+    /// The view key of the account, which is used to find its unspent records.
+    view_key: ViewKey<N>,
+}
+
+/// The `get_wallet_context` request object.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+pub(crate) struct WalletContextRequest<N: Network> {
+    /// The view key of the account, which is used to find its unspent records.
+    view_key: ViewKey<N>,
+    /// The minimum value of a selected record, in microcredits.
+    #[serde(default)]
+    min_microcredits: u64,
+    /// The maximum number of selected records.
+    #[serde(default = "WalletContextRequest::<N>::default_max_records")]
+    max_records: usize,
+}
+
+/// The `get_input_status` request object.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+pub(crate) struct InputStatusRequest<N: Network> {
+    /// The serial numbers of the records to check.
+    #[serde(default)]
+    serial_numbers: Vec<Field<N>>,
+    /// The tags of the records to check, which the owner derives from the graph key and the record commitments.
+    #[serde(default)]
+    tags: Vec<Field<N>>,
+}
+
+impl<N: Network> InputStatusRequest<N> {
+    /// The maximum number of serial numbers and commitments that can be checked per call.
+    const MAX_INPUTS: usize = 256;
+}
+
+impl<N: Network> WalletContextRequest<N> {
+    /// The maximum number of records that can be selected per call.
+    const MAX_RECORDS: usize = 128;
+
+    /// Returns the default maximum number of selected records.
+    const fn default_max_records() -> usize {
+        16
+    }
+}
+
 #[allow(dead_code)]
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // ----------------- DEPRECATED FUNCTIONS -----------------
@@ -100,17 +246,19 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     ) -> Result<ErasedJson, RestError> {
         // Manually parse the height or the height or the hash, axum doesn't support different types
         // for the same path param.
-        let block = if let Ok(height) = height_or_hash.parse::<u32>() {
-            rest.ledger.get_block(height)?
+        let height = if let Ok(height) = height_or_hash.parse::<u32>() {
+            height
         } else {
             let hash = height_or_hash
                 .parse::<N::BlockHash>()
                 .map_err(|_| RestError("invalid input, it is neither a block height nor a block hash".to_string()))?;
 
-            rest.ledger.get_block_by_hash(&hash)?
+            rest.ledger.get_height(&hash)?
         };
+        // Retrieve the block from the block cache, or the ledger.
+        let block = rest.routing.router().block_cache().get_or_load(height, |height| rest.ledger.get_block(height))?;
 
-        Ok(ErasedJson::pretty(block))
+        Ok(ErasedJson::pretty(&*block))
     }
 
     // GET /testnet3/blocks?start={start_height}&end={end_height}
@@ -136,11 +284,70 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             )));
         }
 
+        // Retrieve the blocks from the block cache, or the ledger.
+        let block_cache = rest.routing.router().block_cache();
         let blocks = cfg_into_iter!((start_height..end_height))
-            .map(|height| rest.ledger.get_block(height))
+            .map(|height| block_cache.get_or_load(height, |height| rest.ledger.get_block(height)))
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(ErasedJson::pretty(blocks))
+        Ok(ErasedJson::pretty(blocks.iter().map(|block| &**block).collect::<Vec<_>>()))
+    }
+
+    /// Returns a permit to serve a streamed response, or an error if too many streams are served already.
+    fn stream_permit(&self) -> Result<OwnedSemaphorePermit, RestError> {
+        self.streams
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| RestError("The node is busy with other streams, try again later".to_string()))
+    }
+
+    // GET /testnet3/stream/transactions?start={start_height}&end={end_height}
+    pub(crate) async fn stream_confirmed_transactions(
+        State(rest): State<Self>,
+        Query(block_range): Query<BlockRange>,
+    ) -> Result<Response, RestError> {
+        // Ensure the end height is greater than the start height.
+        if block_range.start > block_range.end {
+            return Err(RestError("Invalid block range".to_string()));
+        }
+        // Bound the block range by the latest block, as the range of a stream is not limited otherwise.
+        let start_height = block_range.start;
+        let end_height = block_range.end.min(rest.ledger.latest_height().saturating_add(1));
+
+        let ledger = rest.ledger.clone();
+        Ok(stream_ndjson(rest.stream_permit()?, start_height, move |start| {
+            let end = start.saturating_add(STREAM_BATCH_BLOCKS).min(end_height);
+            let mut transactions = Vec::new();
+            for height in start..end {
+                transactions.extend(ledger.get_transactions(height)?.iter().cloned());
+            }
+            Ok((transactions, (end < end_height).then_some(end)))
+        }))
+    }
+
+    // GET /testnet3/stream/programs
+    pub(crate) async fn stream_program_ids(State(rest): State<Self>) -> Result<Response, RestError> {
+        let ledger = rest.ledger.clone();
+        // Note: The program IDs are few enough to be read in a single batch.
+        Ok(stream_ndjson(rest.stream_permit()?, (), move |()| {
+            let program_ids = ledger.vm().transaction_store().program_ids().map(|id| *id).collect::<Vec<_>>();
+            Ok((program_ids, None))
+        }))
+    }
+
+    // GET /testnet3/stream/serialNumbers
+    pub(crate) async fn stream_serial_numbers(State(rest): State<Self>) -> Result<Response, RestError> {
+        // Read the serial numbers block by block, as the store cannot resume an iteration in between the batches.
+        let ledger = rest.ledger.clone();
+        let end_height = rest.ledger.latest_height().saturating_add(1);
+        Ok(stream_ndjson(rest.stream_permit()?, 0, move |start: u32| {
+            let end = start.saturating_add(STREAM_BATCH_BLOCKS).min(end_height);
+            let mut serial_numbers = Vec::new();
+            for height in start..end {
+                serial_numbers.extend(ledger.get_transactions(height)?.serial_numbers().copied());
+            }
+            Ok((serial_numbers, (end < end_height).then_some(end)))
+        }))
     }
 
     // GET /testnet3/height/{blockHash}
@@ -159,6 +366,17 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.get_transactions(height)?))
     }
 
+    // GET /testnet3/block/{height}/rejected
+    pub(crate) async fn get_block_rejected(
+        State(rest): State<Self>,
+        Path(height): Path<u32>,
+    ) -> Result<ErasedJson, RestError> {
+        // Derive the rejections from the block, so that any node with the block can serve them.
+        let block =
+            tokio::task::spawn_blocking(move || rest.ledger.get_block(height)).await.map_err(anyhow::Error::from)??;
+        Ok(ErasedJson::pretty(BlockRejections::new(&block)))
+    }
+
     // GET /testnet3/transaction/{transactionID}
     pub(crate) async fn get_transaction(
         State(rest): State<Self>,
@@ -175,6 +393,33 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.get_confirmed_transaction(tx_id)?))
     }
 
+    // GET /testnet3/transaction/status/{transactionID}
+    pub(crate) async fn get_transaction_status(
+        State(rest): State<Self>,
+        Path(tx_id): Path<N::TransactionID>,
+    ) -> Result<ErasedJson, RestError> {
+        // Check if the transaction is in the ledger.
+        if let Some(block_hash) = rest.ledger.find_block_hash(&tx_id)? {
+            return Ok(ErasedJson::pretty(json!({ "status": "confirmed", "block_hash": block_hash })));
+        }
+        // Check if the transaction is in the memory pool, or was replaced by a higher-fee transaction.
+        if let Some(consensus) = &rest.consensus {
+            if let Some(superseded_by) = consensus.superseded_by(&tx_id) {
+                return Ok(ErasedJson::pretty(json!({ "status": "superseded", "superseded_by": superseded_by })));
+            }
+            if consensus.contains_unconfirmed_transaction(&tx_id) {
+                return Ok(ErasedJson::pretty(json!({ "status": "pending" })));
+            }
+        }
+        // Check if the transaction was rejected by one of the recent blocks.
+        if let Some((block_height, reason)) = rest.rejections.find(&tx_id) {
+            return Ok(ErasedJson::pretty(
+                json!({ "status": "rejected", "block_height": block_height, "reason": reason }),
+            ));
+        }
+        Ok(ErasedJson::pretty(json!({ "status": "unknown" })))
+    }
+
     // GET /testnet3/memoryPool/transmissions
     pub(crate) async fn get_memory_pool_transmissions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
         match rest.consensus {
@@ -201,6 +446,107 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         }
     }
 
+    // GET /testnet3/consensus/round/{round}
+    pub(crate) async fn get_consensus_output(
+        State(rest): State<Self>,
+        Path(round): Path<u64>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => match consensus.archive().get(round)? {
+                Some(output) => Ok(ErasedJson::pretty(output)),
+                None => Err(RestError(format!("No consensus output was archived for round {round}"))),
+            },
+            None => Err(RestError("Route isn't available for this node type".to_string())),
+        }
+    }
+
+    // GET /testnet3/events
+    pub(crate) async fn get_events(State(rest): State<Self>, websocket: WebSocketUpgrade) -> Response {
+        // Subscribe before the upgrade, so that no events are missed in the meantime.
+        let events = rest.event_bus.subscribe();
+        websocket.on_upgrade(move |socket| Self::stream_events(socket, events))
+    }
+
+    /// Streams the given node events to the websocket, as JSON, until the websocket is closed.
+    async fn stream_events(mut socket: WebSocket, mut events: broadcast::Receiver<NodeEvent<N>>) {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                // If the subscriber fell behind, skip the missed events.
+                Err(broadcast::error::RecvError::Lagged(num_missed)) => {
+                    debug!("A websocket subscriber missed {num_missed} node events");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let Ok(event) = serde_json::to_string(&event) else {
+                continue;
+            };
+            if socket.send(WsMessage::Text(event)).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    // GET /testnet3/events/transactions?program={programID}&function={functionName}
+    pub(crate) async fn get_transaction_events(
+        State(rest): State<Self>,
+        Query(filter): Query<TransactionFilter<N>>,
+        websocket: WebSocketUpgrade,
+    ) -> Response {
+        // Subscribe before the upgrade, so that no blocks are missed in the meantime.
+        let events = rest.event_bus.subscribe();
+        let next_height = rest.ledger.latest_height().saturating_add(1);
+        websocket.on_upgrade(move |socket| rest.stream_transactions(socket, events, next_height, filter))
+    }
+
+    /// Streams the confirmed transactions that match the given filter to the websocket, as JSON,
+    /// until the websocket is closed, starting from the block at the given height.
+    ///
+    /// The transactions are filtered as each block is added to the ledger. The blocks are read from the ledger,
+    /// so that any blocks that were skipped by a lagging receiver are streamed as well.
+    async fn stream_transactions(
+        self,
+        mut socket: WebSocket,
+        mut events: broadcast::Receiver<NodeEvent<N>>,
+        mut next_height: u32,
+        filter: TransactionFilter<N>,
+    ) {
+        loop {
+            let latest_height = match events.recv().await {
+                Ok(NodeEvent::BlockAdvanced { height, .. }) => height,
+                Ok(_) => continue,
+                // If the subscriber fell behind, catch up to the latest block in the ledger.
+                Err(broadcast::error::RecvError::Lagged(num_missed)) => {
+                    debug!("A transaction subscriber missed {num_missed} node events");
+                    self.ledger.latest_height()
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            // Stream the blocks that were added since the last event, or the new block after a rollback.
+            for height in next_height.min(latest_height)..=latest_height {
+                // Retrieve the confirmed transactions of the block.
+                let ledger = self.ledger.clone();
+                let transactions = match tokio::task::spawn_blocking(move || ledger.get_transactions(height)).await {
+                    Ok(Ok(transactions)) => transactions,
+                    Ok(Err(error)) => {
+                        debug!("Failed to retrieve the transactions of block {height} for a subscriber - {error}");
+                        continue;
+                    }
+                    Err(_) => continue,
+                };
+                // Send the matching transactions.
+                for transaction in transactions.iter().filter(|confirmed| filter.matches(confirmed.transaction())) {
+                    let message = json!({ "height": height, "transaction": transaction });
+                    if socket.send(WsMessage::Text(message.to_string())).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            next_height = latest_height.saturating_add(1);
+        }
+    }
+
     // GET /testnet3/program/{programID}
     pub(crate) async fn get_program(
         State(rest): State<Self>,
@@ -209,6 +555,38 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.get_program(id)?))
     }
 
+    // GET /testnet3/program/{programID}/meta
+    pub(crate) async fn get_program_metadata(
+        State(rest): State<Self>,
+        Path(id): Path<ProgramID<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let ledger = rest.ledger.clone();
+        let metadata = tokio::task::spawn_blocking(move || DeploymentMetadata::get(&ledger, &id))
+            .await
+            .map_err(anyhow::Error::from)??;
+        match metadata {
+            Some(metadata) => Ok(ErasedJson::pretty(metadata)),
+            None => Err(RestError(format!("Program '{id}' was not deployed by a transaction"))),
+        }
+    }
+
+    // GET /testnet3/programs?page={page}
+    pub(crate) async fn get_programs(
+        State(rest): State<Self>,
+        Query(query): Query<ProgramsQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        let ledger = rest.ledger.clone();
+        let (total, programs) = tokio::task::spawn_blocking(move || DeploymentMetadata::page(&ledger, query.page))
+            .await
+            .map_err(anyhow::Error::from)??;
+        Ok(ErasedJson::pretty(json!({
+            "page": query.page,
+            "page_size": DeploymentMetadata::<N>::PAGE_SIZE,
+            "total": total,
+            "programs": programs,
+        })))
+    }
+
     // GET /testnet3/program/{programID}/mappings
     pub(crate) async fn get_mapping_names(
         State(rest): State<Self>,
@@ -257,6 +635,328 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.latest_committee()?))
     }
 
+    // GET /testnet3/committee/proof/{height}
+    pub(crate) async fn get_committee_proof(
+        State(rest): State<Self>,
+        Path(height): Path<u32>,
+    ) -> Result<ErasedJson, RestError> {
+        let block = rest.ledger.get_block(height)?;
+        // Retrieve the committee lookback of the leader round, which certified the block.
+        let lookback_round = committee_lookback_round::<N>(block.round());
+        let Some(committee) = rest.ledger.get_committee_for_round(lookback_round)? else {
+            return Err(RestError(format!("No committee found for round {lookback_round} in the ledger")));
+        };
+        Ok(ErasedJson::pretty(CommitteeProof::new(&block, committee)?))
+    }
+
+    // GET /testnet3/checkpoint/latest
+    pub(crate) async fn get_checkpoint_latest(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let Some(consensus) = &rest.consensus else {
+            return Err(RestError("Route isn't available for this node type".to_string()));
+        };
+        Ok(ErasedJson::pretty(consensus.bft().primary().sync().checkpoints().latest()))
+    }
+
+    // GET /testnet3/checkpoint/{height}
+    pub(crate) async fn get_checkpoint(
+        State(rest): State<Self>,
+        Path(height): Path<u32>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(consensus) = &rest.consensus else {
+            return Err(RestError("Route isn't available for this node type".to_string()));
+        };
+        match consensus.bft().primary().sync().checkpoints().get(height) {
+            Some(certificate) => Ok(ErasedJson::pretty(certificate)),
+            None => Err(RestError(format!("No checkpoint certificate found for block {height}"))),
+        }
+    }
+
+    // GET /testnet3/upgrades/readiness
+    pub(crate) async fn get_upgrade_readiness(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let Some(consensus) = &rest.consensus else {
+            return Err(RestError("Route isn't available for this node type".to_string()));
+        };
+        let tracker = consensus.bft().primary().upgrades();
+        let committee = rest.ledger.latest_committee()?;
+        let upgrades = tracker
+            .upgrades()
+            .into_iter()
+            .map(|upgrade| {
+                let readiness = tracker.readiness(upgrade, &committee);
+                json!({
+                    "version": upgrade.version,
+                    "activation_height": upgrade.activation_height,
+                    "signers": readiness.signers,
+                    "signaled_stake": readiness.signaled_stake,
+                    "total_stake": readiness.total_stake,
+                    "quorum_threshold": readiness.quorum_threshold,
+                    "is_ready": readiness.is_ready(),
+                    "decision": readiness.decision.map(|decision| decision.to_string()),
+                    "activated_at": readiness.activated_at(),
+                })
+            })
+            .collect::<Vec<_>>();
+        let local = tracker.local().map(|upgrade| {
+            json!({ "version": upgrade.version, "activation_height": upgrade.activation_height })
+        });
+        Ok(ErasedJson::pretty(json!({ "local": local, "upgrades": upgrades })))
+    }
+
+    // GET /testnet3/validators/{address}/rewards
+    pub(crate) async fn get_validator_rewards(
+        State(rest): State<Self>,
+        Path(address): Path<Address<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(tracker) = rest.rewards.get() else {
+            return Err(RestError("The rewards tracker is not enabled on this node".to_string()));
+        };
+        let (accounted_height, rewards) = tracker.get(&address);
+        Ok(ErasedJson::pretty(json!({
+            "address": address,
+            "accounted_height": accounted_height,
+            "rewards": rewards,
+        })))
+    }
+
+    // GET /testnet3/validators/{address}/endpoint
+    pub(crate) async fn get_validator_endpoint(
+        State(rest): State<Self>,
+        Path(address): Path<Address<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Retrieve the latest known endpoints of the validator, as seen by the router.
+        let endpoint = rest.routing.router().resolve_validator(&address);
+        // Retrieve the BFT address of the validator, if this node is a validator that is connected to it.
+        let bft_ip = rest.consensus.as_ref().and_then(|consensus| {
+            consensus.bft().primary().gateway().resolver().get_peer_ip_for_address(address)
+        });
+        if endpoint.is_none() && bft_ip.is_none() {
+            return Err(RestError(format!("Validator '{address}' has not been seen by this node")));
+        }
+        Ok(ErasedJson::pretty(json!({ "address": address, "endpoint": endpoint, "bft_ip": bft_ip })))
+    }
+
+    // GET /testnet3/validators/endpoints
+    pub(crate) async fn get_validator_endpoints(State(rest): State<Self>) -> ErasedJson {
+        ErasedJson::pretty(rest.routing.router().validator_directory().endpoints())
+    }
+
+    // GET /testnet3/fee/suggestion
+    pub(crate) async fn get_fee_suggestion(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let ledger = rest.ledger.clone();
+        let priority_fee =
+            tokio::task::spawn_blocking(move || Self::suggested_priority_fee(&ledger, ledger.latest_height()))
+                .await
+                .map_err(anyhow::Error::from)??;
+        Ok(ErasedJson::pretty(priority_fee))
+    }
+
+    // POST /testnet3/wallet/context
+    // body: { "view_key": "AViewKey1...", "min_microcredits": 0, "max_records": 16 }
+    pub(crate) async fn get_wallet_context(
+        State(rest): State<Self>,
+        Json(request): Json<WalletContextRequest<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let max_records = request.max_records.min(WalletContextRequest::<N>::MAX_RECORDS);
+        let records = rest.find_unspent_credits_records(request.view_key).await?;
+        // Read the context after the records, so that every record is included in the returned state root.
+        let ledger = rest.ledger.clone();
+        let (height, hash, state_root, priority_fee) = tokio::task::spawn_blocking(move || {
+            let height = ledger.latest_height();
+            let priority_fee = Self::suggested_priority_fee(&ledger, height)?;
+            Ok::<_, RestError>((height, ledger.latest_hash(), ledger.latest_state_root(), priority_fee))
+        })
+        .await
+        .map_err(anyhow::Error::from)??;
+
+        // Select the largest records above the minimum value.
+        let mut records = records
+            .into_iter()
+            .filter_map(|(commitment, record)| {
+                let microcredits = record.microcredits().ok()?;
+                (microcredits >= request.min_microcredits).then_some((commitment, record, microcredits))
+            })
+            .collect::<Vec<_>>();
+        records.sort_unstable_by(|(_, _, a), (_, _, b)| b.cmp(a));
+        records.truncate(max_records);
+        let records = records
+            .into_iter()
+            .map(|(commitment, record, microcredits)| {
+                json!({ "commitment": commitment, "record": record, "microcredits": microcredits })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(ErasedJson::pretty(json!({
+            "height": height,
+            "hash": hash,
+            "state_root": state_root,
+            "suggested_priority_fee": priority_fee,
+            "records": records,
+        })))
+    }
+
+    // GET /testnet3/address/{address}/balance
+    pub(crate) async fn get_address_balance(
+        State(rest): State<Self>,
+        Path(address): Path<Address<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.account_balance(address, None).await?))
+    }
+
+    // POST /testnet3/address/{address}/balance
+    // body: { "view_key": "AViewKey1..." }
+    pub(crate) async fn get_address_balance_private(
+        State(rest): State<Self>,
+        Path(address): Path<Address<N>>,
+        Json(request): Json<BalanceRequest<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the view key belongs to the address, so that the private balance is not attributed to another account.
+        if request.view_key.to_address() != address {
+            return Err(RestError(format!("The view key does not belong to '{address}'")));
+        }
+        Ok(ErasedJson::pretty(rest.account_balance(address, Some(request.view_key)).await?))
+    }
+
+    /// Returns the unspent `credits.aleo` records of the given view key.
+    ///
+    /// The scan reads every record in the ledger, so it runs in the blocking pool, at most
+    /// `MAX_CONCURRENT_RECORD_SCANS` at once, and the request is refused while that many scans are running.
+    /// The request gives up on the scan after `RECORD_SCAN_TIMEOUT_IN_SECS`, while the scan holds its slot
+    /// until it completes.
+    async fn find_unspent_credits_records(
+        &self,
+        view_key: ViewKey<N>,
+    ) -> Result<IndexMap<Field<N>, Record<N, Plaintext<N>>>, RestError> {
+        let Ok(permit) = self.record_scans.clone().try_acquire_owned() else {
+            return Err(RestError("The node is busy with other record scans, try again later".to_string()));
+        };
+        let ledger = self.ledger.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            ledger.find_unspent_credits_records(&view_key)
+        });
+        let timeout = std::time::Duration::from_secs(RECORD_SCAN_TIMEOUT_IN_SECS);
+        match tokio::time::timeout(timeout, task).await {
+            Ok(result) => Ok(result.map_err(anyhow::Error::from)??),
+            Err(_) => Err(RestError(format!("The record scan did not complete within {RECORD_SCAN_TIMEOUT_IN_SECS}s"))),
+        }
+    }
+
+    /// Returns the finalized public balance of the given account in microcredits, and its private balance
+    /// if its view key is given, i.e. the sum of its unspent `credits.aleo` records, at the latest height.
+    async fn account_balance(
+        &self,
+        address: Address<N>,
+        view_key: Option<ViewKey<N>>,
+    ) -> Result<serde_json::Value, RestError> {
+        const MAX_ATTEMPTS: usize = 3;
+
+        // Sum the unspent records once, if the view key is given, as the scan reads every record in the ledger.
+        let private = match view_key {
+            Some(view_key) => {
+                let records = self.find_unspent_credits_records(view_key).await?;
+                let microcredits = records.iter().try_fold(0u64, |total, (_, record)| {
+                    Ok::<_, anyhow::Error>(total.saturating_add(record.microcredits()?))
+                })?;
+                Some((microcredits, records.len()))
+            }
+            None => None,
+        };
+
+        let credits = ProgramID::from_str("credits.aleo")?;
+        let account = Identifier::from_str("account")?;
+        let key = Plaintext::from(Literal::Address(address));
+        for _ in 0..MAX_ATTEMPTS {
+            let height = self.ledger.latest_height();
+            // Read the public balance from the `credits.aleo/account` mapping.
+            let public = match self.ledger.vm().finalize_store().get_value_confirmed(credits, account, &key)? {
+                Some(Value::Plaintext(Plaintext::Literal(Literal::U64(microcredits), _))) => *microcredits,
+                None => 0,
+                Some(value) => return Err(RestError(format!("The public balance of '{address}' is invalid - {value}"))),
+            };
+            // If a block was added in the meantime, the public balance may not match the height, so read it again.
+            if self.ledger.latest_height() != height {
+                continue;
+            }
+
+            let mut balance = json!({ "address": address, "height": height, "public": public });
+            if let Some((microcredits, num_records)) = private {
+                balance["private"] = json!(microcredits);
+                balance["records"] = json!(num_records);
+            }
+            return Ok(balance);
+        }
+        Err(RestError("The ledger advanced while reading the balance, please try again".to_string()))
+    }
+
+    // POST /testnet3/inputs/status
+    // body: { "serial_numbers": ["..field"], "tags": ["..field"] }
+    pub(crate) async fn get_input_status(
+        State(rest): State<Self>,
+        Json(request): Json<InputStatusRequest<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let num_inputs = request.serial_numbers.len() + request.tags.len();
+        if num_inputs > InputStatusRequest::<N>::MAX_INPUTS {
+            return Err(RestError(format!(
+                "Too many inputs - expected at most {}, found {num_inputs}",
+                InputStatusRequest::<N>::MAX_INPUTS
+            )));
+        }
+
+        let ledger = &rest.ledger;
+        let height = ledger.latest_height();
+        let consensus = rest.consensus.as_ref();
+
+        // Check whether each serial number is spent in the ledger, or by an unconfirmed transaction.
+        let mut inputs = Vec::with_capacity(num_inputs);
+        for serial_number in request.serial_numbers {
+            let pending = consensus.and_then(|consensus| consensus.pending_spender(&serial_number));
+            let status = match (ledger.contains_serial_number(&serial_number)?, pending) {
+                (true, _) => json!({ "serial_number": serial_number, "status": "spent" }),
+                (false, Some(transaction_id)) => {
+                    json!({ "serial_number": serial_number, "status": "pending", "transaction_id": transaction_id })
+                }
+                (false, None) => json!({ "serial_number": serial_number, "status": "unspent" }),
+            };
+            inputs.push(status);
+        }
+        // Check whether each tag is spent in the ledger, or by an unconfirmed transaction.
+        for tag in request.tags {
+            let pending = consensus.and_then(|consensus| consensus.pending_tag_spender(&tag));
+            let status = match (ledger.contains_tag(&tag)?, pending) {
+                (true, _) => json!({ "tag": tag, "status": "spent" }),
+                (false, Some(transaction_id)) => {
+                    json!({ "tag": tag, "status": "pending", "transaction_id": transaction_id })
+                }
+                (false, None) => json!({ "tag": tag, "status": "unspent" }),
+            };
+            inputs.push(status);
+        }
+
+        Ok(ErasedJson::pretty(json!({
+            "height": height,
+            // The memory pool is only checked by the nodes that run the BFT.
+            "mempool_checked": consensus.is_some(),
+            "inputs": inputs,
+        })))
+    }
+
+    /// Returns the median priority fee, in microcredits, of the transactions in the recent blocks.
+    /// This method is blocking, as it reads the recent blocks.
+    fn suggested_priority_fee(ledger: &Ledger<N, C>, height: u32) -> Result<u64, RestError> {
+        const NUM_BLOCKS: u32 = 10;
+
+        let mut priority_fees = Vec::new();
+        for height in height.saturating_sub(NUM_BLOCKS - 1)..=height {
+            for confirmed in ledger.get_transactions(height)?.iter() {
+                if let Some(fee) = confirmed.transaction().fee_transition() {
+                    priority_fees.push(*fee.priority_amount()?);
+                }
+            }
+        }
+        priority_fees.sort_unstable();
+        Ok(priority_fees.get(priority_fees.len() / 2).copied().unwrap_or(0))
+    }
+
     // GET /testnet3/peers/count
     pub(crate) async fn get_peers_count(State(rest): State<Self>) -> ErasedJson {
         ErasedJson::pretty(rest.routing.router().number_of_connected_peers())
@@ -272,11 +972,440 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         ErasedJson::pretty(rest.routing.router().connected_metrics())
     }
 
+    // GET /testnet3/network/apis
+    pub(crate) async fn get_network_apis(State(rest): State<Self>) -> ErasedJson {
+        // Retrieve the REST endpoints of the connected validators, and of this node, if it advertises one.
+        let router = rest.routing.router();
+        let mut rest_endpoints = router.connected_rest_endpoints();
+        rest_endpoints.extend(router.rest_endpoint());
+        ErasedJson::pretty(rest_endpoints)
+    }
+
+    // GET /testnet3/network/health
+    pub(crate) async fn get_network_health(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.network_health()?))
+    }
+
+    /// Returns the condition of the network, as observed by this node from the gossip and the committee.
+    fn network_health(&self) -> Result<NetworkHealth> {
+        let router = self.routing.router();
+        let now = ::time::OffsetDateTime::now_utc().unix_timestamp();
+        // Compare the height of the node with the median height reported by its peers.
+        let height = self.ledger.latest_height();
+        let peers = router.get_connected_peers();
+        let peer_heights = peers.iter().filter(|peer| !peer.is_prover()).map(|peer| peer.height()).collect();
+        let median_peer_height = median(peer_heights);
+        // Collect the connected committee members, including this node, if it runs the BFT.
+        let mut connected =
+            peers.iter().filter(|peer| peer.is_validator()).map(|peer| peer.address()).collect::<HashSet<_>>();
+        if let Some(consensus) = &self.consensus {
+            let gateway = consensus.bft().primary().gateway();
+            connected.extend(gateway.connected_addresses());
+            connected.insert(gateway.account().address());
+        }
+        let committee = self.ledger.latest_committee().ok().map(|committee| {
+            let members = committee.members().keys().filter(|address| connected.contains(*address)).collect::<Vec<_>>();
+            let connected_stake = members.iter().map(|address| committee.get_stake(**address)).sum::<u64>();
+            CommitteeHealth {
+                members: committee.num_members(),
+                connected: members.len(),
+                connected_fraction: members.len() as f64 / committee.num_members().max(1) as f64,
+                connected_stake_fraction: connected_stake as f64 / committee.total_stake().max(1) as f64,
+                has_quorum: connected_stake >= committee.quorum_threshold(),
+            }
+        });
+        // Report the block hash conflicts within the fork window, if the node runs the BFT.
+        let recent_forks = self.consensus.as_ref().map(|consensus| {
+            let forks = consensus.bft().primary().sync().forks();
+            forks
+                .since(now.saturating_sub(NetworkHealth::FORK_WINDOW_IN_SECS))
+                .into_iter()
+                .map(|(timestamp, conflict)| ForkSummary {
+                    timestamp,
+                    peer_ip: conflict.peer_ip,
+                    address: conflict.address.to_string(),
+                    height: conflict.height,
+                    expected_hash: conflict.expected_hash.to_string(),
+                    reported_hash: conflict.reported_hash.to_string(),
+                })
+                .collect()
+        });
+        // Average the interval between the recent blocks.
+        let latest_timestamp = self.ledger.latest_timestamp();
+        let average_block_interval_secs = match height.min(NetworkHealth::BLOCK_INTERVAL_WINDOW) {
+            0 => None,
+            window => {
+                let earliest_timestamp = self.ledger.get_header(height - window)?.timestamp();
+                Some(latest_timestamp.saturating_sub(earliest_timestamp) as f64 / window as f64)
+            }
+        };
+
+        let mut health = NetworkHealth {
+            condition: HealthCondition::Healthy,
+            reasons: vec![],
+            height,
+            median_peer_height,
+            committee,
+            recent_forks,
+            average_block_interval_secs,
+            seconds_since_latest_block: now.saturating_sub(latest_timestamp).max(0),
+            propagation: self.propagation.estimate(),
+        };
+        health.assess(N::BLOCK_TIME as i64, Router::<N>::MAXIMUM_HEIGHT_LAG);
+        Ok(health)
+    }
+
+    // GET /testnet3/node/metrics/history?since={timestamp}
+    #[cfg(feature = "metrics")]
+    pub(crate) async fn get_metrics_history(
+        State(rest): State<Self>,
+        Query(query): Query<HistoryQuery>,
+    ) -> ErasedJson {
+        ErasedJson::pretty(json!({
+            "interval_in_secs": metrics::HISTORY_SAMPLE_INTERVAL_IN_SECS,
+            "samples": rest.metrics_history.samples_since(query.since),
+        }))
+    }
+
+    // GET /testnet3/node/status
+    pub(crate) async fn get_node_status(State(rest): State<Self>) -> ErasedJson {
+        ErasedJson::pretty(rest.node_status())
+    }
+
+    /// Returns a summary of the state of this node.
+    fn node_status(&self) -> NodeStatus {
+        let router = self.routing.router();
+        // Count the connected peers by node type.
+        let mut peers = PeerCounts::default();
+        for peer in router.get_connected_peers() {
+            match peer.node_type() {
+                NodeType::Client => peers.clients += 1,
+                NodeType::Prover => peers.provers += 1,
+                NodeType::Validator => peers.validators += 1,
+            }
+        }
+        // Compare the height of the node with the heights reported by its peers.
+        let height = self.ledger.latest_height();
+        let highest_peer_height = router.highest_peer_height();
+        let is_synced = highest_peer_height
+            .map_or(true, |highest| height.saturating_add(Router::<N>::MAXIMUM_HEIGHT_LAG) >= highest);
+        // Summarize the memory pool and the BFT, if the node runs them.
+        let (memory_pool, bft) = match &self.consensus {
+            Some(consensus) => {
+                let priority_fees = consensus.unconfirmed_transactions().filter_map(|(_, transaction)| {
+                    let transaction = transaction.deserialize_blocking().ok()?;
+                    Some(transaction.fee_transition().and_then(|fee| fee.priority_amount().ok()).map_or(0, |fee| *fee))
+                });
+                let memory_pool = MemoryPoolStatus {
+                    transmissions: consensus.num_unconfirmed_transmissions(),
+                    priority_fees: MemoryPoolStatus::histogram(priority_fees),
+                };
+                let primary = consensus.bft().primary();
+                let bft = BftStatus {
+                    round: primary.current_round(),
+                    paused: primary.is_holding_proposals(ProposalHold::Paused),
+                    quorum_lost: primary.is_holding_proposals(ProposalHold::QuorumLost),
+                    batch_tuning: primary.batch_tuner().is_enabled(),
+                    batch_size: primary.batch_tuner().batch_size(),
+                    batch_delay_ms: primary.batch_tuner().batch_delay_ms(),
+                };
+                (Some(memory_pool), Some(bft))
+            }
+            None => (None, None),
+        };
+
+        NodeStatus {
+            node_type: router.node_type().to_string(),
+            height,
+            highest_peer_height,
+            is_synced,
+            peers,
+            memory_pool,
+            bft,
+            cdn_sync: self.cdn_sync.get().and_then(|progress| progress.status()),
+        }
+    }
+
+    // GET /testnet3/peers/audit?limit={limit}
+    pub(crate) async fn get_peers_audit(
+        State(rest): State<Self>,
+        Query(query): Query<AuditQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.routing.router().audit_log() {
+            Some(audit_log) => Ok(ErasedJson::pretty(audit_log.recent(query.limit.min(AuditQuery::MAXIMUM_LIMIT)))),
+            None => Err(RestError("The peer audit log is not enabled on this node".to_string())),
+        }
+    }
+
     // GET /testnet3/node/address
     pub(crate) async fn get_node_address(State(rest): State<Self>) -> ErasedJson {
         ErasedJson::pretty(rest.routing.router().address())
     }
 
+    // GET /testnet3/node/attest?nonce={nonce}
+    pub(crate) async fn get_node_attestation(
+        State(rest): State<Self>,
+        Query(query): Query<AttestationQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the nonce is well-formed.
+        if query.nonce.is_empty() || query.nonce.len() > AttestationQuery::MAXIMUM_NONCE_LENGTH {
+            return Err(RestError(format!(
+                "Invalid nonce - expected 1 to {} bytes",
+                AttestationQuery::MAXIMUM_NONCE_LENGTH
+            )));
+        }
+        // Sign the nonce with the node account, under the attestation domain.
+        let router = rest.routing.router();
+        let signature = Signature::sign_bytes(router.private_key(), &query.message::<N>(), &mut rand::thread_rng())?;
+
+        Ok(ErasedJson::pretty(json!({
+            "address": router.address(),
+            "node_type": router.node_type(),
+            "height": rest.ledger.latest_height(),
+            "nonce": query.nonce,
+            "signature": signature,
+        })))
+    }
+
+    // GET /testnet3/admin/bft/status
+    pub(crate) async fn get_bft_status(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => {
+                let primary = consensus.bft().primary();
+                Ok(ErasedJson::pretty(json!({
+                    "paused": primary.is_holding_proposals(ProposalHold::Paused),
+                    "quorum_lost": primary.is_holding_proposals(ProposalHold::QuorumLost),
+                    "round": primary.current_round(),
+                })))
+            }
+            None => Err(RestError("Route isn't available for this node type".to_string())),
+        }
+    }
+
+    // POST /testnet3/admin/bft/pause
+    pub(crate) async fn bft_pause(
+        State(rest): State<Self>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<ErasedJson, RestError> {
+        let result = match &rest.consensus {
+            Some(consensus) => {
+                consensus.bft().primary().hold_proposals(ProposalHold::Paused);
+                Ok(ErasedJson::pretty(json!({ "paused": true })))
+            }
+            None => Err(RestError("Route isn't available for this node type".to_string())),
+        };
+        rest.journal(&claims, "bft/pause", json!({}), result)
+    }
+
+    // POST /testnet3/admin/bft/resume
+    pub(crate) async fn bft_resume(
+        State(rest): State<Self>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<ErasedJson, RestError> {
+        let result = match &rest.consensus {
+            Some(consensus) => {
+                consensus.bft().primary().release_proposals(ProposalHold::Paused);
+                Ok(ErasedJson::pretty(json!({ "paused": false })))
+            }
+            None => Err(RestError("Route isn't available for this node type".to_string())),
+        };
+        rest.journal(&claims, "bft/resume", json!({}), result)
+    }
+
+    // POST /testnet3/admin/rest/rebind
+    // body: { "ip": "0.0.0.0:3030", "rps": 10 }
+    pub(crate) async fn rest_rebind(
+        State(rest): State<Self>,
+        Extension(claims): Extension<Claims>,
+        Json(request): Json<RebindRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        let parameters = json!({ "ip": request.ip, "rps": request.rps });
+        let result = match rest.listener() {
+            Some((_, current_rps)) => {
+                let rps = request.rps.unwrap_or(current_rps);
+                // Note: this request is served by the current listener, which is drained once the request completes.
+                rest.rebind(request.ip, rps)
+                    .map(|address| ErasedJson::pretty(json!({ "listening": address, "rps": rps })))
+                    .map_err(RestError::from)
+            }
+            None => Err(RestError("The REST server is not listening".to_string())),
+        };
+        rest.journal(&claims, "rest/rebind", parameters, result)
+    }
+
+    // GET /testnet3/admin/journal?action={action}&principal={principal}&since={timestamp}&limit={limit}
+    pub(crate) async fn get_action_journal(
+        State(rest): State<Self>,
+        Query(query): Query<JournalQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        let journal = rest.journal.clone();
+        let entries = tokio::task::spawn_blocking(move || journal.query(&query)).await.map_err(anyhow::Error::from)??;
+        Ok(ErasedJson::pretty(entries))
+    }
+
+    // GET /testnet3/admin/debug-bundle
+    pub(crate) async fn debug_bundle(
+        State(rest): State<Self>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Response, RestError> {
+        let result = Self::assemble_debug_bundle(rest.clone()).await;
+        rest.journal(&claims, "debug-bundle", json!({}), result)
+    }
+
+    /// Returns a zip of the recent logs, status, redacted configuration, peers, metrics, storage usage,
+    /// and recent consensus rounds of this node, to be attached to bug reports.
+    async fn assemble_debug_bundle(rest: Self) -> Result<Response, RestError> {
+        // Snapshot the in-memory state of the node.
+        let router = rest.routing.router();
+        let status = rest.node_status();
+        let peers = json!({
+            "connected": router.connected_peers(),
+            "metrics": router.connected_metrics(),
+            "audit": router.audit_log().map(|audit_log| audit_log.recent(AuditQuery::MAXIMUM_LIMIT)),
+        });
+        #[cfg(feature = "metrics")]
+        let metrics = Some(json!({
+            "interval_in_secs": metrics::HISTORY_SAMPLE_INTERVAL_IN_SECS,
+            "samples": rest.metrics_history.samples_since(0),
+        }));
+        #[cfg(not(feature = "metrics"))]
+        let metrics: Option<serde_json::Value> = None;
+        let config = rest.debug_bundle.lock().clone();
+        let consensus = rest.consensus.clone();
+
+        // Read the logs, storage, and consensus archive in the blocking pool.
+        let bytes = tokio::task::spawn_blocking(move || {
+            let mut zip = ZipWriter::default();
+            zip.add_json("status.json", &status)?;
+            zip.add_json("peers.json", &peers)?;
+            if let Some(metrics) = metrics {
+                zip.add_json("metrics.json", &metrics)?;
+            }
+            if let Some(config) = &config.config {
+                zip.add("config.txt", config.as_bytes())?;
+            }
+            // A missing log file or ledger directory is reported in the bundle, rather than failing it.
+            if let Some(path) = &config.log_path {
+                match read_log_tail(path, MAXIMUM_LOG_TAIL_IN_BYTES) {
+                    Ok(tail) => zip.add("snarkos.log", &tail)?,
+                    Err(error) => zip.add("snarkos.log.error", error.to_string().as_bytes())?,
+                }
+            }
+            if let Some(path) = &config.storage_path {
+                match StorageStats::measure(path) {
+                    Ok(stats) => zip.add_json("storage.json", &stats)?,
+                    Err(error) => zip.add("storage.json.error", error.to_string().as_bytes())?,
+                }
+            }
+            if let Some(consensus) = consensus {
+                let archive = consensus.archive();
+                let rounds = archive.rounds();
+                let outputs = rounds[rounds.len().saturating_sub(NUM_DEBUG_BUNDLE_ROUNDS)..]
+                    .iter()
+                    .filter_map(|round| archive.get(*round).transpose())
+                    .collect::<Result<Vec<_>>>()?;
+                zip.add_json("consensus.json", &outputs)?;
+            }
+            zip.finish()
+        })
+        .await
+        .map_err(anyhow::Error::from)??;
+        info!("Assembled a debug bundle ({} bytes)", bytes.len());
+
+        let disposition = "attachment; filename=\"snarkos-debug-bundle.zip\"";
+        Ok(([(CONTENT_TYPE, "application/zip"), (CONTENT_DISPOSITION, disposition)], bytes).into_response())
+    }
+
+    // GET /testnet3/admin/mempool/export
+    pub(crate) async fn mempool_export(
+        State(rest): State<Self>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Response, RestError> {
+        let result = Self::export_mempool_snapshot(rest.clone()).await;
+        rest.journal(&claims, "mempool/export", json!({}), result)
+    }
+
+    /// Returns a snapshot of the unconfirmed transactions held by this node.
+    async fn export_mempool_snapshot(rest: Self) -> Result<Response, RestError> {
+        let Some(consensus) = rest.consensus else {
+            return Err(RestError("Route isn't available for this node type".to_string()));
+        };
+        // Serialize the unconfirmed transactions in the blocking pool.
+        let (num_transactions, bytes) = tokio::task::spawn_blocking(move || {
+            let snapshot = MempoolSnapshot::new(consensus.mempool_transactions()?);
+            Ok::<_, anyhow::Error>((snapshot.transactions().len(), snapshot.to_bytes_le()?))
+        })
+        .await
+        .map_err(anyhow::Error::from)??;
+        info!("Exported a snapshot of {num_transactions} unconfirmed transactions ({} bytes)", bytes.len());
+
+        Ok(([(CONTENT_TYPE, "application/octet-stream")], bytes).into_response())
+    }
+
+    // POST /testnet3/admin/mempool/import
+    // body: the bytes of a snapshot from `GET /testnet3/admin/mempool/export`
+    pub(crate) async fn mempool_import(
+        State(rest): State<Self>,
+        Extension(claims): Extension<Claims>,
+        body: Bytes,
+    ) -> Result<ErasedJson, RestError> {
+        let parameters = json!({ "size": body.len() });
+        let result = Self::import_mempool_snapshot(rest.clone(), body).await;
+        rest.journal(&claims, "mempool/import", parameters, result)
+    }
+
+    /// Adds the unconfirmed transactions of the given snapshot to the memory pool, and broadcasts them.
+    async fn import_mempool_snapshot(rest: Self, body: Bytes) -> Result<ErasedJson, RestError> {
+        let Some(consensus) = rest.consensus.clone() else {
+            return Err(RestError("Route isn't available for this node type".to_string()));
+        };
+        // Deserialize the snapshot in the blocking pool.
+        let snapshot = tokio::task::spawn_blocking(move || parse_mempool_snapshot::<N>(&body))
+            .await
+            .map_err(anyhow::Error::from)??;
+        let transactions = snapshot.into_transactions();
+        info!("Importing a snapshot of {} unconfirmed transactions", transactions.len());
+
+        // Add the transactions to the memory pool one by one, recording the ones that are rejected.
+        let mut seen = HashSet::with_capacity(transactions.len());
+        let mut admitted = Vec::with_capacity(transactions.len());
+        let mut rejected = Vec::new();
+        for transaction in transactions {
+            let tx_id = transaction.id();
+            // Skip the transactions that are repeated in the snapshot.
+            if !seen.insert(tx_id) {
+                continue;
+            }
+            match consensus.add_unconfirmed_transaction(transaction.clone()).await {
+                Ok(()) => admitted.push(transaction),
+                Err(error) => {
+                    debug!("Rejected transaction '{tx_id}' from the mempool snapshot - {error}");
+                    rejected.push(json!({ "transaction_id": tx_id, "reason": error.to_string() }));
+                }
+            }
+        }
+
+        // Relay the admitted transactions.
+        let num_admitted = admitted.len();
+        rest.routing.propagate_transactions(admitted, &[]);
+        info!("Imported {num_admitted} unconfirmed transactions ({} rejected)", rejected.len());
+
+        Ok(ErasedJson::pretty(json!({ "imported": num_admitted, "rejected": rejected })))
+    }
+
+    /// Records the given admin action of the authenticated principal in the action journal, and returns its result.
+    fn journal<T>(
+        &self,
+        claims: &Claims,
+        action: &str,
+        parameters: serde_json::Value,
+        result: Result<T, RestError>,
+    ) -> Result<T, RestError> {
+        let outcome = result.as_ref().map(|_| ()).map_err(|error| error.0.clone());
+        self.journal.record(claims.subject(), action, parameters, outcome);
+        result
+    }
+
     // GET /testnet3/find/blockHash/{transactionID}
     pub(crate) async fn find_block_hash(
         State(rest): State<Self>,
@@ -314,6 +1443,163 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         State(rest): State<Self>,
         Json(tx): Json<Transaction<N>>,
     ) -> Result<ErasedJson, RestError> {
+        // Prepare the unconfirmed transaction message.
+        let tx_id = tx.id();
+        info!("Received transaction '{tx_id}' for broadcast");
+
+        // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
+        if let Some(consensus) = rest.consensus {
+            // Add the unconfirmed transaction to the memory pool.
+            consensus.add_unconfirmed_transaction(tx.clone()).await?;
+        }
+
+        let message = UnconfirmedTransaction { transaction_id: tx_id, transaction: Data::Object(tx) };
+
+        // Broadcast the transaction.
+        rest.routing.propagate_transaction(message, &[]);
+        debug!("Propagated transaction '{tx_id}' to the connected peers");
+
+        Ok(ErasedJson::pretty(tx_id))
+    }
+
+    // POST /testnet3/transaction/broadcast/batch
+    pub(crate) async fn transaction_broadcast_batch(
+        State(rest): State<Self>,
+        Json(transactions): Json<Vec<Transaction<N>>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the batch is bounded.
+        let maximum = DataTransactions::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS as usize;
+        if transactions.len() > maximum {
+            return Err(RestError(format!(
+                "Cannot broadcast more than {maximum} transactions per call (requested {})",
+                transactions.len()
+            )));
+        }
+        info!("Received {} transactions for broadcast", transactions.len());
+
+        // Find the transactions that are repeated within the batch.
+        let mut transaction_ids = HashSet::with_capacity(transactions.len());
+        let is_repeated = transactions.iter().map(|tx| !transaction_ids.insert(tx.id())).collect::<Vec<_>>();
+
+        // Admit the transactions, recording the result of each.
+        let outcomes = match &rest.consensus {
+            // Add the transactions to the memory pool one by one.
+            Some(consensus) => {
+                let mut outcomes = Vec::with_capacity(transactions.len());
+                for (transaction, is_repeated) in transactions.iter().zip(&is_repeated) {
+                    outcomes.push(match is_repeated {
+                        true => Err(anyhow::anyhow!("Transaction is repeated in the batch")),
+                        false => consensus.add_unconfirmed_transaction(transaction.clone()).await,
+                    });
+                }
+                outcomes
+            }
+            // Check that the transactions are well-formed.
+            None => rest.check_transactions_basic(&transactions, is_repeated).await?,
+        };
+        let mut results = Vec::with_capacity(transactions.len());
+        let mut admitted = Vec::with_capacity(transactions.len());
+        for (transaction, outcome) in transactions.into_iter().zip(outcomes) {
+            let tx_id = transaction.id();
+            match outcome {
+                Ok(()) => {
+                    results.push(json!({ "transaction_id": tx_id, "accepted": true }));
+                    admitted.push(transaction);
+                }
+                Err(error) => {
+                    debug!("Rejected transaction '{tx_id}' from the batch - {error}");
+                    results.push(json!({ "transaction_id": tx_id, "accepted": false, "reason": error.to_string() }));
+                }
+            }
+        }
+
+        // Relay the admitted transactions.
+        if !admitted.is_empty() {
+            let num_admitted = admitted.len();
+            rest.routing.propagate_transactions(admitted, &[]);
+            debug!("Propagated {num_admitted} transactions to the connected peers");
+        }
+
+        Ok(ErasedJson::pretty(results))
+    }
+
+    /// Checks that the transactions of a broadcast batch are well-formed, skipping the repeated ones.
+    ///
+    /// The checks run in the blocking pool, for at most `MAX_CONCURRENT_BATCH_CHECKS` batches at once, and the
+    /// request is refused while that many batches are being checked. The request gives up on the checks after
+    /// `BATCH_CHECK_TIMEOUT_IN_SECS`, while the checks hold their slot until they complete.
+    async fn check_transactions_basic(
+        &self,
+        transactions: &[Transaction<N>],
+        is_repeated: Vec<bool>,
+    ) -> Result<Vec<Result<()>>, RestError> {
+        let Ok(permit) = self.batch_checks.clone().try_acquire_owned() else {
+            return Err(RestError("The node is busy with other broadcast batches, try again later".to_string()));
+        };
+        let (ledger, transactions) = (self.ledger.clone(), transactions.to_vec());
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            transactions
+                .iter()
+                .zip(is_repeated)
+                .map(|(transaction, is_repeated)| match is_repeated {
+                    true => Err(anyhow::anyhow!("Transaction is repeated in the batch")),
+                    false => ledger.check_transaction_basic(transaction, None, &mut rand::thread_rng()),
+                })
+                .collect::<Vec<_>>()
+        });
+        let timeout = std::time::Duration::from_secs(BATCH_CHECK_TIMEOUT_IN_SECS);
+        match tokio::time::timeout(timeout, task).await {
+            Ok(result) => Ok(result.map_err(anyhow::Error::from)?),
+            Err(_) => {
+                Err(RestError(format!("The broadcast batch was not checked within {BATCH_CHECK_TIMEOUT_IN_SECS}s")))
+            }
+        }
+    }
+
+    // POST /testnet3/faucet
+    // body: { "address": "aleo1...", "token": "..." }
+    #[cfg(feature = "faucet")]
+    pub(crate) async fn faucet_request(
+        State(rest): State<Self>,
+        ConnectInfo(peer): ConnectInfo<SocketAddr>,
+        Json(request): Json<FaucetRequest<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(faucet) = rest.faucet.get() else {
+            return Err(RestError("Route isn't available for this node type".to_string()));
+        };
+        // Queue the address, to be funded in the next block.
+        faucet.request(request.address, peer.ip(), request.token.as_deref())?;
+        Ok(ErasedJson::pretty(json!({ "address": request.address, "amount": faucet.amount() })))
+    }
+
+    // POST /testnet3/program/deploy
+    pub(crate) async fn program_deploy(
+        State(rest): State<Self>,
+        Json(tx): Json<Transaction<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the transaction is a deployment.
+        let Transaction::Deploy(tx_id, _, deployment, _) = &tx else {
+            return Err(RestError("The transaction is not a deployment".to_string()));
+        };
+        let (tx_id, program_id) = (*tx_id, *deployment.program_id());
+        // Ensure the deployment is well-formed.
+        if deployment.program().functions().len() != deployment.verifying_keys().len() {
+            return Err(RestError(format!("The deployment of '{program_id}' has an invalid number of verifying keys")));
+        }
+        // Ensure the program has not already been deployed.
+        if rest.ledger.vm().contains_program(&program_id) {
+            return Err(RestError(format!("Program '{program_id}' is already deployed")));
+        }
+        // Ensure the fee record has not already been spent.
+        for serial_number in tx.serial_numbers() {
+            if rest.ledger.contains_serial_number(serial_number)? {
+                return Err(RestError(format!("The fee record of the deployment of '{program_id}' is already spent")));
+            }
+        }
+
+        info!("Received the deployment of '{program_id}' in transaction '{tx_id}'");
+
         // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
         if let Some(consensus) = rest.consensus {
             // Add the unconfirmed transaction to the memory pool.
@@ -321,16 +1607,102 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         }
 
         // Prepare the unconfirmed transaction message.
-        let tx_id = tx.id();
-        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
-            transaction_id: tx_id,
-            transaction: Data::Object(tx),
+        let message = UnconfirmedTransaction { transaction_id: tx_id, transaction: Data::Object(tx) };
+
+        // Broadcast the transaction.
+        rest.routing.propagate_transaction(message, &[]);
+
+        // Return the handle to track the deployment.
+        Ok(ErasedJson::pretty(json!({
+            "transaction_id": tx_id,
+            "program_id": program_id,
+            "status": format!("/testnet3/transaction/confirmed/{tx_id}"),
+        })))
+    }
+
+    // POST /testnet3/execute
+    // body: { "private_key": { "ciphertext": "ciphertext1...", "nonce": "...group" }, "program_id": "credits.aleo",
+    //         "function": "transfer_public", "inputs": ["aleo1...", "1u64"], "priority_fee": 0, "broadcast": true }
+    #[cfg(feature = "execute")]
+    pub(crate) async fn execute(
+        State(rest): State<Self>,
+        Json(request): Json<ExecuteRequest<N>>,
+    ) -> Result<Response, RestError> {
+        // Reserve an execution slot, so that the proofs do not exhaust the resources of the node.
+        let Ok(permit) = rest.executions.clone().try_acquire_owned() else {
+            return Ok(RestBusy("The node is busy with other executions, try again later".to_string()).into_response());
+        };
+        let broadcast = request.broadcast;
+        let view_key = ViewKey::try_from(rest.routing.router().private_key())?;
+
+        // Execute the request in the blocking pool, holding the slot until the proofs are complete.
+        let ledger = rest.ledger.clone();
+        let span = tracing::Span::current();
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            span.in_scope(|| request.execute(&ledger, &view_key, &mut rand::thread_rng()))
         });
+        let timeout = std::time::Duration::from_secs(EXECUTION_TIMEOUT_IN_SECS);
+        let transaction = match tokio::time::timeout(timeout, task).await {
+            Ok(result) => result.map_err(anyhow::Error::from)??,
+            Err(_) => {
+                return Err(RestError(format!("The execution did not complete within {EXECUTION_TIMEOUT_IN_SECS}s")));
+            }
+        };
+        let transaction_id = transaction.id();
+        info!("Executed transaction '{transaction_id}'");
 
+        // Return the transaction, if it is not to be broadcast.
+        if !broadcast {
+            let response = json!({ "transaction_id": transaction_id, "transaction": transaction });
+            return Ok(ErasedJson::pretty(response).into_response());
+        }
+        // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
+        if let Some(consensus) = rest.consensus {
+            consensus.add_unconfirmed_transaction(transaction.clone()).await?;
+        }
         // Broadcast the transaction.
-        rest.routing.propagate(message, &[]);
+        let message = UnconfirmedTransaction { transaction_id, transaction: Data::Object(transaction) };
+        rest.routing.propagate_transaction(message, &[]);
 
-        Ok(ErasedJson::pretty(tx_id))
+        // Return the handle to track the execution.
+        Ok(ErasedJson::pretty(json!({
+            "transaction_id": transaction_id,
+            "status": format!("/testnet3/transaction/confirmed/{transaction_id}"),
+        }))
+        .into_response())
+    }
+
+    // POST /testnet3/execute/estimate
+    // body: the same as `POST /testnet3/execute`, of which `broadcast` is ignored
+    #[cfg(feature = "execute")]
+    pub(crate) async fn execute_estimate(
+        State(rest): State<Self>,
+        Json(request): Json<ExecuteRequest<N>>,
+    ) -> Result<Response, RestError> {
+        // Reserve an estimate slot, so that the evaluations do not exhaust the resources of the node.
+        // The estimates have their own slots, as they are cheaper than the executions, which are proven.
+        let Ok(permit) = rest.estimates.clone().try_acquire_owned() else {
+            return Ok(RestBusy("The node is busy with other estimates, try again later".to_string()).into_response());
+        };
+        let view_key = ViewKey::try_from(rest.routing.router().private_key())?;
+
+        // Estimate the request in the blocking pool, as its functions are evaluated, holding the slot until the
+        // evaluation is complete.
+        let ledger = rest.ledger.clone();
+        let span = tracing::Span::current();
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            span.in_scope(|| request.estimate(&ledger, &view_key, &mut rand::thread_rng()))
+        });
+        let timeout = std::time::Duration::from_secs(EXECUTION_TIMEOUT_IN_SECS);
+        let estimate = match tokio::time::timeout(timeout, task).await {
+            Ok(result) => result.map_err(anyhow::Error::from)??,
+            Err(_) => {
+                return Err(RestError(format!("The estimate did not complete within {EXECUTION_TIMEOUT_IN_SECS}s")));
+            }
+        };
+        Ok(ErasedJson::pretty(estimate).into_response())
     }
 
     // POST /testnet3/solution/broadcast
@@ -0,0 +1,146 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_router::messages::{Message, MessageCodec, UnconfirmedTransaction};
+use snarkos_node_tcp::{
+    protocols::{Reading, Writing},
+    Config,
+    ConnectionSide,
+    Tcp,
+    P2P,
+};
+use snarkvm::prelude::{block::Block, FromBytes, Network, Testnet3};
+
+use async_trait::async_trait;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures_util::future::join_all;
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+};
+use tokio::runtime::Runtime;
+
+type CurrentNetwork = Testnet3;
+
+/// The numbers of peers the message is propagated to.
+const NUM_PEERS: [u16; 3] = [8, 32, 128];
+
+/// A node that only writes messages, standing in for the router when it propagates a message.
+#[derive(Clone)]
+struct Propagator(Tcp);
+
+impl P2P for Propagator {
+    fn tcp(&self) -> &Tcp {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl Writing for Propagator {
+    type Codec = MessageCodec<CurrentNetwork>;
+    type Message = Message<CurrentNetwork>;
+
+    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        Default::default()
+    }
+}
+
+/// A peer that decodes and discards the messages it receives.
+#[derive(Clone)]
+struct Sink(Tcp);
+
+impl P2P for Sink {
+    fn tcp(&self) -> &Tcp {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl Reading for Sink {
+    type Codec = MessageCodec<CurrentNetwork>;
+    type Message = Message<CurrentNetwork>;
+
+    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        Default::default()
+    }
+
+    async fn process_message(&self, _source: SocketAddr, _message: Self::Message) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns a TCP stack listening on a random local port, which accepts the given number of connections.
+fn local_tcp(max_connections: u16) -> Tcp {
+    Tcp::new(Config::new(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0), max_connections))
+}
+
+/// Returns a propagator that is connected to the given number of sinks, along with the sinks.
+async fn connected_propagator(num_peers: u16) -> (Propagator, Vec<Sink>) {
+    let propagator = Propagator(local_tcp(num_peers));
+    propagator.enable_writing().await;
+
+    let mut sinks = Vec::with_capacity(num_peers as usize);
+    for _ in 0..num_peers {
+        let sink = Sink(local_tcp(1));
+        sink.enable_reading().await;
+        let addr = sink.tcp().enable_listener().await.unwrap();
+        propagator.tcp().connect(addr).await.unwrap();
+        sinks.push(sink);
+    }
+    (propagator, sinks)
+}
+
+/// Sends the message to every connected peer, as the router propagates it, and waits until each copy is written.
+async fn propagate(propagator: &Propagator, message: &Message<CurrentNetwork>) {
+    let deliveries = propagator
+        .tcp()
+        .connected_addrs()
+        .into_iter()
+        .filter_map(|addr| propagator.unicast(addr, message.clone()).ok())
+        .collect::<Vec<_>>();
+    for delivery in join_all(deliveries).await {
+        delivery.unwrap().unwrap();
+    }
+}
+
+fn propagation(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    // Propagate an unconfirmed transaction from the genesis block, as it is the most frequently propagated message.
+    let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+    let transaction = block.transactions().iter().next().unwrap().transaction().clone();
+    let message = Message::UnconfirmedTransaction(UnconfirmedTransaction::from(transaction));
+
+    let mut group = c.benchmark_group("propagation");
+    for num_peers in NUM_PEERS {
+        let (propagator, sinks) = runtime.block_on(connected_propagator(num_peers));
+        group.throughput(Throughput::Elements(num_peers as u64));
+        group.bench_with_input(BenchmarkId::new("unconfirmed_transaction", num_peers), &message, |b, message| {
+            b.to_async(&runtime).iter(|| propagate(&propagator, message))
+        });
+        runtime.block_on(async {
+            propagator.tcp().shut_down().await;
+            for sink in sinks {
+                sink.tcp().shut_down().await;
+            }
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = propagation
+}
+criterion_main!(benches);
@@ -0,0 +1,109 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_router_messages::{
+    BlockRequest,
+    BlockResponse,
+    DataBlocks,
+    DataTransactions,
+    Message,
+    MessageCodec,
+    UnconfirmedTransactions,
+};
+use snarkvm::{
+    ledger::narwhal::Data,
+    prelude::{
+        block::{Block, Transaction},
+        FromBytes,
+        Network,
+        Testnet3,
+    },
+};
+
+use bytes::BytesMut;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use tokio_util::codec::{Decoder, Encoder};
+
+type CurrentNetwork = Testnet3;
+
+/// Returns the sample messages, by name: a block response carrying the genesis block, and a full batch of
+/// unconfirmed transactions, which are the largest messages gossiped in the network.
+fn sample_messages() -> Vec<(&'static str, Message<CurrentNetwork>)> {
+    let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+    let transactions = block.transactions().iter().map(|transaction| transaction.transaction().clone());
+    let transactions = transactions
+        .cycle()
+        .take(DataTransactions::<CurrentNetwork>::MAXIMUM_NUMBER_OF_TRANSACTIONS as usize)
+        .collect::<Vec<Transaction<CurrentNetwork>>>();
+
+    let request = BlockRequest { start_height: 0, end_height: 1 };
+    let block_response = BlockResponse { request, blocks: Data::Object(DataBlocks(vec![block])) };
+    vec![
+        ("block_response", Message::BlockResponse(block_response)),
+        ("unconfirmed_transactions", Message::UnconfirmedTransactions(transactions.into())),
+    ]
+}
+
+/// Returns the given message, encoded as a frame.
+fn encode(message: Message<CurrentNetwork>) -> BytesMut {
+    let mut bytes = BytesMut::new();
+    MessageCodec::<CurrentNetwork>::default().encode(message, &mut bytes).unwrap();
+    bytes
+}
+
+fn message_codec(c: &mut Criterion) {
+    let mut group = c.benchmark_group("message_codec");
+    for (name, message) in sample_messages() {
+        let frame = encode(message.clone());
+        group.throughput(Throughput::Bytes(frame.len() as u64));
+
+        group.bench_function(format!("encode_{name}"), |b| {
+            b.iter_batched(
+                || (message.clone(), BytesMut::with_capacity(frame.len())),
+                |(message, mut bytes)| MessageCodec::<CurrentNetwork>::default().encode(message, &mut bytes).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_function(format!("decode_{name}"), |b| {
+            b.iter_batched(
+                || frame.clone(),
+                |mut bytes| MessageCodec::<CurrentNetwork>::default().decode(&mut bytes).unwrap().unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+        // The payloads are decoded lazily, so the deserialization of the decoded blocks and transactions is measured
+        // on its own, as it happens after the message is dispatched.
+        group.bench_function(format!("deserialize_{name}"), |b| {
+            b.iter_batched(
+                || MessageCodec::<CurrentNetwork>::default().decode(&mut frame.clone()).unwrap().unwrap(),
+                |message| match message {
+                    Message::BlockResponse(response) => response.blocks.deserialize_blocking().map(|_| ()).unwrap(),
+                    Message::UnconfirmedTransactions(batch) => {
+                        batch.transactions.deserialize_blocking().map(|_| ()).unwrap()
+                    }
+                    _ => unreachable!("Unexpected sample message"),
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = message_codec
+}
+criterion_main!(benches);
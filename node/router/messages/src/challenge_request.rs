@@ -24,6 +24,9 @@ pub struct ChallengeRequest<N: Network> {
     pub listener_port: u16,
     pub node_type: NodeType,
     pub address: Address<N>,
+    pub node_id: NodeId<N>,
+    /// The binding of the address to the consensus account of a validator, if the validator uses a separate one.
+    pub consensus_binding: Option<ConsensusBinding<N>>,
     pub nonce: u64,
 }
 
@@ -41,6 +44,13 @@ impl<N: Network> ToBytes for ChallengeRequest<N> {
         self.listener_port.write_le(&mut writer)?;
         self.node_type.write_le(&mut writer)?;
         self.address.write_le(&mut writer)?;
+        self.node_id.write_le(&mut writer)?;
+        if let Some(consensus_binding) = &self.consensus_binding {
+            1u8.write_le(&mut writer)?;
+            consensus_binding.write_le(&mut writer)?;
+        } else {
+            0u8.write_le(&mut writer)?;
+        }
         self.nonce.write_le(&mut writer)?;
         Ok(())
     }
@@ -52,24 +62,43 @@ impl<N: Network> FromBytes for ChallengeRequest<N> {
         let listener_port = u16::read_le(&mut reader)?;
         let node_type = NodeType::read_le(&mut reader)?;
         let address = Address::<N>::read_le(&mut reader)?;
+        let node_id = NodeId::read_le(&mut reader)?;
+        let consensus_binding = match u8::read_le(&mut reader)? {
+            0 => None,
+            1 => Some(ConsensusBinding::read_le(&mut reader)?),
+            _ => return Err(error("Invalid selector of optional consensus binding in challenge request")),
+        };
         let nonce = u64::read_le(&mut reader)?;
 
-        Ok(Self { version, listener_port, node_type, address, nonce })
+        Ok(Self { version, listener_port, node_type, address, node_id, consensus_binding, nonce })
     }
 }
 
 impl<N: Network> ChallengeRequest<N> {
-    pub fn new(listener_port: u16, node_type: NodeType, address: Address<N>, nonce: u64) -> Self {
-        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce }
+    pub fn new(
+        listener_port: u16,
+        node_type: NodeType,
+        address: Address<N>,
+        node_id: NodeId<N>,
+        consensus_binding: Option<ConsensusBinding<N>>,
+        nonce: u64,
+    ) -> Self {
+        Self { version: Message::<N>::VERSION, listener_port, node_type, address, node_id, consensus_binding, nonce }
+    }
+
+    /// Returns the address of the consensus account of the sender, which is its address unless it bound another.
+    /// Note: The consensus binding must be verified by the caller.
+    pub fn consensus_address(&self) -> Address<N> {
+        self.consensus_binding.as_ref().map_or(self.address, |binding| binding.address())
     }
 }
 
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::{ChallengeRequest, NodeType};
+    use crate::{ChallengeRequest, ConsensusBinding, NodeId, NodeType};
     use snarkvm::{
         console::prelude::{FromBytes, ToBytes},
-        prelude::{Address, TestRng, Uniform},
+        prelude::{Address, PrivateKey, TestRng, Uniform},
     };
 
     use bytes::{Buf, BufMut, BytesMut};
@@ -93,14 +122,38 @@ pub mod prop_tests {
             .boxed()
     }
 
+    pub fn any_node_id() -> BoxedStrategy<NodeId<CurrentNetwork>> {
+        any::<u64>()
+            .prop_map(|seed| {
+                let rng = &mut TestRng::fixed(seed);
+                NodeId::new(&PrivateKey::new(rng).unwrap(), rng).unwrap()
+            })
+            .boxed()
+    }
+
+    pub fn any_consensus_binding() -> BoxedStrategy<Option<ConsensusBinding<CurrentNetwork>>> {
+        any::<Option<u64>>()
+            .prop_map(|seed| {
+                seed.map(|seed| {
+                    let rng = &mut TestRng::fixed(seed);
+                    ConsensusBinding::new(&PrivateKey::new(rng).unwrap(), &Address::rand(rng), rng).unwrap()
+                })
+            })
+            .boxed()
+    }
+
     pub fn any_challenge_request() -> BoxedStrategy<ChallengeRequest<CurrentNetwork>> {
-        (any_valid_address(), any::<u64>(), any::<u32>(), any::<u16>(), any_node_type())
-            .prop_map(|(address, nonce, version, listener_port, node_type)| ChallengeRequest {
-                address,
-                nonce,
-                version,
-                listener_port,
-                node_type,
+        (
+            any_valid_address(),
+            any_node_id(),
+            any_consensus_binding(),
+            any::<u64>(),
+            any::<u32>(),
+            any::<u16>(),
+            any_node_type(),
+        )
+            .prop_map(|(address, node_id, consensus_binding, nonce, version, listener_port, node_type)| {
+                ChallengeRequest { address, node_id, consensus_binding, nonce, version, listener_port, node_type }
             })
             .boxed()
     }
@@ -24,10 +24,21 @@ use std::borrow::Cow;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ChallengeResponse<N: Network> {
     pub genesis_header: Header<N>,
+    pub height: u32,
     pub signature: Data<Signature<N>>,
     pub nonce: u64,
 }
 
+impl<N: Network> ChallengeResponse<N> {
+    /// The domain of the signed nonces, which keeps them distinct from the other messages signed by the node.
+    const DOMAIN: &'static [u8] = b"snarkos.router_handshake";
+
+    /// Returns the signed message for the given challenge nonce and response nonce, which is bound to the network.
+    pub fn message(challenge_nonce: u64, response_nonce: u64) -> Vec<u8> {
+        [&N::ID.to_le_bytes()[..], Self::DOMAIN, &challenge_nonce.to_le_bytes(), &response_nonce.to_le_bytes()].concat()
+    }
+}
+
 impl<N: Network> MessageTrait for ChallengeResponse<N> {
     /// Returns the message name.
     #[inline]
@@ -39,6 +50,7 @@ impl<N: Network> MessageTrait for ChallengeResponse<N> {
 impl<N: Network> ToBytes for ChallengeResponse<N> {
     fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         self.genesis_header.write_le(&mut writer)?;
+        self.height.write_le(&mut writer)?;
         self.signature.write_le(&mut writer)?;
         self.nonce.write_le(&mut writer)
     }
@@ -48,6 +60,7 @@ impl<N: Network> FromBytes for ChallengeResponse<N> {
     fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
         Ok(Self {
             genesis_header: Header::read_le(&mut reader)?,
+            height: u32::read_le(&mut reader)?,
             signature: Data::read_le(&mut reader)?,
             nonce: u64::read_le(reader)?,
         })
@@ -86,10 +99,11 @@ pub mod prop_tests {
     }
 
     pub fn any_challenge_response() -> BoxedStrategy<ChallengeResponse<CurrentNetwork>> {
-        (any_signature(), any_genesis_header(), any::<u64>())
-            .prop_map(|(sig, genesis_header, nonce)| ChallengeResponse {
+        (any_signature(), any_genesis_header(), any::<u32>(), any::<u64>())
+            .prop_map(|(sig, genesis_header, height, nonce)| ChallengeResponse {
                 signature: Data::Object(sig),
                 genesis_header,
+                height,
                 nonce,
             })
             .boxed()
@@ -104,6 +118,7 @@ pub mod prop_tests {
             ChallengeResponse::read_le(buf.into_inner().reader()).unwrap();
 
         assert_eq!(original.genesis_header, deserialized.genesis_header);
+        assert_eq!(original.height, deserialized.height);
         assert_eq!(
             original.signature.deserialize_blocking().unwrap(),
             deserialized.signature.deserialize_blocking().unwrap()
@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointRequest {
+    /// The height of the requested checkpoint, or `None` for the latest checkpoint.
+    pub height: Option<u32>,
+}
+
+impl MessageTrait for CheckpointRequest {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        match self.height {
+            Some(height) => format!("CheckpointRequest {height}").into(),
+            None => "CheckpointRequest latest".into(),
+        }
+    }
+}
+
+impl ToBytes for CheckpointRequest {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        match self.height {
+            Some(height) => {
+                1u8.write_le(&mut writer)?;
+                height.write_le(&mut writer)
+            }
+            None => 0u8.write_le(&mut writer),
+        }
+    }
+}
+
+impl FromBytes for CheckpointRequest {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let height = match u8::read_le(&mut reader)? {
+            0 => None,
+            1 => Some(u32::read_le(&mut reader)?),
+            _ => return Err(error("Invalid selector of the height in the checkpoint request")),
+        };
+        Ok(Self { height })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::CheckpointRequest;
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn checkpoint_request_roundtrip(height: Option<u32>) {
+        let checkpoint_request = CheckpointRequest { height };
+        let mut bytes = BytesMut::default().writer();
+        checkpoint_request.write_le(&mut bytes).unwrap();
+        let decoded = CheckpointRequest::read_le(&mut bytes.into_inner().reader()).unwrap();
+        assert_eq!(decoded, checkpoint_request);
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointResponse<N: Network> {
+    /// The checkpoint certificate, or `None` if the requested checkpoint is not certified.
+    pub certificate: Option<CheckpointCertificate<N>>,
+}
+
+impl<N: Network> MessageTrait for CheckpointResponse<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        match &self.certificate {
+            Some(certificate) => format!("CheckpointResponse {}", certificate.checkpoint.height).into(),
+            None => "CheckpointResponse".into(),
+        }
+    }
+}
+
+impl<N: Network> ToBytes for CheckpointResponse<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        match &self.certificate {
+            Some(certificate) => {
+                1u8.write_le(&mut writer)?;
+                certificate.write_le(&mut writer)
+            }
+            None => 0u8.write_le(&mut writer),
+        }
+    }
+}
+
+impl<N: Network> FromBytes for CheckpointResponse<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let certificate = match u8::read_le(&mut reader)? {
+            0 => None,
+            1 => Some(CheckpointCertificate::read_le(&mut reader)?),
+            _ => return Err(error("Invalid selector of the certificate in the checkpoint response")),
+        };
+        Ok(Self { certificate })
+    }
+}
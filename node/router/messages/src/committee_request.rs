@@ -0,0 +1,63 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CommitteeRequest {
+    /// The height of the block, whose committee is requested.
+    pub height: u32,
+}
+
+impl MessageTrait for CommitteeRequest {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("CommitteeRequest {}", self.height).into()
+    }
+}
+
+impl ToBytes for CommitteeRequest {
+    fn write_le<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.height.write_le(writer)
+    }
+}
+
+impl FromBytes for CommitteeRequest {
+    fn read_le<R: io::Read>(reader: R) -> io::Result<Self> {
+        Ok(Self { height: u32::read_le(reader)? })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::CommitteeRequest;
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn committee_request_roundtrip(height: u32) {
+        let committee_request = CommitteeRequest { height };
+        let mut bytes = BytesMut::default().writer();
+        committee_request.write_le(&mut bytes).unwrap();
+        let decoded = CommitteeRequest::read_le(&mut bytes.into_inner().reader()).unwrap();
+        assert_eq!(decoded, committee_request);
+    }
+}
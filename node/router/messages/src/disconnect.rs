@@ -78,6 +78,9 @@ mod tests {
             DisconnectReason::TooManyPeers,
             DisconnectReason::YouNeedToSyncFirst,
             DisconnectReason::YourPortIsClosed(TestRng::default().gen()),
+            DisconnectReason::Banned,
+            DisconnectReason::Oversubscribed,
+            DisconnectReason::DuplicateConnection,
         ];
 
         for reason in all_reasons.iter() {
@@ -16,7 +16,12 @@ use crate::Message;
 use snarkvm::prelude::{FromBytes, Network, ToBytes};
 
 use ::bytes::{Buf, BufMut, BytesMut};
-use core::marker::PhantomData;
+use core::{fmt, marker::PhantomData};
+use std::{
+    io,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
 
 /// The maximum size of a message that can be transmitted during the handshake.
@@ -25,9 +30,81 @@ const MAXIMUM_HANDSHAKE_MESSAGE_SIZE: usize = 1024 * 1024; // 1 MiB
 /// The maximum size of a message that can be transmitted in the network.
 pub(crate) const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
 
+/// The size of the length prefix of a frame, in bytes.
+const LENGTH_PREFIX_SIZE: usize = 4;
+/// The minimum size of a message, i.e. the size of its ID, in bytes.
+const MINIMUM_MESSAGE_SIZE: usize = 2;
+/// The duration in seconds after which a frame that is still partially received is considered malformed,
+/// in addition to the time it takes to receive the frame at the minimum byte rate.
+const PARTIAL_FRAME_TIMEOUT_IN_SECS: u64 = 60;
+/// The minimum rate in bytes per second at which a frame is expected to arrive, before it is considered malformed.
+const MINIMUM_FRAME_RATE_IN_BYTES_PER_SEC: u64 = 64 * 1024; // 64 KiB/s
+
+/// The reason a frame received from a peer is malformed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MalformedFrame {
+    /// The length prefix is below the minimum size of a message.
+    Undersized,
+    /// The length prefix exceeds the maximum size of a message.
+    Oversized,
+    /// The frame was not received in full before the timeout.
+    Timeout,
+    /// The frame does not deserialize into a message.
+    Undecodable,
+    /// The frame has bytes left over after the message.
+    TrailingBytes,
+}
+
+impl MalformedFrame {
+    /// Returns the name of the reason, e.g. to label metrics.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Undersized => "undersized",
+            Self::Oversized => "oversized",
+            Self::Timeout => "timeout",
+            Self::Undecodable => "undecodable",
+            Self::TrailingBytes => "trailing_bytes",
+        }
+    }
+}
+
+impl fmt::Display for MalformedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Undersized => write!(f, "the frame is shorter than a message ID"),
+            Self::Oversized => write!(f, "the frame exceeds the maximum message size"),
+            Self::Timeout => write!(f, "the frame was not received in full in time"),
+            Self::Undecodable => write!(f, "the frame is not a valid message"),
+            Self::TrailingBytes => write!(f, "the frame has trailing bytes after the message"),
+        }
+    }
+}
+
+impl std::error::Error for MalformedFrame {}
+
+/// A function that is notified of each malformed frame received on a connection.
+pub type MalformedFrameHandler = Arc<dyn Fn(MalformedFrame) + Send + Sync>;
+
 /// The codec used to decode and encode network `Message`s.
+///
+/// Each frame is a little-endian `u32` length prefix followed by the message. The decoder validates the
+/// length prefix before buffering the frame, never reserves memory based on it, rejects frames with bytes
+/// left over after the message, and fails frames that are not received in full before a timeout, which
+/// scales with the length of the frame, so that large frames from slow peers are not considered malformed.
 pub struct MessageCodec<N: Network> {
+    /// The codec used to encode the frames.
     codec: LengthDelimitedCodec,
+    /// The maximum size of a message, in bytes.
+    max_message_size: usize,
+    /// The duration after which a partially received frame times out, in addition to the time it takes
+    /// to receive the frame at the minimum byte rate.
+    partial_frame_timeout: Duration,
+    /// The minimum rate in bytes per second at which a frame is expected to arrive.
+    min_frame_rate: u64,
+    /// The time at which the partially received frame started arriving, if there is one.
+    partial_since: Option<Instant>,
+    /// The function that is notified of the malformed frames, if there is one.
+    on_malformed_frame: Option<MalformedFrameHandler>,
     _phantom: PhantomData<N>,
 }
 
@@ -35,14 +112,56 @@ impl<N: Network> MessageCodec<N> {
     pub fn handshake() -> Self {
         let mut codec = Self::default();
         codec.codec.set_max_frame_length(MAXIMUM_HANDSHAKE_MESSAGE_SIZE);
+        codec.max_message_size = MAXIMUM_HANDSHAKE_MESSAGE_SIZE;
         codec
     }
+
+    /// Sets the function that is notified of each malformed frame, e.g. to hold the peer accountable.
+    pub fn with_malformed_frame_handler(mut self, handler: impl Fn(MalformedFrame) + Send + Sync + 'static) -> Self {
+        self.on_malformed_frame = Some(Arc::new(handler));
+        self
+    }
+
+    /// Notifies the handler of the given malformed frame, and returns the corresponding error.
+    fn malformed(&mut self, reason: MalformedFrame) -> io::Error {
+        self.partial_since = None;
+        if let Some(handler) = &self.on_malformed_frame {
+            handler(reason);
+        }
+        io::Error::new(io::ErrorKind::InvalidData, reason)
+    }
+
+    /// Returns the duration after which a frame of the given length times out,
+    /// or of an unknown length if the length prefix has not been received yet.
+    fn partial_frame_deadline(&self, length: Option<usize>) -> Duration {
+        let length = length.unwrap_or_default() as u64;
+        self.partial_frame_timeout + Duration::from_secs(length / self.min_frame_rate.max(1))
+    }
+
+    /// Waits for the rest of a partially received frame of the given length (if known),
+    /// failing if it has been pending for too long.
+    fn await_partial_frame(&mut self, source: &BytesMut, length: Option<usize>) -> io::Result<Option<Message<N>>> {
+        if source.is_empty() {
+            self.partial_since = None;
+            return Ok(None);
+        }
+        let since = *self.partial_since.get_or_insert_with(Instant::now);
+        if since.elapsed() > self.partial_frame_deadline(length) {
+            return Err(self.malformed(MalformedFrame::Timeout));
+        }
+        Ok(None)
+    }
 }
 
 impl<N: Network> Default for MessageCodec<N> {
     fn default() -> Self {
         Self {
             codec: LengthDelimitedCodec::builder().max_frame_length(MAXIMUM_MESSAGE_SIZE).little_endian().new_codec(),
+            max_message_size: MAXIMUM_MESSAGE_SIZE,
+            partial_frame_timeout: Duration::from_secs(PARTIAL_FRAME_TIMEOUT_IN_SECS),
+            min_frame_rate: MINIMUM_FRAME_RATE_IN_BYTES_PER_SEC,
+            partial_since: None,
+            on_malformed_frame: None,
             _phantom: Default::default(),
         }
     }
@@ -69,20 +188,189 @@ impl<N: Network> Decoder for MessageCodec<N> {
     type Item = Message<N>;
 
     fn decode(&mut self, source: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        // Decode a frame containing bytes belonging to a message.
-        let bytes = match self.codec.decode(source)? {
-            Some(bytes) => bytes,
-            None => return Ok(None),
-        };
+        // Wait for the length prefix.
+        if source.len() < LENGTH_PREFIX_SIZE {
+            return self.await_partial_frame(source, None);
+        }
+        // Ensure the length prefix is within bounds, before buffering the frame.
+        let mut length_prefix = [0u8; LENGTH_PREFIX_SIZE];
+        length_prefix.copy_from_slice(&source[..LENGTH_PREFIX_SIZE]);
+        let length = u32::from_le_bytes(length_prefix) as usize;
+        if length < MINIMUM_MESSAGE_SIZE {
+            return Err(self.malformed(MalformedFrame::Undersized));
+        }
+        if length > self.max_message_size {
+            return Err(self.malformed(MalformedFrame::Oversized));
+        }
+        // Wait for the rest of the frame. The buffer grows with the received bytes, not with the length prefix.
+        if source.len() < LENGTH_PREFIX_SIZE + length {
+            return self.await_partial_frame(source, Some(length));
+        }
+        self.partial_since = None;
 
         // Convert the bytes to a message, or fail if it is not valid.
-        let reader = bytes.reader();
-        match Message::read_le(reader) {
-            Ok(message) => Ok(Some(message)),
+        source.advance(LENGTH_PREFIX_SIZE);
+        let bytes = source.split_to(length).freeze();
+        let mut reader = bytes.reader();
+        let message = match Message::read_le(&mut reader) {
+            Ok(message) => message,
             Err(error) => {
                 error!("Failed to deserialize a message: {}", error);
-                Err(std::io::ErrorKind::InvalidData.into())
+                return Err(self.malformed(MalformedFrame::Undecodable));
+            }
+        };
+        // Ensure the message spans the whole frame.
+        if reader.get_ref().has_remaining() {
+            return Err(self.malformed(MalformedFrame::TrailingBytes));
+        }
+        Ok(Some(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ping::prop_tests::any_ping, pong::tests::any_pong, Ping, Pong};
+
+    use proptest::prelude::any;
+    use std::sync::Mutex;
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    /// Returns a codec that records the malformed frames it sees.
+    fn recording_codec() -> (MessageCodec<CurrentNetwork>, Arc<Mutex<Vec<MalformedFrame>>>) {
+        let malformed = Arc::new(Mutex::new(Vec::new()));
+        let malformed_ = malformed.clone();
+        let codec = MessageCodec::default()
+            .with_malformed_frame_handler(move |reason| malformed_.lock().unwrap().push(reason));
+        (codec, malformed)
+    }
+
+    /// Returns the given message, encoded as a frame.
+    fn encode(message: Message<CurrentNetwork>) -> BytesMut {
+        let mut bytes = BytesMut::new();
+        MessageCodec::<CurrentNetwork>::default().encode(message, &mut bytes).unwrap();
+        bytes
+    }
+
+    /// Decodes the given bytes, fed in the given number of chunks, until the codec yields nothing or fails.
+    fn decode_in_chunks(
+        codec: &mut MessageCodec<CurrentNetwork>,
+        bytes: &[u8],
+        chunk_size: usize,
+    ) -> io::Result<Vec<Message<CurrentNetwork>>> {
+        let mut source = BytesMut::new();
+        let mut messages = Vec::new();
+        for chunk in bytes.chunks(chunk_size.max(1)) {
+            source.extend_from_slice(chunk);
+            while let Some(message) = codec.decode(&mut source)? {
+                messages.push(message);
             }
         }
+        Ok(messages)
+    }
+
+    #[proptest]
+    fn arbitrary_bytes_never_panic(bytes: Vec<u8>, #[strategy(1usize..64)] chunk_size: usize) {
+        let (mut codec, malformed) = recording_codec();
+        // Any outcome is acceptable, as long as the decoder does not panic, and every failure is reported.
+        if decode_in_chunks(&mut codec, &bytes, chunk_size).is_err() {
+            assert_eq!(malformed.lock().unwrap().len(), 1);
+        }
+    }
+
+    #[proptest]
+    fn length_prefix_is_validated(#[strategy(any::<u32>())] length: u32) {
+        let (mut codec, malformed) = recording_codec();
+        let mut source = BytesMut::from(&length.to_le_bytes()[..]);
+        let result = codec.decode(&mut source);
+
+        let length = length as usize;
+        if length < MINIMUM_MESSAGE_SIZE {
+            assert!(result.is_err());
+            assert_eq!(*malformed.lock().unwrap(), vec![MalformedFrame::Undersized]);
+        } else if length > MAXIMUM_MESSAGE_SIZE {
+            assert!(result.is_err());
+            assert_eq!(*malformed.lock().unwrap(), vec![MalformedFrame::Oversized]);
+        } else {
+            // A valid length prefix waits for the frame, without reserving memory for it.
+            assert!(result.unwrap().is_none());
+            assert!(source.capacity() < 64);
+            assert!(malformed.lock().unwrap().is_empty());
+        }
+    }
+
+    #[proptest]
+    fn frames_roundtrip_in_chunks(
+        #[strategy(any_ping())] ping: Ping<CurrentNetwork>,
+        #[strategy(any_pong())] pong: Pong,
+        #[strategy(1usize..256)] chunk_size: usize,
+    ) {
+        let (ping, pong) = (Message::Ping(ping), Message::Pong(pong));
+        let mut bytes = encode(ping.clone());
+        bytes.extend_from_slice(&encode(pong.clone()));
+
+        let (mut codec, malformed) = recording_codec();
+        let messages = decode_in_chunks(&mut codec, &bytes, chunk_size).unwrap();
+        assert_eq!(messages, vec![ping, pong]);
+        assert!(malformed.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        // Extend the frame of a message by one byte.
+        let mut bytes = encode(Message::Pong(Pong::new(None, 0)));
+        let length = u32::from_le_bytes(bytes[..LENGTH_PREFIX_SIZE].try_into().unwrap()) + 1;
+        bytes[..LENGTH_PREFIX_SIZE].copy_from_slice(&length.to_le_bytes());
+        bytes.put_u8(0);
+
+        let (mut codec, malformed) = recording_codec();
+        assert!(codec.decode(&mut bytes).is_err());
+        assert_eq!(*malformed.lock().unwrap(), vec![MalformedFrame::TrailingBytes]);
+    }
+
+    #[test]
+    fn test_partial_frame_timeout() {
+        let bytes = encode(Message::Pong(Pong::new(None, 0)));
+        let (mut codec, malformed) = recording_codec();
+        codec.partial_frame_timeout = Duration::ZERO;
+
+        // Ensure a partial frame is awaited at first, and fails once it has been pending for too long.
+        let mut source = BytesMut::from(&bytes[..bytes.len() - 1]);
+        assert!(codec.decode(&mut source).unwrap().is_none());
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(codec.decode(&mut source).is_err());
+        assert_eq!(*malformed.lock().unwrap(), vec![MalformedFrame::Timeout]);
+
+        // Ensure a complete frame is decoded regardless of the timeout.
+        let (mut codec, _) = recording_codec();
+        codec.partial_frame_timeout = Duration::ZERO;
+        assert!(codec.decode(&mut bytes.clone()).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_partial_frame_timeout_scales_with_length() {
+        let (mut codec, malformed) = recording_codec();
+        codec.partial_frame_timeout = Duration::ZERO;
+        codec.min_frame_rate = 1;
+
+        // Ensure the deadline of a frame grows with its length, at the minimum byte rate.
+        assert_eq!(codec.partial_frame_deadline(None), Duration::ZERO);
+        assert_eq!(codec.partial_frame_deadline(Some(1000)), Duration::from_secs(1000));
+
+        // Ensure a large frame that is still arriving is awaited past the base timeout.
+        let mut source = BytesMut::from(&(1000u32.to_le_bytes())[..]);
+        source.put_u8(0);
+        assert!(codec.decode(&mut source).unwrap().is_none());
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(codec.decode(&mut source).unwrap().is_none());
+        assert!(malformed.lock().unwrap().is_empty());
+
+        // Ensure the default deadline of the largest frame allows for the minimum byte rate.
+        let codec = MessageCodec::<CurrentNetwork>::default();
+        let transfer_secs = MAXIMUM_MESSAGE_SIZE as u64 / MINIMUM_FRAME_RATE_IN_BYTES_PER_SEC;
+        let expected = Duration::from_secs(PARTIAL_FRAME_TIMEOUT_IN_SECS + transfer_secs);
+        assert_eq!(codec.partial_frame_deadline(Some(MAXIMUM_MESSAGE_SIZE)), expected);
     }
 }
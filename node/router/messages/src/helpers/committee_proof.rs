@@ -0,0 +1,235 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{
+        authority::Authority,
+        block::{Block, Header},
+        committee::Committee,
+        narwhal::Subdag,
+    },
+    prelude::{bail, ensure, FromBytes, Network, Result, ToBits, ToBytes},
+};
+
+use serde::Serialize;
+use std::{collections::HashSet, io};
+
+/// A proof that a committee certified the block at a given height, which light clients and bridges
+/// can check without trusting the node that served it.
+///
+/// The proof binds the block hash to the header, and the header to the subdag that the committee certified.
+/// The committee is the committee that elected the leader of the subdag, and certified its batch.
+/// Its stakes live in the finalize state, which the ledger exposes no Merkle path into, so a light client
+/// verifies the proof against a committee it already trusts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct CommitteeProof<N: Network> {
+    /// The block height.
+    pub height: u32,
+    /// The block hash.
+    pub block_hash: N::BlockHash,
+    /// The hash of the previous block.
+    pub previous_hash: N::BlockHash,
+    /// The block header, which commits to the subdag.
+    pub header: Header<N>,
+    /// The committee that certified the block, with the stake of each member.
+    pub committee: Committee<N>,
+    /// The subdag of the block, whose leader certificate was certified by the committee.
+    pub subdag: Subdag<N>,
+}
+
+impl<N: Network> CommitteeProof<N> {
+    /// Returns the committee proof for the given block, which was certified by the given committee.
+    ///
+    /// The committee must be the committee lookback for the round of the leader certificate of the block.
+    pub fn new(block: &Block<N>, committee: Committee<N>) -> Result<Self> {
+        // Retrieve the subdag, which only exists for blocks produced by the BFT.
+        let subdag = match block.authority() {
+            Authority::Beacon(_) => bail!("Block {} was produced by the beacon, not by a committee", block.height()),
+            Authority::Quorum(subdag) => subdag.clone(),
+        };
+        Ok(Self {
+            height: block.height(),
+            block_hash: block.hash(),
+            previous_hash: block.previous_hash(),
+            header: *block.header(),
+            committee,
+            subdag,
+        })
+    }
+
+    /// Checks that the block hash commits to the header, and that the header commits to the subdag.
+    fn check_block(&self) -> Result<()> {
+        let height = self.height;
+        ensure!(self.header.height() == height, "The header is for block {}, not {height}", self.header.height());
+        // Ensure the block hash commits to the previous block hash and the header.
+        let mut preimage = self.previous_hash.to_bits_le();
+        preimage.extend(self.header.to_root()?.to_bits_le());
+        ensure!(
+            *self.block_hash == N::hash_bhp1024(&preimage)?,
+            "The header does not match the block hash of block {height}"
+        );
+        // Ensure the header commits to the subdag.
+        ensure!(
+            self.header.subdag_root() == self.subdag.to_subdag_root()?,
+            "The subdag does not match the header of block {height}"
+        );
+        Ok(())
+    }
+
+    /// Checks that the leader certificate of the subdag was authored by the elected leader,
+    /// and signed by a quorum of the given committee, which the light client trusts.
+    fn check_certificate(&self, trusted_committee: &Committee<N>) -> Result<()> {
+        let leader_certificate = self.subdag.leader_certificate();
+        let round = leader_certificate.round();
+        let batch_id = leader_certificate.batch_id();
+
+        // Ensure the committee is the trusted committee.
+        ensure!(self.committee == *trusted_committee, "The committee of block {} is not trusted", self.height);
+        // Ensure the certificate was authored by the leader of its round.
+        let author = leader_certificate.author();
+        ensure!(self.committee.get_leader(round)? == author, "The certificate author {author} is not the leader");
+        // Ensure the author signed the batch.
+        ensure!(
+            leader_certificate.batch_header().signature().verify(&author, &[batch_id]),
+            "Invalid author signature for batch {batch_id}"
+        );
+
+        // Initialize a set of the signers, including the author.
+        let mut signers = HashSet::with_capacity(leader_certificate.signatures().len() + 1);
+        signers.insert(author);
+        // Ensure every signer is a committee member, and signed the batch.
+        for signature in leader_certificate.signatures() {
+            let signer = signature.to_address();
+            ensure!(self.committee.is_committee_member(signer), "Signer {signer} is not in the committee");
+            ensure!(signature.verify(&signer, &[batch_id]), "Invalid signature from {signer} for batch {batch_id}");
+            signers.insert(signer);
+        }
+        // Ensure the signers hold a quorum of the stake.
+        ensure!(self.committee.is_quorum_threshold_reached(&signers), "The quorum threshold has not been reached");
+        Ok(())
+    }
+
+    /// Checks that the block was certified by the given committee, which the light client trusts.
+    pub fn verify(&self, trusted_committee: &Committee<N>) -> Result<()> {
+        self.check_block()?;
+        self.check_certificate(trusted_committee)
+    }
+}
+
+impl<N: Network> ToBytes for CommitteeProof<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.height.write_le(&mut writer)?;
+        self.block_hash.write_le(&mut writer)?;
+        self.previous_hash.write_le(&mut writer)?;
+        self.header.write_le(&mut writer)?;
+        self.committee.write_le(&mut writer)?;
+        self.subdag.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for CommitteeProof<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        Ok(Self {
+            height: u32::read_le(&mut reader)?,
+            block_hash: N::BlockHash::read_le(&mut reader)?,
+            previous_hash: N::BlockHash::read_le(&mut reader)?,
+            header: Header::read_le(&mut reader)?,
+            committee: Committee::read_le(&mut reader)?,
+            subdag: Subdag::read_le(reader)?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::{
+            ledger_test_helpers::sample_genesis_block,
+            narwhal::{BatchCertificate, BatchHeader},
+        },
+        prelude::{Address, Field, PrivateKey, TestRng, Uniform},
+    };
+
+    use indexmap::{IndexMap, IndexSet};
+    use std::collections::BTreeMap;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    /// Samples a committee of four members, and a subdag whose leader certificate is signed by all of them.
+    fn sample_committee_and_subdag(rng: &mut TestRng) -> (Committee<CurrentNetwork>, Subdag<CurrentNetwork>) {
+        let private_keys = (0..4).map(|_| PrivateKey::<CurrentNetwork>::new(rng).unwrap()).collect::<Vec<_>>();
+        let addresses = private_keys.iter().map(|key| Address::try_from(key).unwrap()).collect::<Vec<_>>();
+        let members = addresses.iter().map(|address| (*address, (1_000_000_000_000, true))).collect::<IndexMap<_, _>>();
+        let committee = Committee::new(0, members).unwrap();
+
+        // Sign the batch of the leader of round 2.
+        let round = 2;
+        let leader = committee.get_leader(round).unwrap();
+        let leader_key = private_keys[addresses.iter().position(|address| *address == leader).unwrap()];
+        let previous_certificate_ids = IndexSet::from([Field::rand(rng)]);
+        let transmission_ids = Default::default();
+        let batch_header =
+            BatchHeader::new(&leader_key, round, 0, transmission_ids, previous_certificate_ids, Default::default(), rng)
+                .unwrap();
+        let signatures = private_keys
+            .iter()
+            .filter(|key| **key != leader_key)
+            .map(|key| key.sign(&[batch_header.batch_id()], rng).unwrap())
+            .collect::<IndexSet<_>>();
+        let certificate = BatchCertificate::from(batch_header, signatures).unwrap();
+        let certificates = BTreeMap::from([(round, IndexSet::from([certificate]))]);
+        let subdag = Subdag::from(certificates, Default::default()).unwrap();
+        (committee, subdag)
+    }
+
+    #[test]
+    fn test_committee_proof() {
+        let rng = &mut TestRng::default();
+        let (committee, subdag) = sample_committee_and_subdag(rng);
+
+        // Sample a proof with the header of the genesis block, which does not commit to the subdag.
+        let block = sample_genesis_block(rng);
+        let proof = CommitteeProof::<CurrentNetwork> {
+            height: block.height(),
+            block_hash: block.hash(),
+            previous_hash: block.previous_hash(),
+            header: *block.header(),
+            committee: committee.clone(),
+            subdag,
+        };
+
+        // Ensure the leader certificate is certified by the trusted committee, and not by another committee.
+        assert!(proof.check_certificate(&committee).is_ok());
+        let (other_committee, _) = sample_committee_and_subdag(rng);
+        assert!(proof.check_certificate(&other_committee).is_err());
+        let mut untrusted = proof.clone();
+        untrusted.committee = other_committee.clone();
+        assert!(untrusted.check_certificate(&other_committee).is_err());
+
+        // Ensure the block hash is bound to the header, and the header to the subdag.
+        let error = proof.verify(&committee).unwrap_err().to_string();
+        assert!(error.contains("subdag"), "{error}");
+        let mut tampered = proof.clone();
+        tampered.block_hash = Field::rand(rng).into();
+        let error = tampered.verify(&committee).unwrap_err().to_string();
+        assert!(error.contains("block hash"), "{error}");
+
+        // Ensure the proof roundtrips through bytes.
+        let bytes = proof.to_bytes_le().unwrap();
+        assert_eq!(proof, CommitteeProof::read_le(&bytes[..]).unwrap());
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, CryptoRng, FromBytes, Network, PrivateKey, Rng, Signature, ToBytes};
+
+use anyhow::Result;
+use std::io;
+
+/// A binding of the peer-to-peer identity of a validator to its consensus account, signed by the consensus account.
+///
+/// A validator may identify itself on the peer-to-peer network with a separate account from the one that signs
+/// its batches. The binding lets the peers recognize it as the committee member it is: the signature covers the
+/// peer-to-peer address, which the validator proves it holds in the handshake, so the binding cannot be replayed
+/// by another node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusBinding<N: Network> {
+    /// The signature of the peer-to-peer address, by the consensus account.
+    signature: Signature<N>,
+}
+
+impl<N: Network> ConsensusBinding<N> {
+    /// The domain of the signed bindings, which keeps them distinct from the other messages signed by the validator.
+    const DOMAIN: &'static [u8] = b"snarkos.consensus_binding";
+
+    /// Binds the given peer-to-peer address to the account of the given consensus private key.
+    pub fn new<R: Rng + CryptoRng>(
+        consensus_private_key: &PrivateKey<N>,
+        p2p_address: &Address<N>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let signature = Signature::sign_bytes(consensus_private_key, &Self::message(p2p_address)?, rng)?;
+        Ok(Self { signature })
+    }
+
+    /// Returns the signed message for the given peer-to-peer address, which is bound to the network.
+    fn message(p2p_address: &Address<N>) -> Result<Vec<u8>> {
+        Ok([&N::ID.to_le_bytes()[..], Self::DOMAIN, &p2p_address.to_bytes_le()?].concat())
+    }
+
+    /// Returns the address of the consensus account.
+    pub fn address(&self) -> Address<N> {
+        self.signature.to_address()
+    }
+
+    /// Returns `true` if the consensus account bound the given peer-to-peer address.
+    pub fn verify(&self, p2p_address: &Address<N>) -> bool {
+        Self::message(p2p_address).map_or(false, |message| self.signature.verify_bytes(&self.address(), &message))
+    }
+}
+
+impl<N: Network> ToBytes for ConsensusBinding<N> {
+    fn write_le<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.signature.write_le(writer)
+    }
+}
+
+impl<N: Network> FromBytes for ConsensusBinding<N> {
+    fn read_le<R: io::Read>(reader: R) -> io::Result<Self> {
+        Ok(Self { signature: Signature::read_le(reader)? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_consensus_binding() {
+        let rng = &mut TestRng::default();
+        let consensus_private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let p2p_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let binding = ConsensusBinding::new(&consensus_private_key, &p2p_address, rng).unwrap();
+
+        // Ensure the binding is signed by the consensus account, for the peer-to-peer address.
+        assert_eq!(binding.address(), Address::try_from(&consensus_private_key).unwrap());
+        assert!(binding.verify(&p2p_address));
+        // Ensure the binding does not hold for another peer-to-peer address.
+        let other_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        assert!(!binding.verify(&other_address));
+
+        // Ensure the binding roundtrips.
+        let bytes = binding.to_bytes_le().unwrap();
+        assert_eq!(ConsensusBinding::read_le(&bytes[..]).unwrap(), binding);
+    }
+}
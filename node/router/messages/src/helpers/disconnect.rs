@@ -14,7 +14,7 @@
 
 use snarkvm::prelude::{error, FromBytes, ToBytes};
 
-use std::io;
+use std::{fmt, io};
 
 /// The reason behind the node disconnecting from a peer.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -49,8 +49,52 @@ pub enum DisconnectReason {
     YouNeedToSyncFirst,
     /// The peer's listening port is closed.
     YourPortIsClosed(u16),
+    /// The peer is restricted by the node.
+    Banned,
+    /// The peer has exceeded the rate limits of the node.
+    Oversubscribed,
+    /// The node identity of the peer already holds the maximum number of connections, and this one is the oldest.
+    DuplicateConnection,
 }
 
+impl DisconnectReason {
+    /// Returns the name of the disconnect reason, without any associated data.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::ExceededForkRange => "ExceededForkRange",
+            Self::InvalidChallengeResponse => "InvalidChallengeResponse",
+            Self::InvalidForkDepth => "InvalidForkDepth",
+            Self::INeedToSyncFirst => "INeedToSyncFirst",
+            Self::NoReasonGiven => "NoReasonGiven",
+            Self::ProtocolViolation => "ProtocolViolation",
+            Self::OutdatedClientVersion => "OutdatedClientVersion",
+            Self::PeerHasDisconnected => "PeerHasDisconnected",
+            Self::PeerRefresh => "PeerRefresh",
+            Self::ShuttingDown => "ShuttingDown",
+            Self::SyncComplete => "SyncComplete",
+            Self::TooManyFailures => "TooManyFailures",
+            Self::TooManyPeers => "TooManyPeers",
+            Self::YouNeedToSyncFirst => "YouNeedToSyncFirst",
+            Self::YourPortIsClosed(..) => "YourPortIsClosed",
+            Self::Banned => "Banned",
+            Self::Oversubscribed => "Oversubscribed",
+            Self::DuplicateConnection => "DuplicateConnection",
+        }
+    }
+}
+
+impl fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::YourPortIsClosed(port) => write!(f, "{} ({port})", self.name()),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+/// The disconnect reason doubles as an error, so that message handlers can tag why a peer is dropped.
+impl std::error::Error for DisconnectReason {}
+
 impl ToBytes for DisconnectReason {
     fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         match self {
@@ -72,6 +116,9 @@ impl ToBytes for DisconnectReason {
                 14u8.write_le(&mut writer)?;
                 port.write_le(writer)
             }
+            Self::Banned => 15u8.write_le(writer),
+            Self::Oversubscribed => 16u8.write_le(writer),
+            Self::DuplicateConnection => 17u8.write_le(writer),
         }
     }
 }
@@ -97,6 +144,9 @@ impl FromBytes for DisconnectReason {
                 let port = u16::read_le(reader)?;
                 Ok(Self::YourPortIsClosed(port))
             }
+            15 => Ok(Self::Banned),
+            16 => Ok(Self::Oversubscribed),
+            17 => Ok(Self::DuplicateConnection),
             _ => Err(error("Invalid disconnect reason")),
         }
     }
@@ -13,10 +13,25 @@
 // limitations under the License.
 
 mod codec;
-pub use codec::MessageCodec;
+pub use codec::{MalformedFrame, MalformedFrameHandler, MessageCodec};
+
+mod committee_proof;
+pub use committee_proof::CommitteeProof;
+
+mod consensus_binding;
+pub use consensus_binding::ConsensusBinding;
 
 mod disconnect;
 pub use disconnect::DisconnectReason;
 
+mod node_id;
+pub use node_id::NodeId;
+
+mod node_info;
+pub use node_info::NodeInfo;
+
 mod node_type;
 pub use node_type::*;
+
+mod rest_endpoint;
+pub use rest_endpoint::RestEndpoint;
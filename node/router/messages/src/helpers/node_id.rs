@@ -0,0 +1,107 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, CryptoRng, FromBytes, Network, PrivateKey, Rng, Signature, ToBytes};
+
+use anyhow::Result;
+use std::io;
+
+/// A persistent network identity for a node, signed by the account of the node.
+///
+/// Unlike the socket address of a node, the node ID survives restarts and address changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeId<N: Network> {
+    /// The random identifier of the node.
+    id: u128,
+    /// The signature of the identifier, by the account of the node.
+    signature: Signature<N>,
+}
+
+impl<N: Network> NodeId<N> {
+    /// The domain of the signed identifiers, which keeps them distinct from the other messages signed by the node.
+    const DOMAIN: &'static [u8] = b"snarkos.node_id";
+
+    /// Samples a new node ID, and signs it with the given private key.
+    pub fn new<R: Rng + CryptoRng>(private_key: &PrivateKey<N>, rng: &mut R) -> Result<Self> {
+        let id = rng.gen();
+        let signature = Signature::sign_bytes(private_key, &Self::message(id), rng)?;
+        Ok(Self { id, signature })
+    }
+
+    /// Returns the signed message for the given identifier, which is bound to the network.
+    fn message(id: u128) -> Vec<u8> {
+        [&N::ID.to_le_bytes()[..], Self::DOMAIN, &id.to_le_bytes()].concat()
+    }
+
+    /// Returns the identifier of the node.
+    pub const fn id(&self) -> u128 {
+        self.id
+    }
+
+    /// Returns the signature of the identifier.
+    pub const fn signature(&self) -> &Signature<N> {
+        &self.signature
+    }
+
+    /// Returns `true` if the node ID was signed by the given address.
+    pub fn verify(&self, address: &Address<N>) -> bool {
+        self.signature.verify_bytes(address, &Self::message(self.id))
+    }
+}
+
+impl<N: Network> core::fmt::Display for NodeId<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:032x}", self.id)
+    }
+}
+
+impl<N: Network> ToBytes for NodeId<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.id.write_le(&mut writer)?;
+        self.signature.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for NodeId<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let id = u128::read_le(&mut reader)?;
+        let signature = Signature::read_le(&mut reader)?;
+        Ok(Self { id, signature })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_node_id() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let node_id = NodeId::new(&private_key, rng).unwrap();
+
+        // Ensure the node ID is signed by the account.
+        assert!(node_id.verify(&Address::try_from(&private_key).unwrap()));
+        // Ensure the node ID is not signed by another account.
+        let other_private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        assert!(!node_id.verify(&Address::try_from(&other_private_key).unwrap()));
+
+        // Ensure the node ID roundtrips.
+        let bytes = node_id.to_bytes_le().unwrap();
+        assert_eq!(NodeId::read_le(&bytes[..]).unwrap(), node_id);
+    }
+}
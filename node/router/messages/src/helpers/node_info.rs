@@ -0,0 +1,151 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{error, FromBytes, ToBytes};
+
+use serde::Serialize;
+use std::io;
+
+/// The software version and enabled features of a node, which it shares with its peers in every `Ping`.
+///
+/// Unlike the message version, which must match for peers to connect, the software version and
+/// features allow peers on different releases to tell which messages the other side understands.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeInfo {
+    /// The semantic version of the node software (e.g. `2.2.7`).
+    pub version: String,
+    /// The names of the features enabled on the node.
+    pub features: Vec<String>,
+}
+
+impl NodeInfo {
+    /// The features supported by this version of the node software.
+    pub const FEATURES: &'static [&'static str] = &["node_info", "committee_proof"];
+    /// The maximum length of the version, or of a feature name, in bytes.
+    pub const MAX_STRING_LENGTH: usize = 64;
+    /// The maximum number of features.
+    pub const MAX_FEATURES: usize = 32;
+
+    /// Returns the software version and features of this node.
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            features: Self::FEATURES.iter().map(|feature| feature.to_string()).collect(),
+        }
+    }
+
+    /// Returns the `(major, minor, patch)` version of the node software, if the version is well-formed.
+    pub fn semver(&self) -> Option<(u64, u64, u64)> {
+        // Ignore any pre-release or build metadata.
+        let core = self.version.split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+        let semver = (parts.next()??, parts.next()??, parts.next()??);
+        parts.next().is_none().then_some(semver)
+    }
+
+    /// Returns `true` if the node software is at least the given `(major, minor, patch)` version.
+    pub fn is_at_least(&self, version: (u64, u64, u64)) -> bool {
+        self.semver().map_or(false, |semver| semver >= version)
+    }
+
+    /// Returns `true` if the given feature is enabled on the node.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+
+    /// Writes the given string, with a `u8` length prefix.
+    pub(crate) fn write_string<W: io::Write>(string: &str, mut writer: W) -> io::Result<()> {
+        if string.len() > Self::MAX_STRING_LENGTH {
+            return Err(error("The node info contains a string that is too long"));
+        }
+        (string.len() as u8).write_le(&mut writer)?;
+        writer.write_all(string.as_bytes())
+    }
+
+    /// Reads a string with a `u8` length prefix.
+    pub(crate) fn read_string<R: io::Read>(mut reader: R) -> io::Result<String> {
+        let length = u8::read_le(&mut reader)? as usize;
+        if length > Self::MAX_STRING_LENGTH {
+            return Err(error("The node info contains a string that is too long"));
+        }
+        let mut bytes = vec![0u8; length];
+        reader.read_exact(&mut bytes)?;
+        String::from_utf8(bytes).map_err(|_| error("The node info contains an invalid string"))
+    }
+}
+
+impl ToBytes for NodeInfo {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        Self::write_string(&self.version, &mut writer)?;
+        if self.features.len() > Self::MAX_FEATURES {
+            return Err(error("The node info contains too many features"));
+        }
+        (self.features.len() as u8).write_le(&mut writer)?;
+        for feature in &self.features {
+            Self::write_string(feature, &mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromBytes for NodeInfo {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let version = Self::read_string(&mut reader)?;
+        let num_features = u8::read_le(&mut reader)? as usize;
+        if num_features > Self::MAX_FEATURES {
+            return Err(error("The node info contains too many features"));
+        }
+        let features = (0..num_features).map(|_| Self::read_string(&mut reader)).collect::<io::Result<Vec<_>>>()?;
+        Ok(Self { version, features })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_info() {
+        let node_info = NodeInfo::current();
+        // Ensure the current version is well-formed.
+        assert!(node_info.semver().is_some());
+        assert!(node_info.is_at_least((2, 0, 0)));
+        assert!(!node_info.is_at_least((u64::MAX, 0, 0)));
+        assert!(node_info.has_feature("node_info"));
+
+        // Ensure the node info roundtrips.
+        let bytes = node_info.to_bytes_le().unwrap();
+        assert_eq!(NodeInfo::read_le(&bytes[..]).unwrap(), node_info);
+    }
+
+    #[test]
+    fn test_node_info_semver() {
+        let node_info = |version: &str| NodeInfo { version: version.to_string(), features: vec![] };
+        assert_eq!(node_info("2.2.7").semver(), Some((2, 2, 7)));
+        assert_eq!(node_info("2.3.0-rc.1+build").semver(), Some((2, 3, 0)));
+        assert_eq!(node_info("2.3").semver(), None);
+        assert_eq!(node_info("2.3.0.1").semver(), None);
+        assert_eq!(node_info("latest").semver(), None);
+    }
+
+    #[test]
+    fn test_node_info_limits() {
+        // Ensure oversized strings are rejected.
+        let node_info = NodeInfo { version: "1".repeat(NodeInfo::MAX_STRING_LENGTH + 1), features: vec![] };
+        assert!(node_info.to_bytes_le().is_err());
+        // Ensure too many features are rejected.
+        let node_info = NodeInfo { version: "2.2.7".to_string(), features: vec!["f".to_string(); 33] };
+        assert!(node_info.to_bytes_le().is_err());
+    }
+}
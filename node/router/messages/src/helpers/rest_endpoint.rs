@@ -0,0 +1,132 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{error, Address, CryptoRng, FromBytes, Network, PrivateKey, Rng, Signature, ToBytes};
+
+use anyhow::{ensure, Result};
+use serde::Serialize;
+use std::io;
+
+/// The public REST endpoint of a node, signed by the account of the node.
+///
+/// The signature covers the domain, the network ID and the URL, so that a node cannot advertise an endpoint on behalf
+/// of another account, and wallets that learn of the endpoint can check who operates it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct RestEndpoint<N: Network> {
+    /// The public URL of the REST server.
+    url: String,
+    /// The signature of the URL, by the account of the node.
+    signature: Signature<N>,
+}
+
+impl<N: Network> RestEndpoint<N> {
+    /// The maximum length of the URL, in bytes.
+    pub const MAX_URL_LENGTH: usize = 255;
+    /// The domain of the signed URLs, which keeps them distinct from the other messages signed by the node.
+    const DOMAIN: &'static [u8] = b"snarkos.rest_endpoint";
+
+    /// Signs the given URL with the given private key.
+    pub fn new<R: Rng + CryptoRng>(private_key: &PrivateKey<N>, url: String, rng: &mut R) -> Result<Self> {
+        Self::check_url(&url)?;
+        let signature = Signature::sign_bytes(private_key, &Self::message(&url), rng)?;
+        Ok(Self { url, signature })
+    }
+
+    /// Ensures the given URL is an HTTP(S) URL within the maximum length.
+    fn check_url(url: &str) -> Result<()> {
+        ensure!(url.len() <= Self::MAX_URL_LENGTH, "The REST URL exceeds {} bytes", Self::MAX_URL_LENGTH);
+        ensure!(
+            url.starts_with("http://") || url.starts_with("https://"),
+            "The REST URL '{url}' must start with 'http://' or 'https://'"
+        );
+        Ok(())
+    }
+
+    /// Returns the signed message for the given URL.
+    fn message(url: &str) -> Vec<u8> {
+        [&N::ID.to_le_bytes()[..], Self::DOMAIN, url.as_bytes()].concat()
+    }
+
+    /// Returns the public URL of the REST server.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the address of the account that signed the endpoint.
+    pub fn address(&self) -> Address<N> {
+        self.signature.to_address()
+    }
+
+    /// Returns `true` if the endpoint was signed by the given address.
+    pub fn verify(&self, address: &Address<N>) -> bool {
+        Self::check_url(&self.url).is_ok() && self.signature.verify_bytes(address, &Self::message(&self.url))
+    }
+}
+
+impl<N: Network> ToBytes for RestEndpoint<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        if self.url.len() > Self::MAX_URL_LENGTH {
+            return Err(error("The REST URL is too long"));
+        }
+        (self.url.len() as u8).write_le(&mut writer)?;
+        writer.write_all(self.url.as_bytes())?;
+        self.signature.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for RestEndpoint<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let length = u8::read_le(&mut reader)? as usize;
+        let mut bytes = vec![0u8; length];
+        reader.read_exact(&mut bytes)?;
+        let url = String::from_utf8(bytes).map_err(|_| error("The REST URL is not valid UTF-8"))?;
+        let signature = Signature::read_le(&mut reader)?;
+        Ok(Self { url, signature })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_rest_endpoint() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let address = Address::try_from(&private_key).unwrap();
+        let endpoint = RestEndpoint::new(&private_key, "https://api.example.com".to_string(), rng).unwrap();
+
+        // Ensure the endpoint is signed by the account.
+        assert!(endpoint.verify(&address));
+        assert_eq!(endpoint.address(), address);
+        // Ensure the endpoint is not signed by another account.
+        let other_private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        assert!(!endpoint.verify(&Address::try_from(&other_private_key).unwrap()));
+        // Ensure a spoofed URL does not verify.
+        let spoofed = RestEndpoint { url: "https://evil.example.com".to_string(), ..endpoint.clone() };
+        assert!(!spoofed.verify(&address));
+
+        // Ensure the endpoint roundtrips.
+        let bytes = endpoint.to_bytes_le().unwrap();
+        assert_eq!(RestEndpoint::read_le(&bytes[..]).unwrap(), endpoint);
+
+        // Ensure malformed URLs are rejected.
+        assert!(RestEndpoint::new(&private_key, "ftp://api.example.com".to_string(), rng).is_err());
+        assert!(RestEndpoint::new(&private_key, format!("https://{}", "a".repeat(255)), rng).is_err());
+    }
+}
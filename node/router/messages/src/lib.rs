@@ -32,6 +32,18 @@ pub use challenge_request::ChallengeRequest;
 mod challenge_response;
 pub use challenge_response::ChallengeResponse;
 
+mod checkpoint_request;
+pub use checkpoint_request::CheckpointRequest;
+
+mod checkpoint_response;
+pub use checkpoint_response::CheckpointResponse;
+
+mod committee_request;
+pub use committee_request::CommitteeRequest;
+
+mod committee_response;
+pub use committee_response::CommitteeResponse;
+
 mod disconnect;
 pub use disconnect::Disconnect;
 
@@ -42,7 +54,7 @@ mod peer_response;
 pub use peer_response::PeerResponse;
 
 mod ping;
-pub use ping::Ping;
+pub use ping::{now_in_millis, Ping};
 
 mod pong;
 pub use pong::Pong;
@@ -53,15 +65,21 @@ pub use puzzle_request::PuzzleRequest;
 mod puzzle_response;
 pub use puzzle_response::PuzzleResponse;
 
+mod stem_transaction;
+pub use stem_transaction::StemTransaction;
+
 mod unconfirmed_solution;
 pub use unconfirmed_solution::UnconfirmedSolution;
 
 mod unconfirmed_transaction;
 pub use unconfirmed_transaction::UnconfirmedTransaction;
 
+mod unconfirmed_transactions;
+pub use unconfirmed_transactions::{DataTransactions, UnconfirmedTransactions};
+
 pub use snarkos_node_bft_events::DataBlocks;
 
-use snarkos_node_sync_locators::BlockLocators;
+use snarkos_node_sync_locators::{BlockLocators, CheckpointCertificate};
 use snarkvm::prelude::{
     block::{Header, Transaction},
     coinbase::{EpochChallenge, ProverSolution, PuzzleCommitment},
@@ -101,6 +119,12 @@ pub enum Message<N: Network> {
     PuzzleResponse(PuzzleResponse<N>),
     UnconfirmedSolution(UnconfirmedSolution<N>),
     UnconfirmedTransaction(UnconfirmedTransaction<N>),
+    CommitteeRequest(CommitteeRequest),
+    CommitteeResponse(CommitteeResponse<N>),
+    UnconfirmedTransactions(UnconfirmedTransactions<N>),
+    StemTransaction(StemTransaction<N>),
+    CheckpointRequest(CheckpointRequest),
+    CheckpointResponse(CheckpointResponse<N>),
 }
 
 impl<N: Network> From<DisconnectReason> for Message<N> {
@@ -111,7 +135,16 @@ impl<N: Network> From<DisconnectReason> for Message<N> {
 
 impl<N: Network> Message<N> {
     /// The version of the network protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 15;
+    pub const VERSION: u32 = 27;
+
+    /// The maximum size of a serialized block in a `BlockResponse`, in bytes.
+    pub const MAXIMUM_BLOCK_SIZE: usize = 16 * 1024 * 1024; // 16 MiB
+    /// The maximum size of a serialized block header in a `PuzzleResponse`, in bytes.
+    pub const MAXIMUM_BLOCK_HEADER_SIZE: usize = 64 * 1024; // 64 KiB
+    /// The maximum size of a serialized solution in an `UnconfirmedSolution`, in bytes.
+    pub const MAXIMUM_SOLUTION_SIZE: usize = 64 * 1024; // 64 KiB
+    /// The maximum size of a serialized transaction in an `UnconfirmedTransaction`, in bytes.
+    pub const MAXIMUM_TRANSACTION_SIZE: usize = 1024 * 1024; // 1 MiB
 
     /// Returns the message name.
     #[inline]
@@ -130,6 +163,12 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(message) => message.name(),
             Self::UnconfirmedSolution(message) => message.name(),
             Self::UnconfirmedTransaction(message) => message.name(),
+            Self::CommitteeRequest(message) => message.name(),
+            Self::CommitteeResponse(message) => message.name(),
+            Self::UnconfirmedTransactions(message) => message.name(),
+            Self::StemTransaction(message) => message.name(),
+            Self::CheckpointRequest(message) => message.name(),
+            Self::CheckpointResponse(message) => message.name(),
         }
     }
 
@@ -150,6 +189,12 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(..) => 10,
             Self::UnconfirmedSolution(..) => 11,
             Self::UnconfirmedTransaction(..) => 12,
+            Self::CommitteeRequest(..) => 13,
+            Self::CommitteeResponse(..) => 14,
+            Self::UnconfirmedTransactions(..) => 15,
+            Self::StemTransaction(..) => 16,
+            Self::CheckpointRequest(..) => 17,
+            Self::CheckpointResponse(..) => 18,
         }
     }
 }
@@ -172,6 +217,12 @@ impl<N: Network> ToBytes for Message<N> {
             Self::PuzzleResponse(message) => message.write_le(writer),
             Self::UnconfirmedSolution(message) => message.write_le(writer),
             Self::UnconfirmedTransaction(message) => message.write_le(writer),
+            Self::CommitteeRequest(message) => message.write_le(writer),
+            Self::CommitteeResponse(message) => message.write_le(writer),
+            Self::UnconfirmedTransactions(message) => message.write_le(writer),
+            Self::StemTransaction(message) => message.write_le(writer),
+            Self::CheckpointRequest(message) => message.write_le(writer),
+            Self::CheckpointResponse(message) => message.write_le(writer),
         }
     }
 }
@@ -198,7 +249,13 @@ impl<N: Network> FromBytes for Message<N> {
             10 => Self::PuzzleResponse(PuzzleResponse::read_le(&mut reader)?),
             11 => Self::UnconfirmedSolution(UnconfirmedSolution::read_le(&mut reader)?),
             12 => Self::UnconfirmedTransaction(UnconfirmedTransaction::read_le(&mut reader)?),
-            13.. => return Err(error("Unknown message ID {id}")),
+            13 => Self::CommitteeRequest(CommitteeRequest::read_le(&mut reader)?),
+            14 => Self::CommitteeResponse(CommitteeResponse::read_le(&mut reader)?),
+            15 => Self::UnconfirmedTransactions(UnconfirmedTransactions::read_le(&mut reader)?),
+            16 => Self::StemTransaction(StemTransaction::read_le(&mut reader)?),
+            17 => Self::CheckpointRequest(CheckpointRequest::read_le(&mut reader)?),
+            18 => Self::CheckpointResponse(CheckpointResponse::read_le(&mut reader)?),
+            19.. => return Err(error("Unknown message ID {id}")),
         };
 
         // Ensure that there are no "dangling" bytes.
@@ -14,12 +14,51 @@
 
 use super::*;
 
-use snarkvm::prelude::{FromBytes, ToBytes};
+use snarkvm::prelude::{error, FromBytes, ToBytes};
 
 use std::borrow::Cow;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct PeerRequest;
+/// A request for the peers of the receiver, restricted to the peers that the requester needs.
+///
+/// The receiver only shares its connected peers whose node type is among the requested node types,
+/// and which have all of the requested features enabled. A request without node types and features
+/// matches all peers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeerRequest {
+    /// The node types of the requested peers, or all node types if empty.
+    pub node_types: Vec<NodeType>,
+    /// The names of the features that the requested peers must have enabled.
+    pub features: Vec<String>,
+}
+
+impl PeerRequest {
+    /// Initializes a new peer request for the peers of the given node types, with the given features enabled.
+    pub fn new(node_types: Vec<NodeType>, features: Vec<String>) -> Self {
+        Self { node_types, features }
+    }
+
+    /// Initializes a new peer request for the peers that are useful to a node of the given node type.
+    /// Provers only exchange messages with the nodes that have the ledger, so they only request those.
+    pub fn for_node_type(node_type: NodeType) -> Self {
+        match node_type {
+            NodeType::Prover => Self::new(vec![NodeType::Client, NodeType::Validator], vec![]),
+            NodeType::Client | NodeType::Validator => Self::default(),
+        }
+    }
+
+    /// Returns `true` if a peer of the given node type, and with the given node info, matches the request.
+    pub fn matches(&self, node_type: NodeType, node_info: Option<&NodeInfo>) -> bool {
+        // Ensure the node type is requested.
+        if !self.node_types.is_empty() && !self.node_types.contains(&node_type) {
+            return false;
+        }
+        // Ensure the requested features are enabled on the peer.
+        match node_info {
+            Some(node_info) => self.features.iter().all(|feature| node_info.has_feature(feature)),
+            None => self.features.is_empty(),
+        }
+    }
+}
 
 impl MessageTrait for PeerRequest {
     /// Returns the message name.
@@ -30,30 +69,94 @@ impl MessageTrait for PeerRequest {
 }
 
 impl ToBytes for PeerRequest {
-    fn write_le<W: io::Write>(&self, _writer: W) -> io::Result<()> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Return error if the number of node types or features exceeds the maximum.
+        if self.node_types.len() > u8::MAX as usize {
+            return Err(error(format!("Too many node types: {}", self.node_types.len())));
+        }
+        if self.features.len() > NodeInfo::MAX_FEATURES {
+            return Err(error(format!("Too many features: {}", self.features.len())));
+        }
+
+        (self.node_types.len() as u8).write_le(&mut writer)?;
+        for node_type in &self.node_types {
+            node_type.write_le(&mut writer)?;
+        }
+        (self.features.len() as u8).write_le(&mut writer)?;
+        for feature in &self.features {
+            NodeInfo::write_string(feature, &mut writer)?;
+        }
         Ok(())
     }
 }
 
 impl FromBytes for PeerRequest {
-    fn read_le<R: io::Read>(_reader: R) -> io::Result<Self> {
-        Ok(Self)
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let num_node_types = u8::read_le(&mut reader)?;
+        let mut node_types = Vec::with_capacity(num_node_types as usize);
+        for _ in 0..num_node_types {
+            node_types.push(NodeType::read_le(&mut reader)?);
+        }
+        let num_features = u8::read_le(&mut reader)? as usize;
+        if num_features > NodeInfo::MAX_FEATURES {
+            return Err(error(format!("Too many features: {num_features}")));
+        }
+        let mut features = Vec::with_capacity(num_features);
+        for _ in 0..num_features {
+            features.push(NodeInfo::read_string(&mut reader)?);
+        }
+
+        Ok(Self { node_types, features })
     }
 }
 
 #[cfg(test)]
-pub mod tests {
-    use crate::PeerRequest;
+pub mod prop_tests {
+    use crate::{challenge_request::prop_tests::any_node_type, NodeInfo, NodeType, PeerRequest};
     use snarkvm::utilities::{FromBytes, ToBytes};
 
     use bytes::{Buf, BufMut, BytesMut};
+    use proptest::{
+        collection::vec,
+        prelude::{BoxedStrategy, Strategy},
+    };
+    use test_strategy::proptest;
 
-    #[test]
-    fn peer_request_roundtrip() {
-        let peer_request = PeerRequest;
+    pub fn any_peer_request() -> BoxedStrategy<PeerRequest> {
+        (vec(any_node_type(), 0..3), vec("[a-z_]{1,16}", 0..4))
+            .prop_map(|(node_types, features)| PeerRequest { node_types, features })
+            .boxed()
+    }
+
+    #[proptest]
+    fn peer_request_roundtrip(#[strategy(any_peer_request())] peer_request: PeerRequest) {
         let mut bytes = BytesMut::default().writer();
         peer_request.write_le(&mut bytes).unwrap();
         let decoded = PeerRequest::read_le(&mut bytes.into_inner().reader()).unwrap();
         assert_eq!(decoded, peer_request);
     }
+
+    #[test]
+    fn test_peer_request_matches() {
+        let node_info = NodeInfo::current();
+
+        // Ensure an unrestricted request matches all peers.
+        let request = PeerRequest::default();
+        assert!(request.matches(NodeType::Prover, None));
+        assert!(request.matches(NodeType::Validator, Some(&node_info)));
+
+        // Ensure a prover only requests the nodes that have the ledger.
+        let request = PeerRequest::for_node_type(NodeType::Prover);
+        assert!(request.matches(NodeType::Client, None));
+        assert!(request.matches(NodeType::Validator, None));
+        assert!(!request.matches(NodeType::Prover, Some(&node_info)));
+
+        // Ensure the requested features must all be enabled.
+        let request = PeerRequest::new(vec![NodeType::Validator], vec!["node_info".to_string()]);
+        assert!(request.matches(NodeType::Validator, Some(&node_info)));
+        assert!(!request.matches(NodeType::Validator, None));
+        assert!(!request.matches(NodeType::Client, Some(&node_info)));
+        let request = PeerRequest::new(vec![], vec!["node_info".to_string(), "unknown".to_string()]);
+        assert!(!request.matches(NodeType::Validator, Some(&node_info)));
+    }
 }
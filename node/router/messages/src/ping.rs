@@ -24,6 +24,10 @@ pub struct Ping<N: Network> {
     pub version: u32,
     pub node_type: NodeType,
     pub block_locators: Option<BlockLocators<N>>,
+    pub node_info: NodeInfo,
+    pub rest_endpoint: Option<RestEndpoint<N>>,
+    /// The UTC timestamp of the sender when the ping was sent, in milliseconds.
+    pub timestamp: i64,
 }
 
 impl<N: Network> MessageTrait for Ping<N> {
@@ -55,6 +59,14 @@ impl<N: Network> ToBytes for Ping<N> {
         } else {
             0u8.write_le(&mut writer)?;
         }
+        self.node_info.write_le(&mut writer)?;
+        if let Some(rest_endpoint) = &self.rest_endpoint {
+            1u8.write_le(&mut writer)?;
+            rest_endpoint.write_le(&mut writer)?;
+        } else {
+            0u8.write_le(&mut writer)?;
+        }
+        self.timestamp.write_le(&mut writer)?;
 
         Ok(())
     }
@@ -67,8 +79,8 @@ impl<N: Network> FromBytes for Ping<N> {
 
         let selector = u8::read_le(&mut reader)?;
 
-        if selector == 0 {
-            Ok(Self { version, node_type, block_locators: None })
+        let block_locators = if selector == 0 {
+            None
         } else if selector == 1 {
             let mut recents = IndexMap::new();
             let num_recents = u32::read_le(&mut reader)?;
@@ -86,24 +98,47 @@ impl<N: Network> FromBytes for Ping<N> {
                 checkpoints.insert(height, hash);
             }
 
-            let block_locators = Some(BlockLocators { recents, checkpoints });
-
-            Ok(Self { version, node_type, block_locators })
+            Some(BlockLocators { recents, checkpoints })
         } else {
-            Err(error("Invalid selector of optional block locators in ping message"))
-        }
+            return Err(error("Invalid selector of optional block locators in ping message"));
+        };
+        let node_info = NodeInfo::read_le(&mut reader)?;
+        let rest_endpoint = match u8::read_le(&mut reader)? {
+            0 => None,
+            1 => Some(RestEndpoint::read_le(&mut reader)?),
+            _ => return Err(error("Invalid selector of optional REST endpoint in ping message")),
+        };
+        let timestamp = i64::read_le(&mut reader)?;
+
+        Ok(Self { version, node_type, block_locators, node_info, rest_endpoint, timestamp })
     }
 }
 
 impl<N: Network> Ping<N> {
-    pub fn new(node_type: NodeType, block_locators: Option<BlockLocators<N>>) -> Self {
-        Self { version: <Message<N>>::VERSION, node_type, block_locators }
+    pub fn new(
+        node_type: NodeType,
+        block_locators: Option<BlockLocators<N>>,
+        rest_endpoint: Option<RestEndpoint<N>>,
+    ) -> Self {
+        Self {
+            version: <Message<N>>::VERSION,
+            node_type,
+            block_locators,
+            node_info: NodeInfo::current(),
+            rest_endpoint,
+            timestamp: now_in_millis(),
+        }
     }
 }
 
+/// Returns the current UTC timestamp, in milliseconds.
+pub fn now_in_millis() -> i64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
+
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::{challenge_request::prop_tests::any_node_type, Ping};
+    use crate::{challenge_request::prop_tests::any_node_type, NodeInfo, Ping};
     use snarkos_node_sync_locators::{test_helpers::sample_block_locators, BlockLocators};
     use snarkvm::utilities::{FromBytes, ToBytes};
 
@@ -118,8 +153,15 @@ pub mod prop_tests {
     }
 
     pub fn any_ping() -> BoxedStrategy<Ping<CurrentNetwork>> {
-        (any::<u32>(), any_block_locators(), any_node_type())
-            .prop_map(|(version, bls, node_type)| Ping { version, block_locators: Some(bls), node_type })
+        (any::<u32>(), any_block_locators(), any_node_type(), any::<i64>())
+            .prop_map(|(version, bls, node_type, timestamp)| Ping {
+                version,
+                block_locators: Some(bls),
+                node_type,
+                node_info: NodeInfo::current(),
+                rest_endpoint: None,
+                timestamp,
+            })
             .boxed()
     }
 
@@ -21,6 +21,17 @@ use std::borrow::Cow;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Pong {
     pub is_fork: Option<bool>,
+    /// The timestamp of the ping that this pong answers, in milliseconds, as sent by the receiver of the pong.
+    pub ping_timestamp: i64,
+    /// The UTC timestamp of the sender when the pong was sent, in milliseconds.
+    pub timestamp: i64,
+}
+
+impl Pong {
+    /// Initializes a new pong, in response to the ping with the given timestamp.
+    pub fn new(is_fork: Option<bool>, ping_timestamp: i64) -> Self {
+        Self { is_fork, ping_timestamp, timestamp: now_in_millis() }
+    }
 }
 
 impl MessageTrait for Pong {
@@ -32,14 +43,16 @@ impl MessageTrait for Pong {
 }
 
 impl ToBytes for Pong {
-    fn write_le<W: io::Write>(&self, writer: W) -> io::Result<()> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         let serialized_is_fork: u8 = match self.is_fork {
             Some(true) => 0,
             Some(false) => 1,
             None => 2,
         };
 
-        serialized_is_fork.write_le(writer)
+        serialized_is_fork.write_le(&mut writer)?;
+        self.ping_timestamp.write_le(&mut writer)?;
+        self.timestamp.write_le(&mut writer)
     }
 }
 
@@ -51,8 +64,10 @@ impl FromBytes for Pong {
             2 => None,
             _ => return Err(error("Invalid 'Pong' message")),
         };
+        let ping_timestamp = i64::read_le(&mut reader)?;
+        let timestamp = i64::read_le(&mut reader)?;
 
-        Ok(Self { is_fork })
+        Ok(Self { is_fork, ping_timestamp, timestamp })
     }
 }
 
@@ -69,7 +84,9 @@ pub mod tests {
     use test_strategy::proptest;
 
     pub fn any_pong() -> BoxedStrategy<Pong> {
-        of(any::<bool>()).prop_map(|is_fork| Pong { is_fork }).boxed()
+        (of(any::<bool>()), any::<i64>(), any::<i64>())
+            .prop_map(|(is_fork, ping_timestamp, timestamp)| Pong { is_fork, ping_timestamp, timestamp })
+            .boxed()
     }
 
     #[proptest]
@@ -0,0 +1,96 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::{
+    ledger::narwhal::Data,
+    prelude::{FromBytes, ToBytes},
+};
+
+use std::borrow::Cow;
+
+/// An unconfirmed transaction in the stem phase of the Dandelion relay, which is forwarded to a single peer
+/// (instead of being broadcast), in order to obscure the IP address from which the transaction originated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StemTransaction<N: Network> {
+    pub transaction_id: N::TransactionID,
+    pub transaction: Data<Transaction<N>>,
+}
+
+impl<N: Network> From<UnconfirmedTransaction<N>> for StemTransaction<N> {
+    /// Initializes a new `StemTransaction` message.
+    fn from(message: UnconfirmedTransaction<N>) -> Self {
+        Self { transaction_id: message.transaction_id, transaction: message.transaction }
+    }
+}
+
+impl<N: Network> From<StemTransaction<N>> for UnconfirmedTransaction<N> {
+    /// Initializes a new `UnconfirmedTransaction` message, to broadcast the stem transaction.
+    fn from(message: StemTransaction<N>) -> Self {
+        Self { transaction_id: message.transaction_id, transaction: message.transaction }
+    }
+}
+
+impl<N: Network> MessageTrait for StemTransaction<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "StemTransaction".into()
+    }
+}
+
+impl<N: Network> ToBytes for StemTransaction<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.transaction_id.write_le(&mut writer)?;
+        self.transaction.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for StemTransaction<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        Ok(Self { transaction_id: N::TransactionID::read_le(&mut reader)?, transaction: Data::read_le(reader)? })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{unconfirmed_transaction::prop_tests::any_unconfirmed_transaction, StemTransaction};
+    use snarkvm::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_stem_transaction() -> BoxedStrategy<StemTransaction<CurrentNetwork>> {
+        any_unconfirmed_transaction().prop_map(StemTransaction::from).boxed()
+    }
+
+    #[proptest]
+    fn stem_transaction_roundtrip(#[strategy(any_stem_transaction())] original: StemTransaction<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        StemTransaction::write_le(&original, &mut buf).unwrap();
+
+        let deserialized: StemTransaction<CurrentNetwork> =
+            StemTransaction::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original.transaction_id, deserialized.transaction_id);
+        assert_eq!(
+            original.transaction.deserialize_blocking().unwrap(),
+            deserialized.transaction.deserialize_blocking().unwrap(),
+        );
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::{
+    ledger::narwhal::Data,
+    prelude::{FromBytes, ToBytes},
+};
+
+use std::borrow::Cow;
+
+/// A batch of unconfirmed transactions, which is gossiped at once, e.g. when it is submitted to the REST server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnconfirmedTransactions<N: Network> {
+    pub transaction_ids: Vec<N::TransactionID>,
+    pub transactions: Data<DataTransactions<N>>,
+}
+
+impl<N: Network> From<Vec<Transaction<N>>> for UnconfirmedTransactions<N> {
+    /// Initializes a new `UnconfirmedTransactions` message.
+    fn from(transactions: Vec<Transaction<N>>) -> Self {
+        let transaction_ids = transactions.iter().map(|transaction| transaction.id()).collect();
+        Self { transaction_ids, transactions: Data::Object(DataTransactions(transactions)) }
+    }
+}
+
+impl<N: Network> MessageTrait for UnconfirmedTransactions<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("UnconfirmedTransactions ({})", self.transaction_ids.len()).into()
+    }
+}
+
+impl<N: Network> ToBytes for UnconfirmedTransactions<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Ensure that the number of transactions is within the allowed range.
+        if self.transaction_ids.len() > DataTransactions::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS as usize {
+            return Err(error("Unconfirmed transactions exceed the maximum number of transactions"));
+        }
+        (self.transaction_ids.len() as u8).write_le(&mut writer)?;
+        self.transaction_ids.iter().try_for_each(|transaction_id| transaction_id.write_le(&mut writer))?;
+        self.transactions.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for UnconfirmedTransactions<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let num_transactions = u8::read_le(&mut reader)?;
+        // Ensure that the number of transactions is within the allowed range.
+        if num_transactions > DataTransactions::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+            return Err(error("Unconfirmed transactions exceed the maximum number of transactions"));
+        }
+        let transaction_ids =
+            (0..num_transactions).map(|_| N::TransactionID::read_le(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+        let transactions = Data::read_le(reader)?;
+        Ok(Self { transaction_ids, transactions })
+    }
+}
+
+/// A wrapper for a list of transactions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataTransactions<N: Network>(pub Vec<Transaction<N>>);
+
+impl<N: Network> DataTransactions<N> {
+    /// The maximum number of transactions that can be sent in a single message.
+    pub const MAXIMUM_NUMBER_OF_TRANSACTIONS: u8 = 32;
+}
+
+impl<N: Network> std::ops::Deref for DataTransactions<N> {
+    type Target = Vec<Transaction<N>>;
+
+    /// Returns the list of transactions.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<N: Network> ToBytes for DataTransactions<N> {
+    /// Writes the transactions to the given writer.
+    #[inline]
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Prepare the number of transactions.
+        let num_transactions = self.0.len();
+        // Ensure that the number of transactions is within the allowed range.
+        if num_transactions > Self::MAXIMUM_NUMBER_OF_TRANSACTIONS as usize {
+            return Err(error("Unconfirmed transactions exceed the maximum number of transactions"));
+        }
+        // Write the number of transactions.
+        (num_transactions as u8).write_le(&mut writer)?;
+        // Write the transactions.
+        self.0.iter().try_for_each(|transaction| transaction.write_le(&mut writer))
+    }
+}
+
+impl<N: Network> FromBytes for DataTransactions<N> {
+    /// Reads the transactions from the given reader.
+    #[inline]
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        // Read the number of transactions.
+        let num_transactions = u8::read_le(&mut reader)?;
+        // Ensure that the number of transactions is within the allowed range.
+        if num_transactions > Self::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+            return Err(error("Unconfirmed transactions exceed the maximum number of transactions"));
+        }
+        // Read the transactions.
+        let transactions =
+            (0..num_transactions).map(|_| Transaction::read_le(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(transactions))
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{unconfirmed_transaction::prop_tests::any_transaction, DataTransactions, UnconfirmedTransactions};
+    use snarkvm::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::{
+        collection::vec,
+        prelude::{BoxedStrategy, Strategy},
+    };
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_unconfirmed_transactions() -> BoxedStrategy<UnconfirmedTransactions<CurrentNetwork>> {
+        vec(any_transaction(), 0..=2).prop_map(UnconfirmedTransactions::from).boxed()
+    }
+
+    #[proptest]
+    fn unconfirmed_transactions_roundtrip(
+        #[strategy(any_unconfirmed_transactions())] original: UnconfirmedTransactions<CurrentNetwork>,
+    ) {
+        let mut buf = BytesMut::default().writer();
+        UnconfirmedTransactions::write_le(&original, &mut buf).unwrap();
+
+        let deserialized: UnconfirmedTransactions<CurrentNetwork> =
+            UnconfirmedTransactions::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original.transaction_ids, deserialized.transaction_ids);
+        assert_eq!(
+            original.transactions.deserialize_blocking().unwrap(),
+            deserialized.transactions.deserialize_blocking().unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_maximum_number_of_transactions() {
+        let transactions = DataTransactions::<CurrentNetwork>(vec![]);
+        assert!(transactions.to_bytes_le().is_ok());
+        // The number of transactions is checked before reading any of them.
+        let bytes = [DataTransactions::<CurrentNetwork>::MAXIMUM_NUMBER_OF_TRANSACTIONS + 1];
+        assert!(DataTransactions::<CurrentNetwork>::from_bytes_le(&bytes).is_err());
+    }
+}
@@ -13,20 +13,21 @@
 // limitations under the License.
 
 use crate::{
-    messages::{ChallengeRequest, ChallengeResponse, DisconnectReason, Message, MessageCodec, MessageTrait},
+    messages::{ChallengeRequest, ChallengeResponse, DisconnectReason, Message, MessageCodec, MessageTrait, NodeType},
+    record_disconnect_sent,
+    AuditEvent,
     Peer,
     Router,
 };
-use snarkos_node_tcp::{ConnectionSide, Tcp, P2P};
+use snarkos_node_tcp::{set_keepalive, ConnectionSide, Tcp, P2P};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Header, error, Address, Network},
 };
 
-use anyhow::{bail, Result};
 use futures::SinkExt;
 use rand::{rngs::OsRng, Rng};
-use std::{io, net::SocketAddr};
+use std::{io, net::SocketAddr, time::Duration};
 use tokio::net::TcpStream;
 use tokio_stream::StreamExt;
 use tokio_util::codec::Framed;
@@ -49,8 +50,9 @@ macro_rules! expect_message {
                 data
             }
             // Received a disconnect message, abort.
-            Some(Message::Disconnect(reason)) => {
-                return Err(error(format!("'{}' disconnected: {reason:?}", $peer_addr)))
+            Some(Message::Disconnect(disconnect)) => {
+                $crate::record_disconnect_received(disconnect.reason);
+                return Err(error(format!("'{}' disconnected: {}", $peer_addr, disconnect.reason)))
             }
             // Received an unexpected message, abort.
             Some(ty) => {
@@ -76,6 +78,10 @@ async fn send<N: Network>(
     message: Message<N>,
 ) -> io::Result<()> {
     trace!("Sending '{}' to '{peer_addr}'", message.name());
+    // If the message type is a disconnect, record its reason.
+    if let Message::Disconnect(disconnect) = &message {
+        record_disconnect_sent(disconnect.reason);
+    }
     framed.send(message).await
 }
 
@@ -87,11 +93,25 @@ impl<N: Network> Router<N> {
         stream: &'a mut TcpStream,
         peer_side: ConnectionSide,
         genesis_header: Header<N>,
+        latest_height: u32,
     ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+        // Record the latest block height of the node, which is advertised to the peer.
+        self.set_latest_height(latest_height);
+        // Record the genesis block header, with which the candidate peers are verified.
+        let _ = self.genesis_header.set(genesis_header);
+
+        // Enable TCP keepalive, so that the operating system fails the connection if the peer vanishes.
+        if let Some(keepalive_in_secs) = self.liveness().keepalive_in_secs {
+            if let Err(error) = set_keepalive(stream, Duration::from_secs(keepalive_in_secs)) {
+                warn!("Unable to enable TCP keepalive for '{peer_addr}' - {error}");
+            }
+        }
+
         // If this is an inbound connection, we log it, but don't know the listening address yet.
         // Otherwise, we can immediately register the listening address.
         let mut peer_ip = if peer_side == ConnectionSide::Initiator {
             debug!("Received a connection request from '{peer_addr}'");
+            self.audit(peer_addr, AuditEvent::ConnectionRequest);
             None
         } else {
             debug!("Connecting to {peer_addr}...");
@@ -100,9 +120,9 @@ impl<N: Network> Router<N> {
 
         // Perform the handshake; we pass on a mutable reference to peer_ip in case the process is broken at any point in time.
         let handshake_result = if peer_side == ConnectionSide::Responder {
-            self.handshake_inner_initiator(peer_addr, &mut peer_ip, stream, genesis_header).await
+            self.handshake_inner_initiator(peer_addr, &mut peer_ip, stream, genesis_header, latest_height).await
         } else {
-            self.handshake_inner_responder(peer_addr, &mut peer_ip, stream, genesis_header).await
+            self.handshake_inner_responder(peer_addr, &mut peer_ip, stream, genesis_header, latest_height).await
         };
 
         // Remove the address from the collection of connecting peers (if the handshake got to the point where it's known).
@@ -110,9 +130,15 @@ impl<N: Network> Router<N> {
             self.connecting_peers.lock().remove(&ip);
         }
 
-        // If the handshake succeeded, announce it.
-        if let Ok((ref peer_ip, _)) = handshake_result {
-            info!("Connected to '{peer_ip}'");
+        // Record the result of the handshake, and announce it if it succeeded.
+        match handshake_result {
+            Ok((ref peer_ip, _)) => {
+                info!("Connected to '{peer_ip}'");
+                self.audit(*peer_ip, AuditEvent::HandshakeSucceeded);
+            }
+            Err(ref error) => {
+                self.audit(peer_ip.unwrap_or(peer_addr), AuditEvent::HandshakeFailed { reason: error.to_string() })
+            }
         }
 
         handshake_result
@@ -125,6 +151,7 @@ impl<N: Network> Router<N> {
         peer_ip: &mut Option<SocketAddr>,
         stream: &'a mut TcpStream,
         genesis_header: Header<N>,
+        latest_height: u32,
     ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
         // This value is immediately guaranteed to be present, so it can be unwrapped.
         let peer_ip = peer_ip.unwrap();
@@ -139,7 +166,14 @@ impl<N: Network> Router<N> {
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send a challenge request to the peer.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request = ChallengeRequest::new(
+            self.local_ip().port(),
+            self.node_type,
+            self.address(),
+            self.node_id.clone(),
+            self.consensus_binding().cloned(),
+            our_nonce,
+        );
         send(&mut framed, peer_addr, Message::ChallengeRequest(our_request)).await?;
 
         /* Step 2: Receive the peer's challenge response followed by the challenge request. */
@@ -148,6 +182,8 @@ impl<N: Network> Router<N> {
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
         // Listen for the challenge request message.
         let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
+        // Retrieve the latest block height of the peer.
+        let peer_height = peer_response.height;
 
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
@@ -165,18 +201,24 @@ impl<N: Network> Router<N> {
         /* Step 3: Send the challenge response. */
 
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = ChallengeResponse::<N>::message(peer_request.nonce, response_nonce);
         // Sign the counterparty nonce.
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
         // Send the challenge response.
-        let our_response =
-            ChallengeResponse { genesis_header, signature: Data::Object(our_signature), nonce: response_nonce };
+        let our_response = ChallengeResponse {
+            genesis_header,
+            height: latest_height,
+            signature: Data::Object(our_signature),
+            nonce: response_nonce,
+        };
         send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
 
+        // Compare the latest block heights of the node and the peer.
+        self.check_peer_height(peer_ip, peer_request.node_type, peer_height, latest_height);
         // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, peer_height), peer_addr);
 
         Ok((peer_ip, framed))
     }
@@ -188,6 +230,7 @@ impl<N: Network> Router<N> {
         peer_ip: &mut Option<SocketAddr>,
         stream: &'a mut TcpStream,
         genesis_header: Header<N>,
+        latest_height: u32,
     ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
         // Construct the stream.
         let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
@@ -202,14 +245,26 @@ impl<N: Network> Router<N> {
         let peer_ip = peer_ip.unwrap();
 
         // Knowing the peer's listening address, ensure it is allowed to connect.
-        if let Err(forbidden_message) = self.ensure_peer_is_allowed(peer_ip) {
-            return Err(error(format!("{forbidden_message}")));
+        // If a disconnect reason was returned, send the disconnect message and abort.
+        if let Some(reason) = self.ensure_peer_is_allowed(peer_ip) {
+            send(&mut framed, peer_addr, reason.into()).await?;
+            return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self.verify_challenge_request(peer_addr, &peer_request) {
             send(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
+        // If the node is hidden, ensure the peer is a committee member or an allow-listed relay.
+        // Note: The address is proven by the challenge response of the peer in step 3, and the consensus address
+        // of a validator with a separate peer-to-peer identity by its consensus binding of that address.
+        if let Some(hidden_mode) = self.hidden_mode() {
+            if !hidden_mode.is_admitted(&peer_request.consensus_address()) {
+                debug!("Dropping connection request from '{peer_ip}' (not admitted by the hidden mode)");
+                send(&mut framed, peer_addr, DisconnectReason::NoReasonGiven.into()).await?;
+                return Err(error(format!("Dropped '{peer_addr}' (not admitted by the hidden mode)")));
+            }
+        }
         /* Step 2: Send the challenge response followed by own challenge request. */
 
         // Initialize an RNG.
@@ -217,25 +272,38 @@ impl<N: Network> Router<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = ChallengeResponse::<N>::message(peer_request.nonce, response_nonce);
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
         // Send the challenge response.
-        let our_response =
-            ChallengeResponse { genesis_header, signature: Data::Object(our_signature), nonce: response_nonce };
+        let our_response = ChallengeResponse {
+            genesis_header,
+            height: latest_height,
+            signature: Data::Object(our_signature),
+            nonce: response_nonce,
+        };
         send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
 
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send the challenge request.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request = ChallengeRequest::new(
+            self.local_ip().port(),
+            self.node_type,
+            self.address(),
+            self.node_id.clone(),
+            self.consensus_binding().cloned(),
+            our_nonce,
+        );
         send(&mut framed, peer_addr, Message::ChallengeRequest(our_request)).await?;
 
         /* Step 3: Receive the challenge response. */
 
         // Listen for the challenge response message.
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+        // Retrieve the latest block height of the peer.
+        let peer_height = peer_response.height;
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
             .verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
@@ -244,42 +312,119 @@ impl<N: Network> Router<N> {
             send(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
+        // Compare the latest block heights of the node and the peer.
+        self.check_peer_height(peer_ip, peer_request.node_type, peer_height, latest_height);
         // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, peer_height), peer_addr);
 
         Ok((peer_ip, framed))
     }
 
-    /// Ensure the peer is allowed to connect.
-    fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr) -> Result<()> {
+    /// Probes the given candidate peer, by sending it a challenge request and verifying its challenge response.
+    /// The handshake is not completed, so the probe is not counted as a connection by either node.
+    pub(crate) async fn probe(&self, peer_ip: SocketAddr, genesis_header: Header<N>) -> io::Result<()> {
+        let mut stream = TcpStream::connect(peer_ip).await?;
+        let mut framed = Framed::new(&mut stream, MessageCodec::<N>::handshake());
+
+        // Send a challenge request to the peer.
+        let our_nonce = OsRng.gen();
+        let our_request = ChallengeRequest::new(
+            self.local_ip().port(),
+            self.node_type,
+            self.address(),
+            self.node_id.clone(),
+            self.consensus_binding().cloned(),
+            our_nonce,
+        );
+        send(&mut framed, peer_ip, Message::ChallengeRequest(our_request)).await?;
+
+        // Receive the peer's challenge response followed by the challenge request, and verify them.
+        // Note: A peer at its maximum number of peers rejects the probe, but it is reachable, so it is verified.
+        let peer_response = match framed.try_next().await? {
+            Some(Message::Disconnect(disconnect)) if disconnect.reason == DisconnectReason::TooManyPeers => {
+                debug!("The candidate peer '{peer_ip}' is reachable, but has too many peers");
+                return Ok(());
+            }
+            Some(Message::ChallengeResponse(response)) => response,
+            Some(Message::Disconnect(disconnect)) => {
+                crate::record_disconnect_received(disconnect.reason);
+                return Err(error(format!("'{peer_ip}' disconnected: {}", disconnect.reason)));
+            }
+            Some(message) => {
+                return Err(error(format!(
+                    "'{peer_ip}' did not follow the handshake protocol: received {:?} instead of ChallengeResponse",
+                    message.name()
+                )));
+            }
+            None => return Err(error(format!("'{peer_ip}' disconnected before sending ChallengeResponse"))),
+        };
+        let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_ip);
+        let reason = match self
+            .verify_challenge_response(peer_ip, peer_request.address, peer_response, genesis_header, our_nonce)
+            .await
+        {
+            Some(reason) => Some(reason),
+            None => self.verify_challenge_request(peer_ip, &peer_request),
+        };
+        if let Some(reason) = reason {
+            send(&mut framed, peer_ip, reason.into()).await?;
+            return Err(error(format!("Dropped '{peer_ip}' for reason: {reason:?}")));
+        }
+        // End the probe, without answering the challenge request of the peer.
+        send(&mut framed, peer_ip, DisconnectReason::NoReasonGiven.into()).await
+    }
+
+    /// Compares the latest block height of the peer to the latest block height of the node.
+    /// Warns if the node is far behind the peer, as it may be stale; a peer far behind the node is tagged as syncing.
+    fn check_peer_height(&self, peer_ip: SocketAddr, peer_type: NodeType, peer_height: u32, latest_height: u32) {
+        // Provers do not sync the ledger, so their height is not meaningful.
+        if self.node_type.is_prover() || peer_type.is_prover() {
+            return;
+        }
+        if latest_height.saturating_add(Self::MAXIMUM_HEIGHT_LAG) < peer_height {
+            let num_blocks_behind = peer_height - latest_height;
+            warn!("This node may be stale, as it is {num_blocks_behind} blocks behind '{peer_ip}' (at {latest_height})")
+        } else if peer_height.saturating_add(Self::MAXIMUM_HEIGHT_LAG) < latest_height {
+            let num_blocks_behind = latest_height - peer_height;
+            debug!("Peer '{peer_ip}' is syncing (at block {peer_height}, {num_blocks_behind} blocks behind)");
+        }
+    }
+
+    /// Ensure the peer is allowed to connect. Returns a disconnect reason if the peer is not allowed.
+    fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr) -> Option<DisconnectReason> {
         // Ensure the peer IP is not this node.
         if self.is_local_ip(&peer_ip) {
-            bail!("Dropping connection request from '{peer_ip}' (attempted to self-connect)")
+            debug!("Dropping connection request from '{peer_ip}' (attempted to self-connect)");
+            return Some(DisconnectReason::NoReasonGiven);
         }
         // Ensure the node is not already connecting to this peer.
         if !self.connecting_peers.lock().insert(peer_ip) {
-            bail!("Dropping connection request from '{peer_ip}' (already shaking hands as the initiator)")
+            debug!("Dropping connection request from '{peer_ip}' (already shaking hands as the initiator)");
+            return Some(DisconnectReason::NoReasonGiven);
         }
         // Ensure the node is not already connected to this peer.
         if self.is_connected(&peer_ip) {
-            bail!("Dropping connection request from '{peer_ip}' (already connected)")
+            debug!("Dropping connection request from '{peer_ip}' (already connected)");
+            return Some(DisconnectReason::NoReasonGiven);
         }
         // Ensure the peer is not restricted.
         if self.is_restricted(&peer_ip) {
-            bail!("Dropping connection request from '{peer_ip}' (restricted)")
+            debug!("Dropping connection request from '{peer_ip}' (restricted)");
+            return Some(DisconnectReason::Banned);
         }
-        // Ensure the peer is not spamming connection attempts.
-        if !peer_ip.ip().is_loopback() {
+        // Ensure the peer is not spamming connection attempts, unless its peer groups exempt it from the rate limits.
+        if !peer_ip.ip().is_loopback() && !self.peer_policy(&peer_ip).exempt_from_rate_limits {
             // Add this connection attempt and retrieve the number of attempts.
             let num_attempts = self.cache.insert_inbound_connection(peer_ip.ip(), Self::RADIO_SILENCE_IN_SECS as i64);
             // Ensure the connecting peer has not surpassed the connection attempt limit.
             if num_attempts > Self::MAXIMUM_CONNECTION_FAILURES {
                 // Restrict the peer.
                 self.insert_restricted_peer(peer_ip);
-                bail!("Dropping connection request from '{peer_ip}' (tried {num_attempts} times)")
+                debug!("Dropping connection request from '{peer_ip}' (tried {num_attempts} times)");
+                return Some(DisconnectReason::Banned);
             }
         }
-        Ok(())
+        None
     }
 
     /// Verifies the given challenge request. Returns a disconnect reason if the request is invalid.
@@ -289,13 +434,36 @@ impl<N: Network> Router<N> {
         message: &ChallengeRequest<N>,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, node_type: _, address: _, nonce: _ } = message;
+        let ChallengeRequest { version, listener_port: _, node_type, address, node_id, consensus_binding, nonce: _ } =
+            message;
 
         // Ensure the message protocol version is not outdated.
-        if version < Message::<N>::VERSION {
+        if *version < Message::<N>::VERSION {
             warn!("Dropping '{peer_addr}' on version {version} (outdated)");
             return Some(DisconnectReason::OutdatedClientVersion);
         }
+        // Ensure the node ID was signed by the peer.
+        if !node_id.verify(address) {
+            warn!("Dropping '{peer_addr}' for an invalid node ID signature");
+            return Some(DisconnectReason::ProtocolViolation);
+        }
+        // Ensure the consensus binding, if any, is from a validator and binds the address of the peer.
+        if let Some(consensus_binding) = consensus_binding {
+            if !node_type.is_validator() || !consensus_binding.verify(address) {
+                warn!("Dropping '{peer_addr}' for an invalid consensus binding");
+                return Some(DisconnectReason::ProtocolViolation);
+            }
+        }
+        // Ensure the node ID is not this node.
+        if node_id.id() == self.node_id.id() {
+            warn!("Dropping '{peer_addr}' (attempted to self-connect)");
+            return Some(DisconnectReason::NoReasonGiven);
+        }
+        // Ensure the node ID is not restricted.
+        if self.is_restricted_node_id(node_id.id()) {
+            warn!("Dropping '{peer_addr}' (node ID '{node_id}' is restricted)");
+            return Some(DisconnectReason::Banned);
+        }
         None
     }
 
@@ -309,7 +477,7 @@ impl<N: Network> Router<N> {
         expected_nonce: u64,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge response.
-        let ChallengeResponse { genesis_header, signature, nonce } = response;
+        let ChallengeResponse { genesis_header, height: _, signature, nonce } = response;
 
         // Verify the challenge response, by checking that the block header matches.
         if genesis_header != expected_genesis_header {
@@ -322,7 +490,7 @@ impl<N: Network> Router<N> {
             return Some(DisconnectReason::InvalidChallengeResponse);
         };
         // Verify the signature.
-        if !signature.verify_bytes(&peer_address, &[expected_nonce.to_le_bytes(), nonce.to_le_bytes()].concat()) {
+        if !signature.verify_bytes(&peer_address, &ChallengeResponse::<N>::message(expected_nonce, nonce)) {
             warn!("Handshake with '{peer_addr}' failed (invalid signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         }
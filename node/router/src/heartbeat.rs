@@ -15,7 +15,7 @@
 use crate::{
     messages::{DisconnectReason, Message, PeerRequest},
     Outbound,
-    Router,
+    DEFAULT_MAX_CLOCK_SKEW_IN_MS,
 };
 use snarkvm::prelude::Network;
 
@@ -40,12 +40,18 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     const MEDIAN_NUMBER_OF_PEERS: usize = max(Self::MAXIMUM_NUMBER_OF_PEERS / 2, Self::MINIMUM_NUMBER_OF_PEERS);
     /// The maximum number of peers permitted to maintain connections with.
     const MAXIMUM_NUMBER_OF_PEERS: usize = 21;
+    /// The maximum number of candidate peers that are verified in each heartbeat.
+    const MAXIMUM_CANDIDATE_PROBES: usize = 8;
 
     /// Handles the heartbeat request.
     fn heartbeat(&self) {
         self.safety_check_minimum_number_of_peers();
         self.log_connected_peers();
 
+        // Remove any expired restrictions and failing candidate peers.
+        self.router().garbage_collect();
+        // Verify some of the candidate peers, before they are dialed.
+        self.verify_candidate_peers();
         // Remove any stale connected peers.
         self.remove_stale_connected_peers();
         // Remove the oldest connected peer.
@@ -58,6 +64,8 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         self.handle_trusted_peers();
         // Keep the puzzle request up to date.
         self.handle_puzzle_request();
+        // Warn if the local clock is skewed from the network time.
+        self.check_clock_skew();
     }
 
     /// TODO (howardwu): Consider checking minimum number of validators, to exclude clients and provers.
@@ -82,17 +90,42 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         }
     }
 
-    /// This function removes any connected peers that have not communicated within the predefined time.
+    /// This function removes any connected peers that have not communicated within the radio silence,
+    /// or that have not answered a ping within the pong timeout (i.e. half-open connections).
     fn remove_stale_connected_peers(&self) {
+        // Retrieve the liveness thresholds.
+        let liveness = self.router().liveness();
         // Check if any connected peer is stale.
         for peer in self.router().get_connected_peers() {
-            // Disconnect if the peer has not communicated back within the predefined time.
+            // Disconnect if the peer has not communicated back within the radio silence.
             let elapsed = peer.last_seen().elapsed().as_secs();
-            if elapsed > Router::<N>::RADIO_SILENCE_IN_SECS {
+            if elapsed > liveness.radio_silence_in_secs {
                 warn!("Peer {} has not communicated in {elapsed} seconds", peer.ip());
                 // Disconnect from this peer.
-                self.router().disconnect(peer.ip());
+                self.send_disconnect(peer.ip(), DisconnectReason::PeerHasDisconnected);
+                continue;
             }
+            // Disconnect if the peer has not answered a ping within the pong timeout.
+            let awaiting = peer.ping_sent().map(|ping_sent| ping_sent.elapsed().as_secs()).unwrap_or_default();
+            if awaiting > liveness.pong_timeout_in_secs {
+                warn!("Peer {} has not answered a ping in {awaiting} seconds (half-open connection)", peer.ip());
+                // Disconnect from this peer.
+                self.send_disconnect(peer.ip(), DisconnectReason::PeerHasDisconnected);
+            }
+        }
+    }
+
+    /// This function warns if the local clock is skewed from the network time, as estimated from the committee members.
+    fn check_clock_skew(&self) {
+        let Some(offset) = self.router().time_sync().offset() else {
+            return;
+        };
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::router::CLOCK_OFFSET, offset as f64);
+        if offset.abs() > DEFAULT_MAX_CLOCK_SKEW_IN_MS {
+            let direction = if offset > 0 { "behind" } else { "ahead of" };
+            let (skew, num_peers) = (offset.abs(), self.router().time_sync().num_peers());
+            warn!("The local clock is {skew}ms {direction} the network time ({num_peers} validators), please sync it");
         }
     }
 
@@ -104,26 +137,24 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             return;
         }
 
-        // Retrieve the trusted peers.
-        let trusted = self.router().trusted_peers();
+        // Retrieve the peers that are never evicted.
+        let peer_groups = self.router().peer_groups();
         // Retrieve the bootstrap peers.
         let bootstrap = self.router().bootstrap_peers();
 
-        // Find the oldest connected peer, that is neither trusted nor a bootstrap peer.
+        // Find the oldest connected peer, that is neither exempt by its peer groups nor a bootstrap peer.
         let oldest_peer = self
             .router()
             .get_connected_peers()
             .iter()
-            .filter(|peer| !trusted.contains(&peer.ip()) && !bootstrap.contains(&peer.ip()))
+            .filter(|peer| !peer_groups.policy(&peer.ip()).never_evict && !bootstrap.contains(&peer.ip()))
             .min_by_key(|peer| peer.last_seen())
             .map(|peer| peer.ip());
 
         // Disconnect from the oldest connected peer, if one exists.
         if let Some(oldest) = oldest_peer {
             info!("Disconnecting from '{oldest}' (periodic refresh of peers)");
-            let _ = self.send(oldest, Message::Disconnect(DisconnectReason::PeerRefresh.into()));
-            // Disconnect from this peer.
-            self.router().disconnect(oldest);
+            self.send_disconnect(oldest, DisconnectReason::PeerRefresh);
         }
     }
 
@@ -140,8 +171,8 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         if num_surplus > 0 {
             debug!("Exceeded maximum number of connected peers, disconnecting from {num_surplus} peers");
 
-            // Retrieve the trusted peers.
-            let trusted = self.router().trusted_peers();
+            // Retrieve the peer groups, whose policies may exempt their peers from eviction.
+            let peer_groups = self.router().peer_groups();
             // Retrieve the bootstrap peers.
             let bootstrap = self.router().bootstrap_peers();
 
@@ -155,7 +186,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
                 .router()
                 .connected_peers()
                 .into_iter()
-                .filter(|peer_ip| !trusted.contains(peer_ip) && !bootstrap.contains(peer_ip))
+                .filter(|peer_ip| !peer_groups.policy(peer_ip).never_evict && !bootstrap.contains(peer_ip))
                 .choose_multiple(rng, num_surplus);
 
             // Proceed to send disconnect requests to these peers.
@@ -170,9 +201,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
                 }
 
                 info!("Disconnecting from '{peer_ip}' (exceeded maximum connections)");
-                self.send(peer_ip, Message::Disconnect(DisconnectReason::TooManyPeers.into()));
-                // Disconnect from this peer.
-                self.router().disconnect(peer_ip);
+                self.send_disconnect(peer_ip, DisconnectReason::TooManyPeers);
             }
         }
 
@@ -180,17 +209,29 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             // Initialize an RNG.
             let rng = &mut OsRng;
 
-            // Attempt to connect to more peers.
-            for peer_ip in self.router().candidate_peers().into_iter().choose_multiple(rng, num_deficient) {
+            // Attempt to connect to more peers, among the verified candidate peers.
+            for peer_ip in self.router().verified_candidate_peers().into_iter().choose_multiple(rng, num_deficient) {
                 self.router().connect(peer_ip);
             }
-            // Request more peers from the connected peers.
+            // Request more peers from the connected peers, restricted to the peers that this node needs.
+            let peer_request = PeerRequest::for_node_type(self.router().node_type());
             for peer_ip in self.router().connected_peers().into_iter().choose_multiple(rng, 3) {
-                self.send(peer_ip, Message::PeerRequest(PeerRequest));
+                self.send(peer_ip, Message::PeerRequest(peer_request.clone()));
             }
         }
     }
 
+    /// This function verifies a few of the unverified candidate peers in the background, by probing them with
+    /// a challenge request. This keeps the rate of probes low, while narrowing the gossiped candidate peers
+    /// down to the ones that are reachable.
+    fn verify_candidate_peers(&self) {
+        let rng = &mut OsRng;
+        let candidate_peers = self.router().unverified_candidate_peers();
+        for peer_ip in candidate_peers.into_iter().choose_multiple(rng, Self::MAXIMUM_CANDIDATE_PROBES) {
+            self.router().verify_candidate_peer(peer_ip);
+        }
+    }
+
     /// This function keeps the number of bootstrap peers within the allowed range.
     fn handle_bootstrap_peers(&self) {
         // Split the bootstrap peers into connected and candidate lists.
@@ -219,21 +260,19 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             // Proceed to send disconnect requests to these bootstrap peers.
             for peer_ip in connected_bootstrap.into_iter().choose_multiple(rng, num_surplus) {
                 info!("Disconnecting from '{peer_ip}' (exceeded maximum bootstrap)");
-                self.send(peer_ip, Message::Disconnect(DisconnectReason::TooManyPeers.into()));
-                // Disconnect from this peer.
-                self.router().disconnect(peer_ip);
+                self.send_disconnect(peer_ip, DisconnectReason::TooManyPeers);
             }
         }
     }
 
-    /// This function attempts to connect to any disconnected trusted peers.
+    /// This function attempts to connect to any disconnected peers whose peer groups always reconnect to them.
     fn handle_trusted_peers(&self) {
         // Ensure that the trusted nodes are connected.
-        for peer_ip in self.router().trusted_peers() {
+        for peer_ip in self.router().reconnect_peers() {
             // If the peer is not connected, attempt to connect to it.
-            if !self.router().is_connected(peer_ip) {
+            if !self.router().is_connected(&peer_ip) {
                 // Attempt to connect to the trusted peer.
-                self.router().connect(*peer_ip);
+                self.router().connect(peer_ip);
             }
         }
     }
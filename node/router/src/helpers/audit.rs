@@ -0,0 +1,172 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+use time::OffsetDateTime;
+
+/// The default maximum size of the audit log file in bytes, after which it is rotated.
+pub const DEFAULT_AUDIT_LOG_MAX_SIZE: u64 = 64 * 1024 * 1024; // 64 MiB
+/// The maximum number of recent entries that are kept in memory, to be queried.
+const MAXIMUM_RECENT_ENTRIES: usize = 1024;
+
+/// An event in the lifecycle of a peer connection.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// This node is attempting to connect to the peer.
+    ConnectionAttempt,
+    /// The connection attempt to the peer failed.
+    ConnectionFailed { reason: String },
+    /// The peer is attempting to connect to this node.
+    ConnectionRequest,
+    /// The handshake with the peer succeeded.
+    HandshakeSucceeded,
+    /// The handshake with the peer failed.
+    HandshakeFailed { reason: String },
+    /// The peer was disconnected.
+    Disconnected,
+    /// The peer was restricted.
+    Restricted,
+}
+
+/// An entry in the audit log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuditEntry {
+    /// The UNIX timestamp of the event.
+    pub timestamp: i64,
+    /// The address of the peer.
+    pub peer: SocketAddr,
+    /// The event.
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+/// An append-only log of the peer connections, stored as JSON lines and rotated by size.
+///
+/// When the log file exceeds its maximum size, it is moved to `<path>.1` (replacing the previous one),
+/// and a new log file is started.
+pub struct AuditLog {
+    /// The path of the log file.
+    path: PathBuf,
+    /// The maximum size of the log file in bytes.
+    max_size: u64,
+    /// The log file, and its current size in bytes.
+    file: Mutex<(File, u64)>,
+    /// The recent entries, from oldest to newest.
+    recent: Mutex<VecDeque<AuditEntry>>,
+}
+
+impl AuditLog {
+    /// Opens the audit log at the given path, appending to it if it exists.
+    pub fn open(path: &Path, max_size: u64) -> Result<Self> {
+        let file = Self::open_file(path)
+            .with_context(|| format!("Failed to open the audit log at '{}'", path.display()))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_size,
+            file: Mutex::new((file, size)),
+            recent: Mutex::new(VecDeque::with_capacity(MAXIMUM_RECENT_ENTRIES)),
+        })
+    }
+
+    /// Records the given event for the given peer.
+    pub fn record(&self, peer: SocketAddr, event: AuditEvent) {
+        let entry = AuditEntry { timestamp: OffsetDateTime::now_utc().unix_timestamp(), peer, event };
+        if let Err(error) = self.append(&entry) {
+            warn!("Failed to write to the audit log at '{}' - {error}", self.path.display());
+        }
+        let mut recent = self.recent.lock();
+        if recent.len() == MAXIMUM_RECENT_ENTRIES {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+
+    /// Returns up to the given number of the most recent entries, from newest to oldest.
+    pub fn recent(&self, limit: usize) -> Vec<AuditEntry> {
+        self.recent.lock().iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Appends the given entry to the log file, rotating the file if it exceeds the maximum size.
+    fn append(&self, entry: &AuditEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock();
+        // Rotate the log file, if the entry would exceed the maximum size.
+        if file.1 > 0 && file.1 + line.len() as u64 > self.max_size {
+            let mut rotated_path = self.path.clone().into_os_string();
+            rotated_path.push(".1");
+            std::fs::rename(&self.path, rotated_path)?;
+            *file = (Self::open_file(&self.path)?, 0);
+        }
+        file.0.write_all(&line)?;
+        file.1 += line.len() as u64;
+        Ok(())
+    }
+
+    /// Opens the log file at the given path for appending, creating it if it does not exist.
+    fn open_file(path: &Path) -> io::Result<File> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_log() {
+        let path = std::env::temp_dir().join(format!("snarkos-audit-log-{}", rand::random::<u64>()));
+        let peer = "127.0.0.1:4130".parse().unwrap();
+        let audit_log = AuditLog::open(&path, 200).unwrap();
+
+        // Record a few events.
+        audit_log.record(peer, AuditEvent::ConnectionAttempt);
+        audit_log.record(peer, AuditEvent::HandshakeFailed { reason: "timed out".to_string() });
+        audit_log.record(peer, AuditEvent::Restricted);
+
+        // Ensure the recent entries are returned from newest to oldest.
+        let recent = audit_log.recent(2);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].event, AuditEvent::Restricted);
+        assert_eq!(recent[1].event, AuditEvent::HandshakeFailed { reason: "timed out".to_string() });
+
+        // Ensure the entries are stored as JSON lines, and the log was rotated by size.
+        let mut rotated_path = path.clone().into_os_string();
+        rotated_path.push(".1");
+        let rotated = std::fs::read_to_string(&rotated_path).unwrap();
+        let current = std::fs::read_to_string(&path).unwrap();
+        let lines = rotated.lines().chain(current.lines()).collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains(r#""event":"connection_attempt""#));
+        assert!(lines[1].contains(r#""reason":"timed out""#));
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(rotated_path).unwrap();
+    }
+}
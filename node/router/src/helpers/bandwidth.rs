@@ -0,0 +1,99 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::NodeType;
+use snarkos_node_tcp::TokenBucket;
+
+use anyhow::{ensure, Result};
+use std::sync::Arc;
+
+/// The caps on the bandwidth of the messages the router writes to each class of peers, in bytes per second.
+///
+/// A cap is shared by all peers of its class, so that e.g. serving blocks to many syncing clients cannot starve
+/// the traffic to the validators. A class without a cap is not limited.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BandwidthCaps {
+    /// The cap on the bandwidth to the clients, in bytes per second.
+    pub client: Option<u64>,
+    /// The cap on the bandwidth to the validators, in bytes per second.
+    pub validator: Option<u64>,
+    /// The cap on the bandwidth to the provers, in bytes per second.
+    pub prover: Option<u64>,
+}
+
+impl BandwidthCaps {
+    /// Ensures the caps are usable.
+    pub fn check(&self) -> Result<()> {
+        for (class, cap) in [("client", self.client), ("validator", self.validator), ("prover", self.prover)] {
+            ensure!(cap != Some(0), "The bandwidth cap of the {class}s must be at least 1 byte per second");
+        }
+        Ok(())
+    }
+}
+
+/// The token buckets that enforce the bandwidth caps, one per capped class of peers.
+#[derive(Default)]
+pub struct BandwidthLimiter {
+    /// The caps that the buckets enforce.
+    caps: BandwidthCaps,
+    /// The token bucket of the clients.
+    client: Option<Arc<TokenBucket>>,
+    /// The token bucket of the validators.
+    validator: Option<Arc<TokenBucket>>,
+    /// The token bucket of the provers.
+    prover: Option<Arc<TokenBucket>>,
+}
+
+impl BandwidthLimiter {
+    /// Initializes the token buckets for the given caps.
+    pub fn new(caps: BandwidthCaps) -> Self {
+        let bucket = |cap: Option<u64>| cap.map(|rate| Arc::new(TokenBucket::new(rate)));
+        Self { caps, client: bucket(caps.client), validator: bucket(caps.validator), prover: bucket(caps.prover) }
+    }
+
+    /// Returns the caps that the buckets enforce.
+    pub const fn caps(&self) -> BandwidthCaps {
+        self.caps
+    }
+
+    /// Returns the token bucket of the given class of peers, if the class is capped.
+    pub fn limiter(&self, node_type: NodeType) -> Option<Arc<TokenBucket>> {
+        match node_type {
+            NodeType::Client => self.client.clone(),
+            NodeType::Validator => self.validator.clone(),
+            NodeType::Prover => self.prover.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bandwidth_limiter() {
+        let caps = BandwidthCaps { client: Some(1_000), prover: Some(500), ..Default::default() };
+        assert!(caps.check().is_ok());
+        assert!(BandwidthCaps { validator: Some(0), ..caps }.check().is_err());
+
+        let limiter = BandwidthLimiter::new(caps);
+        assert_eq!(limiter.caps(), caps);
+        assert_eq!(limiter.limiter(NodeType::Client).map(|bucket| bucket.rate()), Some(1_000));
+        assert_eq!(limiter.limiter(NodeType::Prover).map(|bucket| bucket.rate()), Some(500));
+        assert!(limiter.limiter(NodeType::Validator).is_none());
+        // The bucket of a class is shared by its peers.
+        let (first, second) = (limiter.limiter(NodeType::Client).unwrap(), limiter.limiter(NodeType::Client).unwrap());
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+}
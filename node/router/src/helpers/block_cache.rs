@@ -0,0 +1,195 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{block::Block, Network, ToBytes};
+
+use anyhow::{ensure, Result};
+use bytes::Bytes;
+use linked_hash_map::LinkedHashMap;
+use parking_lot::Mutex;
+use std::{ops::Range, sync::Arc};
+
+/// The default number of blocks held in the block cache.
+pub const DEFAULT_BLOCK_CACHE_CAPACITY: usize = 256;
+
+/// A block held in the block cache, along with its serialized form, once it is served to a peer.
+struct CachedBlock<N: Network> {
+    /// The block.
+    block: Arc<Block<N>>,
+    /// The serialized block, if it was serialized.
+    bytes: Option<Bytes>,
+}
+
+/// An LRU cache of the recently accessed and produced blocks, keyed by their height.
+///
+/// The cache is shared by the REST server and the `BlockRequest` handler of the router, so serving the same
+/// recent blocks to many syncing peers and explorer queries does not repeatedly read them from storage,
+/// and serialize them.
+pub struct BlockCache<N: Network> {
+    /// The cached blocks, from the least to the most recently used.
+    blocks: Mutex<LinkedHashMap<u32, CachedBlock<N>>>,
+    /// The maximum number of cached blocks.
+    capacity: usize,
+}
+
+impl<N: Network> Default for BlockCache<N> {
+    /// Initializes a new block cache, with the default capacity.
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_CACHE_CAPACITY)
+    }
+}
+
+impl<N: Network> BlockCache<N> {
+    /// Initializes a new block cache, which holds up to the given number of blocks.
+    pub fn new(capacity: usize) -> Self {
+        Self { blocks: Default::default(), capacity: capacity.max(1) }
+    }
+
+    /// Returns the number of cached blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.lock().len()
+    }
+
+    /// Returns `true` if there are no cached blocks.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.lock().is_empty()
+    }
+
+    /// Returns `true` if the block at the given height is cached.
+    pub fn contains(&self, height: u32) -> bool {
+        self.blocks.lock().contains_key(&height)
+    }
+
+    /// Returns the cached block at the given height, without refreshing it.
+    pub fn get(&self, height: u32) -> Option<Arc<Block<N>>> {
+        self.blocks.lock().get(&height).map(|cached| cached.block.clone())
+    }
+
+    /// Inserts the given block, e.g. a block that was just added to the ledger, and returns it.
+    pub fn insert(&self, block: Block<N>) -> Arc<Block<N>> {
+        let block = Arc::new(block);
+        let mut blocks = self.blocks.lock();
+        blocks.insert(block.height(), CachedBlock { block: block.clone(), bytes: None });
+        // Evict the least recently used blocks.
+        while blocks.len() > self.capacity {
+            blocks.pop_front();
+        }
+        block
+    }
+
+    /// Removes the cached blocks at or above the given height, e.g. once the ledger is rolled back.
+    pub fn remove_from(&self, height: u32) {
+        self.blocks.lock().retain(|block_height, _| *block_height < height);
+    }
+
+    /// Returns the block at the given height, reading it with the given function if it is not cached.
+    pub fn get_or_load(&self, height: u32, load: impl FnOnce(u32) -> Result<Block<N>>) -> Result<Arc<Block<N>>> {
+        if let Some(cached) = self.blocks.lock().get_refresh(&height) {
+            #[cfg(feature = "metrics")]
+            metrics::counter(metrics::router::BLOCK_CACHE_HITS, 1);
+            return Ok(cached.block.clone());
+        }
+        #[cfg(feature = "metrics")]
+        metrics::counter(metrics::router::BLOCK_CACHE_MISSES, 1);
+        // Read the block, without holding the lock.
+        let block = load(height)?;
+        ensure!(block.height() == height, "Expected block {height}, found block {}", block.height());
+        Ok(self.insert(block))
+    }
+
+    /// Returns the serialized block at the given height, reading it with the given function if it is not cached.
+    pub fn get_or_load_bytes(&self, height: u32, load: impl FnOnce(u32) -> Result<Block<N>>) -> Result<Bytes> {
+        // Retrieve the serialized block, if it is cached.
+        if let Some(bytes) = self.blocks.lock().get_refresh(&height).and_then(|cached| cached.bytes.clone()) {
+            #[cfg(feature = "metrics")]
+            metrics::counter(metrics::router::BLOCK_CACHE_HITS, 1);
+            return Ok(bytes);
+        }
+        // Retrieve the block, and serialize it, without holding the lock.
+        let block = self.get_or_load(height, load)?;
+        let bytes = Bytes::from(block.to_bytes_le()?);
+        // Cache the serialized block, if the same block is still cached.
+        if let Some(cached) = self.blocks.lock().get_mut(&height) {
+            if Arc::ptr_eq(&cached.block, &block) {
+                cached.bytes = Some(bytes.clone());
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Returns the serialized blocks in the given range, in the format of `DataBlocks`,
+    /// reading the blocks that are not cached with the given function.
+    pub fn get_or_load_data_blocks(
+        &self,
+        heights: Range<u32>,
+        load: impl Fn(u32) -> Result<Block<N>>,
+    ) -> Result<Bytes> {
+        let num_blocks = u8::try_from(heights.len())?;
+        // Write the number of blocks, followed by the blocks.
+        let mut buffer = vec![num_blocks];
+        for height in heights {
+            buffer.extend_from_slice(&self.get_or_load_bytes(height, &load)?);
+        }
+        Ok(Bytes::from(buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::ledger_test_helpers::sample_genesis_block,
+        prelude::{FromBytes, TestRng},
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_block_cache() {
+        let rng = &mut TestRng::default();
+        let block = sample_genesis_block(rng);
+        let block_bytes = block.to_bytes_le().unwrap();
+
+        let cache = BlockCache::<CurrentNetwork>::new(1);
+        assert!(cache.is_empty());
+
+        // Ensure the block is read once, and then served from the cache.
+        let loads = std::cell::Cell::new(0);
+        let load = |_| {
+            loads.set(loads.get() + 1);
+            Ok(block.clone())
+        };
+        assert_eq!(*cache.get_or_load(0, load).unwrap(), block);
+        assert_eq!(*cache.get_or_load(0, load).unwrap(), block);
+        assert_eq!(cache.get_or_load_bytes(0, load).unwrap(), block_bytes);
+        assert_eq!(loads.get(), 1);
+        assert!(cache.contains(0));
+        assert_eq!(cache.get(0).as_deref(), Some(&block));
+
+        // Ensure the serialized blocks are in the format of `DataBlocks`.
+        let data_blocks = cache.get_or_load_data_blocks(0..1, load).unwrap();
+        let decoded = crate::messages::DataBlocks::<CurrentNetwork>::read_le(&data_blocks[..]).unwrap();
+        assert_eq!(decoded.0, vec![block.clone()]);
+        assert_eq!(loads.get(), 1);
+
+        // Ensure a mismatching block is refused.
+        assert!(cache.get_or_load(1, load).is_err());
+
+        // Ensure the blocks up to the tip are kept, and the blocks above a rolled back height are removed.
+        cache.remove_from(1);
+        assert!(cache.contains(0));
+        cache.remove_from(0);
+        assert!(cache.is_empty());
+    }
+}
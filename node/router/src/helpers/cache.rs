@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::messages::BlockRequest;
+use crate::messages::{BlockRequest, Message};
 use snarkvm::prelude::{coinbase::PuzzleCommitment, Network};
 
 use core::hash::Hash;
@@ -39,13 +39,19 @@ pub struct Cache<N: Network> {
     /// The map of peer IPs to their recent timestamps.
     seen_inbound_messages: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
     /// The map of peer IPs to their recent timestamps.
+    seen_inbound_block_requests: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    /// The map of peer IPs to their recent timestamps.
     seen_inbound_puzzle_requests: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    /// The map of peer IP addresses to the timestamps of their recent malformed frames.
+    seen_inbound_malformed_frames: RwLock<HashMap<IpAddr, VecDeque<OffsetDateTime>>>,
     /// The map of solution commitments to their last seen timestamp.
     seen_inbound_solutions: RwLock<LinkedHashMap<SolutionKey<N>, OffsetDateTime>>,
     /// The map of transaction IDs to their last seen timestamp.
     seen_inbound_transactions: RwLock<LinkedHashMap<TransactionKey<N>, OffsetDateTime>>,
     /// The map of peer IPs to their block requests.
     seen_outbound_block_requests: RwLock<HashMap<SocketAddr, HashSet<BlockRequest>>>,
+    /// The map of peer IPs to their recent failed connection attempts.
+    seen_outbound_connection_failures: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
     /// The map of peer IPs to the number of puzzle requests.
     seen_outbound_puzzle_requests: RwLock<HashMap<SocketAddr, u32>>,
     /// The map of solution commitments to their last seen timestamp.
@@ -69,10 +75,13 @@ impl<N: Network> Cache<N> {
         Self {
             seen_inbound_connections: Default::default(),
             seen_inbound_messages: Default::default(),
+            seen_inbound_block_requests: Default::default(),
             seen_inbound_puzzle_requests: Default::default(),
+            seen_inbound_malformed_frames: Default::default(),
             seen_inbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_inbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_block_requests: Default::default(),
+            seen_outbound_connection_failures: Default::default(),
             seen_outbound_puzzle_requests: Default::default(),
             seen_outbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
@@ -92,11 +101,21 @@ impl<N: Network> Cache<N> {
         Self::retain_and_insert(&self.seen_inbound_messages, peer_ip, interval_in_secs)
     }
 
+    /// Inserts a new timestamp for the given peer IP, returning the number of recent block requests.
+    pub fn insert_inbound_block_request(&self, peer_ip: SocketAddr, interval_in_secs: i64) -> usize {
+        Self::retain_and_insert(&self.seen_inbound_block_requests, peer_ip, interval_in_secs)
+    }
+
     /// Inserts a new timestamp for the given peer IP, returning the number of recent requests.
     pub fn insert_inbound_puzzle_request(&self, peer_ip: SocketAddr) -> usize {
         Self::retain_and_insert(&self.seen_inbound_puzzle_requests, peer_ip, 60)
     }
 
+    /// Inserts a new timestamp for the given peer IP address, returning the number of recent malformed frames.
+    pub fn insert_inbound_malformed_frame(&self, peer_ip: IpAddr, interval_in_secs: i64) -> usize {
+        Self::retain_and_insert(&self.seen_inbound_malformed_frames, peer_ip, interval_in_secs)
+    }
+
     /// Inserts a solution commitment into the cache, returning the previously seen timestamp if it existed.
     pub fn insert_inbound_solution(
         &self,
@@ -114,6 +133,31 @@ impl<N: Network> Cache<N> {
     ) -> Option<OffsetDateTime> {
         Self::refresh_and_insert(&self.seen_inbound_transactions, (peer_ip, transaction))
     }
+
+    /// Returns `true` if the cache contains the given solution commitment from the given peer.
+    pub fn contains_inbound_solution(&self, peer_ip: SocketAddr, solution: PuzzleCommitment<N>) -> bool {
+        self.seen_inbound_solutions.read().contains_key(&(peer_ip, solution))
+    }
+
+    /// Returns `true` if the cache contains the given transaction ID from the given peer.
+    pub fn contains_inbound_transaction(&self, peer_ip: SocketAddr, transaction: N::TransactionID) -> bool {
+        self.seen_inbound_transactions.read().contains_key(&(peer_ip, transaction))
+    }
+
+    /// Removes the inbound connection attempts that are older than the given interval.
+    pub fn decay_inbound_connections(&self, interval_in_secs: i64) {
+        Self::retain_recent(&self.seen_inbound_connections, interval_in_secs)
+    }
+
+    /// Removes the block request timestamps that are older than the given interval.
+    pub fn decay_inbound_block_requests(&self, interval_in_secs: i64) {
+        Self::retain_recent(&self.seen_inbound_block_requests, interval_in_secs)
+    }
+
+    /// Removes the malformed frame timestamps that are older than the given interval.
+    pub fn decay_inbound_malformed_frames(&self, interval_in_secs: i64) {
+        Self::retain_recent(&self.seen_inbound_malformed_frames, interval_in_secs)
+    }
 }
 
 impl<N: Network> Cache<N> {
@@ -136,6 +180,26 @@ impl<N: Network> Cache<N> {
         if let Some(requests) = map_write.get_mut(&peer_ip) { requests.remove(request) } else { false }
     }
 
+    /// Returns the number of recent failed connection attempts to the given peer IP.
+    pub fn num_outbound_connection_failures(&self, peer_ip: &SocketAddr) -> usize {
+        self.seen_outbound_connection_failures.read().get(peer_ip).map_or(0, |failures| failures.len())
+    }
+
+    /// Inserts a failed connection attempt to the given peer IP, returning the number of recent failures.
+    pub fn insert_outbound_connection_failure(&self, peer_ip: SocketAddr, interval_in_secs: i64) -> usize {
+        Self::retain_and_insert(&self.seen_outbound_connection_failures, peer_ip, interval_in_secs)
+    }
+
+    /// Removes the failed connection attempts to the given peer IP.
+    pub fn remove_outbound_connection_failures(&self, peer_ip: &SocketAddr) {
+        self.seen_outbound_connection_failures.write().remove(peer_ip);
+    }
+
+    /// Removes the failed connection attempts that are older than the given interval.
+    pub fn decay_outbound_connection_failures(&self, interval_in_secs: i64) {
+        Self::retain_recent(&self.seen_outbound_connection_failures, interval_in_secs)
+    }
+
     /// Returns `true` if the cache contains a puzzle request from the given peer.
     pub fn contains_outbound_puzzle_request(&self, peer_ip: &SocketAddr) -> bool {
         self.seen_outbound_puzzle_requests.read().get(peer_ip).map(|r| *r > 0).unwrap_or(false)
@@ -169,6 +233,36 @@ impl<N: Network> Cache<N> {
         Self::refresh_and_insert(&self.seen_outbound_transactions, (peer_ip, transaction))
     }
 
+    /// Returns `true` if the cache contains the given solution commitment sent to the given peer.
+    pub fn contains_outbound_solution(&self, peer_ip: SocketAddr, solution: PuzzleCommitment<N>) -> bool {
+        self.seen_outbound_solutions.read().contains_key(&(peer_ip, solution))
+    }
+
+    /// Returns `true` if the cache contains the given transaction ID sent to the given peer.
+    pub fn contains_outbound_transaction(&self, peer_ip: SocketAddr, transaction: N::TransactionID) -> bool {
+        self.seen_outbound_transactions.read().contains_key(&(peer_ip, transaction))
+    }
+
+    /// Returns `true` if the given peer is known to have the payload of the given message,
+    /// as the peer either sent the payload to the node, or was sent the payload by the node.
+    pub fn is_payload_seen_by(&self, peer_ip: SocketAddr, message: &Message<N>) -> bool {
+        let has_transaction = |transaction_id: N::TransactionID| {
+            self.contains_inbound_transaction(peer_ip, transaction_id)
+                || self.contains_outbound_transaction(peer_ip, transaction_id)
+        };
+        match message {
+            Message::UnconfirmedSolution(message) => {
+                self.contains_inbound_solution(peer_ip, message.solution_id)
+                    || self.contains_outbound_solution(peer_ip, message.solution_id)
+            }
+            Message::UnconfirmedTransaction(message) => has_transaction(message.transaction_id),
+            Message::UnconfirmedTransactions(message) => {
+                message.transaction_ids.iter().all(|transaction_id| has_transaction(*transaction_id))
+            }
+            _ => false,
+        }
+    }
+
     /// Returns `true` if the cache contains a peer request from the given peer.
     pub fn contains_outbound_peer_request(&self, peer_ip: SocketAddr) -> bool {
         self.seen_outbound_peer_requests.read().get(&peer_ip).map(|r| *r > 0).unwrap_or(false)
@@ -208,6 +302,20 @@ impl<N: Network> Cache<N> {
         timestamps.len()
     }
 
+    /// Removes the timestamps that are older than the given interval, and the keys without any remaining timestamps.
+    fn retain_recent<K: Eq + Hash>(map: &RwLock<HashMap<K, VecDeque<OffsetDateTime>>>, interval_in_secs: i64) {
+        // Fetch the current timestamp.
+        let now = OffsetDateTime::now_utc();
+
+        map.write().retain(|_, timestamps| {
+            // Retain only the timestamps that are within the recent interval.
+            while timestamps.front().map_or(false, |t| now - *t > Duration::seconds(interval_in_secs)) {
+                timestamps.pop_front();
+            }
+            !timestamps.is_empty()
+        });
+    }
+
     /// Increments the key's counter in the map, returning the updated counter.
     fn increment_counter<K: Hash + Eq>(map: &RwLock<HashMap<K, u32>>, key: K) -> u32 {
         let mut map_write = map.write();
@@ -310,6 +418,51 @@ mod tests {
         assert_eq!(cache.seen_inbound_transactions.read().len(), 1);
     }
 
+    #[test]
+    fn test_outbound_connection_failures() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+
+        // Insert the failed connection attempts.
+        assert_eq!(cache.insert_outbound_connection_failure(peer_ip, 60), 1);
+        assert_eq!(cache.insert_outbound_connection_failure(peer_ip, 60), 2);
+        assert_eq!(cache.num_outbound_connection_failures(&peer_ip), 2);
+
+        // Ensure the recent failures do not decay.
+        cache.decay_outbound_connection_failures(60);
+        assert_eq!(cache.num_outbound_connection_failures(&peer_ip), 2);
+
+        // Ensure the old failures decay, and the peer is removed from the cache.
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.decay_outbound_connection_failures(0);
+        assert_eq!(cache.num_outbound_connection_failures(&peer_ip), 0);
+        assert!(cache.seen_outbound_connection_failures.read().is_empty());
+
+        // Ensure the failures are removed.
+        cache.insert_outbound_connection_failure(peer_ip, 60);
+        cache.remove_outbound_connection_failures(&peer_ip);
+        assert_eq!(cache.num_outbound_connection_failures(&peer_ip), 0);
+    }
+
+    #[test]
+    fn test_inbound_malformed_frames() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        // Insert the malformed frames.
+        assert_eq!(cache.insert_inbound_malformed_frame(peer_ip, 60), 1);
+        assert_eq!(cache.insert_inbound_malformed_frame(peer_ip, 60), 2);
+
+        // Ensure the recent frames do not decay.
+        cache.decay_inbound_malformed_frames(60);
+        assert_eq!(cache.seen_inbound_malformed_frames.read().get(&peer_ip).map(|t| t.len()), Some(2));
+
+        // Ensure the old frames decay, and the peer is removed from the cache.
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.decay_inbound_malformed_frames(0);
+        assert!(cache.seen_inbound_malformed_frames.read().is_empty());
+    }
+
     #[test]
     fn test_outbound_solution() {
         let cache = Cache::<CurrentNetwork>::default();
@@ -318,12 +471,15 @@ mod tests {
 
         // Check that the cache is empty.
         assert_eq!(cache.seen_outbound_solutions.read().len(), 0);
+        assert!(!cache.contains_outbound_solution(peer_ip, solution));
 
         // Insert a solution.
         assert!(cache.insert_outbound_solution(peer_ip, solution).is_none());
 
         // Check that the cache contains the solution.
         assert_eq!(cache.seen_outbound_solutions.read().len(), 1);
+        assert!(cache.contains_outbound_solution(peer_ip, solution));
+        assert!(!cache.contains_inbound_solution(peer_ip, solution));
 
         // Insert the same solution again.
         assert!(cache.insert_outbound_solution(peer_ip, solution).is_some());
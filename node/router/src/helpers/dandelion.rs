@@ -0,0 +1,232 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::UnconfirmedTransaction;
+use snarkvm::prelude::Network;
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    net::SocketAddr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use time::OffsetDateTime;
+
+/// The probability with which a node ends the stem phase of a transaction, and broadcasts (fluffs) it instead.
+const FLUFF_PROBABILITY: f64 = 0.1;
+/// The duration in seconds for which a node relays the stem transactions to the same stem peer.
+const STEM_EPOCH_IN_SECS: i64 = 600; // 10 minutes
+/// The minimum duration in seconds after which a node fluffs a stem transaction it has not seen broadcast.
+/// Note: A random delay of up to the same duration is added, so that the nodes along the stem do not fluff at once.
+const STEM_TIMEOUT_IN_SECS: i64 = 30;
+/// The interval in seconds at which a node checks for stem transactions whose timeout expired.
+pub const STEM_TIMEOUT_CHECK_IN_SECS: u64 = 5;
+/// The maximum number of stem transactions a node awaits to see broadcast.
+const MAXIMUM_STEM_TRANSACTIONS: usize = 1_000;
+
+/// The state of the Dandelion relay, in which transactions are first forwarded along a random path of peers
+/// (the stem), before one of them broadcasts (fluffs) the transaction. This hides the IP address from which
+/// a transaction originated from peers that listen to the broadcasts of many nodes.
+pub struct Dandelion<N: Network> {
+    /// The boolean flag for whether the Dandelion relay is enabled.
+    is_enabled: AtomicBool,
+    /// The peer to which stem transactions are forwarded, along with the timestamp at which it was selected.
+    stem_peer: RwLock<Option<(SocketAddr, i64)>>,
+    /// The map of forwarded stem transactions to the timestamp at which the node fluffs them, if they were not
+    /// seen broadcast in the meantime.
+    stem_transactions: RwLock<IndexMap<N::TransactionID, (UnconfirmedTransaction<N>, i64)>>,
+}
+
+impl<N: Network> Default for Dandelion<N> {
+    /// Initializes a new instance of the Dandelion relay, which is disabled.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> Dandelion<N> {
+    /// Initializes a new instance of the Dandelion relay, which is disabled.
+    pub fn new() -> Self {
+        Self { is_enabled: Default::default(), stem_peer: Default::default(), stem_transactions: Default::default() }
+    }
+
+    /// Returns `true` if the Dandelion relay is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Enables the Dandelion relay.
+    pub fn enable(&self) {
+        self.is_enabled.store(true, Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the node should fluff a stem transaction it received, instead of forwarding it.
+    pub fn should_fluff(&self) -> bool {
+        rand::thread_rng().gen_bool(FLUFF_PROBABILITY)
+    }
+
+    /// Returns the peer to forward the stem transactions to, from the given candidates.
+    /// The stem peer is kept for `STEM_EPOCH_IN_SECS`, unless it disconnects or is excluded.
+    pub fn stem_peer(&self, candidates: &[SocketAddr], excluded_peers: &[SocketAddr]) -> Option<SocketAddr> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let mut stem_peer = self.stem_peer.write();
+        // Retain the stem peer, if it is still connected and the epoch has not ended.
+        if let Some((peer_ip, selected_at)) = *stem_peer {
+            if candidates.contains(&peer_ip) && now - selected_at < STEM_EPOCH_IN_SECS {
+                if !excluded_peers.contains(&peer_ip) {
+                    return Some(peer_ip);
+                }
+                // Otherwise, forward to another peer, without ending the epoch.
+                let candidates = candidates.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
+                return candidates.copied().collect::<Vec<_>>().choose(&mut rand::thread_rng()).copied();
+            }
+        }
+        // Otherwise, select a new stem peer.
+        let candidates = candidates.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
+        let selected = candidates.copied().collect::<Vec<_>>().choose(&mut rand::thread_rng()).copied();
+        *stem_peer = selected.map(|peer_ip| (peer_ip, now));
+        selected
+    }
+
+    /// Returns `true` if the given transaction was forwarded along the stem, and was not yet seen broadcast.
+    pub fn contains_stem_transaction(&self, transaction_id: &N::TransactionID) -> bool {
+        self.stem_transactions.read().contains_key(transaction_id)
+    }
+
+    /// Inserts the given stem transaction, which the node fluffs if it is not seen broadcast before its timeout.
+    /// If the map is full, the oldest stem transaction is removed and returned, so that the node fluffs it now.
+    pub fn insert_stem_transaction(&self, message: UnconfirmedTransaction<N>) -> Option<UnconfirmedTransaction<N>> {
+        let timeout = STEM_TIMEOUT_IN_SECS + rand::thread_rng().gen_range(0..=STEM_TIMEOUT_IN_SECS);
+        let deadline = OffsetDateTime::now_utc().unix_timestamp() + timeout;
+
+        let mut stem_transactions = self.stem_transactions.write();
+        let evicted = match stem_transactions.len() >= MAXIMUM_STEM_TRANSACTIONS
+            && !stem_transactions.contains_key(&message.transaction_id)
+        {
+            true => stem_transactions.shift_remove_index(0).map(|(_, (evicted, _))| evicted),
+            false => None,
+        };
+        stem_transactions.insert(message.transaction_id, (message, deadline));
+        evicted
+    }
+
+    /// Removes the given transaction, as it was seen broadcast.
+    pub fn remove_stem_transaction(&self, transaction_id: &N::TransactionID) {
+        self.stem_transactions.write().shift_remove(transaction_id);
+    }
+
+    /// Removes and returns the stem transactions whose timeout expired, so that the node fluffs them.
+    pub fn take_expired_stem_transactions(&self) -> Vec<UnconfirmedTransaction<N>> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let mut expired = Vec::new();
+        self.stem_transactions.write().retain(|_, (message, deadline)| match *deadline <= now {
+            true => {
+                expired.push(message.clone());
+                false
+            }
+            false => true,
+        });
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::narwhal::Data,
+        prelude::{Field, Testnet3},
+        utilities::{TestRng, Uniform},
+    };
+
+    use bytes::Bytes;
+    use std::net::Ipv4Addr;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_stem_peer() {
+        let dandelion = Dandelion::<CurrentNetwork>::new();
+        assert!(!dandelion.is_enabled());
+        dandelion.enable();
+        assert!(dandelion.is_enabled());
+
+        let peers = (1..=4).map(|port| SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port)).collect::<Vec<_>>();
+        // Ensure there is no stem peer without candidates.
+        assert!(dandelion.stem_peer(&[], &[]).is_none());
+
+        // Ensure the stem peer is kept during the epoch.
+        let stem_peer = dandelion.stem_peer(&peers, &[]).unwrap();
+        for _ in 0..10 {
+            assert_eq!(dandelion.stem_peer(&peers, &[]), Some(stem_peer));
+        }
+        // Ensure an excluded stem peer is bypassed, without ending the epoch.
+        for _ in 0..10 {
+            assert_ne!(dandelion.stem_peer(&peers, &[stem_peer]), Some(stem_peer));
+        }
+        assert_eq!(dandelion.stem_peer(&peers, &[]), Some(stem_peer));
+
+        // Ensure a new stem peer is selected once the stem peer disconnects.
+        let remaining = peers.iter().copied().filter(|peer_ip| *peer_ip != stem_peer).collect::<Vec<_>>();
+        let new_stem_peer = dandelion.stem_peer(&remaining, &[]).unwrap();
+        assert_ne!(new_stem_peer, stem_peer);
+        assert_eq!(dandelion.stem_peer(&peers, &[]), Some(new_stem_peer));
+    }
+
+    #[test]
+    fn test_stem_transactions() {
+        let dandelion = Dandelion::<CurrentNetwork>::new();
+
+        let transaction_id = Default::default();
+        let message = UnconfirmedTransaction { transaction_id, transaction: Data::Buffer(Bytes::new()) };
+        assert!(dandelion.insert_stem_transaction(message.clone()).is_none());
+        assert!(dandelion.contains_stem_transaction(&transaction_id));
+        // Ensure the stem transaction does not expire before its timeout.
+        assert!(dandelion.take_expired_stem_transactions().is_empty());
+
+        // Ensure a stem transaction that was seen broadcast is removed.
+        dandelion.remove_stem_transaction(&transaction_id);
+        assert!(!dandelion.contains_stem_transaction(&transaction_id));
+
+        // Ensure an expired stem transaction is returned once.
+        assert!(dandelion.insert_stem_transaction(message.clone()).is_none());
+        dandelion.stem_transactions.write().get_mut(&transaction_id).unwrap().1 = 0;
+        assert_eq!(dandelion.take_expired_stem_transactions(), vec![message]);
+        assert!(!dandelion.contains_stem_transaction(&transaction_id));
+    }
+
+    #[test]
+    fn test_stem_transactions_are_capped() {
+        let dandelion = Dandelion::<CurrentNetwork>::new();
+        let rng = &mut TestRng::default();
+
+        let sample_message = |rng: &mut TestRng| UnconfirmedTransaction::<CurrentNetwork> {
+            transaction_id: Field::rand(rng).into(),
+            transaction: Data::Buffer(Bytes::new()),
+        };
+        let messages = (0..MAXIMUM_STEM_TRANSACTIONS).map(|_| sample_message(rng)).collect::<Vec<_>>();
+        for message in &messages {
+            assert!(dandelion.insert_stem_transaction(message.clone()).is_none());
+        }
+        // Ensure reinserting a stem transaction does not evict another one.
+        assert!(dandelion.insert_stem_transaction(messages[1].clone()).is_none());
+        // Ensure the oldest stem transaction is evicted once the map is full, so that it is fluffed now.
+        let message = sample_message(rng);
+        assert_eq!(dandelion.insert_stem_transaction(message.clone()), Some(messages[0].clone()));
+        assert_eq!(dandelion.stem_transactions.read().len(), MAXIMUM_STEM_TRANSACTIONS);
+        assert!(dandelion.contains_stem_transaction(&message.transaction_id));
+        assert!(!dandelion.contains_stem_transaction(&messages[0].transaction_id));
+    }
+}
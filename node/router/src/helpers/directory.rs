@@ -0,0 +1,162 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, Network};
+
+use linked_hash_map::LinkedHashMap;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::net::SocketAddr;
+use time::OffsetDateTime;
+
+/// The default number of validators held in the validator directory.
+pub const DEFAULT_VALIDATOR_DIRECTORY_CAPACITY: usize = 1024;
+
+/// The latest known network endpoints of a validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct ValidatorEndpoint<N: Network> {
+    /// The address of the validator.
+    pub address: Address<N>,
+    /// The listener address of the router of the validator, as authenticated in the handshake.
+    pub node_ip: SocketAddr,
+    /// The public REST URL that the validator signed, if it advertises one.
+    pub rest_url: Option<String>,
+    /// Whether the validator is currently connected.
+    pub is_connected: bool,
+    /// The UTC timestamp at which the validator was last connected.
+    pub last_seen: i64,
+}
+
+/// A directory that resolves validator addresses to their latest known network endpoints.
+///
+/// The directory only records first-hand data about the members of the committee, keyed by their consensus address:
+/// the listener address that a validator authenticated with in the handshake, and the REST URL that it signed in its
+/// pings. The entries outlive the connections, so a validator can still be resolved to its last known endpoint after
+/// it disconnects, until it is evicted as the least recently seen validator.
+pub struct ValidatorDirectory<N: Network> {
+    /// The endpoints of the validators, from the least to the most recently seen.
+    endpoints: Mutex<LinkedHashMap<Address<N>, ValidatorEndpoint<N>>>,
+    /// The maximum number of validators in the directory.
+    capacity: usize,
+}
+
+impl<N: Network> Default for ValidatorDirectory<N> {
+    /// Initializes a new validator directory, with the default capacity.
+    fn default() -> Self {
+        Self::new(DEFAULT_VALIDATOR_DIRECTORY_CAPACITY)
+    }
+}
+
+impl<N: Network> ValidatorDirectory<N> {
+    /// Initializes a new validator directory, which holds up to the given number of validators.
+    pub fn new(capacity: usize) -> Self {
+        Self { endpoints: Default::default(), capacity: capacity.max(1) }
+    }
+
+    /// Returns the number of validators in the directory.
+    pub fn len(&self) -> usize {
+        self.endpoints.lock().len()
+    }
+
+    /// Returns `true` if the directory is empty.
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.lock().is_empty()
+    }
+
+    /// Returns the latest known endpoints of the given validator, if it is in the directory.
+    pub fn resolve(&self, address: &Address<N>) -> Option<ValidatorEndpoint<N>> {
+        self.endpoints.lock().get(address).cloned()
+    }
+
+    /// Returns the endpoints of all validators in the directory, from the most to the least recently seen.
+    pub fn endpoints(&self) -> Vec<ValidatorEndpoint<N>> {
+        self.endpoints.lock().values().rev().cloned().collect()
+    }
+
+    /// Records that the given validator connected from the given listener address.
+    pub fn insert_connected(&self, address: Address<N>, node_ip: SocketAddr) {
+        let mut endpoints = self.endpoints.lock();
+        // Keep the REST URL, unless the validator moved, as the URL may have moved with it.
+        let rest_url = match endpoints.remove(&address) {
+            Some(endpoint) if endpoint.node_ip == node_ip => endpoint.rest_url,
+            _ => None,
+        };
+        let last_seen = OffsetDateTime::now_utc().unix_timestamp();
+        endpoints.insert(address, ValidatorEndpoint { address, node_ip, rest_url, is_connected: true, last_seen });
+        // Evict the least recently seen validators.
+        while endpoints.len() > self.capacity {
+            endpoints.pop_front();
+        }
+    }
+
+    /// Records the REST URL that the given validator signed.
+    /// Note: The signature must be verified by the caller.
+    pub fn set_rest_url(&self, address: &Address<N>, rest_url: Option<String>) {
+        if let Some(endpoint) = self.endpoints.lock().get_refresh(address) {
+            endpoint.rest_url = rest_url;
+            endpoint.last_seen = OffsetDateTime::now_utc().unix_timestamp();
+        }
+    }
+
+    /// Records that the given validator disconnected.
+    pub fn set_disconnected(&self, address: &Address<N>) {
+        if let Some(endpoint) = self.endpoints.lock().get_mut(address) {
+            endpoint.is_connected = false;
+            endpoint.last_seen = OffsetDateTime::now_utc().unix_timestamp();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    fn sample_address(rng: &mut TestRng) -> Address<CurrentNetwork> {
+        Address::try_from(PrivateKey::new(rng).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_validator_directory() {
+        let rng = &mut TestRng::default();
+        let directory = ValidatorDirectory::<CurrentNetwork>::new(2);
+        let (a, b, c) = (sample_address(rng), sample_address(rng), sample_address(rng));
+        let ip = |port| SocketAddr::from(([127, 0, 0, 1], port));
+
+        // Resolve a connected validator, along with its REST URL.
+        directory.insert_connected(a, ip(4130));
+        directory.set_rest_url(&a, Some("https://a.example.com".to_string()));
+        let endpoint = directory.resolve(&a).unwrap();
+        assert_eq!(endpoint.node_ip, ip(4130));
+        assert_eq!(endpoint.rest_url.as_deref(), Some("https://a.example.com"));
+        assert!(endpoint.is_connected);
+
+        // A disconnected validator resolves to its last known endpoint.
+        directory.set_disconnected(&a);
+        assert!(!directory.resolve(&a).unwrap().is_connected);
+        // A validator that moves loses its REST URL, until it signs one again.
+        directory.insert_connected(a, ip(4131));
+        assert_eq!(directory.resolve(&a).unwrap().rest_url, None);
+
+        // The least recently seen validator is evicted.
+        directory.insert_connected(b, ip(4132));
+        directory.insert_connected(c, ip(4133));
+        assert_eq!(directory.len(), 2);
+        assert_eq!(directory.resolve(&a), None);
+        assert_eq!(directory.endpoints().iter().map(|endpoint| endpoint.address).collect::<Vec<_>>(), vec![c, b]);
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::DisconnectReason;
+
+/// Returns the reason to send to a peer that is being dropped for the given error.
+/// Handlers attach a specific reason with `Error::new(reason).context(..)`; any other error is a protocol violation.
+pub fn disconnect_reason(error: &anyhow::Error) -> DisconnectReason {
+    error.downcast_ref::<DisconnectReason>().copied().unwrap_or(DisconnectReason::ProtocolViolation)
+}
+
+/// Records a `Disconnect` message sent to a peer, by its reason.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub fn record_disconnect_sent(reason: DisconnectReason) {
+    #[cfg(feature = "metrics")]
+    metrics::increment_labeled_counter(
+        metrics::router::DISCONNECTS_SENT,
+        metrics::router::DISCONNECT_REASON_LABEL,
+        reason.name(),
+    );
+}
+
+/// Records a `Disconnect` message received from a peer, by its reason.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub fn record_disconnect_received(reason: DisconnectReason) {
+    #[cfg(feature = "metrics")]
+    metrics::increment_labeled_counter(
+        metrics::router::DISCONNECTS_RECEIVED,
+        metrics::router::DISCONNECT_REASON_LABEL,
+        reason.name(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::{anyhow, Error};
+
+    #[test]
+    fn test_disconnect_reason() {
+        // A tagged error carries its reason through the context.
+        let error = Error::new(DisconnectReason::Oversubscribed).context("Dropping '127.0.0.1:4130' for spamming");
+        assert_eq!(disconnect_reason(&error), DisconnectReason::Oversubscribed);
+        assert_eq!(error.to_string(), "Dropping '127.0.0.1:4130' for spamming");
+        // An untagged error is a protocol violation.
+        let error = anyhow!("Peer is not following the protocol");
+        assert_eq!(disconnect_reason(&error), DisconnectReason::ProtocolViolation);
+    }
+}
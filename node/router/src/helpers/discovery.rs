@@ -0,0 +1,164 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::NodeType;
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+};
+
+/// The mDNS service type under which the nodes advertise themselves on the local network.
+pub const MDNS_SERVICE_TYPE: &str = "_snarkos._tcp.local.";
+/// The name of the peer group of the peers discovered on the local network.
+pub const MDNS_PEER_GROUP: &str = "mdns";
+
+/// A node that advertises itself on the local network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalPeer {
+    /// The listener address of the node.
+    pub listener: SocketAddr,
+    /// The type of the node.
+    pub node_type: NodeType,
+    /// The address of the BFT gateway of the node, if it is a validator.
+    pub bft: Option<SocketAddr>,
+}
+
+/// The discovery of the nodes on the local network, over mDNS.
+///
+/// The node advertises its listener under `MDNS_SERVICE_TYPE`, along with its network, node type, and BFT port,
+/// and browses for the other nodes of the same network. This is only meant for development networks on a LAN,
+/// as anyone on the local network can advertise themselves.
+pub struct LocalDiscovery {
+    /// The mDNS daemon, which answers the queries and sends the announcements.
+    daemon: ServiceDaemon,
+    /// The full name of the service of this node, so that it is not discovered by itself.
+    fullname: String,
+    /// The network ID of the node.
+    network: u16,
+    /// The discovered nodes, by their listener address.
+    peers: RwLock<IndexMap<SocketAddr, LocalPeer>>,
+}
+
+impl LocalDiscovery {
+    /// Starts advertising this node on the local network, and returns the events of the browsed nodes.
+    pub fn start(
+        network: u16,
+        instance: &str,
+        node_type: NodeType,
+        port: u16,
+        bft_port: Option<u16>,
+    ) -> Result<(Self, mdns_sd::Receiver<ServiceEvent>)> {
+        let daemon = ServiceDaemon::new()?;
+        // Advertise the node on all of the interfaces of the host.
+        let mut properties = HashMap::from([
+            ("network".to_string(), network.to_string()),
+            ("node_type".to_string(), node_type.to_string()),
+        ]);
+        if let Some(bft_port) = bft_port {
+            properties.insert("bft_port".to_string(), bft_port.to_string());
+        }
+        let host_name = format!("{instance}.local.");
+        let service =
+            ServiceInfo::new(MDNS_SERVICE_TYPE, instance, &host_name, "", port, properties)?.enable_addr_auto();
+        let fullname = service.get_fullname().to_string();
+        daemon.register(service)?;
+        // Browse for the other nodes.
+        let events = daemon.browse(MDNS_SERVICE_TYPE)?;
+        Ok((Self { daemon, fullname, network, peers: Default::default() }, events))
+    }
+
+    /// Returns the nodes that were discovered so far.
+    pub fn peers(&self) -> Vec<LocalPeer> {
+        self.peers.read().values().copied().collect()
+    }
+
+    /// Records the nodes advertised by the given resolved service, and returns the newly discovered ones.
+    pub fn insert(&self, service: &ServiceInfo) -> Vec<LocalPeer> {
+        if service.get_fullname() == self.fullname {
+            return vec![];
+        }
+        let mut peers = self.peers.write();
+        let parsed = Self::parse(service, self.network);
+        parsed.into_iter().filter(|peer| peers.insert(peer.listener, *peer).is_none()).collect()
+    }
+
+    /// Stops advertising this node, and stops browsing.
+    pub fn shut_down(&self) {
+        if let Err(error) = self.daemon.shutdown() {
+            warn!("Failed to shut down the mDNS daemon - {error}");
+        }
+    }
+
+    /// Returns the nodes advertised by the given service, which are the node at each of its addresses,
+    /// or nothing if the service is not a node of the given network.
+    fn parse(service: &ServiceInfo, network: u16) -> Vec<LocalPeer> {
+        // Ensure the service is a node of the same network.
+        if service.get_property_val_str("network") != Some(network.to_string().as_str()) {
+            return vec![];
+        }
+        let node_type = match service.get_property_val_str("node_type") {
+            Some("Client") => NodeType::Client,
+            Some("Prover") => NodeType::Prover,
+            Some("Validator") => NodeType::Validator,
+            _ => return vec![],
+        };
+        let bft_port = service.get_property_val_str("bft_port").and_then(|port| port.parse::<u16>().ok());
+        service
+            .get_addresses()
+            .iter()
+            .map(|ip| IpAddr::from(*ip))
+            .map(|ip| LocalPeer {
+                listener: SocketAddr::new(ip, service.get_port()),
+                node_type,
+                bft: bft_port.filter(|_| node_type.is_validator()).map(|port| SocketAddr::new(ip, port)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_local_peers() {
+        let service = |properties: &[(&str, &str)]| {
+            let properties =
+                properties.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect::<HashMap<_, _>>();
+            ServiceInfo::new(MDNS_SERVICE_TYPE, "node", "node.local.", "192.168.1.2", 4130, properties).unwrap()
+        };
+
+        // A validator advertises its BFT gateway.
+        let peers =
+            LocalDiscovery::parse(&service(&[("network", "3"), ("node_type", "Validator"), ("bft_port", "5000")]), 3);
+        assert_eq!(peers, vec![LocalPeer {
+            listener: "192.168.1.2:4130".parse().unwrap(),
+            node_type: NodeType::Validator,
+            bft: Some("192.168.1.2:5000".parse().unwrap()),
+        }]);
+        // A client has no BFT gateway.
+        let client = service(&[("network", "3"), ("node_type", "Client"), ("bft_port", "5000")]);
+        assert_eq!(LocalDiscovery::parse(&client, 3)[0].bft, None);
+
+        // The nodes of other networks, and the unknown node types, are ignored.
+        assert!(LocalDiscovery::parse(&service(&[("network", "1"), ("node_type", "Client")]), 3).is_empty());
+        assert!(LocalDiscovery::parse(&service(&[("network", "3"), ("node_type", "Beacon")]), 3).is_empty());
+        assert!(LocalDiscovery::parse(&service(&[("node_type", "Client")]), 3).is_empty());
+    }
+}
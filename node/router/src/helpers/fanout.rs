@@ -0,0 +1,177 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::Message;
+use snarkvm::{ledger::narwhal::Data, prelude::Network};
+
+use anyhow::{ensure, Result};
+use rand::{seq::SliceRandom, Rng};
+use std::net::SocketAddr;
+
+/// The policy with which the router selects the peers to which a propagated message is sent.
+///
+/// Control messages and small payloads are broadcast to every peer. Large payloads (i.e. the unconfirmed solutions
+/// and transactions of at least the given size) are sent to every connected validator, as the committee members
+/// must receive them to include them in a block, and to a random sample of `ceil(sqrt(N))` of the other peers,
+/// where `N` is the number of peers, which then gossip the payload on. This keeps the payload reaching the whole
+/// network in a few hops, while the bandwidth spent on redundant copies grows with `sqrt(N)` rather than `N`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FanoutPolicy {
+    /// The size in bytes from which a payload is sent to a sample of the peers, or `None` to broadcast every message.
+    pub large_payload_size: Option<usize>,
+    /// The minimum number of the other peers to which a large payload is sent.
+    pub min_sampled_peers: usize,
+}
+
+impl FanoutPolicy {
+    /// The default size in bytes from which a payload is sent to a sample of the peers.
+    pub const DEFAULT_LARGE_PAYLOAD_SIZE: usize = 1024;
+    /// The default minimum number of the other peers to which a large payload is sent.
+    pub const DEFAULT_MIN_SAMPLED_PEERS: usize = 4;
+
+    /// Initializes a policy that broadcasts every message to every peer.
+    pub const fn broadcast() -> Self {
+        Self { large_payload_size: None, min_sampled_peers: Self::DEFAULT_MIN_SAMPLED_PEERS }
+    }
+
+    /// Ensures the policy is usable.
+    pub fn check(&self) -> Result<()> {
+        ensure!(self.large_payload_size != Some(0), "The size of a large payload must be at least 1 byte");
+        ensure!(self.min_sampled_peers > 0, "A large payload must be sent to at least 1 sampled peer");
+        Ok(())
+    }
+
+    /// Returns `true` if the given message carries a large payload, which is only sent to a sample of the peers.
+    pub fn is_large<N: Network>(&self, message: &Message<N>) -> bool {
+        match (self.large_payload_size, payload_size(message)) {
+            (Some(large_payload_size), Some(size)) => size >= large_payload_size,
+            _ => false,
+        }
+    }
+
+    /// Returns the number of the other peers to which a large payload is sent, given the number of peers.
+    pub fn num_sampled_peers(&self, num_peers: usize) -> usize {
+        let sqrt = (num_peers as f64).sqrt().ceil() as usize;
+        sqrt.max(self.min_sampled_peers).min(num_peers)
+    }
+
+    /// Returns the peers to which the given message is sent, from the given validators and other peers.
+    pub fn select_peers<N: Network, R: Rng>(
+        &self,
+        message: &Message<N>,
+        mut validators: Vec<SocketAddr>,
+        others: Vec<SocketAddr>,
+        rng: &mut R,
+    ) -> Vec<SocketAddr> {
+        if !self.is_large(message) {
+            validators.extend(others);
+            return validators;
+        }
+        // Sample the other peers, based on the total number of peers.
+        let num_sampled_peers = self.num_sampled_peers(validators.len() + others.len()).min(others.len());
+        validators.extend(others.choose_multiple(rng, num_sampled_peers).copied());
+        validators
+    }
+}
+
+impl Default for FanoutPolicy {
+    /// Initializes the default policy.
+    fn default() -> Self {
+        Self {
+            large_payload_size: Some(Self::DEFAULT_LARGE_PAYLOAD_SIZE),
+            min_sampled_peers: Self::DEFAULT_MIN_SAMPLED_PEERS,
+        }
+    }
+}
+
+/// Returns the size in bytes of the payload of the given message, or `None` if the message is a control message.
+/// Note: A payload that is not yet serialized is of unknown size, and is counted as a large payload.
+fn payload_size<N: Network>(message: &Message<N>) -> Option<usize> {
+    fn data_size<T>(data: &Data<T>) -> usize {
+        match data {
+            Data::Buffer(bytes) => bytes.len(),
+            Data::Object(_) => usize::MAX,
+        }
+    }
+
+    match message {
+        Message::UnconfirmedSolution(message) => Some(data_size(&message.solution)),
+        Message::UnconfirmedTransaction(message) => Some(data_size(&message.transaction)),
+        Message::UnconfirmedTransactions(message) => Some(data_size(&message.transactions)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{NodeType, Ping, UnconfirmedSolution};
+    use snarkvm::prelude::{coinbase::PuzzleCommitment, TestRng, Testnet3};
+
+    use bytes::Bytes;
+    use std::collections::HashSet;
+
+    type CurrentNetwork = Testnet3;
+
+    fn sample_solution(size: usize) -> Message<CurrentNetwork> {
+        Message::UnconfirmedSolution(UnconfirmedSolution {
+            solution_id: PuzzleCommitment::default(),
+            solution: Data::Buffer(Bytes::from(vec![0u8; size])),
+        })
+    }
+
+    fn sample_peers(range: std::ops::Range<u16>) -> Vec<SocketAddr> {
+        range.map(|port| SocketAddr::from(([127, 0, 0, 1], port))).collect()
+    }
+
+    #[test]
+    fn test_fanout_policy_check() {
+        assert!(FanoutPolicy::default().check().is_ok());
+        assert!(FanoutPolicy::broadcast().check().is_ok());
+        assert!(FanoutPolicy { large_payload_size: Some(0), ..Default::default() }.check().is_err());
+        assert!(FanoutPolicy { min_sampled_peers: 0, ..Default::default() }.check().is_err());
+    }
+
+    #[test]
+    fn test_num_sampled_peers() {
+        let policy = FanoutPolicy::default();
+        assert_eq!(policy.num_sampled_peers(0), 0);
+        assert_eq!(policy.num_sampled_peers(3), 3);
+        assert_eq!(policy.num_sampled_peers(16), 4);
+        assert_eq!(policy.num_sampled_peers(17), 5);
+        assert_eq!(policy.num_sampled_peers(100), 10);
+    }
+
+    #[test]
+    fn test_select_peers() {
+        let rng = &mut TestRng::default();
+        let policy = FanoutPolicy::default();
+        let (validators, others) = (sample_peers(0..4), sample_peers(4..100));
+
+        // A control message is broadcast to every peer.
+        let ping = Message::Ping(Ping::new(NodeType::Client, None, None));
+        assert_eq!(policy.select_peers(&ping, validators.clone(), others.clone(), rng).len(), 100);
+        // A small payload is broadcast to every peer.
+        let small = sample_solution(FanoutPolicy::DEFAULT_LARGE_PAYLOAD_SIZE - 1);
+        assert_eq!(policy.select_peers(&small, validators.clone(), others.clone(), rng).len(), 100);
+        // A large payload is sent to every validator, and a sample of the other peers.
+        let large = sample_solution(FanoutPolicy::DEFAULT_LARGE_PAYLOAD_SIZE);
+        let peers = policy.select_peers(&large, validators.clone(), others.clone(), rng);
+        assert_eq!(peers.len(), 4 + 10);
+        assert!(validators.iter().all(|validator| peers.contains(validator)));
+        assert_eq!(peers.iter().collect::<HashSet<_>>().len(), peers.len());
+        // The broadcast policy sends a large payload to every peer.
+        assert_eq!(FanoutPolicy::broadcast().select_peers(&large, validators, others, rng).len(), 100);
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, Network};
+
+use indexmap::IndexSet;
+
+/// The admission of the inbound peers of a hidden validator.
+///
+/// A hidden validator only accepts the inbound connections of the committee members and of the allow-listed relays,
+/// so that it can shield behind the relays (or clients) of its operator, while it still dials out to any peer.
+/// The peers are admitted by the address in their challenge request, which the handshake then requires them to prove
+/// by signing the nonce of the node, so a peer cannot be admitted by claiming the address of another. A validator
+/// with a separate peer-to-peer identity is admitted by the consensus address that it bound to that identity.
+pub struct HiddenMode<N: Network> {
+    /// The addresses of the allow-listed relays.
+    relays: IndexSet<Address<N>>,
+    /// The function that determines whether the given address is a member of the current committee.
+    is_committee_member: Box<dyn Fn(&Address<N>) -> bool + Send + Sync>,
+}
+
+impl<N: Network> HiddenMode<N> {
+    /// Initializes the hidden mode with the given allow-listed relays, and the given committee membership check.
+    pub fn new(
+        relays: impl IntoIterator<Item = Address<N>>,
+        is_committee_member: impl Fn(&Address<N>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self { relays: relays.into_iter().collect(), is_committee_member: Box::new(is_committee_member) }
+    }
+
+    /// Returns the addresses of the allow-listed relays.
+    pub fn relays(&self) -> &IndexSet<Address<N>> {
+        &self.relays
+    }
+
+    /// Returns `true` if the peer with the given address is admitted, as an allow-listed relay or a committee member.
+    pub fn is_admitted(&self, address: &Address<N>) -> bool {
+        self.relays.contains(address) || (self.is_committee_member)(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    fn sample_address(rng: &mut TestRng) -> Address<CurrentNetwork> {
+        Address::try_from(PrivateKey::new(rng).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_hidden_mode() {
+        let rng = &mut TestRng::default();
+        let (relay, member, stranger) = (sample_address(rng), sample_address(rng), sample_address(rng));
+
+        let hidden = HiddenMode::new([relay], move |address| *address == member);
+        assert!(hidden.is_admitted(&relay));
+        assert!(hidden.is_admitted(&member));
+        assert!(!hidden.is_admitted(&stranger));
+        assert_eq!(hidden.relays().len(), 1);
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::NodeId;
+use snarkos_account::Account;
+use snarkvm::prelude::{FromBytes, Network, ToBytes};
+
+use anyhow::Result;
+use rand::rngs::OsRng;
+use std::path::Path;
+
+/// Loads the node ID stored at the given path, or creates and stores a new one if it does not exist.
+///
+/// If the stored node ID is malformed, or was signed by a different account or under an older signing domain,
+/// a new node ID is created.
+pub fn load_or_create_node_id<N: Network>(account: &Account<N>, path: &Path) -> Result<NodeId<N>> {
+    // Attempt to load the existing node ID.
+    if path.exists() {
+        match NodeId::<N>::from_bytes_le(&std::fs::read(path)?) {
+            Ok(node_id) if node_id.verify(&account.address()) => return Ok(node_id),
+            Ok(_) => warn!("The node ID in '{}' has an invalid signature, creating a new one", path.display()),
+            Err(e) => warn!("The node ID in '{}' is malformed, creating a new one - {e}", path.display()),
+        }
+    }
+    // Create a new node ID.
+    let node_id = NodeId::new(account.private_key(), &mut OsRng)?;
+    // Store the node ID.
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, node_id.to_bytes_le()?)?;
+    debug!("Created node ID '{node_id}' in '{}'", path.display());
+    Ok(node_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_load_or_create_node_id() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("snarkos-node-id-{}", u64::rand(rng)));
+        let account = Account::<CurrentNetwork>::new(rng).unwrap();
+
+        // Ensure the node ID persists.
+        let node_id = load_or_create_node_id(&account, &path).unwrap();
+        assert_eq!(load_or_create_node_id(&account, &path).unwrap(), node_id);
+
+        // Ensure a new node ID is created for a different account.
+        let other_account = Account::<CurrentNetwork>::new(rng).unwrap();
+        let other_node_id = load_or_create_node_id(&other_account, &path).unwrap();
+        assert_ne!(other_node_id.id(), node_id.id());
+        assert!(other_node_id.verify(&other_account.address()));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Result};
+
+/// The thresholds with which the router detects unresponsive peer connections.
+///
+/// A connection is reaped by the heartbeat if either the peer has not sent a message within the radio silence,
+/// or a ping to the peer has not been answered with a pong within the pong timeout. The latter catches half-open
+/// connections well before the radio silence elapses, as the peer pings and answers every few seconds when healthy.
+/// TCP keepalive additionally lets the operating system fail connections whose remote end vanished.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Liveness {
+    /// The idle time in seconds after which TCP keepalive probes are sent, or `None` to disable keepalive.
+    pub keepalive_in_secs: Option<u64>,
+    /// The duration in seconds after which a connected peer is considered inactive,
+    /// if no message has been received in the meantime.
+    pub radio_silence_in_secs: u64,
+    /// The duration in seconds within which a ping must be answered with a pong.
+    pub pong_timeout_in_secs: u64,
+}
+
+impl Liveness {
+    /// The default idle time in seconds after which TCP keepalive probes are sent.
+    pub const DEFAULT_KEEPALIVE_IN_SECS: u64 = 30;
+    /// The default duration in seconds after which a silent peer is considered inactive.
+    pub const DEFAULT_RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
+    /// The default duration in seconds within which a ping must be answered with a pong.
+    pub const DEFAULT_PONG_TIMEOUT_IN_SECS: u64 = 45;
+
+    /// Ensures the thresholds are usable.
+    pub fn check(&self) -> Result<()> {
+        ensure!(self.keepalive_in_secs != Some(0), "The TCP keepalive must be at least 1 second");
+        ensure!(self.radio_silence_in_secs > 0, "The radio silence must be at least 1 second");
+        ensure!(self.pong_timeout_in_secs > 0, "The pong timeout must be at least 1 second");
+        ensure!(
+            self.pong_timeout_in_secs <= self.radio_silence_in_secs,
+            "The pong timeout ({}s) must not exceed the radio silence ({}s)",
+            self.pong_timeout_in_secs,
+            self.radio_silence_in_secs
+        );
+        Ok(())
+    }
+}
+
+impl Default for Liveness {
+    /// Initializes the default thresholds.
+    fn default() -> Self {
+        Self {
+            keepalive_in_secs: Some(Self::DEFAULT_KEEPALIVE_IN_SECS),
+            radio_silence_in_secs: Self::DEFAULT_RADIO_SILENCE_IN_SECS,
+            pong_timeout_in_secs: Self::DEFAULT_PONG_TIMEOUT_IN_SECS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liveness_check() {
+        assert!(Liveness::default().check().is_ok());
+        assert!(Liveness { keepalive_in_secs: None, ..Default::default() }.check().is_ok());
+        assert!(Liveness { keepalive_in_secs: Some(0), ..Default::default() }.check().is_err());
+        assert!(Liveness { radio_silence_in_secs: 0, ..Default::default() }.check().is_err());
+        assert!(Liveness { pong_timeout_in_secs: 0, ..Default::default() }.check().is_err());
+        // The pong timeout must not exceed the radio silence.
+        let liveness = Liveness { radio_silence_in_secs: 30, pong_timeout_in_secs: 60, ..Default::default() };
+        assert!(liveness.check().is_err());
+    }
+}
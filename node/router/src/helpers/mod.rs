@@ -12,11 +12,56 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod audit;
+pub use audit::*;
+
+mod bandwidth;
+pub use bandwidth::*;
+
+mod block_cache;
+pub use block_cache::*;
+
 mod cache;
 pub use cache::Cache;
 
+mod dandelion;
+pub use dandelion::*;
+
+mod directory;
+pub use directory::*;
+
+mod discovery;
+pub use discovery::*;
+
+mod disconnect;
+pub use disconnect::*;
+
+mod fanout;
+pub use fanout::*;
+
+mod hidden;
+pub use hidden::*;
+
+mod identity;
+pub use identity::*;
+
+mod liveness;
+pub use liveness::*;
+
 mod peer;
 pub use peer::*;
 
+mod peer_groups;
+pub use peer_groups::*;
+
+mod recorder;
+pub use recorder::*;
+
 mod resolver;
 pub use resolver::*;
+
+mod time_sync;
+pub use time_sync::*;
+
+mod verification;
+pub use verification::*;
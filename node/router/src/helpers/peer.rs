@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::messages::{ChallengeRequest, NodeType};
+use crate::messages::{ChallengeRequest, NodeInfo, NodeType, RestEndpoint};
 use snarkvm::prelude::{Address, Network};
 
 use std::{net::SocketAddr, time::Instant};
@@ -24,26 +24,44 @@ pub struct Peer<N: Network> {
     peer_ip: SocketAddr,
     /// The Aleo address of the peer.
     address: Address<N>,
+    /// The address of the consensus account of the peer, as bound in its challenge request, or its Aleo address.
+    consensus_address: Address<N>,
+    /// The node ID of the peer.
+    node_id: u128,
     /// The node type of the peer.
     node_type: NodeType,
     /// The message version of the peer.
     version: u32,
+    /// The software version and features of the peer, once it has sent a ping.
+    node_info: Option<NodeInfo>,
+    /// The latest block height of the peer, as reported in the handshake and its pings.
+    height: u32,
     /// The timestamp of the first message received from the peer.
     first_seen: Instant,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
+    /// The timestamp of the oldest ping sent to this peer that has not been answered with a pong yet.
+    ping_sent: Option<Instant>,
+    /// The public REST endpoint advertised by the peer, once verified against its address.
+    rest_endpoint: Option<RestEndpoint<N>>,
 }
 
 impl<N: Network> Peer<N> {
     /// Initializes a new instance of `Peer`.
-    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>) -> Self {
+    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>, height: u32) -> Self {
         Self {
             peer_ip: listening_ip,
             address: challenge_request.address,
+            consensus_address: challenge_request.consensus_address(),
+            node_id: challenge_request.node_id.id(),
             node_type: challenge_request.node_type,
             version: challenge_request.version,
+            node_info: None,
+            height,
             first_seen: Instant::now(),
             last_seen: Instant::now(),
+            ping_sent: None,
+            rest_endpoint: None,
         }
     }
 
@@ -57,6 +75,17 @@ impl<N: Network> Peer<N> {
         self.address
     }
 
+    /// Returns the address of the consensus account of the peer, which is its Aleo address unless the peer is
+    /// a validator with a separate peer-to-peer identity.
+    pub const fn consensus_address(&self) -> Address<N> {
+        self.consensus_address
+    }
+
+    /// Returns the node ID of the peer.
+    pub const fn node_id(&self) -> u128 {
+        self.node_id
+    }
+
     /// Returns the node type.
     pub const fn node_type(&self) -> NodeType {
         self.node_type
@@ -82,6 +111,16 @@ impl<N: Network> Peer<N> {
         self.version
     }
 
+    /// Returns the software version and features of the peer, if it has sent a ping.
+    pub const fn node_info(&self) -> Option<&NodeInfo> {
+        self.node_info.as_ref()
+    }
+
+    /// Returns the latest block height of the peer, as reported in the handshake and its pings.
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
     /// Returns the first seen timestamp of the peer.
     pub fn first_seen(&self) -> Instant {
         self.first_seen
@@ -91,6 +130,16 @@ impl<N: Network> Peer<N> {
     pub fn last_seen(&self) -> Instant {
         self.last_seen
     }
+
+    /// Returns the timestamp of the oldest unanswered ping sent to the peer, if there is one.
+    pub fn ping_sent(&self) -> Option<Instant> {
+        self.ping_sent
+    }
+
+    /// Returns the public REST endpoint advertised by the peer, if there is one.
+    pub const fn rest_endpoint(&self) -> Option<&RestEndpoint<N>> {
+        self.rest_endpoint.as_ref()
+    }
 }
 
 impl<N: Network> Peer<N> {
@@ -104,8 +153,33 @@ impl<N: Network> Peer<N> {
         self.version = version;
     }
 
+    /// Updates the software version and features.
+    pub fn set_node_info(&mut self, node_info: NodeInfo) {
+        self.node_info = Some(node_info);
+    }
+
+    /// Updates the latest block height of the peer.
+    pub fn set_height(&mut self, height: u32) {
+        self.height = height;
+    }
+
     /// Updates the last seen timestamp of the peer.
     pub fn set_last_seen(&mut self, last_seen: Instant) {
         self.last_seen = last_seen;
     }
+
+    /// Records a ping sent to the peer, unless an earlier ping is still unanswered.
+    pub fn set_ping_sent(&mut self, ping_sent: Instant) {
+        self.ping_sent.get_or_insert(ping_sent);
+    }
+
+    /// Records that the peer answered the outstanding ping.
+    pub fn clear_ping_sent(&mut self) {
+        self.ping_sent = None;
+    }
+
+    /// Updates the public REST endpoint advertised by the peer.
+    pub fn set_rest_endpoint(&mut self, rest_endpoint: Option<RestEndpoint<N>>) {
+        self.rest_endpoint = rest_endpoint;
+    }
 }
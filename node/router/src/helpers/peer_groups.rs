@@ -0,0 +1,213 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Context, Result};
+use indexmap::{IndexMap, IndexSet};
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, path::Path};
+
+/// The name of the peer group of the trusted peers, which are given on the command line.
+pub const TRUSTED_PEER_GROUP: &str = "trusted";
+
+/// The policy that a peer group grants to its peers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerPolicy {
+    /// If `true`, the node reconnects to the peers whenever they are disconnected.
+    pub always_reconnect: bool,
+    /// If `true`, the peers are never disconnected to refresh the peers or to make room for other peers.
+    pub never_evict: bool,
+    /// If `true`, the peers are never restricted, e.g. for misbehaving or for spamming connection attempts.
+    pub never_restrict: bool,
+    /// If `true`, the peers are exempt from the limits on inbound messages, puzzle requests,
+    /// connection attempts, block requests while shedding load, and from the bandwidth caps.
+    pub exempt_from_rate_limits: bool,
+}
+
+impl PeerPolicy {
+    /// The policy of the trusted peers, which the node keeps connected.
+    pub const TRUSTED: Self =
+        Self { always_reconnect: true, never_evict: true, never_restrict: false, exempt_from_rate_limits: false };
+    /// The policy of the peers discovered on the local network in development mode, which the node keeps connected
+    /// and exempts from the rate limits, but still restricts for misbehaving.
+    pub const LOCAL_DEV: Self =
+        Self { always_reconnect: true, never_evict: true, never_restrict: false, exempt_from_rate_limits: true };
+
+    /// Returns the policy that grants everything granted by either policy.
+    pub const fn union(self, other: Self) -> Self {
+        Self {
+            always_reconnect: self.always_reconnect || other.always_reconnect,
+            never_evict: self.never_evict || other.never_evict,
+            never_restrict: self.never_restrict || other.never_restrict,
+            exempt_from_rate_limits: self.exempt_from_rate_limits || other.exempt_from_rate_limits,
+        }
+    }
+}
+
+/// A named group of peers, which share a policy.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerGroup {
+    /// The name of the group, e.g. `committee`, `monitoring`, or `private-relay`.
+    pub name: String,
+    /// The listener addresses of the peers in the group.
+    pub peers: Vec<SocketAddr>,
+    /// The policy that the group grants to its peers.
+    #[serde(default)]
+    pub policy: PeerPolicy,
+}
+
+/// The configuration of the peer groups, which is loaded from a JSON file, e.g.
+///
+/// ```json
+/// {
+///     "groups": [
+///         { "name": "committee", "peers": ["1.2.3.4:4130"], "policy": { "always_reconnect": true } },
+///         { "name": "monitoring", "peers": ["5.6.7.8:4130"], "policy": { "exempt_from_rate_limits": true } }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerGroupConfig {
+    /// The peer groups.
+    pub groups: Vec<PeerGroup>,
+}
+
+impl PeerGroupConfig {
+    /// Loads the peer group configuration from the given path.
+    pub fn load(path: &Path) -> Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the peer group configuration at '{}'", path.display()))?;
+        serde_json::from_str(&config)
+            .with_context(|| format!("Failed to parse the peer group configuration at '{}'", path.display()))
+    }
+}
+
+/// The named peer groups of the router.
+///
+/// A peer may belong to several groups, in which case it is granted the union of their policies.
+#[derive(Clone, Debug, Default)]
+pub struct PeerGroups {
+    /// The map of group names to groups.
+    groups: IndexMap<String, PeerGroup>,
+}
+
+impl PeerGroups {
+    /// Initializes the peer groups with the given trusted peers, if there are any.
+    pub fn with_trusted_peers(trusted_peers: &[SocketAddr]) -> Self {
+        let mut groups = Self::default();
+        if !trusted_peers.is_empty() {
+            let peers = trusted_peers.to_vec();
+            let group = PeerGroup { name: TRUSTED_PEER_GROUP.to_string(), peers, policy: PeerPolicy::TRUSTED };
+            groups.groups.insert(group.name.clone(), group);
+        }
+        groups
+    }
+
+    /// Inserts the given peer group, ensuring its name is not already taken.
+    pub fn insert(&mut self, group: PeerGroup) -> Result<()> {
+        ensure!(!group.name.is_empty(), "A peer group must have a name");
+        ensure!(!self.groups.contains_key(&group.name), "The peer group '{}' already exists", group.name);
+        self.groups.insert(group.name.clone(), group);
+        Ok(())
+    }
+
+    /// Adds the given peer to the group with the given name, which is created with the given policy if needed.
+    pub fn insert_peer(&mut self, name: &str, peer: SocketAddr, policy: PeerPolicy) {
+        let group = self.groups.entry(name.to_string()).or_insert_with(|| PeerGroup {
+            name: name.to_string(),
+            peers: Vec::new(),
+            policy,
+        });
+        if !group.peers.contains(&peer) {
+            group.peers.push(peer);
+        }
+    }
+
+    /// Returns the peer groups.
+    pub fn groups(&self) -> impl '_ + Iterator<Item = &PeerGroup> {
+        self.groups.values()
+    }
+
+    /// Returns the policy of the given peer, which is the union of the policies of its groups.
+    pub fn policy(&self, peer_ip: &SocketAddr) -> PeerPolicy {
+        self.groups
+            .values()
+            .filter(|group| group.peers.contains(peer_ip))
+            .fold(PeerPolicy::default(), |policy, group| policy.union(group.policy))
+    }
+
+    /// Returns the peers whose policy satisfies the given predicate.
+    pub fn peers_with(&self, predicate: impl Fn(&PeerPolicy) -> bool) -> IndexSet<SocketAddr> {
+        self.groups.values().filter(|group| predicate(&group.policy)).flat_map(|group| group.peers.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_groups() {
+        let (committee, monitor, trusted) =
+            ("1.2.3.4:4130".parse().unwrap(), "5.6.7.8:4130".parse().unwrap(), "9.9.9.9:4130".parse().unwrap());
+
+        let config: PeerGroupConfig = serde_json::from_str(
+            r#"{ "groups": [
+                { "name": "committee", "peers": ["1.2.3.4:4130", "5.6.7.8:4130"],
+                  "policy": { "always_reconnect": true } },
+                { "name": "monitoring", "peers": ["5.6.7.8:4130"], "policy": { "exempt_from_rate_limits": true } }
+            ] }"#,
+        )
+        .unwrap();
+        let mut groups = PeerGroups::with_trusted_peers(&[trusted]);
+        for group in config.groups {
+            groups.insert(group).unwrap();
+        }
+        assert_eq!(groups.groups().count(), 3);
+
+        // Ensure the trusted peers are kept connected.
+        assert_eq!(groups.policy(&trusted), PeerPolicy::TRUSTED);
+        // Ensure a peer is granted the union of the policies of its groups.
+        assert_eq!(groups.policy(&committee), PeerPolicy { always_reconnect: true, ..Default::default() });
+        let policy = PeerPolicy { always_reconnect: true, exempt_from_rate_limits: true, ..Default::default() };
+        assert_eq!(groups.policy(&monitor), policy);
+        // Ensure a peer outside of the groups is granted nothing.
+        assert_eq!(groups.policy(&"1.1.1.1:4130".parse().unwrap()), PeerPolicy::default());
+
+        // Ensure the peers to reconnect to are found.
+        let reconnect = groups.peers_with(|policy| policy.always_reconnect);
+        assert_eq!(reconnect, [trusted, committee, monitor].into_iter().collect());
+
+        // Ensure a peer can be added to a new or an existing group.
+        let discovered = "192.168.1.2:4130".parse().unwrap();
+        groups.insert_peer("mdns", discovered, PeerPolicy::LOCAL_DEV);
+        groups.insert_peer("mdns", discovered, PeerPolicy::default());
+        assert_eq!(groups.groups().count(), 4);
+        assert_eq!(groups.policy(&discovered), PeerPolicy::LOCAL_DEV);
+        groups.insert_peer("committee", discovered, PeerPolicy::default());
+        assert_eq!(groups.policy(&discovered), PeerPolicy::LOCAL_DEV);
+        assert_eq!(groups.peers_with(|policy| policy.exempt_from_rate_limits).len(), 2);
+
+        // Ensure the group names are unique.
+        let duplicate = PeerGroup { name: "committee".to_string(), peers: vec![], policy: Default::default() };
+        assert!(groups.insert(duplicate).is_err());
+        // Ensure unknown policies are rejected.
+        let result = serde_json::from_str::<PeerGroupConfig>(
+            r#"{ "groups": [{ "name": "admin", "peers": [], "policy": { "allow_everything": true } }] }"#,
+        );
+        assert!(result.is_err());
+    }
+}
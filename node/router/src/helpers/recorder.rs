@@ -0,0 +1,221 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::Message;
+use snarkvm::prelude::{Network, ToBytes};
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+use time::OffsetDateTime;
+
+/// The default maximum size of a message trace file in bytes, after which it is rotated.
+pub const DEFAULT_MESSAGE_TRACE_MAX_SIZE: u64 = 64 * 1024 * 1024; // 64 MiB
+/// The default number of rotated message trace files that are kept, in addition to the current one.
+pub const DEFAULT_MESSAGE_TRACE_MAX_FILES: usize = 8;
+
+/// The direction of a recorded message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageDirection {
+    /// The message was received from the peer.
+    Inbound,
+    /// The message was sent to the peer.
+    Outbound,
+}
+
+/// An entry in a message trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageTraceEntry {
+    /// The UNIX timestamp of the message, in milliseconds.
+    pub timestamp: i128,
+    /// The listener address of the recording node.
+    pub node: SocketAddr,
+    /// The listener address of the peer.
+    pub peer: SocketAddr,
+    /// The direction of the message.
+    pub direction: MessageDirection,
+    /// The type of the message.
+    pub message: String,
+    /// The name of the message, which may include a summary of its contents.
+    pub name: String,
+    /// The size of the serialized message in bytes, excluding the length prefix of the frame.
+    pub size: usize,
+    /// The serialized message, as a hex string, if payloads are recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+}
+
+/// A development tool that records every message the router sends and receives after the handshake to disk,
+/// as JSON lines in rotating files, to make the protocol-level debugging of multi-node networks tractable.
+///
+/// The trace of a node is written to `<directory>/messages-<port>.jsonl`, so that several nodes can share
+/// a directory. When the trace file exceeds its maximum size, the rotated files are shifted from `.1` up to
+/// the maximum number of files (dropping the oldest one), the trace file is moved to `.1`, and a new one is started.
+pub struct MessageRecorder {
+    /// The listener address of the recording node.
+    node_ip: SocketAddr,
+    /// The path of the trace file.
+    path: PathBuf,
+    /// The maximum size of the trace file in bytes.
+    max_size: u64,
+    /// The number of rotated trace files that are kept.
+    max_files: usize,
+    /// The boolean flag to record the serialized messages, in addition to their metadata.
+    record_payloads: bool,
+    /// The trace file, and its current size in bytes.
+    file: Mutex<(File, u64)>,
+}
+
+impl MessageRecorder {
+    /// Opens the message trace of the given node in the given directory, appending to it if it exists.
+    pub fn open(
+        directory: &Path,
+        node_ip: SocketAddr,
+        max_size: u64,
+        max_files: usize,
+        record_payloads: bool,
+    ) -> Result<Self> {
+        let path = Self::trace_path(directory, node_ip);
+        let file = Self::open_file(&path)
+            .with_context(|| format!("Failed to open the message trace at '{}'", path.display()))?;
+        let size = file.metadata()?.len();
+        Ok(Self { node_ip, path, max_size, max_files, record_payloads, file: Mutex::new((file, size)) })
+    }
+
+    /// Returns the path of the trace file of the given node in the given directory.
+    pub fn trace_path(directory: &Path, node_ip: SocketAddr) -> PathBuf {
+        directory.join(format!("messages-{}.jsonl", node_ip.port()))
+    }
+
+    /// Records the given message, sent to or received from the given peer.
+    pub fn record<N: Network>(&self, peer: SocketAddr, direction: MessageDirection, message: &Message<N>) {
+        if let Err(error) = self.try_record(peer, direction, message) {
+            warn!("Failed to write to the message trace at '{}' - {error}", self.path.display());
+        }
+    }
+
+    /// Serializes the given message, and appends its entry to the trace file.
+    fn try_record<N: Network>(
+        &self,
+        peer: SocketAddr,
+        direction: MessageDirection,
+        message: &Message<N>,
+    ) -> Result<()> {
+        let bytes = message.to_bytes_le()?;
+        let name = message.name().to_string();
+        let entry = MessageTraceEntry {
+            timestamp: OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000,
+            node: self.node_ip,
+            peer,
+            direction,
+            message: name.split_whitespace().next().unwrap_or_default().to_string(),
+            name,
+            size: bytes.len(),
+            payload: self.record_payloads.then(|| bytes.iter().map(|byte| format!("{byte:02x}")).collect()),
+        };
+        Ok(self.append(&entry)?)
+    }
+
+    /// Appends the given entry to the trace file, rotating the files if it exceeds the maximum size.
+    fn append(&self, entry: &MessageTraceEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock();
+        // Rotate the trace files, if the entry would exceed the maximum size.
+        if file.1 > 0 && file.1 + line.len() as u64 > self.max_size {
+            self.rotate()?;
+            *file = (Self::open_file(&self.path)?, 0);
+        }
+        file.0.write_all(&line)?;
+        file.1 += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shifts the rotated trace files by one, dropping the oldest one, and moves the trace file to `.1`.
+    fn rotate(&self) -> io::Result<()> {
+        if self.max_files == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        for index in (1..self.max_files).rev() {
+            let from = Self::rotated_path(&self.path, index);
+            if from.exists() {
+                std::fs::rename(from, Self::rotated_path(&self.path, index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, Self::rotated_path(&self.path, 1))
+    }
+
+    /// Returns the path of the rotated trace file with the given index.
+    fn rotated_path(path: &Path, index: usize) -> PathBuf {
+        let mut rotated_path = path.to_path_buf().into_os_string();
+        rotated_path.push(format!(".{index}"));
+        rotated_path.into()
+    }
+
+    /// Opens the trace file at the given path for appending, creating it if it does not exist.
+    fn open_file(path: &Path) -> io::Result<File> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Pong;
+    use snarkvm::prelude::Testnet3;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_message_recorder() {
+        let directory = std::env::temp_dir().join(format!("snarkos-message-trace-{}", rand::random::<u64>()));
+        let (node, peer) = ("127.0.0.1:4130".parse().unwrap(), "127.0.0.1:4131".parse().unwrap());
+        let sent = Message::<CurrentNetwork>::Pong(Pong::new(Some(false), 0));
+        let pong = Message::<CurrentNetwork>::Pong(Pong::new(None, 0));
+
+        // Open a recorder that rotates after every entry, and keeps a single rotated file.
+        let recorder = MessageRecorder::open(&directory, node, 1, 1, true).unwrap();
+        recorder.record(peer, MessageDirection::Outbound, &sent);
+        recorder.record(peer, MessageDirection::Inbound, &pong);
+        recorder.record(peer, MessageDirection::Inbound, &pong);
+
+        // Ensure the oldest entry was dropped, and the others were recorded with their metadata.
+        let path = MessageRecorder::trace_path(&directory, node);
+        let read = |path: &Path| -> MessageTraceEntry {
+            serde_json::from_str(std::fs::read_to_string(path).unwrap().trim_end()).unwrap()
+        };
+        let (rotated, current) = (read(&MessageRecorder::rotated_path(&path, 1)), read(&path));
+        assert!(!MessageRecorder::rotated_path(&path, 2).exists());
+        for entry in [&rotated, &current] {
+            assert_eq!((entry.node, entry.peer), (node, peer));
+            assert_eq!(entry.direction, MessageDirection::Inbound);
+            assert_eq!(entry.message, "Pong");
+            assert_eq!(entry.size, pong.to_bytes_le().unwrap().len());
+            assert_eq!(entry.payload.as_ref().unwrap().len(), 2 * entry.size);
+        }
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
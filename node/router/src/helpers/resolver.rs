@@ -21,6 +21,9 @@ pub struct Resolver {
     from_listener: RwLock<HashMap<SocketAddr, SocketAddr>>,
     /// The map of the (ambiguous) peer address to listener address.
     to_listener: RwLock<HashMap<SocketAddr, SocketAddr>>,
+    /// The map of node IDs to the last-known listener address.
+    /// Note: Unlike the other maps, entries are kept after a peer disconnects.
+    node_ids: RwLock<HashMap<u128, SocketAddr>>,
 }
 
 impl Default for Resolver {
@@ -31,9 +34,12 @@ impl Default for Resolver {
 }
 
 impl Resolver {
+    /// The maximum number of node IDs to remember.
+    const MAXIMUM_NODE_IDS: usize = 10_000;
+
     /// Initializes a new instance of the resolver.
     pub fn new() -> Self {
-        Self { from_listener: Default::default(), to_listener: Default::default() }
+        Self { from_listener: Default::default(), to_listener: Default::default(), node_ids: Default::default() }
     }
 
     /// Returns the listener address for the given (ambiguous) peer address, if it exists.
@@ -46,6 +52,26 @@ impl Resolver {
         self.from_listener.read().get(peer_ip).copied()
     }
 
+    /// Returns the last-known listener address for the given node ID, if it exists.
+    pub fn get_listener_for_node_id(&self, node_id: u128) -> Option<SocketAddr> {
+        self.node_ids.read().get(&node_id).copied()
+    }
+
+    /// Returns the node ID that was last seen at the given listener address, if it exists.
+    pub fn get_node_id(&self, listener_ip: &SocketAddr) -> Option<u128> {
+        self.node_ids.read().iter().find(|(_, ip)| *ip == listener_ip).map(|(node_id, _)| *node_id)
+    }
+
+    /// Inserts the listener address for the given node ID, and returns the previous listener address, if it changed.
+    pub fn insert_node_id(&self, node_id: u128, listener_ip: SocketAddr) -> Option<SocketAddr> {
+        let mut node_ids = self.node_ids.write();
+        // Ensure the map of node IDs does not grow without bound.
+        if node_ids.len() >= Self::MAXIMUM_NODE_IDS && !node_ids.contains_key(&node_id) {
+            return None;
+        }
+        node_ids.insert(node_id, listener_ip).filter(|previous_ip| *previous_ip != listener_ip)
+    }
+
     /// Inserts a bidirectional mapping of the listener address and the (ambiguous) peer address.
     pub fn insert_peer(&self, listener_ip: SocketAddr, peer_addr: SocketAddr) {
         self.from_listener.write().insert(listener_ip, peer_addr);
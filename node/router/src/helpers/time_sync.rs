@@ -0,0 +1,116 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::RwLock;
+use std::{collections::HashMap, net::SocketAddr};
+
+/// The default clock skew in milliseconds, beyond which the node warns that its clock is off.
+pub const DEFAULT_MAX_CLOCK_SKEW_IN_MS: i64 = 2_000;
+
+/// An estimate of the offset of the network time from the local clock, sampled from the timestamps in pings and pongs.
+///
+/// Each pong carries the time of the peer, and the node times the round trip since its own ping, so the offset of the
+/// peer clock is estimated as in NTP, assuming a symmetric round trip. Only the committee members are sampled, and only
+/// the latest sample of each peer is kept. The network time is the median over the peers, so that a minority of
+/// validators with bad (or malicious) clocks cannot move the estimate outside of the range of the honest validators.
+/// As the round trip is timed locally, a peer can only lengthen it, which is bounded by the maximum round trip.
+#[derive(Debug, Default)]
+pub struct TimeSync {
+    /// The latest clock offset of each peer, in milliseconds.
+    offsets: RwLock<HashMap<SocketAddr, i64>>,
+}
+
+impl TimeSync {
+    /// The minimum number of peers to sample, before the network time is estimated.
+    pub const MINIMUM_PEERS: usize = 3;
+    /// The maximum round trip of a sample in milliseconds, beyond which the sample is too imprecise to be kept.
+    pub const MAXIMUM_ROUND_TRIP_IN_MS: i64 = 2_000;
+
+    /// Records the clock offset of the given peer, given the locally timed round trip of the ping, the timestamp
+    /// of the peer at which the pong was sent, and the local timestamp at which the pong was received.
+    /// Returns `false` if the sample is discarded, as its round trip is invalid or too long.
+    pub fn insert_sample(&self, peer_ip: SocketAddr, round_trip: i64, pong_sent: i64, pong_received: i64) -> bool {
+        if !(0..=Self::MAXIMUM_ROUND_TRIP_IN_MS).contains(&round_trip) {
+            return false;
+        }
+        let offset = pong_sent.saturating_sub(pong_received.saturating_sub(round_trip / 2));
+        self.offsets.write().insert(peer_ip, offset);
+        true
+    }
+
+    /// Removes the sample of the given peer.
+    pub fn remove_peer(&self, peer_ip: &SocketAddr) {
+        self.offsets.write().remove(peer_ip);
+    }
+
+    /// Returns the number of sampled peers.
+    pub fn num_peers(&self) -> usize {
+        self.offsets.read().len()
+    }
+
+    /// Returns the estimated offset of the network time from the local clock in milliseconds, if enough peers
+    /// were sampled. A positive offset means the local clock is behind the network.
+    pub fn offset(&self) -> Option<i64> {
+        let mut offsets = self.offsets.read().values().copied().collect::<Vec<_>>();
+        if offsets.len() < Self::MINIMUM_PEERS {
+            return None;
+        }
+        offsets.sort_unstable();
+        // Take the median, averaging the two middle samples if the number of samples is even.
+        let middle = offsets.len() / 2;
+        match offsets.len() % 2 {
+            0 => Some((offsets[middle - 1] + offsets[middle]) / 2),
+            _ => Some(offsets[middle]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    #[test]
+    fn test_time_sync() {
+        let time_sync = TimeSync::default();
+
+        // A peer whose clock is 1s ahead, answering after a 100ms round trip.
+        assert!(time_sync.insert_sample(peer(1), 100, 11_050, 10_100));
+        assert!(time_sync.insert_sample(peer(2), 100, 11_050, 10_100));
+        // There are not enough peers for an estimate yet.
+        assert_eq!(time_sync.offset(), None);
+
+        // A single peer with a wildly wrong clock does not move the estimate.
+        assert!(time_sync.insert_sample(peer(3), 100, 1_000_000, 10_100));
+        assert_eq!(time_sync.offset(), Some(1_000));
+        // Only the latest sample of each peer is kept.
+        assert!(time_sync.insert_sample(peer(3), 100, 2_000_000, 10_100));
+        assert_eq!(time_sync.num_peers(), 3);
+        assert_eq!(time_sync.offset(), Some(1_000));
+
+        // Samples with an invalid or too long round trip are discarded.
+        assert!(!time_sync.insert_sample(peer(4), -1, 10_000, 10_100));
+        assert!(!time_sync.insert_sample(peer(4), TimeSync::MAXIMUM_ROUND_TRIP_IN_MS + 1, 10_000, 12_100));
+
+        // The median averages the two middle samples of an even number of peers.
+        assert!(time_sync.insert_sample(peer(4), 100, 10_050, 10_100));
+        time_sync.remove_peer(&peer(3));
+        assert!(time_sync.insert_sample(peer(5), 100, 10_050, 10_100));
+        assert_eq!(time_sync.num_peers(), 4);
+        assert_eq!(time_sync.offset(), Some(500));
+    }
+}
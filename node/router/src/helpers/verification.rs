@@ -0,0 +1,111 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    time::Instant,
+};
+
+/// The state of the verification of the candidate peers. A gossiped candidate peer is only dialed once it
+/// answered a challenge request, which is sent over a short-lived connection that does not complete the handshake.
+/// The candidate peers that fail to answer are discarded, and are not accepted again until their failures decay.
+#[derive(Debug, Default)]
+pub struct CandidateVerification {
+    /// The candidate peers that answered a challenge request, or that the node was connected to.
+    verified: RwLock<HashSet<SocketAddr>>,
+    /// The candidate peers that are being probed.
+    probing: RwLock<HashSet<SocketAddr>>,
+    /// The number of failed probes of each candidate peer, along with the time of the latest failure.
+    failures: RwLock<HashMap<SocketAddr, (usize, Instant)>>,
+}
+
+impl CandidateVerification {
+    /// The number of failed probes after which a candidate peer is discarded.
+    pub const MAXIMUM_FAILURES: usize = 2;
+
+    /// Returns `true` if the given candidate peer is verified.
+    pub fn is_verified(&self, peer_ip: &SocketAddr) -> bool {
+        self.verified.read().contains(peer_ip)
+    }
+
+    /// Returns `true` if the given candidate peer failed too many probes, within the given decay.
+    pub fn is_discarded(&self, peer_ip: &SocketAddr, decay_in_secs: u64) -> bool {
+        self.failures.read().get(peer_ip).map_or(false, |(num_failures, time)| {
+            *num_failures >= Self::MAXIMUM_FAILURES && time.elapsed().as_secs() < decay_in_secs
+        })
+    }
+
+    /// Marks the given candidate peer as being probed. Returns `false` if it is already being probed.
+    pub fn start_probe(&self, peer_ip: SocketAddr) -> bool {
+        self.probing.write().insert(peer_ip)
+    }
+
+    /// Marks the given candidate peer as verified.
+    pub fn insert_verified(&self, peer_ip: SocketAddr) {
+        self.probing.write().remove(&peer_ip);
+        self.failures.write().remove(&peer_ip);
+        self.verified.write().insert(peer_ip);
+    }
+
+    /// Records a failed probe of the given candidate peer. Returns `true` if the candidate peer is discarded.
+    pub fn insert_failure(&self, peer_ip: SocketAddr) -> bool {
+        self.probing.write().remove(&peer_ip);
+        let mut failures = self.failures.write();
+        let (num_failures, time) = failures.entry(peer_ip).or_insert((0, Instant::now()));
+        *num_failures += 1;
+        *time = Instant::now();
+        *num_failures >= Self::MAXIMUM_FAILURES
+    }
+
+    /// Forgets the verification of the peers that are no longer candidates, and the failures that decayed.
+    pub fn retain(&self, is_candidate: impl Fn(&SocketAddr) -> bool, decay_in_secs: u64) {
+        self.verified.write().retain(|peer_ip| is_candidate(peer_ip));
+        self.failures.write().retain(|_, (_, time)| time.elapsed().as_secs() < decay_in_secs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_verification() {
+        let verification = CandidateVerification::default();
+        let peer_ip = SocketAddr::from(([1, 2, 3, 4], 4130));
+
+        // A candidate peer is discarded once it fails the maximum number of probes.
+        assert!(verification.start_probe(peer_ip));
+        assert!(!verification.start_probe(peer_ip));
+        assert!(!verification.insert_failure(peer_ip));
+        assert!(!verification.is_discarded(&peer_ip, 60));
+        assert!(verification.start_probe(peer_ip));
+        assert!(verification.insert_failure(peer_ip));
+        assert!(verification.is_discarded(&peer_ip, 60));
+        assert!(!verification.is_verified(&peer_ip));
+
+        // The failures are forgotten once they decay.
+        verification.retain(|_| true, 0);
+        assert!(!verification.is_discarded(&peer_ip, 60));
+
+        // A verified candidate peer is forgotten once it is no longer a candidate.
+        verification.insert_verified(peer_ip);
+        assert!(verification.is_verified(&peer_ip));
+        verification.retain(|_| true, 60);
+        assert!(verification.is_verified(&peer_ip));
+        verification.retain(|_| false, 60);
+        assert!(!verification.is_verified(&peer_ip));
+    }
+}
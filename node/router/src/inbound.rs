@@ -16,25 +16,39 @@ use crate::{
     messages::{
         BlockRequest,
         BlockResponse,
+        CheckpointRequest,
+        CheckpointResponse,
+        CommitteeRequest,
         DataBlocks,
+        DataTransactions,
+        DisconnectReason,
+        now_in_millis,
         Message,
+        PeerRequest,
         PeerResponse,
         Ping,
         Pong,
         UnconfirmedSolution,
         UnconfirmedTransaction,
     },
+    record_disconnect_received,
+    MessageDirection,
     Outbound,
     Peer,
 };
 use snarkos_node_tcp::protocols::Reading;
-use snarkvm::prelude::{
-    block::{Block, Header, Transaction},
-    coinbase::{EpochChallenge, ProverSolution},
-    Network,
+use snarkvm::{
+    ledger::narwhal::Data,
+    prelude::{
+        block::{Block, Header, Transaction},
+        coinbase::{EpochChallenge, ProverSolution},
+        FromBytes,
+        Network,
+        ToBytes,
+    },
 };
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, bail, Error, Result};
 use snarkos_node_tcp::is_bogon_ip;
 use std::{net::SocketAddr, time::Instant};
 use tokio::task::spawn_blocking;
@@ -58,14 +72,20 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             None => bail!("Unable to resolve the (ambiguous) peer address '{peer_addr}'"),
         };
 
+        // Determine whether the peer groups of the peer exempt it from the rate limits.
+        let is_exempt = self.router().peer_policy(&peer_ip).exempt_from_rate_limits;
+
         // Drop the peer, if they have sent more than `MESSAGE_LIMIT` messages
         // in the last `MESSAGE_LIMIT_TIME_FRAME_IN_SECS` seconds.
         let num_messages = self.router().cache.insert_inbound_message(peer_ip, Self::MESSAGE_LIMIT_TIME_FRAME_IN_SECS);
-        if num_messages > Self::MESSAGE_LIMIT {
-            bail!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")
+        if num_messages > Self::MESSAGE_LIMIT && !is_exempt {
+            return Err(Error::new(DisconnectReason::Oversubscribed)
+                .context(format!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")));
         }
 
         trace!("Received '{}' from '{peer_ip}'", message.name());
+        // Record the message, if the message recorder is enabled.
+        self.router().record_message(peer_ip, MessageDirection::Inbound, &message);
 
         // This match statement handles the inbound message by deserializing the message,
         // checking that the message is valid, and then calling the appropriate (trait) handler.
@@ -81,6 +101,11 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 if end_height - start_height > DataBlocks::<N>::MAXIMUM_NUMBER_OF_BLOCKS as u32 {
                     bail!("Block request from '{peer_ip}' has an excessive range ({start_height}..{end_height})")
                 }
+                // Drop the block request if the node is shedding load, so that the peer retries it elsewhere.
+                if self.router().is_throttled_block_request(peer_ip) {
+                    debug!("Dropping a block request from '{peer_ip}' while shedding load");
+                    return Ok(());
+                }
 
                 let node = self.clone();
                 match spawn_blocking(move || node.block_request(peer_ip, message)).await? {
@@ -95,6 +120,10 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 if !self.router().cache.remove_outbound_block_request(peer_ip, &request) {
                     bail!("Peer '{peer_ip}' is not following the protocol (unexpected block response)")
                 }
+                // Ensure the serialized blocks are within the maximum size.
+                let maximum_size =
+                    Message::<N>::MAXIMUM_BLOCK_SIZE * DataBlocks::<N>::MAXIMUM_NUMBER_OF_BLOCKS as usize;
+                self.ensure_data_size(peer_ip, "BlockResponse", &blocks, maximum_size)?;
                 // Perform the deferred non-blocking deserialization of the blocks.
                 let blocks = blocks.deserialize().await.map_err(|error| anyhow!("[BlockResponse] {error}"))?;
                 // Ensure the block response is well-formed.
@@ -112,9 +141,13 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 bail!("Peer '{peer_ip}' is not following the protocol")
             }
             Message::Disconnect(message) => {
-                bail!("{:?}", message.reason)
+                record_disconnect_received(message.reason);
+                debug!("Peer '{peer_ip}' disconnected: {}", message.reason);
+                // Disconnect from this peer, without replying, as the peer has already dropped this node.
+                self.router().disconnect(peer_ip);
+                Ok(())
             }
-            Message::PeerRequest(..) => match self.peer_request(peer_ip) {
+            Message::PeerRequest(message) => match self.peer_request(peer_ip, &message) {
                 true => Ok(()),
                 false => bail!("Peer '{peer_ip}' sent an invalid peer request"),
             },
@@ -131,7 +164,8 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             Message::Ping(message) => {
                 // Ensure the message protocol version is not outdated.
                 if message.version < Message::<N>::VERSION {
-                    bail!("Dropping '{peer_ip}' on message version {} (outdated)", message.version);
+                    return Err(Error::new(DisconnectReason::OutdatedClientVersion)
+                        .context(format!("Dropping '{peer_ip}' on message version {} (outdated)", message.version)));
                 }
 
                 // If the peer is a client or validator, ensure there are block locators.
@@ -144,19 +178,47 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     bail!("Peer '{peer_ip}' is a prover or client, but block locators were provided");
                 }
 
+                // If the peer advertises a REST endpoint, ensure it is a validator that signed the endpoint.
+                if let Some(rest_endpoint) = &message.rest_endpoint {
+                    if !message.node_type.is_validator() {
+                        bail!("Peer '{peer_ip}' is a {}, but advertised a REST endpoint", message.node_type);
+                    }
+                    let Some(peer) = self.router().get_connected_peer(&peer_ip) else {
+                        bail!("Peer '{peer_ip}' is not connected");
+                    };
+                    if !rest_endpoint.verify(&peer.address()) {
+                        bail!("Peer '{peer_ip}' advertised a REST endpoint that it did not sign");
+                    }
+                }
+
                 // Update the connected peer.
                 if let Err(error) =
                     self.router().update_connected_peer(peer_ip, message.node_type, |peer: &mut Peer<N>| {
                         // Update the version of the peer.
                         peer.set_version(message.version);
+                        // Update the software version and features of the peer.
+                        peer.set_node_info(message.node_info.clone());
                         // Update the node type of the peer.
                         peer.set_node_type(message.node_type);
+                        // Update the public REST endpoint of the peer.
+                        peer.set_rest_endpoint(message.rest_endpoint.clone());
+                        // Update the latest block height of the peer.
+                        if let Some(block_locators) = &message.block_locators {
+                            peer.set_height(block_locators.latest_locator_height());
+                        }
                         // Update the last seen timestamp of the peer.
                         peer.set_last_seen(Instant::now());
                     })
                 {
                     bail!("[Ping] {error}");
                 }
+                // Record the REST URL that the validator signed, in the validator directory.
+                if message.node_type.is_validator() {
+                    if let Some(peer) = self.router().get_connected_peer(&peer_ip) {
+                        let rest_url = message.rest_endpoint.as_ref().map(|endpoint| endpoint.url().to_string());
+                        self.router().validator_directory().set_rest_url(&peer.consensus_address(), rest_url);
+                    }
+                }
 
                 // Process the ping message.
                 match self.ping(peer_ip, message) {
@@ -164,16 +226,30 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid ping"),
                 }
             }
-            Message::Pong(message) => match self.pong(peer_ip, message) {
-                true => Ok(()),
-                false => bail!("Peer '{peer_ip}' sent an invalid pong"),
-            },
+            Message::Pong(message) => {
+                // Record that the peer answered the outstanding ping.
+                let round_trip = self.router().record_pong_received(peer_ip);
+                // Sample the clock of the peer, if it is a committee member, timing the round trip locally.
+                if let Some(round_trip) = round_trip.filter(|_| self.router().is_time_source(&peer_ip)) {
+                    let round_trip = i64::try_from(round_trip.as_millis()).unwrap_or(i64::MAX);
+                    let (pong_sent, pong_received) = (message.timestamp, now_in_millis());
+                    if !self.router().time_sync().insert_sample(peer_ip, round_trip, pong_sent, pong_received) {
+                        trace!("Discarded the clock sample of '{peer_ip}' (round trip too long)");
+                    }
+                }
+                // Process the pong message.
+                match self.pong(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid pong"),
+                }
+            }
             Message::PuzzleRequest(..) => {
                 // Insert the puzzle request for the peer, and fetch the recent frequency.
                 let frequency = self.router().cache.insert_inbound_puzzle_request(peer_ip);
                 // Check if the number of puzzle requests is within the limit.
-                if frequency > Self::MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL {
-                    bail!("Peer '{peer_ip}' is not following the protocol (excessive puzzle requests)")
+                if frequency > Self::MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL && !is_exempt {
+                    let message = format!("Peer '{peer_ip}' is not following the protocol (excessive puzzle requests)");
+                    return Err(Error::new(DisconnectReason::Oversubscribed).context(message));
                 }
                 // Process the puzzle request.
                 match self.puzzle_request(peer_ip) {
@@ -189,6 +265,13 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 // Decrement the number of puzzle requests.
                 self.router().cache.decrement_outbound_puzzle_requests(peer_ip);
 
+                // Ensure the serialized block header is within the maximum size.
+                self.ensure_data_size(
+                    peer_ip,
+                    "PuzzleResponse",
+                    &message.block_header,
+                    Message::<N>::MAXIMUM_BLOCK_HEADER_SIZE,
+                )?;
                 // Perform the deferred non-blocking deserialization of the block header.
                 let header = match message.block_header.deserialize().await {
                     Ok(header) => header,
@@ -210,6 +293,9 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     trace!("Skipping 'UnconfirmedSolution' from '{peer_ip}'");
                     return Ok(());
                 }
+                // Ensure the serialized solution is within the maximum size.
+                let maximum_size = Message::<N>::MAXIMUM_SOLUTION_SIZE;
+                self.ensure_data_size(peer_ip, "UnconfirmedSolution", &message.solution, maximum_size)?;
                 // Perform the deferred non-blocking deserialization of the solution.
                 let solution = match message.solution.deserialize().await {
                     Ok(solution) => solution,
@@ -226,6 +312,8 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 }
             }
             Message::UnconfirmedTransaction(message) => {
+                // The transaction was broadcast, so it is no longer awaited as a stem transaction.
+                self.router().dandelion().remove_stem_transaction(&message.transaction_id);
                 // Clone the serialized message.
                 let serialized = message.clone();
                 // Update the timestamp for the unconfirmed transaction.
@@ -236,6 +324,9 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     trace!("Skipping 'UnconfirmedTransaction' from '{peer_ip}'");
                     return Ok(());
                 }
+                // Ensure the serialized transaction is within the maximum size.
+                let maximum_size = Message::<N>::MAXIMUM_TRANSACTION_SIZE;
+                self.ensure_data_size(peer_ip, "UnconfirmedTransaction", &message.transaction, maximum_size)?;
                 // Perform the deferred non-blocking deserialization of the transaction.
                 let transaction = match message.transaction.deserialize().await {
                     Ok(transaction) => transaction,
@@ -251,19 +342,164 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed transaction"),
                 }
             }
+            Message::UnconfirmedTransactions(message) => {
+                // Ensure the serialized transactions are within the maximum size.
+                let maximum_size = Message::<N>::MAXIMUM_TRANSACTION_SIZE
+                    * DataTransactions::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS as usize;
+                self.ensure_data_size(peer_ip, "UnconfirmedTransactions", &message.transactions, maximum_size)?;
+                // Perform the deferred non-blocking deserialization of the transactions.
+                let transactions = match message.transactions.deserialize().await {
+                    Ok(transactions) => transactions,
+                    Err(error) => bail!("[UnconfirmedTransactions] {error}"),
+                };
+                // Check that the transaction parameters match.
+                if transactions.iter().map(|transaction| transaction.id()).ne(message.transaction_ids.iter().copied()) {
+                    bail!("Peer '{peer_ip}' is not following the 'UnconfirmedTransactions' protocol")
+                }
+                // Handle each transaction as if it was received in its own 'UnconfirmedTransaction'.
+                for transaction in transactions.0 {
+                    // The transaction was broadcast, so it is no longer awaited as a stem transaction.
+                    self.router().dandelion().remove_stem_transaction(&transaction.id());
+                    // Update the timestamp for the unconfirmed transaction.
+                    let seen_before =
+                        self.router().cache.insert_inbound_transaction(peer_ip, transaction.id()).is_some();
+                    if seen_before {
+                        continue;
+                    }
+                    let serialized = UnconfirmedTransaction::from(transaction.clone());
+                    if !self.unconfirmed_transaction(peer_ip, serialized, transaction).await {
+                        bail!("Peer '{peer_ip}' sent an invalid unconfirmed transaction");
+                    }
+                }
+                Ok(())
+            }
+            Message::StemTransaction(message) => {
+                // Ensure the serialized transaction is within the maximum size.
+                let maximum_size = Message::<N>::MAXIMUM_TRANSACTION_SIZE;
+                self.ensure_data_size(peer_ip, "StemTransaction", &message.transaction, maximum_size)?;
+                // Prepare the serialized message, in case the transaction is broadcast.
+                let serialized = UnconfirmedTransaction::from(message.clone());
+                // Perform the deferred non-blocking deserialization of the transaction.
+                let transaction = match message.transaction.deserialize().await {
+                    Ok(transaction) => transaction,
+                    Err(error) => bail!("[StemTransaction] {error}"),
+                };
+                // Check that the transaction parameters match.
+                if message.transaction_id != transaction.id() {
+                    bail!("Peer '{peer_ip}' is not following the 'StemTransaction' protocol")
+                }
+                // Continue the stem, unless the node is a validator, the Dandelion relay is disabled,
+                // the stem looped back to the node, or the node randomly ends the stem.
+                let dandelion = self.router().dandelion();
+                if !self.router().node_type().is_validator()
+                    && dandelion.is_enabled()
+                    && !dandelion.contains_stem_transaction(&message.transaction_id)
+                    && !dandelion.should_fluff()
+                {
+                    if let Some(stem_peer) = dandelion.stem_peer(&self.router().stem_candidates(), &[peer_ip]) {
+                        // Ensure the transaction is valid, so that this node never relays an invalid transaction.
+                        if !self.is_valid_stem_transaction(transaction).await {
+                            trace!("Skipping invalid stem transaction '{}' from '{peer_ip}'", message.transaction_id);
+                            return Ok(());
+                        }
+                        trace!("Forwarding transaction '{}' along the stem to '{stem_peer}'", message.transaction_id);
+                        if let Some(evicted) = dandelion.insert_stem_transaction(serialized.clone()) {
+                            self.propagate(Message::UnconfirmedTransaction(evicted), &[]);
+                        }
+                        self.send(stem_peer, Message::StemTransaction(serialized.into()));
+                        return Ok(());
+                    }
+                }
+                // Otherwise, broadcast the transaction, as if it was received in an 'UnconfirmedTransaction'.
+                dandelion.remove_stem_transaction(&message.transaction_id);
+                self.router().cache.insert_inbound_transaction(peer_ip, message.transaction_id);
+                match self.unconfirmed_transaction(peer_ip, serialized, transaction).await {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid stem transaction"),
+                }
+            }
+            Message::CommitteeRequest(message) => {
+                let node = self.clone();
+                match spawn_blocking(move || node.committee_request(peer_ip, message)).await? {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid committee request"),
+                }
+            }
+            Message::CommitteeResponse(..) => {
+                // Committee responses are served to light clients, and are never requested by this node.
+                bail!("Peer '{peer_ip}' is not following the protocol (unexpected committee response)")
+            }
+            Message::CheckpointRequest(message) => match self.checkpoint_request(peer_ip, message) {
+                true => Ok(()),
+                false => bail!("Peer '{peer_ip}' sent an invalid checkpoint request"),
+            },
+            Message::CheckpointResponse(message) => match self.checkpoint_response(peer_ip, message) {
+                true => Ok(()),
+                false => bail!("Peer '{peer_ip}' sent an invalid checkpoint response"),
+            },
         }
     }
 
+    /// Ensures the given serialized data from the peer is within the maximum size, before it is deserialized.
+    /// Oversized data is treated as a memory exhaustion attempt, so the peer is restricted.
+    fn ensure_data_size<T: FromBytes + ToBytes + Send + 'static>(
+        &self,
+        peer_ip: SocketAddr,
+        message_name: &str,
+        data: &Data<T>,
+        maximum_size: usize,
+    ) -> Result<()> {
+        if let Data::Buffer(bytes) = data {
+            if bytes.len() > maximum_size {
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter(metrics::router::OVERSIZED_MESSAGES);
+                self.router().insert_restricted_peer(peer_ip);
+                let message = format!(
+                    "Peer '{peer_ip}' sent an oversized '{message_name}' ({} > {maximum_size} bytes)",
+                    bytes.len()
+                );
+                return Err(Error::new(DisconnectReason::Banned).context(message));
+            }
+        }
+        Ok(())
+    }
+
     /// Handles a `BlockRequest` message.
     fn block_request(&self, peer_ip: SocketAddr, _message: BlockRequest) -> bool;
 
     /// Handles a `BlockResponse` message.
     fn block_response(&self, peer_ip: SocketAddr, _blocks: Vec<Block<N>>) -> bool;
 
+    /// Handles a `CommitteeRequest` message.
+    /// By default, nodes without a ledger disconnect on receipt of a `CommitteeRequest` message.
+    fn committee_request(&self, peer_ip: SocketAddr, _message: CommitteeRequest) -> bool {
+        debug!("Disconnecting '{peer_ip}' for an unsupported committee request");
+        false
+    }
+
+    /// Handles a `CheckpointRequest` message.
+    /// By default, nodes that do not certify checkpoints disconnect on receipt of a `CheckpointRequest` message.
+    fn checkpoint_request(&self, peer_ip: SocketAddr, _message: CheckpointRequest) -> bool {
+        debug!("Disconnecting '{peer_ip}' for an unsupported checkpoint request");
+        false
+    }
+
+    /// Handles a `CheckpointResponse` message.
+    /// By default, nodes that do not fast sync ignore a `CheckpointResponse` message, as it is harmless.
+    fn checkpoint_response(&self, peer_ip: SocketAddr, _message: CheckpointResponse<N>) -> bool {
+        debug!("Ignoring an unrequested checkpoint response from '{peer_ip}'");
+        true
+    }
+
     /// Handles a `PeerRequest` message.
-    fn peer_request(&self, peer_ip: SocketAddr) -> bool {
-        // Retrieve the connected peers.
-        let peers = self.router().connected_peers();
+    fn peer_request(&self, peer_ip: SocketAddr, request: &PeerRequest) -> bool {
+        // Retrieve the connected peers that match the request.
+        let peers = self
+            .router()
+            .get_connected_peers()
+            .into_iter()
+            .filter(|peer| request.matches(peer.node_type(), peer.node_info()))
+            .map(|peer| peer.ip());
         // Filter out invalid addresses.
         let peers = match self.router().is_dev() {
             // In development mode, relax the validity requirements to make operating devnets more flexible.
@@ -323,4 +559,8 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         serialized: UnconfirmedTransaction<N>,
         _transaction: Transaction<N>,
     ) -> bool;
+
+    /// Returns `true` if the given stem transaction is well-formed and unique, so that it may be forwarded
+    /// along the stem.
+    async fn is_valid_stem_transaction(&self, transaction: Transaction<N>) -> bool;
 }
@@ -38,23 +38,46 @@ pub use outbound::*;
 mod routing;
 pub use routing::*;
 
-use crate::messages::NodeType;
+use crate::messages::{
+    ConsensusBinding,
+    DisconnectReason,
+    MalformedFrame,
+    Message,
+    MessageCodec,
+    NodeId,
+    NodeInfo,
+    NodeType,
+    RestEndpoint,
+};
 use snarkos_account::Account;
-use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, Config, Tcp};
-use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
+use snarkos_node_bus::{EventBus, NodeEvent};
+use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, Config, Tcp, TokenBucket};
+use snarkvm::prelude::{block::Header, Address, Network, PrivateKey, ViewKey};
 
 use anyhow::{bail, Result};
+use bytes::BytesMut;
+use indexmap::IndexSet;
+use mdns_sd::{ServiceEvent, ServiceInfo};
+use once_cell::sync::OnceCell;
 use parking_lot::{Mutex, RwLock};
 use std::{
     collections::{HashMap, HashSet},
     future::Future,
+    io,
     net::SocketAddr,
     ops::Deref,
     str::FromStr,
-    sync::Arc,
-    time::Instant,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 use tokio::task::JoinHandle;
+use tokio_util::codec::Encoder;
+
+/// The default maximum number of connections that a single node identity may hold.
+pub const DEFAULT_MAXIMUM_CONNECTIONS_PER_IDENTITY: usize = 1;
 
 #[derive(Clone)]
 pub struct Router<N: Network>(Arc<InnerRouter<N>>);
@@ -74,12 +97,16 @@ pub struct InnerRouter<N: Network> {
     node_type: NodeType,
     /// The account of the node.
     account: Account<N>,
+    /// The persistent node ID of the node.
+    node_id: NodeId<N>,
+    /// The binding of the account of the node to the consensus account of the validator, if they are separate.
+    consensus_binding: OnceCell<ConsensusBinding<N>>,
     /// The cache.
     cache: Cache<N>,
     /// The resolver.
     resolver: Resolver,
-    /// The set of trusted peers.
-    trusted_peers: HashSet<SocketAddr>,
+    /// The named peer groups, and the policies they grant to their peers.
+    peer_groups: RwLock<PeerGroups>,
     /// The map of connected peer IPs to their peer handlers.
     connected_peers: RwLock<HashMap<SocketAddr, Peer<N>>>,
     /// The set of handshaking peers. While `Tcp` already recognizes the connecting IP addresses
@@ -89,12 +116,54 @@ pub struct InnerRouter<N: Network> {
     connecting_peers: Mutex<HashSet<SocketAddr>>,
     /// The set of candidate peer IPs.
     candidate_peers: RwLock<HashSet<SocketAddr>>,
+    /// The verification of the candidate peers, which are only dialed once they answered a challenge request.
+    candidate_verification: CandidateVerification,
+    /// The genesis block header, as given to the handshakes, with which the candidate peers are verified.
+    genesis_header: OnceCell<Header<N>>,
     /// The set of restricted peer IPs.
     restricted_peers: RwLock<HashMap<SocketAddr, Instant>>,
+    /// The set of restricted node IDs, so that restrictions survive address changes.
+    restricted_node_ids: RwLock<HashMap<u128, Instant>>,
+    /// The event bus, to which the peer connections are published.
+    event_bus: OnceCell<EventBus<N>>,
     /// The spawned handles.
     handles: Mutex<Vec<JoinHandle<()>>>,
     /// The boolean flag for the development mode.
     is_dev: bool,
+    /// The boolean flag for the load-shedding mode, in which serving blocks to peers is throttled.
+    is_load_shedding: AtomicBool,
+    /// The latest block height of the node, as last advertised to its peers.
+    latest_height: AtomicU32,
+    /// The audit log of the peer connections, if it is enabled.
+    audit_log: OnceCell<AuditLog>,
+    /// The recorder of the messages of the router, if it is enabled.
+    message_recorder: OnceCell<MessageRecorder>,
+    /// The state of the Dandelion relay for the unconfirmed transactions.
+    dandelion: Dandelion<N>,
+    /// The maximum number of connections that a single node identity may hold.
+    max_connections_per_identity: AtomicUsize,
+    /// The thresholds with which unresponsive peer connections are detected.
+    liveness: RwLock<Liveness>,
+    /// The policy with which the peers to which a propagated message is sent are selected.
+    fanout_policy: RwLock<FanoutPolicy>,
+    /// The token buckets that cap the bandwidth of the messages written to each class of peers.
+    bandwidth: RwLock<BandwidthLimiter>,
+    /// The public REST endpoint that the node advertises to its peers, if any.
+    rest_endpoint: RwLock<Option<RestEndpoint<N>>>,
+    /// The cache of the recent blocks, which are served to the peers and the REST server.
+    block_cache: BlockCache<N>,
+    /// The directory of the latest known endpoints of the validators.
+    validator_directory: ValidatorDirectory<N>,
+    /// The committee membership check of the validators recorded in the directory, if the directory is enabled.
+    directory_members: OnceCell<Box<dyn Fn(&Address<N>) -> bool + Send + Sync>>,
+    /// The estimate of the network time, sampled from the pings and pongs of the peers.
+    time_sync: TimeSync,
+    /// The committee membership check of the peers whose clocks are sampled, if the time sync is enabled.
+    time_sources: OnceCell<Box<dyn Fn(&Address<N>) -> bool + Send + Sync>>,
+    /// The discovery of the nodes on the local network, if it is enabled.
+    local_discovery: OnceCell<LocalDiscovery>,
+    /// The admission of the inbound peers, if the node is a hidden validator.
+    hidden_mode: OnceCell<HiddenMode<N>>,
 }
 
 impl<N: Network> Router<N> {
@@ -102,9 +171,25 @@ impl<N: Network> Router<N> {
     const MAXIMUM_CANDIDATE_PEERS: usize = 10_000;
     /// The maximum number of connection failures permitted by an inbound connecting peer.
     const MAXIMUM_CONNECTION_FAILURES: usize = 5;
+    /// The maximum number of failed connection attempts to a candidate peer, before it is removed.
+    const MAXIMUM_CANDIDATE_FAILURES: usize = 3;
+    /// The maximum duration in seconds of the probe with which a candidate peer is verified.
+    const CANDIDATE_PROBE_TIMEOUT_IN_SECS: u64 = 5;
+    /// The duration in seconds after which a failed connection attempt to a peer is forgotten.
+    const CONNECTION_FAILURE_DECAY_IN_SECS: u64 = 1800; // 30 minutes
+    /// The maximum number of malformed frames permitted from a peer IP address, before it is restricted.
+    const MAXIMUM_MALFORMED_FRAMES: usize = 3;
+    /// The duration in seconds after which a malformed frame from a peer is forgotten.
+    const MALFORMED_FRAME_DECAY_IN_SECS: u64 = 1800; // 30 minutes
     /// The duration in seconds after which a connected peer is considered inactive or
     /// disconnected if no message has been received in the meantime.
     const RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
+    /// The maximum number of block requests served per peer in the load-shedding interval, while shedding load.
+    const MAXIMUM_BLOCK_REQUESTS_WHILE_SHEDDING: usize = 10;
+    /// The duration in seconds over which the block requests of a peer are counted, while shedding load.
+    const LOAD_SHEDDING_INTERVAL_IN_SECS: i64 = 10;
+    /// The maximum number of blocks a peer may be behind (or ahead of) the node, before either one is considered stale.
+    pub const MAXIMUM_HEIGHT_LAG: u32 = 100;
 }
 
 impl<N: Network> Router<N> {
@@ -113,28 +198,94 @@ impl<N: Network> Router<N> {
         node_ip: SocketAddr,
         node_type: NodeType,
         account: Account<N>,
+        node_id: NodeId<N>,
         trusted_peers: &[SocketAddr],
         max_peers: u16,
         is_dev: bool,
     ) -> Result<Self> {
-        // Initialize the TCP stack.
-        let tcp = Tcp::new(Config::new(node_ip, max_peers));
+        // Initialize the TCP stack, which tells the inbound peers it rejects for being full that it has too many peers.
+        let mut config = Config::new(node_ip, max_peers);
+        let mut rejection_message = BytesMut::new();
+        MessageCodec::<N>::handshake().encode(Message::from(DisconnectReason::TooManyPeers), &mut rejection_message)?;
+        config.rejection_message = Some(rejection_message.to_vec());
+        let tcp = Tcp::new(config);
         // Initialize the router.
         Ok(Self(Arc::new(InnerRouter {
             tcp,
             node_type,
             account,
+            node_id,
+            consensus_binding: Default::default(),
             cache: Default::default(),
             resolver: Default::default(),
-            trusted_peers: trusted_peers.iter().copied().collect(),
+            peer_groups: RwLock::new(PeerGroups::with_trusted_peers(trusted_peers)),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             candidate_peers: Default::default(),
+            candidate_verification: Default::default(),
+            genesis_header: Default::default(),
             restricted_peers: Default::default(),
+            restricted_node_ids: Default::default(),
+            event_bus: Default::default(),
             handles: Default::default(),
             is_dev,
+            is_load_shedding: Default::default(),
+            latest_height: Default::default(),
+            audit_log: Default::default(),
+            message_recorder: Default::default(),
+            dandelion: Default::default(),
+            max_connections_per_identity: AtomicUsize::new(DEFAULT_MAXIMUM_CONNECTIONS_PER_IDENTITY),
+            liveness: Default::default(),
+            fanout_policy: Default::default(),
+            bandwidth: Default::default(),
+            rest_endpoint: Default::default(),
+            block_cache: Default::default(),
+            validator_directory: Default::default(),
+            directory_members: Default::default(),
+            time_sync: Default::default(),
+            time_sources: Default::default(),
+            local_discovery: Default::default(),
+            hidden_mode: Default::default(),
         })))
     }
+
+    /// Sets the event bus, to which the router publishes the peer connections.
+    pub fn set_event_bus(&self, event_bus: EventBus<N>) {
+        self.event_bus.set(event_bus).expect("Event bus already set");
+    }
+
+    /// Sets the audit log, to which the router records the peer connections.
+    pub fn set_audit_log(&self, audit_log: AuditLog) {
+        if self.audit_log.set(audit_log).is_err() {
+            panic!("Audit log already set");
+        }
+    }
+
+    /// Returns the audit log of the peer connections, if it is enabled.
+    pub fn audit_log(&self) -> Option<&AuditLog> {
+        self.audit_log.get()
+    }
+
+    /// Records the given event for the given peer in the audit log, if it is enabled.
+    pub(crate) fn audit(&self, peer_ip: SocketAddr, event: AuditEvent) {
+        if let Some(audit_log) = self.audit_log.get() {
+            audit_log.record(peer_ip, event);
+        }
+    }
+
+    /// Sets the message recorder, to which the router records every message it sends and receives.
+    pub fn set_message_recorder(&self, message_recorder: MessageRecorder) {
+        if self.message_recorder.set(message_recorder).is_err() {
+            panic!("Message recorder already set");
+        }
+    }
+
+    /// Records the given message for the given peer with the message recorder, if it is enabled.
+    pub(crate) fn record_message(&self, peer_ip: SocketAddr, direction: MessageDirection, message: &Message<N>) {
+        if let Some(message_recorder) = self.message_recorder.get() {
+            message_recorder.record(peer_ip, direction, message);
+        }
+    }
 }
 
 impl<N: Network> Router<N> {
@@ -148,16 +299,21 @@ impl<N: Network> Router<N> {
 
         let router = self.clone();
         Some(tokio::spawn(async move {
+            router.audit(peer_ip, AuditEvent::ConnectionAttempt);
             // Attempt to connect to the candidate peer.
             match router.tcp.connect(peer_ip).await {
                 // Remove the peer from the candidate peers.
                 Ok(()) => {
                     router.remove_candidate_peer(peer_ip);
+                    router.cache.remove_outbound_connection_failures(&peer_ip);
                     true
                 }
                 // If the connection was not allowed, log the error.
                 Err(error) => {
                     router.connecting_peers.lock().remove(&peer_ip);
+                    let decay_in_secs = Self::CONNECTION_FAILURE_DECAY_IN_SECS as i64;
+                    router.cache.insert_outbound_connection_failure(peer_ip, decay_in_secs);
+                    router.audit(peer_ip, AuditEvent::ConnectionFailed { reason: error.to_string() });
                     warn!("Unable to connect to '{peer_ip}' - {error}");
                     false
                 }
@@ -165,6 +321,42 @@ impl<N: Network> Router<N> {
         }))
     }
 
+    /// Verifies the given candidate peer in the background, by probing it with a challenge request, without
+    /// completing the handshake. The candidate peer is dialed once it is verified, and is discarded once it
+    /// failed to answer `CandidateVerification::MAXIMUM_FAILURES` probes.
+    pub fn verify_candidate_peer(&self, peer_ip: SocketAddr) -> Option<JoinHandle<bool>> {
+        // The candidate peers are only probed once the genesis block header is known from a handshake.
+        let genesis_header = *self.genesis_header.get()?;
+        // Ensure the candidate peer is not already verified or being probed.
+        if self.candidate_verification.is_verified(&peer_ip) || !self.candidate_verification.start_probe(peer_ip) {
+            return None;
+        }
+
+        let router = self.clone();
+        Some(tokio::spawn(async move {
+            let timeout = Duration::from_secs(Self::CANDIDATE_PROBE_TIMEOUT_IN_SECS);
+            let result = match tokio::time::timeout(timeout, router.probe(peer_ip, genesis_header)).await {
+                Ok(result) => result,
+                Err(_) => Err(io::ErrorKind::TimedOut.into()),
+            };
+            match result {
+                Ok(()) => {
+                    debug!("Verified the candidate peer '{peer_ip}'");
+                    router.candidate_verification.insert_verified(peer_ip);
+                    true
+                }
+                Err(error) => {
+                    debug!("Unable to verify the candidate peer '{peer_ip}' - {error}");
+                    if router.candidate_verification.insert_failure(peer_ip) {
+                        debug!("Discarding the candidate peer '{peer_ip}' (failed verification)");
+                        router.remove_candidate_peer(peer_ip);
+                    }
+                    false
+                }
+            }
+        }))
+    }
+
     /// Ensure we are allowed to connect to the given peer.
     fn check_connection_attempt(&self, peer_ip: SocketAddr) -> Result<()> {
         // Ensure the peer IP is not this node.
@@ -246,11 +438,342 @@ impl<N: Network> Router<N> {
         self.account.address()
     }
 
+    /// Returns the persistent node ID of the node.
+    pub fn node_id(&self) -> &NodeId<N> {
+        &self.node_id
+    }
+
+    /// Returns the binding of the account of the node to the consensus account, if the validator bound one.
+    pub fn consensus_binding(&self) -> Option<&ConsensusBinding<N>> {
+        self.consensus_binding.get()
+    }
+
+    /// Returns the address of the consensus account of the node, which is its account address unless it bound another.
+    pub fn consensus_address(&self) -> Address<N> {
+        self.consensus_binding.get().map_or(self.address(), |binding| binding.address())
+    }
+
+    /// Binds the account of the node to the given consensus account of the validator, which signs its batches.
+    /// The binding is sent in the handshakes, so that the peers recognize the validator as a committee member.
+    pub fn bind_consensus_account(&self, consensus_account: &Account<N>) -> Result<()> {
+        if !self.node_type.is_validator() {
+            bail!("Only validators can bind a consensus account")
+        }
+        // The binding is only needed if the accounts are separate.
+        if consensus_account.address() == self.address() {
+            return Ok(());
+        }
+        let binding = ConsensusBinding::new(consensus_account.private_key(), &self.address(), &mut rand::thread_rng())?;
+        if self.consensus_binding.set(binding).is_err() {
+            bail!("The consensus account is already bound")
+        }
+        Ok(())
+    }
+
     /// Returns `true` if the node is in development mode.
     pub fn is_dev(&self) -> bool {
         self.is_dev
     }
 
+    /// Returns `true` if the node is shedding load.
+    pub fn is_load_shedding(&self) -> bool {
+        self.is_load_shedding.load(Ordering::Relaxed)
+    }
+
+    /// Sets the load-shedding mode, in which serving blocks to peers is throttled.
+    pub fn set_load_shedding(&self, is_load_shedding: bool) {
+        self.is_load_shedding.store(is_load_shedding, Ordering::Relaxed)
+    }
+
+    /// Returns the state of the Dandelion relay for the unconfirmed transactions.
+    pub fn dandelion(&self) -> &Dandelion<N> {
+        &self.dandelion
+    }
+
+    /// Returns the connected peers that stem transactions may be forwarded to, which excludes the provers,
+    /// as they do not relay transactions.
+    pub fn stem_candidates(&self) -> Vec<SocketAddr> {
+        self.connected_peers.read().iter().filter(|(_, peer)| !peer.is_prover()).map(|(ip, _)| *ip).collect()
+    }
+
+    /// Returns the latest block height of the node, as last advertised to its peers.
+    pub fn latest_height(&self) -> u32 {
+        self.latest_height.load(Ordering::Relaxed)
+    }
+
+    /// Updates the latest block height of the node, as advertised to its peers.
+    pub fn set_latest_height(&self, height: u32) {
+        self.latest_height.store(height, Ordering::Relaxed)
+    }
+
+    /// Returns the highest block height reported by the connected peers, excluding the provers.
+    pub fn highest_peer_height(&self) -> Option<u32> {
+        self.connected_peers.read().values().filter(|peer| !peer.is_prover()).map(|peer| peer.height()).max()
+    }
+
+    /// Returns `true` if the given peer is far behind the node, and is therefore still syncing.
+    /// Syncing peers are not asked for blocks, as they cannot serve the blocks the node is missing.
+    pub fn is_syncing_peer(&self, peer_ip: &SocketAddr) -> bool {
+        match self.connected_peers.read().get(peer_ip) {
+            Some(peer) => {
+                !peer.is_prover() && peer.height().saturating_add(Self::MAXIMUM_HEIGHT_LAG) < self.latest_height()
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the block request from the given peer should be dropped, because the node is shedding load.
+    pub fn is_throttled_block_request(&self, peer_ip: SocketAddr) -> bool {
+        self.is_load_shedding()
+            && !self.peer_policy(&peer_ip).exempt_from_rate_limits
+            && self.cache.insert_inbound_block_request(peer_ip, Self::LOAD_SHEDDING_INTERVAL_IN_SECS)
+                > Self::MAXIMUM_BLOCK_REQUESTS_WHILE_SHEDDING
+    }
+
+    /// Returns the maximum number of connections that a single node identity may hold.
+    pub fn max_connections_per_identity(&self) -> usize {
+        self.max_connections_per_identity.load(Ordering::Relaxed)
+    }
+
+    /// Sets the maximum number of connections that a single node identity may hold.
+    pub fn set_max_connections_per_identity(&self, max_connections: usize) -> Result<()> {
+        if max_connections == 0 {
+            bail!("A node identity must be allowed at least one connection")
+        }
+        self.max_connections_per_identity.store(max_connections, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns the thresholds with which unresponsive peer connections are detected.
+    pub fn liveness(&self) -> Liveness {
+        *self.liveness.read()
+    }
+
+    /// Sets the thresholds with which unresponsive peer connections are detected.
+    /// Note: The TCP keepalive only applies to the connections established afterwards.
+    pub fn set_liveness(&self, liveness: Liveness) -> Result<()> {
+        liveness.check()?;
+        *self.liveness.write() = liveness;
+        Ok(())
+    }
+
+    /// Returns the policy with which the peers to which a propagated message is sent are selected.
+    pub fn fanout_policy(&self) -> FanoutPolicy {
+        *self.fanout_policy.read()
+    }
+
+    /// Sets the policy with which the peers to which a propagated message is sent are selected.
+    pub fn set_fanout_policy(&self, fanout_policy: FanoutPolicy) -> Result<()> {
+        fanout_policy.check()?;
+        *self.fanout_policy.write() = fanout_policy;
+        Ok(())
+    }
+
+    /// Returns the caps on the bandwidth of the messages written to each class of peers.
+    pub fn bandwidth_caps(&self) -> BandwidthCaps {
+        self.bandwidth.read().caps()
+    }
+
+    /// Sets the caps on the bandwidth of the messages written to each class of peers.
+    pub fn set_bandwidth_caps(&self, caps: BandwidthCaps) -> Result<()> {
+        caps.check()?;
+        *self.bandwidth.write() = BandwidthLimiter::new(caps);
+        Ok(())
+    }
+
+    /// Returns the token bucket that caps the bandwidth to the given (ambiguous) peer address, if the class of
+    /// the peer is capped. The peers that are exempt from the rate limits are not capped.
+    pub fn bandwidth_limiter(&self, peer_addr: SocketAddr) -> Option<Arc<TokenBucket>> {
+        if self.bandwidth_caps() == BandwidthCaps::default() {
+            return None;
+        }
+        let peer_ip = self.resolve_to_listener(&peer_addr)?;
+        let node_type = self.connected_peers.read().get(&peer_ip)?.node_type();
+        match self.peer_policy(&peer_ip).exempt_from_rate_limits {
+            true => None,
+            false => self.bandwidth.read().limiter(node_type),
+        }
+    }
+
+    /// Returns the cache of the recent blocks, which are served to the peers and the REST server.
+    pub fn block_cache(&self) -> &BlockCache<N> {
+        &self.block_cache
+    }
+
+    /// Returns the directory of the latest known endpoints of the validators.
+    pub fn validator_directory(&self) -> &ValidatorDirectory<N> {
+        &self.validator_directory
+    }
+
+    /// Returns the latest known endpoints of the validator with the given address, if the node has seen it.
+    pub fn resolve_validator(&self, address: &Address<N>) -> Option<ValidatorEndpoint<N>> {
+        self.validator_directory.resolve(address)
+    }
+
+    /// Enables the validator directory, which records the connected validators that pass the given committee
+    /// membership check. The other validators are not recorded, as anyone can connect any number of them to the node.
+    pub fn enable_validator_directory(
+        &self,
+        is_committee_member: impl Fn(&Address<N>) -> bool + Send + Sync + 'static,
+    ) -> Result<()> {
+        if self.directory_members.set(Box::new(is_committee_member)).is_err() {
+            bail!("The validator directory is already enabled")
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the given peer is recorded in the validator directory, as a member of the committee.
+    /// The membership is checked on the consensus address of the peer, which is authenticated in the handshake.
+    fn is_directory_member(&self, peer: &Peer<N>) -> bool {
+        let Some(is_committee_member) = self.directory_members.get() else {
+            return false;
+        };
+        peer.node_type().is_validator() && is_committee_member(&peer.consensus_address())
+    }
+
+    /// Returns the estimate of the network time, sampled from the pings and pongs of the peers.
+    pub fn time_sync(&self) -> &TimeSync {
+        &self.time_sync
+    }
+
+    /// Enables the time sync, which samples the clocks of the connected validators that pass the given committee
+    /// membership check. The other peers are not sampled, as anyone can connect any number of them to the node.
+    pub fn enable_time_sync(
+        &self,
+        is_committee_member: impl Fn(&Address<N>) -> bool + Send + Sync + 'static,
+    ) -> Result<()> {
+        if self.time_sources.set(Box::new(is_committee_member)).is_err() {
+            bail!("The time sync is already enabled")
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the clock of the given peer is sampled, as it is a connected member of the committee.
+    /// The membership is checked on the consensus address of the peer, which a validator with a separate
+    /// peer-to-peer identity bound to that identity in its handshake.
+    pub fn is_time_source(&self, peer_ip: &SocketAddr) -> bool {
+        let Some(is_committee_member) = self.time_sources.get() else {
+            return false;
+        };
+        self.get_connected_peer(peer_ip)
+            .map_or(false, |peer| peer.node_type().is_validator() && is_committee_member(&peer.consensus_address()))
+    }
+
+    /// Returns the admission of the inbound peers, if the node is a hidden validator.
+    pub fn hidden_mode(&self) -> Option<&HiddenMode<N>> {
+        self.hidden_mode.get()
+    }
+
+    /// Enables the hidden mode, in which the validator only accepts the inbound connections of the committee
+    /// members and the allow-listed relays, while it still dials out to any peer.
+    pub fn enable_hidden_mode(&self, hidden_mode: HiddenMode<N>) -> Result<()> {
+        if !self.node_type.is_validator() {
+            bail!("Only validators can run in hidden mode")
+        }
+        let num_relays = hidden_mode.relays().len();
+        if self.hidden_mode.set(hidden_mode).is_err() {
+            bail!("Hidden mode is already enabled")
+        }
+        info!("Hidden mode is enabled, with {num_relays} allow-listed relay(s)");
+        Ok(())
+    }
+
+    /// Starts discovering the nodes on the local network over mDNS, which is only permitted in development mode.
+    /// The discovered nodes are added to the candidate peers, and to the `MDNS_PEER_GROUP` peer group,
+    /// whose `LOCAL_DEV` policy keeps them connected.
+    pub fn enable_local_discovery(&self, bft_port: Option<u16>) -> Result<()> {
+        if !self.is_dev {
+            bail!("Local peer discovery is only available in development mode")
+        }
+        if self.local_discovery.get().is_some() {
+            bail!("Local peer discovery is already enabled")
+        }
+        // Name the node after its type and the end of its address, which is unique across the machines.
+        let address = self.address().to_string();
+        let suffix = &address[address.len().saturating_sub(12)..];
+        let instance = format!("snarkos-{}-{suffix}", self.node_type.to_string().to_lowercase());
+        let (discovery, events) =
+            LocalDiscovery::start(N::ID, &instance, self.node_type, self.local_ip().port(), bft_port)?;
+        let _ = self.local_discovery.set(discovery);
+        info!("Discovering the nodes on the local network over mDNS, as '{instance}'");
+
+        let router = self.clone();
+        self.spawn(async move {
+            while let Ok(event) = events.recv_async().await {
+                if let ServiceEvent::ServiceResolved(service) = event {
+                    router.insert_local_peers(&service);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Returns the nodes discovered on the local network so far.
+    pub fn local_peers(&self) -> Vec<LocalPeer> {
+        self.local_discovery.get().map_or_else(Vec::new, |discovery| discovery.peers())
+    }
+
+    /// Adds the nodes advertised by the given service on the local network to the candidate peers.
+    fn insert_local_peers(&self, service: &ServiceInfo) {
+        let Some(discovery) = self.local_discovery.get() else {
+            return;
+        };
+        let mut listeners = Vec::new();
+        for peer in discovery.insert(service) {
+            if self.is_local_ip(&peer.listener) {
+                continue;
+            }
+            debug!("Discovered {} at '{}' on the local network", peer.node_type.description(), peer.listener);
+            self.peer_groups.write().insert_peer(MDNS_PEER_GROUP, peer.listener, PeerPolicy::LOCAL_DEV);
+            // The nodes on the local network are dialed without being verified.
+            self.candidate_verification.insert_verified(peer.listener);
+            listeners.push(peer.listener);
+        }
+        self.insert_candidate_peers(&listeners);
+    }
+
+    /// Returns the public REST endpoint that the node advertises to its peers, if any.
+    pub fn rest_endpoint(&self) -> Option<RestEndpoint<N>> {
+        self.rest_endpoint.read().clone()
+    }
+
+    /// Signs the given public REST URL with the account of the node, and advertises it to the peers.
+    /// Note: Only validators may advertise their REST endpoint.
+    pub fn set_rest_endpoint(&self, url: String) -> Result<()> {
+        if !self.node_type.is_validator() {
+            bail!("Only validators may advertise a REST endpoint");
+        }
+        let rest_endpoint = RestEndpoint::new(self.private_key(), url, &mut rand::thread_rng())?;
+        *self.rest_endpoint.write() = Some(rest_endpoint);
+        Ok(())
+    }
+
+    /// Returns the verified public REST endpoints advertised by the connected validators.
+    pub fn connected_rest_endpoints(&self) -> Vec<RestEndpoint<N>> {
+        self.connected_peers.read().values().filter_map(|peer| peer.rest_endpoint().cloned()).collect()
+    }
+
+    /// Returns the other connections of the node identity of the given peer that exceed the maximum number
+    /// of connections per identity, oldest first. The given peer is never included, as it is the newest connection.
+    ///
+    /// Note: The identity is the persistent node ID, rather than the Aleo address,
+    /// as provers commonly share an address.
+    pub fn duplicate_connections(&self, peer_ip: &SocketAddr) -> Vec<SocketAddr> {
+        let connected_peers = self.connected_peers.read();
+        let Some(node_id) = connected_peers.get(peer_ip).map(|peer| peer.node_id()) else {
+            return vec![];
+        };
+        // Retrieve the other connections of the node identity.
+        let mut duplicates = connected_peers
+            .values()
+            .filter(|peer| peer.node_id() == node_id && peer.ip() != *peer_ip)
+            .collect::<Vec<_>>();
+        // Select the oldest connections in excess of the maximum.
+        let num_excess = (duplicates.len() + 1).saturating_sub(self.max_connections_per_identity());
+        duplicates.sort_by_key(|peer| peer.first_seen());
+        duplicates.into_iter().take(num_excess).map(|peer| peer.ip()).collect()
+    }
+
     /// Returns the listener IP address from the (ambiguous) peer address.
     pub fn resolve_to_listener(&self, peer_addr: &SocketAddr) -> Option<SocketAddr> {
         self.resolver.get_listener(peer_addr)
@@ -276,6 +799,16 @@ impl<N: Network> Router<N> {
         self.connected_peers.read().get(peer_ip).map_or(false, |peer| peer.is_prover())
     }
 
+    /// Returns `true` if the given peer IP is connected, and has the given feature enabled.
+    /// This can be used to avoid sending messages to peers whose software does not understand them.
+    pub fn is_feature_enabled(&self, peer_ip: &SocketAddr, feature: &str) -> bool {
+        self.connected_peers
+            .read()
+            .get(peer_ip)
+            .and_then(|peer| peer.node_info().map(|node_info| node_info.has_feature(feature)))
+            .unwrap_or(false)
+    }
+
     /// Returns `true` if the given peer IP is a connected client.
     pub fn is_connected_client(&self, peer_ip: &SocketAddr) -> bool {
         self.connected_peers.read().get(peer_ip).map_or(false, |peer| peer.is_client())
@@ -295,6 +828,15 @@ impl<N: Network> Router<N> {
             .unwrap_or(false)
     }
 
+    /// Returns `true` if the given node ID is restricted.
+    pub fn is_restricted_node_id(&self, node_id: u128) -> bool {
+        self.restricted_node_ids
+            .read()
+            .get(&node_id)
+            .map(|time| time.elapsed().as_secs() < Self::RADIO_SILENCE_IN_SECS)
+            .unwrap_or(false)
+    }
+
     /// Returns the maximum number of connected peers.
     pub fn max_connected_peers(&self) -> usize {
         self.tcp.config().max_connections as usize
@@ -365,14 +907,47 @@ impl<N: Network> Router<N> {
         self.candidate_peers.read().clone()
     }
 
+    /// Returns the list of candidate peers that are verified, which are the ones that are dialed.
+    pub fn verified_candidate_peers(&self) -> Vec<SocketAddr> {
+        let candidate_peers = self.candidate_peers.read();
+        candidate_peers.iter().filter(|peer_ip| self.candidate_verification.is_verified(peer_ip)).copied().collect()
+    }
+
+    /// Returns the list of candidate peers that are not verified yet.
+    pub fn unverified_candidate_peers(&self) -> Vec<SocketAddr> {
+        let candidate_peers = self.candidate_peers.read();
+        candidate_peers.iter().filter(|peer_ip| !self.candidate_verification.is_verified(peer_ip)).copied().collect()
+    }
+
     /// Returns the list of restricted peers.
     pub fn restricted_peers(&self) -> Vec<SocketAddr> {
         self.restricted_peers.read().keys().copied().collect()
     }
 
-    /// Returns the list of trusted peers.
-    pub fn trusted_peers(&self) -> &HashSet<SocketAddr> {
-        &self.trusted_peers
+    /// Returns the peer groups.
+    pub fn peer_groups(&self) -> PeerGroups {
+        self.peer_groups.read().clone()
+    }
+
+    /// Adds the given peer group, whose policy is enforced for its peers from now on.
+    pub fn add_peer_group(&self, group: PeerGroup) -> Result<()> {
+        self.peer_groups.write().insert(group)
+    }
+
+    /// Returns the policy of the given peer, which is granted by the peer groups it belongs to.
+    pub fn peer_policy(&self, peer_ip: &SocketAddr) -> PeerPolicy {
+        self.peer_groups.read().policy(peer_ip)
+    }
+
+    /// Returns the trusted peers, which are given on the command line.
+    pub fn trusted_peers(&self) -> Vec<SocketAddr> {
+        let peer_groups = self.peer_groups.read();
+        peer_groups.groups().find(|group| group.name == TRUSTED_PEER_GROUP).map_or(vec![], |group| group.peers.clone())
+    }
+
+    /// Returns the peers that the node reconnects to whenever they are disconnected.
+    pub fn reconnect_peers(&self) -> IndexSet<SocketAddr> {
+        self.peer_groups.read().peers_with(|policy| policy.always_reconnect)
     }
 
     /// Returns the list of bootstrap peers.
@@ -390,8 +965,9 @@ impl<N: Network> Router<N> {
     }
 
     /// Returns the list of metrics for the connected peers.
-    pub fn connected_metrics(&self) -> Vec<(SocketAddr, NodeType)> {
-        self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.node_type())).collect()
+    pub fn connected_metrics(&self) -> Vec<(SocketAddr, NodeType, Option<NodeInfo>)> {
+        let connected_peers = self.connected_peers.read();
+        connected_peers.iter().map(|(ip, peer)| (*ip, peer.node_type(), peer.node_info().cloned())).collect()
     }
 
     #[cfg(feature = "metrics")]
@@ -403,15 +979,29 @@ impl<N: Network> Router<N> {
 
     /// Inserts the given peer into the connected peers.
     pub fn insert_connected_peer(&self, peer: Peer<N>, peer_addr: SocketAddr) {
-        let peer_ip = peer.ip();
+        let (peer_ip, node_id) = (peer.ip(), peer.node_id());
+        // Record the listener address of a committee member in the validator directory.
+        if self.is_directory_member(&peer) {
+            self.validator_directory.insert_connected(peer.consensus_address(), peer_ip);
+        }
         // Adds a bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.insert_peer(peer_ip, peer_addr);
+        // Record the listener address of the node ID. If the peer has moved, forget its previous address.
+        if let Some(previous_ip) = self.resolver.insert_node_id(node_id, peer_ip) {
+            debug!("Peer '{node_id:032x}' has moved from '{previous_ip}' to '{peer_ip}'");
+            self.candidate_peers.write().remove(&previous_ip);
+        }
         // Add an entry for this `Peer` in the connected peers.
         self.connected_peers.write().insert(peer_ip, peer);
         // Remove this peer from the candidate peers, if it exists.
         self.candidate_peers.write().remove(&peer_ip);
         // Remove this peer from the restricted peers, if it exists.
         self.restricted_peers.write().remove(&peer_ip);
+        self.restricted_node_ids.write().remove(&node_id);
+        // Publish the connection.
+        if let Some(event_bus) = self.event_bus.get() {
+            event_bus.publish(NodeEvent::PeerConnected { peer_ip });
+        }
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
@@ -429,6 +1019,10 @@ impl<N: Network> Router<N> {
             .filter(|peer_ip| {
                 // Ensure the peer is not itself, is not already connected, and is not restricted.
                 !self.is_local_ip(peer_ip) && !self.is_connected(peer_ip) && !self.is_restricted(peer_ip)
+                    // Ensure the peer has not repeatedly failed to connect recently.
+                    && self.cache.num_outbound_connection_failures(peer_ip) < Self::MAXIMUM_CANDIDATE_FAILURES
+                    // Ensure the peer has not repeatedly failed verification recently.
+                    && !self.candidate_verification.is_discarded(peer_ip, Self::CONNECTION_FAILURE_DECAY_IN_SECS)
             })
             .take(max_candidate_peers);
 
@@ -438,16 +1032,45 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
-    /// Inserts the given peer into the restricted peers.
+    /// Inserts the given peer into the restricted peers, unless its peer groups exempt it from restrictions.
     pub fn insert_restricted_peer(&self, peer_ip: SocketAddr) {
+        if self.peer_policy(&peer_ip).never_restrict {
+            debug!("Not restricting '{peer_ip}' (exempt by its peer group)");
+            return;
+        }
         // Remove this peer from the candidate peers, if it exists.
         self.candidate_peers.write().remove(&peer_ip);
         // Add the peer to the restricted peers.
         self.restricted_peers.write().insert(peer_ip, Instant::now());
+        self.audit(peer_ip, AuditEvent::Restricted);
+        // Add the node ID of the peer to the restricted node IDs, if it is known.
+        let node_id = self.connected_peers.read().get(&peer_ip).map(|peer| peer.node_id());
+        if let Some(node_id) = node_id.or_else(|| self.resolver.get_node_id(&peer_ip)) {
+            self.restricted_node_ids.write().insert(node_id, Instant::now());
+        }
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
 
+    /// Records a malformed frame received from the given peer address. The frames are counted per IP address,
+    /// so that the peer is restricted once it sends too many of them, even if it keeps reconnecting.
+    pub fn record_malformed_frame(&self, peer_addr: SocketAddr, reason: MalformedFrame) {
+        #[cfg(feature = "metrics")]
+        metrics::increment_labeled_counter(
+            metrics::router::MALFORMED_FRAMES,
+            metrics::router::MALFORMED_FRAME_REASON_LABEL,
+            reason.name(),
+        );
+        let peer_ip = self.resolve_to_listener(&peer_addr).unwrap_or(peer_addr);
+        let decay_in_secs = Self::MALFORMED_FRAME_DECAY_IN_SECS as i64;
+        let num_malformed = self.cache.insert_inbound_malformed_frame(peer_addr.ip(), decay_in_secs);
+        debug!("Received a malformed frame from '{peer_ip}' - {reason} ({num_malformed} recently)");
+        if num_malformed >= Self::MAXIMUM_MALFORMED_FRAMES {
+            warn!("Restricting '{peer_ip}' for sending {num_malformed} malformed frames");
+            self.insert_restricted_peer(peer_ip);
+        }
+    }
+
     /// Updates the connected peer with the given function.
     pub fn update_connected_peer<Fn: FnMut(&mut Peer<N>)>(
         &self,
@@ -467,14 +1090,46 @@ impl<N: Network> Router<N> {
         Ok(())
     }
 
+    /// Records a ping sent to the given peer, which the peer is expected to answer with a pong.
+    pub fn record_ping_sent(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.set_ping_sent(Instant::now());
+        }
+    }
+
+    /// Records a pong received from the given peer, which answers its outstanding ping.
+    /// Returns the round trip since the outstanding ping was sent, if there is one.
+    pub fn record_pong_received(&self, peer_ip: SocketAddr) -> Option<Duration> {
+        let mut connected_peers = self.connected_peers.write();
+        let peer = connected_peers.get_mut(&peer_ip)?;
+        let round_trip = peer.ping_sent().map(|ping_sent| ping_sent.elapsed());
+        peer.clear_ping_sent();
+        round_trip
+    }
+
     /// Removes the connected peer and adds them to the candidate peers.
     pub fn remove_connected_peer(&self, peer_ip: SocketAddr) {
         // Removes the bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.remove_peer(&peer_ip);
         // Remove this peer from the connected peers, if it exists.
-        self.connected_peers.write().remove(&peer_ip);
-        // Add the peer to the candidate peers.
+        let removed_peer = self.connected_peers.write().remove(&peer_ip);
+        let was_connected = removed_peer.is_some();
+        if was_connected {
+            self.audit(peer_ip, AuditEvent::Disconnected);
+        }
+        // Remove the clock sample of the peer.
+        self.time_sync.remove_peer(&peer_ip);
+        // Record the disconnection of a validator in the validator directory.
+        if let Some(peer) = removed_peer.filter(|peer| peer.node_type().is_validator()) {
+            self.validator_directory.set_disconnected(&peer.consensus_address());
+        }
+        // Add the peer to the candidate peers. As the peer completed a handshake, it does not need to be verified.
         self.candidate_peers.write().insert(peer_ip);
+        self.candidate_verification.insert_verified(peer_ip);
+        // Publish the disconnection.
+        if let (true, Some(event_bus)) = (was_connected, self.event_bus.get()) {
+            event_bus.publish(NodeEvent::PeerDisconnected { peer_ip });
+        }
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
@@ -493,6 +1148,35 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Removes the expired restrictions, and the candidate peers that repeatedly failed to connect.
+    /// The failed connection attempts decay over time, so that such peers may eventually be retried.
+    pub fn garbage_collect(&self) {
+        // Remove the expired restrictions.
+        self.restricted_peers.write().retain(|_, time| time.elapsed().as_secs() < Self::RADIO_SILENCE_IN_SECS);
+        self.restricted_node_ids.write().retain(|_, time| time.elapsed().as_secs() < Self::RADIO_SILENCE_IN_SECS);
+        // Decay the connection attempts and failures.
+        self.cache.decay_inbound_connections(Self::RADIO_SILENCE_IN_SECS as i64);
+        self.cache.decay_outbound_connection_failures(Self::CONNECTION_FAILURE_DECAY_IN_SECS as i64);
+        self.cache.decay_inbound_block_requests(Self::LOAD_SHEDDING_INTERVAL_IN_SECS);
+        self.cache.decay_inbound_malformed_frames(Self::MALFORMED_FRAME_DECAY_IN_SECS as i64);
+        // Remove the candidate peers that repeatedly failed to connect.
+        let num_candidates = self.number_of_candidate_peers();
+        self.candidate_peers
+            .write()
+            .retain(|peer_ip| self.cache.num_outbound_connection_failures(peer_ip) < Self::MAXIMUM_CANDIDATE_FAILURES);
+        let num_removed = num_candidates.saturating_sub(self.number_of_candidate_peers());
+        if num_removed > 0 {
+            debug!("Removed {num_removed} candidate peers that repeatedly failed to connect");
+        }
+        // Forget the verification of the former candidate peers, and the failed verifications that decayed.
+        let candidate_peers = self.candidate_peers.read();
+        self.candidate_verification
+            .retain(|peer_ip| candidate_peers.contains(peer_ip), Self::CONNECTION_FAILURE_DECAY_IN_SECS);
+        drop(candidate_peers);
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -501,6 +1185,10 @@ impl<N: Network> Router<N> {
     /// Shuts down the router.
     pub async fn shut_down(&self) {
         info!("Shutting down the router...");
+        // Stop advertising the node on the local network.
+        if let Some(discovery) = self.local_discovery.get() {
+            discovery.shut_down();
+        }
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
         // Close the listener.
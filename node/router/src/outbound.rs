@@ -13,12 +13,14 @@
 // limitations under the License.
 
 use crate::{
-    messages::{Message, Ping},
+    messages::{DataTransactions, DisconnectReason, Message, Ping, UnconfirmedTransaction},
+    record_disconnect_sent,
+    MessageDirection,
     Router,
 };
 use snarkos_node_sync_locators::BlockLocators;
 use snarkos_node_tcp::protocols::Writing;
-use snarkvm::prelude::Network;
+use snarkvm::prelude::{Network, Transaction};
 use std::io;
 
 use std::net::SocketAddr;
@@ -30,7 +32,38 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
 
     /// Sends a "Ping" message to the given peer.
     fn send_ping(&self, peer_ip: SocketAddr, block_locators: Option<BlockLocators<N>>) {
-        self.send(peer_ip, Message::Ping(Ping::new(self.router().node_type(), block_locators)));
+        // Record the latest block height of the node, as advertised to the peer.
+        if let Some(block_locators) = &block_locators {
+            self.router().set_latest_height(block_locators.latest_locator_height());
+        }
+        let rest_endpoint = self.router().rest_endpoint();
+        self.send(peer_ip, Message::Ping(Ping::new(self.router().node_type(), block_locators, rest_endpoint)));
+        // Expect the peer to answer with a pong.
+        self.router().record_ping_sent(peer_ip);
+    }
+
+    /// Sends a "Disconnect" message with the given reason to the given peer, and disconnects from it.
+    fn send_disconnect(&self, peer_ip: SocketAddr, reason: DisconnectReason) {
+        self.send(peer_ip, Message::Disconnect(reason.into()));
+        // Disconnect from this peer.
+        self.router().disconnect(peer_ip);
+    }
+
+    /// Disconnects from the older connections of the node identity of the given peer,
+    /// if the identity holds more connections than allowed.
+    fn disconnect_duplicate_connections(&self, peer_ip: SocketAddr) {
+        for duplicate_ip in self.router().duplicate_connections(&peer_ip) {
+            debug!("Disconnecting from '{duplicate_ip}' (duplicate connection of '{peer_ip}')");
+            self.send_disconnect(duplicate_ip, DisconnectReason::DuplicateConnection);
+        }
+    }
+
+    /// Sends a "Disconnect" message to every connected peer, as the node is shutting down.
+    /// Note: This is best-effort, as the messages may not be delivered before the router shuts down.
+    fn send_shutdown(&self) {
+        for peer_ip in self.router().connected_peers() {
+            self.send(peer_ip, Message::Disconnect(DisconnectReason::ShuttingDown.into()));
+        }
     }
 
     /// Sends the given message to specified peer.
@@ -63,6 +96,12 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         if matches!(message, Message::PeerRequest(_)) {
             self.router().cache.increment_outbound_peer_requests(peer_ip);
         }
+        // If the message type is a disconnect, record its reason.
+        if let Message::Disconnect(disconnect) = &message {
+            record_disconnect_sent(disconnect.reason);
+        }
+        // Record the message, if the message recorder is enabled.
+        self.router().record_message(peer_ip, MessageDirection::Outbound, &message);
         // Retrieve the message name.
         let name = message.name();
         // Send the message to the peer.
@@ -77,7 +116,8 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         result.ok()
     }
 
-    /// Sends the given message to every connected peer, excluding the sender and any specified peer IPs.
+    /// Propagates the given message to the connected peers, excluding the sender and any specified peer IPs.
+    /// Large payloads are only sent to a sample of the peers, as selected by the fan-out policy of the router.
     fn propagate(&self, message: Message<N>, excluded_peers: &[SocketAddr]) {
         // TODO (howardwu): Serialize large messages once only.
         // // Perform ahead-of-time, non-blocking serialization just once for applicable objects.
@@ -95,17 +135,44 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         //     }
         // }
 
-        // Prepare the peers to send to.
-        let connected_peers = self.router().connected_peers();
-        let peers = connected_peers.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
+        self.fan_out(message, self.router().connected_peers(), excluded_peers);
+    }
 
-        // Iterate through all peers that are not the sender and excluded peers.
-        for peer_ip in peers {
-            self.send(*peer_ip, message.clone());
+    /// Relays the given transaction, excluding the sender and any specified peer IPs.
+    /// If the Dandelion relay is enabled, the transaction is forwarded to the stem peer, which
+    /// continues the stem or broadcasts the transaction. Otherwise, the transaction is broadcast.
+    fn propagate_transaction(&self, message: UnconfirmedTransaction<N>, excluded_peers: &[SocketAddr]) {
+        let dandelion = self.router().dandelion();
+        if dandelion.is_enabled() && !dandelion.contains_stem_transaction(&message.transaction_id) {
+            if let Some(stem_peer) = dandelion.stem_peer(&self.router().stem_candidates(), excluded_peers) {
+                trace!("Forwarding transaction '{}' along the stem to '{stem_peer}'", message.transaction_id);
+                // Fluff the transaction, if it is not seen broadcast before its timeout.
+                if let Some(evicted) = dandelion.insert_stem_transaction(message.clone()) {
+                    self.propagate(Message::UnconfirmedTransaction(evicted), &[]);
+                }
+                self.send(stem_peer, Message::StemTransaction(message.into()));
+                return;
+            }
+        }
+        self.propagate(Message::UnconfirmedTransaction(message), excluded_peers);
+    }
+
+    /// Relays the given transactions, excluding the sender and any specified peer IPs.
+    /// If the Dandelion relay is enabled, each transaction is relayed along its own stem, as in
+    /// `propagate_transaction`. Otherwise, the transactions are broadcast in as few messages as possible.
+    fn propagate_transactions(&self, transactions: Vec<Transaction<N>>, excluded_peers: &[SocketAddr]) {
+        if self.router().dandelion().is_enabled() {
+            for transaction in transactions {
+                self.propagate_transaction(UnconfirmedTransaction::from(transaction), excluded_peers);
+            }
+            return;
+        }
+        for chunk in transactions.chunks(DataTransactions::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS as usize) {
+            self.propagate(Message::UnconfirmedTransactions(chunk.to_vec().into()), excluded_peers);
         }
     }
 
-    /// Sends the given message to every connected validator, excluding the sender and any specified IPs.
+    /// Propagates the given message to the connected validators, excluding the sender and any specified IPs.
     fn propagate_to_validators(&self, message: Message<N>, excluded_peers: &[SocketAddr]) {
         // TODO (howardwu): Serialize large messages once only.
         // // Perform ahead-of-time, non-blocking serialization just once for applicable objects.
@@ -123,13 +190,22 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         //     }
         // }
 
-        // Prepare the peers to send to.
-        let connected_validators = self.router().connected_validators();
-        let peers = connected_validators.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
+        self.fan_out(message, self.router().connected_validators(), excluded_peers);
+    }
 
-        // Iterate through all validators that are not the sender and excluded validators.
+    /// Sends the given message to the peers selected from the given peers by the fan-out policy of the router.
+    /// The excluded peers, and the peers known to have seen the payload of the message, are skipped.
+    fn fan_out(&self, message: Message<N>, peers: Vec<SocketAddr>, excluded_peers: &[SocketAddr]) {
+        let router = self.router();
+        // Skip the excluded peers, and the peers that already have the payload, and separate the validators.
+        let (validators, others): (Vec<_>, Vec<_>) = peers
+            .into_iter()
+            .filter(|peer_ip| !excluded_peers.contains(peer_ip) && !router.cache.is_payload_seen_by(*peer_ip, &message))
+            .partition(|peer_ip| router.is_connected_validator(peer_ip));
+        // Select the peers to send to.
+        let peers = router.fanout_policy().select_peers(&message, validators, others, &mut rand::thread_rng());
         for peer_ip in peers {
-            self.send(*peer_ip, message.clone());
+            self.send(peer_ip, message.clone());
         }
     }
 
@@ -155,6 +231,15 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
                 // Determine whether to send the transaction.
                 !seen_before
             }
+            Message::UnconfirmedTransactions(message) => {
+                // Update the timestamps for the unconfirmed transactions.
+                let cache = &self.router().cache;
+                let all_seen_before = message.transaction_ids.iter().fold(true, |all_seen_before, transaction_id| {
+                    cache.insert_outbound_transaction(peer_ip, *transaction_id).is_some() && all_seen_before
+                });
+                // Determine whether to send the transactions.
+                !all_seen_before
+            }
             // For all other message types, return `true`.
             _ => true,
         }
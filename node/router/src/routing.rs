@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{messages::Message, Heartbeat, Inbound, Outbound};
+use crate::{messages::Message, Heartbeat, Inbound, Outbound, STEM_TIMEOUT_CHECK_IN_SECS};
+use snarkos_node_clock as clock;
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect},
     P2P,
@@ -37,6 +38,8 @@ pub trait Routing<N: Network>:
         self.enable_listener().await;
         // Initialize the heartbeat.
         self.initialize_heartbeat();
+        // Initialize the Dandelion relay.
+        self.initialize_dandelion();
         // Initialize the report.
         #[cfg(not(feature = "test"))]
         self.initialize_report();
@@ -55,7 +58,32 @@ pub trait Routing<N: Network>:
                 // Process a heartbeat in the router.
                 self_clone.heartbeat();
                 // Sleep for `HEARTBEAT_IN_SECS` seconds.
-                tokio::time::sleep(Duration::from_secs(Self::HEARTBEAT_IN_SECS)).await;
+                clock::sleep(Duration::from_secs(Self::HEARTBEAT_IN_SECS)).await;
+            }
+        });
+    }
+
+    /// Initialize the Dandelion relay, which broadcasts the stem transactions that were not seen broadcast in time.
+    fn initialize_dandelion(&self) {
+        let self_clone = self.clone();
+        self.router().spawn(async move {
+            loop {
+                // Sleep for `STEM_TIMEOUT_CHECK_IN_SECS` seconds.
+                clock::sleep(Duration::from_secs(STEM_TIMEOUT_CHECK_IN_SECS)).await;
+                // Broadcast the expired stem transactions, as the stem may have been interrupted.
+                for message in self_clone.router().dandelion().take_expired_stem_transactions() {
+                    let transaction_id = message.transaction_id;
+                    let transaction = match message.transaction.clone().deserialize().await {
+                        Ok(transaction) => transaction,
+                        Err(error) => {
+                            warn!("Failed to deserialize the stem transaction '{transaction_id}' - {error}");
+                            continue;
+                        }
+                    };
+                    debug!("Broadcasting the stem transaction '{transaction_id}' (timeout expired)");
+                    let local_ip = self_clone.router().local_ip();
+                    self_clone.unconfirmed_transaction(local_ip, message, transaction).await;
+                }
             }
         });
     }
@@ -75,7 +103,7 @@ pub trait Routing<N: Network>:
                 let url = "https://vm.aleo.org/testnet3/report";
                 let _ = reqwest::Client::new().post(url).json(&report).send().await;
                 // Sleep for a fixed duration in seconds.
-                tokio::time::sleep(Duration::from_secs(6 * 60 * 60)).await;
+                clock::sleep(Duration::from_secs(6 * 60 * 60)).await;
             }
         });
     }
@@ -23,7 +23,10 @@ use std::{
 };
 
 use snarkos_account::Account;
-use snarkos_node_router::{messages::NodeType, Router};
+use snarkos_node_router::{
+    messages::{NodeId, NodeType},
+    Router,
+};
 use snarkvm::prelude::{block::Block, FromBytes, Network, Testnet3 as CurrentNetwork};
 
 /// A helper macro to print the TCP listening address, along with the connected and connecting peers.
@@ -44,6 +47,11 @@ pub fn sample_account() -> Account<CurrentNetwork> {
     Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap()
 }
 
+/// Returns a new node ID for the fixed account.
+pub fn sample_node_id() -> NodeId<CurrentNetwork> {
+    NodeId::new(sample_account().private_key(), &mut rand::thread_rng()).unwrap()
+}
+
 /// Loads the current network's genesis block.
 pub fn sample_genesis_block<N: Network>() -> Block<N> {
     Block::<N>::from_bytes_le(N::genesis_bytes()).unwrap()
@@ -75,6 +83,7 @@ pub async fn client(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listening_port),
         NodeType::Client,
         sample_account(),
+        sample_node_id(),
         &[],
         max_peers,
         true,
@@ -91,6 +100,7 @@ pub async fn prover(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listening_port),
         NodeType::Prover,
         sample_account(),
+        sample_node_id(),
         &[],
         max_peers,
         true,
@@ -107,6 +117,7 @@ pub async fn validator(listening_port: u16, max_peers: u16) -> TestRouter<Curren
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listening_port),
         NodeType::Validator,
         sample_account(),
+        sample_node_id(),
         &[],
         max_peers,
         true,
@@ -16,7 +16,6 @@ use crate::common::sample_genesis_block;
 use snarkos_node_router::{
     messages::{
         BlockRequest,
-        DisconnectReason,
         Message,
         MessageCodec,
         Ping,
@@ -24,6 +23,7 @@ use snarkos_node_router::{
         UnconfirmedSolution,
         UnconfirmedTransaction,
     },
+    disconnect_reason,
     Heartbeat,
     Inbound,
     Outbound,
@@ -80,7 +80,7 @@ impl<N: Network> Handshake for TestRouter<N> {
         let conn_side = connection.side();
         let stream = self.borrow_stream(&mut connection);
         let genesis_header = *sample_genesis_block().header();
-        self.router().handshake(peer_addr, stream, conn_side, genesis_header).await?;
+        self.router().handshake(peer_addr, stream, conn_side, genesis_header, 0).await?;
 
         Ok(connection)
     }
@@ -131,9 +131,7 @@ impl<N: Network> Reading for TestRouter<N> {
         // Process the message. Disconnect if the peer violated the protocol.
         if let Err(error) = self.inbound(peer_ip, message).await {
             warn!("Disconnecting from '{peer_ip}' - {error}");
-            self.send(peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
-            // Disconnect from this peer.
-            self.router().disconnect(peer_ip);
+            self.send_disconnect(peer_ip, disconnect_reason(&error));
         }
         Ok(())
     }
@@ -202,4 +200,8 @@ impl<N: Network> Inbound<N> for TestRouter<N> {
     ) -> bool {
         true
     }
+
+    async fn is_valid_stem_transaction(&self, _transaction: Transaction<N>) -> bool {
+        true
+    }
 }
@@ -185,3 +185,80 @@ async fn test_connect_simultaneously_with_handshake() {
         assert_eq!(node1.number_of_connected_peers(), 1);
     }
 }
+
+#[tokio::test]
+async fn test_duplicate_connections() {
+    use snarkos_node_router::{
+        messages::{ChallengeRequest, NodeType},
+        Peer,
+    };
+
+    let node = client(0, 10).await;
+
+    // Insert three connections of the same node identity, and one of another identity.
+    let (node_id, other_node_id) = (sample_node_id(), sample_node_id());
+    let peer_ips = (1..=4).map(|i| format!("1.2.3.{i}:4130").parse().unwrap()).collect::<Vec<_>>();
+    for (i, peer_ip) in peer_ips.iter().enumerate() {
+        let node_id = if i == 3 { other_node_id.clone() } else { node_id.clone() };
+        let request = ChallengeRequest::new(4130, NodeType::Client, sample_account().address(), node_id, None, 0);
+        node.insert_connected_peer(Peer::new(*peer_ip, &request, 0), *peer_ip);
+        // Ensure the connections are ordered by the time they were first seen.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Ensure the older connections of the identity are duplicates of the newest one.
+    assert_eq!(node.duplicate_connections(&peer_ips[2]), vec![peer_ips[0], peer_ips[1]]);
+    // Ensure the other identity has no duplicates.
+    assert!(node.duplicate_connections(&peer_ips[3]).is_empty());
+
+    // Ensure a higher limit keeps the newest connections of the identity.
+    node.set_max_connections_per_identity(2).unwrap();
+    assert_eq!(node.duplicate_connections(&peer_ips[2]), vec![peer_ips[0]]);
+    assert!(node.set_max_connections_per_identity(0).is_err());
+}
+
+#[tokio::test]
+async fn test_hidden_mode_with_consensus_binding() {
+    use snarkos_account::Account;
+    use snarkos_node_router::HiddenMode;
+    use snarkvm::prelude::Testnet3 as CurrentNetwork;
+
+    // Create a hidden validator, which only admits the given committee member.
+    let consensus_account = Account::<CurrentNetwork>::new(&mut rand::thread_rng()).unwrap();
+    let member = consensus_account.address();
+    let node0 = validator(0, 2).await;
+    node0.enable_hidden_mode(HiddenMode::new([], move |address| *address == member)).unwrap();
+    node0.enable_time_sync(move |address| *address == member).unwrap();
+    // Create a validator whose peer-to-peer identity is separate from its consensus account, and bind them.
+    let node1 = validator(0, 2).await;
+    node1.bind_consensus_account(&consensus_account).unwrap();
+    assert_eq!(node1.consensus_address(), member);
+    // Create a validator that does not bind its consensus account.
+    let node2 = validator(0, 2).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+    node2.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+    node2.tcp().enable_listener().await.unwrap();
+
+    // Ensure the hidden validator admits the committee member by its bound consensus address.
+    node1.connect(node0.local_ip());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    let peer = node0.get_connected_peer(&node1.local_ip()).unwrap();
+    assert_eq!(peer.consensus_address(), member);
+    assert_eq!(peer.address(), node1.address());
+    // Ensure the clock of the committee member is sampled.
+    assert!(node0.is_time_source(&node1.local_ip()));
+
+    // Ensure the hidden validator refuses the validator without a binding.
+    node2.connect(node0.local_ip());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node2.number_of_connected_peers(), 0);
+}
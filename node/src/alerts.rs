@@ -0,0 +1,311 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, time::Duration};
+use tokio::task::JoinHandle;
+
+/// The default number of seconds in between evaluations of the alert rules.
+const DEFAULT_ALERT_INTERVAL_IN_SECS: u64 = 30;
+
+/// The alerting configuration of a node, loaded from a JSON file.
+///
+/// For example:
+/// ```json
+/// {
+///   "interval_secs": 30,
+///   "webhooks": ["https://example.com/alerts"],
+///   "rules": [
+///     { "rule": "no_new_block", "minutes": 10 },
+///     { "rule": "stake_below_quorum" },
+///     { "rule": "proposals_halted" },
+///     { "rule": "disk_nearly_full", "min_free_percent": 10 },
+///     { "rule": "no_peers" },
+///     { "rule": "block_hash_conflict" }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AlertConfig {
+    /// The number of seconds in between evaluations of the rules.
+    #[serde(default = "AlertConfig::default_interval_secs")]
+    pub interval_secs: u64,
+    /// The URLs to post alerts to, in addition to logging them.
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+    /// The rules to evaluate.
+    pub rules: Vec<AlertRule>,
+}
+
+impl AlertConfig {
+    /// Loads the alerting configuration from the given path.
+    pub fn load(path: &Path) -> Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the alerting configuration at '{}'", path.display()))?;
+        serde_json::from_str(&config)
+            .with_context(|| format!("Failed to parse the alerting configuration at '{}'", path.display()))
+    }
+
+    /// Returns the default number of seconds in between evaluations of the rules.
+    const fn default_interval_secs() -> u64 {
+        DEFAULT_ALERT_INTERVAL_IN_SECS
+    }
+}
+
+/// A rule that fires an alert when the state of the node is unhealthy.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum AlertRule {
+    /// Fires if the latest block is older than the given number of minutes.
+    NoNewBlock { minutes: u64 },
+    /// Fires if the stake of the connected committee members is below the quorum threshold.
+    StakeBelowQuorum,
+    /// Fires if the primary halted its batch proposals, as the connected stake stayed below the quorum threshold.
+    ProposalsHalted,
+    /// Fires if the free space on the disk of the ledger is below the given percentage.
+    DiskNearlyFull { min_free_percent: u8 },
+    /// Fires if the node has no connected peers.
+    NoPeers,
+    /// Fires if a connected committee member reports a block hash that conflicts with the ledger.
+    BlockHashConflict,
+}
+
+impl AlertRule {
+    /// Returns the name of the rule.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::NoNewBlock { .. } => "no_new_block",
+            Self::StakeBelowQuorum => "stake_below_quorum",
+            Self::ProposalsHalted => "proposals_halted",
+            Self::DiskNearlyFull { .. } => "disk_nearly_full",
+            Self::NoPeers => "no_peers",
+            Self::BlockHashConflict => "block_hash_conflict",
+        }
+    }
+
+    /// Returns the alert message, if the rule fires for the given sample.
+    /// Note: A rule does not fire if the sample does not contain the state it is evaluated over.
+    pub fn evaluate(&self, sample: &AlertSample) -> Option<String> {
+        match self {
+            Self::NoNewBlock { minutes } => sample
+                .seconds_since_latest_block
+                .filter(|seconds| *seconds >= minutes.saturating_mul(60))
+                .map(|seconds| format!("No new block in {} minutes", seconds / 60)),
+            Self::StakeBelowQuorum => sample
+                .connected_stake
+                .filter(|(stake, quorum_threshold)| stake < quorum_threshold)
+                .map(|(stake, quorum_threshold)| {
+                    format!("Connected stake {stake} is below the quorum threshold {quorum_threshold}")
+                }),
+            Self::ProposalsHalted => sample.proposals_halted.then(|| {
+                "Halted the batch proposals, as the connected stake stayed below the quorum threshold".to_string()
+            }),
+            Self::DiskNearlyFull { min_free_percent } => sample
+                .disk_free_percent
+                .filter(|free_percent| *free_percent < *min_free_percent as f64)
+                .map(|free_percent| format!("Only {free_percent:.1}% of the ledger disk is free")),
+            Self::NoPeers => (sample.connected_peers == 0).then(|| "No connected peers".to_string()),
+            Self::BlockHashConflict => match sample.block_hash_conflicts.is_empty() {
+                true => None,
+                false => Some(sample.block_hash_conflicts.join("; ")),
+            },
+        }
+    }
+}
+
+/// A sample of the state of a node, over which the alert rules are evaluated.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AlertSample {
+    /// The number of seconds since the timestamp of the latest block, if the node has a ledger.
+    pub seconds_since_latest_block: Option<u64>,
+    /// The number of connected peers.
+    pub connected_peers: usize,
+    /// The stake of the connected committee members (including this node) and the quorum threshold.
+    pub connected_stake: Option<(u64, u64)>,
+    /// Whether the primary halted its batch proposals, as the connected stake stayed below the quorum threshold.
+    pub proposals_halted: bool,
+    /// The percentage of free space on the disk of the ledger, if it is known.
+    pub disk_free_percent: Option<f64>,
+    /// The block hash conflicts reported by the connected committee members.
+    pub block_hash_conflicts: Vec<String>,
+}
+
+/// An alert, which is sent when a rule starts or stops firing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Alert {
+    /// The name of the rule.
+    pub rule: &'static str,
+    /// Whether the rule started firing (`true`) or was resolved (`false`).
+    pub firing: bool,
+    /// The alert message.
+    pub message: String,
+}
+
+/// Evaluates the alert rules, and tracks which of them are firing.
+pub struct Alerter {
+    /// The alerting configuration.
+    config: AlertConfig,
+    /// Whether each rule is currently firing.
+    firing: Vec<bool>,
+}
+
+impl Alerter {
+    /// Initializes a new alerter.
+    pub fn new(config: AlertConfig) -> Self {
+        let firing = vec![false; config.rules.len()];
+        Self { config, firing }
+    }
+
+    /// Evaluates the rules for the given sample, and returns the alerts for the rules that started or stopped firing.
+    pub fn evaluate(&mut self, sample: &AlertSample) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for (rule, firing) in self.config.rules.iter().zip(self.firing.iter_mut()) {
+            match (rule.evaluate(sample), *firing) {
+                // The rule started firing.
+                (Some(message), false) => alerts.push(Alert { rule: rule.name(), firing: true, message }),
+                // The rule was resolved.
+                (None, true) => alerts.push(Alert {
+                    rule: rule.name(),
+                    firing: false,
+                    message: format!("The '{}' alert was resolved", rule.name()),
+                }),
+                // The rule did not change.
+                _ => continue,
+            }
+            *firing = !*firing;
+        }
+        alerts
+    }
+
+    /// Logs the given alert, and posts it to the configured webhooks.
+    async fn notify(&self, client: &reqwest::Client, alert: &Alert) {
+        match alert.firing {
+            true => warn!("Alert '{}' - {}", alert.rule, alert.message),
+            false => info!("{}", alert.message),
+        }
+        let Ok(body) = serde_json::to_string(alert) else {
+            return;
+        };
+        for webhook in &self.config.webhooks {
+            let request = client.post(webhook).header("Content-Type", "application/json").body(body.clone());
+            if let Err(error) = request.send().await.and_then(|response| response.error_for_status()) {
+                warn!("Failed to post the '{}' alert to '{webhook}' - {error}", alert.rule);
+            }
+        }
+    }
+}
+
+/// Starts a task that periodically evaluates the given alert rules over the samples of the node state.
+pub fn start_alerts<F: Fn() -> AlertSample + Send + 'static>(config: AlertConfig, sample: F) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(config.interval_secs.max(1));
+        let client = reqwest::Client::new();
+        let mut alerter = Alerter::new(config);
+        loop {
+            tokio::time::sleep(interval).await;
+            for alert in alerter.evaluate(&sample()) {
+                alerter.notify(&client, &alert).await;
+            }
+        }
+    })
+}
+
+/// Returns the percentage of free space on the disk of the given path, if it can be determined.
+pub fn disk_free_percent(path: &Path) -> Option<f64> {
+    #[cfg(target_family = "unix")]
+    {
+        let stat = nix::sys::statvfs::statvfs(path).ok()?;
+        match stat.blocks() {
+            0 => None,
+            blocks => Some(stat.blocks_available() as f64 / blocks as f64 * 100.0),
+        }
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alert_config() {
+        let config = r#"{
+            "webhooks": ["http://localhost:8080"],
+            "rules": [{ "rule": "no_new_block", "minutes": 10 }, { "rule": "no_peers" }]
+        }"#;
+        let config: AlertConfig = serde_json::from_str(config).unwrap();
+        assert_eq!(config.interval_secs, DEFAULT_ALERT_INTERVAL_IN_SECS);
+        assert_eq!(config.webhooks, vec!["http://localhost:8080".to_string()]);
+        assert_eq!(config.rules, vec![AlertRule::NoNewBlock { minutes: 10 }, AlertRule::NoPeers]);
+    }
+
+    #[test]
+    fn test_alert_rules() {
+        let healthy = AlertSample {
+            seconds_since_latest_block: Some(60),
+            connected_peers: 5,
+            connected_stake: Some((100, 67)),
+            proposals_halted: false,
+            disk_free_percent: Some(50.0),
+            block_hash_conflicts: vec![],
+        };
+        let unhealthy = AlertSample {
+            seconds_since_latest_block: Some(900),
+            connected_peers: 0,
+            connected_stake: Some((50, 67)),
+            proposals_halted: true,
+            disk_free_percent: Some(5.0),
+            block_hash_conflicts: vec!["Validator 'aleo1..' reports a conflicting block hash".to_string()],
+        };
+        let rules = [
+            AlertRule::NoNewBlock { minutes: 10 },
+            AlertRule::StakeBelowQuorum,
+            AlertRule::ProposalsHalted,
+            AlertRule::DiskNearlyFull { min_free_percent: 10 },
+            AlertRule::NoPeers,
+            AlertRule::BlockHashConflict,
+        ];
+        for rule in &rules {
+            assert!(rule.evaluate(&healthy).is_none());
+            assert!(rule.evaluate(&unhealthy).is_some());
+            // Ensure the rules do not fire without the state they are evaluated over.
+            if *rule != AlertRule::NoPeers {
+                assert!(rule.evaluate(&AlertSample { connected_peers: 1, ..Default::default() }).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_alerter() {
+        let config = AlertConfig { interval_secs: 1, webhooks: vec![], rules: vec![AlertRule::NoPeers] };
+        let mut alerter = Alerter::new(config);
+        let (connected, disconnected) =
+            (AlertSample { connected_peers: 1, ..Default::default() }, AlertSample::default());
+
+        // Ensure an alert is only sent when the rule starts or stops firing.
+        assert!(alerter.evaluate(&connected).is_empty());
+        let alerts = alerter.evaluate(&disconnected);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].firing);
+        assert!(alerter.evaluate(&disconnected).is_empty());
+        let alerts = alerter.evaluate(&connected);
+        assert_eq!(alerts.len(), 1);
+        assert!(!alerts[0].firing);
+    }
+}
@@ -14,19 +14,33 @@
 
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{
+    traits::NodeInterface,
+    AlertConfig,
+    AlertSample,
+    IndexAuditConfig,
+    ShutdownCoordinator,
+    WebhookConfig,
+    WebhookTasks,
+    LEDGER_SHUTDOWN_TIMEOUT,
+    SHUTDOWN_STAGE_TIMEOUT,
+    WEBHOOK_SHUTDOWN_TIMEOUT,
+};
 use snarkos_account::Account;
-use snarkos_node_bft::ledger_service::CoreLedgerService;
-use snarkos_node_rest::Rest;
+use snarkos_node_bft::ledger_service::{CommitteeSource, CoreLedgerService, LedgerService};
+use snarkos_node_bus::EventBus;
+use snarkos_node_cdn::{CdnConfig, CdnSyncProgress};
+use snarkos_node_clock as clock;
+use snarkos_node_rest::{CdnSyncServer, Rest};
 use snarkos_node_router::{
-    messages::{Message, NodeType, UnconfirmedSolution},
+    messages::{CheckpointRequest, Message, NodeType, UnconfirmedSolution},
     Heartbeat,
     Inbound,
     Outbound,
     Router,
     Routing,
 };
-use snarkos_node_sync::{BlockSync, BlockSyncMode};
+use snarkos_node_sync::{BlockSync, BlockSyncMode, ChainPolicy};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
     P2P,
@@ -47,15 +61,22 @@ use core::future::Future;
 use parking_lot::Mutex;
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{atomic::AtomicBool, Arc},
 };
+use time::OffsetDateTime;
 use tokio::task::JoinHandle;
 
+/// The interval at which a fast-syncing client requests the latest checkpoint certificate from its trusted peers.
+const CHECKPOINT_REQUEST_INTERVAL_IN_SECS: u64 = 30; // 30 seconds
+
 /// A client node is a full node, capable of querying with the network.
 #[derive(Clone)]
 pub struct Client<N: Network, C: ConsensusStorage<N>> {
     /// The ledger of the node.
     ledger: Ledger<N, C>,
+    /// The ledger service of the node.
+    ledger_service: Arc<CoreLedgerService<N, C>>,
     /// The router of the node.
     router: Router<N>,
     /// The REST server of the node.
@@ -66,8 +87,14 @@ pub struct Client<N: Network, C: ConsensusStorage<N>> {
     genesis: Block<N>,
     /// The coinbase puzzle.
     coinbase_puzzle: CoinbasePuzzle<N>,
+    /// The event bus between the subsystems of the node.
+    event_bus: EventBus<N>,
+    /// The boolean flag for the read-only mode, in which the node does not relay or accept new transmissions.
+    is_read_only: Arc<AtomicBool>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// The tasks that post the events of the node to the webhooks, which are stopped after the other tasks.
+    webhooks: Arc<Mutex<Option<WebhookTasks>>>,
     /// The shutdown signal.
     shutdown: Arc<AtomicBool>,
 }
@@ -81,8 +108,9 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
-        cdn: Option<String>,
+        cdn: Option<CdnConfig>,
         storage_mode: StorageMode,
+        bft_activation_height: Option<u32>,
     ) -> Result<Self> {
         // Prepare the shutdown flag.
         let shutdown: Arc<AtomicBool> = Default::default();
@@ -95,53 +123,86 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         // TODO: Remove me after Phase 3.
         let ledger = crate::phase_3_reset(ledger, storage_mode.clone())?;
         // Initialize the CDN.
-        if let Some(base_url) = cdn {
-            // Sync the ledger with the CDN.
+        let mut cdn_sync = None;
+        if let Some(cdn) = cdn {
+            let cdn_progress = CdnSyncProgress::default();
+            // Report the progress of the sync in the node status while it runs, if the REST server is enabled.
+            if let Some(rest_ip) = rest_ip {
+                let (node_type, height) = (NodeType::Client, ledger.latest_height());
+                cdn_sync = Some(CdnSyncServer::start(rest_ip, rest_rps, node_type, height, cdn_progress.clone())?);
+            }
+            // Sync the ledger with the CDNs.
             if let Err((_, error)) =
-                snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone(), shutdown.clone()).await
+                snarkos_node_cdn::sync_ledger_with_cdn(&cdn, ledger.clone(), cdn_progress, shutdown.clone())
+                    .await
             {
                 crate::log_clean_error(&storage_mode);
                 return Err(error);
             }
         }
 
+        // Initialize the event bus.
+        let event_bus = EventBus::default();
         // Initialize the ledger service.
         let ledger_service = Arc::new(CoreLedgerService::<N, C>::new(ledger.clone(), shutdown.clone()));
+        ledger_service.set_event_bus(event_bus.clone());
+        // Set the height of the last beacon-produced block, after which the BFT produces the blocks, if specified.
+        if let Some(height) = bft_activation_height {
+            ledger_service.set_bft_activation_height(height);
+        }
         // Initialize the sync module.
         let sync = BlockSync::new(BlockSyncMode::Router, ledger_service.clone());
 
+        // Load the persistent node ID.
+        let node_id = crate::load_node_id(&account, &storage_mode)?;
         // Initialize the node router.
         let router = Router::new(
             node_ip,
             NodeType::Client,
             account,
+            node_id,
             trusted_peers,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
+        router.set_event_bus(event_bus.clone());
+        // Record the endpoints of the committee members in the validator directory.
+        let directory_ledger = ledger.clone();
+        router.enable_validator_directory(move |address| {
+            directory_ledger.latest_committee().map_or(false, |committee| committee.is_committee_member(*address))
+        })?;
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
         // Initialize the node.
         let mut node = Self {
             ledger: ledger.clone(),
+            ledger_service,
             router,
             rest: None,
             sync: Arc::new(sync),
             genesis,
             coinbase_puzzle,
+            event_bus,
+            is_read_only: Default::default(),
             handles: Default::default(),
+            webhooks: Default::default(),
             shutdown,
         };
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, rest_rps, None, ledger.clone(), Arc::new(node.clone())).await?);
+            let (routing, event_bus) = (Arc::new(node.clone()), node.event_bus.clone());
+            let rest = Rest::start(rest_ip, rest_rps, None, ledger.clone(), routing, event_bus, cdn_sync).await?;
+            node.rest = Some(rest);
         }
         // Initialize the routing.
         node.initialize_routing().await;
         // Initialize the sync module.
         node.initialize_sync();
+        // Cache the blocks as they are added to the ledger.
+        let block_cache_loop = crate::start_block_cache_loop(node.router.clone(), ledger.clone(), &node.event_bus);
+        node.handles.lock().push(block_cache_loop);
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
         // Pass the node to the signal handler.
@@ -155,10 +216,84 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         &self.ledger
     }
 
+    /// Returns the event bus.
+    pub const fn event_bus(&self) -> &EventBus<N> {
+        &self.event_bus
+    }
+
     /// Returns the REST server.
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Returns `true` if the node is a read-only replica.
+    pub fn is_read_only(&self) -> bool {
+        self.is_read_only.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Runs the node as a read-only replica, which syncs and serves the ledger, but does not
+    /// relay unconfirmed solutions and transactions, nor accept new ones over REST.
+    pub fn set_read_only(&self) {
+        self.is_read_only.store(true, std::sync::atomic::Ordering::Relaxed);
+        if let Some(rest) = &self.rest {
+            rest.set_read_only(true);
+        }
+        info!("Running as a read-only replica");
+    }
+
+    /// Enables fast sync, in which the blocks covered by a checkpoint certificate from the trusted peers are applied
+    /// after verifying only their hashes and signatures. The blocks after the checkpoint are still fully verified.
+    pub fn enable_fast_sync(&self) {
+        if self.router.trusted_peers().is_empty() {
+            warn!("Fast sync requires trusted validators to attest to the checkpoints (see '--peers')");
+        }
+        self.sync.enable_fast_sync();
+        // Periodically request the latest checkpoint certificate from the connected trusted peers.
+        let node = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                for peer_ip in node.router.trusted_peers() {
+                    if node.router.is_connected(&peer_ip) {
+                        Outbound::send(&node, peer_ip, Message::CheckpointRequest(CheckpointRequest { height: None }));
+                    }
+                }
+                clock::sleep(std::time::Duration::from_secs(CHECKPOINT_REQUEST_INTERVAL_IN_SECS)).await;
+            }
+        }));
+        info!("Fast sync is enabled");
+    }
+
+    /// Sets the chain policy, with which the next block is accepted, and the chains of the peers are compared.
+    pub fn set_chain_policy(&self, policy: Arc<dyn ChainPolicy<N>>) {
+        self.sync.set_chain_policy(policy);
+    }
+
+    /// Starts periodically auditing the derived indexes of a sample of the blocks in the ledger.
+    pub fn start_index_audit(&self, config: IndexAuditConfig) {
+        self.handles.lock().push(crate::start_index_audit(self.ledger_service.clone(), config));
+    }
+
+    /// Starts posting the events of the node to the given webhooks, i.e. the new blocks.
+    pub fn start_webhooks(&self, config: WebhookConfig) {
+        let (_, webhooks) = WebhookTasks::start(config, &self.event_bus);
+        *self.webhooks.lock() = Some(webhooks);
+    }
+
+    /// Starts evaluating the given alert rules over the state of the node.
+    pub fn start_alerts(&self, config: AlertConfig, ledger_dir: PathBuf) {
+        let node = self.clone();
+        self.handles.lock().push(crate::start_alerts(config, move || {
+            let now = OffsetDateTime::now_utc().unix_timestamp();
+            AlertSample {
+                seconds_since_latest_block: Some(now.saturating_sub(node.ledger.latest_timestamp()).max(0) as u64),
+                connected_peers: node.router.number_of_connected_peers(),
+                connected_stake: None,
+                proposals_halted: false,
+                disk_free_percent: crate::disk_free_percent(&ledger_dir),
+                block_hash_conflicts: vec![],
+            }
+        }));
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
@@ -175,7 +310,7 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
                 }
 
                 // Sleep briefly to avoid triggering spam detection.
-                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                clock::sleep(std::time::Duration::from_secs(5)).await;
                 // Perform the sync routine.
                 node.sync.try_block_sync(&node).await;
             }
@@ -198,13 +333,38 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Client<N, C> {
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
 
-        // Abort the tasks.
-        trace!("Shutting down the validator...");
-        self.handles.lock().iter().for_each(|handle| handle.abort());
-
-        // Shut down the router.
-        self.router.shut_down().await;
+        // Shut down the subsystems in dependency order.
+        let ledger_service = self.ledger_service.clone();
+        let report = ShutdownCoordinator::default()
+            // Notify the connected peers, abort the tasks, and stop accepting connections.
+            .stage("router", SHUTDOWN_STAGE_TIMEOUT, async {
+                self.send_shutdown();
+                self.handles.lock().iter().for_each(|handle| handle.abort());
+                self.router.shut_down().await;
+            })
+            // Deliver the pending webhook events.
+            .stage("webhooks", SHUTDOWN_STAGE_TIMEOUT, async {
+                let webhooks = self.webhooks.lock().take();
+                if let Some(webhooks) = webhooks {
+                    webhooks.shut_down(WEBHOOK_SHUTDOWN_TIMEOUT).await;
+                }
+            })
+            // Wait for the pending block to be written to the ledger.
+            .stage("ledger", LEDGER_SHUTDOWN_TIMEOUT, async move {
+                let _ = tokio::task::spawn_blocking(move || ledger_service.wait_for_pending_writes()).await;
+            })
+            // Stop the REST server.
+            .stage("REST server", SHUTDOWN_STAGE_TIMEOUT, async {
+                if let Some(rest) = &self.rest {
+                    rest.shut_down();
+                }
+            })
+            .run()
+            .await;
 
-        info!("Node has shut down.");
+        match report.is_clean() {
+            true => info!("Node has shut down - {report}"),
+            false => warn!("Node has shut down, but some stages timed out - {report}"),
+        }
     }
 }
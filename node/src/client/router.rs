@@ -17,7 +17,10 @@ use snarkos_node_router::{
     messages::{
         BlockRequest,
         BlockResponse,
-        DataBlocks,
+        CheckpointResponse,
+        CommitteeProof,
+        CommitteeRequest,
+        CommitteeResponse,
         DisconnectReason,
         MessageCodec,
         Ping,
@@ -25,9 +28,10 @@ use snarkos_node_router::{
         PuzzleResponse,
         UnconfirmedTransaction,
     },
+    disconnect_reason,
     Routing,
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{Connection, ConnectionSide, Tcp, TokenBucket};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, Network},
@@ -52,7 +56,8 @@ impl<N: Network, C: ConsensusStorage<N>> Handshake for Client<N, C> {
         let conn_side = connection.side();
         let stream = self.borrow_stream(&mut connection);
         let genesis_header = *self.genesis.header();
-        self.router.handshake(peer_addr, stream, conn_side, genesis_header).await?;
+        let latest_height = self.ledger.latest_height();
+        self.router.handshake(peer_addr, stream, conn_side, genesis_header, latest_height).await?;
 
         Ok(connection)
     }
@@ -66,6 +71,8 @@ where
     async fn on_connect(&self, peer_addr: SocketAddr) {
         // Resolve the peer address to the listener address.
         let Some(peer_ip) = self.router.resolve_to_listener(&peer_addr) else { return };
+        // Close the older connections of the same node identity, if it holds too many.
+        self.disconnect_duplicate_connections(peer_ip);
         // Retrieve the block locators.
         let block_locators = match self.sync.get_block_locators() {
             Ok(block_locators) => Some(block_locators),
@@ -100,6 +107,11 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Client<N, C> {
     fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the token bucket that caps the bandwidth to the given peer address, if any.
+    fn bandwidth_limiter(&self, addr: SocketAddr) -> Option<Arc<TokenBucket>> {
+        self.router.bandwidth_limiter(addr)
+    }
 }
 
 #[async_trait]
@@ -109,8 +121,10 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Client<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        let router = self.router().clone();
+        MessageCodec::default()
+            .with_malformed_frame_handler(move |reason| router.record_malformed_frame(peer_addr, reason))
     }
 
     /// Processes a message received from the network.
@@ -119,9 +133,7 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Client<N, C> {
         if let Err(error) = self.inbound(peer_addr, message).await {
             if let Some(peer_ip) = self.router().resolve_to_listener(&peer_addr) {
                 warn!("Disconnecting from '{peer_ip}' - {error}");
-                Outbound::send(self, peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
-                // Disconnect from this peer.
-                self.router().disconnect(peer_ip);
+                self.send_disconnect(peer_ip, disconnect_reason(&error));
             }
         }
         Ok(())
@@ -167,13 +179,19 @@ impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Client<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
-    /// Handles a `BlockRequest` message.
+    /// Handles a `BlockRequest` message, by serving the requested blocks from the local ledger.
+    /// Note: Clients always maintain a synced local ledger, so they serve block requests from other
+    /// clients and provers, which offloads sync traffic from the validators.
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
-        // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
-            Ok(blocks) => Data::Object(DataBlocks(blocks)),
+        // Retrieve the serialized blocks within the requested range, from the block cache or the ledger.
+        let blocks = self
+            .router()
+            .block_cache()
+            .get_or_load_data_blocks(*start_height..*end_height, |height| self.ledger.get_block(height));
+        let blocks = match blocks {
+            Ok(bytes) => Data::Buffer(bytes),
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
                 return false;
@@ -196,12 +214,65 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         }
     }
 
+    /// Proves the committee that certified the requested block, and returns the committee response to the peer.
+    fn committee_request(&self, peer_ip: SocketAddr, message: CommitteeRequest) -> bool {
+        let CommitteeRequest { height } = message;
+
+        // Prove the committee for the requested block, which is the committee lookback of its leader round.
+        let ledger = &self.ledger_service;
+        let proof = ledger.get_block(height).and_then(|block| {
+            CommitteeProof::new(&block, ledger.get_committee_lookback_for_round(block.round())?)
+        });
+        let proof = match proof {
+            Ok(proof) => Data::Object(proof),
+            Err(error) => {
+                debug!("Failed to prove the committee for block {height} - {error}");
+                return false;
+            }
+        };
+        // Send the `CommitteeResponse` message to the peer.
+        Outbound::send(self, peer_ip, Message::CommitteeResponse(CommitteeResponse { height, proof }));
+        true
+    }
+
+    /// Stores the checkpoint certificate from a trusted peer, if it is signed by a quorum of the current committee.
+    fn checkpoint_response(&self, peer_ip: SocketAddr, message: CheckpointResponse<N>) -> bool {
+        // Ignore the checkpoint certificate, unless it was requested from the peer.
+        // Note: A response may still arrive after the fast sync is over, so it is not treated as a violation.
+        if !self.sync.is_fast_sync() || !self.router.trusted_peers().contains(&peer_ip) {
+            debug!("Ignoring an unrequested checkpoint response from '{peer_ip}'");
+            return true;
+        }
+        let Some(certificate) = message.certificate else {
+            return true;
+        };
+        let height = certificate.checkpoint.height;
+        if self.sync.checkpoints().contains(height) {
+            return true;
+        }
+        // Verify the checkpoint certificate against the current committee, and store it.
+        // Note: If the committee has since changed, the blocks are fully verified instead.
+        let result = self
+            .ledger
+            .latest_committee()
+            .and_then(|committee| self.sync.checkpoints().verify_and_insert(certificate, &committee));
+        match result {
+            Ok(()) => info!("Fast syncing up to the checkpoint at block {height}, attested by '{peer_ip}'"),
+            Err(error) => debug!("Failed to verify the checkpoint at block {height} from '{peer_ip}' - {error}"),
+        }
+        true
+    }
+
     /// Processes the block locators and sends back a `Pong` message.
     fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
         // Check if the sync module is in router mode.
         if self.sync.mode().is_router() {
+            // If the peer is far behind this node, it cannot serve any blocks, so remove it from the sync pool.
+            if self.router().is_syncing_peer(&peer_ip) {
+                self.sync.remove_peer(&peer_ip);
+            }
             // If block locators were provided, then update the peer in the sync pool.
-            if let Some(block_locators) = message.block_locators {
+            else if let Some(block_locators) = message.block_locators {
                 // Check the block locators are valid, and update the peer in the sync pool.
                 if let Err(error) = self.sync.update_peer_locators(peer_ip, block_locators) {
                     warn!("Peer '{peer_ip}' sent invalid block locators: {error}");
@@ -211,7 +282,7 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         }
 
         // Send a `Pong` message to the peer.
-        Outbound::send(self, peer_ip, Message::Pong(Pong { is_fork: Some(false) }));
+        Outbound::send(self, peer_ip, Message::Pong(Pong::new(Some(false), message.timestamp)));
         true
     }
 
@@ -265,6 +336,10 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         serialized: UnconfirmedSolution<N>,
         solution: ProverSolution<N>,
     ) -> bool {
+        // A read-only replica does not relay solutions.
+        if self.is_read_only() {
+            return true; // Maintain the connection.
+        }
         // Retrieve the latest epoch challenge.
         if let Ok(epoch_challenge) = self.ledger.latest_epoch_challenge() {
             // Retrieve the latest proof target.
@@ -299,6 +374,10 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         serialized: UnconfirmedTransaction<N>,
         transaction: Transaction<N>,
     ) -> bool {
+        // A read-only replica does not relay transactions.
+        if self.is_read_only() {
+            return true; // Maintain the connection.
+        }
         // Check that the transaction is not a fee transaction.
         if transaction.is_fee() {
             return true; // Maintain the connection.
@@ -310,4 +389,18 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         }
         true
     }
+
+    /// Returns `true` if the given stem transaction is well-formed and unique.
+    async fn is_valid_stem_transaction(&self, transaction: Transaction<N>) -> bool {
+        // A read-only replica does not relay transactions, and fee transactions are never relayed.
+        if self.is_read_only() || transaction.is_fee() {
+            return false;
+        }
+        let ledger = self.ledger.clone();
+        let is_valid = tokio::task::spawn_blocking(move || {
+            ledger.check_transaction_basic(&transaction, None, &mut rand::thread_rng())
+        })
+        .await;
+        matches!(is_valid, Ok(Ok(())))
+    }
 }
@@ -0,0 +1,301 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod router;
+
+use crate::traits::NodeInterface;
+use snarkos_account::Account;
+use snarkos_node_router::{
+    messages::{Message, NodeId, NodeInfo, NodeType, PeerRequest},
+    Heartbeat,
+    Outbound,
+    Router,
+    Routing,
+};
+use snarkos_node_tcp::P2P;
+use snarkvm::prelude::{block::Block, Address, Network};
+
+use anyhow::Result;
+use indexmap::{IndexMap, IndexSet};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    net::SocketAddr,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::task::JoinHandle;
+
+/// The crawl state of a node in the network.
+#[derive(Clone, Debug)]
+pub struct CrawledPeer<N: Network> {
+    /// The node type of the peer, if a handshake has succeeded.
+    pub node_type: Option<NodeType>,
+    /// The message version of the peer, if a handshake has succeeded.
+    pub version: Option<u32>,
+    /// The software version and features of the peer, if it has sent a ping.
+    pub node_info: Option<NodeInfo>,
+    /// The Aleo address of the peer, if a handshake has succeeded.
+    pub address: Option<Address<N>>,
+    /// The peers reported by the peer in its latest `PeerResponse`.
+    pub peers: IndexSet<SocketAddr>,
+    /// The number of connection attempts made to the peer.
+    pub num_attempts: u8,
+    /// The UNIX timestamp at which the peer last responded with its peers.
+    pub last_crawled: Option<i64>,
+}
+
+impl<N: Network> Default for CrawledPeer<N> {
+    fn default() -> Self {
+        Self {
+            node_type: None,
+            version: None,
+            node_info: None,
+            address: None,
+            peers: Default::default(),
+            num_attempts: 0,
+            last_crawled: None,
+        }
+    }
+}
+
+impl<N: Network> CrawledPeer<N> {
+    /// Returns `true` if the peer has responded with its peers.
+    pub const fn is_crawled(&self) -> bool {
+        self.last_crawled.is_some()
+    }
+}
+
+/// A crawler is a light node, capable of walking the network graph to map its topology.
+/// It does not participate in consensus or sync.
+#[derive(Clone)]
+pub struct Crawler<N: Network> {
+    /// The router of the node.
+    router: Router<N>,
+    /// The genesis block.
+    genesis: Block<N>,
+    /// The crawl state of every node that has been observed.
+    peers: Arc<RwLock<IndexMap<SocketAddr, CrawledPeer<N>>>>,
+    /// The spawned handles.
+    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// The shutdown signal.
+    shutdown: Arc<AtomicBool>,
+}
+
+impl<N: Network> Crawler<N> {
+    /// The maximum number of connection attempts made to a peer.
+    const MAXIMUM_CONNECTION_ATTEMPTS: u8 = 3;
+
+    /// Initializes a new crawler node.
+    pub async fn new(node_ip: SocketAddr, seed_peers: &[SocketAddr], genesis: Block<N>, is_dev: bool) -> Result<Self> {
+        // Prepare the shutdown flag.
+        let shutdown: Arc<AtomicBool> = Default::default();
+
+        // Initialize the signal handler.
+        let signal_node = Self::handle_signals(shutdown.clone());
+
+        // Sample an ephemeral account and node ID, as the crawler does not need a persistent identity.
+        let account = Account::new(&mut rand::thread_rng())?;
+        let node_id = NodeId::new(account.private_key(), &mut rand::thread_rng())?;
+        // Initialize the node router.
+        // Note: The crawler identifies as a prover, as provers are not expected to provide block locators.
+        let max_peers = Self::MAXIMUM_NUMBER_OF_PEERS as u16;
+        let router = Router::new(node_ip, NodeType::Prover, account, node_id, &[], max_peers, is_dev).await?;
+
+        // Initialize the node.
+        let node = Self {
+            router,
+            genesis,
+            peers: Default::default(),
+            handles: Default::default(),
+            shutdown,
+        };
+        // Seed the candidate peers, falling back to the bootstrap peers.
+        match seed_peers.is_empty() {
+            true => node.router.insert_candidate_peers(&node.router.bootstrap_peers()),
+            false => node.router.insert_candidate_peers(seed_peers),
+        }
+        // Initialize the routing.
+        node.initialize_routing().await;
+        // Pass the node to the signal handler.
+        let _ = signal_node.set(node.clone());
+        // Return the node.
+        Ok(node)
+    }
+
+    /// Returns the number of observed nodes.
+    pub fn num_observed(&self) -> usize {
+        self.peers.read().len()
+    }
+
+    /// Returns the number of nodes that have responded with their peers.
+    pub fn num_crawled(&self) -> usize {
+        self.peers.read().values().filter(|peer| peer.is_crawled()).count()
+    }
+
+    /// Returns the crawl state of every observed node.
+    pub fn peers(&self) -> IndexMap<SocketAddr, CrawledPeer<N>> {
+        self.peers.read().clone()
+    }
+
+    /// Returns the network graph as a JSON object, with a list of nodes and a list of edges.
+    pub fn to_json(&self) -> serde_json::Value {
+        let peers = self.peers.read();
+        // Prepare the nodes.
+        let nodes = peers
+            .iter()
+            .map(|(ip, peer)| {
+                serde_json::json!({
+                    "ip": ip,
+                    "node_type": peer.node_type.map(|node_type| node_type.to_string()),
+                    "version": peer.version,
+                    "software_version": peer.node_info.as_ref().map(|node_info| node_info.version.clone()),
+                    "features": peer.node_info.as_ref().map(|node_info| node_info.features.clone()),
+                    "address": peer.address.map(|address| address.to_string()),
+                    "num_peers": peer.peers.len(),
+                    "last_crawled": peer.last_crawled,
+                })
+            })
+            .collect::<Vec<_>>();
+        // Prepare the edges.
+        let edges = peers
+            .iter()
+            .flat_map(|(ip, peer)| peer.peers.iter().map(move |neighbor| serde_json::json!([ip, neighbor])))
+            .collect::<Vec<_>>();
+
+        serde_json::json!({
+            "network": N::ID,
+            "timestamp": Self::now(),
+            "num_nodes": nodes.len(),
+            "num_crawled": peers.values().filter(|peer| peer.is_crawled()).count(),
+            "num_edges": edges.len(),
+            "nodes": nodes,
+            "edges": edges,
+        })
+    }
+
+    /// Writes the network graph to the given path, as pretty-printed JSON.
+    pub fn export(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.to_json())?)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> Crawler<N> {
+    /// Records the handshake details of a newly-connected peer, and requests its peers.
+    fn crawl_peer(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.router.get_connected_peer(&peer_ip) {
+            let mut peers = self.peers.write();
+            let entry = peers.entry(peer_ip).or_default();
+            entry.node_type = Some(peer.node_type());
+            entry.version = Some(peer.version());
+            entry.address = Some(peer.address());
+        }
+        // Request the peers of the peer.
+        self.send(peer_ip, Message::PeerRequest(PeerRequest::default()));
+    }
+
+    /// Records the software version and features reported by the given peer.
+    fn record_node_info(&self, peer_ip: SocketAddr, node_info: NodeInfo) {
+        self.peers.write().entry(peer_ip).or_default().node_info = Some(node_info);
+    }
+
+    /// Records the peers reported by the given peer, and returns the newly-observed peers.
+    fn record_peers(&self, peer_ip: SocketAddr, neighbors: &[SocketAddr]) -> Vec<SocketAddr> {
+        let mut peers = self.peers.write();
+        // Update the reporting peer.
+        let entry = peers.entry(peer_ip).or_default();
+        entry.peers = neighbors.iter().copied().collect();
+        entry.last_crawled = Some(Self::now());
+        // Record the newly-observed peers.
+        let mut new_peers = Vec::new();
+        for neighbor in neighbors {
+            if !peers.contains_key(neighbor) {
+                peers.insert(*neighbor, Default::default());
+                new_peers.push(*neighbor);
+            }
+        }
+        new_peers
+    }
+
+    /// Connects to candidate peers that have not been crawled yet.
+    fn connect_to_uncrawled_peers(&self) {
+        // Determine the number of available connection slots.
+        let num_available = Self::MAXIMUM_NUMBER_OF_PEERS.saturating_sub(self.router.number_of_connected_peers());
+        // Select the candidate peers that have not been crawled, and have not exceeded the connection attempts.
+        let candidates = {
+            let mut peers = self.peers.write();
+            self.router
+                .candidate_peers()
+                .into_iter()
+                .filter(|peer_ip| {
+                    let entry = peers.entry(*peer_ip).or_default();
+                    match !entry.is_crawled() && entry.num_attempts < Self::MAXIMUM_CONNECTION_ATTEMPTS {
+                        true => {
+                            entry.num_attempts += 1;
+                            true
+                        }
+                        false => false,
+                    }
+                })
+                .take(num_available)
+                .collect::<Vec<_>>()
+        };
+        // Attempt to connect to the candidate peers.
+        for peer_ip in candidates {
+            self.router.connect(peer_ip);
+        }
+    }
+
+    /// Re-requests the peers of connected peers that have not responded yet.
+    fn request_pending_peers(&self) {
+        for peer_ip in self.router.connected_peers() {
+            let is_crawled = self.peers.read().get(&peer_ip).map(|peer| peer.is_crawled()).unwrap_or(false);
+            if !is_crawled {
+                self.send(peer_ip, Message::PeerRequest(PeerRequest::default()));
+            }
+        }
+    }
+
+    /// Returns the current UNIX timestamp.
+    fn now() -> i64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs() as i64).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl<N: Network> NodeInterface<N> for Crawler<N> {
+    /// Shuts down the node.
+    async fn shut_down(&self) {
+        info!("Shutting down...");
+
+        // Update the shutdown flag.
+        self.shutdown.store(true, Ordering::Relaxed);
+
+        // Notify the connected peers.
+        self.send_shutdown();
+
+        // Abort the tasks.
+        trace!("Shutting down the crawler...");
+        self.handles.lock().iter().for_each(|handle| handle.abort());
+
+        // Shut down the router.
+        self.router.shut_down().await;
+
+        info!("Node has shut down.");
+    }
+}
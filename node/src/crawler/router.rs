@@ -0,0 +1,235 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkos_node_router::{
+    messages::{
+        BlockRequest,
+        DisconnectReason,
+        MessageCodec,
+        Ping,
+        Pong,
+        UnconfirmedSolution,
+        UnconfirmedTransaction,
+    },
+    disconnect_reason,
+    Inbound,
+};
+use snarkos_node_tcp::{
+    protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
+    Connection,
+    ConnectionSide,
+    Tcp,
+};
+use snarkvm::prelude::{
+    block::{Header, Transaction},
+    coinbase::{EpochChallenge, ProverSolution},
+};
+
+use std::io;
+
+impl<N: Network> P2P for Crawler<N> {
+    /// Returns a reference to the TCP instance.
+    fn tcp(&self) -> &Tcp {
+        self.router.tcp()
+    }
+}
+
+#[async_trait]
+impl<N: Network> Handshake for Crawler<N> {
+    /// Performs the handshake protocol.
+    async fn perform_handshake(&self, mut connection: Connection) -> io::Result<Connection> {
+        // Perform the handshake.
+        let peer_addr = connection.addr();
+        let conn_side = connection.side();
+        let stream = self.borrow_stream(&mut connection);
+        let genesis_header = *self.genesis.header();
+        // The crawler does not maintain a ledger.
+        self.router.handshake(peer_addr, stream, conn_side, genesis_header, 0).await?;
+
+        Ok(connection)
+    }
+}
+
+#[async_trait]
+impl<N: Network> OnConnect for Crawler<N>
+where
+    Self: Outbound<N>,
+{
+    async fn on_connect(&self, peer_addr: SocketAddr) {
+        // Resolve the peer address to the listener address.
+        let Some(peer_ip) = self.router.resolve_to_listener(&peer_addr) else { return };
+        // Record the peer, and request its peers.
+        self.crawl_peer(peer_ip);
+    }
+}
+
+#[async_trait]
+impl<N: Network> Disconnect for Crawler<N> {
+    /// Any extra operations to be performed during a disconnect.
+    async fn handle_disconnect(&self, peer_addr: SocketAddr) {
+        if let Some(peer_ip) = self.router.resolve_to_listener(&peer_addr) {
+            self.router.remove_connected_peer(peer_ip);
+        }
+    }
+}
+
+#[async_trait]
+impl<N: Network> Writing for Crawler<N> {
+    type Codec = MessageCodec<N>;
+    type Message = Message<N>;
+
+    /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
+    /// The `side` parameter indicates the connection side **from the node's perspective**.
+    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        Default::default()
+    }
+}
+
+#[async_trait]
+impl<N: Network> Reading for Crawler<N> {
+    type Codec = MessageCodec<N>;
+    type Message = Message<N>;
+
+    /// Creates a [`Decoder`] used to interpret messages from the network.
+    /// The `side` param indicates the connection side **from the node's perspective**.
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        let router = self.router().clone();
+        MessageCodec::default()
+            .with_malformed_frame_handler(move |reason| router.record_malformed_frame(peer_addr, reason))
+    }
+
+    /// Processes a message received from the network.
+    async fn process_message(&self, peer_addr: SocketAddr, message: Self::Message) -> io::Result<()> {
+        // Process the message. Disconnect if the peer violated the protocol.
+        if let Err(error) = self.inbound(peer_addr, message).await {
+            if let Some(peer_ip) = self.router().resolve_to_listener(&peer_addr) {
+                debug!("Disconnecting from '{peer_addr}' - {error}");
+                self.send_disconnect(peer_ip, disconnect_reason(&error));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<N: Network> Routing<N> for Crawler<N> {}
+
+impl<N: Network> Heartbeat<N> for Crawler<N> {
+    /// The duration in seconds to sleep in between heartbeat executions.
+    const HEARTBEAT_IN_SECS: u64 = 5; // 5 seconds
+    /// The maximum number of peers permitted to maintain connections with.
+    const MAXIMUM_NUMBER_OF_PEERS: usize = 50;
+
+    /// Handles the heartbeat request.
+    fn heartbeat(&self) {
+        // Remove any stale connected peers.
+        self.remove_stale_connected_peers();
+        // Re-request the peers of connected peers that have not responded yet.
+        self.request_pending_peers();
+        // Connect to the peers that have not been crawled yet.
+        self.connect_to_uncrawled_peers();
+
+        debug!(
+            "Crawled {} of {} observed nodes ({} connected)",
+            self.num_crawled(),
+            self.num_observed(),
+            self.router.number_of_connected_peers()
+        );
+    }
+}
+
+impl<N: Network> Outbound<N> for Crawler<N> {
+    /// Returns a reference to the router.
+    fn router(&self) -> &Router<N> {
+        &self.router
+    }
+}
+
+#[async_trait]
+impl<N: Network> Inbound<N> for Crawler<N> {
+    /// Disconnects on receipt of a `BlockRequest` message.
+    fn block_request(&self, peer_ip: SocketAddr, _message: BlockRequest) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Disconnects on receipt of a `BlockResponse` message.
+    fn block_response(&self, peer_ip: SocketAddr, _blocks: Vec<Block<N>>) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Records the peers of the peer, and disconnects from it, as it has been crawled.
+    fn peer_response(&self, peer_ip: SocketAddr, peers: &[SocketAddr]) -> bool {
+        // Record the reported peers.
+        let new_peers = self.record_peers(peer_ip, peers);
+        trace!("Crawled '{peer_ip}' ({} peers, {} new)", peers.len(), new_peers.len());
+        // Add the newly-observed peers to the candidate peers.
+        self.router().insert_candidate_peers(&new_peers);
+        // Disconnect from the peer, to free up a connection slot.
+        self.send_disconnect(peer_ip, DisconnectReason::PeerRefresh);
+        true
+    }
+
+    /// Records the software version and features of the peer, and sends back a `Pong` message.
+    fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
+        self.record_node_info(peer_ip, message.node_info);
+        // Send a `Pong` message to the peer.
+        self.send(peer_ip, Message::Pong(Pong::new(Some(false), message.timestamp)));
+        true
+    }
+
+    /// Ignores the `Pong` message, as the crawler does not maintain long-lived connections.
+    fn pong(&self, _peer_ip: SocketAddr, _message: Pong) -> bool {
+        true
+    }
+
+    /// Disconnects on receipt of a `PuzzleRequest` message.
+    fn puzzle_request(&self, peer_ip: SocketAddr) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Ignores the `PuzzleResponse` message.
+    fn puzzle_response(&self, _peer_ip: SocketAddr, _epoch_challenge: EpochChallenge<N>, _header: Header<N>) -> bool {
+        true
+    }
+
+    /// Ignores the unconfirmed solution, as the crawler does not propagate messages.
+    async fn unconfirmed_solution(
+        &self,
+        _peer_ip: SocketAddr,
+        _serialized: UnconfirmedSolution<N>,
+        _solution: ProverSolution<N>,
+    ) -> bool {
+        true
+    }
+
+    /// Ignores the unconfirmed transaction, as the crawler does not propagate messages.
+    async fn unconfirmed_transaction(
+        &self,
+        _peer_ip: SocketAddr,
+        _serialized: UnconfirmedTransaction<N>,
+        _transaction: Transaction<N>,
+    ) -> bool {
+        true
+    }
+
+    /// Refuses to continue the stem, as the crawler does not propagate messages.
+    async fn is_valid_stem_transaction(&self, _transaction: Transaction<N>) -> bool {
+        false
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::MEMORY_POOL_PORT;
+
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The number of development IDs for which ports are allocated, in each range of a custom port base.
+pub const DEV_PORT_SPAN: u16 = 1000;
+
+/// The allocation of the local ports of the nodes of a development network, derived from their development IDs.
+///
+/// Each service of a node listens on the port at the offset of its development ID in the range of the service.
+/// By default, the ranges start at the historical ports (4130 for the router, 3030 for the REST server, and 5000 for
+/// the BFT). Given a port base, the ranges are laid out one after the other from the base, `DEV_PORT_SPAN` ports
+/// apart, so that several development networks run side by side if their port bases are `3 * DEV_PORT_SPAN` apart.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DevPortAllocation {
+    /// The port base from which the ranges are laid out, or `None` for the historical ports.
+    pub port_base: Option<u16>,
+    /// The first port of the range of the router.
+    pub node_port_base: u16,
+    /// The first port of the range of the REST server.
+    pub rest_port_base: u16,
+    /// The first port of the range of the BFT.
+    pub bft_port_base: u16,
+}
+
+impl DevPortAllocation {
+    /// The historical first port of the range of the router.
+    pub const DEFAULT_NODE_PORT_BASE: u16 = 4130;
+    /// The historical first port of the range of the REST server.
+    pub const DEFAULT_REST_PORT_BASE: u16 = 3030;
+
+    /// Initializes the allocation from the given port base, or the historical ports if none is given.
+    pub fn new(port_base: Option<u16>) -> Result<Self> {
+        let Some(port_base) = port_base else {
+            return Ok(Self {
+                port_base: None,
+                node_port_base: Self::DEFAULT_NODE_PORT_BASE,
+                rest_port_base: Self::DEFAULT_REST_PORT_BASE,
+                bft_port_base: MEMORY_POOL_PORT,
+            });
+        };
+        ensure!(port_base >= 1024, "The port base ({port_base}) must not be a privileged port");
+        ensure!(
+            u32::from(port_base) + 3 * u32::from(DEV_PORT_SPAN) <= u32::from(u16::MAX) + 1,
+            "The port base ({port_base}) leaves no room for the {DEV_PORT_SPAN} ports of each service"
+        );
+        Ok(Self {
+            port_base: Some(port_base),
+            node_port_base: port_base,
+            rest_port_base: port_base + DEV_PORT_SPAN,
+            bft_port_base: port_base + 2 * DEV_PORT_SPAN,
+        })
+    }
+
+    /// Returns the port of the router of the given development ID.
+    pub fn node_port(&self, dev: u16) -> Result<u16> {
+        self.port(self.node_port_base, dev)
+    }
+
+    /// Returns the port of the REST server of the given development ID.
+    pub fn rest_port(&self, dev: u16) -> Result<u16> {
+        self.port(self.rest_port_base, dev)
+    }
+
+    /// Returns the port of the BFT of the given development ID.
+    pub fn bft_port(&self, dev: u16) -> Result<u16> {
+        self.port(self.bft_port_base, dev)
+    }
+
+    /// Returns the port at the offset of the given development ID, in the range starting at the given port.
+    fn port(&self, range_start: u16, dev: u16) -> Result<u16> {
+        if self.port_base.is_some() {
+            ensure!(dev < DEV_PORT_SPAN, "The development ID ({dev}) must be below {DEV_PORT_SPAN} with a port base");
+        }
+        range_start.checked_add(dev).with_context(|| format!("The development ID ({dev}) is out of the port range"))
+    }
+
+    /// Returns the path of the file that records the allocation of the development network in the given directory.
+    /// The development networks are told apart by the first port of the range of the router.
+    pub fn path(&self, dir: &Path, network: u16) -> PathBuf {
+        dir.join(format!(".devnet-{network}-{}.json", self.node_port_base))
+    }
+
+    /// Records the allocation in its file in the given directory, atomically, so that tools (and the operator) can
+    /// discover the ports of the nodes of the development network. Returns the path of the file.
+    /// Note: As the allocation is deterministic, every node of the development network writes the same file.
+    pub fn record(&self, dir: &Path, network: u16) -> Result<PathBuf> {
+        let path = self.path(dir, network);
+        let temp_path = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to record the port allocation at '{}'", path.display()))?;
+        Ok(path)
+    }
+
+    /// Loads the allocation from the given file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let allocation = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the port allocation at '{}'", path.display()))?;
+        serde_json::from_str(&allocation)
+            .with_context(|| format!("Failed to parse the port allocation at '{}'", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_allocation() {
+        let allocation = DevPortAllocation::new(None).unwrap();
+        assert_eq!(allocation.node_port(0).unwrap(), 4130);
+        assert_eq!(allocation.rest_port(3).unwrap(), 3033);
+        assert_eq!(allocation.bft_port(2).unwrap(), MEMORY_POOL_PORT + 2);
+        assert!(allocation.node_port(u16::MAX).is_err());
+    }
+
+    #[test]
+    fn test_custom_allocation() {
+        let allocation = DevPortAllocation::new(Some(10000)).unwrap();
+        assert_eq!(allocation.node_port(0).unwrap(), 10000);
+        assert_eq!(allocation.rest_port(1).unwrap(), 11001);
+        assert_eq!(allocation.bft_port(2).unwrap(), 12002);
+        assert!(allocation.node_port(DEV_PORT_SPAN).is_err());
+
+        // The ranges must fit below the maximum port, above the privileged ports.
+        assert!(DevPortAllocation::new(Some(62536)).is_ok());
+        assert!(DevPortAllocation::new(Some(62537)).is_err());
+        assert!(DevPortAllocation::new(Some(80)).is_err());
+    }
+
+    #[test]
+    fn test_record_allocation() {
+        let dir = std::env::temp_dir().join(format!("snarkos-dev-ports-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let allocation = DevPortAllocation::new(Some(20000)).unwrap();
+        let path = allocation.record(&dir, 3).unwrap();
+        assert_eq!(path, dir.join(".devnet-3-20000.json"));
+        assert_eq!(DevPortAllocation::load(&path).unwrap(), allocation);
+        // Recording the allocation again overwrites the file.
+        allocation.record(&dir, 3).unwrap();
+        assert_eq!(DevPortAllocation::load(&path).unwrap(), allocation);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{traits::NodeInterface, Validator};
+use snarkos_account::Account;
+use snarkos_node_router::{messages::UnconfirmedTransaction, Inbound, Outbound};
+use snarkvm::prelude::{block::Block, store::helpers::memory::ConsensusMemory, Network, PrivateKey, Program};
+
+use aleo_std::StorageMode;
+use anyhow::{ensure, Context, Result};
+use rand::{CryptoRng, Rng};
+use serde::{Deserialize, Serialize};
+use std::{
+    net::{SocketAddr, TcpListener},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// The source of the test program that an ephemeral development network deploys by default.
+pub const EPHEMERAL_TEST_PROGRAM: &str = r"program ephemeral_test.aleo;
+
+function add:
+    input r0 as u32.public;
+    input r1 as u32.public;
+    add r0 r1 into r2;
+    output r2 as u32.public;
+";
+
+/// The default number of fixture accounts.
+const DEFAULT_NUM_FIXTURE_ACCOUNTS: usize = 4;
+/// The default public balance (in microcredits) of a fixture account.
+const DEFAULT_FIXTURE_BALANCE: u64 = 1_000_000_000_000;
+/// The maximum number of seconds to wait for a fixture program to be deployed, before deploying the next one.
+const MAX_DEPLOYMENT_WAIT_IN_SECS: u64 = 300;
+
+/// The accounts and programs that an ephemeral development network is seeded with.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EphemeralFixtures {
+    /// The fixture accounts, which are allocated a public balance in the genesis block.
+    #[serde(default)]
+    pub accounts: Vec<FixtureAccount>,
+    /// The paths to the programs to deploy, in order.
+    #[serde(default)]
+    pub programs: Vec<PathBuf>,
+    /// If `true`, the built-in `ephemeral_test.aleo` program is deployed before the other programs.
+    #[serde(default = "EphemeralFixtures::default_test_program")]
+    pub test_program: bool,
+}
+
+/// A fixture account of an ephemeral development network.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FixtureAccount {
+    /// The private key of the account, which is generated if it is not specified.
+    #[serde(default)]
+    pub private_key: Option<String>,
+    /// The public balance of the account, in microcredits.
+    pub balance: u64,
+}
+
+impl Default for EphemeralFixtures {
+    /// Returns a few generated accounts with a public balance, and the built-in test program.
+    fn default() -> Self {
+        Self {
+            accounts: vec![
+                FixtureAccount { private_key: None, balance: DEFAULT_FIXTURE_BALANCE };
+                DEFAULT_NUM_FIXTURE_ACCOUNTS
+            ],
+            programs: vec![],
+            test_program: true,
+        }
+    }
+}
+
+impl EphemeralFixtures {
+    /// Loads the fixtures from the given path.
+    pub fn load(path: &Path) -> Result<Self> {
+        let fixtures = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the fixtures at '{}'", path.display()))?;
+        serde_json::from_str(&fixtures).with_context(|| format!("Failed to parse the fixtures at '{}'", path.display()))
+    }
+
+    /// Returns the private keys and balances of the fixture accounts.
+    /// The missing private keys are generated from the given RNG, so that a seeded RNG yields the same accounts.
+    pub fn accounts<N: Network, R: Rng + CryptoRng>(&self, rng: &mut R) -> Result<Vec<(PrivateKey<N>, u64)>> {
+        self.accounts
+            .iter()
+            .map(|account| {
+                let private_key = match &account.private_key {
+                    Some(private_key) => {
+                        PrivateKey::from_str(private_key).context("Failed to parse the private key of a fixture")?
+                    }
+                    None => PrivateKey::new(rng)?,
+                };
+                Ok((private_key, account.balance))
+            })
+            .collect()
+    }
+
+    /// Returns the programs to deploy, in order.
+    pub fn programs<N: Network>(&self) -> Result<Vec<Program<N>>> {
+        let mut programs = Vec::with_capacity(self.programs.len() + 1);
+        if self.test_program {
+            programs.push(Program::from_str(EPHEMERAL_TEST_PROGRAM)?);
+        }
+        for path in &self.programs {
+            let source = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read the program at '{}'", path.display()))?;
+            let program = Program::from_str(&source)
+                .with_context(|| format!("Failed to parse the program at '{}'", path.display()))?;
+            programs.push(program);
+        }
+        Ok(programs)
+    }
+
+    /// Returns the default setting for deploying the built-in test program.
+    const fn default_test_program() -> bool {
+        true
+    }
+}
+
+/// An ephemeral development network, which runs every member of the genesis committee in this process.
+///
+/// The ledgers and the Narwhal transmissions are kept in memory, and the nodes listen on random local ports.
+/// The only files are the consensus archive, the transaction overflow queue, and the node ID of each validator,
+/// which are kept in the temporary directory of the OS and removed when the validator shuts down.
+pub struct EphemeralDevnet<N: Network> {
+    /// The validators, of which the first one runs the REST server and deploys the fixture programs.
+    validators: Vec<Validator<N, ConsensusMemory<N>>>,
+    /// The IP address of the REST server.
+    rest_ip: SocketAddr,
+}
+
+impl<N: Network> EphemeralDevnet<N> {
+    /// Starts a validator for each of the given committee members, and deploys the given programs.
+    pub async fn start(
+        committee: Vec<Account<N>>,
+        genesis: Block<N>,
+        rest_rps: u32,
+        programs: Vec<Program<N>>,
+    ) -> Result<Self> {
+        ensure!(!committee.is_empty(), "An ephemeral development network requires at least one validator");
+
+        // Pick the random ports of the validators and the REST server.
+        let node_ips = committee.iter().map(|_| random_local_ip()).collect::<Result<Vec<_>>>()?;
+        let bft_ips = committee.iter().map(|_| random_local_ip()).collect::<Result<Vec<_>>>()?;
+        let rest_ip = random_local_ip()?;
+        // Returns the IPs of all validators except the one at the given index.
+        let others = |ips: &[SocketAddr], index: usize| {
+            ips.iter().enumerate().filter(|(i, _)| *i != index).map(|(_, ip)| *ip).collect::<Vec<_>>()
+        };
+
+        // Number the devnets of this process, so that their storage directories do not collide.
+        static NUM_DEVNETS: AtomicUsize = AtomicUsize::new(0);
+        let devnet_id = NUM_DEVNETS.fetch_add(1, Ordering::Relaxed);
+
+        // Start the validators.
+        let mut validators = Vec::with_capacity(committee.len());
+        for (i, account) in committee.into_iter().enumerate() {
+            let dir = std::env::temp_dir().join(format!("snarkos-ephemeral-{}-{devnet_id}-{i}", std::process::id()));
+            let validator = Validator::new(
+                node_ips[i],
+                Some(bft_ips[i]),
+                (i == 0).then_some(rest_ip),
+                rest_rps,
+                account.into(),
+                &others(&node_ips, i),
+                &others(&bft_ips, i),
+                genesis.clone(),
+                None,
+                StorageMode::Development(i as u16),
+                None,
+                None,
+                Some(dir),
+            )
+            .await?;
+            // Propose a batch as soon as a transaction arrives, to minimize the latency of the development network.
+            validator.set_proposal_trigger(1, 0);
+            validators.push(validator);
+        }
+
+        let devnet = Self { validators, rest_ip };
+        // Deploy the programs.
+        devnet.deploy_programs(programs);
+        Ok(devnet)
+    }
+
+    /// Returns the validator that runs the REST server.
+    pub fn primary(&self) -> &Validator<N, ConsensusMemory<N>> {
+        &self.validators[0]
+    }
+
+    /// Returns the validators.
+    pub fn validators(&self) -> &[Validator<N, ConsensusMemory<N>>] {
+        &self.validators
+    }
+
+    /// Returns the IP address of the REST server.
+    pub const fn rest_ip(&self) -> SocketAddr {
+        self.rest_ip
+    }
+
+    /// Deploys the given programs in order from the primary validator, waiting for each deployment to be
+    /// included in a block, so that the programs can import the ones that precede them.
+    fn deploy_programs(&self, programs: Vec<Program<N>>) {
+        if programs.is_empty() {
+            return;
+        }
+        let validator = self.primary().clone();
+        self.primary().spawn(async move {
+            for program in programs {
+                let program_id = *program.id();
+                info!("Deploying the fixture program '{program_id}'...");
+                // Compute the deployment.
+                let validator_ = validator.clone();
+                let transaction = tokio::task::spawn_blocking(move || {
+                    let rng = &mut rand::thread_rng();
+                    validator_.ledger().vm().deploy(validator_.private_key(), &program, None, 0, None, rng)
+                })
+                .await;
+                let transaction = match transaction {
+                    Ok(Ok(transaction)) => transaction,
+                    Ok(Err(error)) => {
+                        error!("Failed to deploy the fixture program '{program_id}' - {error}");
+                        continue;
+                    }
+                    Err(error) => {
+                        error!("Failed to deploy the fixture program '{program_id}' - {error}");
+                        continue;
+                    }
+                };
+                // Submit the deployment to the memory pool.
+                let peer_ip = validator.router().local_ip();
+                let message = UnconfirmedTransaction::from(transaction.clone());
+                if !validator.unconfirmed_transaction(peer_ip, message, transaction).await {
+                    error!("Failed to submit the deployment of the fixture program '{program_id}'");
+                    continue;
+                }
+                // Wait for the deployment to be included in a block.
+                let mut waited = 0;
+                while !validator.ledger().vm().contains_program(&program_id) {
+                    if waited >= MAX_DEPLOYMENT_WAIT_IN_SECS {
+                        warn!("The fixture program '{program_id}' was not deployed after {waited} seconds");
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    waited += 1;
+                }
+                if validator.ledger().vm().contains_program(&program_id) {
+                    info!("Deployed the fixture program '{program_id}'");
+                }
+            }
+        });
+    }
+}
+
+/// Returns a local IP address with a port that is currently free.
+fn random_local_ip() -> Result<SocketAddr> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::Testnet3;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_fixtures() {
+        // The default fixtures fund a few generated accounts and deploy the test program.
+        let fixtures = EphemeralFixtures::default();
+        let accounts = fixtures.accounts::<CurrentNetwork, _>(&mut ChaChaRng::seed_from_u64(1)).unwrap();
+        assert_eq!(accounts.len(), DEFAULT_NUM_FIXTURE_ACCOUNTS);
+        assert!(accounts.iter().all(|(_, balance)| *balance == DEFAULT_FIXTURE_BALANCE));
+        // The generated accounts are deterministic for a seeded RNG.
+        let again = fixtures.accounts::<CurrentNetwork, _>(&mut ChaChaRng::seed_from_u64(1)).unwrap();
+        assert_eq!(accounts, again);
+        let programs = fixtures.programs::<CurrentNetwork>().unwrap();
+        assert_eq!(programs.len(), 1);
+        assert_eq!(programs[0].id().to_string(), "ephemeral_test.aleo");
+
+        // The fixtures are parsed from JSON, deploying the test program unless it is disabled.
+        let private_key = accounts[0].0.to_string();
+        let json = format!(r#"{{"accounts": [{{"private_key": "{private_key}", "balance": 5}}, {{"balance": 7}}]}}"#);
+        let fixtures: EphemeralFixtures = serde_json::from_str(&json).unwrap();
+        assert!(fixtures.test_program);
+        assert!(fixtures.programs.is_empty());
+        let accounts = fixtures.accounts::<CurrentNetwork, _>(&mut ChaChaRng::seed_from_u64(2)).unwrap();
+        assert_eq!(accounts[0].0.to_string(), private_key);
+        assert_eq!((accounts[0].1, accounts[1].1), (5, 7));
+
+        let fixtures: EphemeralFixtures = serde_json::from_str(r#"{"test_program": false}"#).unwrap();
+        assert!(fixtures.accounts.is_empty());
+        assert!(fixtures.programs::<CurrentNetwork>().unwrap().is_empty());
+
+        // A malformed private key is rejected.
+        let fixtures: EphemeralFixtures =
+            serde_json::from_str(r#"{"accounts": [{"private_key": "APrivateKey1", "balance": 1}]}"#).unwrap();
+        assert!(fixtures.accounts::<CurrentNetwork, _>(&mut ChaChaRng::seed_from_u64(3)).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::ledger_service::CoreLedgerService;
+use snarkvm::prelude::{store::ConsensusStorage, Network};
+
+use anyhow::Result;
+use core::fmt::{self, Display};
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+/// The default interval in seconds in between the rounds of the index audit.
+pub const DEFAULT_INDEX_AUDIT_INTERVAL_IN_SECS: u64 = 900; // 15 minutes
+/// The default number of blocks that are sampled in each round of the index audit.
+pub const DEFAULT_INDEX_AUDIT_SAMPLE_SIZE: usize = 16;
+
+/// The configuration of the index audit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexAuditConfig {
+    /// The interval in seconds in between the rounds of the audit.
+    pub interval_secs: u64,
+    /// The number of blocks that are sampled in each round of the audit.
+    pub sample_size: usize,
+    /// If `true`, the inconsistent indexes are rewritten from the blocks, where possible.
+    pub repair: bool,
+}
+
+impl Default for IndexAuditConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: DEFAULT_INDEX_AUDIT_INTERVAL_IN_SECS,
+            sample_size: DEFAULT_INDEX_AUDIT_SAMPLE_SIZE,
+            repair: false,
+        }
+    }
+}
+
+/// A secondary index of the ledger, which is derived from the blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DerivedIndex {
+    /// The index of the transaction IDs to the hashes of the blocks that contain them.
+    TransactionToBlockHash,
+    /// The index of the transition IDs to the IDs of the transactions that contain them.
+    TransitionToTransaction,
+    /// The index of the program IDs to the IDs of the transactions that deployed them.
+    ProgramToTransaction,
+}
+
+impl DerivedIndex {
+    /// Returns `true` if the index is rewritten when the transaction is reinserted into the transaction store.
+    /// Note: The block hash of a transaction is indexed by the block store, so it is only restored by a resync.
+    pub const fn is_repairable(&self) -> bool {
+        !matches!(self, Self::TransactionToBlockHash)
+    }
+}
+
+impl Display for DerivedIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TransactionToBlockHash => write!(f, "transaction -> block hash"),
+            Self::TransitionToTransaction => write!(f, "transition -> transaction"),
+            Self::ProgramToTransaction => write!(f, "program -> transaction"),
+        }
+    }
+}
+
+/// An entry of a derived index that does not match the block it is derived from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexInconsistency {
+    /// The derived index.
+    pub index: DerivedIndex,
+    /// The height of the block from which the entry is derived.
+    pub height: u32,
+    /// The key of the entry.
+    pub key: String,
+    /// The value of the entry, as derived from the block.
+    pub expected: String,
+    /// The value of the entry, as stored in the index.
+    pub found: Option<String>,
+    /// Whether the entry was repaired.
+    pub is_repaired: bool,
+}
+
+impl Display for IndexInconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let found = self.found.as_deref().unwrap_or("nothing");
+        let Self { index, height, key, expected, .. } = self;
+        write!(f, "'{key}' ({index}) in block {height} maps to {found}, instead of {expected}")
+    }
+}
+
+/// The outcome of a round of the index audit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexAuditReport {
+    /// The number of audited blocks.
+    pub num_blocks: usize,
+    /// The number of audited index entries.
+    pub num_entries: usize,
+    /// The inconsistent index entries.
+    pub inconsistencies: Vec<IndexInconsistency>,
+}
+
+impl IndexAuditReport {
+    /// Compares the given stored value of an index entry to its derived value, and records an inconsistency
+    /// if they differ. Returns `true` if the entry is consistent.
+    fn check<K: Display, V: Display + PartialEq>(
+        &mut self,
+        index: DerivedIndex,
+        height: u32,
+        key: K,
+        expected: V,
+        found: Option<V>,
+    ) -> bool {
+        self.num_entries += 1;
+        if found.as_ref() == Some(&expected) {
+            return true;
+        }
+        self.inconsistencies.push(IndexInconsistency {
+            index,
+            height,
+            key: key.to_string(),
+            expected: expected.to_string(),
+            found: found.map(|found| found.to_string()),
+            is_repaired: false,
+        });
+        false
+    }
+}
+
+/// Re-derives the secondary indexes of the transactions in the blocks at the given heights, and compares them to
+/// the stored indexes. If `repair` is set, the transactions with inconsistent transition or program indexes are
+/// reinserted into the transaction store, which rewrites their indexes from the block data. The reinsertion holds
+/// the advance lock of the ledger, so that its writes are never interleaved with the writes of a new block.
+///
+/// Only the accepted transactions are audited, as they are the ones that the indexes resolve to their blocks.
+pub fn audit_indexes<N: Network, C: ConsensusStorage<N>>(
+    ledger_service: &CoreLedgerService<N, C>,
+    heights: impl IntoIterator<Item = u32>,
+    repair: bool,
+) -> Result<IndexAuditReport> {
+    let ledger = ledger_service.ledger();
+    let mut report = IndexAuditReport::default();
+    for height in heights {
+        let block = ledger.get_block(height)?;
+        let hash = block.hash();
+        for confirmed in block.transactions().iter().filter(|confirmed| confirmed.is_accepted()) {
+            let transaction = confirmed.transaction();
+            let transaction_id = transaction.id();
+            let num_inconsistencies = report.inconsistencies.len();
+
+            // Check the block hash of the transaction.
+            let found = ledger.find_block_hash(&transaction_id)?;
+            report.check(DerivedIndex::TransactionToBlockHash, height, transaction_id, hash, found);
+            // Check the transaction ID of each transition, and of the deployed program.
+            let mut is_repairable = false;
+            for transition in transaction.transitions() {
+                let (index, key) = (DerivedIndex::TransitionToTransaction, transition.id());
+                let found = ledger.find_transaction_id_from_transition_id(key)?;
+                is_repairable |= !report.check(index, height, key, transaction_id, found);
+            }
+            if let Some(deployment) = transaction.deployment() {
+                let (index, key) = (DerivedIndex::ProgramToTransaction, deployment.program_id());
+                let found = ledger.find_transaction_id_from_program_id(key)?;
+                is_repairable |= !report.check(index, height, key, transaction_id, found);
+            }
+
+            // Rewrite the transaction indexes from the block data.
+            if repair && is_repairable {
+                match ledger_service.with_advance_lock(|ledger| ledger.vm().transaction_store().insert(transaction)) {
+                    Ok(()) => report.inconsistencies[num_inconsistencies..]
+                        .iter_mut()
+                        .for_each(|inconsistency| inconsistency.is_repaired = inconsistency.index.is_repairable()),
+                    Err(error) => warn!("Failed to repair the indexes of transaction '{transaction_id}' - {error}"),
+                }
+            }
+        }
+        report.num_blocks += 1;
+    }
+    Ok(report)
+}
+
+/// Starts a task that periodically audits the derived indexes of a random sample of the blocks in the ledger.
+///
+/// Each round runs on the blocking thread pool, so that it does not compete with the network tasks,
+/// and logs the inconsistencies it finds, which would otherwise only surface as wrong answers of the REST server.
+pub fn start_index_audit<N: Network, C: ConsensusStorage<N>>(
+    ledger_service: Arc<CoreLedgerService<N, C>>,
+    config: IndexAuditConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(config.interval_secs.max(1));
+        loop {
+            tokio::time::sleep(interval).await;
+            let ledger_service = ledger_service.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                // Sample the heights of the blocks to audit.
+                let num_blocks = ledger_service.ledger().latest_height() as usize + 1;
+                let sample_size = config.sample_size.min(num_blocks);
+                let heights = rand::seq::index::sample(&mut rand::thread_rng(), num_blocks, sample_size);
+                audit_indexes(&ledger_service, heights.into_iter().map(|height| height as u32), config.repair)
+            })
+            .await;
+            match result {
+                Ok(Ok(report)) if report.inconsistencies.is_empty() => {
+                    debug!("Audited {} index entries in {} blocks", report.num_entries, report.num_blocks)
+                }
+                Ok(Ok(report)) => {
+                    for inconsistency in &report.inconsistencies {
+                        match inconsistency.is_repaired {
+                            true => warn!("Repaired an inconsistent index entry - {inconsistency}"),
+                            false => error!("Found an inconsistent index entry - {inconsistency}"),
+                        }
+                    }
+                }
+                Ok(Err(error)) => warn!("Failed to audit the derived indexes - {error}"),
+                Err(error) => error!("The index audit panicked - {error}"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleo_std::StorageMode;
+    use snarkvm::prelude::{
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        Ledger,
+        PrivateKey,
+        TestRng,
+        Testnet3,
+        VM,
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_audit_indexes() {
+        let rng = &mut TestRng::default();
+        // Initialize a ledger with the genesis block.
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None).unwrap();
+        let genesis = VM::from(store).unwrap().genesis_beacon(&private_key, rng).unwrap();
+        let ledger =
+            Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, StorageMode::Production).unwrap();
+        let ledger_service = CoreLedgerService::new(ledger, Default::default());
+
+        // Ensure the indexes of the genesis block are consistent.
+        let report = audit_indexes(&ledger_service, [0], true).unwrap();
+        assert_eq!(report.num_blocks, 1);
+        assert!(report.num_entries > 0);
+        assert!(report.inconsistencies.is_empty());
+        // Ensure an unknown block is an error.
+        assert!(audit_indexes(&ledger_service, [1], false).is_err());
+    }
+
+    #[test]
+    fn test_index_audit_report() {
+        let mut report = IndexAuditReport::default();
+        assert!(report.check(DerivedIndex::ProgramToTransaction, 1, "foo.aleo", "at1", Some("at1")));
+        assert!(!report.check(DerivedIndex::TransactionToBlockHash, 2, "at2", "ab2", None));
+        assert_eq!(report.num_entries, 2);
+        assert_eq!(report.inconsistencies.len(), 1);
+        assert_eq!(
+            report.inconsistencies[0].to_string(),
+            "'at2' (transaction -> block hash) in block 2 maps to nothing, instead of ab2"
+        );
+        assert!(!report.inconsistencies[0].index.is_repairable());
+    }
+}
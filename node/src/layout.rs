@@ -0,0 +1,275 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The version of the data directory layout that this release reads and writes.
+pub const CURRENT_LAYOUT_VERSION: u32 = 1;
+
+/// The manifest of a data directory, which records the version of its layout.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LayoutManifest {
+    /// The version of the layout.
+    pub version: u32,
+    /// The ID of the network whose ledger is stored in the directory.
+    pub network: u16,
+    /// The version of snarkOS that last wrote the manifest.
+    pub written_by: String,
+}
+
+/// A migration of a data directory from one layout version to the next.
+struct Migration {
+    /// The layout version that the migration upgrades from.
+    from: u32,
+    /// A description of the migration, for the operator.
+    description: &'static str,
+    /// Whether the migration leaves the stores untouched, in which case it is applied on startup.
+    is_automatic: bool,
+    /// Applies the migration to the given data directory.
+    apply: fn(&Path) -> Result<()>,
+}
+
+/// The migrations between the layout versions, in order.
+///
+/// Version `0` is the unversioned layout of the releases that predate the manifest.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "Record the layout manifest (the stores are unchanged)",
+    is_automatic: true,
+    apply: record_manifest,
+}];
+
+/// Migrates the unversioned layout, whose stores already match version `1`, by only recording the manifest.
+fn record_manifest(_dir: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// The versioned layout of the data directory of a node, i.e. its ledger directory.
+///
+/// The manifest is stored inside the data directory, so that `snarkos clean` removes it along with the stores.
+/// A node refuses to start on a layout it does not know, or on an outdated layout that requires a migration
+/// which rewrites the stores, rather than opening the stores with the wrong paths and corrupting or recreating them.
+#[derive(Clone, Debug)]
+pub struct DataLayout {
+    /// The data directory.
+    dir: PathBuf,
+    /// The ID of the network.
+    network: u16,
+}
+
+impl DataLayout {
+    /// The file name of the manifest, in the data directory.
+    pub const MANIFEST_FILE_NAME: &'static str = "layout.json";
+
+    /// Initializes the layout of the given data directory, for the given network.
+    pub fn new(dir: PathBuf, network: u16) -> Self {
+        Self { dir, network }
+    }
+
+    /// Returns the data directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path to the manifest.
+    pub fn manifest_path(&self) -> PathBuf {
+        self.dir.join(Self::MANIFEST_FILE_NAME)
+    }
+
+    /// Returns the manifest, or `None` if the data directory does not have one.
+    pub fn manifest(&self) -> Result<Option<LayoutManifest>> {
+        let path = self.manifest_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let manifest = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read the layout manifest at '{}'", path.display()))?;
+        let manifest = serde_json::from_str(&manifest)
+            .with_context(|| format!("Failed to parse the layout manifest at '{}'", path.display()))?;
+        Ok(Some(manifest))
+    }
+
+    /// Returns `true` if the data directory does not exist yet, or is empty.
+    fn is_fresh(&self) -> Result<bool> {
+        match self.dir.exists() {
+            true => Ok(std::fs::read_dir(&self.dir)?.next().is_none()),
+            false => Ok(true),
+        }
+    }
+
+    /// Returns the layout version of the data directory, or `None` if the directory is fresh.
+    pub fn version(&self) -> Result<Option<u32>> {
+        match self.manifest()? {
+            Some(manifest) => {
+                ensure!(
+                    manifest.network == self.network,
+                    "The data directory '{}' belongs to network {}, not network {}",
+                    self.dir.display(),
+                    manifest.network,
+                    self.network
+                );
+                Ok(Some(manifest.version))
+            }
+            None => match self.is_fresh()? {
+                true => Ok(None),
+                false => Ok(Some(0)),
+            },
+        }
+    }
+
+    /// Ensures the data directory has the current layout, before the stores are opened.
+    ///
+    /// A fresh directory is stamped with the current layout, and the automatic migrations are applied.
+    /// An unknown layout, or an outdated one that requires the operator to migrate, is refused.
+    pub fn check(&self) -> Result<()> {
+        let version = match self.version()? {
+            Some(version) => version,
+            None => return self.write_manifest(CURRENT_LAYOUT_VERSION),
+        };
+        ensure!(
+            version <= CURRENT_LAYOUT_VERSION,
+            "The data directory '{}' has layout version {version}, which is newer than the supported version \
+             {CURRENT_LAYOUT_VERSION} - upgrade snarkOS, or point it at another directory",
+            self.dir.display()
+        );
+        let pending = Self::pending_migrations(version)?;
+        if let Some(migration) = pending.iter().find(|migration| !migration.is_automatic) {
+            bail!(
+                "The data directory '{}' has layout version {version}, and requires a migration ({}) - \
+                 run 'snarkos storage migrate' first",
+                self.dir.display(),
+                migration.description
+            )
+        }
+        self.apply(version, &pending)?;
+        Ok(())
+    }
+
+    /// Migrates the data directory to the current layout, and returns the descriptions of the applied migrations.
+    pub fn migrate(&self) -> Result<Vec<&'static str>> {
+        let Some(version) = self.version()? else {
+            bail!("There is no data directory at '{}' to migrate", self.dir.display())
+        };
+        ensure!(
+            version <= CURRENT_LAYOUT_VERSION,
+            "The data directory '{}' has layout version {version}, which is newer than the supported version \
+             {CURRENT_LAYOUT_VERSION}",
+            self.dir.display()
+        );
+        let pending = Self::pending_migrations(version)?;
+        self.apply(version, &pending)?;
+        Ok(pending.iter().map(|migration| migration.description).collect())
+    }
+
+    /// Returns the migrations from the given layout version to the current one, in order.
+    fn pending_migrations(mut version: u32) -> Result<Vec<&'static Migration>> {
+        let mut pending = Vec::new();
+        while version < CURRENT_LAYOUT_VERSION {
+            let Some(migration) = MIGRATIONS.iter().find(|migration| migration.from == version) else {
+                bail!("There is no migration from layout version {version}")
+            };
+            pending.push(migration);
+            version += 1;
+        }
+        Ok(pending)
+    }
+
+    /// Applies the given migrations in order, recording the version in the manifest after each one,
+    /// so that an interrupted migration resumes from the last completed step.
+    fn apply(&self, version: u32, migrations: &[&Migration]) -> Result<()> {
+        for migration in migrations {
+            info!("Migrating the data directory from layout version {} - {}", migration.from, migration.description);
+            (migration.apply)(&self.dir)
+                .with_context(|| format!("Failed to migrate from layout version {}", migration.from))?;
+            self.write_manifest(migration.from + 1)?;
+        }
+        // Refresh the manifest, if it is current but was last written by another release.
+        if migrations.is_empty() && version == CURRENT_LAYOUT_VERSION {
+            if let Some(manifest) = self.manifest()? {
+                if manifest.written_by != env!("CARGO_PKG_VERSION") {
+                    self.write_manifest(version)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the manifest with the given layout version, atomically.
+    fn write_manifest(&self, version: u32) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let manifest =
+            LayoutManifest { version, network: self.network, written_by: env!("CARGO_PKG_VERSION").to_string() };
+        let path = self.manifest_path();
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(&manifest)?)?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to write the layout manifest at '{}'", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("snarkos-layout-{}", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn test_fresh_layout() {
+        let layout = DataLayout::new(sample_dir(), 3);
+        assert_eq!(layout.version().unwrap(), None);
+
+        // A fresh directory is stamped with the current layout.
+        layout.check().unwrap();
+        assert_eq!(layout.version().unwrap(), Some(CURRENT_LAYOUT_VERSION));
+        assert!(layout.check().is_ok());
+
+        // A different network is refused.
+        assert!(DataLayout::new(layout.dir.clone(), 4).check().is_err());
+        std::fs::remove_dir_all(&layout.dir).unwrap();
+    }
+
+    #[test]
+    fn test_legacy_layout() {
+        let layout = DataLayout::new(sample_dir(), 3);
+        std::fs::create_dir_all(&layout.dir).unwrap();
+        std::fs::write(layout.dir.join("CURRENT"), "MANIFEST-000001").unwrap();
+
+        // A directory without a manifest predates the versioning.
+        assert_eq!(layout.version().unwrap(), Some(0));
+        assert_eq!(layout.migrate().unwrap().len(), 1);
+        assert_eq!(layout.version().unwrap(), Some(CURRENT_LAYOUT_VERSION));
+        // Migrating again is a no-op.
+        assert!(layout.migrate().unwrap().is_empty());
+        std::fs::remove_dir_all(&layout.dir).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_layout() {
+        let layout = DataLayout::new(sample_dir(), 3);
+        layout.write_manifest(CURRENT_LAYOUT_VERSION + 1).unwrap();
+
+        // A newer layout is refused, both on startup and by the migration.
+        assert!(layout.check().is_err());
+        assert!(layout.migrate().is_err());
+
+        // A corrupted manifest is refused.
+        std::fs::write(layout.manifest_path(), "{").unwrap();
+        assert!(layout.check().is_err());
+        std::fs::remove_dir_all(&layout.dir).unwrap();
+    }
+}
@@ -30,22 +30,61 @@ pub use snarkos_node_sync as sync;
 pub use snarkos_node_tcp as tcp;
 pub use snarkvm;
 
+mod alerts;
+pub use alerts::*;
+
 mod client;
 pub use client::*;
 
+mod crawler;
+pub use crawler::*;
+
+mod dev_ports;
+pub use dev_ports::*;
+
+mod ephemeral;
+pub use ephemeral::*;
+
 mod prover;
 pub use prover::*;
 
 mod validator;
 pub use validator::*;
 
+mod index_audit;
+pub use index_audit::*;
+
+mod layout;
+pub use layout::*;
+
 mod node;
 pub use node::*;
 
+mod safe_mode;
+pub use safe_mode::*;
+
+mod shutdown;
+pub use shutdown::*;
+
 mod traits;
 pub use traits::*;
 
+mod webhooks;
+pub use webhooks::*;
+
 use aleo_std::StorageMode;
+use snarkos_account::Account;
+use snarkos_node_bus::{EventBus, NodeEvent};
+use snarkos_node_router::{messages::NodeId, Router};
+
+/// Loads the persistent node ID of the given account, creating it if it does not exist.
+///
+/// The node ID is stored next to the ledger directory, so that it survives `snarkos clean`.
+pub fn load_node_id<N: Network>(account: &Account<N>, storage_mode: &StorageMode) -> Result<NodeId<N>> {
+    let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+    let path = std::path::PathBuf::from(format!("{}.node-id", ledger_dir.display()));
+    snarkos_node_router::load_or_create_node_id(account, &path)
+}
 
 /// A helper to log instructions to recover.
 pub fn log_clean_error(storage_mode: &StorageMode) {
@@ -201,6 +240,48 @@ pub fn phase_3_reset<N: Network, C: ConsensusStorage<N>>(
     Ok(ledger)
 }
 
+/// Starts a task that caches each block as it is added to the ledger, since the syncing peers are about to request it,
+/// and evicts the cached blocks that no longer match the ledger, including the blocks above the tip after a rollback.
+pub fn start_block_cache_loop<N: Network, C: ConsensusStorage<N>>(
+    router: Router<N>,
+    ledger: Ledger<N, C>,
+    event_bus: &EventBus<N>,
+) -> tokio::task::JoinHandle<()> {
+    use tokio::sync::broadcast::error::RecvError;
+
+    let mut events = event_bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(NodeEvent::BlockAdvanced { height, hash }) => {
+                    // Evict the cached blocks from this height onwards, if they no longer match the ledger,
+                    // or otherwise the cached blocks above this height, as they are above the tip after a rollback.
+                    match router.block_cache().get(height).map_or(false, |block| block.hash() != hash) {
+                        true => router.block_cache().remove_from(height),
+                        false => router.block_cache().remove_from(height.saturating_add(1)),
+                    }
+                    // Cache the new block.
+                    let ledger = ledger.clone();
+                    match tokio::task::spawn_blocking(move || ledger.get_block(height)).await {
+                        Ok(Ok(block)) if block.hash() == hash => {
+                            router.block_cache().insert(block);
+                        }
+                        Ok(Ok(_)) => (),
+                        Ok(Err(error)) => debug!("Failed to cache block {height} - {error}"),
+                        Err(error) => error!("Failed to cache block {height} - {error}"),
+                    }
+                }
+                // If the receiver fell behind, evict the cached blocks above the tip, in case of a missed rollback.
+                Err(RecvError::Lagged(_)) => {
+                    router.block_cache().remove_from(ledger.latest_height().saturating_add(1));
+                }
+                Ok(_) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
 /// Starts the notification message loop.
 pub fn start_notification_message_loop() -> tokio::task::JoinHandle<()> {
     let mut interval = tokio::time::interval(std::time::Duration::from_secs(180));
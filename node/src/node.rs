@@ -12,11 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{traits::NodeInterface, Client, Prover, Validator};
-use snarkos_account::Account;
-use snarkos_node_router::messages::NodeType;
+use crate::{
+    traits::NodeInterface,
+    AlertConfig,
+    Client,
+    EphemeralDevnet,
+    IndexAuditConfig,
+    LockFile,
+    Prover,
+    Validator,
+    WebhookConfig,
+};
+use snarkos_account::{Account, AccountRoles};
+use snarkos_node_bft::helpers::{BatchTuningBounds, ProtocolUpgrade, TransactionValidator};
+use snarkos_node_router::{
+    messages::NodeType,
+    AuditLog,
+    BandwidthCaps,
+    FanoutPolicy,
+    Liveness,
+    MessageRecorder,
+    Outbound,
+    PeerGroupConfig,
+};
+use snarkos_node_sync::ChainPolicy;
 use snarkvm::prelude::{
     block::Block,
+    Program,
     store::helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
     Address,
     Network,
@@ -26,7 +48,7 @@ use snarkvm::prelude::{
 
 use aleo_std::StorageMode;
 use anyhow::Result;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
 pub enum Node<N: Network> {
     /// A validator is a full node, capable of validating blocks.
@@ -35,6 +57,8 @@ pub enum Node<N: Network> {
     Prover(Arc<Prover<N, ConsensusMemory<N>>>),
     /// A client node is a full node, capable of querying with the network.
     Client(Arc<Client<N, ConsensusDB<N>>>),
+    /// An ephemeral development network, which runs the genesis committee in memory, for local development.
+    Ephemeral(Arc<EphemeralDevnet<N>>),
 }
 
 impl<N: Network> Node<N> {
@@ -44,12 +68,14 @@ impl<N: Network> Node<N> {
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
-        account: Account<N>,
+        accounts: AccountRoles<N>,
         trusted_peers: &[SocketAddr],
         trusted_validators: &[SocketAddr],
         genesis: Block<N>,
-        cdn: Option<String>,
+        cdn: Option<snarkos_node_cdn::CdnConfig>,
         storage_mode: StorageMode,
+        validation_concurrency: Option<usize>,
+        bft_activation_height: Option<u32>,
     ) -> Result<Self> {
         Ok(Self::Validator(Arc::new(
             Validator::new(
@@ -57,12 +83,15 @@ impl<N: Network> Node<N> {
                 bft_ip,
                 rest_ip,
                 rest_rps,
-                account,
+                accounts,
                 trusted_peers,
                 trusted_validators,
                 genesis,
                 cdn,
                 storage_mode,
+                validation_concurrency,
+                bft_activation_height,
+                None,
             )
             .await?,
         )))
@@ -87,20 +116,43 @@ impl<N: Network> Node<N> {
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
-        cdn: Option<String>,
+        cdn: Option<snarkos_node_cdn::CdnConfig>,
         storage_mode: StorageMode,
+        bft_activation_height: Option<u32>,
     ) -> Result<Self> {
         Ok(Self::Client(Arc::new(
-            Client::new(node_ip, rest_ip, rest_rps, account, trusted_peers, genesis, cdn, storage_mode).await?,
+            Client::new(
+                node_ip,
+                rest_ip,
+                rest_rps,
+                account,
+                trusted_peers,
+                genesis,
+                cdn,
+                storage_mode,
+                bft_activation_height,
+            )
+            .await?,
         )))
     }
 
+    /// Initializes a new ephemeral development network of the given committee members.
+    pub async fn new_ephemeral(
+        committee: Vec<Account<N>>,
+        genesis: Block<N>,
+        rest_rps: u32,
+        programs: Vec<Program<N>>,
+    ) -> Result<Self> {
+        Ok(Self::Ephemeral(Arc::new(EphemeralDevnet::start(committee, genesis, rest_rps, programs).await?)))
+    }
+
     /// Returns the node type.
     pub fn node_type(&self) -> NodeType {
         match self {
             Self::Validator(validator) => validator.node_type(),
             Self::Prover(prover) => prover.node_type(),
             Self::Client(client) => client.node_type(),
+            Self::Ephemeral(devnet) => devnet.primary().node_type(),
         }
     }
 
@@ -110,6 +162,7 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.private_key(),
             Self::Prover(node) => node.private_key(),
             Self::Client(node) => node.private_key(),
+            Self::Ephemeral(node) => node.primary().private_key(),
         }
     }
 
@@ -119,6 +172,7 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.view_key(),
             Self::Prover(node) => node.view_key(),
             Self::Client(node) => node.view_key(),
+            Self::Ephemeral(node) => node.primary().view_key(),
         }
     }
 
@@ -128,6 +182,7 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.address(),
             Self::Prover(node) => node.address(),
             Self::Client(node) => node.address(),
+            Self::Ephemeral(node) => node.primary().address(),
         }
     }
 
@@ -137,6 +192,341 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.is_dev(),
             Self::Prover(node) => node.is_dev(),
             Self::Client(node) => node.is_dev(),
+            Self::Ephemeral(node) => node.primary().is_dev(),
+        }
+    }
+
+    /// Starts evaluating the given alert rules over the state of the node.
+    /// The free disk space is measured for the given ledger directory.
+    pub fn start_alerts(&self, config: AlertConfig, ledger_dir: PathBuf) {
+        match self {
+            Self::Validator(node) => node.start_alerts(config, ledger_dir),
+            Self::Prover(node) => node.start_alerts(config),
+            Self::Client(node) => node.start_alerts(config, ledger_dir),
+            Self::Ephemeral(node) => node.primary().start_alerts(config, ledger_dir),
+        }
+    }
+
+    /// Starts periodically auditing the derived indexes of a sample of the blocks in the ledger.
+    pub fn start_index_audit(&self, config: IndexAuditConfig) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.start_index_audit(config),
+            Self::Client(node) => node.start_index_audit(config),
+            Self::Ephemeral(node) => node.primary().start_index_audit(config),
+            Self::Prover(_) => anyhow::bail!("Provers do not store the ledger"),
+        }
+        Ok(())
+    }
+
+    /// Starts posting the events of the node to the given webhooks.
+    pub fn start_webhooks(&self, config: WebhookConfig) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.start_webhooks(config),
+            Self::Client(node) => node.start_webhooks(config),
+            Self::Ephemeral(node) => node.primary().start_webhooks(config),
+            Self::Prover(_) => anyhow::bail!("Provers do not post the events of a ledger"),
+        }
+        Ok(())
+    }
+
+    /// Sets the audit log, to which the node records its peer connections.
+    pub fn set_audit_log(&self, audit_log: AuditLog) {
+        match self {
+            Self::Validator(node) => node.router().set_audit_log(audit_log),
+            Self::Prover(node) => node.router().set_audit_log(audit_log),
+            Self::Client(node) => node.router().set_audit_log(audit_log),
+            Self::Ephemeral(node) => node.primary().router().set_audit_log(audit_log),
+        }
+    }
+
+    /// Opens the journal of the admin actions taken against the node over REST, at the given path.
+    pub fn open_action_journal(&self, path: &std::path::Path) -> Result<()> {
+        let rest = match self {
+            Self::Validator(node) => node.rest(),
+            Self::Client(node) => node.rest(),
+            Self::Prover(_) | Self::Ephemeral(_) => {
+                anyhow::bail!("The action journal is only available on validators and clients")
+            }
+        };
+        match rest {
+            Some(rest) => rest.open_action_journal(path),
+            None => anyhow::bail!("The action journal requires the REST server to be enabled"),
+        }
+    }
+
+    /// Starts the rewards tracker of the REST server, which persists its accounting to the given file.
+    pub fn start_rewards_tracker(&self, path: &std::path::Path) -> Result<()> {
+        let rest = match self {
+            Self::Validator(node) => node.rest(),
+            Self::Client(node) => node.rest(),
+            Self::Prover(_) | Self::Ephemeral(_) => {
+                anyhow::bail!("The rewards tracker is only available on validators and clients")
+            }
+        };
+        match rest {
+            Some(rest) => rest.start_rewards_tracker(path),
+            None => anyhow::bail!("The rewards tracker requires the REST server to be enabled"),
+        }
+    }
+
+    /// Sets the log file, ledger directory, and redacted configuration that are included in the debug bundles
+    /// of the REST server, if the node runs one.
+    pub fn set_debug_bundle_config(&self, config: snarkos_node_rest::DebugBundleConfig) {
+        let rest = match self {
+            Self::Validator(node) => node.rest(),
+            Self::Client(node) => node.rest(),
+            Self::Prover(_) | Self::Ephemeral(_) => &None,
+        };
+        if let Some(rest) = rest {
+            rest.set_debug_bundle_config(config);
+        }
+    }
+
+    /// Sets the message recorder, to which the node records the messages of its router.
+    pub fn set_message_recorder(&self, message_recorder: MessageRecorder) {
+        match self {
+            Self::Validator(node) => node.router().set_message_recorder(message_recorder),
+            Self::Prover(node) => node.router().set_message_recorder(message_recorder),
+            Self::Client(node) => node.router().set_message_recorder(message_recorder),
+            Self::Ephemeral(node) => node.primary().router().set_message_recorder(message_recorder),
+        }
+    }
+
+    /// Adds the given peer groups to the router, whose policies are enforced for their peers.
+    pub fn add_peer_groups(&self, config: PeerGroupConfig) -> Result<()> {
+        let router = match self {
+            Self::Validator(node) => node.router(),
+            Self::Prover(node) => node.router(),
+            Self::Client(node) => node.router(),
+            Self::Ephemeral(node) => node.primary().router(),
+        };
+        for group in config.groups {
+            router.add_peer_group(group)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the maximum number of connections that a single node identity may hold.
+    pub fn set_max_connections_per_identity(&self, max_connections: usize) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.router().set_max_connections_per_identity(max_connections),
+            Self::Prover(node) => node.router().set_max_connections_per_identity(max_connections),
+            Self::Client(node) => node.router().set_max_connections_per_identity(max_connections),
+            Self::Ephemeral(node) => node.primary().router().set_max_connections_per_identity(max_connections),
+        }
+    }
+
+    /// Sets the thresholds with which unresponsive peer connections are detected and reaped.
+    pub fn set_liveness(&self, liveness: Liveness) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.router().set_liveness(liveness),
+            Self::Prover(node) => node.router().set_liveness(liveness),
+            Self::Client(node) => node.router().set_liveness(liveness),
+            Self::Ephemeral(node) => node.primary().router().set_liveness(liveness),
+        }
+    }
+
+    /// Sets the caps on the bandwidth of the messages sent to each class of peers.
+    pub fn set_bandwidth_caps(&self, caps: BandwidthCaps) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.router().set_bandwidth_caps(caps),
+            Self::Prover(node) => node.router().set_bandwidth_caps(caps),
+            Self::Client(node) => node.router().set_bandwidth_caps(caps),
+            Self::Ephemeral(node) => node.primary().router().set_bandwidth_caps(caps),
+        }
+    }
+
+    /// Sets the policy with which the peers to which a propagated message is sent are selected.
+    pub fn set_fanout_policy(&self, fanout_policy: FanoutPolicy) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.router().set_fanout_policy(fanout_policy),
+            Self::Prover(node) => node.router().set_fanout_policy(fanout_policy),
+            Self::Client(node) => node.router().set_fanout_policy(fanout_policy),
+            Self::Ephemeral(node) => node.primary().router().set_fanout_policy(fanout_policy),
+        }
+    }
+
+    /// Signs the given public REST URL, and advertises it to the peers.
+    pub fn set_rest_endpoint(&self, url: String) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.router().set_rest_endpoint(url),
+            Self::Prover(node) => node.router().set_rest_endpoint(url),
+            Self::Client(node) => node.router().set_rest_endpoint(url),
+            Self::Ephemeral(node) => node.primary().router().set_rest_endpoint(url),
+        }
+    }
+
+    /// Enables the Dandelion relay, in which the node forwards transactions along a random path of peers,
+    /// before one of them broadcasts the transaction.
+    pub fn enable_dandelion(&self) {
+        match self {
+            Self::Validator(node) => node.router().dandelion().enable(),
+            Self::Prover(node) => node.router().dandelion().enable(),
+            Self::Client(node) => node.router().dandelion().enable(),
+            Self::Ephemeral(node) => node.primary().router().dandelion().enable(),
+        }
+    }
+
+    /// Runs the node as a read-only replica, which serves the ledger without relaying or accepting transmissions.
+    pub fn set_read_only(&self) -> Result<()> {
+        match self {
+            Self::Client(node) => {
+                node.set_read_only();
+                Ok(())
+            }
+            Self::Validator(_) | Self::Prover(_) | Self::Ephemeral(_) => {
+                anyhow::bail!("Only clients can run as read-only replicas")
+            }
+        }
+    }
+
+    /// Tunes the batch size and delay at runtime within the given bounds, rather than using the static values.
+    pub fn set_batch_tuning(&self, bounds: BatchTuningBounds) -> Result<()> {
+        match self {
+            Self::Validator(node) => {
+                node.set_batch_tuning(bounds);
+                Ok(())
+            }
+            Self::Ephemeral(node) => {
+                node.validators().iter().for_each(|validator| validator.set_batch_tuning(bounds));
+                Ok(())
+            }
+            Self::Prover(_) | Self::Client(_) => anyhow::bail!("Only validators propose batches"),
+        }
+    }
+
+    /// Enables the adjustment of the timestamp tolerance of the BFT, for the skew of the local clock
+    /// from the network time.
+    pub fn enable_clock_adjustment(&self) -> Result<()> {
+        match self {
+            Self::Validator(node) => {
+                node.enable_clock_adjustment();
+                Ok(())
+            }
+            Self::Ephemeral(_) => anyhow::bail!("The validators of an ephemeral network share the local clock"),
+            Self::Prover(_) | Self::Client(_) => anyhow::bail!("Only validators check the batch timestamps"),
+        }
+    }
+
+    /// Starts discovering the nodes on the local network over mDNS, which is only available in development mode.
+    pub fn enable_local_discovery(&self) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.enable_local_discovery(),
+            Self::Prover(node) => node.router().enable_local_discovery(None),
+            Self::Client(node) => node.router().enable_local_discovery(None),
+            Self::Ephemeral(_) => anyhow::bail!("The validators of an ephemeral network are already connected"),
+        }
+    }
+
+    /// Runs the validator in hidden mode, in which it only accepts the inbound connections of the committee members
+    /// and the given relays, while it still dials out to any peer.
+    pub fn enable_hidden_mode(&self, relays: Vec<Address<N>>) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.enable_hidden_mode(relays),
+            Self::Prover(_) | Self::Client(_) | Self::Ephemeral(_) => {
+                anyhow::bail!("Only the validators of a network can run in hidden mode")
+            }
+        }
+    }
+
+    /// Signals to the committee that the validator is ready for the given protocol upgrade, which activates at its
+    /// activation height if a quorum of the committee is ready for it.
+    pub fn signal_upgrade(&self, upgrade: ProtocolUpgrade) -> Result<()> {
+        match self {
+            Self::Validator(node) => {
+                node.signal_upgrade(upgrade);
+                Ok(())
+            }
+            Self::Ephemeral(node) => {
+                node.validators().iter().for_each(|validator| validator.signal_upgrade(upgrade));
+                Ok(())
+            }
+            Self::Prover(_) | Self::Client(_) => anyhow::bail!("Only validators signal the protocol upgrades"),
+        }
+    }
+
+    /// Enables fast sync, in which the blocks covered by a checkpoint certificate are applied without re-verifying
+    /// their proofs.
+    pub fn enable_fast_sync(&self) -> Result<()> {
+        match self {
+            Self::Client(node) => {
+                node.enable_fast_sync();
+                Ok(())
+            }
+            Self::Validator(_) | Self::Prover(_) | Self::Ephemeral(_) => anyhow::bail!("Only clients can fast sync"),
+        }
+    }
+
+    /// Sets the chain policy, with which the next block is accepted, and the chains of the peers are compared.
+    ///
+    /// Note: This is intended for experimental networks, as a node with a different policy forks from the network.
+    pub fn set_chain_policy(&self, policy: Arc<dyn ChainPolicy<N>>) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.set_chain_policy(policy),
+            Self::Client(node) => node.set_chain_policy(policy),
+            Self::Ephemeral(node) => {
+                node.validators().iter().for_each(|validator| validator.set_chain_policy(policy.clone()))
+            }
+            Self::Prover(_) => anyhow::bail!("Provers do not accept blocks"),
+        }
+        Ok(())
+    }
+
+    /// Marks the lock file as running, as the node started, and sets it to be released
+    /// when the node shuts down cleanly.
+    pub fn set_lock_file(&self, mut lock_file: LockFile) -> Result<()> {
+        match self {
+            Self::Validator(node) => {
+                lock_file.mark_running()?;
+                node.set_lock_file(lock_file);
+                Ok(())
+            }
+            Self::Prover(_) | Self::Client(_) | Self::Ephemeral(_) => anyhow::bail!("Only validators hold a lock file"),
+        }
+    }
+
+    /// Sets the pipeline that the workers apply to the unconfirmed transactions before batching them.
+    pub fn set_transaction_validator(&self, transaction_validator: TransactionValidator<N>) -> Result<()> {
+        match self {
+            Self::Validator(node) => {
+                node.set_transaction_validator(transaction_validator);
+                Ok(())
+            }
+            Self::Ephemeral(node) => {
+                node.validators()
+                    .iter()
+                    .for_each(|validator| validator.set_transaction_validator(transaction_validator.clone()));
+                Ok(())
+            }
+            Self::Prover(_) | Self::Client(_) => anyhow::bail!("Only validators batch transactions"),
+        }
+    }
+
+    /// Sets the thresholds of the arriving unconfirmed transactions, upon which a batch is proposed
+    /// without waiting out the maximum batch delay. A threshold of `0` is disabled.
+    pub fn set_proposal_trigger(&self, min_transactions: usize, min_priority_fees: u64) -> Result<()> {
+        match self {
+            Self::Validator(node) => {
+                node.set_proposal_trigger(min_transactions, min_priority_fees);
+                Ok(())
+            }
+            Self::Ephemeral(node) => {
+                node.validators()
+                    .iter()
+                    .for_each(|validator| validator.set_proposal_trigger(min_transactions, min_priority_fees));
+                Ok(())
+            }
+            Self::Prover(_) | Self::Client(_) => anyhow::bail!("Only validators propose batches"),
+        }
+    }
+
+    /// Starts the faucet with the given configuration.
+    #[cfg(feature = "faucet")]
+    pub fn start_faucet(&self, config: snarkos_node_rest::FaucetConfig) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.start_faucet(config),
+            Self::Ephemeral(node) => node.primary().start_faucet(config),
+            Self::Prover(_) | Self::Client(_) => anyhow::bail!("The faucet is only available on validators"),
         }
     }
 }
@@ -14,9 +14,10 @@
 
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{traits::NodeInterface, AlertConfig, AlertSample};
 use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::ProverLedgerService;
+use snarkos_node_clock as clock;
 use snarkos_node_router::{
     messages::{Message, NodeType, UnconfirmedSolution},
     Heartbeat,
@@ -53,6 +54,7 @@ use std::{
         Arc,
     },
 };
+use time::OffsetDateTime;
 use tokio::task::JoinHandle;
 
 /// A prover is a light node, capable of producing proofs for consensus.
@@ -102,11 +104,14 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         // Initialize the sync module.
         let sync = BlockSync::new(BlockSyncMode::Router, ledger_service.clone());
 
+        // Load the persistent node ID.
+        let node_id = crate::load_node_id(&account, &storage_mode)?;
         // Initialize the node router.
         let router = Router::new(
             node_ip,
             NodeType::Prover,
             account,
+            node_id,
             trusted_peers,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
             matches!(storage_mode, StorageMode::Development(_)),
@@ -141,6 +146,26 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         // Return the node.
         Ok(node)
     }
+
+    /// Starts evaluating the given alert rules over the state of the node.
+    pub fn start_alerts(&self, config: AlertConfig) {
+        let node = self.clone();
+        self.handles.lock().push(crate::start_alerts(config, move || {
+            let now = OffsetDateTime::now_utc().unix_timestamp();
+            AlertSample {
+                seconds_since_latest_block: node
+                    .latest_block_header
+                    .read()
+                    .as_ref()
+                    .map(|header| now.saturating_sub(header.timestamp()).max(0) as u64),
+                connected_peers: node.router.number_of_connected_peers(),
+                connected_stake: None,
+                proposals_halted: false,
+                disk_free_percent: None,
+                block_hash_conflicts: vec![],
+            }
+        }));
+    }
 }
 
 #[async_trait]
@@ -153,6 +178,9 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Prover<N, C> {
         trace!("Shutting down the coinbase puzzle...");
         self.shutdown.store(true, Ordering::Relaxed);
 
+        // Notify the connected peers.
+        self.send_shutdown();
+
         // Abort the tasks.
         trace!("Shutting down the prover...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
@@ -181,14 +209,14 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             // If the node is not connected to any peers, then skip this iteration.
             if self.router.number_of_connected_peers() == 0 {
                 trace!("Skipping an iteration of the coinbase puzzle (no connected peers)");
-                tokio::time::sleep(Duration::from_secs(N::ANCHOR_TIME as u64)).await;
+                clock::sleep(Duration::from_secs(N::ANCHOR_TIME as u64)).await;
                 continue;
             }
 
             // If the number of instances of the coinbase puzzle exceeds the maximum, then skip this iteration.
             if self.num_puzzle_instances() > self.max_puzzle_instances {
                 // Sleep for a brief period of time.
-                tokio::time::sleep(Duration::from_millis(500)).await;
+                clock::sleep(Duration::from_millis(500)).await;
                 continue;
             }
 
@@ -218,7 +246,7 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
                 }
             } else {
                 // Otherwise, sleep for a brief period of time, to await for puzzle state.
-                tokio::time::sleep(Duration::from_secs(1)).await;
+                clock::sleep(Duration::from_secs(1)).await;
             }
 
             // If the Ctrl-C handler registered the signal, stop the prover.
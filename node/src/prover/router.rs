@@ -14,17 +14,20 @@
 
 use super::*;
 
-use snarkos_node_router::messages::{
-    BlockRequest,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    PuzzleRequest,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    disconnect_reason,
+    messages::{
+        BlockRequest,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        Ping,
+        Pong,
+        PuzzleRequest,
+        UnconfirmedTransaction,
+    },
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{Connection, ConnectionSide, Tcp, TokenBucket};
 use snarkvm::prelude::{block::Transaction, Network};
 
 use std::{io, net::SocketAddr};
@@ -45,7 +48,8 @@ impl<N: Network, C: ConsensusStorage<N>> Handshake for Prover<N, C> {
         let conn_side = connection.side();
         let stream = self.borrow_stream(&mut connection);
         let genesis_header = *self.genesis.header();
-        self.router.handshake(peer_addr, stream, conn_side, genesis_header).await?;
+        let latest_height = self.latest_block_header.read().as_ref().map_or(0, |header| header.height());
+        self.router.handshake(peer_addr, stream, conn_side, genesis_header, latest_height).await?;
 
         Ok(connection)
     }
@@ -59,6 +63,8 @@ where
     async fn on_connect(&self, peer_addr: SocketAddr) {
         // Resolve the peer address to the listener address.
         let Some(peer_ip) = self.router.resolve_to_listener(&peer_addr) else { return };
+        // Close the older connections of the same node identity, if it holds too many.
+        self.disconnect_duplicate_connections(peer_ip);
         // Send the first `Ping` message to the peer.
         self.send_ping(peer_ip, None);
     }
@@ -85,6 +91,11 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Prover<N, C> {
     fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the token bucket that caps the bandwidth to the given peer address, if any.
+    fn bandwidth_limiter(&self, addr: SocketAddr) -> Option<Arc<TokenBucket>> {
+        self.router.bandwidth_limiter(addr)
+    }
 }
 
 #[async_trait]
@@ -94,8 +105,10 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Prover<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        let router = self.router().clone();
+        MessageCodec::default()
+            .with_malformed_frame_handler(move |reason| router.record_malformed_frame(peer_addr, reason))
     }
 
     /// Processes a message received from the network.
@@ -104,9 +117,7 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Prover<N, C> {
         if let Err(error) = self.inbound(peer_addr, message).await {
             if let Some(peer_ip) = self.router().resolve_to_listener(&peer_addr) {
                 warn!("Disconnecting from '{peer_addr}' - {error}");
-                Outbound::send(self, peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
-                // Disconnect from this peer.
-                self.router().disconnect(peer_ip);
+                self.send_disconnect(peer_ip, disconnect_reason(&error));
             }
         }
         Ok(())
@@ -166,7 +177,7 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Prover<N, C> {
         }
 
         // Send a `Pong` message to the peer.
-        Outbound::send(self, peer_ip, Message::Pong(Pong { is_fork: Some(false) }));
+        Outbound::send(self, peer_ip, Message::Pong(Pong::new(Some(false), message.timestamp)));
         true
     }
 
@@ -259,4 +270,9 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Prover<N, C> {
     ) -> bool {
         true
     }
+
+    /// Refuses to continue the stem, as the prover does not relay transactions.
+    async fn is_valid_stem_transaction(&self, _transaction: Transaction<N>) -> bool {
+        false
+    }
 }
@@ -0,0 +1,205 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::audit_indexes;
+use snarkos_node_bft::ledger_service::CoreLedgerService;
+use snarkvm::prelude::{store::ConsensusStorage, Ledger, Network};
+
+use aleo_std::StorageMode;
+use anyhow::{bail, ensure, Result};
+use fs2::FileExt;
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+/// The default number of blocks, from the tip of the ledger, that are verified in safe mode.
+pub const DEFAULT_SAFE_MODE_DEPTH: u32 = 100;
+
+/// The contents of the lock file while the node is running.
+const RUNNING_MARKER: &str = "running";
+
+/// A lock file that holds an exclusive OS lock on the ledger while the node is running.
+///
+/// The lock file is marked as running once the node started, and is only removed when the node shuts down cleanly.
+/// If the lock file is still marked as running when the node starts, the previous run crashed, was killed,
+/// or timed out while flushing its state, and the node should start in safe mode. A run that failed to start
+/// never marks the lock file, so it does not cause the next start to enter safe mode.
+#[derive(Debug)]
+pub struct LockFile {
+    /// The path to the lock file.
+    path: PathBuf,
+    /// The lock file, which holds the OS lock until it is closed.
+    file: File,
+}
+
+impl LockFile {
+    /// Returns the path to the lock file of the ledger in the given storage mode.
+    ///
+    /// The lock file is stored next to the ledger directory, so that `snarkos clean` leaves it untouched.
+    pub fn path<N: Network>(storage_mode: &StorageMode) -> PathBuf {
+        let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+        PathBuf::from(format!("{}.lock", ledger_dir.display()))
+    }
+
+    /// Acquires the lock file at the given path, and returns it along with `true`
+    /// if the lock file is marked as running, i.e. if the previous run did not shut down cleanly.
+    ///
+    /// Fails if another process holds the lock file, i.e. if another node runs on the same ledger.
+    pub fn acquire(path: PathBuf) -> Result<(Self, bool)> {
+        // Ensure the parent directory exists.
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)?;
+        if file.try_lock_exclusive().is_err() {
+            bail!("The ledger is in use by another process (locked by '{}')", path.display())
+        }
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        let is_unclean = contents.starts_with(RUNNING_MARKER);
+        Ok((Self { path, file }, is_unclean))
+    }
+
+    /// Marks the lock file as running, along with the process ID of the node, so that the lock file
+    /// causes the next start to enter safe mode, unless it is released by a clean shutdown.
+    pub fn mark_running(&mut self) -> Result<()> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        write!(self.file, "{RUNNING_MARKER} {}", std::process::id())?;
+        Ok(self.file.sync_all()?)
+    }
+
+    /// Releases the lock file, which marks the shutdown as clean.
+    ///
+    /// Note: The lock file is not removed on drop, so that a panic is treated as an unclean shutdown.
+    /// The OS lock is released on drop, or when the process exits.
+    pub fn release(self) -> Result<()> {
+        Ok(std::fs::remove_file(&self.path)?)
+    }
+}
+
+/// The outcome of the recovery of the latest blocks in safe mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SafeModeReport {
+    /// The number of verified blocks.
+    pub num_blocks: u32,
+    /// The number of index entries that were rebuilt from the blocks.
+    pub num_repaired: usize,
+}
+
+/// Verifies the latest `depth` blocks of the ledger, and rebuilds the indexes that are derived from them.
+///
+/// Each block is checked to be linked to its predecessor, to match the height and hash indexes, and to match
+/// its transactions root. These are stored by the block store, so an inconsistency is an error that requires
+/// a resync. The transition and program indexes of the accepted transactions are then rebuilt from the blocks
+/// where they are inconsistent, and the transaction indexes are checked to resolve back to their blocks.
+pub fn recover_latest_blocks<N: Network, C: ConsensusStorage<N>>(
+    ledger: Ledger<N, C>,
+    depth: u32,
+) -> Result<SafeModeReport> {
+    let latest_height = ledger.latest_height();
+    let start_height = latest_height.saturating_sub(depth.saturating_sub(1));
+
+    let mut previous_hash = match start_height {
+        0 => None,
+        height => Some(ledger.get_hash(height - 1)?),
+    };
+    for height in start_height..=latest_height {
+        let block = ledger.get_block(height)?;
+        let hash = block.hash();
+
+        // Ensure the block is indexed under its height and hash.
+        ensure!(block.height() == height, "Block {height} is stored with height {}", block.height());
+        ensure!(ledger.get_hash(height)? == hash, "Block {height} does not match the block hash index");
+        ensure!(ledger.get_height(&hash)? == height, "Block {height} does not match the block height index");
+        // Ensure the block is linked to its predecessor.
+        if let Some(previous_hash) = previous_hash {
+            ensure!(block.previous_hash() == previous_hash, "Block {height} is not linked to block {}", height - 1);
+        }
+        // Ensure the block matches its transactions root.
+        ensure!(
+            block.header().transactions_root() == block.transactions().to_transactions_root()?,
+            "Block {height} does not match its transactions root"
+        );
+        previous_hash = Some(hash);
+    }
+
+    // Rebuild the indexes of the transactions from the blocks.
+    // Note: The ledger is not advanced in safe mode, so the advance lock is uncontended.
+    let ledger_service = CoreLedgerService::new(ledger, Default::default());
+    let report = audit_indexes(&ledger_service, start_height..=latest_height, true)?;
+    if let Some(inconsistency) = report.inconsistencies.iter().find(|inconsistency| !inconsistency.is_repaired) {
+        bail!("Failed to rebuild an inconsistent index entry - {inconsistency}")
+    }
+    Ok(SafeModeReport { num_blocks: latest_height - start_height + 1, num_repaired: report.inconsistencies.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        PrivateKey,
+        TestRng,
+        Testnet3,
+        VM,
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_lock_file() {
+        let path = std::env::temp_dir().join(format!("snarkos-lock-file-{}", rand::random::<u64>()));
+
+        // Ensure the first run is considered clean.
+        let (lock_file, is_unclean) = LockFile::acquire(path.clone()).unwrap();
+        assert!(!is_unclean);
+        assert!(path.exists());
+        // Ensure the lock file cannot be acquired while it is held.
+        assert!(LockFile::acquire(path.clone()).is_err());
+        // Ensure a run that failed to start is considered clean.
+        drop(lock_file);
+        let (mut lock_file, is_unclean) = LockFile::acquire(path.clone()).unwrap();
+        assert!(!is_unclean);
+        // Ensure a run that started, and did not release the lock file, is considered unclean.
+        lock_file.mark_running().unwrap();
+        drop(lock_file);
+        let (lock_file, is_unclean) = LockFile::acquire(path.clone()).unwrap();
+        assert!(is_unclean);
+        // Ensure a run that released the lock file is considered clean.
+        lock_file.release().unwrap();
+        assert!(!path.exists());
+        let (lock_file, is_unclean) = LockFile::acquire(path.clone()).unwrap();
+        assert!(!is_unclean);
+        lock_file.release().unwrap();
+    }
+
+    #[test]
+    fn test_recover_latest_blocks() {
+        let rng = &mut TestRng::default();
+        // Initialize a ledger with the genesis block.
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None).unwrap();
+        let genesis = VM::from(store).unwrap().genesis_beacon(&private_key, rng).unwrap();
+        let ledger =
+            Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, StorageMode::Production).unwrap();
+
+        // Ensure the genesis block is verified, regardless of the depth, and its indexes need no rebuild.
+        let report = recover_latest_blocks(ledger.clone(), DEFAULT_SAFE_MODE_DEPTH).unwrap();
+        assert_eq!(report, SafeModeReport { num_blocks: 1, num_repaired: 0 });
+        assert_eq!(recover_latest_blocks(ledger, 0).unwrap().num_blocks, 1);
+    }
+}
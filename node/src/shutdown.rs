@@ -0,0 +1,134 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::{fmt, future::Future, pin::Pin};
+use std::time::{Duration, Instant};
+
+/// The timeout for a shutdown stage.
+pub const SHUTDOWN_STAGE_TIMEOUT: Duration = Duration::from_secs(5);
+/// The timeout for the shutdown stage that waits for pending ledger writes.
+pub const LEDGER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The outcome of a shutdown stage.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StageOutcome {
+    /// The stage completed within its timeout.
+    Completed,
+    /// The stage did not complete within its timeout.
+    TimedOut,
+}
+
+/// The report of a shutdown stage.
+#[derive(Clone, Debug)]
+pub struct StageReport {
+    /// The name of the stage.
+    pub name: &'static str,
+    /// The outcome of the stage.
+    pub outcome: StageOutcome,
+    /// The time spent on the stage.
+    pub elapsed: Duration,
+}
+
+/// The report of a shutdown sequence.
+#[derive(Clone, Debug, Default)]
+pub struct ShutdownReport {
+    /// The reports of the stages, in the order they ran.
+    pub stages: Vec<StageReport>,
+}
+
+impl ShutdownReport {
+    /// Returns `true` if every stage completed within its timeout.
+    pub fn is_clean(&self) -> bool {
+        self.stages.iter().all(|stage| stage.outcome == StageOutcome::Completed)
+    }
+}
+
+impl fmt::Display for ShutdownReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stages = self.stages.iter().map(|stage| match stage.outcome {
+            StageOutcome::Completed => format!("{} ({}ms)", stage.name, stage.elapsed.as_millis()),
+            StageOutcome::TimedOut => format!("{} (timed out after {}ms)", stage.name, stage.elapsed.as_millis()),
+        });
+        write!(f, "{}", stages.collect::<Vec<_>>().join(", "))
+    }
+}
+
+/// A shutdown stage, with its name, timeout, and future.
+type Stage<'a> = (&'static str, Duration, Pin<Box<dyn Future<Output = ()> + Send + 'a>>);
+
+/// A coordinator that shuts down the subsystems of a node in dependency order.
+///
+/// Each stage runs only after the previous stage has completed or timed out,
+/// so that a stalled subsystem delays, but does not prevent, the remaining stages.
+#[derive(Default)]
+pub struct ShutdownCoordinator<'a> {
+    /// The stages, in the order they are run.
+    stages: Vec<Stage<'a>>,
+}
+
+impl<'a> ShutdownCoordinator<'a> {
+    /// Appends a stage with the given name and timeout to the shutdown sequence.
+    pub fn stage<F: Future<Output = ()> + Send + 'a>(
+        mut self,
+        name: &'static str,
+        timeout: Duration,
+        future: F,
+    ) -> Self {
+        self.stages.push((name, timeout, Box::pin(future)));
+        self
+    }
+
+    /// Runs the stages in order, and returns the shutdown report.
+    pub async fn run(self) -> ShutdownReport {
+        let mut report = ShutdownReport::default();
+        for (name, timeout, future) in self.stages {
+            trace!("Shutting down the {name}...");
+            let start = Instant::now();
+            let outcome = match tokio::time::timeout(timeout, future).await {
+                Ok(()) => StageOutcome::Completed,
+                Err(_) => {
+                    warn!("Timed out shutting down the {name} after {}s", timeout.as_secs());
+                    StageOutcome::TimedOut
+                }
+            };
+            report.stages.push(StageReport { name, outcome, elapsed: start.elapsed() });
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_shutdown_coordinator() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let (order_1, order_2) = (order.clone(), order.clone());
+
+        let report = ShutdownCoordinator::default()
+            .stage("first", SHUTDOWN_STAGE_TIMEOUT, async move { order_1.lock().unwrap().push(1) })
+            .stage("stalled", Duration::from_millis(10), std::future::pending())
+            .stage("last", SHUTDOWN_STAGE_TIMEOUT, async move { order_2.lock().unwrap().push(2) })
+            .run()
+            .await;
+
+        // Ensure the stages ran in order, and the stalled stage did not prevent the last stage.
+        assert_eq!(*order.lock().unwrap(), vec![1, 2]);
+        let outcomes = report.stages.iter().map(|stage| stage.outcome).collect::<Vec<_>>();
+        assert_eq!(outcomes, vec![StageOutcome::Completed, StageOutcome::TimedOut, StageOutcome::Completed]);
+        assert!(!report.is_clean());
+    }
+}
@@ -96,6 +96,9 @@ pub trait NodeInterface<N: Network>: Routing<N> {
                         // Otherwise, if the node is not yet initialized, then set the shutdown flag directly.
                         None => shutdown_flag.store(true, Ordering::Relaxed),
                     }
+                    // Push the final metrics, if the node pushes them to a push gateway.
+                    #[cfg(feature = "metrics")]
+                    metrics::flush_metrics().await;
 
                     // A best-effort attempt to let any ongoing activity conclude.
                     tokio::time::sleep(Duration::from_secs(3)).await;
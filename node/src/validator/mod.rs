@@ -14,20 +14,40 @@
 
 mod router;
 
-use crate::traits::NodeInterface;
-use snarkos_account::Account;
-use snarkos_node_bft::{helpers::init_primary_channels, ledger_service::CoreLedgerService};
+use crate::{
+    traits::NodeInterface,
+    AlertConfig,
+    AlertSample,
+    IndexAuditConfig,
+    LockFile,
+    ShutdownCoordinator,
+    WebhookConfig,
+    WebhookEvent,
+    WebhookTasks,
+    LEDGER_SHUTDOWN_TIMEOUT,
+    SHUTDOWN_STAGE_TIMEOUT,
+    WEBHOOK_SHUTDOWN_TIMEOUT,
+};
+use snarkos_account::AccountRoles;
+use snarkos_node_bft::{
+    helpers::{init_primary_channels, BatchTuningBounds, ProposalHold, ProtocolUpgrade, TransactionValidator},
+    ledger_service::{CommitteeSource, CoreLedgerService, LedgerService},
+};
+use snarkos_node_bus::EventBus;
+use snarkos_node_cdn::{CdnConfig, CdnSyncProgress};
+use snarkos_node_clock as clock;
 use snarkos_node_consensus::Consensus;
-use snarkos_node_rest::Rest;
+use snarkos_node_rest::{CdnSyncServer, Rest};
 use snarkos_node_router::{
     messages::{NodeType, PuzzleResponse, UnconfirmedSolution, UnconfirmedTransaction},
     Heartbeat,
+    HiddenMode,
     Inbound,
     Outbound,
     Router,
     Routing,
 };
-use snarkos_node_sync::{BlockSync, BlockSyncMode};
+use snarkos_node_sync::{BlockSync, BlockSyncMode, ChainPolicy, MAX_BLOCKS_BEHIND};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
     P2P,
@@ -36,8 +56,11 @@ use snarkvm::prelude::{
     block::{Block, Header},
     coinbase::ProverSolution,
     store::ConsensusStorage,
+    Address,
     Ledger,
     Network,
+    PrivateKey,
+    ViewKey,
 };
 
 use aleo_std::StorageMode;
@@ -45,10 +68,13 @@ use anyhow::Result;
 use core::future::Future;
 use parking_lot::Mutex;
 use std::{
+    collections::HashSet,
     net::SocketAddr,
+    path::PathBuf,
     sync::{atomic::AtomicBool, Arc},
     time::Duration,
 };
+use time::OffsetDateTime;
 use tokio::task::JoinHandle;
 
 /// A validator is a full node, capable of validating blocks.
@@ -56,6 +82,8 @@ use tokio::task::JoinHandle;
 pub struct Validator<N: Network, C: ConsensusStorage<N>> {
     /// The ledger of the node.
     ledger: Ledger<N, C>,
+    /// The ledger service of the node.
+    ledger_service: Arc<CoreLedgerService<N, C>>,
     /// The consensus module of the node.
     consensus: Consensus<N>,
     /// The router of the node.
@@ -64,10 +92,18 @@ pub struct Validator<N: Network, C: ConsensusStorage<N>> {
     rest: Option<Rest<N, C, Self>>,
     /// The sync module.
     sync: BlockSync<N>,
+    /// The event bus between the subsystems of the node.
+    event_bus: EventBus<N>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// The tasks that post the events of the node to the webhooks, which are stopped after the other tasks.
+    webhooks: Arc<Mutex<Option<WebhookTasks>>>,
     /// The shutdown signal.
     shutdown: Arc<AtomicBool>,
+    /// The temporary directory of an ephemeral node, which is removed on shutdown.
+    ephemeral_dir: Option<PathBuf>,
+    /// The lock file, which is released when the node shuts down cleanly.
+    lock_file: Arc<Mutex<Option<LockFile>>>,
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
@@ -77,12 +113,15 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
-        account: Account<N>,
+        accounts: AccountRoles<N>,
         trusted_peers: &[SocketAddr],
         trusted_validators: &[SocketAddr],
         genesis: Block<N>,
-        cdn: Option<String>,
+        cdn: Option<CdnConfig>,
         storage_mode: StorageMode,
+        validation_concurrency: Option<usize>,
+        bft_activation_height: Option<u32>,
+        ephemeral_dir: Option<PathBuf>,
     ) -> Result<Self> {
         // Prepare the shutdown flag.
         let shutdown: Arc<AtomicBool> = Default::default();
@@ -90,65 +129,151 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         // Initialize the signal handler.
         let signal_node = Self::handle_signals(shutdown.clone());
 
+        // Ensure the block-signing account is used by the BFT, and the peer-to-peer identity by the router.
+        let (account, p2p_account) = (accounts.signing().clone(), accounts.p2p().clone());
+        if accounts.is_p2p_separated() {
+            info!("Using a separate peer-to-peer identity ({})", p2p_account.address());
+        }
+
         // Initialize the ledger.
         let ledger = Ledger::load(genesis, storage_mode.clone())?;
         // TODO: Remove me after Phase 3.
         let ledger = crate::phase_3_reset(ledger, storage_mode.clone())?;
         // Initialize the CDN.
-        if let Some(base_url) = cdn {
-            // Sync the ledger with the CDN.
+        let mut cdn_sync = None;
+        if let Some(cdn) = cdn {
+            let cdn_progress = CdnSyncProgress::default();
+            // Report the progress of the sync in the node status while it runs, if the REST server is enabled.
+            if let Some(rest_ip) = rest_ip {
+                let (node_type, height) = (NodeType::Validator, ledger.latest_height());
+                cdn_sync = Some(CdnSyncServer::start(rest_ip, rest_rps, node_type, height, cdn_progress.clone())?);
+            }
+            // Sync the ledger with the CDNs.
             if let Err((_, error)) =
-                snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone(), shutdown.clone()).await
+                snarkos_node_cdn::sync_ledger_with_cdn(&cdn, ledger.clone(), cdn_progress, shutdown.clone())
+                    .await
             {
                 crate::log_clean_error(&storage_mode);
                 return Err(error);
             }
         }
 
+        // Initialize the event bus.
+        let event_bus = EventBus::default();
         // Initialize the ledger service.
         let ledger_service = Arc::new(CoreLedgerService::new(ledger.clone(), shutdown.clone()));
+        ledger_service.set_event_bus(event_bus.clone());
         // Initialize the sync module.
         let sync = BlockSync::new(BlockSyncMode::Gateway, ledger_service.clone());
 
         // Initialize the consensus.
-        let mut consensus =
-            Consensus::new(account.clone(), ledger_service, bft_ip, trusted_validators, storage_mode.clone())?;
+        let mut consensus = match (&ephemeral_dir, &storage_mode) {
+            // An ephemeral node keeps the Narwhal transmissions in memory.
+            (Some(dir), StorageMode::Development(dev)) => Consensus::new_ephemeral(
+                account.clone(),
+                ledger_service.clone(),
+                bft_ip,
+                trusted_validators,
+                *dev,
+                dir,
+            )?,
+            (Some(_), _) => anyhow::bail!("An ephemeral validator must run in development mode"),
+            (None, _) => Consensus::new(
+                account.clone(),
+                ledger_service.clone(),
+                bft_ip,
+                trusted_validators,
+                storage_mode.clone(),
+            )?,
+        };
+        // Set the maximum number of concurrent transmission checks, if specified.
+        if let Some(validation_concurrency) = validation_concurrency {
+            consensus.bft().primary().set_max_concurrent_checks(validation_concurrency)?;
+        }
+        consensus.set_event_bus(event_bus.clone());
+        // Set the height of the last beacon-produced block, after which the BFT produces the blocks, if specified.
+        if let Some(height) = bft_activation_height {
+            ledger_service.set_bft_activation_height(height);
+            // Refrain from proposing batches until the ledger reaches the last beacon-produced block.
+            if ledger.latest_height() < height {
+                consensus.bft().primary().hold_proposals(ProposalHold::AwaitingActivation);
+            }
+        }
+        // Refrain from proposing batches until the ledger catches up with the network.
+        consensus.bft().primary().hold_proposals(ProposalHold::CatchingUp);
         // Initialize the primary channels.
         let (primary_sender, primary_receiver) = init_primary_channels::<N>();
         // Start the consensus.
         consensus.run(primary_sender, primary_receiver).await?;
 
+        // Load the persistent node ID, or keep it in the temporary directory of an ephemeral node.
+        let node_id = match &ephemeral_dir {
+            Some(dir) => snarkos_node_router::load_or_create_node_id(&p2p_account, &dir.join("node-id"))?,
+            None => crate::load_node_id(&p2p_account, &storage_mode)?,
+        };
         // Initialize the node router.
         let router = Router::new(
             node_ip,
             NodeType::Validator,
-            account,
+            p2p_account,
+            node_id,
             trusted_peers,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
+        router.set_event_bus(event_bus.clone());
+        // Bind the peer-to-peer identity to the block-signing account, so that the peers recognize the validator.
+        router.bind_consensus_account(&account)?;
+        // Record the endpoints of the committee members in the validator directory.
+        let directory_ledger = ledger.clone();
+        router.enable_validator_directory(move |address| {
+            directory_ledger.latest_committee().map_or(false, |committee| committee.is_committee_member(*address))
+        })?;
+        // Estimate the network time from the clocks of the committee members.
+        let committee_ledger = ledger.clone();
+        router.enable_time_sync(move |address| {
+            committee_ledger.latest_committee().map_or(false, |committee| committee.is_committee_member(*address))
+        })?;
 
         // Initialize the node.
         let mut node = Self {
             ledger: ledger.clone(),
+            ledger_service,
             consensus: consensus.clone(),
             router,
             rest: None,
             sync,
+            event_bus,
             handles: Default::default(),
+            webhooks: Default::default(),
             shutdown,
+            ephemeral_dir,
+            lock_file: Default::default(),
         };
-        // Initialize the transaction pool.
-        node.initialize_transaction_pool(storage_mode)?;
+        // Initialize the transaction pool, unless the node is ephemeral and its transactions come from the user.
+        if node.ephemeral_dir.is_none() {
+            node.initialize_transaction_pool(storage_mode)?;
+        }
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest =
-                Some(Rest::start(rest_ip, rest_rps, Some(consensus), ledger.clone(), Arc::new(node.clone())).await?);
+            let (routing, event_bus) = (Arc::new(node.clone()), node.event_bus.clone());
+            let rest =
+                Rest::start(rest_ip, rest_rps, Some(consensus), ledger.clone(), routing, event_bus, cdn_sync).await?;
+            node.rest = Some(rest);
         }
         // Initialize the routing.
         node.initialize_routing().await;
+        // Initialize the catch-up, which holds off proposing batches while the ledger is behind the network.
+        node.initialize_catch_up();
+        // Initialize the BFT activation, if the BFT takes over block production from the beacon.
+        if let Some(height) = bft_activation_height {
+            node.initialize_bft_activation(height);
+        }
+        // Cache the blocks as they are added to the ledger.
+        let block_cache_loop = crate::start_block_cache_loop(node.router.clone(), ledger.clone(), &node.event_bus);
+        node.handles.lock().push(block_cache_loop);
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
         // Pass the node to the signal handler.
@@ -162,10 +287,199 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         &self.ledger
     }
 
+    /// Returns the consensus module.
+    pub fn consensus(&self) -> &Consensus<N> {
+        &self.consensus
+    }
+
+    /// Returns the event bus.
+    pub const fn event_bus(&self) -> &EventBus<N> {
+        &self.event_bus
+    }
+
     /// Returns the REST server.
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Starts periodically auditing the derived indexes of a sample of the blocks in the ledger.
+    pub fn start_index_audit(&self, config: IndexAuditConfig) {
+        self.handles.lock().push(crate::start_index_audit(self.ledger_service.clone(), config));
+    }
+
+    /// Starts evaluating the given alert rules over the state of the node.
+    pub fn start_alerts(&self, config: AlertConfig, ledger_dir: PathBuf) {
+        let node = self.clone();
+        self.handles.lock().push(crate::start_alerts(config, move || {
+            let now = OffsetDateTime::now_utc().unix_timestamp();
+            AlertSample {
+                seconds_since_latest_block: Some(now.saturating_sub(node.ledger.latest_timestamp()).max(0) as u64),
+                connected_peers: node.router.number_of_connected_peers()
+                    + node.consensus.bft().primary().gateway().number_of_connected_peers(),
+                connected_stake: node.connected_stake(),
+                proposals_halted: node.consensus.bft().primary().is_holding_proposals(ProposalHold::QuorumLost),
+                disk_free_percent: crate::disk_free_percent(&ledger_dir),
+                block_hash_conflicts: node
+                    .consensus
+                    .bft()
+                    .primary()
+                    .sync()
+                    .block_hash_conflicts()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            }
+        }));
+    }
+
+    /// Starts posting the events of the node to the given webhooks: the new blocks, the start and stop of the BFT,
+    /// the forks reported by the committee members, and the loss and recovery of the quorum of the connected stake.
+    pub fn start_webhooks(&self, config: WebhookConfig) {
+        /// The interval in seconds in between the checks for the forks and the quorum.
+        const CHECK_INTERVAL_IN_SECS: u64 = 10;
+
+        let (sender, webhooks) = WebhookTasks::start(config, &self.event_bus);
+        *self.webhooks.lock() = Some(webhooks);
+
+        let node = self.clone();
+        self.spawn(async move {
+            let forks = node.consensus.bft().primary().sync().forks().clone();
+            let mut since = clock::unix_timestamp();
+            let mut notified = HashSet::new();
+            let mut has_quorum = true;
+            loop {
+                clock::sleep(Duration::from_secs(CHECK_INTERVAL_IN_SECS)).await;
+                // Post the block hash conflicts reported since the last check.
+                // Note: The conflicts reported within the second of the check are queried again, so they are
+                // tracked until then, to be posted once.
+                let now = clock::unix_timestamp();
+                for (reported_at, conflict) in forks.since(since) {
+                    if notified.insert((reported_at, conflict.address, conflict.height)) {
+                        sender.send(WebhookEvent::ForkDetected {
+                            height: conflict.height,
+                            peer_ip: conflict.peer_ip,
+                            validator: conflict.address.to_string(),
+                            expected_hash: conflict.expected_hash.to_string(),
+                            reported_hash: conflict.reported_hash.to_string(),
+                        });
+                    }
+                }
+                since = now;
+                notified.retain(|(reported_at, _, _)| *reported_at >= since);
+                // Post the loss or recovery of the quorum.
+                if let Some((connected_stake, quorum_threshold)) = node.connected_stake() {
+                    let is_quorum = connected_stake >= quorum_threshold;
+                    if is_quorum != has_quorum {
+                        has_quorum = is_quorum;
+                        sender.send(match is_quorum {
+                            true => WebhookEvent::QuorumRestored { connected_stake, quorum_threshold },
+                            false => WebhookEvent::QuorumLost { connected_stake, quorum_threshold },
+                        });
+                    }
+                }
+            }
+        });
+    }
+
+    /// Returns the stake of the connected committee members (including this node) and the quorum threshold.
+    fn connected_stake(&self) -> Option<(u64, u64)> {
+        let committee = self.ledger.latest_committee().ok()?;
+        let mut addresses = self.consensus.bft().primary().gateway().connected_addresses();
+        addresses.insert(self.address());
+        let stake = addresses.into_iter().map(|address| committee.get_stake(address)).sum();
+        Some((stake, committee.quorum_threshold()))
+    }
+
+    /// Starts the faucet on the REST server, funding the requests from the account of the node.
+    #[cfg(feature = "faucet")]
+    pub fn start_faucet(&self, config: snarkos_node_rest::FaucetConfig) -> Result<()> {
+        match &self.rest {
+            Some(rest) => rest.start_faucet(*self.private_key(), config),
+            None => anyhow::bail!("The faucet requires the REST server to be enabled"),
+        }
+    }
+
+    /// Sets the pipeline that the workers apply to the unconfirmed transactions before batching them.
+    pub fn set_transaction_validator(&self, transaction_validator: TransactionValidator<N>) {
+        self.consensus.bft().primary().set_transaction_validator(transaction_validator);
+    }
+
+    /// Sets the thresholds of the arriving unconfirmed transactions, upon which a batch is proposed
+    /// without waiting out the maximum batch delay. A threshold of `0` is disabled.
+    pub fn set_proposal_trigger(&self, min_transactions: usize, min_priority_fees: u64) {
+        self.consensus.bft().primary().set_proposal_trigger(min_transactions, min_priority_fees);
+    }
+
+    /// Tunes the batch size and delay at runtime within the given bounds, rather than using the static values.
+    pub fn set_batch_tuning(&self, bounds: BatchTuningBounds) {
+        self.consensus.bft().primary().set_batch_tuning(bounds);
+    }
+
+    /// Signals to the committee that this validator is ready for the given protocol upgrade.
+    pub fn signal_upgrade(&self, upgrade: ProtocolUpgrade) {
+        self.consensus.bft().primary().signal_upgrade(upgrade);
+    }
+
+    /// Sets the chain policy, with which the next block is accepted, and the chains of the peers are compared.
+    pub fn set_chain_policy(&self, policy: Arc<dyn ChainPolicy<N>>) {
+        self.consensus.bft().primary().sync().set_chain_policy(policy.clone());
+        self.sync.set_chain_policy(policy);
+    }
+
+    /// Enables the adjustment of the timestamp tolerance of the BFT, for the skew of the local clock
+    /// from the network time, as estimated by the router from the pings and pongs of the committee members.
+    pub fn enable_clock_adjustment(&self) {
+        /// The interval in seconds in between the adjustments.
+        const ADJUSTMENT_INTERVAL_IN_SECS: u64 = 30;
+
+        let router = self.router.clone();
+        self.spawn(async move {
+            loop {
+                clock::sleep(Duration::from_secs(ADJUSTMENT_INTERVAL_IN_SECS)).await;
+                if let Some(offset) = router.time_sync().offset() {
+                    snarkos_node_bft::helpers::set_clock_offset(offset / 1000);
+                }
+            }
+        });
+    }
+
+    /// Starts discovering the nodes on the local network over mDNS, in development mode.
+    /// The BFT gateway connects to the discovered validators, in addition to the trusted validators.
+    pub fn enable_local_discovery(&self) -> Result<()> {
+        /// The interval in seconds in between the connection attempts to the discovered validators.
+        const CONNECT_INTERVAL_IN_SECS: u64 = 5;
+
+        let gateway = self.consensus.bft().primary().gateway().clone();
+        self.router.enable_local_discovery(Some(gateway.local_ip().port()))?;
+
+        let router = self.router.clone();
+        self.spawn(async move {
+            loop {
+                clock::sleep(Duration::from_secs(CONNECT_INTERVAL_IN_SECS)).await;
+                for bft_ip in router.local_peers().into_iter().filter_map(|peer| peer.bft) {
+                    if !gateway.is_connected_ip(bft_ip) {
+                        gateway.connect(bft_ip);
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Runs the validator in hidden mode, in which its router only accepts the inbound connections of the members of
+    /// the latest committee and of the given relays, while it still dials out to any peer.
+    /// Note: The gateway of the BFT already only accepts the connections of the committee members.
+    pub fn enable_hidden_mode(&self, relays: Vec<Address<N>>) -> Result<()> {
+        let ledger = self.ledger.clone();
+        self.router.enable_hidden_mode(HiddenMode::new(relays, move |address| {
+            ledger.latest_committee().map_or(false, |committee| committee.is_committee_member(*address))
+        }))
+    }
+
+    /// Sets the lock file, which is released when the node shuts down cleanly.
+    pub fn set_lock_file(&self, lock_file: LockFile) {
+        *self.lock_file.lock() = Some(lock_file);
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
@@ -361,7 +675,7 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             }
             _ => {
                 // Retrieve the genesis committee.
-                let Ok(Some(committee)) = self.ledger.get_committee_for_round(0) else {
+                let Ok(committee) = self.ledger_service.genesis_committee() else {
                     // If the genesis committee is not available, do not start the loop.
                     return Ok(());
                 };
@@ -377,12 +691,12 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
 
         let self_ = self.clone();
         self.spawn(async move {
-            tokio::time::sleep(Duration::from_secs(3)).await;
+            clock::sleep(Duration::from_secs(3)).await;
             info!("Starting transaction pool...");
 
             // Start the transaction loop.
             loop {
-                tokio::time::sleep(Duration::from_millis(500)).await;
+                clock::sleep(Duration::from_millis(500)).await;
 
                 // Prepare the inputs.
                 let inputs = [Value::from(Literal::Address(self_.address())), Value::from(Literal::U64(U64::new(1)))];
@@ -418,6 +732,64 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         Ok(())
     }
 
+    /// Starts proposing batches once the connected committee members hold the quorum threshold of stake,
+    /// and the ledger is within `MAX_BLOCKS_BEHIND` blocks of the height that they reached, as reported over the
+    /// gateway. Until then, the node keeps connecting to the committee members and syncing blocks. If the ledger
+    /// falls behind again, the node holds off proposing batches until it catches up.
+    fn initialize_catch_up(&self) {
+        /// The number of catch-up checks in between the progress logs.
+        const LOG_INTERVAL: u32 = 15;
+
+        let primary = self.consensus.bft().primary().clone();
+        let self_ = self.clone();
+        self.spawn(async move {
+            for iteration in 0u32.. {
+                clock::sleep(Duration::from_secs(1)).await;
+
+                let Ok(committee) = self_.ledger.latest_committee() else {
+                    continue;
+                };
+                // Retrieve the height that the committee members holding the quorum threshold of stake reached.
+                // Note: Only the committee members are trusted, so the heights reported on the router are ignored.
+                let latest_height = self_.ledger.latest_height();
+                let quorum_height = primary.sync().quorum_peer_height(&committee);
+
+                let is_caught_up =
+                    quorum_height.map_or(false, |height| height.saturating_sub(latest_height) <= MAX_BLOCKS_BEHIND);
+                if is_caught_up {
+                    primary.release_proposals(ProposalHold::CatchingUp);
+                    continue;
+                }
+                primary.hold_proposals(ProposalHold::CatchingUp);
+                if iteration % LOG_INTERVAL == 0 {
+                    let tip = quorum_height.map_or("unknown".to_string(), |height| height.to_string());
+                    info!("Catching up with the committee (block {latest_height} of {tip})");
+                }
+            }
+        });
+    }
+
+    /// Resumes proposing batches once the ledger reaches the last beacon-produced block at the given height,
+    /// so that the BFT produces the blocks from the next height onwards.
+    fn initialize_bft_activation(&self, activation_height: u32) {
+        let primary = self.consensus.bft().primary().clone();
+        // If the ledger already reached the activation height, the BFT is active.
+        if !primary.is_holding_proposals(ProposalHold::AwaitingActivation) {
+            return;
+        }
+        info!("The BFT activates after block {activation_height} (current height {})", self.ledger.latest_height());
+
+        let self_ = self.clone();
+        self.spawn(async move {
+            // Wait for the ledger to sync up to the last beacon-produced block.
+            while self_.ledger.latest_height() < activation_height {
+                clock::sleep(Duration::from_secs(1)).await;
+            }
+            info!("Reached block {activation_height}, activating the BFT");
+            primary.release_proposals(ProposalHold::AwaitingActivation);
+        });
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -426,6 +798,22 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
+    /// Returns the account private key of the node.
+    /// Note: This is the block-signing account, which may differ from the peer-to-peer identity of the router.
+    fn private_key(&self) -> &PrivateKey<N> {
+        self.consensus.bft().primary().gateway().account().private_key()
+    }
+
+    /// Returns the account view key of the node.
+    fn view_key(&self) -> &ViewKey<N> {
+        self.consensus.bft().primary().gateway().account().view_key()
+    }
+
+    /// Returns the account address of the node.
+    fn address(&self) -> Address<N> {
+        self.consensus.bft().primary().gateway().account().address()
+    }
+
     /// Shuts down the node.
     async fn shut_down(&self) {
         info!("Shutting down...");
@@ -434,24 +822,70 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
 
-        // Abort the tasks.
-        trace!("Shutting down the validator...");
-        self.handles.lock().iter().for_each(|handle| handle.abort());
-
-        // Shut down the router.
-        self.router.shut_down().await;
-
-        // Shut down consensus.
-        trace!("Shutting down consensus...");
-        self.consensus.shut_down().await;
-
-        info!("Node has shut down.");
+        // Shut down the subsystems in dependency order.
+        let ledger_service = self.ledger_service.clone();
+        let report = ShutdownCoordinator::default()
+            // Notify the connected peers, abort the tasks, and stop accepting connections.
+            .stage("router", SHUTDOWN_STAGE_TIMEOUT, async {
+                self.send_shutdown();
+                self.handles.lock().iter().for_each(|handle| handle.abort());
+                self.router.shut_down().await;
+            })
+            // Stop the BFT.
+            .stage("consensus", SHUTDOWN_STAGE_TIMEOUT, self.consensus.shut_down())
+            // Deliver the pending webhook events, including the stop of the BFT.
+            .stage("webhooks", SHUTDOWN_STAGE_TIMEOUT, async {
+                let webhooks = self.webhooks.lock().take();
+                if let Some(webhooks) = webhooks {
+                    webhooks.shut_down(WEBHOOK_SHUTDOWN_TIMEOUT).await;
+                }
+            })
+            // Flush the consensus archive.
+            .stage("consensus archive", SHUTDOWN_STAGE_TIMEOUT, async {
+                if let Err(error) = self.consensus.archive().sync() {
+                    warn!("Failed to flush the consensus archive - {error}");
+                }
+            })
+            // Wait for the pending block to be written to the ledger.
+            .stage("ledger", LEDGER_SHUTDOWN_TIMEOUT, async move {
+                let _ = tokio::task::spawn_blocking(move || ledger_service.wait_for_pending_writes()).await;
+            })
+            // Stop the REST server.
+            .stage("REST server", SHUTDOWN_STAGE_TIMEOUT, async {
+                if let Some(rest) = &self.rest {
+                    rest.shut_down();
+                }
+            })
+            // Remove the temporary directory of an ephemeral node.
+            .stage("ephemeral storage", SHUTDOWN_STAGE_TIMEOUT, async {
+                if let Some(dir) = &self.ephemeral_dir {
+                    if let Err(error) = std::fs::remove_dir_all(dir) {
+                        warn!("Failed to remove the ephemeral storage in '{}' - {error}", dir.display());
+                    }
+                }
+            })
+            .run()
+            .await;
+
+        match report.is_clean() {
+            true => {
+                // Release the lock file, so that the next start does not enter safe mode.
+                if let Some(lock_file) = self.lock_file.lock().take() {
+                    if let Err(error) = lock_file.release() {
+                        warn!("Failed to release the lock file - {error}");
+                    }
+                }
+                info!("Node has shut down - {report}")
+            }
+            false => warn!("Node has shut down, but some stages timed out - {report}"),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use snarkos_account::Account;
     use snarkvm::prelude::{
         store::{helpers::memory::ConsensusMemory, ConsensusStore},
         Testnet3,
@@ -490,12 +924,15 @@ mod tests {
             None,
             Some(rest),
             10,
-            account,
+            account.into(),
             &[],
             &[],
             genesis,
             None,
             storage_mode,
+            None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -13,18 +13,25 @@
 // limitations under the License.
 
 use super::*;
-use snarkos_node_router::messages::{
-    BlockRequest,
-    BlockResponse,
-    DataBlocks,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    disconnect_reason,
+    messages::{
+        BlockRequest,
+        BlockResponse,
+        CheckpointRequest,
+        CheckpointResponse,
+        CommitteeProof,
+        CommitteeRequest,
+        CommitteeResponse,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        Ping,
+        Pong,
+        UnconfirmedTransaction,
+    },
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{Connection, ConnectionSide, Tcp, TokenBucket};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, coinbase::EpochChallenge, error, Network},
@@ -48,7 +55,8 @@ impl<N: Network, C: ConsensusStorage<N>> Handshake for Validator<N, C> {
         let conn_side = connection.side();
         let stream = self.borrow_stream(&mut connection);
         let genesis_header = self.ledger.get_header(0).map_err(|e| error(format!("{e}")))?;
-        self.router.handshake(peer_addr, stream, conn_side, genesis_header).await?;
+        let latest_height = self.ledger.latest_height();
+        self.router.handshake(peer_addr, stream, conn_side, genesis_header, latest_height).await?;
 
         Ok(connection)
     }
@@ -62,6 +70,8 @@ where
     async fn on_connect(&self, peer_addr: SocketAddr) {
         // Resolve the peer address to the listener address.
         let Some(peer_ip) = self.router.resolve_to_listener(&peer_addr) else { return };
+        // Close the older connections of the same node identity, if it holds too many.
+        self.disconnect_duplicate_connections(peer_ip);
         // Retrieve the block locators.
         let block_locators = match self.sync.get_block_locators() {
             Ok(block_locators) => Some(block_locators),
@@ -96,6 +106,11 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Validator<N, C> {
     fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the token bucket that caps the bandwidth to the given peer address, if any.
+    fn bandwidth_limiter(&self, addr: SocketAddr) -> Option<Arc<TokenBucket>> {
+        self.router.bandwidth_limiter(addr)
+    }
 }
 
 #[async_trait]
@@ -105,8 +120,10 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        let router = self.router().clone();
+        MessageCodec::default()
+            .with_malformed_frame_handler(move |reason| router.record_malformed_frame(peer_addr, reason))
     }
 
     /// Processes a message received from the network.
@@ -115,9 +132,7 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
         if let Err(error) = self.inbound(peer_addr, message).await {
             if let Some(peer_ip) = self.router().resolve_to_listener(&peer_addr) {
                 warn!("Disconnecting from '{peer_ip}' - {error}");
-                Outbound::send(self, peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
-                // Disconnect from this peer.
-                self.router().disconnect(peer_ip);
+                self.send_disconnect(peer_ip, disconnect_reason(&error));
             }
         }
         Ok(())
@@ -145,9 +160,13 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
-        // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
-            Ok(blocks) => Data::Object(DataBlocks(blocks)),
+        // Retrieve the serialized blocks within the requested range, from the block cache or the ledger.
+        let blocks = self
+            .router()
+            .block_cache()
+            .get_or_load_data_blocks(*start_height..*end_height, |height| self.ledger.get_block(height));
+        let blocks = match blocks {
+            Ok(bytes) => Data::Buffer(bytes),
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
                 return false;
@@ -170,12 +189,49 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         }
     }
 
+    /// Proves the committee that certified the requested block, and returns the committee response to the peer.
+    fn committee_request(&self, peer_ip: SocketAddr, message: CommitteeRequest) -> bool {
+        let CommitteeRequest { height } = message;
+
+        // Prove the committee for the requested block, which is the committee lookback of its leader round.
+        let ledger = &self.ledger_service;
+        let proof = ledger.get_block(height).and_then(|block| {
+            CommitteeProof::new(&block, ledger.get_committee_lookback_for_round(block.round())?)
+        });
+        let proof = match proof {
+            Ok(proof) => Data::Object(proof),
+            Err(error) => {
+                debug!("Failed to prove the committee for block {height} - {error}");
+                return false;
+            }
+        };
+        // Send the `CommitteeResponse` message to the peer.
+        Outbound::send(self, peer_ip, Message::CommitteeResponse(CommitteeResponse { height, proof }));
+        true
+    }
+
+    /// Returns the requested checkpoint certificate to the peer, if the checkpoint is certified.
+    fn checkpoint_request(&self, peer_ip: SocketAddr, message: CheckpointRequest) -> bool {
+        let checkpoints = self.consensus.bft().primary().sync().checkpoints();
+        let certificate = match message.height {
+            Some(height) => checkpoints.get(height),
+            None => checkpoints.latest(),
+        };
+        // Send the `CheckpointResponse` message to the peer.
+        Outbound::send(self, peer_ip, Message::CheckpointResponse(CheckpointResponse { certificate }));
+        true
+    }
+
     /// Processes the block locators and sends back a `Pong` message.
     fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
         // Check if the sync module is in router mode.
         if self.sync.mode().is_router() {
+            // If the peer is far behind this node, it cannot serve any blocks, so remove it from the sync pool.
+            if self.router().is_syncing_peer(&peer_ip) {
+                self.sync.remove_peer(&peer_ip);
+            }
             // If block locators were provided, then update the peer in the sync pool.
-            if let Some(block_locators) = message.block_locators {
+            else if let Some(block_locators) = message.block_locators {
                 // Check the block locators are valid, and update the peer in the sync pool.
                 if let Err(error) = self.sync.update_peer_locators(peer_ip, block_locators) {
                     warn!("Peer '{peer_ip}' sent invalid block locators: {error}");
@@ -185,7 +241,7 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         }
 
         // Send a `Pong` message to the peer.
-        Outbound::send(self, peer_ip, Message::Pong(Pong { is_fork: Some(false) }));
+        Outbound::send(self, peer_ip, Message::Pong(Pong::new(Some(false), message.timestamp)));
         true
     }
 
@@ -267,4 +323,9 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         self.propagate_to_validators(message, &[peer_ip]);
         true
     }
+
+    /// Refuses to continue the stem, as the validator ends every stem by adding the transaction to the memory pool.
+    async fn is_valid_stem_transaction(&self, _transaction: Transaction<N>) -> bool {
+        false
+    }
 }
@@ -0,0 +1,418 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bus::{EventBus, NodeEvent};
+use snarkvm::prelude::Network;
+
+use anyhow::{Context, Result};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fmt,
+    fs::OpenOptions,
+    io::Write,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use time::OffsetDateTime;
+use tokio::{
+    sync::{mpsc, Notify},
+    task::{JoinHandle, JoinSet},
+};
+
+/// The default number of times a failed webhook delivery is retried, before it is written to the dead-letter log.
+const DEFAULT_WEBHOOK_MAX_RETRIES: u32 = 3;
+/// The delay in milliseconds before the first retry of a webhook delivery, which doubles with each retry.
+const WEBHOOK_RETRY_DELAY_IN_MS: u64 = 1000;
+/// The timeout in seconds of a single webhook delivery.
+const WEBHOOK_TIMEOUT_IN_SECS: u64 = 10;
+/// The maximum number of events awaiting delivery to the webhooks.
+const WEBHOOK_QUEUE_CAPACITY: usize = 1024;
+/// The time for which a shutdown waits for the pending webhook deliveries.
+pub const WEBHOOK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The name of the header that carries the HMAC-SHA256 signature of the payload.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Snarkos-Signature";
+/// The name of the header that carries the name of the event.
+pub const WEBHOOK_EVENT_HEADER: &str = "X-Snarkos-Event";
+
+/// The webhook configuration of a node, loaded from a JSON file.
+///
+/// For example:
+/// ```json
+/// {
+///   "webhooks": [
+///     { "url": "https://example.com/hooks/blocks", "secret": "...", "events": ["block_advanced"] },
+///     { "url": "https://example.com/hooks/incidents", "secret": "...", "events": ["fork_detected", "quorum_lost"] }
+///   ],
+///   "max_retries": 3,
+///   "dead_letter_path": "/var/log/snarkos/webhooks.dead.jsonl"
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// The webhooks to post the events to.
+    pub webhooks: Vec<Webhook>,
+    /// The number of times a failed delivery is retried.
+    #[serde(default = "WebhookConfig::default_max_retries")]
+    pub max_retries: u32,
+    /// The path of the log to which the undeliverable events are appended, one JSON object per line.
+    #[serde(default)]
+    pub dead_letter_path: Option<PathBuf>,
+}
+
+impl WebhookConfig {
+    /// Loads the webhook configuration from the given path.
+    pub fn load(path: &Path) -> Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the webhook configuration at '{}'", path.display()))?;
+        serde_json::from_str(&config)
+            .with_context(|| format!("Failed to parse the webhook configuration at '{}'", path.display()))
+    }
+
+    /// Returns the default number of times a failed delivery is retried.
+    const fn default_max_retries() -> u32 {
+        DEFAULT_WEBHOOK_MAX_RETRIES
+    }
+}
+
+/// A webhook, to which the events of the node are posted.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Webhook {
+    /// The URL to post the events to.
+    pub url: String,
+    /// The secret with which the payloads are signed.
+    pub secret: String,
+    /// The names of the events to post, or all events if it is empty.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl Webhook {
+    /// Returns `true` if the webhook subscribes to the event with the given name.
+    pub fn accepts(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|name| name == event)
+    }
+}
+
+impl fmt::Debug for Webhook {
+    /// Formats the webhook, without its secret.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Webhook")
+            .field("url", &self.url)
+            .field("secret", &"<redacted>")
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+/// An event of the node, which is posted to the webhooks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// The ledger advanced to the given block.
+    BlockAdvanced { height: u32, hash: String },
+    /// A committee member reports a block hash that conflicts with the ledger, i.e. the node is on a different fork.
+    ForkDetected { height: u32, peer_ip: SocketAddr, validator: String, expected_hash: String, reported_hash: String },
+    /// The BFT started.
+    BftStarted,
+    /// The BFT stopped.
+    BftStopped,
+    /// The stake of the connected committee members fell below the quorum threshold.
+    QuorumLost { connected_stake: u64, quorum_threshold: u64 },
+    /// The stake of the connected committee members reached the quorum threshold again.
+    QuorumRestored { connected_stake: u64, quorum_threshold: u64 },
+}
+
+impl WebhookEvent {
+    /// Returns the name of the event.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::BlockAdvanced { .. } => "block_advanced",
+            Self::ForkDetected { .. } => "fork_detected",
+            Self::BftStarted => "bft_started",
+            Self::BftStopped => "bft_stopped",
+            Self::QuorumLost { .. } => "quorum_lost",
+            Self::QuorumRestored { .. } => "quorum_restored",
+        }
+    }
+
+    /// Returns the webhook event for the given event of the node, if it is posted to the webhooks.
+    pub fn from_node_event<N: Network>(event: &NodeEvent<N>) -> Option<Self> {
+        match event {
+            NodeEvent::BlockAdvanced { height, hash } => {
+                Some(Self::BlockAdvanced { height: *height, hash: hash.to_string() })
+            }
+            NodeEvent::BftStarted => Some(Self::BftStarted),
+            NodeEvent::BftStopped => Some(Self::BftStopped),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the hex-encoded HMAC-SHA256 signature of the given payload, with the given secret.
+pub fn sign_webhook_payload(secret: &str, payload: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hex::encode(hmac::sign(&key, payload).as_ref())
+}
+
+/// The sender of the events to the webhooks.
+#[derive(Clone, Debug)]
+pub struct WebhookSender(mpsc::Sender<WebhookEvent>);
+
+impl WebhookSender {
+    /// Queues the given event for delivery. If the queue is full, the event is dropped.
+    pub fn send(&self, event: WebhookEvent) {
+        if let Err(error) = self.0.try_send(event) {
+            warn!("Dropping a webhook event - {error}");
+        }
+    }
+}
+
+/// Starts a task that posts the queued events to the webhooks, and returns the sender of the events.
+///
+/// Each payload is a JSON object with the UTC `timestamp` at which the event was queued, and the event as `data`.
+/// It is signed with the secret of the webhook in the `X-Snarkos-Signature` header, as `sha256=<hex>`.
+/// A failed delivery is retried with an exponential backoff, and then appended to the dead-letter log.
+/// Once every sender is dropped, the task waits for the pending deliveries, and then stops.
+/// Note: The deliveries run concurrently, so a webhook may receive the events out of order.
+pub fn start_webhooks(config: WebhookConfig) -> (WebhookSender, JoinHandle<()>) {
+    let (sender, mut receiver) = mpsc::channel::<WebhookEvent>(WEBHOOK_QUEUE_CAPACITY);
+    let handle = tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_IN_SECS))
+            .build()
+            .unwrap_or_default();
+        let mut deliveries = JoinSet::new();
+        loop {
+            let event = tokio::select! {
+                event = receiver.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                // Reap the completed deliveries.
+                Some(_) = deliveries.join_next(), if !deliveries.is_empty() => continue,
+            };
+            let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+            let payload = json!({ "timestamp": timestamp, "data": event }).to_string();
+            for webhook in config.webhooks.iter().filter(|webhook| webhook.accepts(event.name())) {
+                let (client, webhook, payload) = (client.clone(), webhook.clone(), payload.clone());
+                let (name, max_retries) = (event.name(), config.max_retries);
+                let dead_letter_path = config.dead_letter_path.clone();
+                deliveries.spawn(async move {
+                    if let Err(error) = deliver(&client, &webhook, name, &payload, max_retries).await {
+                        warn!("Failed to post the '{name}' event to '{}' - {error}", webhook.url);
+                        if let Some(path) = dead_letter_path {
+                            if let Err(error) = record_dead_letter(&path, &webhook.url, &payload, &error) {
+                                error!("Failed to write to the webhook dead-letter log - {error}");
+                            }
+                        }
+                    }
+                });
+            }
+        }
+        // Wait for the pending deliveries.
+        while deliveries.join_next().await.is_some() {}
+    });
+    (WebhookSender(sender), handle)
+}
+
+/// Starts a task that forwards the events of the node from the given event bus to the webhooks.
+/// Once the given signal is notified, the task forwards the events published until then, and stops.
+pub fn forward_node_events<N: Network>(
+    event_bus: &EventBus<N>,
+    sender: WebhookSender,
+    stop: Arc<Notify>,
+) -> JoinHandle<()> {
+    use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+    let mut events = event_bus.subscribe();
+    tokio::spawn(async move {
+        let forward = |event: &NodeEvent<N>| {
+            if let Some(event) = WebhookEvent::from_node_event(event) {
+                sender.send(event);
+            }
+        };
+        loop {
+            tokio::select! {
+                result = events.recv() => match result {
+                    Ok(event) => forward(&event),
+                    Err(RecvError::Lagged(num_events)) => warn!("The webhooks skipped {num_events} events of the node"),
+                    Err(RecvError::Closed) => break,
+                },
+                _ = stop.notified() => {
+                    loop {
+                        match events.try_recv() {
+                            Ok(event) => forward(&event),
+                            Err(TryRecvError::Lagged(num_events)) => {
+                                warn!("The webhooks skipped {num_events} events of the node")
+                            }
+                            Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// The tasks that post the events of a node to its webhooks.
+pub struct WebhookTasks {
+    /// The signal for the forwarder to stop.
+    stop: Arc<Notify>,
+    /// The task that forwards the events of the node to the webhooks.
+    forwarder: JoinHandle<()>,
+    /// The task that posts the events to the webhooks.
+    dispatcher: JoinHandle<()>,
+}
+
+impl WebhookTasks {
+    /// Starts posting the events published on the given event bus to the webhooks.
+    /// Returns the tasks, and the sender of the events that are not published on the event bus.
+    pub fn start<N: Network>(config: WebhookConfig, event_bus: &EventBus<N>) -> (WebhookSender, Self) {
+        let (sender, dispatcher) = start_webhooks(config);
+        let stop = Arc::new(Notify::new());
+        let forwarder = forward_node_events(event_bus, sender.clone(), stop.clone());
+        (sender, Self { stop, forwarder, dispatcher })
+    }
+
+    /// Forwards the events published so far, and waits for up to the given timeout for them to be delivered,
+    /// after which the remaining deliveries are aborted.
+    /// Note: The deliveries only complete once every other sender of the events is dropped.
+    pub async fn shut_down(self, timeout: Duration) {
+        let (forwarder, dispatcher) = (self.forwarder.abort_handle(), self.dispatcher.abort_handle());
+        self.stop.notify_one();
+        let drain = async {
+            let _ = self.forwarder.await;
+            let _ = self.dispatcher.await;
+        };
+        if tokio::time::timeout(timeout, drain).await.is_err() {
+            warn!("Aborting the webhook deliveries that did not complete within {}s", timeout.as_secs());
+            forwarder.abort();
+            dispatcher.abort();
+        }
+    }
+}
+
+/// Posts the given payload to the given webhook, retrying with an exponential backoff on failure.
+async fn deliver(
+    client: &reqwest::Client,
+    webhook: &Webhook,
+    event: &str,
+    payload: &str,
+    max_retries: u32,
+) -> Result<(), reqwest::Error> {
+    let signature = format!("sha256={}", sign_webhook_payload(&webhook.secret, payload.as_bytes()));
+    let mut attempt = 0;
+    loop {
+        let request = client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header(WEBHOOK_EVENT_HEADER, event)
+            .header(WEBHOOK_SIGNATURE_HEADER, &signature)
+            .body(payload.to_string());
+        match request.send().await.and_then(|response| response.error_for_status()) {
+            Ok(_) => return Ok(()),
+            Err(error) if attempt >= max_retries => return Err(error),
+            Err(error) => debug!("Retrying the '{event}' event to '{}' - {error}", webhook.url),
+        }
+        tokio::time::sleep(Duration::from_millis(WEBHOOK_RETRY_DELAY_IN_MS << attempt.min(16))).await;
+        attempt += 1;
+    }
+}
+
+/// Appends the given undeliverable payload to the dead-letter log at the given path.
+fn record_dead_letter(path: &Path, url: &str, payload: &str, error: &reqwest::Error) -> Result<()> {
+    let payload = serde_json::from_str::<serde_json::Value>(payload)?;
+    let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+    let line = json!({ "timestamp": timestamp, "url": url, "error": error.to_string(), "payload": payload });
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::Testnet3;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_webhook_config() {
+        let config = r#"{
+            "webhooks": [
+                { "url": "http://localhost:8080", "secret": "foo" },
+                { "url": "http://localhost:8081", "secret": "bar", "events": ["fork_detected"] }
+            ],
+            "dead_letter_path": "/tmp/webhooks.jsonl"
+        }"#;
+        let config: WebhookConfig = serde_json::from_str(config).unwrap();
+        assert_eq!(config.max_retries, DEFAULT_WEBHOOK_MAX_RETRIES);
+        assert_eq!(config.dead_letter_path, Some(PathBuf::from("/tmp/webhooks.jsonl")));
+        assert!(config.webhooks[0].accepts("block_advanced"));
+        assert!(!config.webhooks[1].accepts("block_advanced"));
+        assert!(config.webhooks[1].accepts("fork_detected"));
+        // Ensure the secret is not printed.
+        assert!(!format!("{config:?}").contains("foo"));
+        // Ensure unknown fields are rejected.
+        assert!(serde_json::from_str::<WebhookConfig>(r#"{ "webhooks": [], "foo": 1 }"#).is_err());
+    }
+
+    #[test]
+    fn test_webhook_event() {
+        let event = WebhookEvent::QuorumLost { connected_stake: 10, quorum_threshold: 20 };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "event": "quorum_lost", "connected_stake": 10, "quorum_threshold": 20 })
+        );
+        let event = NodeEvent::<CurrentNetwork>::BftStopped;
+        assert_eq!(WebhookEvent::from_node_event(&event), Some(WebhookEvent::BftStopped));
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+        assert_eq!(WebhookEvent::from_node_event(&NodeEvent::<CurrentNetwork>::PeerConnected { peer_ip }), None);
+    }
+
+    #[test]
+    fn test_sign_webhook_payload() {
+        // The test vector of RFC 4231, test case 2.
+        assert_eq!(
+            sign_webhook_payload("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forward_node_events_until_stopped() {
+        let event_bus = EventBus::<CurrentNetwork>::default();
+        let (sender, mut receiver) = mpsc::channel(WEBHOOK_QUEUE_CAPACITY);
+        let stop = Arc::new(Notify::new());
+        let forwarder = forward_node_events(&event_bus, WebhookSender(sender), stop.clone());
+
+        // Ensure the events published right before the stop are still forwarded.
+        event_bus.publish(NodeEvent::BftStarted);
+        event_bus.publish(NodeEvent::BftStopped);
+        stop.notify_one();
+        forwarder.await.unwrap();
+        assert_eq!(receiver.recv().await.map(|event| event.name()), Some("bft_started"));
+        assert_eq!(receiver.recv().await.map(|event| event.name()), Some("bft_stopped"));
+        // Ensure the forwarder dropped its sender, so that the dispatcher stops once the queue is drained.
+        assert!(receiver.recv().await.is_none());
+    }
+}
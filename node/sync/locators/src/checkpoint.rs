@@ -0,0 +1,219 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{
+        error,
+        Address,
+        CryptoRng,
+        FromBytes,
+        IoResult,
+        Network,
+        PrivateKey,
+        Read,
+        Rng,
+        Signature,
+        ToBytes,
+        Write,
+    },
+};
+
+use anyhow::{ensure, Result};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// The interval between committee checkpoints, in blocks.
+pub const COMMITTEE_CHECKPOINT_INTERVAL: u32 = 1_000; // 1,000 block intervals
+
+/// A block height, hash, and previous state root, which the committee signs
+/// every `COMMITTEE_CHECKPOINT_INTERVAL` blocks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(bound = "")]
+pub struct CommitteeCheckpoint<N: Network> {
+    /// The block height.
+    pub height: u32,
+    /// The block hash.
+    pub block_hash: N::BlockHash,
+    /// The previous state root in the header of the block, i.e. the state root before the block.
+    pub previous_state_root: N::StateRoot,
+}
+
+impl<N: Network> CommitteeCheckpoint<N> {
+    /// Returns `true` if the committee signs a checkpoint at the given height.
+    pub const fn is_checkpoint_height(height: u32) -> bool {
+        height > 0 && height % COMMITTEE_CHECKPOINT_INTERVAL == 0
+    }
+
+    /// Returns the height of the latest checkpoint at or below the given height, if there is one.
+    pub const fn latest_checkpoint_height(height: u32) -> Option<u32> {
+        match height / COMMITTEE_CHECKPOINT_INTERVAL {
+            0 => None,
+            index => Some(index * COMMITTEE_CHECKPOINT_INTERVAL),
+        }
+    }
+
+    /// Signs the checkpoint with the given private key.
+    pub fn sign<R: Rng + CryptoRng>(&self, private_key: &PrivateKey<N>, rng: &mut R) -> Result<Signature<N>> {
+        Signature::sign_bytes(private_key, &self.message()?, rng)
+    }
+
+    /// Returns `true` if the given signature over the checkpoint is from the given address.
+    pub fn verify(&self, address: &Address<N>, signature: &Signature<N>) -> bool {
+        self.message().map_or(false, |message| signature.verify_bytes(address, &message))
+    }
+
+    /// Returns the signed message, which is bound to the network.
+    fn message(&self) -> Result<Vec<u8>> {
+        Ok([N::ID.to_bytes_le()?, self.to_bytes_le()?].concat())
+    }
+}
+
+impl<N: Network> ToBytes for CommitteeCheckpoint<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.height.write_le(&mut writer)?;
+        self.block_hash.write_le(&mut writer)?;
+        self.previous_state_root.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for CommitteeCheckpoint<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let height = u32::read_le(&mut reader)?;
+        let block_hash = N::BlockHash::read_le(&mut reader)?;
+        let previous_state_root = N::StateRoot::read_le(&mut reader)?;
+        Ok(Self { height, block_hash, previous_state_root })
+    }
+}
+
+/// A checkpoint signed by a quorum of the stake in the committee of the checkpoint block.
+///
+/// Nodes that hold a certificate never accept a block at its height with another hash, which bounds
+/// how far the history can be rewritten, even if the keys of a past committee are later compromised.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct CheckpointCertificate<N: Network> {
+    /// The checkpoint.
+    pub checkpoint: CommitteeCheckpoint<N>,
+    /// The signatures of the committee members over the checkpoint.
+    pub signatures: IndexMap<Address<N>, Signature<N>>,
+}
+
+impl<N: Network> CheckpointCertificate<N> {
+    /// Checks that the signers are members of the given committee that hold a quorum of the stake,
+    /// and that every signature over the checkpoint is valid.
+    pub fn verify(&self, committee: &Committee<N>) -> Result<()> {
+        ensure!(
+            self.signatures.len() <= Committee::<N>::MAX_COMMITTEE_SIZE as usize,
+            "The checkpoint certificate has too many signatures"
+        );
+        let mut signers = HashSet::with_capacity(self.signatures.len());
+        for (signer, signature) in &self.signatures {
+            ensure!(committee.is_committee_member(*signer), "Signer {signer} is not in the committee");
+            ensure!(self.checkpoint.verify(signer, signature), "Invalid checkpoint signature from {signer}");
+            signers.insert(*signer);
+        }
+        ensure!(committee.is_quorum_threshold_reached(&signers), "The quorum threshold has not been reached");
+        Ok(())
+    }
+}
+
+impl<N: Network> ToBytes for CheckpointCertificate<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.checkpoint.write_le(&mut writer)?;
+        u16::try_from(self.signatures.len()).map_err(error)?.write_le(&mut writer)?;
+        for (signer, signature) in &self.signatures {
+            signer.write_le(&mut writer)?;
+            signature.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for CheckpointCertificate<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let checkpoint = CommitteeCheckpoint::read_le(&mut reader)?;
+        let num_signatures = u16::read_le(&mut reader)?;
+        if num_signatures > Committee::<N>::MAX_COMMITTEE_SIZE {
+            return Err(error("The checkpoint certificate has too many signatures"));
+        }
+        let mut signatures = IndexMap::with_capacity(num_signatures as usize);
+        for _ in 0..num_signatures {
+            let signer = Address::read_le(&mut reader)?;
+            let signature = Signature::read_le(&mut reader)?;
+            signatures.insert(signer, signature);
+        }
+        Ok(Self { checkpoint, signatures })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_checkpoint_heights() {
+        assert!(!CommitteeCheckpoint::<CurrentNetwork>::is_checkpoint_height(0));
+        assert!(!CommitteeCheckpoint::<CurrentNetwork>::is_checkpoint_height(COMMITTEE_CHECKPOINT_INTERVAL - 1));
+        assert!(CommitteeCheckpoint::<CurrentNetwork>::is_checkpoint_height(COMMITTEE_CHECKPOINT_INTERVAL));
+        assert_eq!(CommitteeCheckpoint::<CurrentNetwork>::latest_checkpoint_height(5), None);
+        assert_eq!(
+            CommitteeCheckpoint::<CurrentNetwork>::latest_checkpoint_height(2 * COMMITTEE_CHECKPOINT_INTERVAL + 5),
+            Some(2 * COMMITTEE_CHECKPOINT_INTERVAL)
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_certificate() {
+        let rng = &mut TestRng::default();
+
+        // Sample a committee of four members with equal stake.
+        let private_keys = (0..4).map(|_| PrivateKey::<CurrentNetwork>::new(rng).unwrap()).collect::<Vec<_>>();
+        let addresses = private_keys.iter().map(|key| Address::try_from(key).unwrap()).collect::<Vec<_>>();
+        let members = addresses.iter().map(|address| (*address, (1_000_000_000_000, true))).collect::<IndexMap<_, _>>();
+        let committee = Committee::new(0, members).unwrap();
+
+        let checkpoint = CommitteeCheckpoint::<CurrentNetwork> {
+            height: COMMITTEE_CHECKPOINT_INTERVAL,
+            block_hash: Field::rand(rng).into(),
+            previous_state_root: Field::rand(rng).into(),
+        };
+        let mut certificate = CheckpointCertificate { checkpoint, signatures: IndexMap::new() };
+
+        // Ensure two of four members do not reach the quorum threshold.
+        for private_key in &private_keys[..2] {
+            let signature = checkpoint.sign(private_key, rng).unwrap();
+            certificate.signatures.insert(Address::try_from(private_key).unwrap(), signature);
+        }
+        assert!(certificate.verify(&committee).is_err());
+
+        // Ensure three of four members reach the quorum threshold.
+        let signature = checkpoint.sign(&private_keys[2], rng).unwrap();
+        certificate.signatures.insert(addresses[2], signature);
+        assert!(certificate.verify(&committee).is_ok());
+
+        // Ensure a signature over another checkpoint is rejected.
+        let mut forged = certificate.clone();
+        forged.checkpoint.block_hash = Field::rand(rng).into();
+        assert!(forged.verify(&committee).is_err());
+
+        // Ensure the certificate roundtrips through bytes.
+        let bytes = certificate.to_bytes_le().unwrap();
+        assert_eq!(CheckpointCertificate::read_le(&bytes[..]).unwrap(), certificate);
+    }
+}
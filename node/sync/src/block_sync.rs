@@ -13,10 +13,21 @@
 // limitations under the License.
 
 use crate::{
-    helpers::{PeerPair, SyncRequest},
-    locators::BlockLocators,
+    helpers::{
+        check_block_contents,
+        check_block_quorum,
+        check_block_signatures,
+        check_hash_chain,
+        ChainPolicy,
+        CheckpointStore,
+        DefaultChainPolicy,
+        PeerPair,
+        SyncRequest,
+    },
+    locators::{BlockLocators, CheckpointCertificate},
 };
 use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_clock as clock;
 use snarkos_node_sync_communication_service::CommunicationService;
 use snarkos_node_sync_locators::{CHECKPOINT_INTERVAL, NUM_RECENT_BLOCKS};
 use snarkvm::prelude::{block::Block, Network};
@@ -29,6 +40,7 @@ use rand::{prelude::IteratorRandom, CryptoRng, Rng};
 use std::{
     collections::BTreeMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    ops::RangeInclusive,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -111,6 +123,12 @@ pub struct BlockSync<N: Network> {
     is_block_synced: Arc<AtomicBool>,
     /// The lock to guarantee advance_with_sync_blocks() is called only once at a time.
     advance_with_sync_blocks_lock: Arc<Mutex<()>>,
+    /// The checkpoint certificates, which the blocks and block locators from peers must not conflict with.
+    checkpoints: Arc<CheckpointStore<N>>,
+    /// The boolean indicator of whether the blocks covered by a certified checkpoint skip the full verification.
+    is_fast_sync: Arc<AtomicBool>,
+    /// The rules with which the next block is accepted, and the chains of the peers are compared.
+    chain_policy: Arc<RwLock<Arc<dyn ChainPolicy<N>>>>,
 }
 
 impl<N: Network> BlockSync<N> {
@@ -127,9 +145,18 @@ impl<N: Network> BlockSync<N> {
             request_timeouts: Default::default(),
             is_block_synced: Default::default(),
             advance_with_sync_blocks_lock: Default::default(),
+            checkpoints: Default::default(),
+            is_fast_sync: Default::default(),
+            chain_policy: Arc::new(RwLock::new(Arc::new(DefaultChainPolicy))),
         }
     }
 
+    /// Returns the checkpoint certificates.
+    #[inline]
+    pub fn checkpoints(&self) -> &Arc<CheckpointStore<N>> {
+        &self.checkpoints
+    }
+
     /// Returns the block sync mode.
     #[inline]
     pub const fn mode(&self) -> BlockSyncMode {
@@ -141,6 +168,35 @@ impl<N: Network> BlockSync<N> {
     pub fn is_block_synced(&self) -> bool {
         self.is_block_synced.load(Ordering::SeqCst)
     }
+
+    /// Returns `true` if fast sync is enabled.
+    #[inline]
+    pub fn is_fast_sync(&self) -> bool {
+        self.is_fast_sync.load(Ordering::Relaxed)
+    }
+
+    /// Enables fast sync, in which the blocks up to the latest certified checkpoint are applied after verifying
+    /// only their hashes, contents and signatures, rather than re-verifying their proofs. The blocks after the
+    /// latest certified checkpoint are still fully verified.
+    ///
+    /// Note: This trusts the committee that attests to the checkpoints, so it is opt-in. The blocks between two
+    /// checkpoints are held in memory until the hash chain to the next checkpoint is complete.
+    pub fn enable_fast_sync(&self) {
+        self.is_fast_sync.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the chain policy.
+    pub fn chain_policy(&self) -> Arc<dyn ChainPolicy<N>> {
+        self.chain_policy.read().clone()
+    }
+
+    /// Sets the chain policy, with which the next block is accepted, and the chains of the peers are compared.
+    ///
+    /// Note: This is intended for experimental networks, as a node with a different policy forks from the network.
+    pub fn set_chain_policy(&self, policy: Arc<dyn ChainPolicy<N>>) {
+        info!("Using the '{}' chain policy", policy.name());
+        *self.chain_policy.write() = policy;
+    }
 }
 
 #[allow(dead_code)]
@@ -261,7 +317,7 @@ impl<N: Network> BlockSync<N> {
                 }
             }
             // Sleep for 10 milliseconds to avoid triggering spam detection.
-            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            clock::sleep(std::time::Duration::from_millis(10)).await;
         }
     }
 
@@ -305,7 +361,50 @@ impl<N: Network> BlockSync<N> {
     }
 
     /// Handles the block responses from the sync pool.
+    ///
+    /// Note: The blocks are checked and applied one at a time. `check_next_block` verifies each block against the
+    /// ledger state left by its predecessor, and verifies the signatures and proofs of the block in the process,
+    /// so checking them ahead in parallel would repeat that work rather than shorten it.
     fn try_advancing_with_block_responses(&self, mut current_height: u32) {
+        // Retrieve the chain policy.
+        let policy = self.chain_policy();
+
+        // If fast sync covers the next blocks, apply them once all of the blocks up to the checkpoint are received.
+        while let Some(certificate) = self.next_fast_sync_checkpoint(current_height) {
+            let checkpoint_height = certificate.checkpoint.height;
+            // If any of the blocks up to the checkpoint is missing, wait for it.
+            let Some(blocks) = self.remove_block_responses(current_height + 1..=checkpoint_height) else {
+                return;
+            };
+            // Ensure the blocks are attested by the checkpoint, or otherwise fully verify them.
+            let checkpoint_hash = certificate.checkpoint.block_hash;
+            let is_attested = match self.check_attested_blocks(current_height, &blocks, checkpoint_hash) {
+                Ok(()) => true,
+                Err(error) => {
+                    debug!("Fully verifying blocks {} to {checkpoint_height} - {error}", current_height + 1);
+                    false
+                }
+            };
+            for block in blocks {
+                // Check the next block, with the rules of the chain policy for attested blocks if it is attested.
+                let result = match is_attested {
+                    true => policy.check_attested_block(self.canon.as_ref(), &block),
+                    false => policy.check_next_block(self.canon.as_ref(), &block),
+                };
+                if let Err(error) = result {
+                    warn!("The next block ({}) is invalid - {error}", block.height());
+                    return;
+                }
+                // Attempt to advance to the next block.
+                if let Err(error) = policy.advance_to_next_block(self.canon.as_ref(), &block) {
+                    warn!("{error}");
+                    return;
+                }
+            }
+            // Update the latest height.
+            current_height = self.canon.latest_block_height();
+        }
+
         while let Some(block) = self.remove_block_response(current_height + 1) {
             // Ensure the block height matches.
             if block.height() != current_height + 1 {
@@ -313,12 +412,12 @@ impl<N: Network> BlockSync<N> {
                 break;
             }
             // Check the next block.
-            if let Err(error) = self.canon.check_next_block(&block) {
+            if let Err(error) = policy.check_next_block(self.canon.as_ref(), &block) {
                 warn!("The next block ({}) is invalid - {error}", block.height());
                 break;
             }
             // Attempt to advance to the next block.
-            if let Err(error) = self.canon.advance_to_next_block(&block) {
+            if let Err(error) = policy.advance_to_next_block(self.canon.as_ref(), &block) {
                 warn!("{error}");
                 break;
             }
@@ -329,6 +428,36 @@ impl<N: Network> BlockSync<N> {
 }
 
 impl<N: Network> BlockSync<N> {
+    /// Returns the first certified checkpoint above the given height, if fast sync is enabled,
+    /// and a peer advertised the blocks up to the checkpoint.
+    fn next_fast_sync_checkpoint(&self, height: u32) -> Option<CheckpointCertificate<N>> {
+        if !self.is_fast_sync() {
+            return None;
+        }
+        let certificate = self.checkpoints.first_after(height)?;
+        let greatest_peer_height = self.locators.read().values().map(|l| l.latest_locator_height()).max()?;
+        (certificate.checkpoint.height <= greatest_peer_height).then_some(certificate)
+    }
+
+    /// Checks the given blocks, which follow the given height, are attested by the given checkpoint block hash.
+    ///
+    /// The blocks must form a hash chain from the ledger to the checkpoint, which is checked backward from the
+    /// checkpoint, before any of them is applied. The header of each block must commit to the contents of the block,
+    /// and its certificates must be signed by a quorum of the current committee. The rules of the chain policy
+    /// are checked as each block is applied.
+    fn check_attested_blocks(&self, height: u32, blocks: &[Block<N>], checkpoint_hash: N::BlockHash) -> Result<()> {
+        // Ensure the blocks form a hash chain from the ledger to the checkpoint.
+        check_hash_chain(self.canon.get_block_hash(height)?, blocks, checkpoint_hash)?;
+        // Ensure each block is certified by the committee, and commits to its contents.
+        let committee = self.canon.current_committee()?;
+        for block in blocks {
+            check_block_signatures(block)?;
+            check_block_contents(block)?;
+            check_block_quorum(&committee, block)?;
+        }
+        Ok(())
+    }
+
     /// Returns the sync peers with their latest heights, and their minimum common ancestor, if the node can sync.
     /// This function returns peers that are consistent with each other, and have a block height
     /// that is greater than the canon height of this node.
@@ -344,6 +473,11 @@ impl<N: Network> BlockSync<N> {
         }
     }
 
+    /// Returns the latest height of each peer, as reported by its block locators.
+    pub fn peer_heights(&self) -> IndexMap<SocketAddr, u32> {
+        self.locators.read().iter().map(|(peer_ip, locators)| (*peer_ip, locators.latest_locator_height())).collect()
+    }
+
     /// Updates the block locators and common ancestors for the given peer IP.
     /// This function checks that the given block locators are well-formed, however it does **not** check
     /// that the block locators are consistent the peer's previous block locators or other peers' block locators.
@@ -355,6 +489,8 @@ impl<N: Network> BlockSync<N> {
 
         // Ensure the given block locators are well-formed.
         locators.ensure_is_valid()?;
+        // Ensure the given block locators do not conflict with a certified checkpoint.
+        self.checkpoints.check_block_locators(&locators)?;
         // Update the locators entry for the given peer IP.
         self.locators.write().insert(peer_ip, locators.clone());
 
@@ -519,6 +655,8 @@ impl<N: Network> BlockSync<N> {
     fn check_block_response(&self, peer_ip: &SocketAddr, block: &Block<N>) -> Result<()> {
         // Retrieve the block height.
         let height = block.height();
+        // Ensure the candidate block does not conflict with a certified checkpoint.
+        self.checkpoints.check_block_hash(height, block.hash())?;
 
         // Retrieve the request entry for the candidate block.
         if let Some((expected_hash, expected_previous_hash, sync_ips)) = self.requests.read().get(&height) {
@@ -576,6 +714,33 @@ impl<N: Network> BlockSync<N> {
         self.responses.write().remove(&height)
     }
 
+    /// Removes and returns the block responses for the given heights, if every request is complete.
+    fn remove_block_responses(&self, heights: RangeInclusive<u32>) -> Option<Vec<Block<N>>> {
+        // Acquire the write locks on the requests and responses maps.
+        let mut requests = self.requests.write();
+        let mut responses = self.responses.write();
+
+        // Determine if every request is complete.
+        let is_complete = heights.clone().all(|height| {
+            let is_request_complete = requests.get(&height).map_or(false, |(_, _, peer_ips)| peer_ips.is_empty());
+            is_request_complete && responses.contains_key(&height)
+        });
+        // If any request is not complete, return early.
+        if !is_complete {
+            return None;
+        }
+
+        // Remove the request, request timestamp, and response entries for the given heights.
+        let mut request_timestamps = self.request_timestamps.write();
+        heights
+            .map(|height| {
+                requests.remove(&height);
+                request_timestamps.remove(&height);
+                responses.remove(&height)
+            })
+            .collect()
+    }
+
     /// Removes the block request for the given peer IP, if it exists.
     #[allow(dead_code)]
     fn remove_block_request_to_peer(&self, peer_ip: &SocketAddr, height: u32) {
@@ -685,6 +850,9 @@ impl<N: Network> BlockSync<N> {
             .map(|(peer_ip, timestamps)| (*peer_ip, timestamps.len()))
             .collect::<IndexMap<_, _>>();
 
+        // Retrieve the chain policy.
+        let policy = self.chain_policy();
+
         // Pick a set of peers above the latest canon height, and include their locators.
         let candidate_locators: IndexMap<_, _> = self
             .locators
@@ -692,7 +860,7 @@ impl<N: Network> BlockSync<N> {
             .iter()
             .filter(|(_, locators)| locators.latest_locator_height() > latest_canon_height)
             .filter(|(ip, _)| timeouts.get(*ip).map(|count| *count < MAX_BLOCK_REQUEST_TIMEOUTS).unwrap_or(true))
-            .sorted_by(|(_, a), (_, b)| b.latest_locator_height().cmp(&a.latest_locator_height()))
+            .sorted_by(|(_, a), (_, b)| policy.compare_chains(b, a))
             .take(NUM_SYNC_CANDIDATE_PEERS)
             .map(|(peer_ip, locators)| (*peer_ip, locators.clone()))
             .collect();
@@ -773,11 +941,24 @@ impl<N: Network> BlockSync<N> {
         // Compute the start height for the block request.
         let start_height = latest_canon_height + 1;
         // Compute the end height for the block request.
-        let end_height = (min_common_ancestor + 1).min(start_height + MAX_BLOCK_REQUESTS as u32);
+        // Note: With fast sync, the blocks up to the next checkpoint are applied only once all of them are received,
+        // so the requests extend up to the checkpoint, while the number of pending requests remains bounded.
+        let max_end_height = match self.next_fast_sync_checkpoint(latest_canon_height) {
+            Some(certificate) => (certificate.checkpoint.height + 1).max(start_height + MAX_BLOCK_REQUESTS as u32),
+            None => start_height + MAX_BLOCK_REQUESTS as u32,
+        };
+        let end_height = (min_common_ancestor + 1).min(max_end_height);
+        // Compute the number of requests that are still waiting for a response.
+        let num_pending_requests =
+            self.requests.read().values().filter(|(_, _, sync_ips)| !sync_ips.is_empty()).count();
 
-        let mut requests = Vec::with_capacity((start_height..end_height).len());
+        let mut requests = Vec::with_capacity((start_height..end_height).len().min(MAX_BLOCK_REQUESTS));
 
         for height in start_height..end_height {
+            // Ensure the number of pending requests remains bounded.
+            if num_pending_requests + requests.len() >= MAX_BLOCK_REQUESTS {
+                break;
+            }
             // Ensure the current height is not canonized or already requested.
             if self.check_block_request(height).is_err() {
                 continue;
@@ -888,7 +1069,10 @@ mod tests {
         NUM_RECENT_BLOCKS,
     };
     use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkvm::prelude::{Field, TestRng};
+    use snarkvm::{
+        ledger::ledger_test_helpers::sample_genesis_block,
+        prelude::{Field, TestRng, Uniform},
+    };
 
     use indexmap::indexset;
     use snarkvm::ledger::committee::Committee;
@@ -1317,5 +1501,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fast_sync_rejects_tampered_block() {
+        let rng = &mut TestRng::default();
+        let sync = sample_sync_at_height(0);
+        sync.enable_fast_sync();
+        let block = sample_genesis_block(rng);
+
+        // Ensure a block is refused if the checkpoint attests to a different block.
+        let checkpoint_hash = Field::<CurrentNetwork>::rand(rng).into();
+        assert!(sync.check_attested_blocks(0, &[block.clone()], checkpoint_hash).is_err());
+        // Ensure a block is refused if it is not certified by a quorum of the committee.
+        assert!(sync.check_attested_blocks(0, &[block.clone()], block.hash()).is_err());
+        // Ensure there is no checkpoint to fast sync to.
+        assert!(sync.next_fast_sync_checkpoint(0).is_none());
+    }
+
     // TODO: duplicate responses, ensure fails.
 }
@@ -0,0 +1,290 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::locators::{BlockLocators, CheckpointCertificate, CommitteeCheckpoint};
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{Address, FromBytes, Network, Signature, ToBytes},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use indexmap::IndexMap;
+use once_cell::sync::OnceCell;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::{BTreeMap, HashSet},
+    ops::Bound,
+    path::{Path, PathBuf},
+};
+
+/// The file extension of the stored checkpoint certificates.
+const CERTIFICATE_FILE_EXTENSION: &str = "checkpoint";
+
+/// The store of the checkpoint certificates, and of the signatures collected for the checkpoints
+/// that are not certified yet.
+///
+/// The block sync refuses blocks and block locators that conflict with a certified checkpoint.
+#[derive(Debug)]
+pub struct CheckpointStore<N: Network> {
+    /// The map of block height to the checkpoint certificate.
+    certificates: RwLock<BTreeMap<u32, CheckpointCertificate<N>>>,
+    /// The map of block height to the checkpoint and the signatures collected for it.
+    pending: Mutex<BTreeMap<u32, CheckpointCertificate<N>>>,
+    /// The directory in which the certificates are stored, if they are persisted.
+    directory: OnceCell<PathBuf>,
+}
+
+impl<N: Network> Default for CheckpointStore<N> {
+    fn default() -> Self {
+        Self { certificates: Default::default(), pending: Default::default(), directory: Default::default() }
+    }
+}
+
+impl<N: Network> CheckpointStore<N> {
+    /// The name of the checkpoint directory, which is stored inside the ledger directory.
+    pub const DIRECTORY_NAME: &'static str = "checkpoints";
+
+    /// Persists the certificates in the given directory, and loads the certificates stored in it.
+    pub fn open(&self, directory: PathBuf) -> Result<()> {
+        std::fs::create_dir_all(&directory)
+            .with_context(|| format!("Failed to create the checkpoint directory '{}'", directory.display()))?;
+        // Load the stored certificates.
+        let mut certificates = BTreeMap::new();
+        for entry in std::fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(CERTIFICATE_FILE_EXTENSION) {
+                continue;
+            }
+            let bytes = std::fs::read(&path)?;
+            let certificate = CheckpointCertificate::<N>::read_le(&bytes[..])
+                .with_context(|| format!("Failed to deserialize the checkpoint at '{}'", path.display()))?;
+            certificates.insert(certificate.checkpoint.height, certificate);
+        }
+        if !certificates.is_empty() {
+            debug!("Loaded {} checkpoint certificates", certificates.len());
+        }
+        self.certificates.write().extend(certificates);
+        if self.directory.set(directory).is_err() {
+            bail!("The checkpoint directory is already set");
+        }
+        Ok(())
+    }
+
+    /// Returns the number of checkpoint certificates.
+    pub fn len(&self) -> usize {
+        self.certificates.read().len()
+    }
+
+    /// Returns `true` if there are no checkpoint certificates.
+    pub fn is_empty(&self) -> bool {
+        self.certificates.read().is_empty()
+    }
+
+    /// Returns `true` if the checkpoint at the given height is certified.
+    pub fn contains(&self, height: u32) -> bool {
+        self.certificates.read().contains_key(&height)
+    }
+
+    /// Returns the checkpoint certificate at the given height, if there is one.
+    pub fn get(&self, height: u32) -> Option<CheckpointCertificate<N>> {
+        self.certificates.read().get(&height).cloned()
+    }
+
+    /// Returns the checkpoint certificate with the greatest height, if there is one.
+    pub fn latest(&self) -> Option<CheckpointCertificate<N>> {
+        self.certificates.read().values().next_back().cloned()
+    }
+
+    /// Returns the greatest height of a certified checkpoint, if there is one.
+    pub fn latest_height(&self) -> Option<u32> {
+        self.certificates.read().keys().next_back().copied()
+    }
+
+    /// Returns the checkpoint certificate with the least height above the given height, if there is one.
+    pub fn first_after(&self, height: u32) -> Option<CheckpointCertificate<N>> {
+        self.certificates.read().range((Bound::Excluded(height), Bound::Unbounded)).next().map(|(_, c)| c.clone())
+    }
+
+    /// Ensures the given block hash does not conflict with a certified checkpoint.
+    pub fn check_block_hash(&self, height: u32, block_hash: N::BlockHash) -> Result<()> {
+        if let Some(certificate) = self.certificates.read().get(&height) {
+            ensure!(
+                certificate.checkpoint.block_hash == block_hash,
+                "Block {height} conflicts with the certified checkpoint ('{}' != '{block_hash}')",
+                certificate.checkpoint.block_hash
+            );
+        }
+        Ok(())
+    }
+
+    /// Ensures the given block locators do not conflict with a certified checkpoint.
+    pub fn check_block_locators(&self, locators: &BlockLocators<N>) -> Result<()> {
+        let certificates = self.certificates.read();
+        for (height, hash) in locators.clone().into_iter() {
+            if let Some(certificate) = certificates.get(&height) {
+                ensure!(
+                    certificate.checkpoint.block_hash == hash,
+                    "The block locators conflict with the certified checkpoint at block {height}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the signature of a committee member over the given checkpoint, and returns the checkpoint
+    /// certificate once the signers hold a quorum of the stake in the given committee.
+    ///
+    /// The caller must ensure the checkpoint matches the ledger, as the signatures of a conflicting checkpoint
+    /// at the same height are refused.
+    pub fn insert_signature(
+        &self,
+        checkpoint: CommitteeCheckpoint<N>,
+        signer: Address<N>,
+        signature: Signature<N>,
+        committee: &Committee<N>,
+    ) -> Result<Option<CheckpointCertificate<N>>> {
+        let height = checkpoint.height;
+        // Skip the signature if the checkpoint is already certified.
+        if self.contains(height) {
+            return Ok(None);
+        }
+        // Ensure the signer is a committee member, and signed the checkpoint.
+        ensure!(committee.is_committee_member(signer), "Signer {signer} is not in the committee");
+        ensure!(checkpoint.verify(&signer, &signature), "Invalid checkpoint signature from {signer}");
+
+        let certificate = {
+            let mut pending = self.pending.lock();
+            let entry = pending
+                .entry(height)
+                .or_insert_with(|| CheckpointCertificate { checkpoint, signatures: IndexMap::new() });
+            ensure!(entry.checkpoint == checkpoint, "The checkpoint at block {height} conflicts with the signatures");
+            entry.signatures.insert(signer, signature);
+            // Check if the signers hold a quorum of the stake.
+            let signers = entry.signatures.keys().copied().collect::<HashSet<_>>();
+            if !committee.is_quorum_threshold_reached(&signers) {
+                return Ok(None);
+            }
+            // Remove the signatures for this checkpoint, and for any earlier checkpoints.
+            let certificate = pending.remove(&height).expect("The pending checkpoint exists");
+            pending.retain(|pending_height, _| *pending_height > height);
+            certificate
+        };
+        // Store the certificate.
+        self.insert_certificate(certificate.clone())?;
+        Ok(Some(certificate))
+    }
+
+    /// Verifies the given checkpoint certificate (e.g. from a peer) against the given committee, and stores it.
+    ///
+    /// The certificate is refused if it conflicts with a stored certificate at the same height.
+    pub fn verify_and_insert(&self, certificate: CheckpointCertificate<N>, committee: &Committee<N>) -> Result<()> {
+        let height = certificate.checkpoint.height;
+        ensure!(CommitteeCheckpoint::<N>::is_checkpoint_height(height), "Block {height} is not a checkpoint");
+        // Skip the certificate if the checkpoint is already certified, ensuring it does not conflict.
+        if let Some(existing) = self.get(height) {
+            ensure!(existing.checkpoint == certificate.checkpoint, "The checkpoint at block {height} conflicts");
+            return Ok(());
+        }
+        certificate.verify(committee)?;
+        self.insert_certificate(certificate)
+    }
+
+    /// Stores the given (verified) checkpoint certificate.
+    fn insert_certificate(&self, certificate: CheckpointCertificate<N>) -> Result<()> {
+        if let Some(directory) = self.directory.get() {
+            Self::write_certificate(directory, &certificate)?;
+        }
+        self.certificates.write().insert(certificate.checkpoint.height, certificate);
+        Ok(())
+    }
+
+    /// Writes the given certificate to the given directory.
+    /// Note: The certificate is written to a temporary file first, so that a crash never leaves a partial file.
+    fn write_certificate(directory: &Path, certificate: &CheckpointCertificate<N>) -> Result<()> {
+        let path = directory.join(format!("{}.{CERTIFICATE_FILE_EXTENSION}", certificate.checkpoint.height));
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, certificate.to_bytes_le()?)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locators::COMMITTEE_CHECKPOINT_INTERVAL;
+    use snarkvm::prelude::{Field, PrivateKey, TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_checkpoint_store() {
+        let rng = &mut TestRng::default();
+        let directory = std::env::temp_dir().join(format!("snarkos-checkpoints-{}", u64::rand(rng)));
+
+        // Sample a committee of four members with equal stake.
+        let private_keys = (0..4).map(|_| PrivateKey::<CurrentNetwork>::new(rng).unwrap()).collect::<Vec<_>>();
+        let addresses = private_keys.iter().map(|key| Address::try_from(key).unwrap()).collect::<Vec<_>>();
+        let members = addresses.iter().map(|address| (*address, (1_000_000_000_000, true))).collect::<IndexMap<_, _>>();
+        let committee = Committee::new(0, members).unwrap();
+
+        let store = CheckpointStore::<CurrentNetwork>::default();
+        store.open(directory.clone()).unwrap();
+
+        let height = COMMITTEE_CHECKPOINT_INTERVAL;
+        let checkpoint = CommitteeCheckpoint {
+            height,
+            block_hash: Field::rand(rng).into(),
+            previous_state_root: Field::rand(rng).into(),
+        };
+
+        // Ensure the signatures of a conflicting checkpoint are refused.
+        let conflicting = CommitteeCheckpoint { block_hash: Field::rand(rng).into(), ..checkpoint };
+        let signature = checkpoint.sign(&private_keys[0], rng).unwrap();
+        assert!(store.insert_signature(checkpoint, addresses[0], signature, &committee).unwrap().is_none());
+        let signature = conflicting.sign(&private_keys[1], rng).unwrap();
+        assert!(store.insert_signature(conflicting, addresses[1], signature, &committee).is_err());
+
+        // Ensure the checkpoint is certified once a quorum signed it.
+        let signature = checkpoint.sign(&private_keys[1], rng).unwrap();
+        assert!(store.insert_signature(checkpoint, addresses[1], signature, &committee).unwrap().is_none());
+        let signature = checkpoint.sign(&private_keys[2], rng).unwrap();
+        let certificate = store.insert_signature(checkpoint, addresses[2], signature, &committee).unwrap().unwrap();
+        assert!(certificate.verify(&committee).is_ok());
+        assert_eq!(store.latest(), Some(certificate.clone()));
+
+        // Ensure blocks that conflict with the checkpoint are refused.
+        assert!(store.check_block_hash(height, checkpoint.block_hash).is_ok());
+        assert!(store.check_block_hash(height, conflicting.block_hash).is_err());
+        assert!(store.check_block_hash(height + 1, conflicting.block_hash).is_ok());
+
+        // Ensure the certificate is loaded from the directory.
+        let reopened = CheckpointStore::<CurrentNetwork>::default();
+        reopened.open(directory.clone()).unwrap();
+        assert_eq!(reopened.get(height), Some(certificate.clone()));
+
+        // Ensure a certificate from a peer is stored once it is verified.
+        let store = CheckpointStore::<CurrentNetwork>::default();
+        let mut forged = certificate.clone();
+        forged.checkpoint = conflicting;
+        assert!(store.verify_and_insert(forged.clone(), &committee).is_err());
+        assert!(store.verify_and_insert(certificate.clone(), &committee).is_ok());
+        assert_eq!(store.latest_height(), Some(height));
+        assert_eq!(store.first_after(height - 1), Some(certificate.clone()));
+        assert_eq!(store.first_after(height), None);
+        assert!(store.verify_and_insert(forged, &committee).is_err());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
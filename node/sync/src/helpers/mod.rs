@@ -12,11 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkvm::prelude::Network;
+mod checkpoints;
+pub use checkpoints::*;
 
+mod policy;
+pub use policy::*;
+
+use snarkvm::{
+    ledger::{authority::Authority, block::Block, committee::Committee, narwhal::TransmissionID},
+    prelude::{Network, ToBits},
+};
+
+use anyhow::{bail, ensure, Result};
 use core::hash::Hash;
 use indexmap::IndexSet;
-use std::net::SocketAddr;
+use rayon::prelude::*;
+use std::{collections::HashSet, net::SocketAddr};
 
 /// A tuple of the block hash (optional), previous block hash (optional), and sync IPs.
 pub type SyncRequest<N> = (Option<<N as Network>::BlockHash>, Option<<N as Network>::BlockHash>, IndexSet<SocketAddr>);
@@ -39,3 +50,153 @@ impl Hash for PeerPair {
         b.hash(state);
     }
 }
+
+/// Checks the signatures in the authority of the given block.
+///
+/// This check does not ensure the signers form a quorum of the committee, which depends on the ledger state.
+pub fn check_block_signatures<N: Network>(block: &Block<N>) -> Result<()> {
+    match block.authority() {
+        // Ensure the beacon signed the block hash.
+        Authority::Beacon(signature) => {
+            ensure!(
+                signature.verify(&signature.to_address(), &[*block.hash()]),
+                "Invalid beacon signature in block {}",
+                block.height()
+            );
+            Ok(())
+        }
+        // Ensure the author and the signers signed the batch ID of every certificate.
+        Authority::Quorum(subdag) => {
+            let certificates = subdag.values().flatten().collect::<Vec<_>>();
+            certificates.par_iter().try_for_each(|certificate| {
+                let batch_id = certificate.batch_id();
+                ensure!(
+                    certificate.batch_header().signature().verify(&certificate.author(), &[batch_id]),
+                    "Invalid author signature for batch {batch_id} in block {}",
+                    block.height()
+                );
+                for signature in certificate.signatures() {
+                    ensure!(
+                        signature.verify(&signature.to_address(), &[batch_id]),
+                        "Invalid signature for batch {batch_id} in block {}",
+                        block.height()
+                    );
+                }
+                Ok(())
+            })
+        }
+    }
+}
+
+/// Checks the given blocks form a hash chain from the given previous block hash to the given (trusted) block hash.
+///
+/// The chain is checked backward from the trusted block hash, so each block hash is vouched for by its successor.
+pub fn check_hash_chain<N: Network>(
+    previous_hash: N::BlockHash,
+    blocks: &[Block<N>],
+    block_hash: N::BlockHash,
+) -> Result<()> {
+    let mut expected_hash = block_hash;
+    for block in blocks.iter().rev() {
+        ensure!(block.hash() == expected_hash, "Block {} is not in the chain of block '{block_hash}'", block.height());
+        expected_hash = block.previous_hash();
+    }
+    ensure!(expected_hash == previous_hash, "The blocks do not extend the block '{previous_hash}'");
+    Ok(())
+}
+
+/// Checks the hash and header of the given block commit to its contents, and that the aborted transactions of the
+/// block were ordered by its subdag, if it has one.
+///
+/// The order of the transactions of the block is checked by the chain policy.
+pub fn check_block_contents<N: Network>(block: &Block<N>) -> Result<()> {
+    let height = block.height();
+    // Ensure the block hash commits to the previous block hash and the header.
+    let mut preimage = block.previous_hash().to_bits_le();
+    preimage.extend(block.header().to_root()?.to_bits_le());
+    ensure!(*block.hash() == N::hash_bhp1024(&preimage)?, "Block {height} does not match its block hash");
+    // Ensure the header commits to the transactions and ratifications.
+    ensure!(
+        block.header().transactions_root() == block.transactions().to_transactions_root()?,
+        "Block {height} does not match its transactions root"
+    );
+    ensure!(
+        block.header().ratifications_root() == block.ratifications().to_ratifications_root()?,
+        "Block {height} does not match its ratifications root"
+    );
+    // Ensure the aborted transactions were ordered by the subdag.
+    if let Authority::Quorum(subdag) = block.authority() {
+        let transmission_ids = subdag.values().flatten().flat_map(|c| c.transmission_ids()).collect::<HashSet<_>>();
+        for transaction_id in block.aborted_transaction_ids() {
+            ensure!(
+                transmission_ids.contains(&TransmissionID::Transaction(*transaction_id)),
+                "Aborted transaction '{transaction_id}' in block {height} is not in its subdag"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks each certificate in the given block is signed by a quorum of the given committee.
+///
+/// This check does not verify the signatures themselves, which is done by [`check_block_signatures`].
+pub fn check_block_quorum<N: Network>(committee: &Committee<N>, block: &Block<N>) -> Result<()> {
+    let height = block.height();
+    let Authority::Quorum(subdag) = block.authority() else {
+        bail!("Block {height} is not certified by the committee");
+    };
+    for certificate in subdag.values().flatten() {
+        let signers = certificate
+            .signatures()
+            .map(|signature| signature.to_address())
+            .chain([certificate.author()])
+            .collect::<HashSet<_>>();
+        ensure!(
+            signers.iter().all(|signer| committee.is_committee_member(*signer)),
+            "Block {height} is signed by a validator outside the committee"
+        );
+        ensure!(
+            committee.is_quorum_threshold_reached(&signers),
+            "A certificate in block {height} is not signed by a quorum of the committee"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::ledger_test_helpers::sample_genesis_block,
+        prelude::{Field, TestRng, Testnet3, Uniform},
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_check_block_signatures() {
+        let rng = &mut TestRng::default();
+        let block = sample_genesis_block(rng);
+        assert!(check_block_signatures(&block).is_ok());
+    }
+
+    #[test]
+    fn test_check_attested_block() {
+        let rng = &mut TestRng::default();
+        let block: Block<CurrentNetwork> = sample_genesis_block(rng);
+        let (previous_hash, block_hash) = (block.previous_hash(), block.hash());
+
+        // Ensure the block is attested by its own hash, and commits to its contents.
+        assert!(check_hash_chain(previous_hash, &[block.clone()], block_hash).is_ok());
+        assert!(check_block_contents(&block).is_ok());
+
+        // Ensure the block is refused in place of a different attested block.
+        let attested_hash = Field::<CurrentNetwork>::rand(rng).into();
+        assert!(check_hash_chain(previous_hash, &[block.clone()], attested_hash).is_err());
+        // Ensure the block is refused if it does not extend the ledger.
+        assert!(check_hash_chain(Field::rand(rng).into(), &[block.clone()], block_hash).is_err());
+        // Ensure the block is refused if it is not certified by a quorum of the committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        assert!(check_block_quorum(&committee, &block).is_err());
+    }
+}
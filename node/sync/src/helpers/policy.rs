@@ -0,0 +1,165 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::locators::BlockLocators;
+use snarkos_node_bft_ledger_service::LedgerService;
+use snarkvm::{
+    ledger::{
+        authority::Authority,
+        block::Block,
+        narwhal::{Subdag, Transmission, TransmissionID},
+    },
+    prelude::{Network, Result},
+};
+
+use anyhow::ensure;
+use core::cmp::Ordering;
+use indexmap::IndexMap;
+use std::{collections::HashMap, fmt::Debug};
+
+/// The rules with which a node accepts the next block, and chooses between the chains of its peers.
+///
+/// The default implementation is [`DefaultChainPolicy`], which is the behavior of the network. Experimental
+/// networks may substitute their own rules (e.g. skipping the verification of the ordering of the subdag, or
+/// breaking the ties between peers differently), without forking the node.
+///
+/// Note: A node with a policy that differs from the rest of the network will fork from it.
+pub trait ChainPolicy<N: Network>: Debug + Send + Sync {
+    /// Returns the name of the policy.
+    fn name(&self) -> &'static str;
+
+    /// Returns a candidate for the next block in the ledger, using a committed subdag and its transmissions.
+    fn prepare_next_block(
+        &self,
+        ledger: &dyn LedgerService<N>,
+        subdag: Subdag<N>,
+        transmissions: IndexMap<TransmissionID<N>, Transmission<N>>,
+    ) -> Result<Block<N>> {
+        ledger.prepare_advance_to_next_quorum_block(subdag, transmissions)
+    }
+
+    /// Checks the transactions of the given block follow the order in which its subdag committed them.
+    fn check_block_ordering(&self, block: &Block<N>) -> Result<()> {
+        check_transaction_order(block)
+    }
+
+    /// Checks the given block is the valid next block, including the ordering of its subdag.
+    ///
+    /// Note: The ledger also enforces the consensus rules of the VM, which are not part of the policy.
+    fn check_next_block(&self, ledger: &dyn LedgerService<N>, block: &Block<N>) -> Result<()> {
+        self.check_block_ordering(block)?;
+        ledger.check_next_block(block)
+    }
+
+    /// Checks the given block, which is attested by a certified checkpoint, is the valid next block.
+    /// This is used by fast sync, in place of [`Self::check_next_block`], once the hashes, contents and signatures
+    /// of the block are verified against the checkpoint.
+    fn check_attested_block(&self, _ledger: &dyn LedgerService<N>, block: &Block<N>) -> Result<()> {
+        self.check_block_ordering(block)
+    }
+
+    /// Adds the given block as the next block in the ledger.
+    fn advance_to_next_block(&self, ledger: &dyn LedgerService<N>, block: &Block<N>) -> Result<()> {
+        ledger.advance_to_next_block(block)
+    }
+
+    /// Compares the chains of two peers, by their block locators, where the greater chain is preferred for sync.
+    /// The peers that compare as equal retain the order in which they were seen.
+    fn compare_chains(&self, a: &BlockLocators<N>, b: &BlockLocators<N>) -> Ordering {
+        a.latest_locator_height().cmp(&b.latest_locator_height())
+    }
+}
+
+/// The chain policy of the network, which fully verifies each block, and prefers the longest chain.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DefaultChainPolicy;
+
+impl<N: Network> ChainPolicy<N> for DefaultChainPolicy {
+    fn name(&self) -> &'static str {
+        "default"
+    }
+}
+
+/// Checks the transactions of the given block follow the order in which its subdag committed them.
+pub fn check_transaction_order<N: Network>(block: &Block<N>) -> Result<()> {
+    // A block without a subdag has no ordering to check.
+    let Authority::Quorum(subdag) = block.authority() else {
+        return Ok(());
+    };
+    // Retrieve the (first) position of each transaction in the subdag.
+    let mut positions = HashMap::new();
+    let transaction_ids = subdag.values().flatten().flat_map(|certificate| certificate.transmission_ids());
+    for (position, transmission_id) in transaction_ids.enumerate() {
+        if let TransmissionID::Transaction(transaction_id) = transmission_id {
+            positions.entry(*transaction_id).or_insert(position);
+        }
+    }
+    // Ensure the transactions in the block are in the same order as in the subdag.
+    let mut previous_position = None;
+    for transaction in block.transactions().iter() {
+        let transaction_id = transaction.to_unconfirmed_transaction_id()?;
+        let position = positions.get(&transaction_id).copied();
+        ensure!(position.is_some(), "Transaction '{transaction_id}' in block {} is not in its subdag", block.height());
+        ensure!(
+            position > previous_position,
+            "Transaction '{transaction_id}' in block {} is out of the order of its subdag",
+            block.height()
+        );
+        previous_position = position;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::MockLedgerService;
+    use snarkvm::{
+        ledger::{committee::test_helpers::sample_committee, ledger_test_helpers::sample_genesis_block},
+        prelude::{TestRng, Testnet3},
+    };
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_default_compare_chains() {
+        let policy = DefaultChainPolicy;
+        let shorter = BlockLocators::<CurrentNetwork>::new_unchecked(
+            [(0, Default::default()), (5, Default::default())].into_iter().collect(),
+            Default::default(),
+        );
+        let longer = BlockLocators::<CurrentNetwork>::new_unchecked(
+            [(0, Default::default()), (9, Default::default())].into_iter().collect(),
+            Default::default(),
+        );
+        assert_eq!(ChainPolicy::<CurrentNetwork>::name(&policy), "default");
+        assert_eq!(policy.compare_chains(&longer, &shorter), Ordering::Greater);
+        assert_eq!(policy.compare_chains(&shorter, &longer), Ordering::Less);
+        assert_eq!(policy.compare_chains(&longer, &longer), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_default_check_block_ordering() {
+        let rng = &mut TestRng::default();
+        let block = sample_genesis_block(rng);
+        // A block without a subdag has no ordering to check.
+        assert!(ChainPolicy::<CurrentNetwork>::check_block_ordering(&DefaultChainPolicy, &block).is_ok());
+        assert!(ChainPolicy::<CurrentNetwork>::check_attested_block(
+            &DefaultChainPolicy,
+            &MockLedgerService::new(sample_committee(rng)),
+            &block
+        )
+        .is_ok());
+    }
+}
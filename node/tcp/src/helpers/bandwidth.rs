@@ -0,0 +1,104 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// A token bucket that limits the rate at which bytes are written, e.g. to a class of connections.
+///
+/// Messages are written whole, and their size is only known once they are encoded, so the bucket may go into
+/// debt; the writer then waits until the debt has been replenished, before it writes its next message.
+pub struct TokenBucket {
+    /// The number of bytes replenished per second.
+    rate: u64,
+    /// The maximum number of bytes that may accumulate, i.e. the size of a burst.
+    capacity: u64,
+    /// The number of available bytes (negative while in debt), and the time at which they were last replenished.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    /// Initializes a new token bucket with the given rate in bytes per second, which allows bursts of one second.
+    pub fn new(rate: u64) -> Self {
+        Self::with_capacity(rate, rate)
+    }
+
+    /// Initializes a new token bucket with the given rate in bytes per second, and the given burst size in bytes.
+    pub fn with_capacity(rate: u64, capacity: u64) -> Self {
+        let rate = rate.max(1);
+        Self { rate, capacity, state: Mutex::new((capacity as f64, Instant::now())) }
+    }
+
+    /// Returns the number of bytes replenished per second.
+    pub fn rate(&self) -> u64 {
+        self.rate
+    }
+
+    /// Consumes the given number of bytes, and returns the duration until the bucket is out of debt.
+    pub fn consume(&self, num_bytes: usize) -> Duration {
+        let mut state = self.state.lock();
+        let (available, last_replenished) = &mut *state;
+
+        // Replenish the bytes that accrued since the last time, up to the capacity.
+        let now = Instant::now();
+        let accrued = now.saturating_duration_since(*last_replenished).as_secs_f64() * self.rate as f64;
+        *available = (*available + accrued).min(self.capacity as f64);
+        *last_replenished = now;
+
+        // Consume the bytes, which may put the bucket into debt.
+        *available -= num_bytes as f64;
+        match *available < 0.0 {
+            true => Duration::from_secs_f64(-*available / self.rate as f64),
+            false => Duration::ZERO,
+        }
+    }
+
+    /// Consumes the given number of bytes, and waits until the bucket is out of debt.
+    pub async fn throttle(&self, num_bytes: usize) {
+        let delay = self.consume(num_bytes);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let bucket = TokenBucket::with_capacity(1000, 1000);
+        // A burst up to the capacity is not delayed.
+        assert_eq!(bucket.consume(600), Duration::ZERO);
+        assert_eq!(bucket.consume(400), Duration::ZERO);
+        // Going into debt is delayed until the debt is replenished.
+        let delay = bucket.consume(500);
+        assert!(delay > Duration::from_millis(450) && delay <= Duration::from_millis(500), "{delay:?}");
+        // Further debt accumulates.
+        let delay = bucket.consume(1000);
+        assert!(delay > Duration::from_millis(1450) && delay <= Duration::from_millis(1500), "{delay:?}");
+    }
+
+    #[test]
+    fn test_token_bucket_replenishes() {
+        let bucket = TokenBucket::with_capacity(100_000, 100);
+        assert_eq!(bucket.consume(100), Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(10));
+        // 10ms at 100kB/s replenish 1000 bytes, which is capped at the capacity.
+        assert_eq!(bucket.consume(100), Duration::ZERO);
+        assert!(bucket.consume(100) > Duration::ZERO);
+    }
+}
@@ -54,6 +54,9 @@ pub struct Config {
     pub max_connections: u16,
     /// The maximum time (in milliseconds) allowed to establish a raw (before the [`Handshake`] protocol) TCP connection.
     pub connection_timeout_ms: u16,
+    /// The bytes that are written (best-effort) to an inbound connection that is rejected, as the Tcp is at its
+    /// maximum number of connections, so that the peer can tell the Tcp is reachable, but full.
+    pub rejection_message: Option<Vec<u8>>,
 }
 
 impl Config {
@@ -90,6 +93,7 @@ impl Default for Config {
             fatal_io_errors: vec![ConnectionReset, ConnectionAborted, BrokenPipe, InvalidData, UnexpectedEof],
             max_connections: 100,
             connection_timeout_ms: 1_000,
+            rejection_message: None,
         }
     }
 }
@@ -25,7 +25,10 @@ pub mod protocols;
 mod tcp;
 pub use tcp::Tcp;
 
-use std::net::IpAddr;
+use std::{io, net::IpAddr, time::Duration};
+
+use socket2::{SockRef, TcpKeepalive};
+use tokio::net::TcpStream;
 
 /// A trait for objects containing a [`Tcp`]; it is required to implement protocols.
 pub trait P2P {
@@ -44,6 +47,15 @@ pub fn is_bogon_ip(ip: IpAddr) -> bool {
     }
 }
 
+/// Enables TCP keepalive on the given stream, probing the peer after the given idle time, and again at the same
+/// interval, so that the operating system fails the connection if the peer has vanished without closing it.
+pub fn set_keepalive(stream: &TcpStream, idle: Duration) -> io::Result<()> {
+    let keepalive = TcpKeepalive::new().with_time(idle).with_interval(idle);
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    let keepalive = keepalive.with_retries(3);
+    SockRef::from(stream).set_tcp_keepalive(&keepalive)
+}
+
 /// Checks if the given IP address is unspecified or broadcast.
 pub fn is_unspecified_or_broadcast_ip(ip: IpAddr) -> bool {
     match ip {
@@ -30,6 +30,7 @@ use crate::{
     protocols::{Protocol, ProtocolHandler, ReturnableConnection},
     Connection,
     ConnectionSide,
+    TokenBucket,
     P2P,
 };
 
@@ -92,6 +93,15 @@ where
     /// The `side` param indicates the connection side **from the node's perspective**.
     fn codec(&self, addr: SocketAddr, side: ConnectionSide) -> Self::Codec;
 
+    /// Returns the [`TokenBucket`] that limits the bandwidth of the messages written to the specified address,
+    /// if any. It is consulted after every written message, and the next message to the address is only written
+    /// once the bucket is out of debt. Buckets may be shared between connections, to limit them collectively.
+    ///
+    /// By default, the bandwidth is not limited.
+    fn bandwidth_limiter(&self, _addr: SocketAddr) -> Option<Arc<TokenBucket>> {
+        None
+    }
+
     /// Sends the provided message to the specified [`SocketAddr`]. Returns as soon as the message is queued to
     /// be sent, without waiting for the actual delivery; instead, the caller is provided with a [`oneshot::Receiver`]
     /// which can be used to determine when and whether the message has been delivered.
@@ -222,6 +232,10 @@ impl<W: Writing> WritingInternal for W {
                         node.known_peers().register_sent_message(addr, len);
                         node.stats().register_sent_message(len);
                         trace!(parent: node.span(), "sent {}B to {}", len, addr);
+                        // wait until the bandwidth of the connection allows for another message
+                        if let Some(limiter) = self_clone.bandwidth_limiter(addr) {
+                            limiter.throttle(len).await;
+                        }
                     }
                     Err(e) => {
                         node.known_peers().register_failure(addr);
@@ -365,7 +365,15 @@ impl Tcp {
     fn handle_connection(&self, stream: TcpStream, addr: SocketAddr) {
         debug!(parent: self.span(), "Received a connection from {addr}");
 
-        if !self.can_add_connection() || self.is_self_connect(addr) {
+        if !self.can_add_connection() {
+            debug!(parent: self.span(), "Rejecting the connection from {addr}");
+            // Tell the peer why it was rejected, without waiting for the write.
+            if let Some(rejection_message) = &self.config.rejection_message {
+                let _ = stream.try_write(rejection_message);
+            }
+            return;
+        }
+        if self.is_self_connect(addr) {
             debug!(parent: self.span(), "Rejecting the connection from {addr}");
             return;
         }
@@ -677,6 +685,32 @@ mod tests {
         assert!(!tcp.is_connecting(peer2_ip));
     }
 
+    #[tokio::test]
+    async fn test_rejection_message() {
+        let tcp = Tcp::new(Config {
+            listener_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            max_connections: 1,
+            rejection_message: Some(b"full".to_vec()),
+            ..Default::default()
+        });
+
+        // Simulate an active connection.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listener_ip = listener.local_addr().unwrap();
+        let (stream, _accepted) = tokio::try_join!(TcpStream::connect(listener_ip), listener.accept()).unwrap();
+        tcp.connections.add(Connection::new(listener_ip, stream, ConnectionSide::Responder));
+        assert!(!tcp.can_add_connection());
+
+        // Ensure a rejected peer is told why.
+        let (mut peer, (stream, peer_ip)) =
+            tokio::try_join!(TcpStream::connect(listener_ip), listener.accept()).unwrap();
+        tcp.handle_connection(stream, peer_ip);
+        let mut buffer = Vec::new();
+        tokio::io::AsyncReadExt::read_to_end(&mut peer, &mut buffer).await.unwrap();
+        assert_eq!(buffer, b"full");
+        assert!(!tcp.is_connecting(peer_ip));
+    }
+
     #[tokio::test]
     async fn test_adapt_stream() {
         let tcp = Tcp::new(Config { max_connections: 1, ..Default::default() });
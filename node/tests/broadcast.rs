@@ -0,0 +1,96 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{node::*, sample_genesis_block, test_peer::TestPeer};
+
+use snarkos_node::Client;
+use snarkos_node_router::Outbound;
+use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, Testnet3 as CurrentNetwork, Transaction};
+
+use deadline::deadline;
+use pea2pea::Pea2Pea;
+use std::time::Duration;
+
+/// Returns the transactions of the genesis block.
+fn sample_transactions() -> Vec<Transaction<CurrentNetwork>> {
+    sample_genesis_block().transactions().iter().map(|transaction| transaction.transaction().clone()).collect()
+}
+
+/// Connects the given node to a new client test peer, which records the messages it receives.
+async fn connect_test_peer(node: &Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>>) -> TestPeer {
+    let peer = TestPeer::client().await;
+    node.router().connect(peer.node().listening_addr().unwrap()).unwrap().await.unwrap();
+    let node_clone = node.clone();
+    deadline!(Duration::from_secs(5), move || node_clone.router().number_of_connected_peers() == 1);
+    peer
+}
+
+#[tokio::test]
+async fn test_transactions_are_broadcast_at_once() {
+    let node = client().await;
+    let peer = connect_test_peer(&node).await;
+
+    // Ensure the transactions are broadcast in a single message, without the Dandelion relay.
+    node.propagate_transactions(sample_transactions(), &[]);
+    let peer_clone = peer.clone();
+    deadline!(Duration::from_secs(5), move || peer_clone.interceptor().count("UnconfirmedTransactions") == 1);
+    assert_eq!(peer.interceptor().count("StemTransaction"), 0);
+}
+
+#[tokio::test]
+async fn test_transactions_are_relayed_along_the_stem() {
+    let node = client().await;
+    node.router().dandelion().enable();
+    let peer = connect_test_peer(&node).await;
+
+    // Ensure each transaction is forwarded along the stem, and none is broadcast.
+    let transactions = sample_transactions();
+    let num_transactions = transactions.len();
+    node.propagate_transactions(transactions, &[]);
+    let peer_clone = peer.clone();
+    deadline!(Duration::from_secs(5), move || peer_clone.interceptor().count("StemTransaction") == num_transactions);
+    assert_eq!(peer.interceptor().count("UnconfirmedTransactions"), 0);
+    assert_eq!(peer.interceptor().count("UnconfirmedTransaction"), 0);
+}
+
+#[tokio::test]
+async fn test_batch_broadcast_relays_only_the_admitted_transactions() {
+    let node = client_with_rest().await;
+    node.router().dandelion().enable();
+    let peer = connect_test_peer(&node).await;
+    let (rest_ip, _) = node.rest().as_ref().unwrap().listener().unwrap();
+
+    // Broadcast the genesis transactions, which are rejected, as they are already in the ledger.
+    let transactions = sample_transactions();
+    let response = reqwest::Client::new()
+        .post(format!("http://{rest_ip}/testnet3/transaction/broadcast/batch"))
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&transactions).unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let results: Vec<serde_json::Value> = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(results.len(), transactions.len());
+    assert!(results.iter().all(|result| result["accepted"] == false));
+
+    // Ensure none of the rejected transactions is relayed.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(peer.interceptor().count("StemTransaction"), 0);
+    assert_eq!(peer.interceptor().count("UnconfirmedTransactions"), 0);
+}
@@ -0,0 +1,148 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::test_peer::TestPeer;
+use snarkos_account::Account;
+use snarkos_node_router::messages::{Message, NodeType};
+use snarkvm::prelude::{block::Block, TestRng, Testnet3 as CurrentNetwork};
+
+use std::{io, net::SocketAddr, sync::Arc};
+
+use once_cell::sync::OnceCell;
+use parking_lot::{Mutex, RwLock};
+use pea2pea::{protocols::Writing, Pea2Pea};
+
+/// A predicate over a received message and its sender, returning `true` if the message should be dropped.
+pub type Filter = Box<dyn Fn(SocketAddr, &Message<CurrentNetwork>) -> bool + Send + Sync>;
+
+/// Records the messages received by a test peer, and forwards them through another test peer unless filtered.
+#[derive(Clone, Default)]
+pub struct Interceptor {
+    /// The messages received by the test peer, in order of arrival.
+    received: Arc<Mutex<Vec<(SocketAddr, Message<CurrentNetwork>)>>>,
+    /// The filter for the messages that are not forwarded.
+    filter: Arc<RwLock<Option<Filter>>>,
+    /// The test peer through which the received messages are forwarded.
+    forward_to: Arc<OnceCell<TestPeer>>,
+}
+
+impl Interceptor {
+    /// Returns the messages received so far, along with their senders.
+    pub fn received(&self) -> Vec<(SocketAddr, Message<CurrentNetwork>)> {
+        self.received.lock().clone()
+    }
+
+    /// Returns the results of the given function on the received messages, skipping `None` results.
+    pub fn find<T>(&self, f: impl Fn(&Message<CurrentNetwork>) -> Option<T>) -> Vec<T> {
+        self.received.lock().iter().filter_map(|(_, message)| f(message)).collect()
+    }
+
+    /// Returns the number of received messages with the given name.
+    pub fn count(&self, name: &str) -> usize {
+        self.received.lock().iter().filter(|(_, message)| message.name() == name).count()
+    }
+
+    /// Sets the filter for the messages that are not forwarded.
+    pub fn set_filter(&self, filter: impl Fn(SocketAddr, &Message<CurrentNetwork>) -> bool + Send + Sync + 'static) {
+        *self.filter.write() = Some(Box::new(filter));
+    }
+
+    /// Drops the messages with any of the given names, instead of forwarding them.
+    pub fn drop_messages(&self, names: &'static [&'static str]) {
+        self.set_filter(move |_, message| names.contains(&&*message.name()));
+    }
+
+    /// Removes the filter, so that all messages are forwarded.
+    pub fn clear_filter(&self) {
+        *self.filter.write() = None;
+    }
+
+    /// Forwards the received messages to all connections of the given test peer.
+    pub fn forward_to(&self, peer: TestPeer) {
+        if self.forward_to.set(peer).is_err() {
+            panic!("the interceptor is already forwarding to a test peer");
+        }
+    }
+
+    /// Records the given message, and forwards it if it passes the filter.
+    pub(crate) fn intercept(&self, peer_addr: SocketAddr, message: Message<CurrentNetwork>) {
+        self.received.lock().push((peer_addr, message.clone()));
+
+        // Check if the message should be dropped.
+        if let Some(filter) = &*self.filter.read() {
+            if filter(peer_addr, &message) {
+                return;
+            }
+        }
+        // Forward the message, if there is a test peer to forward it through.
+        if let Some(peer) = self.forward_to.get() {
+            for addr in peer.node().connected_addrs() {
+                let _ = peer.unicast(addr, message.clone());
+            }
+        }
+    }
+}
+
+/// A man-in-the-middle between a downstream and an upstream node, built on a pair of test peers.
+///
+/// The downstream node connects to the front test peer, which poses as the upstream node type,
+/// and the back test peer connects to the upstream node, posing as the downstream node type.
+/// Peer discovery messages are dropped by default, so that the nodes stay behind the relay.
+#[derive(Clone)]
+pub struct Relay {
+    /// The test peer facing the downstream node.
+    front: TestPeer,
+    /// The test peer facing the upstream node.
+    back: TestPeer,
+}
+
+impl Relay {
+    /// The messages that are dropped by default.
+    pub const PEER_DISCOVERY: &'static [&'static str] = &["PeerRequest", "PeerResponse"];
+
+    /// Initializes a new relay between nodes of the given types, for the given genesis block.
+    pub async fn new(downstream: NodeType, upstream: NodeType, genesis: &Block<CurrentNetwork>) -> Self {
+        let rng = &mut TestRng::default();
+
+        let front = TestPeer::with_genesis(upstream, Account::new(rng).unwrap(), genesis).await;
+        let back = TestPeer::with_genesis(downstream, Account::new(rng).unwrap(), genesis).await;
+
+        front.interceptor().forward_to(back.clone());
+        back.interceptor().forward_to(front.clone());
+        front.interceptor().drop_messages(Self::PEER_DISCOVERY);
+        back.interceptor().drop_messages(Self::PEER_DISCOVERY);
+
+        Self { front, back }
+    }
+
+    /// Returns the listening address of the relay, which the downstream node should connect to.
+    pub fn listening_addr(&self) -> SocketAddr {
+        self.front.node().listening_addr().expect("listening address should be present")
+    }
+
+    /// Connects the relay to the upstream node.
+    pub async fn connect(&self, upstream: SocketAddr) -> io::Result<()> {
+        self.back.node().connect(upstream).await
+    }
+
+    /// Returns the interceptor of the messages sent by the downstream node.
+    pub fn downstream(&self) -> &Interceptor {
+        self.front.interceptor()
+    }
+
+    /// Returns the interceptor of the messages sent by the upstream node.
+    pub fn upstream(&self) -> &Interceptor {
+        self.back.interceptor()
+    }
+}
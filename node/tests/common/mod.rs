@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod interceptor;
 pub mod node;
 pub mod test_peer;
+pub mod topology;
 
 use std::{env, str::FromStr};
 
@@ -30,6 +30,23 @@ pub async fn client() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
         sample_genesis_block(),
         None, // No CDN.
         StorageMode::Production,
+        None, // No beacon-to-BFT transition.
+    )
+    .await
+    .expect("couldn't create client instance")
+}
+
+pub async fn client_with_rest() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+    Client::new(
+        "127.0.0.1:0".parse().unwrap(),
+        Some("127.0.0.1:0".parse().unwrap()),
+        10,
+        Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
+        &[],
+        sample_genesis_block(),
+        None, // No CDN.
+        StorageMode::Production,
+        None, // No beacon-to-BFT transition.
     )
     .await
     .expect("couldn't create client instance")
@@ -53,12 +70,17 @@ pub async fn validator() -> Validator<CurrentNetwork, ConsensusMemory<CurrentNet
         None,
         None,
         10,
-        Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
+        Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1")
+            .unwrap()
+            .into(),
         &[],
         &[],
         sample_genesis_block(), // Should load the current network's genesis block.
         None,                   // No CDN.
         StorageMode::Production,
+        None, // No validation concurrency limit.
+        None, // No beacon-to-BFT transition.
+        None, // Not ephemeral.
     )
     .await
     .expect("couldn't create validator instance")
@@ -12,14 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::common::interceptor::Interceptor;
 use snarkos_account::Account;
 use snarkos_node_router::{
     expect_message,
-    messages::{ChallengeRequest, ChallengeResponse, Message, MessageCodec, MessageTrait, NodeType},
+    messages::{ChallengeRequest, ChallengeResponse, Message, MessageCodec, MessageTrait, NodeId, NodeType},
 };
 use snarkvm::{
     ledger::narwhal::Data,
-    prelude::{block::Block, error, Address, FromBytes, Network, TestRng, Testnet3 as CurrentNetwork},
+    prelude::{
+        block::{Block, Header},
+        error,
+        Address,
+        FromBytes,
+        Network,
+        TestRng,
+        Testnet3 as CurrentNetwork,
+    },
 };
 
 use std::{
@@ -58,6 +67,8 @@ pub struct TestPeer {
     node: Node,
     node_type: NodeType,
     account: Account<CurrentNetwork>,
+    genesis_header: Header<CurrentNetwork>,
+    interceptor: Interceptor,
 }
 
 impl Pea2Pea for TestPeer {
@@ -80,6 +91,14 @@ impl TestPeer {
     }
 
     pub async fn new(node_type: NodeType, account: Account<CurrentNetwork>) -> Self {
+        Self::with_genesis(node_type, account, &sample_genesis_block()).await
+    }
+
+    pub async fn with_genesis(
+        node_type: NodeType,
+        account: Account<CurrentNetwork>,
+        genesis: &Block<CurrentNetwork>,
+    ) -> Self {
         let peer = Self {
             node: Node::new(Config {
                 listener_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
@@ -88,6 +107,8 @@ impl TestPeer {
             }),
             node_type,
             account,
+            genesis_header: *genesis.header(),
+            interceptor: Default::default(),
         };
 
         peer.enable_handshake().await;
@@ -111,6 +132,10 @@ impl TestPeer {
     pub fn address(&self) -> Address<CurrentNetwork> {
         self.account.address()
     }
+
+    pub fn interceptor(&self) -> &Interceptor {
+        &self.interceptor
+    }
 }
 
 #[async_trait::async_trait]
@@ -126,13 +151,20 @@ impl Handshake for TestPeer {
         let mut framed = Framed::new(stream, MessageCodec::<CurrentNetwork>::default());
 
         // Retrieve the genesis block header.
-        let genesis_header = *sample_genesis_block().header();
+        let genesis_header = self.genesis_header;
 
         // TODO(nkls): add assertions on the contents of messages.
         match node_side {
             ConnectionSide::Initiator => {
                 // Send a challenge request to the peer.
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request = ChallengeRequest::new(
+                    local_ip.port(),
+                    self.node_type(),
+                    self.address(),
+                    NodeId::new(self.account().private_key(), rng).unwrap(),
+                    None,
+                    rng.gen(),
+                );
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Receive the peer's challenge bundle.
@@ -141,12 +173,16 @@ impl Handshake for TestPeer {
 
                 // Sign the nonce.
                 let response_nonce: u64 = rng.gen();
-                let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+                let data = ChallengeResponse::<CurrentNetwork>::message(peer_request.nonce, response_nonce);
                 let signature = self.account().sign_bytes(&data, rng).unwrap();
 
                 // Send the challenge response.
-                let our_response =
-                    ChallengeResponse { genesis_header, signature: Data::Object(signature), nonce: response_nonce };
+                let our_response = ChallengeResponse {
+                    genesis_header,
+                    height: 0,
+                    signature: Data::Object(signature),
+                    nonce: response_nonce,
+                };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
             }
             ConnectionSide::Responder => {
@@ -155,14 +191,25 @@ impl Handshake for TestPeer {
 
                 // Sign the nonce.
                 let response_nonce: u64 = rng.gen();
-                let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+                let data = ChallengeResponse::<CurrentNetwork>::message(peer_request.nonce, response_nonce);
                 let signature = self.account().sign_bytes(&data, rng).unwrap();
 
                 // Send our challenge bundle.
-                let our_response =
-                    ChallengeResponse { genesis_header, signature: Data::Object(signature), nonce: response_nonce };
+                let our_response = ChallengeResponse {
+                    genesis_header,
+                    height: 0,
+                    signature: Data::Object(signature),
+                    nonce: response_nonce,
+                };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request = ChallengeRequest::new(
+                    local_ip.port(),
+                    self.node_type(),
+                    self.address(),
+                    NodeId::new(self.account().private_key(), rng).unwrap(),
+                    None,
+                    rng.gen(),
+                );
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Listen for the challenge response.
@@ -193,7 +240,8 @@ impl Reading for TestPeer {
         Default::default()
     }
 
-    async fn process_message(&self, _peer_ip: SocketAddr, _message: Self::Message) -> io::Result<()> {
+    async fn process_message(&self, peer_ip: SocketAddr, message: Self::Message) -> io::Result<()> {
+        self.interceptor.intercept(peer_ip, message);
         Ok(())
     }
 }
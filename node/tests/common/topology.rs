@@ -0,0 +1,215 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::interceptor::Relay;
+use snarkos_account::Account;
+use snarkos_node::{Client, EphemeralDevnet, Prover, Validator};
+use snarkos_node_router::{messages::NodeType, Outbound};
+use snarkvm::{
+    ledger::{
+        block::Block,
+        committee::Committee,
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+    },
+    prelude::{Address, Network, PrivateKey, TestRng, Testnet3 as CurrentNetwork},
+    synthesizer::VM,
+};
+
+use aleo_std::StorageMode;
+use indexmap::IndexMap;
+use once_cell::sync::OnceCell;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+
+/// The number of validators in the sample committee, which is the minimum for the BFT to make progress.
+pub const NUM_COMMITTEE_MEMBERS: usize = 4;
+/// The seed of the RNG that derives the sample committee.
+const COMMITTEE_RNG_SEED: u64 = 1234567;
+/// The first development storage ID of the clients, which follow the validators.
+const CLIENT_STORAGE_ID: u16 = 100;
+/// The first development storage ID of the provers, which follow the clients.
+const PROVER_STORAGE_ID: u16 = 200;
+
+/// Returns the accounts of the sample committee, and a genesis block in which they are staked and funded.
+///
+/// The genesis block is computed once per test binary, as it takes a while.
+pub fn sample_committee_genesis() -> &'static (Vec<Account<CurrentNetwork>>, Block<CurrentNetwork>) {
+    static GENESIS: OnceCell<(Vec<Account<CurrentNetwork>>, Block<CurrentNetwork>)> = OnceCell::new();
+
+    GENESIS.get_or_init(|| {
+        let rng = &mut ChaChaRng::seed_from_u64(COMMITTEE_RNG_SEED);
+        let private_keys =
+            (0..NUM_COMMITTEE_MEMBERS).map(|_| PrivateKey::<CurrentNetwork>::new(rng).unwrap()).collect::<Vec<_>>();
+        let addresses =
+            private_keys.iter().map(|private_key| Address::try_from(private_key).unwrap()).collect::<Vec<_>>();
+
+        // Stake half of the starting supply evenly among the committee members.
+        let stake_per_member = CurrentNetwork::STARTING_SUPPLY / 2 / NUM_COMMITTEE_MEMBERS as u64;
+        let members = addresses.iter().map(|address| (*address, (stake_per_member, true))).collect::<IndexMap<_, _>>();
+        let committee = Committee::new(0u64, members).unwrap();
+
+        // Distribute the remaining supply as public balances, giving any leftover to the first member.
+        let remaining_balance = CurrentNetwork::STARTING_SUPPLY - committee.total_stake();
+        let balance_per_member = remaining_balance / NUM_COMMITTEE_MEMBERS as u64;
+        let mut public_balances =
+            addresses.iter().map(|address| (*address, balance_per_member)).collect::<IndexMap<_, _>>();
+        *public_balances.get_index_mut(0).unwrap().1 += remaining_balance % NUM_COMMITTEE_MEMBERS as u64;
+
+        // Compute the genesis block.
+        let vm = VM::from(ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(Some(0)).unwrap())
+            .unwrap();
+        let genesis = vm.genesis_quorum(&private_keys[0], committee, public_balances, rng).unwrap();
+
+        let accounts = private_keys.into_iter().map(|private_key| Account::try_from(private_key).unwrap()).collect();
+        (accounts, genesis)
+    })
+}
+
+/// A topology of the sample committee's validators, along with clients and provers,
+/// each of which is connected to one of the validators through a relay.
+///
+/// Note: the blocks are produced by the validators' BFT, there is no separate beacon.
+pub struct Topology {
+    /// The validators of the sample committee.
+    devnet: EphemeralDevnet<CurrentNetwork>,
+    /// The clients, along with their relays.
+    clients: Vec<(Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>>, Relay)>,
+    /// The provers, along with their relays.
+    provers: Vec<(Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>>, Relay)>,
+}
+
+impl Topology {
+    /// Returns a builder of a topology.
+    pub fn builder() -> TopologyBuilder {
+        TopologyBuilder::default()
+    }
+
+    /// Returns the genesis block of the topology.
+    pub fn genesis(&self) -> &Block<CurrentNetwork> {
+        &sample_committee_genesis().1
+    }
+
+    /// Returns the accounts of the committee members.
+    pub fn committee(&self) -> &[Account<CurrentNetwork>] {
+        &sample_committee_genesis().0
+    }
+
+    /// Returns the validators.
+    pub fn validators(&self) -> &[Validator<CurrentNetwork, ConsensusMemory<CurrentNetwork>>] {
+        self.devnet.validators()
+    }
+
+    /// Returns the client at the given index.
+    pub fn client(&self, index: usize) -> &Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+        &self.clients[index].0
+    }
+
+    /// Returns the relay between the client at the given index and its validator.
+    pub fn client_relay(&self, index: usize) -> &Relay {
+        &self.clients[index].1
+    }
+
+    /// Returns the prover at the given index.
+    pub fn prover(&self, index: usize) -> &Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+        &self.provers[index].0
+    }
+
+    /// Returns the relay between the prover at the given index and its validator.
+    pub fn prover_relay(&self, index: usize) -> &Relay {
+        &self.provers[index].1
+    }
+}
+
+/// A builder of a [`Topology`], which assigns the clients and provers to the validators in a round-robin fashion.
+#[derive(Default)]
+pub struct TopologyBuilder {
+    /// The number of clients.
+    num_clients: usize,
+    /// The number of provers.
+    num_provers: usize,
+}
+
+impl TopologyBuilder {
+    /// Sets the number of clients.
+    pub fn clients(mut self, num_clients: usize) -> Self {
+        self.num_clients = num_clients;
+        self
+    }
+
+    /// Sets the number of provers.
+    pub fn provers(mut self, num_provers: usize) -> Self {
+        self.num_provers = num_provers;
+        self
+    }
+
+    /// Starts the validators, and connects the clients and provers to them through their relays.
+    pub async fn build(self) -> Topology {
+        let rng = &mut TestRng::default();
+        let (committee, genesis) = sample_committee_genesis();
+
+        // Start the validators.
+        let devnet = EphemeralDevnet::start(committee.clone(), genesis.clone(), 10, vec![])
+            .await
+            .expect("couldn't start the validators");
+        let validator_ips =
+            devnet.validators().iter().map(|validator| validator.router().local_ip()).collect::<Vec<_>>();
+
+        // Start the clients.
+        let mut clients = Vec::with_capacity(self.num_clients);
+        for i in 0..self.num_clients {
+            let relay = relay(NodeType::Client, genesis, validator_ips[i % validator_ips.len()]).await;
+            let client = Client::new(
+                "127.0.0.1:0".parse().unwrap(),
+                None,
+                10,
+                Account::new(rng).unwrap(),
+                &[relay.listening_addr()],
+                genesis.clone(),
+                None, // No CDN.
+                StorageMode::Development(CLIENT_STORAGE_ID + i as u16),
+                None, // No beacon-to-BFT transition.
+            )
+            .await
+            .expect("couldn't create client instance");
+            client.router().connect(relay.listening_addr()).unwrap().await.unwrap();
+            clients.push((client, relay));
+        }
+
+        // Start the provers.
+        let mut provers = Vec::with_capacity(self.num_provers);
+        for i in 0..self.num_provers {
+            let relay = relay(NodeType::Prover, genesis, validator_ips[i % validator_ips.len()]).await;
+            let prover = Prover::new(
+                "127.0.0.1:0".parse().unwrap(),
+                Account::new(rng).unwrap(),
+                &[relay.listening_addr()],
+                genesis.clone(),
+                StorageMode::Development(PROVER_STORAGE_ID + i as u16),
+            )
+            .await
+            .expect("couldn't create prover instance");
+            prover.router().connect(relay.listening_addr()).unwrap().await.unwrap();
+            provers.push((prover, relay));
+        }
+
+        Topology { devnet, clients, provers }
+    }
+}
+
+/// Returns a relay between a node of the given type and the validator at the given address.
+async fn relay(node_type: NodeType, genesis: &Block<CurrentNetwork>, validator_ip: std::net::SocketAddr) -> Relay {
+    let relay = Relay::new(node_type, NodeType::Validator, genesis).await;
+    relay.connect(validator_ip).await.expect("couldn't connect the relay to the validator");
+    relay
+}
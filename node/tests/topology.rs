@@ -0,0 +1,130 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[allow(dead_code)]
+mod common;
+use common::topology::Topology;
+
+use snarkos_node::Validator;
+use snarkos_node_router::{
+    messages::{Message, UnconfirmedTransaction},
+    Inbound,
+    Outbound,
+};
+use snarkvm::{
+    console::{
+        program::{Identifier, Literal, ProgramID, Value},
+        types::U64,
+    },
+    prelude::{block::Transaction, store::helpers::memory::ConsensusMemory, Network, Testnet3 as CurrentNetwork},
+};
+
+use deadline::deadline;
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+/// Returns a public transfer from the first committee member to itself.
+async fn sample_transfer(
+    validator: &Validator<CurrentNetwork, ConsensusMemory<CurrentNetwork>>,
+) -> Transaction<CurrentNetwork> {
+    let ledger = validator.ledger().clone();
+    let private_key = *common::topology::sample_committee_genesis().0[0].private_key();
+    tokio::task::spawn_blocking(move || {
+        let locator = (ProgramID::from_str("credits.aleo").unwrap(), Identifier::from_str("transfer_public").unwrap());
+        let address = snarkvm::prelude::Address::try_from(&private_key).unwrap();
+        let inputs = [Value::from(Literal::Address(address)), Value::from(Literal::U64(U64::new(1)))];
+        ledger.vm().execute(&private_key, locator, inputs.into_iter(), None, 0, None, &mut rand::thread_rng()).unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+/// Returns `true` if any of the validators holds the given transaction, either unconfirmed or in a block.
+fn contains_transaction(topology: &Topology, transaction_id: &<CurrentNetwork as Network>::TransactionID) -> bool {
+    topology.validators().iter().any(|validator| {
+        validator.ledger().contains_transaction_id(transaction_id).unwrap_or(false)
+            || validator.consensus().unconfirmed_transactions().any(|(id, _)| id == *transaction_id)
+    })
+}
+
+/// Returns the ID of the transaction in the given message, if any.
+fn find_transaction_id(message: &Message<CurrentNetwork>) -> Option<<CurrentNetwork as Network>::TransactionID> {
+    match message {
+        Message::UnconfirmedTransaction(message) => Some(message.transaction_id),
+        _ => None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn client_broadcast_reaches_validator_mempool() {
+    let topology = Arc::new(Topology::builder().clients(1).build().await);
+    let client = topology.client(0).clone();
+
+    // Broadcast a transaction from the client.
+    let transaction = sample_transfer(&topology.validators()[0]).await;
+    let transaction_id = transaction.id();
+    let message = UnconfirmedTransaction::from(transaction.clone());
+    assert!(client.unconfirmed_transaction(client.router().local_ip(), message, transaction).await);
+
+    // Check that the transaction passed through the relay.
+    let relay = topology.client_relay(0).clone();
+    deadline!(Duration::from_secs(30), move || relay.downstream().find(find_transaction_id).contains(&transaction_id));
+
+    // Check that the transaction reached the validators.
+    deadline!(Duration::from_secs(60), move || contains_transaction(&topology, &transaction_id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn relay_drops_intercepted_transactions() {
+    let topology = Topology::builder().clients(1).build().await;
+    let client = topology.client(0).clone();
+
+    // Drop the transactions sent by the client.
+    let relay = topology.client_relay(0).clone();
+    relay.downstream().drop_messages(&["PeerRequest", "PeerResponse", "UnconfirmedTransaction"]);
+
+    // Broadcast a transaction from the client.
+    let transaction = sample_transfer(&topology.validators()[0]).await;
+    let transaction_id = transaction.id();
+    let message = UnconfirmedTransaction::from(transaction.clone());
+    assert!(client.unconfirmed_transaction(client.router().local_ip(), message, transaction).await);
+
+    // Check that the transaction was intercepted, but never reached the validators.
+    deadline!(Duration::from_secs(30), move || {
+        relay.downstream().find(find_transaction_id).contains(&transaction_id)
+    });
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert!(!contains_transaction(&topology, &transaction_id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "the prover takes minutes to find a solution that meets the target"]
+async fn prover_solution_included_in_block() {
+    let topology = Arc::new(Topology::builder().provers(1).build().await);
+
+    // Wait for the prover to broadcast a solution.
+    let relay = topology.prover_relay(0).clone();
+    let relay_clone = relay.clone();
+    deadline!(Duration::from_secs(600), move || relay_clone.downstream().count("UnconfirmedSolution") > 0);
+
+    // Check that one of the solutions was included in a block.
+    deadline!(Duration::from_secs(600), move || {
+        let solution_ids = relay.downstream().find(|message| match message {
+            Message::UnconfirmedSolution(message) => Some(message.solution_id),
+            _ => None,
+        });
+        topology.validators().iter().any(|validator| {
+            solution_ids.iter().any(|id| validator.ledger().contains_puzzle_commitment(id).unwrap_or(false))
+        })
+    });
+}